@@ -0,0 +1,179 @@
+//! Ad-hoc single-container flags shared by `ctst run` and `ctst create`.
+
+use clap::Args;
+use containust_compose::parser::ast::{ComponentDecl, CompositionFile};
+use containust_runtime::engine::LimitOverride;
+
+use super::limits::{parse_cpu_arg, parse_memory_arg, parse_pids_arg};
+
+/// Flags describing one container without a `.ctst` file.
+#[derive(Args, Debug, Default)]
+pub struct AdhocArgs {
+    /// Run a single ad-hoc container from this image instead of a `.ctst` file.
+    #[arg(long)]
+    pub image: Option<String>,
+
+    /// Name of the ad-hoc container (defaults to the image name).
+    #[arg(long, requires = "image", value_parser = super::parse_name_arg)]
+    pub name: Option<String>,
+
+    /// Environment variable for the ad-hoc container (repeatable).
+    #[arg(short = 'e', long = "env", value_name = "KEY=VALUE", requires = "image", value_parser = parse_env_pair)]
+    pub env: Vec<(String, String)>,
+
+    /// Container port to publish on the same host port (repeatable).
+    #[arg(short = 'p', long = "publish", value_name = "PORT", requires = "image")]
+    pub publish: Vec<u16>,
+
+    /// Memory limit of the ad-hoc container (e.g. `256MiB`).
+    #[arg(long, requires = "image", value_parser = parse_memory_arg)]
+    pub memory: Option<u64>,
+
+    /// CPU limit of the ad-hoc container (e.g. `1.5` cores).
+    #[arg(long, requires = "image", value_parser = parse_cpu_arg)]
+    pub cpu: Option<u64>,
+
+    /// Maximum number of processes in the ad-hoc container.
+    #[arg(long, requires = "image", value_parser = parse_pids_arg)]
+    pub pids: Option<u64>,
+}
+
+impl AdhocArgs {
+    /// Builds a one-component composition and its resource limits when
+    /// `--image` was given.
+    #[must_use]
+    pub fn into_composition(
+        self,
+        command: Vec<String>,
+    ) -> Option<(CompositionFile, LimitOverride)> {
+        let image = self.image?;
+        let name = self.name.unwrap_or_else(|| adhoc_name(&image));
+        let limits = LimitOverride {
+            component: name.clone(),
+            memory_bytes: self.memory,
+            cpu_shares: self.cpu,
+            pids_max: self.pids,
+        };
+        let component = ComponentDecl {
+            name,
+            image: Some(image),
+            command,
+            port: self.publish.first().copied(),
+            ports: self.publish,
+            env: self.env.into_iter().collect(),
+            ..ComponentDecl::default()
+        };
+        let composition = CompositionFile {
+            components: vec![component],
+            ..CompositionFile::default()
+        };
+        Some((composition, limits))
+    }
+}
+
+/// Parses a `KEY=VALUE` environment assignment.
+pub(super) fn parse_env_pair(raw: &str) -> Result<(String, String), String> {
+    match raw.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{raw}'")),
+    }
+}
+
+/// Derives a container name from the last path segment of an image URI.
+fn adhoc_name(image: &str) -> String {
+    let location = image.split_once("://").map_or(image, |(_, rest)| rest);
+    let segment = location
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    let base = segment
+        .split(['@', ':'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(".tar.gz")
+        .trim_end_matches(".tar");
+    let name: String = base
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let name = name.trim_start_matches(['-', '_']);
+    if name.is_empty() {
+        "container".into()
+    } else {
+        name.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn adhoc_name_uses_last_image_segment() {
+        assert_eq!(adhoc_name("preset://alpine"), "alpine");
+        assert_eq!(adhoc_name("oci://docker.io/library/nginx:1.27"), "nginx");
+        assert_eq!(adhoc_name("tar:///tmp/images/app.tar.gz"), "app");
+        assert_eq!(adhoc_name("image://web@sha256:abc"), "web");
+        assert_eq!(adhoc_name("file:///"), "container");
+        assert_eq!(adhoc_name("file:///srv/_cache"), "cache");
+    }
+
+    #[test]
+    fn into_composition_prefers_explicit_name() {
+        let args = AdhocArgs {
+            image: Some("preset://alpine".into()),
+            name: Some("box".into()),
+            ..AdhocArgs::default()
+        };
+        let (composition, _) = args
+            .into_composition(vec!["sh".into()])
+            .expect("composition");
+        let component = &composition.components[0];
+        assert_eq!(component.name, "box");
+        assert_eq!(component.image.as_deref(), Some("preset://alpine"));
+        assert_eq!(component.command, vec!["sh"]);
+    }
+
+    #[test]
+    fn into_composition_carries_env_ports_and_limits() {
+        let args = AdhocArgs {
+            image: Some("file:///opt/alpine".into()),
+            env: vec![("MODE".into(), "dev".into())],
+            publish: vec![8080],
+            memory: Some(256 * 1024 * 1024),
+            cpu: Some(512),
+            pids: Some(128),
+            ..AdhocArgs::default()
+        };
+        let (composition, limits) = args.into_composition(Vec::new()).expect("composition");
+        let component = &composition.components[0];
+        assert_eq!(component.name, "alpine");
+        assert_eq!(component.env.get("MODE").map(String::as_str), Some("dev"));
+        assert_eq!(component.ports, vec![8080]);
+        assert_eq!(limits.component, "alpine");
+        assert_eq!(limits.memory_bytes, Some(256 * 1024 * 1024));
+        assert_eq!(limits.cpu_shares, Some(512));
+        assert_eq!(limits.pids_max, Some(128));
+    }
+
+    #[test]
+    fn into_composition_none_without_image() {
+        assert!(AdhocArgs::default().into_composition(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn parse_env_pair_splits_on_first_equals() {
+        assert_eq!(parse_env_pair("URL=a=b"), Ok(("URL".into(), "a=b".into())));
+        assert!(parse_env_pair("NOVALUE").is_err());
+        assert!(parse_env_pair("=x").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! `ctst attach` — Connect to a running container's main process stdio.
+
+use std::io::Read;
+use std::sync::mpsc;
+
+use clap::Args;
+use containust_runtime::attach::{AttachEnd, pump};
+
+/// Arguments for the `attach` command.
+#[derive(Args, Debug)]
+pub struct AttachArgs {
+    /// Container ID or name.
+    pub container: String,
+
+    /// Only stream output; do not forward this terminal's stdin.
+    #[arg(long)]
+    pub no_stdin: bool,
+}
+
+/// Executes the `attach` command.
+///
+/// Streams the main process's stdout/stderr from now on and forwards
+/// stdin to it until the process exits or Ctrl+C detaches. The container
+/// keeps running after a detach.
+///
+/// # Errors
+///
+/// Returns an error if the container is not running or the stream fails.
+pub fn execute(args: AttachArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    let engine = options.engine();
+    let id = super::resolve_container_id(&engine, &args.container)?;
+    let mut stream = engine
+        .attach(&id, !args.no_stdin)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    options.cancel_on_interrupt()?;
+    let input = (!args.no_stdin).then(spawn_stdin_reader);
+
+    let end = pump(
+        stream.as_mut(),
+        &mut std::io::stdout(),
+        input.as_ref(),
+        &options.cancel,
+    )
+    .map_err(|e| anyhow::anyhow!("{e}"))?;
+    if end == AttachEnd::Exited {
+        tracing::info!(container = %args.container, "main process exited");
+    }
+    Ok(())
+}
+
+/// Reads this process's stdin on a thread; the channel closes at EOF.
+fn spawn_stdin_reader() -> mpsc::Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    let _ = std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut buffer = [0_u8; 4096];
+        while let Ok(read) = stdin.read(&mut buffer) {
+            if read == 0 || sender.send(buffer[..read].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
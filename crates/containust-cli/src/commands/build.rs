@@ -1,121 +1,197 @@
 //! `ctst build` — Import composition images into the local catalog.
 
 use std::path::Path;
+use std::time::Instant;
 
 use clap::Args;
-use containust_image::import::{ImportRequest, import_image};
+use containust_common::cancel::CancellationToken;
+use containust_compose::input::CompositionSource;
+use containust_image::import::{ImportRequest, import_image_with_progress};
 use containust_image::preset::resolve_preset;
 use containust_image::reference::{ImageReference, ImageScheme};
 
+use super::build_report::{BuildReport, ImageBuildReport, ImageStatus, StepRecorder};
+
 /// Arguments for the `build` command.
 #[derive(Args, Debug)]
 pub struct BuildArgs {
-    /// Path to the .ctst composition file.
+    /// Path to the .ctst composition file (`-` reads stdin).
     #[arg(default_value = "containust.ctst")]
     pub file: String,
 
     /// Plan the import without writing layers or catalog entries.
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Print a JSON build report with per-step timings instead of text.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Fail when a `file://` image directory does not look like a rootfs
+    /// instead of warning.
+    #[arg(long)]
+    pub strict: bool,
 }
 
 /// Executes the `build` command.
 ///
 /// Parses the `.ctst` file, validates it, and imports every declared
-/// image source into the project's content-addressed catalog. With
-/// `--dry-run`, only the planned imports are displayed.
+/// image source into the project's content-addressed catalog, timing
+/// each import step. With `--dry-run`, only the planned imports are
+/// displayed; with `--json`, a [`BuildReport`] is printed instead.
 ///
 /// # Errors
 ///
 /// Returns an error if parsing, validation, or an image import fails.
 pub fn execute(args: BuildArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
     tracing::info!(file = %args.file, dry_run = args.dry_run, "building from .ctst file");
+    let started = Instant::now();
 
-    let content = std::fs::read_to_string(&args.file)?;
-    let composition =
-        containust_compose::parser::parse_ctst(&content).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let input = CompositionSource::from_arg(&args.file);
+    let composition = input.parse().map_err(|e| anyhow::anyhow!("{e}"))?;
     if options.offline {
         containust_compose::validate_offline(&composition).map_err(|e| anyhow::anyhow!("{e}"))?;
     }
 
-    println!(
+    options.cancel_on_interrupt()?;
+    let engine = options.engine_for_project(&input.project_anchor());
+    let context = BuildContext {
+        data_dir: engine.data_dir(),
+        cancel: &options.cancel,
+        offline: options.offline,
+        dry_run: args.dry_run,
+        human: !args.json,
+    };
+    context.say(&format!(
         "Parsed {} components, {} connections",
         composition.components.len(),
         composition.connections.len()
-    );
+    ));
 
-    let engine = options.engine_for_project(Path::new(&args.file));
-    let mut imported = 0_usize;
+    let mut report = BuildReport::new(&input.display(), args.dry_run);
     for component in &composition.components {
         let Some(image) = component.image.as_deref() else {
             continue;
         };
         let reference = ImageReference::parse(image).map_err(|e| anyhow::anyhow!("{e}"))?;
-        imported += usize::from(build_component(
-            &BuildContext {
-                data_dir: engine.data_dir(),
-                offline: options.offline,
-                dry_run: args.dry_run,
-            },
-            &component.name,
-            &reference,
-        )?);
+        check_file_source(&reference, args.strict)?;
+        report
+            .images
+            .push(build_component(&context, &component.name, &reference)?);
     }
+    report.total_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
 
-    if args.dry_run {
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if args.dry_run {
         println!("Dry run complete. No layers or catalog entries were written.");
     } else {
-        println!("Build complete. {imported} image(s) imported.");
+        println!(
+            "Build complete. {} image(s) imported in {} ms.",
+            report.imported(),
+            report.total_ms
+        );
     }
     Ok(())
 }
 
 struct BuildContext<'a> {
     data_dir: &'a Path,
+    cancel: &'a CancellationToken,
     offline: bool,
     dry_run: bool,
+    human: bool,
+}
+
+impl BuildContext<'_> {
+    /// Prints a line of the human view; silent under `--json`.
+    fn say(&self, line: &str) {
+        if self.human {
+            println!("{line}");
+        }
+    }
 }
 
-/// Imports one component image; returns whether an import happened.
+/// Checks that a `file://` image directory looks like a rootfs; other
+/// sources are left to the import.
+fn check_file_source(reference: &ImageReference, strict: bool) -> anyhow::Result<()> {
+    if reference.scheme() != ImageScheme::File {
+        return Ok(());
+    }
+    containust_image::source::check_rootfs(Path::new(reference.location()), strict)
+        .map_err(|e| anyhow::anyhow!("{e}"))
+}
+
+/// Imports one component image and reports what happened.
 fn build_component(
     context: &BuildContext<'_>,
     name: &str,
     reference: &ImageReference,
-) -> anyhow::Result<bool> {
-    println!("  {name} -> {reference}");
+) -> anyhow::Result<ImageBuildReport> {
+    let source = reference.to_string();
+    context.say(&format!("  {name} -> {reference}"));
     if reference.scheme() == ImageScheme::Catalog {
         let catalog = containust_image::registry::ImageCatalog::open(context.data_dir)
             .map_err(|e| anyhow::anyhow!("{e}"))?;
         let entry = catalog
             .find(reference.location())
             .map_err(|e| anyhow::anyhow!("{e}"))?;
-        println!(
+        context.say(&format!(
             "    Already imported (digest {})",
             entry.digest.as_deref().unwrap_or("<none>")
-        );
-        return Ok(false);
+        ));
+        return Ok(ImageBuildReport::new(
+            name,
+            &source,
+            (ImageStatus::Cached, entry.digest),
+            Vec::new(),
+        ));
     }
     if context.dry_run {
-        if reference.scheme() == ImageScheme::Preset {
-            let preset = resolve_preset(reference).map_err(|e| anyhow::anyhow!("{e}"))?;
-            println!(
-                "    Would download {} ({}) → sha256:{}",
-                preset.url, preset.description, preset.sha256
-            );
-        } else {
-            println!(
-                "    Would import as '{name}' (cache key {})",
-                reference.cache_key()
-            );
-        }
-        return Ok(false);
+        let digest = plan_component(context, name, reference)?;
+        return Ok(ImageBuildReport::new(
+            name,
+            &source,
+            (ImageStatus::Planned, digest),
+            Vec::new(),
+        ));
     }
-    let request = ImportRequest::new(name, context.offline);
-    let entry =
-        import_image(context.data_dir, reference, &request).map_err(|e| anyhow::anyhow!("{e}"))?;
-    println!(
-        "    Imported as image://{name}@sha256:{}",
-        entry.digest.as_deref().unwrap_or_default()
+    let request =
+        ImportRequest::new(name, context.offline).with_cancellation(context.cancel.clone());
+    let recorder = StepRecorder::new(context.human);
+    let entry = import_image_with_progress(context.data_dir, reference, &request, &recorder)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    let image = ImageBuildReport::new(
+        name,
+        &source,
+        (ImageStatus::Imported, entry.digest),
+        recorder.into_steps(),
     );
-    Ok(true)
+    context.say(&format!(
+        "    Imported as image://{name}@sha256:{} ({} ms)",
+        image.digest.as_deref().unwrap_or_default(),
+        image.duration_ms
+    ));
+    Ok(image)
+}
+
+/// Prints the planned import; returns the curated digest for presets.
+fn plan_component(
+    context: &BuildContext<'_>,
+    name: &str,
+    reference: &ImageReference,
+) -> anyhow::Result<Option<String>> {
+    if reference.scheme() == ImageScheme::Preset {
+        let preset = resolve_preset(reference).map_err(|e| anyhow::anyhow!("{e}"))?;
+        context.say(&format!(
+            "    Would download {} ({}) → sha256:{}",
+            preset.url, preset.description, preset.sha256
+        ));
+        return Ok(Some(preset.sha256.to_string()));
+    }
+    context.say(&format!(
+        "    Would import as '{name}' (cache key {})",
+        reference.cache_key()
+    ));
+    Ok(None)
 }
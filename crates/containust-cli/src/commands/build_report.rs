@@ -0,0 +1,227 @@
+//! Machine-readable `ctst build --json` report.
+//!
+//! The field names are part of the CLI contract for CI artifacts; bump
+//! [`BUILD_REPORT_VERSION`] when a field is removed or changes meaning.
+
+use std::cell::RefCell;
+
+use containust_image::progress::{ImportProgress, StepTiming};
+use serde::{Deserialize, Serialize};
+
+/// Schema version of [`BuildReport`].
+pub const BUILD_REPORT_VERSION: u32 = 1;
+
+/// Outcome of one component image during a build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageStatus {
+    /// The image was imported into the catalog.
+    Imported,
+    /// An `image://` reference that was already in the catalog.
+    Cached,
+    /// `--dry-run`: the import was only planned.
+    Planned,
+}
+
+/// Timings of one component image.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageBuildReport {
+    /// Component that declared the image.
+    pub component: String,
+    /// Image source URI as written in the composition.
+    pub source: String,
+    /// What the build did with the image.
+    pub status: ImageStatus,
+    /// Resulting content digest, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    /// Completed import steps in execution order.
+    pub steps: Vec<StepTiming>,
+    /// Sum of all step durations in milliseconds.
+    pub duration_ms: u64,
+}
+
+impl ImageBuildReport {
+    /// Assembles an image entry, totalling its step durations.
+    pub fn new(
+        component: &str,
+        source: &str,
+        outcome: (ImageStatus, Option<String>),
+        steps: Vec<StepTiming>,
+    ) -> Self {
+        let (status, digest) = outcome;
+        let duration_ms = steps
+            .iter()
+            .fold(0_u64, |total, step| total.saturating_add(step.duration_ms));
+        Self {
+            component: component.to_string(),
+            source: source.to_string(),
+            status,
+            digest,
+            steps,
+            duration_ms,
+        }
+    }
+}
+
+/// Full report of one `ctst build` invocation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildReport {
+    /// Report schema version ([`BUILD_REPORT_VERSION`]).
+    pub schema_version: u32,
+    /// Composition file that was built.
+    pub file: String,
+    /// Whether this was a `--dry-run`.
+    pub dry_run: bool,
+    /// Per-component results in declaration order.
+    pub images: Vec<ImageBuildReport>,
+    /// Wall-clock duration of the whole build in milliseconds.
+    pub total_ms: u64,
+}
+
+impl BuildReport {
+    /// Creates an empty report for `file`.
+    pub fn new(file: &str, dry_run: bool) -> Self {
+        Self {
+            schema_version: BUILD_REPORT_VERSION,
+            file: file.to_string(),
+            dry_run,
+            images: Vec::new(),
+            total_ms: 0,
+        }
+    }
+
+    /// Number of images actually imported.
+    pub fn imported(&self) -> usize {
+        self.images
+            .iter()
+            .filter(|image| image.status == ImageStatus::Imported)
+            .count()
+    }
+}
+
+/// Collects step timings and optionally echoes them for the human view.
+pub struct StepRecorder {
+    steps: RefCell<Vec<StepTiming>>,
+    echo: bool,
+}
+
+impl StepRecorder {
+    /// Creates a recorder; `echo` prints each step as it finishes.
+    pub const fn new(echo: bool) -> Self {
+        Self {
+            steps: RefCell::new(Vec::new()),
+            echo,
+        }
+    }
+
+    /// Returns the recorded steps.
+    pub fn into_steps(self) -> Vec<StepTiming> {
+        self.steps.into_inner()
+    }
+}
+
+impl ImportProgress for StepRecorder {
+    fn step_finished(&self, timing: &StepTiming) {
+        if self.echo {
+            println!("    {}", format_step(timing));
+        }
+        self.steps.borrow_mut().push(timing.clone());
+    }
+}
+
+/// Renders one step for the human build output.
+pub fn format_step(timing: &StepTiming) -> String {
+    let mut line = format!("{:<9} {:>6} ms", timing.step.as_str(), timing.duration_ms);
+    if let Some(detail) = &timing.detail {
+        line.push_str("  ");
+        line.push_str(detail);
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use std::time::Duration;
+
+    use containust_image::progress::ImportStep;
+
+    use super::*;
+
+    fn step(step: ImportStep, millis: u64) -> StepTiming {
+        StepTiming::new(step, None, Duration::from_millis(millis))
+    }
+
+    #[test]
+    fn image_report_sums_mocked_step_durations() {
+        let recorder = StepRecorder::new(false);
+        recorder.step_finished(&step(ImportStep::Resolve, 3));
+        recorder.step_finished(&step(ImportStep::Download, 1200));
+        recorder.step_finished(&step(ImportStep::Commit, 40));
+        let image = ImageBuildReport::new(
+            "web",
+            "preset://alpine",
+            (ImageStatus::Imported, Some("abc".into())),
+            recorder.into_steps(),
+        );
+        assert_eq!(image.duration_ms, 1243);
+        assert_eq!(image.steps.len(), 3);
+        assert_eq!(image.steps[1].step, ImportStep::Download);
+    }
+
+    #[test]
+    fn build_report_counts_only_imported_images() {
+        let mut report = BuildReport::new("app.ctst", false);
+        report.images.push(ImageBuildReport::new(
+            "web",
+            "file:///opt/web",
+            (ImageStatus::Imported, None),
+            Vec::new(),
+        ));
+        report.images.push(ImageBuildReport::new(
+            "db",
+            "image://db",
+            (ImageStatus::Cached, None),
+            Vec::new(),
+        ));
+        assert_eq!(report.imported(), 1);
+    }
+
+    #[test]
+    fn build_report_json_is_stable() {
+        let mut report = BuildReport::new("app.ctst", false);
+        report.images.push(ImageBuildReport::new(
+            "web",
+            "tar:///tmp/web.tar",
+            (ImageStatus::Imported, Some("ff".into())),
+            vec![
+                step(ImportStep::Pack, 5),
+                StepTiming::new(
+                    ImportStep::Commit,
+                    Some("ff".into()),
+                    Duration::from_millis(2),
+                ),
+            ],
+        ));
+        report.total_ms = 9;
+        let json = serde_json::to_string(&report).expect("serialize");
+        assert_eq!(
+            json,
+            r#"{"schema_version":1,"file":"app.ctst","dry_run":false,"images":[{"component":"web","source":"tar:///tmp/web.tar","status":"imported","digest":"ff","steps":[{"step":"pack","duration_ms":5},{"step":"commit","detail":"ff","duration_ms":2}],"duration_ms":7}],"total_ms":9}"#
+        );
+        let parsed: BuildReport = serde_json::from_str(&json).expect("parse");
+        assert_eq!(parsed, report);
+    }
+
+    #[test]
+    fn format_step_aligns_label_and_detail() {
+        let timing = StepTiming::new(
+            ImportStep::Commit,
+            Some("abc".into()),
+            Duration::from_millis(12),
+        );
+        assert_eq!(format_step(&timing), "commit        12 ms  abc");
+    }
+}
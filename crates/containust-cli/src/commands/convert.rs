@@ -1,44 +1,152 @@
-//! `ctst convert` — Convert a docker-compose.yml to `.ctst` format.
+//! `ctst convert` — Convert a docker-compose.yml or Kubernetes manifests
+//! to `.ctst` format.
 
 use std::path::PathBuf;
 
 use clap::Args;
 
+use crate::converter::{ConversionSummary, ConvertOptions, SourceFormat};
+
 /// Arguments for the `convert` subcommand.
 #[derive(Args, Debug)]
 pub struct ConvertArgs {
-    /// Path to the docker-compose.yml file.
+    /// Path to the docker-compose.yml file or Kubernetes manifest.
     #[arg(default_value = "docker-compose.yml")]
     pub file: PathBuf,
 
+    /// Format of the input file.
+    #[arg(long, value_enum, default_value_t = SourceFormat::Compose)]
+    pub from: SourceFormat,
+
     /// Write output to a file instead of stdout.
     #[arg(short, long)]
     pub output: Option<PathBuf>,
+
+    /// Print the conversion summary as JSON.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Substitute `${VAR}` references from the current environment instead
+    /// of preserving them (compose only).
+    #[arg(long)]
+    pub expand_env: bool,
+
+    /// Print the generated `.ctst` and check that it parses, validates,
+    /// and has a resolvable start order, without writing `--output`.
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 /// Executes the `convert` command.
 ///
+/// The conversion summary goes to stdout when `--output` is set and to
+/// stderr otherwise, so the `.ctst` on stdout stays pipeable. With
+/// `--dry-run` nothing is written and the `.ctst` always goes to stdout.
+///
 /// # Errors
 ///
-/// Returns an error if the file cannot be read, parsed, or written.
+/// Returns an error if the file cannot be read, parsed, or written, or a
+/// dry run finds the generated composition invalid.
 pub fn execute(args: ConvertArgs, _options: &super::RuntimeOptions) -> anyhow::Result<()> {
     let input = &args.file;
-    tracing::info!(path = %input.display(), "converting docker-compose file");
+    tracing::info!(path = %input.display(), from = ?args.from, "converting file");
 
     if !input.exists() {
         anyhow::bail!("file not found: {}", input.display());
     }
 
-    let ctst_output = crate::converter::convert_file(input)?;
+    let (ctst_output, summary) = convert(&args)?;
+    let report = if args.json {
+        format!("{}\n", serde_json::to_string_pretty(&summary)?)
+    } else {
+        summary.to_text()
+    };
 
-    if let Some(ref out_path) = args.output {
+    if args.dry_run {
+        print!("{ctst_output}");
+        eprint!("{report}");
+        if let Some(ref out_path) = args.output {
+            eprintln!("Dry run: {} not written", out_path.display());
+        }
+    } else if let Some(ref out_path) = args.output {
         std::fs::write(out_path, &ctst_output)?;
-        println!("Converted {} -> {}", input.display(), out_path.display());
-        println!("Components: {}", ctst_output.matches("COMPONENT ").count());
-        println!("Connections: {}", ctst_output.matches("CONNECT ").count());
+        if !args.json {
+            println!("Converted {} -> {}", input.display(), out_path.display());
+        }
+        print!("{report}");
     } else {
         print!("{ctst_output}");
+        eprint!("{report}");
     }
 
     Ok(())
 }
+
+/// Converts `args.file`; a dry run also checks the generated composition
+/// has a resolvable start order.
+fn convert(args: &ConvertArgs) -> anyhow::Result<(String, ConversionSummary)> {
+    let (ctst_output, summary) = match args.from {
+        SourceFormat::Compose => crate::converter::convert_file(
+            &args.file,
+            ConvertOptions {
+                expand_env: args.expand_env,
+            },
+        )?,
+        SourceFormat::Kubernetes => crate::converter::convert_kubernetes_file(&args.file)?,
+    };
+    if args.dry_run {
+        let composition = containust_compose::parser::parse_ctst(&ctst_output)
+            .map_err(|e| anyhow::anyhow!("generated .ctst is invalid: {e}"))?;
+        let _ = containust_compose::graph::DependencyGraph::from_composition(&composition)
+            .resolve_order()
+            .map_err(|e| anyhow::anyhow!("generated .ctst is invalid: {e}"))?;
+    }
+    Ok((ctst_output, summary))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    fn dry_run_args(dir: &std::path::Path, compose: &str) -> ConvertArgs {
+        let file = dir.join("docker-compose.yml");
+        std::fs::write(&file, compose).expect("write compose");
+        ConvertArgs {
+            file,
+            from: SourceFormat::Compose,
+            output: Some(dir.join("out.ctst")),
+            json: false,
+            expand_env: false,
+            dry_run: true,
+        }
+    }
+
+    #[test]
+    fn dry_run_validates_without_writing_output() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let args = dry_run_args(dir.path(), "services:\n  web:\n    image: nginx:1.25\n");
+        let (ctst_output, _) = convert(&args).expect("convert");
+        let composition =
+            containust_compose::parser::parse_ctst(&ctst_output).expect("parseable output");
+        assert_eq!(composition.components[0].name, "web");
+
+        execute(args, &super::super::RuntimeOptions::default()).expect("dry run");
+        assert!(!dir.path().join("out.ctst").exists());
+    }
+
+    #[test]
+    fn dry_run_rejects_unresolvable_composition() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let args = dry_run_args(
+            dir.path(),
+            "services:\n  a:\n    image: a\n    depends_on: [b]\n  b:\n    image: b\n    depends_on: [a]\n",
+        );
+        let error = execute(args, &super::super::RuntimeOptions::default())
+            .expect_err("cycle")
+            .to_string();
+        assert!(error.contains("generated .ctst is invalid"), "{error}");
+        assert!(!dir.path().join("out.ctst").exists());
+    }
+}
@@ -0,0 +1,63 @@
+//! `ctst create` — Create containers without starting them.
+
+use std::path::Path;
+
+use clap::Args;
+use containust_runtime::engine::DeployedComponent;
+
+use super::adhoc::AdhocArgs;
+
+/// Arguments for the `create` command.
+#[derive(Args, Debug)]
+pub struct CreateArgs {
+    /// Path to the .ctst composition file (ignored with `--image`).
+    #[arg(default_value = "containust.ctst")]
+    pub file: String,
+
+    /// Single-container flags used instead of a `.ctst` file.
+    #[command(flatten)]
+    pub adhoc: AdhocArgs,
+
+    /// Command (and arguments) for the ad-hoc container; must come last.
+    #[arg(long, requires = "image", num_args = 1.., allow_hyphen_values = true)]
+    pub command: Vec<String>,
+}
+
+/// Executes the `create` command.
+///
+/// Prints the id of every created container on stdout, one per line.
+///
+/// # Errors
+///
+/// Returns an error if the composition is missing or creation fails.
+pub fn execute(args: CreateArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    options.cancel_on_interrupt()?;
+    let created = if let Some((composition, limits)) = args.adhoc.into_composition(args.command) {
+        options
+            .engine()
+            .with_limit_overrides(vec![limits])
+            .create_composition(&composition)
+            .map_err(|e| anyhow::anyhow!("{e}"))?
+    } else {
+        let path = Path::new(&args.file);
+        if !path.exists() {
+            return Err(anyhow::anyhow!(
+                "Composition file not found: {}\n\
+                 Create a .ctst file, specify a path, or pass --image",
+                args.file
+            ));
+        }
+        options
+            .engine_for_project(path)
+            .create(path)
+            .map_err(|e| anyhow::anyhow!("{e}"))?
+    };
+    print_created(&created);
+    Ok(())
+}
+
+fn print_created(created: &[DeployedComponent]) {
+    for component in created {
+        println!("{}", component.id);
+    }
+}
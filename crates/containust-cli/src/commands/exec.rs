@@ -1,6 +1,7 @@
 //! `ctst exec` — Execute a command inside a running container.
 
 use clap::Args;
+use containust_runtime::exec::ExecOptions;
 
 /// Arguments for the `exec` command.
 #[derive(Args, Debug)]
@@ -8,6 +9,18 @@ pub struct ExecArgs {
     /// Container ID or name.
     pub container: String,
 
+    /// Working directory for the command (default: the container's).
+    #[arg(short = 'w', long)]
+    pub workdir: Option<String>,
+
+    /// Numeric `UID[:GID]` to run as (default: the container's).
+    #[arg(short = 'u', long)]
+    pub user: Option<String>,
+
+    /// Extra environment variable, overriding the container's (repeatable).
+    #[arg(short = 'e', long = "env", value_name = "KEY=VALUE", value_parser = super::adhoc::parse_env_pair)]
+    pub env: Vec<(String, String)>,
+
     /// Command to execute.
     #[arg(trailing_var_arg = true, required = true)]
     pub command: Vec<String>,
@@ -16,7 +29,8 @@ pub struct ExecArgs {
 /// Executes the `exec` command.
 ///
 /// Joins the target container's namespaces and runs the specified
-/// command, forwarding stdout/stderr.
+/// command, forwarding stdout/stderr. Unset flags inherit the container's
+/// workdir, user, and environment.
 ///
 /// # Errors
 ///
@@ -24,8 +38,13 @@ pub struct ExecArgs {
 pub fn execute(args: ExecArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
     let engine = options.engine();
     let id = super::resolve_container_id(&engine, &args.container)?;
+    let exec_options = ExecOptions {
+        workdir: args.workdir,
+        user: args.user,
+        env: args.env,
+    };
     let output = engine
-        .exec(&id, &args.command)
+        .exec_with(&id, &args.command, &exec_options)
         .map_err(|e| anyhow::anyhow!("{e}"))?;
 
     if !output.stdout.is_empty() {
@@ -0,0 +1,62 @@
+//! `ctst graph` — Render the dependency graph of a composition.
+
+use clap::{Args, ValueEnum};
+use containust_compose::graph::DependencyGraph;
+use containust_compose::input::CompositionSource;
+
+/// Output format of `ctst graph`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT.
+    #[default]
+    Dot,
+    /// Nodes and edges as JSON.
+    Json,
+    /// Mermaid flowchart for Markdown docs.
+    Mermaid,
+}
+
+/// Arguments for the `graph` command.
+#[derive(Args, Debug)]
+pub struct GraphArgs {
+    /// Path to the .ctst composition file (`-` reads stdin).
+    #[arg(default_value = "containust.ctst")]
+    pub file: String,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = GraphFormat::Dot)]
+    pub format: GraphFormat,
+}
+
+/// Executes the `graph` command.
+///
+/// Parses the composition, checks that the graph is acyclic, and prints
+/// it with edges pointing from each dependency to its dependent.
+///
+/// # Errors
+///
+/// Returns an error if parsing fails or the graph has a cycle.
+pub fn execute(args: GraphArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    let composition = CompositionSource::from_arg(&args.file)
+        .parse()
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    if options.offline {
+        containust_compose::validate_offline(&composition).map_err(|e| anyhow::anyhow!("{e}"))?;
+    }
+    let graph = DependencyGraph::from_composition(&composition);
+    let _ = graph.resolve_order().map_err(|e| anyhow::anyhow!("{e}"))?;
+    print!("{}", render(&graph, args.format)?);
+    Ok(())
+}
+
+fn render(graph: &DependencyGraph, format: GraphFormat) -> anyhow::Result<String> {
+    Ok(match format {
+        GraphFormat::Dot => graph.to_dot(),
+        GraphFormat::Json => {
+            let mut json = graph.to_json().map_err(|e| anyhow::anyhow!("{e}"))?;
+            json.push('\n');
+            json
+        }
+        GraphFormat::Mermaid => graph.to_mermaid(),
+    })
+}
@@ -0,0 +1,104 @@
+//! `ctst inspect` — Print a container's full details as JSON.
+
+use clap::Args;
+use containust_runtime::backend::ContainerDetail;
+use containust_runtime::engine::Engine;
+use containust_runtime::metrics::{MetricsSnapshot, collect_metrics};
+use serde::Serialize;
+
+/// Arguments for the `inspect` command.
+#[derive(Args, Debug)]
+pub struct InspectArgs {
+    /// Container ID or name.
+    pub container: String,
+}
+
+/// Tracked configuration plus live usage of one container.
+#[derive(Serialize)]
+struct InspectReport {
+    #[serde(flatten)]
+    detail: ContainerDetail,
+    /// Live resource usage; absent when the container is not running.
+    metrics: Option<MetricsSnapshot>,
+}
+
+/// Executes the `inspect` command.
+///
+/// Prints the container's state entry, its rootfs and log paths, and
+/// live metrics as pretty-printed JSON.
+///
+/// # Errors
+///
+/// Returns an error if no container matches or its state cannot be read.
+pub fn execute(args: InspectArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    let engine = options.engine();
+    println!("{}", inspect_json(&engine, &args.container)?);
+    Ok(())
+}
+
+fn inspect_json(engine: &Engine, target: &str) -> anyhow::Result<String> {
+    let id = super::resolve_container_id(engine, target)?;
+    let detail = engine.inspect(&id).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let metrics = if detail.state == "running" {
+        collect_metrics(&engine.project_id(), &id).ok()
+    } else {
+        None
+    };
+    Ok(serde_json::to_string_pretty(&InspectReport {
+        detail,
+        metrics,
+    })?)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use containust_runtime::engine::EngineOptions;
+
+    use super::*;
+
+    fn engine_with_state(dir: &std::path::Path) -> Engine {
+        let state_file = dir.join("state.json");
+        std::fs::write(
+            &state_file,
+            r#"{"containers":[{
+                "id": "c0ffee",
+                "name": "web",
+                "state": "Stopped",
+                "pid": null,
+                "image": "file:///images/web",
+                "rootfs_path": "/tmp/rootfs/c0ffee",
+                "log_path": "/tmp/logs/c0ffee.log",
+                "created_at": "2026-01-01T00:00:00Z"
+            }]}"#,
+        )
+        .expect("write state");
+        Engine::with_options(EngineOptions {
+            data_dir: dir.to_path_buf(),
+            state_file,
+            offline: true,
+            backend: containust_runtime::backend::BackendKind::Native,
+        })
+    }
+
+    #[test]
+    fn inspect_prints_state_entry_by_name() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let engine = engine_with_state(dir.path());
+        let json: serde_json::Value =
+            serde_json::from_str(&inspect_json(&engine, "web").expect("inspect")).expect("json");
+        assert_eq!(json["id"], "c0ffee");
+        assert_eq!(json["state"], "stopped");
+        assert_eq!(json["log_path"], "/tmp/logs/c0ffee.log");
+        assert!(json["metrics"].is_null());
+    }
+
+    #[test]
+    fn inspect_unknown_container_fails() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let engine = engine_with_state(dir.path());
+        let error = inspect_json(&engine, "missing").expect_err("unknown container");
+        assert!(error.to_string().contains("missing"), "{error}");
+    }
+}
@@ -0,0 +1,179 @@
+//! `ctst kill` — Send a signal to a container's main process.
+
+use clap::Args;
+use containust_runtime::engine::Engine;
+use containust_runtime::signal::ContainerSignal;
+
+/// Arguments for the `kill` command.
+#[derive(Args, Debug)]
+pub struct KillArgs {
+    /// Container IDs or names to signal.
+    #[arg(required = true)]
+    pub containers: Vec<String>,
+
+    /// Signal name or number (e.g. `SIGHUP`, `HUP`, `1`).
+    #[arg(short, long, default_value = "SIGKILL", value_parser = parse_signal_arg)]
+    pub signal: ContainerSignal,
+}
+
+/// Executes the `kill` command.
+///
+/// Unlike `ctst stop`, the container's tracked state is left untouched; a
+/// signal that terminates the process is picked up by the next `ctst ps`.
+///
+/// # Errors
+///
+/// Returns an error when a target is missing, not running, or cannot be
+/// signalled.
+pub fn execute(args: KillArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    signal_containers(&options.engine(), &args)
+}
+
+fn signal_containers(engine: &Engine, args: &KillArgs) -> anyhow::Result<()> {
+    let containers = engine.list().map_err(|e| anyhow::anyhow!("{e}"))?;
+    for target in &args.containers {
+        let id = super::resolve_container_id_from(&containers, target)?;
+        engine
+            .kill(&id, args.signal)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        println!("Sent {}: {target}", args.signal);
+    }
+    Ok(())
+}
+
+/// Parses a `--signal` value against the known signal table.
+pub(super) fn parse_signal_arg(raw: &str) -> Result<ContainerSignal, String> {
+    ContainerSignal::parse(raw).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use std::sync::{Arc, Mutex};
+
+    use containust_common::error::Result;
+    use containust_common::types::ContainerId;
+    use containust_runtime::backend::{ContainerBackend, ContainerConfig, ContainerInfo};
+    use containust_runtime::engine::EngineOptions;
+    use containust_runtime::exec::ExecOutput;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct SignalBackend {
+        sent: Arc<Mutex<Vec<(ContainerId, ContainerSignal)>>>,
+    }
+
+    impl ContainerBackend for SignalBackend {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn create(&self, _config: &ContainerConfig) -> Result<ContainerId> {
+            Ok(ContainerId::new("unused"))
+        }
+
+        fn start(&self, _id: &ContainerId) -> Result<u32> {
+            Ok(1)
+        }
+
+        fn stop(&self, _id: &ContainerId) -> Result<()> {
+            Ok(())
+        }
+
+        fn signal(&self, id: &ContainerId, signal: ContainerSignal) -> Result<()> {
+            self.sent
+                .lock()
+                .expect("sent lock")
+                .push((id.clone(), signal));
+            Ok(())
+        }
+
+        fn exec(&self, _id: &ContainerId, _cmd: &[String]) -> Result<ExecOutput> {
+            Ok(ExecOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code: 0,
+            })
+        }
+
+        fn remove(&self, _id: &ContainerId) -> Result<()> {
+            Ok(())
+        }
+
+        fn logs(&self, _id: &ContainerId) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn list(&self) -> Result<Vec<ContainerInfo>> {
+            Ok(vec![ContainerInfo {
+                id: ContainerId::new("id-web"),
+                name: "web".into(),
+                state: "running".into(),
+                pid: Some(7),
+                image: "file:///image".into(),
+                created_at: "2026-01-01T00:00:00Z".into(),
+                started_at: None,
+                finished_at: None,
+                exit_code: None,
+                health: None,
+            }])
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    fn engine_with(backend: SignalBackend, dir: &std::path::Path) -> Engine {
+        let options = EngineOptions {
+            data_dir: dir.to_path_buf(),
+            state_file: dir.join("state.json"),
+            offline: true,
+            backend: containust_runtime::backend::BackendKind::Auto,
+        };
+        Engine::with_backend(options, Box::new(backend))
+    }
+
+    #[test]
+    fn parse_signal_arg_validates_names() {
+        assert_eq!(parse_signal_arg("HUP"), Ok(ContainerSignal::HUP));
+        let error = parse_signal_arg("SIGNOPE").expect_err("unknown signal");
+        assert!(error.contains("'SIGNOPE'"), "{error}");
+    }
+
+    #[test]
+    fn kill_resolves_name_and_dispatches_signal() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = SignalBackend::default();
+        let sent = Arc::clone(&backend.sent);
+        let engine = engine_with(backend, dir.path());
+        let args = KillArgs {
+            containers: vec!["web".into()],
+            signal: ContainerSignal::HUP,
+        };
+
+        signal_containers(&engine, &args).expect("kill");
+        let sent = sent.lock().expect("sent lock").clone();
+        assert_eq!(
+            sent,
+            vec![(ContainerId::new("id-web"), ContainerSignal::HUP)]
+        );
+    }
+
+    #[test]
+    fn kill_unknown_container_sends_nothing() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = SignalBackend::default();
+        let sent = Arc::clone(&backend.sent);
+        let engine = engine_with(backend, dir.path());
+        let args = KillArgs {
+            containers: vec!["missing".into()],
+            signal: ContainerSignal::TERM,
+        };
+
+        assert!(signal_containers(&engine, &args).is_err());
+        assert!(sent.lock().expect("sent lock").is_empty());
+    }
+}
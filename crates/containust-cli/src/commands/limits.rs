@@ -0,0 +1,94 @@
+//! Resource limit flag parsing shared by the run/create commands.
+
+use containust_common::units;
+use containust_runtime::engine::LimitOverride;
+
+/// Parses a `--memory` value such as `256MiB`.
+pub(super) fn parse_memory_arg(raw: &str) -> Result<u64, String> {
+    units::parse_bytes(raw).map_err(|e| e.to_string())
+}
+
+/// Parses a `--cpu` value such as `1.5` (cores) or `512` (weight).
+pub(super) fn parse_cpu_arg(raw: &str) -> Result<u64, String> {
+    units::parse_cpu(raw).map_err(|e| e.to_string())
+}
+
+/// Parses a `--pids` value such as `128`.
+pub(super) fn parse_pids_arg(raw: &str) -> Result<u64, String> {
+    units::parse_pids(raw).map_err(|e| e.to_string())
+}
+
+/// Parses `COMPONENT:KEY=VALUE[,KEY=VALUE...]` with keys `memory`, `cpu`, `pids`.
+pub(super) fn parse_limit_override(raw: &str) -> Result<LimitOverride, String> {
+    let (component, assignments) = raw
+        .split_once(':')
+        .filter(|(component, assignments)| !component.is_empty() && !assignments.is_empty())
+        .ok_or_else(|| format!("expected COMPONENT:KEY=VALUE, got '{raw}'"))?;
+    let mut limits = LimitOverride {
+        component: component.to_string(),
+        ..LimitOverride::default()
+    };
+    for assignment in assignments.split(',') {
+        let (key, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| format!("expected KEY=VALUE in '{raw}', got '{assignment}'"))?;
+        match key.trim() {
+            "memory" => limits.memory_bytes = Some(parse_memory_arg(value)?),
+            "cpu" => limits.cpu_shares = Some(parse_cpu_arg(value)?),
+            "pids" => limits.pids_max = Some(parse_pids_arg(value)?),
+            other => {
+                return Err(format!(
+                    "unknown limit '{other}' in '{raw}' (expected memory, cpu, or pids)"
+                ));
+            }
+        }
+    }
+    Ok(limits)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn parse_memory_arg_reports_offending_value() {
+        assert_eq!(parse_memory_arg("256MiB"), Ok(256 * 1024 * 1024));
+        let error = parse_memory_arg("huge").expect_err("invalid memory");
+        assert!(error.contains("'huge'"), "{error}");
+    }
+
+    #[test]
+    fn parse_cpu_arg_accepts_decimal_cores() {
+        assert_eq!(parse_cpu_arg("1.5"), Ok(1536));
+        assert!(parse_cpu_arg("0").expect_err("zero").contains("'0'"));
+    }
+
+    #[test]
+    fn parse_pids_arg_requires_positive_count() {
+        assert_eq!(parse_pids_arg("128"), Ok(128));
+        assert!(parse_pids_arg("0").is_err());
+    }
+
+    #[test]
+    fn parse_limit_override_single_and_combined_keys() {
+        let single = parse_limit_override("web:memory=512MiB").expect("single");
+        assert_eq!(single.component, "web");
+        assert_eq!(single.memory_bytes, Some(512 * 1024 * 1024));
+        assert_eq!(single.cpu_shares, None);
+
+        let combined = parse_limit_override("db:cpu=0.5,pids=64").expect("combined");
+        assert_eq!(combined.cpu_shares, Some(512));
+        assert_eq!(combined.pids_max, Some(64));
+    }
+
+    #[test]
+    fn parse_limit_override_rejects_malformed_input() {
+        assert!(parse_limit_override("memory=512MiB").is_err());
+        assert!(parse_limit_override(":memory=1GiB").is_err());
+        assert!(parse_limit_override("web:swap=1GiB").is_err());
+        let error = parse_limit_override("web:memory=lots").expect_err("bad value");
+        assert!(error.contains("'lots'"), "{error}");
+    }
+}
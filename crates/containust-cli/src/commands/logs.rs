@@ -1,9 +1,8 @@
 //! `ctst logs` — View container logs.
 
 use clap::Args;
+use containust_runtime::logs::LogOptions;
 use std::io::Write;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 /// Arguments for the `logs` command.
@@ -15,25 +14,176 @@ pub struct LogsArgs {
     /// Follow log output.
     #[arg(short, long)]
     pub follow: bool,
+
+    /// Print the log of the previous run, kept when the container was
+    /// last restarted.
+    #[arg(long, conflicts_with = "follow")]
+    pub previous: bool,
+
+    /// Only print the last N lines.
+    #[arg(long, value_name = "N")]
+    pub tail: Option<usize>,
+
+    /// Only print lines written within DURATION (e.g. `30s`, `5m`, `1h`).
+    #[arg(long, value_name = "DURATION", value_parser = super::stop::parse_duration_arg)]
+    pub since: Option<Duration>,
+
+    /// Line filtering.
+    #[command(flatten)]
+    pub filter: GrepArgs,
+}
+
+impl LogsArgs {
+    /// The `--tail` / `--since` selection, with `--since` counted back
+    /// from `now`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `--since` reaches before the representable range.
+    pub fn log_options(&self, now: chrono::DateTime<chrono::Utc>) -> anyhow::Result<LogOptions> {
+        let since = self
+            .since
+            .map(|window| {
+                chrono::Duration::from_std(window)
+                    .ok()
+                    .and_then(|window| now.checked_sub_signed(window))
+                    .ok_or_else(|| anyhow::anyhow!("--since {window:?} is out of range"))
+            })
+            .transpose()?;
+        Ok(LogOptions {
+            tail: self.tail,
+            since,
+        })
+    }
+}
+
+/// `--grep` line filtering flags.
+#[derive(Args, Debug, Default)]
+pub struct GrepArgs {
+    /// Only print lines containing PATTERN.
+    #[arg(long, value_name = "PATTERN")]
+    pub grep: Option<String>,
+
+    /// Print lines that do not match `--grep` instead.
+    #[arg(long = "grep-v", requires = "grep")]
+    pub invert: bool,
+
+    /// Match `--grep` case-insensitively.
+    #[arg(long, requires = "grep")]
+    pub ignore_case: bool,
+
+    /// Treat `--grep` as a regular expression.
+    #[arg(long, requires = "grep")]
+    pub regex: bool,
+}
+
+/// Line predicate built from the `--grep` family of flags.
+#[derive(Debug)]
+pub struct LineFilter {
+    matcher: Matcher,
+    invert: bool,
+}
+
+#[derive(Debug)]
+enum Matcher {
+    /// Substring, lowercased when matching case-insensitively.
+    Substring {
+        needle: String,
+        ignore_case: bool,
+    },
+    Regex(regex::Regex),
+}
+
+impl LineFilter {
+    /// Builds the filter for `args`, or `None` when `--grep` is absent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when `--regex` is set and the pattern is invalid.
+    pub fn from_args(args: &GrepArgs) -> anyhow::Result<Option<Self>> {
+        let Some(pattern) = &args.grep else {
+            return Ok(None);
+        };
+        let matcher = if args.regex {
+            let regex = regex::RegexBuilder::new(pattern)
+                .case_insensitive(args.ignore_case)
+                .build()
+                .map_err(|e| anyhow::anyhow!("invalid --grep regex: {e}"))?;
+            Matcher::Regex(regex)
+        } else {
+            Matcher::Substring {
+                needle: if args.ignore_case {
+                    pattern.to_lowercase()
+                } else {
+                    pattern.clone()
+                },
+                ignore_case: args.ignore_case,
+            }
+        };
+        Ok(Some(Self {
+            matcher,
+            invert: args.invert,
+        }))
+    }
+
+    /// Returns whether `line` should be printed.
+    #[must_use]
+    pub fn keeps(&self, line: &str) -> bool {
+        let hit = match &self.matcher {
+            Matcher::Substring {
+                needle,
+                ignore_case: true,
+            } => line.to_lowercase().contains(needle.as_str()),
+            Matcher::Substring { needle, .. } => line.contains(needle.as_str()),
+            Matcher::Regex(regex) => regex.is_match(line),
+        };
+        hit != self.invert
+    }
+
+    /// Keeps the complete lines of `text` that pass, newlines included.
+    #[must_use]
+    pub fn apply(&self, text: &str) -> String {
+        text.split_inclusive('\n')
+            .filter(|line| self.keeps(line.trim_end_matches(['\r', '\n'])))
+            .collect()
+    }
 }
 
 /// Executes the `logs` command.
 ///
-/// Retrieves and displays logs for the specified container.
+/// Retrieves and displays logs for the specified container. `--since`
+/// relies on timestamped log lines; untimed lines follow the timed line
+/// before them.
 ///
 /// # Errors
 ///
 /// Returns an error if the container is not found or logs are unavailable.
 pub fn execute(args: LogsArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    let filter = LineFilter::from_args(&args.filter)?;
+    let selection = args.log_options(chrono::Utc::now())?;
     let engine = options.engine();
     let id = super::resolve_container_id(&engine, &args.container)?;
     if args.follow {
-        return follow(&engine, &id);
+        options.cancel_on_interrupt()?;
+        return follow(&engine, &id, filter.as_ref(), &selection);
+    }
+    let mut logs = if args.previous {
+        engine.previous_logs(&id, &selection)
+    } else {
+        engine.logs_with(&id, &selection)
+    }
+    .map_err(|e| anyhow::anyhow!("{e}"))?;
+    if let Some(filter) = &filter {
+        logs = filter.apply(&logs);
     }
-    let logs = engine.logs(&id).map_err(|e| anyhow::anyhow!("{e}"))?;
 
     if logs.is_empty() {
-        println!("No logs available for container: {}", args.container);
+        let kind = if args.previous {
+            "previous logs"
+        } else {
+            "logs"
+        };
+        println!("No {kind} available for container: {}", args.container);
     } else {
         print!("{logs}");
     }
@@ -41,20 +191,31 @@ pub fn execute(args: LogsArgs, options: &super::RuntimeOptions) -> anyhow::Resul
     Ok(())
 }
 
+/// Streams new log output until Ctrl+C cancels the engine's token.
 fn follow(
     engine: &containust_runtime::engine::Engine,
     id: &containust_common::types::ContainerId,
+    filter: Option<&LineFilter>,
+    selection: &LogOptions,
 ) -> anyhow::Result<()> {
-    let running = Arc::new(AtomicBool::new(true));
-    let signal = Arc::clone(&running);
-    ctrlc::set_handler(move || signal.store(false, Ordering::Release))
-        .map_err(|error| anyhow::anyhow!("failed to install Ctrl+C handler: {error}"))?;
-
-    let mut offset = 0;
-    while running.load(Ordering::Acquire) {
-        let (content, next) =
+    let mut offset = if selection.is_full() {
+        0
+    } else {
+        print_backlog(engine, id, filter, selection)?
+    };
+    // Filtering works on whole lines, so a trailing partial line waits
+    // for the rest of it to arrive.
+    let mut partial = String::new();
+    while !engine.cancellation().is_cancelled() {
+        let (mut content, next) =
             containust_runtime::logs::read_logs_from(engine.data_dir(), id.as_str(), offset)
                 .map_err(|error| anyhow::anyhow!("{error}"))?;
+        if let Some(filter) = filter {
+            partial.push_str(&content);
+            let complete = partial.rfind('\n').map_or(0, |end| end + 1);
+            content = filter.apply(&partial[..complete]);
+            partial = partial.split_off(complete);
+        }
         if !content.is_empty() {
             print!("{content}");
             std::io::stdout().flush()?;
@@ -64,3 +225,120 @@ fn follow(
     }
     Ok(())
 }
+
+/// Prints the `--tail` / `--since` selection and returns the log offset
+/// to follow from.
+fn print_backlog(
+    engine: &containust_runtime::engine::Engine,
+    id: &containust_common::types::ContainerId,
+    filter: Option<&LineFilter>,
+    selection: &LogOptions,
+) -> anyhow::Result<u64> {
+    let (_, end) =
+        containust_runtime::logs::read_logs_from(engine.data_dir(), id.as_str(), u64::MAX)
+            .map_err(|error| anyhow::anyhow!("{error}"))?;
+    let mut backlog = engine
+        .logs_with(id, selection)
+        .map_err(|error| anyhow::anyhow!("{error}"))?;
+    if let Some(filter) = filter {
+        backlog = filter.apply(&backlog);
+    }
+    print!("{backlog}");
+    Ok(end)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    const SAMPLE: &str = "INFO starting\nERROR disk full\nwarn: error budget low\nINFO done\n";
+
+    fn filter(pattern: &str, invert: bool, ignore_case: bool, regex: bool) -> LineFilter {
+        let args = GrepArgs {
+            grep: Some(pattern.into()),
+            invert,
+            ignore_case,
+            regex,
+        };
+        LineFilter::from_args(&args)
+            .expect("valid filter")
+            .expect("filter present")
+    }
+
+    #[test]
+    fn substring_filter_keeps_matching_lines() {
+        assert_eq!(
+            filter("ERROR", false, false, false).apply(SAMPLE),
+            "ERROR disk full\n"
+        );
+    }
+
+    #[test]
+    fn inverted_filter_drops_matching_lines() {
+        assert_eq!(
+            filter("INFO", true, false, false).apply(SAMPLE),
+            "ERROR disk full\nwarn: error budget low\n"
+        );
+    }
+
+    #[test]
+    fn ignore_case_matches_any_case() {
+        assert_eq!(
+            filter("error", false, true, false).apply(SAMPLE),
+            "ERROR disk full\nwarn: error budget low\n"
+        );
+    }
+
+    #[test]
+    fn regex_filter_matches_patterns() {
+        assert_eq!(
+            filter(r"^(ERROR|warn)\b", false, false, true).apply(SAMPLE),
+            "ERROR disk full\nwarn: error budget low\n"
+        );
+        assert_eq!(
+            filter("^info", false, true, true).apply(SAMPLE),
+            "INFO starting\nINFO done\n"
+        );
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected() {
+        let args = GrepArgs {
+            grep: Some("(".into()),
+            regex: true,
+            ..GrepArgs::default()
+        };
+        let error = LineFilter::from_args(&args).expect_err("invalid regex");
+        assert!(
+            error.to_string().contains("invalid --grep regex"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn since_counts_back_from_now() {
+        let args = LogsArgs {
+            container: "web".into(),
+            follow: false,
+            previous: false,
+            tail: Some(50),
+            since: Some(Duration::from_secs(300)),
+            filter: GrepArgs::default(),
+        };
+        let now = chrono::Utc::now();
+        let selection = args.log_options(now).expect("options");
+        assert_eq!(selection.tail, Some(50));
+        assert_eq!(selection.since, Some(now - chrono::Duration::minutes(5)));
+    }
+
+    #[test]
+    fn no_grep_means_no_filter() {
+        assert!(
+            LineFilter::from_args(&GrepArgs::default())
+                .expect("no filter")
+                .is_none()
+        );
+    }
+}
@@ -1,26 +1,42 @@
 //! CLI command definitions and dispatch.
 
+pub mod adhoc;
+pub mod attach;
 pub mod build;
+mod build_report;
 pub mod convert;
+pub mod create;
 pub mod doctor;
 pub mod exec;
+pub mod graph;
 pub mod images;
+pub mod inspect;
+pub mod kill;
+mod limits;
 pub mod logs;
 pub mod plan;
+mod plan_report;
+pub mod prune;
 pub mod ps;
 pub mod pull;
 pub mod remove;
+pub mod rename;
 pub mod run;
+pub mod start;
 pub mod stop;
+pub mod upgrade;
 pub mod vm;
 
 use clap::{Parser, Subcommand};
+use containust_common::cancel::CancellationToken;
+use containust_common::config::DefaultsConfig;
 use containust_common::types::ContainerId;
-use containust_runtime::backend::ContainerInfo;
+use containust_runtime::backend::{self, BackendKind, ContainerInfo};
 use containust_runtime::engine::{Engine, EngineOptions};
 use std::path::{Path, PathBuf};
 
 use crate::build_info;
+use crate::logging::LogFormat;
 
 const LONG_VERSION: &str = build_info::long_version();
 
@@ -45,6 +61,22 @@ pub struct Cli {
     /// Path to the state file.
     #[arg(long, global = true)]
     pub state_file: Option<String>,
+
+    /// Increase log verbosity (`-v` info, `-vv` debug, `-vvv` trace).
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Log errors only.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Log line format [env: `CONTAINUST_LOG_FORMAT`].
+    #[arg(long, global = true, value_enum)]
+    pub log_format: Option<LogFormat>,
+
+    /// Container backend: `native`, `vm`, or `auto` [env: `CONTAINUST_BACKEND`].
+    #[arg(long, global = true)]
+    pub backend: Option<BackendKind>,
 }
 
 /// Runtime settings shared by every CLI command.
@@ -54,24 +86,54 @@ pub struct RuntimeOptions {
     pub offline: bool,
     /// Optional explicit state index path.
     pub state_file: Option<PathBuf>,
+    /// `[defaults]` from the user config file.
+    pub defaults: DefaultsConfig,
+    /// Backend resolved for this host.
+    pub backend: BackendKind,
+    /// This command's cancellation, set by Ctrl+C once
+    /// [`Self::cancel_on_interrupt`] is installed.
+    pub cancel: CancellationToken,
 }
 
 impl RuntimeOptions {
-    fn from_cli(cli: &Cli) -> Self {
+    fn from_cli(cli: &Cli) -> anyhow::Result<Self> {
         let env_offline = std::env::var("CONTAINUST_OFFLINE").is_ok_and(|value| {
             matches!(
                 value.trim().to_ascii_lowercase().as_str(),
                 "1" | "true" | "yes"
             )
         });
-        Self {
+        let config =
+            containust_common::config::load_config().map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok(Self {
             offline: cli.offline || env_offline,
             state_file: cli
                 .state_file
                 .clone()
                 .map(PathBuf::from)
                 .or_else(|| std::env::var_os("CONTAINUST_STATE_FILE").map(PathBuf::from)),
-        }
+            defaults: config.defaults,
+            backend: resolve_backend(cli.backend, std::env::var(backend::BACKEND_ENV_VAR).ok())?,
+            cancel: CancellationToken::new(),
+        })
+    }
+
+    /// Makes Ctrl+C cancel this command's token, so deploys, pulls, and VM
+    /// boots stop at their next safe point and clean up. A second Ctrl+C
+    /// exits at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handler cannot be installed.
+    pub fn cancel_on_interrupt(&self) -> anyhow::Result<()> {
+        let cancel = self.cancel.clone();
+        ctrlc::set_handler(move || {
+            if cancel.is_cancelled() {
+                std::process::exit(130);
+            }
+            cancel.cancel();
+        })
+        .map_err(|error| anyhow::anyhow!("failed to install Ctrl+C handler: {error}"))
     }
 
     /// Creates an engine using this command's storage and policy.
@@ -104,10 +166,31 @@ impl RuntimeOptions {
             data_dir,
             state_file,
             offline: self.offline,
+            backend: self.backend,
         })
+        .with_limit_defaults(self.defaults.clone())
+        .with_cancellation(self.cancel.clone())
     }
 }
 
+/// Picks the backend from the `--backend` flag, else `CONTAINUST_BACKEND`,
+/// else `auto`, and checks it can run on this host.
+fn resolve_backend(flag: Option<BackendKind>, env: Option<String>) -> anyhow::Result<BackendKind> {
+    let requested = match (flag, env) {
+        (Some(kind), _) => kind,
+        (None, Some(raw)) => raw.parse().map_err(|e| anyhow::anyhow!("{e}"))?,
+        (None, None) => BackendKind::Auto,
+    };
+    backend::select_backend(requested, std::env::consts::OS).map_err(|e| anyhow::anyhow!("{e}"))
+}
+
+/// Parses a user-supplied container name against the safe name charset.
+fn parse_name_arg(raw: &str) -> Result<String, String> {
+    containust_common::types::validate_name(raw)
+        .map(|()| raw.to_string())
+        .map_err(|e| e.to_string())
+}
+
 fn resolve_container_id(engine: &Engine, target: &str) -> anyhow::Result<ContainerId> {
     let containers = engine.list().map_err(|e| anyhow::anyhow!("{e}"))?;
     resolve_container_id_from(&containers, target)
@@ -131,22 +214,40 @@ pub enum Command {
     Build(build::BuildArgs),
     /// Display the planned infrastructure changes before applying.
     Plan(plan::PlanArgs),
+    /// Render the composition's dependency graph (DOT, JSON, or Mermaid).
+    Graph(graph::GraphArgs),
     /// Deploy the component graph.
     Run(run::RunArgs),
+    /// Replace changed components of a running composition.
+    Upgrade(upgrade::UpgradeArgs),
+    /// Create containers without starting them.
+    Create(create::CreateArgs),
+    /// Start previously created containers.
+    Start(start::StartArgs),
     /// List running containers with real-time metrics.
     Ps(ps::PsArgs),
     /// Execute a command inside a running container.
     Exec(exec::ExecArgs),
+    /// Print a container's full details as JSON.
+    Inspect(inspect::InspectArgs),
+    /// Attach to a running container's main process stdio.
+    Attach(attach::AttachArgs),
     /// Stop containers and clean up resources.
     Stop(stop::StopArgs),
+    /// Send a signal to a container's main process.
+    Kill(kill::KillArgs),
     /// Remove stopped containers and their project-owned resources.
     #[command(name = "rm")]
     Remove(remove::RemoveArgs),
+    /// Rename a container.
+    Rename(rename::RenameArgs),
+    /// Remove containers left in `created` by a start that never ran.
+    Prune(prune::PruneArgs),
     /// Pull an OCI registry image into the local catalog.
     Pull(pull::PullArgs),
     /// Manage the local image catalog.
     Images(images::ImagesArgs),
-    /// Convert a docker-compose.yml to .ctst format.
+    /// Convert a docker-compose.yml or Kubernetes manifests to .ctst format.
     Convert(convert::ConvertArgs),
     /// View container logs.
     Logs(logs::LogsArgs),
@@ -165,6 +266,12 @@ pub enum VmCommand {
     Start(vm::VmStartArgs),
     /// Stop the VM backend.
     Stop(vm::VmStopArgs),
+    /// Stop the VM and boot it again with the same port forwards.
+    Restart(vm::VmRestartArgs),
+    /// Show whether the VM runs, its PID, uptime, and forwarded ports.
+    Status(vm::VmStatusArgs),
+    /// Show the VM's guest console output.
+    Logs(vm::VmLogsArgs),
 }
 
 /// Dispatches the parsed CLI command to its handler.
@@ -173,15 +280,24 @@ pub enum VmCommand {
 ///
 /// Returns an error if the command execution fails.
 pub fn execute(cli: Cli) -> anyhow::Result<()> {
-    let options = RuntimeOptions::from_cli(&cli);
+    let options = RuntimeOptions::from_cli(&cli)?;
     match cli.command {
         Command::Build(args) => build::execute(args, &options),
         Command::Plan(args) => plan::execute(args, &options),
+        Command::Graph(args) => graph::execute(args, &options),
         Command::Run(args) => run::execute(args, &options),
+        Command::Upgrade(args) => upgrade::execute(args, &options),
+        Command::Create(args) => create::execute(args, &options),
+        Command::Start(args) => start::execute(args, &options),
         Command::Ps(args) => ps::execute(args, &options),
         Command::Exec(args) => exec::execute(args, &options),
+        Command::Inspect(args) => inspect::execute(args, &options),
+        Command::Attach(args) => attach::execute(args, &options),
         Command::Stop(args) => stop::execute(args, &options),
+        Command::Kill(args) => kill::execute(args, &options),
         Command::Remove(args) => remove::execute(args, &options),
+        Command::Rename(args) => rename::execute(args, &options),
+        Command::Prune(args) => prune::execute(args, &options),
         Command::Pull(args) => pull::execute(args, &options),
         Command::Images(args) => images::execute(args, &options),
         Command::Convert(args) => convert::execute(args, &options),
@@ -189,6 +305,9 @@ pub fn execute(cli: Cli) -> anyhow::Result<()> {
         Command::Vm(subcommand) => match subcommand {
             VmCommand::Start(args) => vm::vm_start(args, &options),
             VmCommand::Stop(args) => vm::vm_stop(args, &options),
+            VmCommand::Restart(args) => vm::vm_restart(args, &options),
+            VmCommand::Status(args) => vm::vm_status(args, &options),
+            VmCommand::Logs(args) => vm::vm_logs(args, &options),
         },
         Command::Doctor(args) => doctor::execute(args, &options),
     }
@@ -238,6 +357,141 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_build_subcommand_parses_strict_flag() {
+        let cli = Cli::try_parse_from(&["ctst", "build", "--strict"]).expect("should parse");
+        match cli.command {
+            Command::Build(args) => assert!(args.strict && !args.dry_run),
+            other => panic!("expected Build, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_kill_parses_signal_and_defaults_to_sigkill() {
+        let cli = Cli::try_parse_from(&["ctst", "kill", "web", "--signal", "SIGHUP"])
+            .expect("should parse");
+        match cli.command {
+            Command::Kill(args) => {
+                assert_eq!(args.containers, vec!["web"]);
+                assert_eq!(args.signal.name(), "SIGHUP");
+            }
+            other => panic!("expected Kill, got {other:?}"),
+        }
+        let cli = Cli::try_parse_from(&["ctst", "kill", "web"]).expect("should parse");
+        match cli.command {
+            Command::Kill(args) => assert_eq!(args.signal.name(), "SIGKILL"),
+            other => panic!("expected Kill, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_rename_parses_target_and_new_name() {
+        let cli =
+            Cli::try_parse_from(&["ctst", "rename", "web", "frontend"]).expect("should parse");
+        match cli.command {
+            Command::Rename(args) => {
+                assert_eq!(args.container, "web");
+                assert_eq!(args.new_name, "frontend");
+            }
+            other => panic!("expected Rename, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(&["ctst", "rename", "web"]).is_err());
+        assert!(Cli::try_parse_from(&["ctst", "rename", "web", "a/b"]).is_err());
+    }
+
+    #[test]
+    fn cli_prune_parses_older_than_with_default() {
+        let cli = Cli::try_parse_from(&["ctst", "prune"]).expect("should parse");
+        match cli.command {
+            Command::Prune(args) => {
+                assert_eq!(args.older_than, std::time::Duration::from_secs(3600))
+            }
+            other => panic!("expected Prune, got {other:?}"),
+        }
+        let cli =
+            Cli::try_parse_from(&["ctst", "prune", "--older-than", "30m"]).expect("should parse");
+        match cli.command {
+            Command::Prune(args) => {
+                assert_eq!(args.older_than, std::time::Duration::from_secs(1800))
+            }
+            other => panic!("expected Prune, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(&["ctst", "prune", "--older-than", "soon"]).is_err());
+    }
+
+    #[test]
+    fn cli_kill_rejects_unknown_signal() {
+        assert!(Cli::try_parse_from(&["ctst", "kill", "web", "-s", "SIGBOGUS"]).is_err());
+    }
+
+    #[test]
+    fn cli_accepts_dash_for_stdin_composition() {
+        let cli = Cli::try_parse_from(&["ctst", "run", "-d", "-"]).expect("should parse");
+        match cli.command {
+            Command::Run(args) => assert_eq!(args.file, "-"),
+            other => panic!("expected Run, got {other:?}"),
+        }
+        let cli = Cli::try_parse_from(&["ctst", "plan", "-"]).expect("should parse");
+        match cli.command {
+            Command::Plan(args) => assert_eq!(args.file, "-"),
+            other => panic!("expected Plan, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_upgrade_parses_file_and_strategy() {
+        let cli =
+            Cli::try_parse_from(&["ctst", "upgrade", "-f", "new.ctst"]).expect("should parse");
+        match cli.command {
+            Command::Upgrade(args) => {
+                assert_eq!(args.file, "new.ctst");
+                assert_eq!(args.strategy, upgrade::Strategy::Rolling);
+            }
+            other => panic!("expected Upgrade, got {other:?}"),
+        }
+        let cli = Cli::try_parse_from(&["ctst", "upgrade", "--strategy", "recreate"])
+            .expect("should parse");
+        match cli.command {
+            Command::Upgrade(args) => {
+                assert_eq!(args.file, "containust.ctst");
+                assert_eq!(args.strategy, upgrade::Strategy::Recreate);
+            }
+            other => panic!("expected Upgrade, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(&["ctst", "upgrade", "--strategy", "blue-green"]).is_err());
+    }
+
+    #[test]
+    fn cli_graph_parses_format() {
+        let cli = Cli::try_parse_from(&["ctst", "graph", "-f", "mermaid", "app.ctst"])
+            .expect("should parse");
+        match cli.command {
+            Command::Graph(args) => {
+                assert_eq!(args.format, graph::GraphFormat::Mermaid);
+                assert_eq!(args.file, "app.ctst");
+            }
+            other => panic!("expected Graph, got {other:?}"),
+        }
+        let cli = Cli::try_parse_from(&["ctst", "graph"]).expect("should parse");
+        match cli.command {
+            Command::Graph(args) => assert_eq!(args.format, graph::GraphFormat::Dot),
+            other => panic!("expected Graph, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_build_subcommand_parses_json_flag() {
+        let cli =
+            Cli::try_parse_from(&["ctst", "build", "--json", "app.ctst"]).expect("should parse");
+        match cli.command {
+            Command::Build(args) => {
+                assert!(args.json);
+                assert_eq!(args.file, "app.ctst");
+            }
+            other => panic!("expected Build, got {other:?}"),
+        }
+    }
+
     #[test]
     fn cli_plan_subcommand_parses_with_default_file() {
         let cli = Cli::try_parse_from(&["ctst", "plan"]).expect("should parse");
@@ -247,6 +501,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_verbosity_flags_are_global() {
+        let cli = Cli::try_parse_from(&["ctst", "-vv", "ps"]).expect("should parse");
+        assert_eq!(cli.verbose, 2);
+        let cli = Cli::try_parse_from(&["ctst", "ps", "--quiet"]).expect("should parse");
+        assert!(cli.quiet);
+        assert!(Cli::try_parse_from(&["ctst", "-v", "-q", "ps"]).is_err());
+    }
+
+    #[test]
+    fn cli_log_format_flag_parses() {
+        let cli =
+            Cli::try_parse_from(&["ctst", "ps", "--log-format", "json"]).expect("should parse");
+        assert_eq!(cli.log_format, Some(LogFormat::Json));
+        assert!(Cli::try_parse_from(&["ctst", "ps", "--log-format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn cli_backend_flag_parses() {
+        let cli = Cli::try_parse_from(&["ctst", "--backend", "vm", "ps"]).expect("should parse");
+        assert_eq!(cli.backend, Some(BackendKind::Vm));
+        assert!(Cli::try_parse_from(&["ctst", "ps", "--backend", "docker"]).is_err());
+    }
+
+    #[test]
+    fn resolve_backend_prefers_flag_over_env() {
+        let vm = resolve_backend(Some(BackendKind::Vm), Some("auto".into())).expect("flag");
+        assert_eq!(vm, BackendKind::Vm);
+        let from_env = resolve_backend(None, Some("vm".into())).expect("env");
+        assert_eq!(from_env, BackendKind::Vm);
+        let auto = resolve_backend(None, None).expect("auto");
+        assert_eq!(auto == BackendKind::Native, cfg!(target_os = "linux"));
+        assert!(resolve_backend(None, Some("docker".into())).is_err());
+    }
+
+    #[test]
+    fn cli_plan_json_flag_parses() {
+        let cli =
+            Cli::try_parse_from(&["ctst", "plan", "--json", "app.ctst"]).expect("should parse");
+        match cli.command {
+            Command::Plan(args) => {
+                assert!(args.json);
+                assert_eq!(args.file, "app.ctst");
+            }
+            other => panic!("expected Plan, got {other:?}"),
+        }
+    }
+
     #[test]
     fn cli_run_subcommand_parses_with_flags() {
         let cli = Cli::try_parse_from(&["ctst", "run", "--detach"]).expect("should parse");
@@ -256,6 +558,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_run_subcommand_parses_fail_if_running() {
+        let cli = Cli::try_parse_from(&["ctst", "run", "--fail-if-running"]).expect("should parse");
+        match cli.command {
+            Command::Run(args) => assert!(args.fail_if_running),
+            other => panic!("expected Run, got {other:?}"),
+        }
+    }
+
     #[test]
     fn cli_run_subcommand_parses_default_attach() {
         let cli = Cli::try_parse_from(&["ctst", "run"]).expect("should parse");
@@ -274,6 +585,176 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_run_adhoc_splits_command_after_double_dash() {
+        let cli = Cli::try_parse_from(&[
+            "ctst",
+            "run",
+            "--image",
+            "file:///opt/alpine",
+            "--name",
+            "test",
+            "-e",
+            "MODE=dev",
+            "-p",
+            "8080",
+            "--memory",
+            "128MiB",
+            "--cpu",
+            "0.5",
+            "--",
+            "/bin/sh",
+            "-c",
+            "echo hi",
+        ])
+        .expect("should parse");
+        match cli.command {
+            Command::Run(args) => {
+                assert_eq!(args.file, "containust.ctst");
+                assert_eq!(args.adhoc.image.as_deref(), Some("file:///opt/alpine"));
+                assert_eq!(args.adhoc.name.as_deref(), Some("test"));
+                assert_eq!(args.adhoc.env, vec![("MODE".into(), "dev".into())]);
+                assert_eq!(args.adhoc.publish, vec![8080]);
+                assert_eq!(args.adhoc.memory, Some(128 * 1024 * 1024));
+                assert_eq!(args.adhoc.cpu, Some(512));
+                assert_eq!(args.command, vec!["/bin/sh", "-c", "echo hi"]);
+            }
+            other => panic!("expected Run, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_run_adhoc_flags_require_image() {
+        assert!(Cli::try_parse_from(&["ctst", "run", "--", "sh"]).is_err());
+        assert!(Cli::try_parse_from(&["ctst", "run", "-e", "A=b"]).is_err());
+        assert!(Cli::try_parse_from(&["ctst", "run", "--memory", "1GiB"]).is_err());
+    }
+
+    #[test]
+    fn cli_run_rejects_unsafe_adhoc_name() {
+        for name in ["a b", "../x", "x;id"] {
+            let args = ["ctst", "run", "--image", "file:///img", "--name", name];
+            assert!(Cli::try_parse_from(&args).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn cli_run_rejects_malformed_env_pair() {
+        assert!(
+            Cli::try_parse_from(&["ctst", "run", "--image", "preset://alpine", "-e", "NOVALUE"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn cli_run_adhoc_parses_pids_and_rejects_bad_limits() {
+        let cli = Cli::try_parse_from(&[
+            "ctst",
+            "run",
+            "--image",
+            "preset://alpine",
+            "--pids",
+            "128",
+            "--cpu",
+            "1.5",
+        ])
+        .expect("should parse");
+        match cli.command {
+            Command::Run(args) => {
+                assert_eq!(args.adhoc.pids, Some(128));
+                assert_eq!(args.adhoc.cpu, Some(1536));
+            }
+            other => panic!("expected Run, got {other:?}"),
+        }
+        for bad in [["--memory", "lots"], ["--cpu", "0"], ["--pids", "0"]] {
+            let result =
+                Cli::try_parse_from(["ctst", "run", "--image", "preset://alpine", bad[0], bad[1]]);
+            let error = result.expect_err("invalid limit").to_string();
+            assert!(error.contains(&format!("'{}'", bad[1])), "{error}");
+        }
+    }
+
+    #[test]
+    fn cli_run_parses_per_component_limit_overrides() {
+        let cli = Cli::try_parse_from(&[
+            "ctst",
+            "run",
+            "app.ctst",
+            "--limit",
+            "web:memory=512MiB",
+            "--limit",
+            "db:cpu=0.5,pids=64",
+        ])
+        .expect("should parse");
+        match cli.command {
+            Command::Run(args) => {
+                assert_eq!(args.file, "app.ctst");
+                assert_eq!(args.limits.len(), 2);
+                assert_eq!(args.limits[0].component, "web");
+                assert_eq!(args.limits[0].memory_bytes, Some(512 * 1024 * 1024));
+                assert_eq!(args.limits[1].pids_max, Some(64));
+            }
+            other => panic!("expected Run, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(&["ctst", "run", "--limit", "web:swap=1GiB"]).is_err());
+    }
+
+    #[test]
+    fn cli_create_subcommand_parses_default_file() {
+        let cli = Cli::try_parse_from(&["ctst", "create"]).expect("should parse");
+        match cli.command {
+            Command::Create(args) => {
+                assert_eq!(args.file, "containust.ctst");
+                assert!(args.adhoc.image.is_none());
+            }
+            other => panic!("expected Create, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_create_subcommand_parses_adhoc_image_and_command() {
+        let cli = Cli::try_parse_from(&[
+            "ctst",
+            "create",
+            "--image",
+            "preset://alpine",
+            "--name",
+            "box",
+            "--command",
+            "sleep",
+            "-n",
+            "60",
+        ])
+        .expect("should parse");
+        match cli.command {
+            Command::Create(args) => {
+                assert_eq!(args.adhoc.image.as_deref(), Some("preset://alpine"));
+                assert_eq!(args.adhoc.name.as_deref(), Some("box"));
+                assert_eq!(args.command, vec!["sleep", "-n", "60"]);
+            }
+            other => panic!("expected Create, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_create_command_requires_image() {
+        assert!(Cli::try_parse_from(&["ctst", "create", "--command", "sh"]).is_err());
+    }
+
+    #[test]
+    fn cli_start_subcommand_parses_targets() {
+        let cli = Cli::try_parse_from(&["ctst", "start", "web", "db"]).expect("should parse");
+        match cli.command {
+            Command::Start(args) => assert_eq!(args.containers, vec!["web", "db"]),
+            other => panic!("expected Start, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_start_subcommand_requires_target() {
+        assert!(Cli::try_parse_from(&["ctst", "start"]).is_err());
+    }
+
     #[test]
     fn cli_ps_subcommand_parses_all_flag() {
         let cli = Cli::try_parse_from(&["ctst", "ps", "--all"]).expect("should parse");
@@ -292,6 +773,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_ps_subcommand_parses_format() {
+        let cli = Cli::try_parse_from(&["ctst", "ps", "--format", "wide"]).expect("should parse");
+        match cli.command {
+            Command::Ps(args) => assert_eq!(args.format, crate::output::OutputFormat::Wide),
+            other => panic!("expected Ps, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(&["ctst", "ps", "--format", "yaml"]).is_err());
+    }
+
     #[test]
     fn cli_exec_subcommand_parses_container_and_command() {
         let cli =
@@ -305,6 +796,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_exec_parses_option_flags() {
+        let cli = Cli::try_parse_from(&[
+            "ctst",
+            "exec",
+            "-w",
+            "/srv",
+            "-u",
+            "1000:1000",
+            "-e",
+            "A=1",
+            "--env",
+            "B=2",
+            "api",
+            "env",
+        ])
+        .expect("should parse");
+        match cli.command {
+            Command::Exec(args) => {
+                assert_eq!(args.workdir.as_deref(), Some("/srv"));
+                assert_eq!(args.user.as_deref(), Some("1000:1000"));
+                assert_eq!(
+                    args.env,
+                    vec![("A".into(), "1".into()), ("B".into(), "2".into())]
+                );
+                assert_eq!(args.command, vec!["env"]);
+            }
+            other => panic!("expected Exec, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_attach_parses_no_stdin() {
+        let cli = Cli::try_parse_from(&["ctst", "attach", "api"]).expect("should parse");
+        match cli.command {
+            Command::Attach(args) => {
+                assert_eq!(args.container, "api");
+                assert!(!args.no_stdin);
+            }
+            other => panic!("expected Attach, got {other:?}"),
+        }
+        let cli =
+            Cli::try_parse_from(&["ctst", "attach", "--no-stdin", "api"]).expect("should parse");
+        match cli.command {
+            Command::Attach(args) => assert!(args.no_stdin),
+            other => panic!("expected Attach, got {other:?}"),
+        }
+    }
+
     #[test]
     fn cli_exec_subcommand_requires_command() {
         let result = Cli::try_parse_from(&["ctst", "exec", "abc123"]);
@@ -408,6 +948,20 @@ mod tests {
             Command::Convert(args) => {
                 assert_eq!(args.file, std::path::PathBuf::from("compose.yml"));
                 assert_eq!(args.output, Some(std::path::PathBuf::from("out.ctst")));
+                assert_eq!(args.from, crate::converter::SourceFormat::Compose);
+            }
+            other => panic!("expected Convert, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cli_convert_subcommand_parses_from_kubernetes() {
+        let cli = Cli::try_parse_from(&["ctst", "convert", "--from", "kubernetes", "app.yaml"])
+            .expect("should parse");
+        match cli.command {
+            Command::Convert(args) => {
+                assert_eq!(args.from, crate::converter::SourceFormat::Kubernetes);
+                assert_eq!(args.file, std::path::PathBuf::from("app.yaml"));
             }
             other => panic!("expected Convert, got {other:?}"),
         }
@@ -426,6 +980,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_logs_subcommand_parses_grep_flags() {
+        let cli = Cli::try_parse_from(&[
+            "ctst",
+            "logs",
+            "ctr1",
+            "--grep",
+            "ERROR",
+            "--grep-v",
+            "--ignore-case",
+            "--regex",
+        ])
+        .expect("should parse");
+        match cli.command {
+            Command::Logs(args) => {
+                assert_eq!(args.filter.grep.as_deref(), Some("ERROR"));
+                assert!(args.filter.invert && args.filter.ignore_case && args.filter.regex);
+            }
+            other => panic!("expected Logs, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(&["ctst", "logs", "ctr1", "--grep-v"]).is_err());
+    }
+
+    #[test]
+    fn cli_logs_subcommand_parses_tail_and_since() {
+        let cli = Cli::try_parse_from(&["ctst", "logs", "ctr1", "--tail", "50", "--since", "5m"])
+            .expect("should parse");
+        match cli.command {
+            Command::Logs(args) => {
+                assert_eq!(args.tail, Some(50));
+                assert_eq!(args.since, Some(std::time::Duration::from_secs(300)));
+            }
+            other => panic!("expected Logs, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(&["ctst", "logs", "ctr1", "--since", "soon"]).is_err());
+    }
+
+    #[test]
+    fn cli_logs_subcommand_parses_previous_without_follow() {
+        let cli =
+            Cli::try_parse_from(&["ctst", "logs", "ctr1", "--previous"]).expect("should parse");
+        match cli.command {
+            Command::Logs(args) => assert!(args.previous),
+            other => panic!("expected Logs, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(&["ctst", "logs", "ctr1", "--previous", "-f"]).is_err());
+    }
+
     #[test]
     fn cli_logs_subcommand_parses_without_follow() {
         let cli = Cli::try_parse_from(&["ctst", "logs", "ctr1"]).expect("should parse");
@@ -522,6 +1124,9 @@ mod tests {
         let options = RuntimeOptions {
             offline: false,
             state_file: Some(state_file.clone()),
+            defaults: DefaultsConfig::default(),
+            backend: BackendKind::Auto,
+            cancel: CancellationToken::new(),
         };
 
         let engine = options.engine_for_project(&dir.path().join("app.ctst"));
@@ -529,6 +1134,25 @@ mod tests {
         assert_eq!(engine.data_dir(), dir.path().join("custom"));
     }
 
+    #[test]
+    fn cancelled_command_deploys_nothing() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("app.ctst");
+        std::fs::write(&file, r#"COMPONENT web { image = "file:///unused" }"#)
+            .expect("write composition");
+        let options = RuntimeOptions {
+            state_file: Some(dir.path().join("state.json")),
+            backend: BackendKind::Native,
+            ..RuntimeOptions::default()
+        };
+        options.cancel.cancel();
+
+        let engine = options.engine_for_project(&file);
+        let error = engine.deploy(&file).expect_err("cancelled deploy");
+        assert!(error.to_string().contains("deploy cancelled"), "{error}");
+        assert!(engine.list().expect("list").is_empty());
+    }
+
     // --- Error cases ---
 
     #[test]
@@ -585,6 +1209,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cli_vm_logs_subcommand_parses_tail() {
+        let cli = Cli::try_parse_from(&["ctst", "vm", "logs", "-n", "20"]).expect("should parse");
+        match cli.command {
+            Command::Vm(VmCommand::Logs(args)) => assert_eq!(args.tail, Some(20)),
+            other => panic!("expected vm logs, got {other:?}"),
+        }
+    }
+
     #[test]
     fn cli_vm_no_subcommand_fails() {
         let result = Cli::try_parse_from(&["ctst", "vm"]);
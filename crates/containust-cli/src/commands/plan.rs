@@ -1,78 +1,103 @@
 //! `ctst plan` — Display planned infrastructure changes before applying.
 
 use clap::Args;
+use containust_compose::input::CompositionSource;
+
+use super::plan_report::PlanReport;
 
 /// Arguments for the `plan` command.
 #[derive(Args, Debug)]
 pub struct PlanArgs {
-    /// Path to the .ctst composition file.
+    /// Path to the .ctst composition file (`-` reads stdin).
     #[arg(default_value = "containust.ctst")]
     pub file: String,
+
+    /// Print the plan as JSON, classifying each component against state.
+    #[arg(long)]
+    pub json: bool,
 }
 
 /// Executes the `plan` command.
 ///
 /// Parses the `.ctst` file, builds the dependency graph, resolves
-/// topological order, and displays a deployment plan.
+/// topological order, and displays a deployment plan. With `--json`, a
+/// [`PlanReport`] classified against the project's state is printed.
 ///
 /// # Errors
 ///
 /// Returns an error if parsing, validation, or graph resolution fails.
 pub fn execute(args: PlanArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
-    let content = std::fs::read_to_string(&args.file)?;
-    let composition =
-        containust_compose::parser::parse_ctst(&content).map_err(|e| anyhow::anyhow!("{e}"))?;
+    let source = CompositionSource::from_arg(&args.file);
+    let composition = source.parse().map_err(|e| anyhow::anyhow!("{e}"))?;
     if options.offline {
         containust_compose::validate_offline(&composition).map_err(|e| anyhow::anyhow!("{e}"))?;
     }
 
-    let mut graph = containust_compose::graph::DependencyGraph::new();
-    let mut node_map = std::collections::HashMap::new();
-
-    for comp in &composition.components {
-        let idx = graph.add_component(&comp.name);
-        let _ = node_map.insert(comp.name.clone(), idx);
-    }
-    for conn in &composition.connections {
-        if let (Some(&from), Some(&to)) = (node_map.get(&conn.from), node_map.get(&conn.to)) {
-            graph.add_dependency(from, to);
-        }
-    }
-
+    let graph = containust_compose::graph::DependencyGraph::from_composition(&composition);
     let order = graph.resolve_order().map_err(|e| anyhow::anyhow!("{e}"))?;
+    if args.json {
+        let engine = options.engine_for_project(&source.project_anchor());
+        let prior = if engine.state_file().exists() {
+            containust_runtime::state::load_state(engine.state_file())
+                .map_err(|e| anyhow::anyhow!("{e}"))?
+                .containers
+        } else {
+            Vec::new()
+        };
+        let report = PlanReport::assemble(&composition, order, &prior, &options.defaults)?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
 
-    println!("Deployment Plan for: {}", args.file);
+    println!("Deployment Plan for: {}", source.display());
     println!(
         "\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}"
     );
     println!();
 
     for name in &order {
-        let comp = composition.components.iter().find(|c| c.name == *name);
-        if let Some(c) = comp {
-            println!("  + {name}");
-            if let Some(ref img) = c.image {
-                println!("      image: {img}");
-            }
-            if let Some(p) = c.port {
-                println!("      port: {p}");
-            }
-            if let Some(ref mem) = c.memory {
-                println!("      memory: {mem}");
-            }
+        if let Some(component) = composition.components.iter().find(|c| c.name == *name) {
+            print_component(component);
         }
     }
 
     println!();
     println!("  {} component(s) will be deployed.", order.len());
 
-    if !composition.connections.is_empty() {
-        println!();
-        println!("  Connections:");
-        for conn in &composition.connections {
-            println!("    {} -> {}", conn.from, conn.to);
-        }
-    }
+    print_edges(
+        "Connections",
+        composition.connections.iter().map(|c| (&c.from, &c.to)),
+    );
+    print_edges(
+        "Start order (DEPENDS_ON)",
+        composition.dependencies.iter().map(|d| (&d.from, &d.to)),
+    );
 
     Ok(())
 }
+
+/// Prints a titled `from -> to` list, or nothing when `edges` is empty.
+fn print_edges<'a>(title: &str, edges: impl Iterator<Item = (&'a String, &'a String)>) {
+    let mut edges = edges.peekable();
+    if edges.peek().is_none() {
+        return;
+    }
+    println!();
+    println!("  {title}:");
+    for (from, to) in edges {
+        println!("    {from} -> {to}");
+    }
+}
+
+fn print_component(component: &containust_compose::parser::ast::ComponentDecl) {
+    println!("  + {}", component.name);
+    if let Some(ref img) = component.image {
+        println!("      image: {img}");
+    }
+    if let Some(p) = component.port {
+        println!("      port: {p}");
+    }
+    if let Some(ref mem) = component.memory {
+        println!("      memory: {mem}");
+    }
+}
@@ -0,0 +1,360 @@
+//! Machine-readable `ctst plan --json` output.
+//!
+//! Each component is classified against the project's recorded state so
+//! CI can diff plans and gate on unexpected changes. Env values are
+//! redacted exactly as they are in `state.json`.
+
+use std::collections::HashSet;
+
+use containust_common::config::DefaultsConfig;
+use containust_common::redact::redact_env;
+use containust_common::types::PortMapping;
+use containust_compose::graph::DependencyGraph;
+use containust_compose::parser::ast::{ComponentDecl, CompositionFile};
+use containust_runtime::engine::{effective_limits, published_port_mappings};
+use containust_runtime::state::StateEntry;
+use serde::Serialize;
+
+/// What applying the plan would do to one component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanAction {
+    /// No container with this name is recorded.
+    Create,
+    /// A recorded container differs in image, env, ports, or limits.
+    Update,
+    /// A recorded container already matches the composition.
+    Unchanged,
+    /// A recorded container is no longer declared.
+    Delete,
+}
+
+/// Resource limits a component runs with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PlannedLimits {
+    /// Memory limit in bytes.
+    pub memory_bytes: Option<u64>,
+    /// CPU weight.
+    pub cpu_shares: Option<u64>,
+    /// Maximum number of processes.
+    pub pids_max: Option<u64>,
+    /// Swap allowed beyond the memory limit.
+    pub swap_bytes: Option<u64>,
+    /// Memory protected from reclaim.
+    pub memory_low_bytes: Option<u64>,
+    /// Memory throttling threshold.
+    pub memory_high_bytes: Option<u64>,
+    /// Hard CPU quota in microseconds per period.
+    pub cpu_quota_us: Option<u64>,
+    /// CPU quota period in microseconds.
+    pub cpu_period_us: Option<u64>,
+    /// Cores the component is pinned to.
+    pub cpuset_cpus: Option<Vec<u32>>,
+    /// NUMA nodes the component's memory comes from.
+    pub cpuset_mems: Option<Vec<u32>>,
+}
+
+/// One component of a [`PlanReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedComponent {
+    /// Component name.
+    pub name: String,
+    /// Image source URI.
+    pub image: Option<String>,
+    /// Components that must start first.
+    pub depends_on: Vec<String>,
+    /// Environment with secret values redacted, sorted by key.
+    pub env: Vec<(String, String)>,
+    /// Host→container port publishes, sorted.
+    pub ports: Vec<PortMapping>,
+    /// Effective resource limits.
+    pub limits: PlannedLimits,
+    /// Classification against recorded state.
+    pub action: PlanAction,
+}
+
+/// Full `ctst plan --json` document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlanReport {
+    /// Declared components in deploy order, then deletions.
+    pub components: Vec<PlannedComponent>,
+    /// Deploy order of declared components.
+    pub order: Vec<String>,
+}
+
+impl PlanReport {
+    /// Classifies every declared component in `order` against `prior`
+    /// state entries and appends a `delete` for each undeclared entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when connections, publishes, or limits are invalid.
+    pub fn assemble(
+        composition: &CompositionFile,
+        order: Vec<String>,
+        prior: &[StateEntry],
+        defaults: &DefaultsConfig,
+    ) -> anyhow::Result<Self> {
+        let graph = DependencyGraph::from_composition(composition).view();
+        let resolved = containust_compose::resolver::resolve_connections(composition)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        let mut components = Vec::with_capacity(order.len());
+        for name in &order {
+            let Some(component) = composition.components.iter().find(|c| c.name == *name) else {
+                continue;
+            };
+            let env = resolved
+                .iter()
+                .find(|r| r.name == *name)
+                .map_or_else(Vec::new, |r| r.env.clone());
+            let mut planned = declared_component(component, env, composition, defaults)?;
+            planned.depends_on = graph
+                .edges
+                .iter()
+                .filter(|edge| edge.to == *name)
+                .map(|edge| edge.from.clone())
+                .collect();
+            planned.action = prior
+                .iter()
+                .find(|entry| entry.name == *name)
+                .map_or(PlanAction::Create, |entry| classify(&planned, entry));
+            components.push(planned);
+        }
+        let declared: HashSet<&str> = order.iter().map(String::as_str).collect();
+        components.extend(
+            prior
+                .iter()
+                .filter(|entry| !declared.contains(entry.name.as_str()))
+                .map(deleted_component),
+        );
+        Ok(Self { components, order })
+    }
+}
+
+fn declared_component(
+    component: &ComponentDecl,
+    env: Vec<(String, String)>,
+    composition: &CompositionFile,
+    defaults: &DefaultsConfig,
+) -> anyhow::Result<PlannedComponent> {
+    let ports = published_port_mappings(component, &composition.exposes)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    let limits = effective_limits(component, defaults).map_err(|e| anyhow::anyhow!("{e}"))?;
+    Ok(PlannedComponent {
+        name: component.name.clone(),
+        image: component.image.clone(),
+        depends_on: Vec::new(),
+        env: sorted(redact_env(&env)),
+        ports: sorted_ports(ports),
+        limits: PlannedLimits {
+            memory_bytes: limits.memory_bytes,
+            cpu_shares: limits.cpu_shares,
+            pids_max: limits.pids_max,
+            swap_bytes: limits.swap_bytes,
+            memory_low_bytes: limits.memory_low_bytes,
+            memory_high_bytes: limits.memory_high_bytes,
+            cpu_quota_us: limits.cpu_quota_us,
+            cpu_period_us: limits.cpu_period_us,
+            cpuset_cpus: limits.cpuset_cpus,
+            cpuset_mems: limits.cpuset_mems,
+        },
+        action: PlanAction::Create,
+    })
+}
+
+fn deleted_component(entry: &StateEntry) -> PlannedComponent {
+    PlannedComponent {
+        name: entry.name.clone(),
+        image: Some(entry.image.clone()),
+        depends_on: Vec::new(),
+        env: sorted(entry.env.clone()),
+        ports: sorted_ports(entry.port_mappings.clone()),
+        limits: recorded_limits(entry),
+        action: PlanAction::Delete,
+    }
+}
+
+fn classify(planned: &PlannedComponent, entry: &StateEntry) -> PlanAction {
+    let same = planned
+        .image
+        .as_deref()
+        .is_some_and(|image| image_matches(image, &entry.image))
+        && planned.env == sorted(entry.env.clone())
+        && planned.ports == sorted_ports(entry.port_mappings.clone())
+        && planned.limits == recorded_limits(entry);
+    if same {
+        PlanAction::Unchanged
+    } else {
+        PlanAction::Update
+    }
+}
+
+/// Preset images are pinned to a catalog digest at deploy, so a recorded
+/// `image://` reference satisfies a declared `preset://` one.
+fn image_matches(declared: &str, recorded: &str) -> bool {
+    declared == recorded || (declared.starts_with("preset://") && recorded.starts_with("image://"))
+}
+
+fn recorded_limits(entry: &StateEntry) -> PlannedLimits {
+    PlannedLimits {
+        memory_bytes: entry.memory_bytes,
+        cpu_shares: entry.cpu_shares,
+        pids_max: entry.pids_max,
+        swap_bytes: entry.swap_bytes,
+        memory_low_bytes: entry.memory_low_bytes,
+        memory_high_bytes: entry.memory_high_bytes,
+        cpu_quota_us: entry.cpu_quota_us,
+        cpu_period_us: entry.cpu_period_us,
+        cpuset_cpus: entry.cpuset_cpus.clone(),
+        cpuset_mems: entry.cpuset_mems.clone(),
+    }
+}
+
+fn sorted<T: Ord>(mut items: Vec<T>) -> Vec<T> {
+    items.sort();
+    items
+}
+
+fn sorted_ports(mut ports: Vec<PortMapping>) -> Vec<PortMapping> {
+    ports.sort_by_key(|mapping| (mapping.host, mapping.container));
+    ports
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    const COMPOSITION: &str = r#"
+COMPONENT db {
+    image = "file:///db"
+    port = 5432
+    memory = "64MiB"
+}
+COMPONENT api {
+    image = "file:///api"
+    port = 8080
+    env = { DB_PASSWORD = "hunter2" }
+}
+COMPONENT web {
+    image = "file:///web"
+}
+CONNECT api -> db
+"#;
+
+    fn recorded(name: &str, image: &str) -> StateEntry {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("id-{name}"),
+            "name": name,
+            "state": "Running",
+            "pid": null,
+            "image": image,
+            "rootfs_path": null,
+            "log_path": null,
+            "created_at": "2026-01-01T00:00:00Z",
+        }))
+        .expect("state entry")
+    }
+
+    fn plan(prior: &[StateEntry]) -> PlanReport {
+        let composition =
+            containust_compose::parser::parse_ctst(COMPOSITION).expect("parse composition");
+        let order = DependencyGraph::from_composition(&composition)
+            .resolve_order()
+            .expect("order");
+        PlanReport::assemble(&composition, order, prior, &DefaultsConfig::default()).expect("plan")
+    }
+
+    fn action_of(report: &PlanReport, name: &str) -> PlanAction {
+        report
+            .components
+            .iter()
+            .find(|component| component.name == name)
+            .expect("planned component")
+            .action
+    }
+
+    #[test]
+    fn empty_state_plans_every_component_as_create() {
+        let report = plan(&[]);
+        assert_eq!(report.order.len(), 3);
+        assert!(
+            report
+                .components
+                .iter()
+                .all(|component| component.action == PlanAction::Create)
+        );
+        let api = &report.components[report.order.iter().position(|n| n == "api").expect("api")];
+        assert_eq!(api.depends_on, vec!["db"]);
+        assert!(
+            api.env
+                .contains(&("DB_PASSWORD".into(), "<redacted>".into()))
+        );
+        assert!(api.env.contains(&("DB_HOST".into(), "db".into())));
+    }
+
+    #[test]
+    fn recorded_state_classifies_unchanged_update_and_delete() {
+        let mut db = recorded("db", "file:///db");
+        db.memory_bytes = Some(64 * 1024 * 1024);
+        let web = recorded("web", "file:///web-old");
+        let stale = recorded("cache", "file:///cache");
+
+        let report = plan(&[db, web, stale]);
+        assert_eq!(action_of(&report, "db"), PlanAction::Unchanged);
+        assert_eq!(action_of(&report, "web"), PlanAction::Update);
+        assert_eq!(action_of(&report, "api"), PlanAction::Create);
+        assert_eq!(action_of(&report, "cache"), PlanAction::Delete);
+        assert!(!report.order.contains(&"cache".to_string()));
+    }
+
+    #[test]
+    fn limit_changes_are_updates() {
+        let db = recorded("db", "file:///db");
+        let report = plan(&[db]);
+        assert_eq!(action_of(&report, "db"), PlanAction::Update);
+    }
+
+    #[test]
+    fn swap_and_soft_memory_changes_are_updates() {
+        let composition = containust_compose::parser::parse_ctst(
+            r#"
+COMPONENT db {
+    image = "file:///db"
+    memory = "64MiB"
+    memory_swap = "128MiB"
+    memory_reservation = "32MiB"
+}
+"#,
+        )
+        .expect("parse composition");
+        let mut db = recorded("db", "file:///db");
+        db.memory_bytes = Some(64 * 1024 * 1024);
+        let order = vec!["db".to_string()];
+        let assemble = |entry: &StateEntry| {
+            PlanReport::assemble(
+                &composition,
+                order.clone(),
+                std::slice::from_ref(entry),
+                &DefaultsConfig::default(),
+            )
+            .expect("plan")
+        };
+        assert_eq!(action_of(&assemble(&db), "db"), PlanAction::Update);
+
+        db.swap_bytes = Some(64 * 1024 * 1024);
+        db.memory_low_bytes = Some(32 * 1024 * 1024);
+        let report = assemble(&db);
+        assert_eq!(action_of(&report, "db"), PlanAction::Unchanged);
+        assert_eq!(report.components[0].limits.swap_bytes, db.swap_bytes);
+    }
+
+    #[test]
+    fn plan_serializes_snake_case_actions() {
+        let json = serde_json::to_value(plan(&[])).expect("serialize");
+        assert_eq!(json["components"][0]["action"], "create");
+        assert!(json["order"].is_array());
+        assert!(json["components"][0]["limits"].is_object());
+    }
+}
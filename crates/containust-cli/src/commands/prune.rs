@@ -0,0 +1,31 @@
+//! `ctst prune` — Remove containers stuck in `created`.
+
+use std::time::Duration;
+
+use clap::Args;
+
+/// Arguments for the `prune` command.
+#[derive(Args, Debug)]
+pub struct PruneArgs {
+    /// Minimum age of a `created` container before it is removed
+    /// (e.g. `30m`, `2h`).
+    #[arg(long, default_value = "1h", value_parser = super::stop::parse_duration_arg)]
+    pub older_than: Duration,
+}
+
+/// Executes the `prune` command.
+///
+/// # Errors
+///
+/// Returns an error if containers cannot be listed or removed.
+pub fn execute(args: PruneArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    let engine = options.engine();
+    let pruned = engine
+        .prune_created(args.older_than)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    for info in &pruned {
+        println!("Removed: {}", info.name);
+    }
+    println!("Pruned {} stale created container(s).", pruned.len());
+    Ok(())
+}
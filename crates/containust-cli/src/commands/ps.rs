@@ -1,7 +1,8 @@
 //! `ctst ps` — List running containers with real-time metrics.
 
 use clap::Args;
-use containust_runtime::metrics::{MetricAvailability, collect_metrics};
+
+use crate::output::{OutputFormat, render_ps};
 
 /// Arguments for the `ps` command.
 #[derive(Args, Debug)]
@@ -13,6 +14,10 @@ pub struct PsArgs {
     /// Launch the interactive TUI dashboard.
     #[arg(long)]
     pub tui: bool,
+
+    /// Output format: `table`, `json`, or `wide` (adds image and creation time).
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
 }
 
 /// Executes the `ps` command.
@@ -50,29 +55,10 @@ pub fn execute(args: PsArgs, options: &super::RuntimeOptions) -> anyhow::Result<
         return containust_tui::run_dashboard(&rows).map_err(Into::into);
     }
 
-    if filtered.is_empty() {
-        println!("No containers found.");
-        return Ok(());
-    }
-
     println!(
-        "{:<36} {:<14} {:<10} {:<8} {:>10} {:>10} {:<20}",
-        "CONTAINER ID", "NAME", "STATE", "PID", "CPU(ns)", "MEM(B)", "IMAGE"
+        "{}",
+        render_ps(&filtered, args.format, &engine.project_id())?
     );
-    for c in &filtered {
-        let (cpu, mem) = format_metrics(&c.id);
-        println!(
-            "{:<36} {:<14} {:<10} {:<8} {:>10} {:>10} {:<20}",
-            c.id,
-            c.name,
-            c.state,
-            c.pid.map_or_else(|| "-".to_string(), |p| p.to_string()),
-            cpu,
-            mem,
-            c.image
-        );
-    }
-
     Ok(())
 }
 
@@ -89,20 +75,3 @@ fn print_reconciliation(reconciliation: &containust_runtime::backend::Reconcilia
         );
     }
 }
-
-fn format_metrics(id: &containust_common::types::ContainerId) -> (String, String) {
-    match collect_metrics(id) {
-        Ok(snap) => {
-            let cpu = match snap.cpu {
-                MetricAvailability::Available => snap.cpu_usage_ns.to_string(),
-                MetricAvailability::Unavailable | MetricAvailability::Missing => "-".into(),
-            };
-            let mem = match snap.memory {
-                MetricAvailability::Available => snap.memory_usage_bytes.to_string(),
-                MetricAvailability::Unavailable | MetricAvailability::Missing => "-".into(),
-            };
-            (cpu, mem)
-        }
-        Err(_) => ("-".into(), "-".into()),
-    }
-}
@@ -52,9 +52,12 @@ pub fn execute(args: PullArgs, options: &super::RuntimeOptions) -> anyhow::Resul
         .clone()
         .unwrap_or_else(|| default_catalog_name(reference.location()));
 
+    options.cancel_on_interrupt()?;
     println!("Pulling {reference} as '{catalog_name}'...");
     let engine = options.engine_for_project(Path::new(&args.file));
-    let mut request = ImportRequest::new(&catalog_name, options.offline).with_unpinned();
+    let mut request = ImportRequest::new(&catalog_name, options.offline)
+        .with_unpinned()
+        .with_cancellation(options.cancel.clone());
     if args.require_provenance {
         request = request.with_require_provenance();
     }
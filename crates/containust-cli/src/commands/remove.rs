@@ -36,7 +36,7 @@ pub fn execute(args: RemoveArgs, options: &super::RuntimeOptions) -> anyhow::Res
                     "container {target} is running; stop it first or use --force"
                 ));
             }
-            engine
+            let _ = engine
                 .stop_with_force(&id, true)
                 .map_err(|error| anyhow::anyhow!("{error}"))?;
         }
@@ -0,0 +1,29 @@
+//! `ctst rename` — Rename a container.
+
+use clap::Args;
+
+/// Arguments for the `rename` command.
+#[derive(Args, Debug)]
+pub struct RenameArgs {
+    /// Container ID or current name.
+    pub container: String,
+
+    /// New container name; must not be used by another container.
+    #[arg(value_parser = super::parse_name_arg)]
+    pub new_name: String,
+}
+
+/// Executes the `rename` command.
+///
+/// # Errors
+///
+/// Returns an error when the container is missing or the name is taken.
+pub fn execute(args: RenameArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    let engine = options.engine();
+    let id = super::resolve_container_id(&engine, &args.container)?;
+    engine
+        .rename(&id, &args.new_name)
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    println!("Renamed: {} -> {}", args.container, args.new_name);
+    Ok(())
+}
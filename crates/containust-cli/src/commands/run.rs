@@ -1,23 +1,44 @@
 //! `ctst run` — Deploy and run the component graph.
 
-use std::path::Path;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 use clap::Args;
-use containust_runtime::engine::{DeployedComponent, Engine};
+use containust_common::cancel::CancellationToken;
+use containust_compose::input::CompositionSource;
+use containust_runtime::engine::{DeployedComponent, Engine, LimitOverride};
+
+use super::adhoc::AdhocArgs;
+use super::limits::parse_limit_override;
 
 /// Arguments for the `run` command.
 #[derive(Args, Debug)]
 pub struct RunArgs {
-    /// Path to the .ctst composition file.
+    /// Path to the .ctst composition file, `-` for stdin (ignored with `--image`).
     #[arg(default_value = "containust.ctst")]
     pub file: String,
 
     /// Run in detached mode (don't wait for Ctrl+C).
     #[arg(short, long)]
     pub detach: bool,
+
+    /// Fail instead of warning when a component is already running.
+    #[arg(long)]
+    pub fail_if_running: bool,
+
+    /// Single-container flags used instead of a `.ctst` file.
+    #[command(flatten)]
+    pub adhoc: AdhocArgs,
+
+    /// Per-component limit override, e.g. `web:memory=512MiB` (repeatable).
+    ///
+    /// Keys are `memory`, `cpu`, and `pids`; values replace the `.ctst` ones.
+    #[arg(long = "limit", value_name = "COMPONENT:KEY=VALUE", value_parser = parse_limit_override)]
+    pub limits: Vec<LimitOverride>,
+
+    /// Command for the ad-hoc container, given after `--`.
+    #[arg(last = true, requires = "image", value_name = "COMMAND")]
+    pub command: Vec<String>,
 }
 
 const BOLD: &str = "\x1b[1m";
@@ -35,22 +56,29 @@ const RESET: &str = "\x1b[0m";
 pub fn execute(args: RunArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
     let total_start = Instant::now();
     print_header();
+    options.cancel_on_interrupt()?;
 
-    let path = std::path::Path::new(&args.file);
-    if !path.exists() {
-        return Err(anyhow::anyhow!(
-            "Composition file not found: {}\n\
-             Create a .ctst file or specify a path: ctst run <file>",
-            args.file
-        ));
-    }
-
-    let engine = options.engine_for_project(path);
-    if !engine.is_available() {
-        print_vm_notice();
-    }
-
-    let deployed = deploy_and_report(&engine, path, total_start)?;
+    let mut limits = args.limits;
+    let (engine, deployed, reload_path) = if let Some((composition, adhoc_limits)) =
+        args.adhoc.into_composition(args.command)
+    {
+        limits.insert(0, adhoc_limits);
+        let engine = options
+            .engine()
+            .with_limit_overrides(limits)
+            .with_fail_if_running(args.fail_if_running);
+        announce_backend(&engine);
+        let deployed = engine
+            .deploy_composition(&composition)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        (engine, deployed, None)
+    } else {
+        let (engine, deployed) = deploy_file(&args.file, limits, args.fail_if_running, options)?;
+        (engine, deployed, reloadable_path(&args.file))
+    };
+    report_deployed(&engine, &deployed, total_start);
+    // A foreground run takes the VM it booted down with it.
+    engine.set_vm_keep_alive(args.detach);
 
     if args.detach {
         eprintln!();
@@ -58,7 +86,47 @@ pub fn execute(args: RunArgs, options: &super::RuntimeOptions) -> anyhow::Result
         return Ok(());
     }
 
-    wait_for_shutdown(&engine, &deployed)
+    wait_for_shutdown(&engine, deployed, reload_path.as_deref(), &options.cancel)
+}
+
+/// The file a foreground run re-reads on `SIGHUP`; stdin cannot be re-read.
+fn reloadable_path(file: &str) -> Option<PathBuf> {
+    match CompositionSource::from_arg(file) {
+        CompositionSource::File(path) => Some(path),
+        CompositionSource::Stdin => None,
+    }
+}
+
+/// Deploys the composition at `file`, or from stdin when it is `-`.
+fn deploy_file(
+    file: &str,
+    limits: Vec<LimitOverride>,
+    fail_if_running: bool,
+    options: &super::RuntimeOptions,
+) -> anyhow::Result<(Engine, Vec<DeployedComponent>)> {
+    let source = CompositionSource::from_arg(file);
+    if let CompositionSource::File(path) = &source
+        && !path.exists()
+    {
+        return Err(anyhow::anyhow!(
+            "Composition file not found: {file}\n\
+             Create a .ctst file, specify a path (ctst run <file>), pipe one \
+             in (ctst run -), or pass --image"
+        ));
+    }
+    let engine = options
+        .engine_for_project(&source.project_anchor())
+        .with_limit_overrides(limits)
+        .with_fail_if_running(fail_if_running);
+    announce_backend(&engine);
+    let deployed = match &source {
+        CompositionSource::File(path) => engine.deploy(path),
+        CompositionSource::Stdin => source
+            .parse()
+            .and_then(|composition| engine.deploy_composition(&composition)),
+    }
+    .map_err(|e| anyhow::anyhow!("{e}"))?;
+    Ok((engine, deployed))
 }
 
 fn print_header() {
@@ -70,19 +138,19 @@ fn print_header() {
     eprintln!();
 }
 
+fn announce_backend(engine: &Engine) {
+    if !engine.is_available() {
+        print_vm_notice();
+    }
+}
+
 fn print_vm_notice() {
     eprintln!("  {YELLOW}Note:{RESET} No native container support on this OS.");
     eprintln!("        A lightweight Linux VM will be used (requires QEMU).");
     eprintln!();
 }
 
-fn deploy_and_report(
-    engine: &Engine,
-    path: &Path,
-    total_start: Instant,
-) -> anyhow::Result<Vec<DeployedComponent>> {
-    let deployed = engine.deploy(path).map_err(|e| anyhow::anyhow!("{e}"))?;
-
+fn report_deployed(engine: &Engine, deployed: &[DeployedComponent], total_start: Instant) {
     eprintln!();
     eprintln!(
         "  {GREEN}{BOLD}Deployed {}{RESET} container(s) in {:.1}s:",
@@ -91,7 +159,7 @@ fn deploy_and_report(
     );
     eprintln!();
 
-    for comp in &deployed {
+    for comp in deployed {
         let port_info = comp.port.map_or_else(String::new, |p| {
             format!(" {CYAN}->{RESET} http://localhost:{p}")
         });
@@ -109,31 +177,91 @@ fn deploy_and_report(
         }
     }
 
-    let project_dir = containust_common::constants::project_dir(path);
     eprintln!();
-    eprintln!("  {DIM}Project state: {}{RESET}", project_dir.display());
+    eprintln!(
+        "  {DIM}Project state: {}{RESET}",
+        engine.data_dir().display()
+    );
+}
+
+/// Poll ticks (250 ms each) between restart-policy passes while attached.
+const SUPERVISE_EVERY_TICKS: u32 = 8;
 
-    Ok(deployed)
+/// Applies restart policies to containers that exited while the run is
+/// attached; a failed pass is reported and retried on the next tick.
+fn supervise(engine: &Engine) {
+    match engine.reconcile() {
+        Ok(report) if report.restarted > 0 => {
+            eprintln!(
+                "  {YELLOW}Restarted {} container(s) by restart policy.{RESET}",
+                report.restarted
+            );
+        }
+        Ok(_) => {}
+        Err(error) => tracing::warn!(%error, "restart policy pass failed"),
+    }
 }
 
-fn wait_for_shutdown(engine: &Engine, _deployed: &[DeployedComponent]) -> anyhow::Result<()> {
+/// Converges the running components on the edited composition at `path`;
+/// on failure the previous set is kept and the error reported.
+fn reload(
+    engine: &Engine,
+    path: &Path,
+    deployed: Vec<DeployedComponent>,
+) -> Vec<DeployedComponent> {
     eprintln!();
-    eprintln!("  Press {BOLD}Ctrl+C{RESET} to stop all containers...");
+    eprintln!("  Reloading {}...", path.display());
+    match engine.reconcile_composition(path, &deployed) {
+        Ok((diff, next)) => {
+            eprintln!(
+                "  {GREEN}Reloaded:{RESET} {} added, {} removed, {} unchanged.",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.unchanged.len()
+            );
+            next
+        }
+        Err(error) => {
+            eprintln!("  {YELLOW}Reload failed:{RESET} {error}");
+            deployed
+        }
+    }
+}
 
-    let running = Arc::new(AtomicBool::new(true));
-    let r = running.clone();
-    ctrlc::set_handler(move || {
-        r.store(false, Ordering::SeqCst);
-    })
-    .map_err(|e| anyhow::anyhow!("failed to set Ctrl+C handler: {e}"))?;
+/// Supervises the deployment until Ctrl+C cancels `cancel`, then stops it.
+fn wait_for_shutdown(
+    engine: &Engine,
+    mut deployed: Vec<DeployedComponent>,
+    reload_path: Option<&Path>,
+    cancel: &CancellationToken,
+) -> anyhow::Result<()> {
+    eprintln!();
+    eprintln!("  Press {BOLD}Ctrl+C{RESET} to stop all containers...");
+    if let Some(path) = reload_path {
+        containust_runtime::hangup::install_reload_handler().map_err(|e| anyhow::anyhow!("{e}"))?;
+        eprintln!("  Send {BOLD}SIGHUP{RESET} to reload {}.", path.display());
+    }
 
-    while running.load(Ordering::SeqCst) {
+    let mut ticks = 0_u32;
+    while !cancel.is_cancelled() {
         std::thread::sleep(std::time::Duration::from_millis(250));
+        ticks += 1;
+        if ticks.is_multiple_of(SUPERVISE_EVERY_TICKS) {
+            supervise(engine);
+        }
+        if let Some(path) = reload_path
+            && containust_runtime::hangup::take_reload_request()
+        {
+            deployed = reload(engine, path, deployed);
+        }
     }
 
     eprintln!();
     eprintln!("  Stopping containers...");
-    engine.stop_all().map_err(|e| anyhow::anyhow!("{e}"))?;
+    let stopped = engine.stop_all().map_err(|e| anyhow::anyhow!("{e}"))?;
+    for name in super::stop::escalated_names(&stopped) {
+        eprintln!("  {YELLOW}Force-killed after stop timeout:{RESET} {name}");
+    }
     eprintln!("  {GREEN}All containers stopped.{RESET}");
 
     Ok(())
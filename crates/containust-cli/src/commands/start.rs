@@ -0,0 +1,36 @@
+//! `ctst start` — Start previously created containers.
+
+use clap::Args;
+
+/// Arguments for the `start` command.
+#[derive(Args, Debug)]
+pub struct StartArgs {
+    /// Container IDs or names to start.
+    #[arg(required = true)]
+    pub containers: Vec<String>,
+}
+
+/// Executes the `start` command.
+///
+/// # Errors
+///
+/// Returns an error when a target is missing or cannot be started.
+pub fn execute(args: StartArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    options.cancel_on_interrupt()?;
+    let engine = options.engine();
+    let containers = engine.list().map_err(|error| anyhow::anyhow!("{error}"))?;
+
+    for target in &args.containers {
+        engine
+            .cancellation()
+            .check("start")
+            .map_err(|error| anyhow::anyhow!("{error}"))?;
+        let id = super::resolve_container_id_from(&containers, target)?;
+        let pid = engine
+            .start(&id)
+            .map_err(|error| anyhow::anyhow!("{error}"))?;
+        println!("Started: {target} (pid {pid})");
+    }
+
+    Ok(())
+}
@@ -1,6 +1,11 @@
 //! `ctst stop` — Stop containers and clean up resources.
 
+use std::time::Duration;
+
 use clap::Args;
+use containust_runtime::backend::StopOptions;
+use containust_runtime::engine::{StopOutcome, StoppedContainer};
+use containust_runtime::signal::ContainerSignal;
 
 /// Arguments for the `stop` command.
 #[derive(Args, Debug)]
@@ -11,6 +16,31 @@ pub struct StopArgs {
     /// Force kill without graceful shutdown.
     #[arg(short, long)]
     pub force: bool,
+
+    /// Signal requesting the stop, overriding the component's `stop_signal`.
+    #[arg(short, long, conflicts_with = "force", value_parser = super::kill::parse_signal_arg)]
+    pub signal: Option<ContainerSignal>,
+
+    /// Grace period before a force kill (e.g. `30s`), overriding the
+    /// component's `stop_timeout`.
+    #[arg(short, long, conflicts_with = "force", value_parser = parse_duration_arg)]
+    pub timeout: Option<Duration>,
+}
+
+impl StopArgs {
+    /// Stop options given on the command line; unset fields fall back to
+    /// the component's declared values.
+    #[must_use]
+    pub const fn stop_options(&self) -> StopOptions {
+        StopOptions {
+            signal: self.signal,
+            timeout: self.timeout,
+        }
+    }
+}
+
+pub(super) fn parse_duration_arg(raw: &str) -> Result<Duration, String> {
+    containust_common::units::parse_duration(raw).map_err(|e| e.to_string())
 }
 
 /// Executes the `stop` command.
@@ -24,25 +54,47 @@ pub struct StopArgs {
 pub fn execute(args: StopArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
     let engine = options.engine();
 
+    let options = args.stop_options();
     if args.containers.is_empty() {
-        engine
-            .stop_all_with_force(args.force)
-            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        let stopped = if args.force {
+            engine.stop_all_with_force(true)
+        } else {
+            engine.stop_all_with_options(options)
+        }
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+        for name in escalated_names(&stopped) {
+            println!("Force-killed after stop timeout: {name}");
+        }
         println!("All containers stopped.");
     } else {
         let containers = engine.list().map_err(|e| anyhow::anyhow!("{e}"))?;
         for name in &args.containers {
             let id = super::resolve_container_id_from(&containers, name)?;
-            engine
-                .stop_with_force(&id, args.force)
-                .map_err(|e| anyhow::anyhow!("{e}"))?;
-            println!("Stopped: {name}");
+            let outcome = if args.force {
+                engine.stop_with_force(&id, true)
+            } else {
+                engine.stop_with_options(&id, options)
+            }
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+            if outcome == StopOutcome::Escalated {
+                println!("Stopped: {name} (force-killed after stop timeout)");
+            } else {
+                println!("Stopped: {name}");
+            }
         }
     }
 
     Ok(())
 }
 
+/// Names of containers that had to be force-killed after the stop timeout.
+pub(super) fn escalated_names(stopped: &[StoppedContainer]) -> impl Iterator<Item = &str> {
+    stopped
+        .iter()
+        .filter(|container| container.outcome == StopOutcome::Escalated)
+        .map(|container| container.name.as_str())
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::expect_used)]
@@ -50,6 +102,8 @@ mod tests {
     use containust_common::types::ContainerId;
     use containust_runtime::backend::ContainerInfo;
 
+    use super::*;
+
     #[test]
     fn resolve_stop_target_prefers_name_or_id_match() {
         let id = ContainerId::new("id-1");
@@ -60,6 +114,10 @@ mod tests {
             pid: Some(1),
             image: "file:///image".into(),
             created_at: "2026-01-01T00:00:00Z".into(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
+            health: None,
         }];
 
         assert_eq!(
@@ -72,4 +130,67 @@ mod tests {
         );
         assert!(super::super::resolve_container_id_from(&containers, "missing").is_err());
     }
+
+    #[test]
+    fn stop_flags_produce_stop_options() {
+        use clap::Parser;
+
+        let cli = crate::commands::Cli::try_parse_from([
+            "ctst",
+            "stop",
+            "web",
+            "--signal",
+            "SIGQUIT",
+            "--timeout",
+            "30s",
+        ])
+        .expect("should parse");
+        let crate::commands::Command::Stop(args) = cli.command else {
+            unreachable!("expected Stop");
+        };
+        let options = args.stop_options();
+        assert_eq!(options.signal.map(ContainerSignal::name), Some("SIGQUIT"));
+        assert_eq!(options.timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn stop_without_flags_defers_to_component_config() {
+        use clap::Parser;
+
+        let cli = crate::commands::Cli::try_parse_from(["ctst", "stop", "web"]).expect("parse");
+        let crate::commands::Command::Stop(args) = cli.command else {
+            unreachable!("expected Stop");
+        };
+        assert_eq!(args.stop_options(), StopOptions::default());
+        assert!(
+            crate::commands::Cli::try_parse_from(["ctst", "stop", "web", "-f", "-s", "HUP"])
+                .is_err()
+        );
+        assert!(
+            crate::commands::Cli::try_parse_from(["ctst", "stop", "web", "--timeout", "soon"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn escalated_names_lists_only_force_killed_containers() {
+        use containust_runtime::engine::{StopOutcome, StoppedContainer};
+
+        let stopped = [
+            StoppedContainer {
+                id: ContainerId::new("a"),
+                name: "web".into(),
+                outcome: StopOutcome::Graceful,
+            },
+            StoppedContainer {
+                id: ContainerId::new("b"),
+                name: "worker".into(),
+                outcome: StopOutcome::Escalated,
+            },
+        ];
+        assert_eq!(
+            super::escalated_names(&stopped).collect::<Vec<_>>(),
+            vec!["worker"]
+        );
+    }
 }
@@ -0,0 +1,93 @@
+//! `ctst upgrade` — Replace changed components of a running composition.
+
+use clap::{Args, ValueEnum};
+use containust_compose::input::CompositionSource;
+use containust_runtime::engine::UpgradeStrategy;
+
+use super::plan_report::{PlanAction, PlanReport};
+
+/// How each changed component is replaced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Strategy {
+    /// Start the new container, wait until it is ready, then stop the old one.
+    #[default]
+    Rolling,
+    /// Stop the old container, then start the new one.
+    Recreate,
+}
+
+impl From<Strategy> for UpgradeStrategy {
+    fn from(strategy: Strategy) -> Self {
+        match strategy {
+            Strategy::Rolling => Self::Rolling,
+            Strategy::Recreate => Self::Recreate,
+        }
+    }
+}
+
+/// Arguments for the `upgrade` command.
+#[derive(Args, Debug)]
+pub struct UpgradeArgs {
+    /// Path to the updated .ctst composition file (`-` reads stdin).
+    #[arg(short, long, default_value = "containust.ctst")]
+    pub file: String,
+
+    /// Replacement strategy for changed components.
+    #[arg(long, value_enum, default_value_t = Strategy::Rolling)]
+    pub strategy: Strategy,
+}
+
+/// Executes the `upgrade` command.
+///
+/// Classifies the composition against the project's state as `ctst plan
+/// --json` does and replaces every component whose image, env, ports, or
+/// limits changed. Added and removed components are reported, not applied.
+///
+/// # Errors
+///
+/// Returns an error if parsing, classification, or a replacement fails.
+pub fn execute(args: UpgradeArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    let source = CompositionSource::from_arg(&args.file);
+    let composition = source.parse().map_err(|e| anyhow::anyhow!("{e}"))?;
+    let engine = options.engine_for_project(&source.project_anchor());
+    let prior = if engine.state_file().exists() {
+        containust_runtime::state::load_state(engine.state_file())
+            .map_err(|e| anyhow::anyhow!("{e}"))?
+            .containers
+    } else {
+        Vec::new()
+    };
+    let order = containust_compose::graph::DependencyGraph::from_composition(&composition)
+        .resolve_order()
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    let report = PlanReport::assemble(&composition, order, &prior, &options.defaults)?;
+
+    let named = |action: PlanAction| -> Vec<String> {
+        report
+            .components
+            .iter()
+            .filter(|component| component.action == action)
+            .map(|component| component.name.clone())
+            .collect()
+    };
+    let changed = named(PlanAction::Update);
+    for name in named(PlanAction::Create) {
+        println!("Skipped: {name} is not deployed (use `ctst run` to add it)");
+    }
+    for name in named(PlanAction::Delete) {
+        println!("Skipped: {name} is no longer declared (use `ctst rm` to remove it)");
+    }
+    if changed.is_empty() {
+        println!("No component changed.");
+        return Ok(());
+    }
+
+    options.cancel_on_interrupt()?;
+    let upgraded = engine
+        .upgrade_composition(&composition, &changed, args.strategy.into())
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    for component in &upgraded {
+        println!("Upgraded: {} [{}]", component.name, component.id);
+    }
+    Ok(())
+}
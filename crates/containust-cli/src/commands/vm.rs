@@ -1,6 +1,14 @@
 //! `ctst vm` — Virtual machine management commands.
+//!
+//! Every subcommand prints a notice and succeeds without acting when
+//! containers run natively, since there is no VM to manage.
 
 use clap::Args;
+use containust_runtime::backend::vm::VmStatus;
+use containust_runtime::engine::Engine;
+
+/// Printed instead of acting when containers run natively.
+const NATIVE_NOTICE: &str = "Containers run natively on this host; there is no VM to manage.";
 
 /// Arguments for the `vm start` command.
 #[derive(Args, Debug)]
@@ -22,6 +30,35 @@ pub struct VmStopArgs {
     pub force: bool,
 }
 
+/// Arguments for the `vm status` command.
+#[derive(Args, Debug)]
+pub struct VmStatusArgs {}
+
+/// Arguments for the `vm restart` command.
+#[derive(Args, Debug)]
+pub struct VmRestartArgs {}
+
+/// Arguments for the `vm logs` command.
+#[derive(Args, Debug)]
+pub struct VmLogsArgs {
+    /// Show only the last N lines of console output.
+    #[arg(short = 'n', long)]
+    pub tail: Option<usize>,
+}
+
+/// Runs `action` when `engine` drives a VM and returns what to print;
+/// on the native backend returns [`NATIVE_NOTICE`] instead.
+fn on_vm(
+    engine: &Engine,
+    action: impl FnOnce(&Engine) -> anyhow::Result<String>,
+) -> anyhow::Result<String> {
+    if engine.uses_vm() {
+        action(engine)
+    } else {
+        Ok(NATIVE_NOTICE.into())
+    }
+}
+
 /// Executes the `vm start` command.
 ///
 /// Boots or ensures the QEMU-based VM backend is running.
@@ -30,11 +67,14 @@ pub struct VmStopArgs {
 ///
 /// Returns an error if QEMU is not installed or the VM fails to start.
 pub fn vm_start(args: VmStartArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
-    let engine = options.engine();
-    engine
-        .vm_start(args.kernel.as_deref(), args.initramfs.as_deref())
-        .map_err(|e| anyhow::anyhow!("{e}"))?;
-    println!("VM is ready (pinned Alpine assets in ~/.containust/cache/vm/).");
+    options.cancel_on_interrupt()?;
+    let output = on_vm(&options.engine(), |engine| {
+        engine
+            .vm_start(args.kernel.as_deref(), args.initramfs.as_deref())
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok("VM is ready (pinned Alpine assets in ~/.containust/cache/vm/).".into())
+    })?;
+    println!("{output}");
     Ok(())
 }
 
@@ -46,14 +86,186 @@ pub fn vm_start(args: VmStartArgs, options: &super::RuntimeOptions) -> anyhow::R
 ///
 /// Returns an error if the VM cannot be stopped.
 pub fn vm_stop(args: VmStopArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
-    let engine = options.engine();
-    engine
-        .vm_stop(args.force)
-        .map_err(|e| anyhow::anyhow!("{e}"))?;
-    if args.force {
-        println!("VM force stopped.");
+    let output = on_vm(&options.engine(), |engine| {
+        engine
+            .vm_stop(args.force)
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok(if args.force {
+            "VM force stopped.".into()
+        } else {
+            "VM stopped.".into()
+        })
+    })?;
+    println!("{output}");
+    Ok(())
+}
+
+/// Executes the `vm restart` command, keeping the VM's port forwards.
+///
+/// # Errors
+///
+/// Returns an error if the VM cannot be stopped or booted again.
+pub fn vm_restart(_args: VmRestartArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    options.cancel_on_interrupt()?;
+    let output = on_vm(&options.engine(), |engine| {
+        engine.vm_restart().map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok("VM restarted.".into())
+    })?;
+    println!("{output}");
+    Ok(())
+}
+
+/// Executes the `vm status` command.
+///
+/// # Errors
+///
+/// Returns an error if the VM pidfile cannot be read.
+pub fn vm_status(_args: VmStatusArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    let output = on_vm(&options.engine(), |engine| {
+        let status = engine.vm_status().map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok(render_status(&status))
+    })?;
+    println!("{output}");
+    Ok(())
+}
+
+/// Executes the `vm logs` command, printing the guest console output.
+///
+/// # Errors
+///
+/// Returns an error if the console log cannot be read.
+pub fn vm_logs(args: VmLogsArgs, options: &super::RuntimeOptions) -> anyhow::Result<()> {
+    let output = on_vm(&options.engine(), |engine| {
+        let log = engine.vm_logs().map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok(tail_lines(&log, args.tail))
+    })?;
+    println!("{output}");
+    Ok(())
+}
+
+/// Formats `status` as an aligned key/value block.
+fn render_status(status: &VmStatus) -> String {
+    if !status.running {
+        return "VM:         stopped".into();
+    }
+    let pid = status
+        .pid
+        .map_or_else(|| "unknown (no pidfile)".into(), |pid| pid.to_string());
+    let uptime = status
+        .uptime
+        .and_then(|uptime| chrono::TimeDelta::from_std(uptime).ok())
+        .map_or_else(|| "unknown".into(), crate::output::format_duration);
+    let forwarded = if status.forwarded.is_empty() {
+        "none".into()
     } else {
-        println!("VM stopped.");
+        status
+            .forwarded
+            .iter()
+            .map(|mapping| format!("{}->{}", mapping.host, mapping.container))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!(
+        "VM:         running\nPID:        {pid}\nAgent port: {}\nUptime:     {uptime}\n\
+         Forwarded:  {forwarded}",
+        status.agent_port
+    )
+}
+
+/// The last `tail` lines of `log`, or all of it.
+fn tail_lines(log: &str, tail: Option<usize>) -> String {
+    let lines: Vec<&str> = log.lines().collect();
+    let start = tail.map_or(0, |tail| lines.len().saturating_sub(tail));
+    lines[start..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use containust_common::error::Result;
+    use containust_common::types::{ContainerId, PortMapping};
+    use containust_runtime::backend::{ContainerBackend, ContainerConfig, ContainerInfo};
+    use containust_runtime::engine::EngineOptions;
+    use containust_runtime::exec::ExecOutput;
+
+    use super::*;
+
+    /// A native backend that never touches a container.
+    struct NativeBackend;
+
+    impl ContainerBackend for NativeBackend {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+        fn create(&self, _config: &ContainerConfig) -> Result<ContainerId> {
+            unreachable!("vm commands never create")
+        }
+        fn start(&self, _id: &ContainerId) -> Result<u32> {
+            unreachable!("vm commands never start")
+        }
+        fn stop(&self, _id: &ContainerId) -> Result<()> {
+            unreachable!("vm commands never stop containers")
+        }
+        fn exec(&self, _id: &ContainerId, _cmd: &[String]) -> Result<ExecOutput> {
+            unreachable!("vm commands never exec")
+        }
+        fn remove(&self, _id: &ContainerId) -> Result<()> {
+            unreachable!("vm commands never remove")
+        }
+        fn logs(&self, _id: &ContainerId) -> Result<String> {
+            Ok(String::new())
+        }
+        fn list(&self) -> Result<Vec<ContainerInfo>> {
+            Ok(Vec::new())
+        }
+        fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn native_backend_skips_vm_action_with_notice() {
+        let engine = Engine::with_backend(EngineOptions::default(), Box::new(NativeBackend));
+        let output = on_vm(&engine, |_| unreachable!("no VM to manage")).unwrap_or_default();
+        assert_eq!(output, NATIVE_NOTICE);
+    }
+
+    #[test]
+    fn render_status_lists_running_vm_details() {
+        let status = VmStatus {
+            running: true,
+            pid: Some(4242),
+            agent_port: 10809,
+            uptime: Some(Duration::from_secs(3_900)),
+            forwarded: vec![PortMapping {
+                host: 8080,
+                container: 80,
+            }],
+        };
+        let text = render_status(&status);
+        assert!(text.contains("PID:        4242"), "{text}");
+        assert!(text.contains("Uptime:     1h5m"), "{text}");
+        assert!(text.contains("Forwarded:  8080->80"), "{text}");
+    }
+
+    #[test]
+    fn render_status_of_stopped_vm_is_one_line() {
+        let status = VmStatus {
+            running: false,
+            pid: None,
+            agent_port: 10809,
+            uptime: None,
+            forwarded: Vec::new(),
+        };
+        assert_eq!(render_status(&status), "VM:         stopped");
+    }
+
+    #[test]
+    fn tail_lines_keeps_the_last_lines() {
+        let log = "boot\nmount\nagent ready\n";
+        assert_eq!(tail_lines(log, Some(2)), "mount\nagent ready");
+        assert_eq!(tail_lines(log, Some(10)), "boot\nmount\nagent ready");
+        assert_eq!(tail_lines(log, None), "boot\nmount\nagent ready");
     }
-    Ok(())
 }
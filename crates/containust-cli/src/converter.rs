@@ -1,19 +1,49 @@
 //! Docker Compose YAML to `.ctst` converter.
 //!
 //! Parses a `docker-compose.yml` file and emits the equivalent
-//! Containust `.ctst` composition language output.
+//! Containust `.ctst` composition language output. Kubernetes
+//! Deployment and Service manifests are handled by [`kubernetes`].
 
 use std::collections::BTreeMap;
 use std::fmt::Write as FmtWrite;
 use std::path::Path;
 
+use containust_compose::parser::ast::SchemaVersion;
+use containust_compose::parser::lexer::escape_ctst_string;
 use serde::Deserialize;
 
+mod env;
+mod kubernetes;
+mod summary;
+
+pub use summary::ConversionSummary;
+
+/// How a conversion treats the compose file's contents.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConvertOptions {
+    /// Substitute `${VAR}` references from the host environment instead of
+    /// preserving them.
+    pub expand_env: bool,
+}
+
+/// Format of the file being converted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SourceFormat {
+    /// A `docker-compose.yml` file.
+    #[default]
+    Compose,
+    /// Kubernetes Deployment and Service manifests.
+    Kubernetes,
+}
+
 /// Top-level docker-compose structure (subset we support).
 #[derive(Debug, Deserialize)]
 struct ComposeFile {
     #[serde(default)]
     services: BTreeMap<String, Service>,
+    /// Top-level keys the converter does not translate.
+    #[serde(flatten)]
+    extra: BTreeMap<String, serde_yaml::Value>,
 }
 
 /// A single docker-compose service definition.
@@ -26,6 +56,7 @@ struct Service {
     volumes: Vec<String>,
     #[serde(default)]
     environment: EnvFormat,
+    env_file: Option<env::EnvFileFormat>,
     #[serde(default)]
     depends_on: DependsOnFormat,
     command: Option<CommandFormat>,
@@ -44,6 +75,9 @@ struct Service {
     #[serde(default, rename = "read_only")]
     read_only: Option<bool>,
     build: Option<BuildConfig>,
+    /// Service keys the converter does not translate.
+    #[serde(flatten)]
+    extra: BTreeMap<String, serde_yaml::Value>,
 }
 
 /// Ports can be strings like "8080:80" or numbers.
@@ -133,14 +167,32 @@ struct ExtendedBuild {
     context: Option<String>,
 }
 
-/// Converts a docker-compose YAML file to `.ctst` format.
+/// Converts a docker-compose YAML file to `.ctst` format and reports what
+/// was translated. `env_file` paths resolve against the file's directory.
 ///
 /// # Errors
 ///
-/// Returns an error if the file cannot be read or parsed.
-pub fn convert_file(path: &Path) -> anyhow::Result<String> {
+/// Returns an error if the file or an `env_file` cannot be read, or the
+/// YAML cannot be parsed.
+pub fn convert_file(
+    path: &Path,
+    options: ConvertOptions,
+) -> anyhow::Result<(String, ConversionSummary)> {
     let content = std::fs::read_to_string(path)?;
-    convert_string(&content)
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    convert_source(&content, base_dir, options)
+}
+
+/// Converts a file of Kubernetes Deployment and Service manifests to
+/// `.ctst` format and reports what was translated.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, the YAML cannot be parsed,
+/// or it holds no Deployment.
+pub fn convert_kubernetes_file(path: &Path) -> anyhow::Result<(String, ConversionSummary)> {
+    let content = std::fs::read_to_string(path)?;
+    kubernetes::convert_manifests(&content)
 }
 
 /// Converts a docker-compose YAML string to `.ctst` format.
@@ -148,45 +200,63 @@ pub fn convert_file(path: &Path) -> anyhow::Result<String> {
 /// # Errors
 ///
 /// Returns an error if the YAML cannot be parsed.
+#[cfg(test)]
 pub fn convert_string(yaml: &str) -> anyhow::Result<String> {
+    Ok(convert_string_with_summary(yaml)?.0)
+}
+
+/// Converts a docker-compose YAML string and reports what was translated
+/// and which compose features were skipped.
+///
+/// # Errors
+///
+/// Returns an error if the YAML cannot be parsed.
+#[cfg(test)]
+pub fn convert_string_with_summary(yaml: &str) -> anyhow::Result<(String, ConversionSummary)> {
+    convert_source(yaml, Path::new("."), ConvertOptions::default())
+}
+
+fn convert_source(
+    yaml: &str,
+    base_dir: &Path,
+    options: ConvertOptions,
+) -> anyhow::Result<(String, ConversionSummary)> {
     let compose: ComposeFile = serde_yaml::from_str(yaml)?;
     let mut output = String::with_capacity(2048);
 
-    write_header(&mut output, &compose);
-    write_components(&mut output, &compose)?;
+    write_header(&mut output, "docker-compose.yml", compose.services.len());
+    for (name, svc) in &compose.services {
+        let env = env::ServiceEnv::collect(svc, base_dir, options)
+            .map_err(|error| anyhow::anyhow!("service '{name}': {error}"))?;
+        write_component(&mut output, name, svc, &env);
+        output.push('\n');
+    }
     write_connections(&mut output, &compose);
     let _ = containust_compose::parser::parse_ctst(&output)
         .map_err(|error| anyhow::anyhow!("generated .ctst failed validation: {error}"))?;
 
-    Ok(output)
+    Ok((output, summary::summarize(&compose)))
 }
 
-fn write_header(out: &mut String, compose: &ComposeFile) {
+fn write_header(out: &mut String, source: &str, components: usize) {
     out.push_str("// Auto-generated by: ctst convert\n");
-    out.push_str("// Source: docker-compose.yml\n");
-    let _ = writeln!(out, "// Components: {}", compose.services.len());
+    let _ = writeln!(out, "// Source: {source}");
+    let _ = writeln!(out, "// Components: {components}");
     out.push_str("//\n");
     out.push_str("// Review image sources — Docker Hub references have been converted\n");
     out.push_str("// to tar:// placeholders. Export images with:\n");
     out.push_str("//   docker save <image> -o /opt/images/<name>.tar\n\n");
+    let _ = writeln!(out, "SCHEMA \"{}\"\n", SchemaVersion::LATEST);
 }
 
-fn write_components(out: &mut String, compose: &ComposeFile) -> anyhow::Result<()> {
-    for (name, svc) in &compose.services {
-        write_component(out, name, svc)?;
-        out.push('\n');
-    }
-    Ok(())
-}
-
-fn write_component(out: &mut String, name: &str, svc: &Service) -> anyhow::Result<()> {
+fn write_component(out: &mut String, name: &str, svc: &Service, env: &env::ServiceEnv) {
     let _ = writeln!(out, "COMPONENT {name} {{");
 
     write_image(out, svc);
     write_ports(out, svc);
     write_memory(out, svc);
     write_cpu(out, svc);
-    write_env(out, svc);
+    env.write(out);
     write_volumes(out, svc);
     write_command(out, "command", svc.command.as_ref());
     write_command(out, "entrypoint", svc.entrypoint.as_ref());
@@ -199,13 +269,12 @@ fn write_component(out: &mut String, name: &str, svc: &Service) -> anyhow::Resul
     write_healthcheck(out, svc);
 
     out.push_str("}\n");
-    Ok(())
 }
 
 fn write_image(out: &mut String, svc: &Service) {
     if let Some(ref image) = svc.image {
         let ctst_image = convert_image_ref(image);
-        let _ = writeln!(out, "    image = \"{ctst_image}\"");
+        let _ = writeln!(out, "    image = {}", quoted(&ctst_image));
     } else if let Some(ref build) = svc.build {
         let ctx = match build {
             BuildConfig::Simple(s) => s.clone(),
@@ -335,61 +404,15 @@ fn write_cpu(out: &mut String, svc: &Service) {
     }
 }
 
-fn write_env(out: &mut String, svc: &Service) {
-    let vars = collect_env_vars(&svc.environment);
-    if vars.is_empty() {
-        return;
-    }
-
-    out.push_str("    env = {\n");
-    for (key, value) in &vars {
-        if value.starts_with("${") && value.ends_with('}') {
-            let inner = &value[2..value.len() - 1];
-            let _ = writeln!(out, "        {key} = \"${{secret.{inner}}}\"");
-        } else {
-            let escaped = value.replace('\"', "\\\"");
-            let _ = writeln!(out, "        {key} = \"{escaped}\"");
-        }
-    }
-    out.push_str("    }\n");
-}
-
-fn collect_env_vars(env: &EnvFormat) -> Vec<(String, String)> {
-    match env {
-        EnvFormat::Map(m) => m
-            .iter()
-            .map(|(k, v)| {
-                let val = match v {
-                    serde_yaml::Value::String(s) => s.clone(),
-                    serde_yaml::Value::Number(n) => n.to_string(),
-                    serde_yaml::Value::Bool(b) => b.to_string(),
-                    serde_yaml::Value::Null => String::new(),
-                    other => format!("{other:?}"),
-                };
-                (k.clone(), val)
-            })
-            .collect(),
-        EnvFormat::List(list) => list
-            .iter()
-            .filter_map(|entry| {
-                let (k, v) = entry.split_once('=')?;
-                Some((k.to_string(), v.to_string()))
-            })
-            .collect(),
-        EnvFormat::None => Vec::new(),
-    }
-}
-
 fn write_volumes(out: &mut String, svc: &Service) {
     if svc.volumes.is_empty() {
         return;
     }
 
     if svc.volumes.len() == 1 {
-        let _ = writeln!(out, "    volume = \"{}\"", svc.volumes[0]);
+        let _ = writeln!(out, "    volume = {}", quoted(&svc.volumes[0]));
     } else {
-        let quoted: Vec<String> = svc.volumes.iter().map(|v| format!("\"{v}\"")).collect();
-        let _ = writeln!(out, "    volumes = [{}]", quoted.join(", "));
+        let _ = writeln!(out, "    volumes = [{}]", quoted_list(&svc.volumes));
     }
 }
 
@@ -399,16 +422,28 @@ fn write_command(out: &mut String, prop: &str, cmd: Option<&CommandFormat>) {
     match cmd {
         CommandFormat::String(s) => {
             let parts: Vec<&str> = s.split_whitespace().collect();
-            let quoted: Vec<String> = parts.iter().map(|p| format!("\"{p}\"")).collect();
-            let _ = writeln!(out, "    {prop} = [{}]", quoted.join(", "));
+            let _ = writeln!(out, "    {prop} = [{}]", quoted_list(&parts));
         }
         CommandFormat::List(list) => {
-            let quoted: Vec<String> = list.iter().map(|p| format!("\"{p}\"")).collect();
-            let _ = writeln!(out, "    {prop} = [{}]", quoted.join(", "));
+            let _ = writeln!(out, "    {prop} = [{}]", quoted_list(list));
         }
     }
 }
 
+/// `value` as a quoted `.ctst` string literal.
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", escape_ctst_string(value))
+}
+
+/// `items` as the comma-separated body of a `.ctst` string list.
+fn quoted_list<S: AsRef<str>>(items: &[S]) -> String {
+    items
+        .iter()
+        .map(|item| quoted(item.as_ref()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn write_readonly(out: &mut String, svc: &Service) {
     if let Some(ro) = svc.read_only {
         let _ = writeln!(out, "    readonly = {ro}");
@@ -417,7 +452,7 @@ fn write_readonly(out: &mut String, svc: &Service) {
 
 fn write_string_prop(out: &mut String, prop: &str, value: Option<&String>) {
     if let Some(v) = value {
-        let _ = writeln!(out, "    {prop} = \"{v}\"");
+        let _ = writeln!(out, "    {prop} = {}", quoted(v));
     }
 }
 
@@ -437,7 +472,7 @@ fn write_restart(out: &mut String, svc: &Service) {
 
 fn write_network(out: &mut String, svc: &Service) {
     if svc.networks.len() == 1 {
-        let _ = writeln!(out, "    network = \"{}\"", svc.networks[0]);
+        let _ = writeln!(out, "    network = {}", quoted(&svc.networks[0]));
     }
 }
 
@@ -451,15 +486,14 @@ fn write_healthcheck(out: &mut String, svc: &Service) {
     if let Some(ref test) = hc.test {
         match test {
             HealthcheckTest::String(s) => {
-                let _ = writeln!(out, "        command = [\"{s}\"]");
+                let _ = writeln!(out, "        command = [{}]", quoted(s));
             }
             HealthcheckTest::List(list) => {
                 let filtered: Vec<&String> = list
                     .iter()
                     .filter(|s| *s != "CMD" && *s != "CMD-SHELL")
                     .collect();
-                let quoted: Vec<String> = filtered.iter().map(|p| format!("\"{p}\"")).collect();
-                let _ = writeln!(out, "        command = [{}]", quoted.join(", "));
+                let _ = writeln!(out, "        command = [{}]", quoted_list(&filtered));
             }
         }
     }
@@ -848,6 +882,32 @@ services:
         assert!(result.contains("restart = \"never\""));
     }
 
+    #[test]
+    fn test_convert_declares_the_schema_version() {
+        let yaml = "services:\n  web:\n    image: nginx\n";
+        let output = convert_string(yaml).expect("convert");
+        let parsed = containust_compose::parser::parse_ctst(&output).expect("parse");
+        assert_eq!(parsed.schema, Some(SchemaVersion::LATEST));
+    }
+
+    #[test]
+    fn test_convert_escapes_quotes_and_backslashes() {
+        let yaml = r#"
+services:
+  app:
+    image: myapp
+    command: ["sh", "-c", "echo \"hi\" > C:\\out"]
+    healthcheck:
+      test: ["CMD-SHELL", "test \"$$(cat /ready)\" = ok"]
+"#;
+        let result = convert_string(yaml).expect("conversion should succeed");
+        let composition = containust_compose::parser::parse_ctst(&result).expect("valid .ctst");
+        let app = &composition.components[0];
+        assert_eq!(app.command, ["sh", "-c", r#"echo "hi" > C:\out"#]);
+        let healthcheck = app.healthcheck.as_ref().expect("healthcheck");
+        assert_eq!(healthcheck.command, [r#"test "$$(cat /ready)" = ok"#]);
+    }
+
     #[test]
     fn test_convert_with_healthcheck_string_test() {
         let yaml = r#"
@@ -1015,7 +1075,8 @@ services:
         let mut f = std::fs::File::create(&tmp).expect("create tmp file");
         f.write_all(yaml.as_bytes()).expect("write tmp file");
 
-        let result = convert_file(&tmp).expect("conversion should succeed");
+        let (result, _) =
+            convert_file(&tmp, ConvertOptions::default()).expect("conversion should succeed");
         assert!(result.contains("COMPONENT app"));
 
         let _ = std::fs::remove_file(&tmp);
@@ -1031,7 +1092,10 @@ services:
 
     #[test]
     fn test_convert_file_nonexistent_fails() {
-        let result = convert_file(std::path::Path::new("/nonexistent/file.yml"));
+        let result = convert_file(
+            std::path::Path::new("/nonexistent/file.yml"),
+            ConvertOptions::default(),
+        );
         assert!(result.is_err());
     }
 
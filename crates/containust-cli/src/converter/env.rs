@@ -0,0 +1,348 @@
+//! Service environment: `environment` (map or list), `env_file`, and
+//! `${VAR}` references.
+//!
+//! A variable named without a value (`- DEBUG` or `DEBUG:`) passes the
+//! host's value through in compose, so it becomes an `inherit_env` entry.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as FmtWrite;
+use std::path::Path;
+
+use containust_compose::parser::lexer::escape_ctst_string;
+use serde::Deserialize;
+
+use super::{ConvertOptions, EnvFormat, Service};
+
+/// `env_file` is a path or a list of paths or `{ path, required }` entries.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(super) enum EnvFileFormat {
+    Single(String),
+    List(Vec<EnvFileEntry>),
+}
+
+/// One `env_file` entry.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(super) enum EnvFileEntry {
+    Path(String),
+    Detailed {
+        path: String,
+        #[serde(default = "required_by_default")]
+        required: bool,
+    },
+}
+
+const fn required_by_default() -> bool {
+    true
+}
+
+/// The environment a converted component declares.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct ServiceEnv {
+    vars: BTreeMap<String, String>,
+    inherit: Vec<String>,
+}
+
+impl ServiceEnv {
+    /// Collects `env_file` entries (read relative to `base_dir`), then
+    /// `environment`, which overrides them.
+    pub(super) fn collect(
+        svc: &Service,
+        base_dir: &Path,
+        options: ConvertOptions,
+    ) -> anyhow::Result<Self> {
+        let mut env = Self::default();
+        for (path, required) in env_file_paths(svc.env_file.as_ref()) {
+            for (key, value) in read_env_file(&base_dir.join(path), required)? {
+                env.insert(key, value, options);
+            }
+        }
+        for (key, value) in environment_entries(&svc.environment) {
+            env.insert(key, value, options);
+        }
+        Ok(env)
+    }
+
+    fn insert(&mut self, key: String, value: Option<String>, options: ConvertOptions) {
+        self.inherit.retain(|name| *name != key);
+        let _ = self.vars.remove(&key);
+        match value {
+            Some(value) if options.expand_env => {
+                let _ = self
+                    .vars
+                    .insert(key, expand_vars(&value, |name| std::env::var(name).ok()));
+            }
+            Some(value) => {
+                let _ = self.vars.insert(key, value);
+            }
+            None => self.inherit.push(key),
+        }
+    }
+
+    /// Writes the `env` map and `inherit_env` list.
+    pub(super) fn write(&self, out: &mut String) {
+        if !self.vars.is_empty() {
+            out.push_str("    env = {\n");
+            for (key, value) in &self.vars {
+                let _ = writeln!(out, "        {key} = \"{}\"", ctst_value(value));
+            }
+            out.push_str("    }\n");
+        }
+        if !self.inherit.is_empty() {
+            let _ = writeln!(
+                out,
+                "    inherit_env = [{}]",
+                super::quoted_list(&self.inherit)
+            );
+        }
+    }
+}
+
+/// A value that is exactly `${VAR}` becomes a `${secret.VAR}` reference;
+/// anything else is escaped as a string literal.
+pub(super) fn ctst_value(value: &str) -> String {
+    if value.starts_with("${") && value.ends_with('}') {
+        return format!("${{secret.{}}}", &value[2..value.len() - 1]);
+    }
+    escape_ctst_string(value)
+}
+
+/// Reads an env file; a missing optional file contributes nothing.
+fn read_env_file(path: &Path, required: bool) -> anyhow::Result<Vec<(String, Option<String>)>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(parse_env_file(&content)),
+        Err(error) if !required && error.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(error) => anyhow::bail!("cannot read env_file {}: {error}", path.display()),
+    }
+}
+
+fn env_file_paths(env_file: Option<&EnvFileFormat>) -> Vec<(&str, bool)> {
+    match env_file {
+        None => Vec::new(),
+        Some(EnvFileFormat::Single(path)) => vec![(path.as_str(), true)],
+        Some(EnvFileFormat::List(entries)) => entries
+            .iter()
+            .map(|entry| match entry {
+                EnvFileEntry::Path(path) => (path.as_str(), true),
+                EnvFileEntry::Detailed { path, required } => (path.as_str(), *required),
+            })
+            .collect(),
+    }
+}
+
+/// `environment` entries; `None` values pass the host's value through.
+fn environment_entries(env: &EnvFormat) -> Vec<(String, Option<String>)> {
+    match env {
+        EnvFormat::Map(map) => map
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_yaml::Value::String(s) => Some(s.clone()),
+                    serde_yaml::Value::Number(n) => Some(n.to_string()),
+                    serde_yaml::Value::Bool(b) => Some(b.to_string()),
+                    serde_yaml::Value::Null => None,
+                    other => Some(format!("{other:?}")),
+                };
+                (key.clone(), value)
+            })
+            .collect(),
+        EnvFormat::List(list) => list.iter().map(|entry| split_assignment(entry)).collect(),
+        EnvFormat::None => Vec::new(),
+    }
+}
+
+/// Parses `KEY=VALUE` lines, skipping blanks and `#` comments and
+/// stripping one pair of matching quotes around the value.
+fn parse_env_file(content: &str) -> Vec<(String, Option<String>)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (key, value) = split_assignment(line);
+            let value = value.map(|value| {
+                let trimmed = value.trim();
+                ['"', '\'']
+                    .iter()
+                    .find_map(|&quote| {
+                        trimmed
+                            .strip_prefix(quote)
+                            .and_then(|rest| rest.strip_suffix(quote))
+                    })
+                    .unwrap_or(trimmed)
+                    .to_string()
+            });
+            (key, value)
+        })
+        .collect()
+}
+
+/// Splits `KEY=VALUE` on the first `=`; a bare `KEY` has no value.
+fn split_assignment(entry: &str) -> (String, Option<String>) {
+    entry.split_once('=').map_or_else(
+        || (entry.trim().to_string(), None),
+        |(key, value)| (key.trim().to_string(), Some(value.to_string())),
+    )
+}
+
+/// Substitutes `${VAR}`, `${VAR:-default}`, `${VAR-default}`, and `$VAR`
+/// with `lookup`, as compose does; `$$` is a literal `$` and an unset
+/// variable without a default becomes empty.
+pub(super) fn expand_vars(value: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(index) = rest.find('$') {
+        out.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            out.push('$');
+            rest = after;
+        } else if let Some((body, after)) = rest
+            .strip_prefix('{')
+            .and_then(|inner| inner.split_once('}'))
+        {
+            out.push_str(&expand_braced(body, &lookup));
+            rest = after;
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            if end == 0 {
+                out.push('$');
+            } else {
+                out.push_str(&lookup(&rest[..end]).unwrap_or_default());
+            }
+            rest = &rest[end..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn expand_braced(body: &str, lookup: &impl Fn(&str) -> Option<String>) -> String {
+    if let Some((name, default)) = body.split_once(":-") {
+        return lookup(name)
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| default.to_string());
+    }
+    if let Some((name, default)) = body.split_once('-') {
+        return lookup(name).unwrap_or_else(|| default.to_string());
+    }
+    lookup(body).unwrap_or_else(|| {
+        tracing::warn!(
+            variable = body,
+            "variable is not set; substituting an empty string"
+        );
+        String::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used, clippy::literal_string_with_formatting_args)]
+
+    use super::super::{ConvertOptions, convert_source};
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "USER" => Some("app".into()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expand_vars_follows_compose_rules() {
+        assert_eq!(expand_vars("pg://${USER}@db", lookup), "pg://app@db");
+        assert_eq!(expand_vars("$USER-$MISSING.", lookup), "app-.");
+        assert_eq!(expand_vars("${MISSING:-dflt}", lookup), "dflt");
+        assert_eq!(expand_vars("${EMPTY:-dflt}|${EMPTY-dflt}", lookup), "dflt|");
+        assert_eq!(expand_vars("cost $$5 $", lookup), "cost $5 $");
+    }
+
+    #[test]
+    fn both_environment_forms_convert() {
+        let yaml = r"
+services:
+  map:
+    image: app
+    environment:
+      PORT: 8080
+      TZ:
+  list:
+    image: app
+    environment:
+      - PORT=8080
+      - URL=a=b
+      - TZ
+";
+        let (ctst, _) =
+            convert_source(yaml, Path::new("."), ConvertOptions::default()).expect("convert");
+        assert_eq!(ctst.matches("PORT = \"8080\"").count(), 2, "{ctst}");
+        assert!(ctst.contains("URL = \"a=b\""), "{ctst}");
+        assert_eq!(ctst.matches("inherit_env = [\"TZ\"]").count(), 2, "{ctst}");
+    }
+
+    #[test]
+    fn env_file_is_inlined_and_overridden_by_environment() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            dir.path().join("app.env"),
+            "# defaults\nMODE=dev\nGREETING=\"hello world\"\nexport_me\n\nLEVEL='debug'\n",
+        )
+        .expect("env file");
+        let yaml = r"
+services:
+  app:
+    image: app
+    env_file:
+      - app.env
+      - path: missing.env
+        required: false
+    environment:
+      MODE: prod
+";
+        let (ctst, _) =
+            convert_source(yaml, dir.path(), ConvertOptions::default()).expect("convert");
+        assert!(ctst.contains("MODE = \"prod\""), "{ctst}");
+        assert!(ctst.contains("GREETING = \"hello world\""), "{ctst}");
+        assert!(ctst.contains("LEVEL = \"debug\""), "{ctst}");
+        assert!(ctst.contains("inherit_env = [\"export_me\"]"), "{ctst}");
+
+        let missing = convert_source(
+            "services:\n  app:\n    image: app\n    env_file: nope.env\n",
+            dir.path(),
+            ConvertOptions::default(),
+        )
+        .expect_err("required env_file");
+        assert!(missing.to_string().contains("nope.env"), "{missing}");
+    }
+
+    #[test]
+    fn references_are_preserved_unless_expanded() {
+        let yaml = r"
+services:
+  app:
+    image: app
+    environment:
+      KEY: ${API_KEY}
+      URL: http://${CTST_CONVERT_UNSET_HOST:-localhost}/
+";
+        let (kept, _) =
+            convert_source(yaml, Path::new("."), ConvertOptions::default()).expect("convert");
+        assert!(kept.contains("KEY = \"${secret.API_KEY}\""), "{kept}");
+        assert!(
+            kept.contains("URL = \"http://${CTST_CONVERT_UNSET_HOST:-localhost}/\""),
+            "{kept}"
+        );
+
+        let options = ConvertOptions { expand_env: true };
+        let (expanded, _) = convert_source(yaml, Path::new("."), options).expect("convert");
+        assert!(
+            expanded.contains("URL = \"http://localhost/\""),
+            "{expanded}"
+        );
+    }
+}
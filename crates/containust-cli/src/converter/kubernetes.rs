@@ -0,0 +1,828 @@
+//! Kubernetes Deployment and Service manifests to `.ctst` converter.
+//!
+//! Every container of a Deployment becomes a `COMPONENT`; a Service whose
+//! selector matches the pod template publishes its ports with `EXPOSE`.
+//! Fields with no `.ctst` equivalent are written as `// unsupported:`
+//! comments and listed in the conversion summary.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as FmtWrite;
+
+use containust_common::types::ResourceLimits;
+use serde::Deserialize;
+
+use super::summary::{ConversionSummary, SkippedFeature};
+
+/// Deployment spec keys that need no `.ctst` counterpart.
+const IGNORED_DEPLOYMENT_KEYS: [&str; 1] = ["selector"];
+
+/// Container keys that need no `.ctst` counterpart.
+const IGNORED_CONTAINER_KEYS: [&str; 1] = ["imagePullPolicy"];
+
+/// Period a bare `cpu_max` quota applies to (the kernel default).
+const CPU_PERIOD_US: u64 = 100_000;
+
+/// Kubernetes memory suffixes and their `.ctst` size units.
+const MEMORY_UNITS: [(&str, &str); 6] = [
+    ("Ki", "KiB"),
+    ("Mi", "MiB"),
+    ("Gi", "GiB"),
+    ("k", "KB"),
+    ("M", "MB"),
+    ("G", "GB"),
+];
+
+/// Object metadata (subset we read).
+#[derive(Debug, Default, Deserialize)]
+struct Metadata {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+/// An `apps/v1` Deployment.
+#[derive(Debug, Deserialize)]
+struct Deployment {
+    #[serde(default)]
+    metadata: Metadata,
+    spec: DeploymentSpec,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeploymentSpec {
+    replicas: Option<u32>,
+    template: PodTemplate,
+    /// Deployment keys the converter does not translate.
+    #[serde(flatten)]
+    extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PodTemplate {
+    #[serde(default)]
+    metadata: Metadata,
+    spec: PodSpec,
+}
+
+#[derive(Debug, Deserialize)]
+struct PodSpec {
+    containers: Vec<Container>,
+    /// Pod keys the converter does not translate.
+    #[serde(flatten)]
+    extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Container {
+    name: String,
+    image: Option<String>,
+    #[serde(default)]
+    command: Vec<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: Vec<EnvVar>,
+    #[serde(default)]
+    resources: Resources,
+    #[serde(default)]
+    ports: Vec<ContainerPort>,
+    working_dir: Option<String>,
+    /// Container keys the converter does not translate.
+    #[serde(flatten)]
+    extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnvVar {
+    name: String,
+    value: Option<String>,
+    value_from: Option<serde_yaml::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Resources {
+    #[serde(default)]
+    limits: Quantities,
+    #[serde(default)]
+    requests: Quantities,
+}
+
+/// Quantities are strings (`"512Mi"`, `"500m"`) or bare numbers.
+#[derive(Debug, Default, Deserialize)]
+struct Quantities {
+    memory: Option<serde_yaml::Value>,
+    cpu: Option<serde_yaml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContainerPort {
+    container_port: u16,
+    name: Option<String>,
+}
+
+/// A `v1` Service.
+#[derive(Debug, Deserialize)]
+struct Service {
+    #[serde(default)]
+    metadata: Metadata,
+    spec: ServiceSpec,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServiceSpec {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    selector: BTreeMap<String, String>,
+    #[serde(default)]
+    ports: Vec<ServicePort>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServicePort {
+    port: u16,
+    target_port: Option<TargetPort>,
+    node_port: Option<u16>,
+}
+
+/// `targetPort` is a port number or the name of a container port.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TargetPort {
+    Number(u16),
+    Name(String),
+}
+
+/// The Deployments and Services of a manifest, plus the objects skipped.
+#[derive(Debug, Default)]
+struct Manifests {
+    deployments: Vec<Deployment>,
+    services: Vec<Service>,
+    skipped: Vec<SkippedFeature>,
+}
+
+impl Manifests {
+    /// Splits a multi-document manifest by `kind`.
+    fn parse(yaml: &str) -> anyhow::Result<Self> {
+        let mut manifests = Self::default();
+        for document in serde_yaml::Deserializer::from_str(yaml) {
+            let value = serde_yaml::Value::deserialize(document)?;
+            if value.is_null() {
+                continue;
+            }
+            let kind = value
+                .get("kind")
+                .and_then(serde_yaml::Value::as_str)
+                .unwrap_or("(no kind)")
+                .to_string();
+            match kind.as_str() {
+                "Deployment" => manifests.deployments.push(
+                    serde_yaml::from_value(value)
+                        .map_err(|error| anyhow::anyhow!("invalid Deployment: {error}"))?,
+                ),
+                "Service" => manifests.services.push(
+                    serde_yaml::from_value(value)
+                        .map_err(|error| anyhow::anyhow!("invalid Service: {error}"))?,
+                ),
+                _ => manifests.skipped.push(SkippedFeature {
+                    service: None,
+                    feature: object_name(&kind, &value),
+                    reason: "only Deployment and Service objects are converted".into(),
+                }),
+            }
+        }
+        if manifests.deployments.is_empty() {
+            anyhow::bail!("no Deployment found in the Kubernetes manifest");
+        }
+        Ok(manifests)
+    }
+}
+
+fn object_name(kind: &str, value: &serde_yaml::Value) -> String {
+    value
+        .get("metadata")
+        .and_then(|metadata| metadata.get("name"))
+        .and_then(serde_yaml::Value::as_str)
+        .map_or_else(|| kind.to_string(), |name| format!("{kind}/{name}"))
+}
+
+/// One container of a Deployment, rendered as a `COMPONENT`.
+#[derive(Debug)]
+struct Component<'a> {
+    name: String,
+    /// Index of the owning Deployment.
+    deployment: usize,
+    container: &'a Container,
+    ports: Vec<u16>,
+    skipped: Vec<SkippedFeature>,
+}
+
+impl<'a> Component<'a> {
+    /// Builds one component per container; a single container takes the
+    /// Deployment's name, several are named `<deployment>-<container>`.
+    fn from_deployment(index: usize, deployment: &'a Deployment) -> Vec<Self> {
+        let containers = &deployment.spec.template.spec.containers;
+        let mut components: Vec<Self> = containers
+            .iter()
+            .map(|container| {
+                let name = if containers.len() == 1 {
+                    deployment.metadata.name.clone()
+                } else {
+                    format!("{}-{}", deployment.metadata.name, container.name)
+                };
+                let mut component = Self {
+                    skipped: Vec::new(),
+                    ports: container.ports.iter().map(|p| p.container_port).collect(),
+                    name,
+                    deployment: index,
+                    container,
+                };
+                component.skip_container_features();
+                component
+            })
+            .collect();
+        if let Some(first) = components.first_mut() {
+            let mut skipped = deployment_skips(deployment, &first.name);
+            skipped.append(&mut first.skipped);
+            first.skipped = skipped;
+        }
+        components
+    }
+
+    fn skip(&mut self, feature: impl Into<String>, reason: impl Into<String>) {
+        self.skipped.push(SkippedFeature {
+            service: Some(self.name.clone()),
+            feature: feature.into(),
+            reason: reason.into(),
+        });
+    }
+
+    fn skip_container_features(&mut self) {
+        let container = self.container;
+        for key in container.extra.keys() {
+            if !IGNORED_CONTAINER_KEYS.contains(&key.as_str()) {
+                self.skip(key.clone(), container_reason(key));
+            }
+        }
+        for var in container.env.iter().filter(|var| var.value_from.is_some()) {
+            self.skip(
+                format!("env {} valueFrom", var.name),
+                "ConfigMap and Secret references are not resolved; set the value or use a \
+                 ${secret.NAME} reference",
+            );
+        }
+    }
+
+    fn write(&mut self, out: &mut String) {
+        let container = self.container;
+        let _ = writeln!(out, "COMPONENT {} {{", self.name);
+        if let Some(ref image) = container.image {
+            let _ = writeln!(
+                out,
+                "    image = {}",
+                super::quoted(&super::convert_image_ref(image))
+            );
+        }
+        match self.ports.as_slice() {
+            [] => {}
+            [port] => {
+                let _ = writeln!(out, "    port = {port}");
+            }
+            ports => {
+                let list: Vec<String> = ports.iter().map(u16::to_string).collect();
+                let _ = writeln!(out, "    ports = [{}]", list.join(", "));
+            }
+        }
+        self.write_resources(out);
+        write_env(out, &container.env);
+        write_list(out, "entrypoint", &container.command);
+        write_list(out, "command", &container.args);
+        if let Some(ref workdir) = container.working_dir {
+            let _ = writeln!(out, "    workdir = {}", super::quoted(workdir));
+        }
+        for skipped in &self.skipped {
+            let _ = writeln!(
+                out,
+                "    // unsupported: {} — {}",
+                skipped.feature, skipped.reason
+            );
+        }
+        out.push_str("}\n");
+    }
+
+    /// Maps memory limits and requests to `memory` and
+    /// `memory_reservation`, the CPU limit to a `cpu_max` quota, and the
+    /// CPU request to `cpu` shares.
+    fn write_resources(&mut self, out: &mut String) {
+        let resources = &self.container.resources;
+        let memory = [
+            ("memory", "limits.memory", resources.limits.memory.as_ref()),
+            (
+                "memory_reservation",
+                "requests.memory",
+                resources.requests.memory.as_ref(),
+            ),
+        ];
+        for (prop, feature, quantity) in memory {
+            let Some(quantity) = quantity else { continue };
+            match memory_size(quantity) {
+                Some(size) => {
+                    let _ = writeln!(out, "    {prop} = \"{size}\"");
+                }
+                None => self.skip(feature, unit_reason(quantity)),
+            }
+        }
+        if let Some(quantity) = &resources.limits.cpu {
+            match cpu_quota_us(quantity) {
+                Some(quota) => {
+                    let _ = writeln!(out, "    cpu_max = \"{quota}\"");
+                }
+                None => self.skip("limits.cpu", quota_reason(quantity)),
+            }
+        }
+        if let Some(quantity) = &resources.requests.cpu {
+            match cpu_shares(quantity) {
+                Some(shares) => {
+                    let _ = writeln!(out, "    cpu = \"{shares}\"");
+                }
+                None => self.skip("requests.cpu", unit_reason(quantity)),
+            }
+        }
+    }
+}
+
+/// Deployment- and pod-level features, reported on the first component.
+fn deployment_skips(deployment: &Deployment, component: &str) -> Vec<SkippedFeature> {
+    let spec = &deployment.spec;
+    let mut skipped = Vec::new();
+    let mut skip = |feature: String, reason: &str| {
+        skipped.push(SkippedFeature {
+            service: Some(component.to_string()),
+            feature,
+            reason: reason.into(),
+        });
+    };
+    if let Some(replicas) = spec.replicas.filter(|&replicas| replicas > 1) {
+        skip(
+            format!("replicas: {replicas}"),
+            "a component runs a single instance",
+        );
+    }
+    if spec.template.spec.containers.len() > 1 {
+        skip(
+            "containers".into(),
+            "each container becomes its own component and no longer shares localhost",
+        );
+    }
+    for key in spec.extra.keys() {
+        if !IGNORED_DEPLOYMENT_KEYS.contains(&key.as_str()) {
+            skip(key.clone(), "rollout settings have no .ctst equivalent");
+        }
+    }
+    for key in spec.template.spec.extra.keys() {
+        skip(key.clone(), pod_reason(key));
+    }
+    skipped
+}
+
+fn container_reason(key: &str) -> &'static str {
+    match key {
+        "livenessProbe" | "readinessProbe" | "startupProbe" => {
+            "write an equivalent healthcheck block by hand"
+        }
+        "volumeMounts" => "declare volume = \"host:container\" on the component",
+        "securityContext" => "set user, readonly, and capabilities on the component",
+        _ => "no .ctst equivalent",
+    }
+}
+
+fn pod_reason(key: &str) -> &'static str {
+    match key {
+        "volumes" => "declare volume = \"host:container\" on the component",
+        "initContainers" => "convert to a separate component and DEPENDS_ON it",
+        _ => "pod-level setting has no .ctst equivalent",
+    }
+}
+
+fn unit_reason(quantity: &serde_yaml::Value) -> String {
+    format!(
+        "quantity '{}' has no .ctst equivalent",
+        quantity_text(quantity).unwrap_or_default()
+    )
+}
+
+fn quota_reason(quantity: &serde_yaml::Value) -> String {
+    format!(
+        "quantity '{}' is not a CPU quota of at least {}m",
+        quantity_text(quantity).unwrap_or_default(),
+        ResourceLimits::MIN_CPU_QUOTA_US * 1000 / CPU_PERIOD_US
+    )
+}
+
+fn quantity_text(quantity: &serde_yaml::Value) -> Option<String> {
+    match quantity {
+        serde_yaml::Value::String(text) => Some(text.trim().to_string()),
+        serde_yaml::Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// Converts a memory quantity such as `512Mi` or `1G` to a `.ctst` size.
+fn memory_size(quantity: &serde_yaml::Value) -> Option<String> {
+    let text = quantity_text(quantity)?;
+    let (number, unit) = MEMORY_UNITS
+        .iter()
+        .find_map(|(suffix, unit)| text.strip_suffix(suffix).map(|number| (number, *unit)))
+        .unwrap_or((text.as_str(), ""));
+    let number: u64 = number.parse().ok()?;
+    Some(format!("{number}{unit}"))
+}
+
+/// Converts a CPU quantity (`2`, `0.5`, `500m`) to shares of 1024 per core.
+fn cpu_shares(quantity: &serde_yaml::Value) -> Option<u64> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let shares = (cpu_cores(quantity)? * 1024.0) as u64;
+    (shares > 0).then_some(shares)
+}
+
+/// Converts a CPU quantity to a quota per default `cpu.max` period; a
+/// limit below the kernel's minimum quota has no equivalent.
+fn cpu_quota_us(quantity: &serde_yaml::Value) -> Option<u64> {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let quota = (cpu_cores(quantity)? * CPU_PERIOD_US as f64) as u64;
+    (quota >= ResourceLimits::MIN_CPU_QUOTA_US).then_some(quota)
+}
+
+fn cpu_cores(quantity: &serde_yaml::Value) -> Option<f64> {
+    let text = quantity_text(quantity)?;
+    match text.strip_suffix('m') {
+        Some(millis) => Some(millis.parse::<f64>().ok()? / 1000.0),
+        None => text.parse::<f64>().ok(),
+    }
+}
+
+fn write_env(out: &mut String, env: &[EnvVar]) {
+    let values: Vec<(&str, &str)> = env
+        .iter()
+        .filter_map(|var| Some((var.name.as_str(), var.value.as_deref()?)))
+        .collect();
+    if values.is_empty() {
+        return;
+    }
+    out.push_str("    env = {\n");
+    for (key, value) in values {
+        let _ = writeln!(out, "        {key} = \"{}\"", super::env::ctst_value(value));
+    }
+    out.push_str("    }\n");
+}
+
+fn write_list(out: &mut String, prop: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "    {prop} = [{}]", super::quoted_list(items));
+}
+
+/// Publishes each Service port on the component serving its target port
+/// and returns the `EXPOSE` mappings, keyed by host port.
+fn publish_services(
+    manifests: &Manifests,
+    components: &mut [Component<'_>],
+    skipped: &mut Vec<SkippedFeature>,
+) -> BTreeMap<u16, u16> {
+    let mut exposes = BTreeMap::new();
+    for service in &manifests.services {
+        let name = format!("Service/{}", service.metadata.name);
+        let Some(deployment) = manifests
+            .deployments
+            .iter()
+            .position(|deployment| selects(&service.spec.selector, deployment))
+        else {
+            skipped.push(SkippedFeature {
+                service: None,
+                feature: name,
+                reason: "its selector matches no Deployment in the manifest".into(),
+            });
+            continue;
+        };
+        for port in &service.spec.ports {
+            match publish_port(service, port, deployment, components) {
+                Ok(container) => {
+                    let _ = exposes.entry(host_port(service, port)).or_insert(container);
+                }
+                Err(reason) => skipped.push(SkippedFeature {
+                    service: None,
+                    feature: format!("{name} port {}", port.port),
+                    reason,
+                }),
+            }
+        }
+    }
+    exposes
+}
+
+fn selects(selector: &BTreeMap<String, String>, deployment: &Deployment) -> bool {
+    let labels = &deployment.spec.template.metadata.labels;
+    !selector.is_empty()
+        && selector
+            .iter()
+            .all(|(key, value)| labels.get(key) == Some(value))
+}
+
+/// Resolves a Service port to a container port of `deployment` and adds it
+/// to the serving component's ports.
+fn publish_port(
+    service: &Service,
+    port: &ServicePort,
+    deployment: usize,
+    components: &mut [Component<'_>],
+) -> Result<u16, String> {
+    let target = match &port.target_port {
+        None => port.port,
+        Some(TargetPort::Number(number)) => *number,
+        Some(TargetPort::Name(name)) => components
+            .iter()
+            .filter(|component| component.deployment == deployment)
+            .flat_map(|component| &component.container.ports)
+            .find(|p| p.name.as_deref() == Some(name))
+            .map(|p| p.container_port)
+            .ok_or_else(|| {
+                format!(
+                    "targetPort '{name}' names no container port of Service {}",
+                    service.metadata.name
+                )
+            })?,
+    };
+    let mut owned: Vec<&mut Component<'_>> = components
+        .iter_mut()
+        .filter(|component| component.deployment == deployment)
+        .collect();
+    let serving = owned
+        .iter()
+        .position(|component| component.ports.contains(&target))
+        .unwrap_or(0);
+    if let Some(component) = owned.get_mut(serving)
+        && !component.ports.contains(&target)
+    {
+        component.ports.push(target);
+    }
+    Ok(target)
+}
+
+/// A `NodePort` Service is reached on its node port, anything else on the
+/// Service port.
+fn host_port(service: &Service, port: &ServicePort) -> u16 {
+    match (service.spec.kind.as_deref(), port.node_port) {
+        (Some("NodePort"), Some(node_port)) => node_port,
+        _ => port.port,
+    }
+}
+
+/// Converts Kubernetes Deployment and Service manifests to `.ctst` format
+/// and reports what was translated.
+///
+/// # Errors
+///
+/// Returns an error if the YAML cannot be parsed, holds no Deployment, or
+/// the generated `.ctst` fails validation.
+pub(super) fn convert_manifests(yaml: &str) -> anyhow::Result<(String, ConversionSummary)> {
+    let manifests = Manifests::parse(yaml)?;
+    let mut components: Vec<Component<'_>> = manifests
+        .deployments
+        .iter()
+        .enumerate()
+        .flat_map(|(index, deployment)| Component::from_deployment(index, deployment))
+        .collect();
+    let mut skipped = manifests.skipped.clone();
+    let exposes = publish_services(&manifests, &mut components, &mut skipped);
+
+    let mut output = String::with_capacity(2048);
+    super::write_header(&mut output, "Kubernetes manifests", components.len());
+    for skipped in &skipped {
+        let _ = writeln!(
+            output,
+            "// unsupported: {} — {}",
+            skipped.feature, skipped.reason
+        );
+    }
+    if !skipped.is_empty() {
+        output.push('\n');
+    }
+    for component in &mut components {
+        component.write(&mut output);
+        output.push('\n');
+        skipped.append(&mut component.skipped);
+    }
+    for (host, container) in &exposes {
+        let _ = writeln!(output, "EXPOSE {host}:{container}");
+    }
+    let _ = containust_compose::parser::parse_ctst(&output)
+        .map_err(|error| anyhow::anyhow!("generated .ctst failed validation: {error}"))?;
+
+    let summary = ConversionSummary {
+        services: components.len(),
+        skipped,
+        ..ConversionSummary::default()
+    };
+    Ok((output, summary))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use containust_compose::parser::parse_ctst;
+
+    use super::*;
+
+    const DEPLOYMENT_AND_SERVICE: &str = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+spec:
+  replicas: 3
+  selector:
+    matchLabels:
+      app: web
+  template:
+    metadata:
+      labels:
+        app: web
+    spec:
+      containers:
+        - name: web
+          image: nginx:1.25
+          imagePullPolicy: IfNotPresent
+          command: ["nginx"]
+          args: ["-g", "daemon off;"]
+          workingDir: /srv
+          env:
+            - name: MODE
+              value: production
+            - name: TOKEN
+              valueFrom:
+                secretKeyRef:
+                  name: web-secrets
+                  key: token
+          ports:
+            - name: http
+              containerPort: 8080
+          resources:
+            requests:
+              memory: 128Mi
+              cpu: 250m
+            limits:
+              memory: 512Mi
+              cpu: "1"
+          livenessProbe:
+            httpGet:
+              path: /healthz
+              port: http
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: web
+spec:
+  type: NodePort
+  selector:
+    app: web
+  ports:
+    - port: 80
+      targetPort: http
+      nodePort: 30080
+"#;
+
+    #[test]
+    fn deployment_and_service_become_a_component() {
+        let (output, summary) =
+            convert_manifests(DEPLOYMENT_AND_SERVICE).expect("conversion should succeed");
+        let composition = parse_ctst(&output).expect("valid .ctst");
+        let web = &composition.components[0];
+        assert_eq!(web.name, "web");
+        assert_eq!(web.image.as_deref(), Some("tar:///opt/images/nginx.tar"));
+        assert_eq!(web.port, Some(8080));
+        assert_eq!(web.memory.as_deref(), Some("512MiB"));
+        assert_eq!(web.memory_reservation.as_deref(), Some("128MiB"));
+        assert_eq!(web.cpu_max_spec(), Ok(Some((100_000, None))));
+        assert_eq!(web.cpu.as_deref(), Some("256"));
+        assert_eq!(web.env.get("MODE").map(String::as_str), Some("production"));
+        assert!(!web.env.contains_key("TOKEN"));
+        assert_eq!(web.entrypoint, Some(vec!["nginx".to_string()]));
+        assert_eq!(web.command, ["-g", "daemon off;"]);
+        assert_eq!(web.workdir.as_deref(), Some("/srv"));
+        assert_eq!(composition.exposes.len(), 1);
+        assert_eq!(composition.exposes[0].host_port, 30080);
+        assert_eq!(composition.exposes[0].container_port, 8080);
+        assert_eq!(
+            composition.schema,
+            Some(containust_compose::parser::ast::SchemaVersion::LATEST)
+        );
+        assert_eq!(summary.services, 1);
+    }
+
+    #[test]
+    fn unsupported_fields_become_comments_and_summary_entries() {
+        let (output, summary) =
+            convert_manifests(DEPLOYMENT_AND_SERVICE).expect("conversion should succeed");
+        assert!(output.contains("// unsupported: replicas: 3"), "{output}");
+        assert!(output.contains("// unsupported: livenessProbe"), "{output}");
+        assert!(
+            output.contains("// unsupported: env TOKEN valueFrom"),
+            "{output}"
+        );
+        assert!(!output.contains("imagePullPolicy"), "{output}");
+        let features: Vec<&str> = summary
+            .skipped
+            .iter()
+            .map(|skipped| skipped.feature.as_str())
+            .collect();
+        assert_eq!(
+            features,
+            ["replicas: 3", "livenessProbe", "env TOKEN valueFrom"]
+        );
+    }
+
+    #[test]
+    fn cluster_ip_service_publishes_service_port_on_numeric_target() {
+        let yaml = r"
+kind: Deployment
+metadata: {name: api}
+spec:
+  template:
+    metadata: {labels: {app: api}}
+    spec:
+      containers:
+        - {name: api, image: ghcr.io/acme/api:2}
+---
+kind: Service
+metadata: {name: api}
+spec:
+  selector: {app: api}
+  ports:
+    - {port: 80, targetPort: 3000}
+---
+kind: ConfigMap
+metadata: {name: api-config}
+";
+        let (output, summary) = convert_manifests(yaml).expect("conversion should succeed");
+        let composition = parse_ctst(&output).expect("valid .ctst");
+        assert_eq!(composition.components[0].port, Some(3000));
+        assert_eq!(composition.exposes[0].host_port, 80);
+        assert_eq!(composition.exposes[0].container_port, 3000);
+        assert_eq!(summary.skipped[0].feature, "ConfigMap/api-config");
+    }
+
+    #[test]
+    fn multi_container_pods_name_components_per_container() {
+        let yaml = r"
+kind: Deployment
+metadata: {name: app}
+spec:
+  template:
+    spec:
+      containers:
+        - {name: main, image: app:1}
+        - {name: proxy, image: envoy:1}
+";
+        let (output, summary) = convert_manifests(yaml).expect("conversion should succeed");
+        assert!(output.contains("COMPONENT app-main {"), "{output}");
+        assert!(output.contains("COMPONENT app-proxy {"), "{output}");
+        assert_eq!(summary.skipped[0].feature, "containers");
+    }
+
+    #[test]
+    fn quantities_convert_to_ctst_units() {
+        let quantity = |text: &str| serde_yaml::Value::String(text.into());
+        assert_eq!(memory_size(&quantity("256Mi")).as_deref(), Some("256MiB"));
+        assert_eq!(memory_size(&quantity("1G")).as_deref(), Some("1GB"));
+        assert_eq!(
+            memory_size(&quantity("1048576")).as_deref(),
+            Some("1048576")
+        );
+        assert_eq!(memory_size(&quantity("1.5Gi")), None);
+        assert_eq!(cpu_shares(&quantity("500m")), Some(512));
+        assert_eq!(cpu_shares(&quantity("2")), Some(2048));
+        assert_eq!(cpu_shares(&serde_yaml::Value::from(0.5)), Some(512));
+        assert_eq!(cpu_quota_us(&quantity("500m")), Some(50_000));
+        assert_eq!(cpu_quota_us(&quantity("2")), Some(200_000));
+        assert_eq!(cpu_quota_us(&quantity("10m")), Some(1_000));
+        assert_eq!(cpu_quota_us(&quantity("5m")), None);
+    }
+
+    #[test]
+    fn manifest_without_deployment_is_rejected() {
+        let error = convert_manifests("kind: Service\nspec: {}\n").expect_err("no deployment");
+        assert!(error.to_string().contains("no Deployment"), "{error}");
+    }
+}
@@ -0,0 +1,226 @@
+//! What a conversion translated and which compose features it skipped.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as FmtWrite;
+
+use serde::Serialize;
+
+use super::{ComposeFile, DependsOnFormat, Service};
+
+/// Top-level compose keys that need no `.ctst` counterpart.
+const IGNORED_TOP_LEVEL: [&str; 2] = ["version", "name"];
+
+/// Counts of converted compose items plus the features left out.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ConversionSummary {
+    /// Services converted to `COMPONENT` blocks.
+    pub services: usize,
+    /// Volume mounts converted to `volume` / `volumes`.
+    pub volumes: usize,
+    /// Distinct networks assigned with `network`.
+    pub networks: usize,
+    /// Healthchecks converted to `healthcheck` blocks.
+    pub healthchecks: usize,
+    /// `depends_on` entries converted to `CONNECT` statements.
+    pub connections: usize,
+    /// Compose features with no `.ctst` equivalent.
+    pub skipped: Vec<SkippedFeature>,
+}
+
+/// A compose feature the converter left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedFeature {
+    /// Service declaring the feature; `None` for top-level keys.
+    pub service: Option<String>,
+    /// Compose key, e.g. `env_file`.
+    pub feature: String,
+    /// Why it was skipped and what to do instead.
+    pub reason: String,
+}
+
+impl ConversionSummary {
+    /// Renders the summary as human-readable lines.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Converted: {} service(s), {} volume(s), {} network(s), {} healthcheck(s), \
+             {} connection(s)\n",
+            self.services, self.volumes, self.networks, self.healthchecks, self.connections
+        );
+        if !self.skipped.is_empty() {
+            let _ = writeln!(
+                text,
+                "Skipped {} unsupported feature(s):",
+                self.skipped.len()
+            );
+        }
+        for skipped in &self.skipped {
+            let scope = skipped.service.as_deref().unwrap_or("(top level)");
+            let _ = writeln!(text, "  {scope}: {} — {}", skipped.feature, skipped.reason);
+        }
+        text
+    }
+}
+
+/// Tallies what converting `compose` produces and what it leaves out.
+pub(super) fn summarize(compose: &ComposeFile) -> ConversionSummary {
+    let mut summary = ConversionSummary {
+        services: compose.services.len(),
+        ..ConversionSummary::default()
+    };
+    let mut networks = BTreeSet::new();
+    for key in compose.extra.keys() {
+        if !IGNORED_TOP_LEVEL.contains(&key.as_str()) {
+            summary.skipped.push(SkippedFeature {
+                service: None,
+                feature: key.clone(),
+                reason: top_level_reason(key).into(),
+            });
+        }
+    }
+    for (name, svc) in &compose.services {
+        summary.volumes += svc.volumes.len();
+        summary.healthchecks += usize::from(svc.healthcheck.is_some());
+        summary.connections += dependency_count(&svc.depends_on);
+        if let [network] = svc.networks.as_slice() {
+            let _ = networks.insert(network.as_str());
+        }
+        summary.skipped.extend(skipped_in_service(name, svc));
+    }
+    summary.networks = networks.len();
+    summary
+}
+
+fn dependency_count(depends_on: &DependsOnFormat) -> usize {
+    match depends_on {
+        DependsOnFormat::List(list) => list.len(),
+        DependsOnFormat::Map(map) => map.len(),
+        DependsOnFormat::None => 0,
+    }
+}
+
+fn skipped_in_service(name: &str, svc: &Service) -> Vec<SkippedFeature> {
+    let skip = |feature: &str, reason: &str| SkippedFeature {
+        service: Some(name.to_string()),
+        feature: feature.to_string(),
+        reason: reason.to_string(),
+    };
+    let mut skipped: Vec<SkippedFeature> = svc
+        .extra
+        .keys()
+        .map(|key| skip(key, service_key_reason(key)))
+        .collect();
+    if svc.networks.len() > 1 {
+        skipped.push(skip(
+            "networks",
+            "a component joins a single network; pick one and set `network`",
+        ));
+    }
+    if let Some(restart) = &svc.restart
+        && !matches!(
+            restart.as_str(),
+            "no" | "never" | "on-failure" | "always" | "unless-stopped"
+        )
+    {
+        skipped.push(skip("restart", "unrecognised restart policy"));
+    }
+    skipped
+}
+
+fn top_level_reason(key: &str) -> &'static str {
+    match key {
+        "volumes" => "named volumes are created on first use; no declaration is needed",
+        "networks" => "networks are created on first use; no declaration is needed",
+        "secrets" | "configs" => "reference values with ${secret.NAME} in env instead",
+        _ => "no .ctst equivalent",
+    }
+}
+
+fn service_key_reason(key: &str) -> &'static str {
+    match key {
+        "container_name" => "the component name is used as the container name",
+        "expose" => "connected components reach each other through CONNECT",
+        "labels" => "labels have no .ctst equivalent",
+        "privileged" | "cap_add" => "list the capabilities to keep in `capabilities`",
+        _ => "no .ctst equivalent",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::super::convert_string_with_summary;
+
+    #[test]
+    fn summary_counts_converted_items_and_lists_skipped_features() {
+        let yaml = r#"
+version: "3.9"
+services:
+  web:
+    image: nginx
+    container_name: web-1
+    expose: ["80"]
+    volumes: ["./html:/html", "logs:/var/log"]
+    networks: [front]
+    depends_on: [api]
+    healthcheck:
+      test: ["CMD", "true"]
+  api:
+    image: api
+    networks: [front, back]
+    restart: sometimes
+volumes:
+  logs: {}
+"#;
+        let (_, summary) = convert_string_with_summary(yaml).expect("convert");
+        assert_eq!(summary.services, 2);
+        assert_eq!(summary.volumes, 2);
+        assert_eq!(summary.networks, 1);
+        assert_eq!(summary.healthchecks, 1);
+        assert_eq!(summary.connections, 1);
+
+        let skipped: Vec<(Option<&str>, &str)> = summary
+            .skipped
+            .iter()
+            .map(|s| (s.service.as_deref(), s.feature.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                (None, "volumes"),
+                (Some("api"), "networks"),
+                (Some("api"), "restart"),
+                (Some("web"), "container_name"),
+                (Some("web"), "expose"),
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_text_lists_reasons() {
+        let (_, summary) = convert_string_with_summary(
+            "services:\n  app:\n    image: app\n    labels: {tier: web}\n",
+        )
+        .expect("convert");
+        let text = summary.to_text();
+        assert!(
+            text.starts_with("Converted: 1 service(s), 0 volume(s)"),
+            "{text}"
+        );
+        assert!(text.contains("Skipped 1 unsupported feature(s):"), "{text}");
+        assert!(
+            text.contains("app: labels — labels have no .ctst equivalent"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn summary_serializes_as_json() {
+        let (_, summary) =
+            convert_string_with_summary("services:\n  app:\n    image: app\n").expect("convert");
+        let json = serde_json::to_value(&summary).expect("json");
+        assert_eq!(json["services"], 1);
+        assert_eq!(json["skipped"], serde_json::json!([]));
+    }
+}
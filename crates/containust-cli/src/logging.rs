@@ -0,0 +1,126 @@
+//! Tracing subscriber setup.
+//!
+//! An explicit `CONTAINUST_LOG` (or `RUST_LOG`) filter always wins;
+//! otherwise the level follows the global `-v` / `--quiet` flags. Logs go
+//! to stderr so they never mix with command output such as `--json`, as
+//! human-readable text or, with `--log-format json`, one JSON object per
+//! line.
+
+use clap::ValueEnum;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::LevelFilter;
+
+/// Environment variables holding filter directives, in priority order.
+const FILTER_ENV_VARS: [&str; 2] = ["CONTAINUST_LOG", "RUST_LOG"];
+
+/// Environment variable selecting the log format when no flag is given.
+const FORMAT_ENV_VAR: &str = "CONTAINUST_LOG_FORMAT";
+
+/// Log line format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines (the default).
+    #[default]
+    #[value(alias = "pretty")]
+    Text,
+    /// JSON lines for log aggregation.
+    Json,
+}
+
+/// Picks the format: the `--log-format` flag, else `CONTAINUST_LOG_FORMAT`,
+/// else text. An unrecognised environment value falls back to text.
+#[must_use]
+pub fn select_format(flag: Option<LogFormat>, env_value: Option<&str>) -> LogFormat {
+    flag.or_else(|| {
+        env_value
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .and_then(|value| {
+                LogFormat::from_str(value, true)
+                    .map_err(|_| eprintln!("warning: ignoring invalid {FORMAT_ENV_VAR}={value}"))
+                    .ok()
+            })
+    })
+    .unwrap_or_default()
+}
+
+/// Maps `--quiet` and the `-v` count to a maximum level.
+///
+/// Quiet logs errors only; the default is warn, then info, debug, and
+/// trace for `-v`, `-vv`, and `-vvv` or more.
+#[must_use]
+pub const fn verbosity_level(verbose: u8, quiet: bool) -> LevelFilter {
+    if quiet {
+        return LevelFilter::ERROR;
+    }
+    match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Returns the first non-empty, valid filter from the environment.
+fn explicit_filter() -> Option<EnvFilter> {
+    FILTER_ENV_VARS.iter().find_map(|var| {
+        let directives = std::env::var(var).ok().filter(|v| !v.trim().is_empty())?;
+        EnvFilter::try_new(&directives)
+            .map_err(|error| eprintln!("warning: ignoring invalid {var}: {error}"))
+            .ok()
+    })
+}
+
+/// Installs the global tracing subscriber.
+pub fn init(verbose: u8, quiet: bool, format: Option<LogFormat>) {
+    let filter = explicit_filter().unwrap_or_else(|| {
+        EnvFilter::default().add_directive(verbosity_level(verbose, quiet).into())
+    });
+    let env_format = std::env::var(FORMAT_ENV_VAR).ok();
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match select_format(format, env_format.as_deref()) {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity_level_maps_flag_counts() {
+        assert_eq!(verbosity_level(0, false), LevelFilter::WARN);
+        assert_eq!(verbosity_level(1, false), LevelFilter::INFO);
+        assert_eq!(verbosity_level(2, false), LevelFilter::DEBUG);
+        assert_eq!(verbosity_level(3, false), LevelFilter::TRACE);
+        assert_eq!(verbosity_level(9, false), LevelFilter::TRACE);
+    }
+
+    #[test]
+    fn quiet_overrides_verbosity() {
+        assert_eq!(verbosity_level(0, true), LevelFilter::ERROR);
+        assert_eq!(verbosity_level(2, true), LevelFilter::ERROR);
+    }
+
+    #[test]
+    fn select_format_prefers_flag_then_env() {
+        assert_eq!(select_format(None, None), LogFormat::Text);
+        assert_eq!(select_format(None, Some("json")), LogFormat::Json);
+        assert_eq!(select_format(None, Some(" JSON ")), LogFormat::Json);
+        assert_eq!(select_format(None, Some("pretty")), LogFormat::Text);
+        assert_eq!(
+            select_format(Some(LogFormat::Text), Some("json")),
+            LogFormat::Text
+        );
+        assert_eq!(select_format(Some(LogFormat::Json), None), LogFormat::Json);
+    }
+
+    #[test]
+    fn select_format_ignores_unknown_env_values() {
+        assert_eq!(select_format(None, Some("xml")), LogFormat::Text);
+        assert_eq!(select_format(None, Some("")), LogFormat::Text);
+    }
+}
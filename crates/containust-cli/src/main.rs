@@ -14,6 +14,7 @@
 mod build_info;
 mod commands;
 mod converter;
+mod logging;
 mod output;
 
 use clap::Parser;
@@ -22,11 +23,8 @@ use containust_common::codes;
 use crate::commands::Cli;
 
 fn main() {
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
-
     let cli = Cli::parse();
+    logging::init(cli.verbose, cli.quiet, cli.log_format);
     if let Err(error) = commands::execute(cli) {
         let class = codes::classify_message(&format!("{error:#}"));
         eprintln!("error[{}]: {error}", class.code);
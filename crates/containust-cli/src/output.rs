@@ -3,6 +3,218 @@
 //! Provides consistent table formatting, colored status indicators,
 //! and human-readable byte/duration formatting.
 
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use containust_common::types::HealthState;
+use containust_runtime::backend::ContainerInfo;
+use containust_runtime::metrics::{MetricAvailability, collect_metrics};
+
+/// Output format of container listings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable table.
+    #[default]
+    Table,
+    /// The container list as JSON.
+    Json,
+    /// Table with the image and creation time added.
+    Wide,
+}
+
+/// One column of the `ctst ps` table.
+struct Column {
+    header: &'static str,
+    width: usize,
+    right_aligned: bool,
+}
+
+impl Column {
+    const fn left(header: &'static str, width: usize) -> Self {
+        Self {
+            header,
+            width,
+            right_aligned: false,
+        }
+    }
+
+    const fn right(header: &'static str, width: usize) -> Self {
+        Self {
+            header,
+            width,
+            right_aligned: true,
+        }
+    }
+}
+
+/// `ctst ps` columns; the table shows the leading [`TABLE_COLUMNS`], the
+/// wide view all of them.
+const PS_COLUMNS: [Column; 9] = [
+    Column::left("CONTAINER ID", 36),
+    Column::left("NAME", 14),
+    Column::left("STATUS", 24),
+    Column::left("PID", 8),
+    Column::right("CPU(ns)", 10),
+    Column::right("MEM(B)", 10),
+    Column::right("OOM", 4),
+    Column::left("IMAGE", 20),
+    Column::left("CREATED", 25),
+];
+
+/// Number of [`PS_COLUMNS`] in the default table.
+const TABLE_COLUMNS: usize = 7;
+
+fn ps_columns(format: OutputFormat) -> &'static [Column] {
+    match format {
+        OutputFormat::Wide => &PS_COLUMNS,
+        OutputFormat::Table | OutputFormat::Json => &PS_COLUMNS[..TABLE_COLUMNS],
+    }
+}
+
+/// Renders a `ctst ps` listing: a table (with live metrics read from the
+/// cgroups of project `project_id`) or the containers serialized as JSON.
+///
+/// # Errors
+///
+/// Returns an error if JSON serialization fails.
+pub fn render_ps(
+    infos: &[ContainerInfo],
+    format: OutputFormat,
+    project_id: &str,
+) -> serde_json::Result<String> {
+    if format == OutputFormat::Json {
+        return serde_json::to_string_pretty(infos);
+    }
+    if infos.is_empty() {
+        return Ok("No containers found.".into());
+    }
+    let columns = ps_columns(format);
+    let now = Utc::now();
+    let mut lines = vec![render_row(
+        columns,
+        columns.iter().map(|column| column.header.to_string()),
+    )];
+    lines.extend(
+        infos
+            .iter()
+            .map(|info| render_row(columns, ps_cells(info, project_id, now))),
+    );
+    Ok(lines.join("\n"))
+}
+
+/// Every [`PS_COLUMNS`] cell of one container.
+fn ps_cells(info: &ContainerInfo, project_id: &str, now: DateTime<Utc>) -> [String; 9] {
+    let (cpu, mem, oom) = format_metrics(project_id, &info.id);
+    [
+        info.id.to_string(),
+        info.name.clone(),
+        status_line(info, now),
+        info.pid.map_or_else(|| "-".to_string(), |p| p.to_string()),
+        cpu,
+        mem,
+        oom,
+        info.image.clone(),
+        info.created_at.clone(),
+    ]
+}
+
+/// Pads `cells` to their column widths, dropping cells past `columns`.
+fn render_row(columns: &[Column], cells: impl IntoIterator<Item = String>) -> String {
+    columns
+        .iter()
+        .zip(cells)
+        .map(|(column, cell)| {
+            let width = column.width;
+            if column.right_aligned {
+                format!("{cell:>width$}")
+            } else {
+                format!("{cell:<width$}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end()
+        .to_string()
+}
+
+/// Formats CPU time, memory usage, and OOM kill count, `-` when unknown.
+fn format_metrics(
+    project_id: &str,
+    id: &containust_common::types::ContainerId,
+) -> (String, String, String) {
+    match collect_metrics(project_id, id) {
+        Ok(snap) => {
+            let cpu = match snap.cpu {
+                MetricAvailability::Available => snap.cpu_usage_ns.to_string(),
+                MetricAvailability::Unavailable | MetricAvailability::Missing => "-".into(),
+            };
+            let (mem, oom) = match snap.memory {
+                MetricAvailability::Available => (
+                    snap.memory_usage_bytes.to_string(),
+                    snap.oom_kills.to_string(),
+                ),
+                MetricAvailability::Unavailable | MetricAvailability::Missing => {
+                    ("-".into(), "-".into())
+                }
+            };
+            (cpu, mem, oom)
+        }
+        Err(_) => ("-".into(), "-".into(), "-".into()),
+    }
+}
+
+/// Combines state, run times, and health into e.g. `Up 2m (healthy)` or
+/// `Exited (1) 5m ago`.
+fn status_line(info: &ContainerInfo, now: DateTime<Utc>) -> String {
+    let elapsed_since = |timestamp: Option<&str>| {
+        timestamp
+            .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+            .map(|at| format_duration(now.signed_duration_since(at)))
+    };
+    match info.state.as_str() {
+        "running" => {
+            let uptime = elapsed_since(info.started_at.as_deref())
+                .or_else(|| elapsed_since(Some(&info.created_at)));
+            let status = uptime.map_or_else(|| "Up".to_string(), |up| format!("Up {up}"));
+            match info.health {
+                Some(HealthState::Starting) => format!("{status} (health: starting)"),
+                Some(health) => format!("{status} ({health})"),
+                None => status,
+            }
+        }
+        "stopped" | "failed" => {
+            let Some(ago) = elapsed_since(info.finished_at.as_deref()) else {
+                return if info.state == "failed" {
+                    "Failed"
+                } else {
+                    "Exited"
+                }
+                .into();
+            };
+            info.exit_code.map_or_else(
+                || format!("Exited {ago} ago"),
+                |code| format!("Exited ({code}) {ago} ago"),
+            )
+        }
+        "created" => "Created".into(),
+        other => other.into(),
+    }
+}
+
+/// Formats a duration compactly with at most two units: `45s`, `2m`,
+/// `3h15m`, `2d4h`. Negative durations (clock skew) read as `0s`.
+pub fn format_duration(elapsed: chrono::TimeDelta) -> String {
+    let secs = elapsed.num_seconds().max(0);
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{secs}s"),
+        (0, 0, _) => format!("{minutes}m"),
+        (0, _, 0) => format!("{hours}h"),
+        (0, _, _) => format!("{hours}h{minutes}m"),
+        (_, 0, _) => format!("{days}d"),
+        _ => format!("{days}d{hours}h"),
+    }
+}
+
 /// Formats a byte count into a human-readable string (e.g., "128 MiB").
 #[allow(clippy::cast_precision_loss)]
 #[must_use]
@@ -24,8 +236,166 @@ pub fn format_bytes(bytes: u64) -> String {
 
 #[cfg(test)]
 mod tests {
+    #![allow(clippy::expect_used)]
+
+    use containust_common::types::ContainerId;
+
     use super::*;
 
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T12:00:00Z")
+            .expect("timestamp")
+            .with_timezone(&Utc)
+    }
+
+    fn info(state: &str) -> ContainerInfo {
+        ContainerInfo {
+            id: ContainerId::new("c1"),
+            name: "web".into(),
+            state: state.into(),
+            pid: None,
+            image: "file:///web".into(),
+            created_at: "2026-01-01T08:00:00Z".into(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
+            health: None,
+        }
+    }
+
+    fn headers(format: OutputFormat) -> Vec<&'static str> {
+        ps_columns(format)
+            .iter()
+            .map(|column| column.header)
+            .collect()
+    }
+
+    #[test]
+    fn render_ps_json_serializes_container_list() {
+        let json = render_ps(&[info("stopped")], OutputFormat::Json, "test").expect("json");
+        let value: serde_json::Value = serde_json::from_str(&json).expect("parse");
+        assert_eq!(value[0]["id"], "c1");
+        assert_eq!(value[0]["name"], "web");
+        assert_eq!(value[0]["state"], "stopped");
+        assert_eq!(value[0]["image"], "file:///web");
+        assert!(value[0]["pid"].is_null());
+        assert_eq!(
+            render_ps(&[], OutputFormat::Json, "test").expect("empty"),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn render_ps_table_omits_image_and_created() {
+        assert_eq!(
+            headers(OutputFormat::Table),
+            [
+                "CONTAINER ID",
+                "NAME",
+                "STATUS",
+                "PID",
+                "CPU(ns)",
+                "MEM(B)",
+                "OOM"
+            ]
+        );
+        let rendered = render_ps(&[info("created")], OutputFormat::Table, "test").expect("table");
+        assert!(rendered.starts_with("CONTAINER ID"), "{rendered}");
+        assert!(!rendered.contains("IMAGE"), "{rendered}");
+        assert!(!rendered.contains("file:///web"), "{rendered}");
+    }
+
+    #[test]
+    fn render_ps_wide_adds_image_and_created() {
+        assert_eq!(
+            headers(OutputFormat::Wide),
+            [
+                "CONTAINER ID",
+                "NAME",
+                "STATUS",
+                "PID",
+                "CPU(ns)",
+                "MEM(B)",
+                "OOM",
+                "IMAGE",
+                "CREATED"
+            ]
+        );
+        let rendered = render_ps(&[info("created")], OutputFormat::Wide, "test").expect("wide");
+        assert!(
+            rendered
+                .lines()
+                .next()
+                .expect("header")
+                .ends_with("CREATED")
+        );
+        let row = rendered.lines().nth(1).expect("row");
+        assert!(row.contains("file:///web"), "{row}");
+        assert!(row.ends_with("2026-01-01T08:00:00Z"), "{row}");
+    }
+
+    #[test]
+    fn render_ps_table_reports_no_containers() {
+        assert_eq!(
+            render_ps(&[], OutputFormat::Wide, "test").expect("empty"),
+            "No containers found."
+        );
+    }
+
+    #[test]
+    fn running_status_shows_uptime_and_health() {
+        let healthy = ContainerInfo {
+            started_at: Some("2026-01-01T11:58:00Z".into()),
+            health: Some(HealthState::Healthy),
+            ..info("running")
+        };
+        assert_eq!(status_line(&healthy, now()), "Up 2m (healthy)");
+
+        let unhealthy = ContainerInfo {
+            started_at: Some("2026-01-01T08:45:00Z".into()),
+            health: Some(HealthState::Unhealthy),
+            ..info("running")
+        };
+        assert_eq!(status_line(&unhealthy, now()), "Up 3h15m (unhealthy)");
+    }
+
+    #[test]
+    fn running_status_falls_back_to_created_at() {
+        assert_eq!(status_line(&info("running"), now()), "Up 4h");
+    }
+
+    #[test]
+    fn exited_status_shows_code_when_known() {
+        let exited = ContainerInfo {
+            finished_at: Some("2026-01-01T11:55:00Z".into()),
+            exit_code: Some(1),
+            ..info("stopped")
+        };
+        assert_eq!(status_line(&exited, now()), "Exited (1) 5m ago");
+
+        let unknown = ContainerInfo {
+            exit_code: None,
+            ..exited
+        };
+        assert_eq!(status_line(&unknown, now()), "Exited 5m ago");
+        assert_eq!(status_line(&info("failed"), now()), "Failed");
+    }
+
+    #[test]
+    fn created_status_has_no_uptime() {
+        assert_eq!(status_line(&info("created"), now()), "Created");
+    }
+
+    #[test]
+    fn duration_uses_two_largest_units() {
+        let secs = chrono::TimeDelta::seconds;
+        assert_eq!(format_duration(secs(45)), "45s");
+        assert_eq!(format_duration(secs(120)), "2m");
+        assert_eq!(format_duration(secs(3 * 3_600)), "3h");
+        assert_eq!(format_duration(secs(2 * 86_400 + 4 * 3_600 + 59)), "2d4h");
+        assert_eq!(format_duration(secs(-5)), "0s");
+    }
+
     #[test]
     fn format_bytes_displays_bytes() {
         assert_eq!(format_bytes(512), "512 B");
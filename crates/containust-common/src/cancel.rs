@@ -0,0 +1,113 @@
+//! Cooperative cancellation of long-running operations.
+//!
+//! A [`CancellationToken`] is shared between the caller (typically a
+//! Ctrl+C handler) and the operation, which polls it at safe points —
+//! between components, chunks, archive entries, or readiness polls —
+//! and returns [`ContainustError::Cancelled`] after cleaning up.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::error::{ContainustError, Result};
+
+/// Shared flag requesting that an operation stop early.
+///
+/// Clones observe the same flag. A default token is never cancelled
+/// unless [`CancellationToken::cancel`] is called on it or a clone.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation; every clone observes it.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether cancellation was requested.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Returns [`ContainustError::Cancelled`] for `operation` once
+    /// cancellation was requested.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is cancelled.
+    pub fn check(&self, operation: &str) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(ContainustError::Cancelled {
+                operation: operation.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Sleeps for `duration`, waking every [`SLEEP_POLL`] to return
+    /// [`ContainustError::Cancelled`] for `operation` as soon as
+    /// cancellation is requested.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is or becomes cancelled.
+    pub fn sleep(&self, duration: Duration, operation: &str) -> Result<()> {
+        let deadline = Instant::now() + duration;
+        loop {
+            self.check(operation)?;
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(());
+            }
+            std::thread::sleep(remaining.min(SLEEP_POLL));
+        }
+    }
+}
+
+/// How often [`CancellationToken::sleep`] checks for cancellation.
+pub const SLEEP_POLL: Duration = Duration::from_millis(100);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_the_cancellation() {
+        let token = CancellationToken::new();
+        let handler = token.clone();
+        assert!(token.check("deploy").is_ok());
+
+        handler.cancel();
+        assert!(token.is_cancelled());
+        let error = token.check("deploy").unwrap_err();
+        assert!(
+            matches!(&error, ContainustError::Cancelled { operation } if operation == "deploy")
+        );
+        assert_eq!(error.to_string(), "deploy cancelled");
+        assert!(!error.is_retryable());
+    }
+
+    #[test]
+    fn sleep_returns_early_once_cancelled() {
+        let token = CancellationToken::new();
+        assert!(token.sleep(Duration::ZERO, "wait").is_ok());
+
+        let handler = token.clone();
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            handler.cancel();
+        });
+        let started = Instant::now();
+        let error = token.sleep(Duration::from_secs(30), "wait").unwrap_err();
+        canceller.join().unwrap();
+        assert!(matches!(error, ContainustError::Cancelled { .. }));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
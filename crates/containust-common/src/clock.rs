@@ -0,0 +1,159 @@
+//! Wall-clock access that tests can replace.
+//!
+//! Time-dependent code takes a [`Clock`] instead of calling
+//! `chrono::Utc::now()` or `std::thread::sleep` directly. Production code
+//! uses [`SystemClock`]; tests drive a [`MockClock`] forward by hand, so
+//! probe intervals, backoff, and uptimes are checked without real sleeping.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+/// Source of the current time and of blocking waits.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Returns the current UTC time.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Blocks for `duration`.
+    fn sleep(&self, duration: Duration);
+}
+
+/// A clock shared between an owner and the helpers it calls.
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The host's real clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// Returns the system clock as a [`SharedClock`].
+    #[must_use]
+    pub fn shared() -> SharedClock {
+        Arc::new(Self)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// A manually driven clock for tests.
+///
+/// `sleep` returns immediately, advancing the time and recording the
+/// requested duration. Clones share the same time, so a test can keep a
+/// handle while the code under test owns another.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    inner: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug)]
+struct MockState {
+    now: DateTime<Utc>,
+    sleeps: Vec<Duration>,
+}
+
+impl MockClock {
+    /// Creates a clock stopped at `start`.
+    #[must_use]
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(MockState {
+                now: start,
+                sleeps: Vec::new(),
+            })),
+        }
+    }
+
+    /// Creates a clock stopped at the Unix epoch.
+    #[must_use]
+    pub fn at_epoch() -> Self {
+        Self::new(DateTime::UNIX_EPOCH)
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.lock();
+        state.now += to_chrono(duration);
+    }
+
+    /// Sets the clock to `now`.
+    pub fn set(&self, now: DateTime<Utc>) {
+        self.lock().now = now;
+    }
+
+    /// Durations passed to [`Clock::sleep`], in call order.
+    #[must_use]
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.lock().sleeps.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::at_epoch()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.lock().now
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let mut state = self.lock();
+        state.now += to_chrono(duration);
+        state.sleeps.push(duration);
+    }
+}
+
+/// Converts a std duration, saturating at chrono's maximum.
+fn to_chrono(duration: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_clock_advances_without_sleeping() {
+        let clock = MockClock::at_epoch();
+        let handle = clock.clone();
+        clock.sleep(Duration::from_secs(30));
+        handle.advance(Duration::from_millis(500));
+        assert_eq!(
+            handle.now(),
+            DateTime::UNIX_EPOCH + chrono::Duration::milliseconds(30_500)
+        );
+        assert_eq!(clock.sleeps(), vec![Duration::from_secs(30)]);
+    }
+
+    #[test]
+    fn mock_clock_can_be_set() {
+        let clock = MockClock::default();
+        let later = DateTime::from_timestamp(1_700_000_000, 0).expect("timestamp");
+        clock.set(later);
+        assert_eq!(clock.now(), later);
+        assert_eq!(clock.sleeps(), Vec::<Duration>::new());
+    }
+
+    #[test]
+    fn system_clock_tracks_utc_now() {
+        let before = Utc::now();
+        let now = SystemClock::shared().now();
+        assert!(now >= before && now <= Utc::now());
+    }
+}
@@ -39,10 +39,23 @@ pub fn classify(error: &ContainustError) -> ErrorClass {
             1,
             "Repair or remove the corrupt state/catalog JSON and retry",
         ),
+        ContainustError::UnsupportedKernelFeature { .. } => class(
+            "R013",
+            1,
+            "Enable the feature as the error's hint describes, or run as root",
+        ),
         ContainustError::Network { message, .. } => classify_network(message),
+        ContainustError::Cancelled { .. } => CANCELLED,
     }
 }
 
+/// Cancelled operations exit like a `SIGINT`-terminated process.
+const CANCELLED: ErrorClass = class(
+    "R014",
+    130,
+    "The operation was interrupted; partial work was cleaned up, rerun it",
+);
+
 const fn class(code: &'static str, exit_code: i32, remediation: &'static str) -> ErrorClass {
     ErrorClass {
         code,
@@ -79,6 +92,9 @@ fn classify_network(message: &str) -> ErrorClass {
 #[must_use]
 pub fn classify_message(message: &str) -> ErrorClass {
     let lower = message.to_ascii_lowercase();
+    if lower.contains(" cancelled") {
+        return CANCELLED;
+    }
     if lower.contains("offline") {
         return ErrorClass {
             code: "I004",
@@ -115,11 +131,7 @@ pub fn classify_message(message: &str) -> ErrorClass {
         };
     }
     if lower.contains("permission denied") {
-        return ErrorClass {
-            code: "R008",
-            exit_code: 1,
-            remediation: "Run with sufficient privileges or adjust policy",
-        };
+        return class("R008", 1, "Run with sufficient privileges or adjust policy");
     }
     ErrorClass {
         code: "R000",
@@ -167,6 +179,7 @@ mod tests {
         let err = ContainustError::Network {
             url: "https://example.test".into(),
             message: "offline mode blocks remote fetch".into(),
+            retryable: false,
         };
         let class = classify(&err);
         assert_eq!(class.code, "I004");
@@ -179,4 +192,14 @@ mod tests {
         assert_eq!(class.code, "E001");
         assert_eq!(class.exit_code, 2);
     }
+
+    #[test]
+    fn classify_cancelled_exits_like_sigint() {
+        let err = ContainustError::Cancelled {
+            operation: "deploy".into(),
+        };
+        let class = classify(&err);
+        assert_eq!((class.code, class.exit_code), ("R014", 130));
+        assert_eq!(classify_message(&err.to_string()), class);
+    }
 }
@@ -1,9 +1,21 @@
 //! Global configuration model for the Containust runtime.
+//!
+//! User settings live in a TOML file at `$CONTAINUST_CONFIG`, falling back
+//! to `config.toml` in the data directory (`~/.containust/config.toml`).
+//! A missing file means every setting takes its default.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{ContainustError, Result};
+
+/// Environment variable overriding the config file location.
+pub const CONFIG_ENV: &str = "CONTAINUST_CONFIG";
+
+/// File name of the config file inside the data directory.
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
 /// Root configuration for the Containust runtime.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContainustConfig {
@@ -30,6 +42,89 @@ impl Default for ContainustConfig {
     }
 }
 
+/// Contents of the user config file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// `[defaults]` table.
+    pub defaults: DefaultsConfig,
+}
+
+/// `[defaults]` — values applied to components that leave them unset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DefaultsConfig {
+    /// Default memory limit (e.g. `"512MiB"`).
+    pub memory: Option<String>,
+    /// Default CPU limit (weight or decimal cores, e.g. `"1.0"`).
+    pub cpu: Option<String>,
+    /// Default maximum number of processes.
+    pub pids: Option<u64>,
+    /// Whether a limit the host refuses to apply aborts the start
+    /// (`true`, the default) or is skipped with a warning.
+    pub enforce_limits: Option<bool>,
+}
+
+impl DefaultsConfig {
+    /// Returns whether limits that cannot be applied fail the start.
+    #[must_use]
+    pub fn enforces_limits(&self) -> bool {
+        self.enforce_limits.unwrap_or(true)
+    }
+}
+
+/// Returns the config file path: `$CONTAINUST_CONFIG` or
+/// `<data_dir>/config.toml`.
+#[must_use]
+pub fn config_path() -> PathBuf {
+    std::env::var_os(CONFIG_ENV).map_or_else(
+        || crate::constants::data_dir().join(CONFIG_FILE_NAME),
+        PathBuf::from,
+    )
+}
+
+/// Loads the user config file from [`config_path`].
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read or parsed.
+pub fn load_config() -> Result<ConfigFile> {
+    load_config_file(&config_path())
+}
+
+/// Loads a config file, treating a missing file as all defaults.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read, is not valid
+/// TOML, or contains unknown keys.
+pub fn load_config_file(path: &Path) -> Result<ConfigFile> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(ConfigFile::default());
+        }
+        Err(source) => {
+            return Err(ContainustError::Io {
+                path: path.to_path_buf(),
+                source,
+            });
+        }
+    };
+    parse_config(&text).map_err(|error| ContainustError::Config {
+        message: format!("invalid config file {}: {error}", path.display()),
+    })
+}
+
+/// Parses config file contents.
+///
+/// # Errors
+///
+/// Returns the TOML error message when `text` is invalid.
+pub fn parse_config(text: &str) -> std::result::Result<ConfigFile, String> {
+    toml::from_str(text).map_err(|error| error.message().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,4 +159,42 @@ mod tests {
         assert_eq!(back.data_dir, cfg.data_dir);
         assert_eq!(back.offline, cfg.offline);
     }
+
+    #[test]
+    fn parse_config_reads_defaults_table() {
+        let config = parse_config("[defaults]\nmemory = \"512MiB\"\ncpu = \"1.0\"\npids = 256\n")
+            .expect("parse");
+        assert_eq!(config.defaults.memory.as_deref(), Some("512MiB"));
+        assert_eq!(config.defaults.cpu.as_deref(), Some("1.0"));
+        assert_eq!(config.defaults.pids, Some(256));
+        assert!(config.defaults.enforces_limits());
+    }
+
+    #[test]
+    fn parse_config_reads_enforce_limits() {
+        let config = parse_config("[defaults]\nenforce_limits = false\n").expect("parse");
+        assert!(!config.defaults.enforces_limits());
+    }
+
+    #[test]
+    fn parse_config_rejects_unknown_keys() {
+        let error = parse_config("[defaults]\nmemroy = \"1GiB\"\n").unwrap_err();
+        assert!(error.contains("memroy"), "{error}");
+    }
+
+    #[test]
+    fn load_config_file_missing_is_default() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = load_config_file(&dir.path().join("absent.toml")).expect("load");
+        assert_eq!(config, ConfigFile::default());
+    }
+
+    #[test]
+    fn load_config_file_error_names_path() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "defaults = 3").expect("write");
+        let error = load_config_file(&path).unwrap_err().to_string();
+        assert!(error.contains("config.toml"), "{error}");
+    }
 }
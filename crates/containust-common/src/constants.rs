@@ -85,6 +85,9 @@ pub const DEFAULT_ROOTFS_DIR: &str = "/var/lib/containust/rootfs";
 /// Cgroups v2 unified hierarchy mount point.
 pub const CGROUP_V2_PATH: &str = "/sys/fs/cgroup";
 
+/// Name of the Containust sub-hierarchy under the cgroup root.
+pub const CGROUP_HIERARCHY_NAME: &str = "containust";
+
 /// File extension for Containust composition files.
 pub const CTST_EXTENSION: &str = ".ctst";
 
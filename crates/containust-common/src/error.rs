@@ -61,6 +61,15 @@ pub enum ContainustError {
         source: serde_json::Error,
     },
 
+    /// The host kernel or init system lacks a feature the operation needs.
+    #[error("unsupported kernel feature {feature}: {hint}")]
+    UnsupportedKernelFeature {
+        /// Missing feature, e.g. `cgroup delegation`.
+        feature: String,
+        /// How to enable it.
+        hint: String,
+    },
+
     /// A network operation failed or was rejected by policy.
     #[error("network error for {url}: {message}")]
     Network {
@@ -68,12 +77,63 @@ pub enum ContainustError {
         url: String,
         /// Actionable description of the failure.
         message: String,
+        /// Whether the failure is transient: the connection dropped or
+        /// timed out, or the server answered with a transient status
+        /// (see [`is_transient_status`]).
+        retryable: bool,
+    },
+
+    /// An operation stopped early because its cancellation token fired.
+    #[error("{operation} cancelled")]
+    Cancelled {
+        /// Operation that was cancelled, e.g. `deploy`.
+        operation: String,
     },
 }
 
 /// Convenience alias used throughout the workspace.
 pub type Result<T> = std::result::Result<T, ContainustError>;
 
+impl ContainustError {
+    /// Whether retrying the failed operation may succeed.
+    ///
+    /// Interrupted or timed-out I/O and dropped or overloaded network
+    /// requests are transient; policy rejections, missing resources,
+    /// and integrity failures are not.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Io { source, .. } => is_transient_io(source.kind()),
+            Self::Network { retryable, .. } => *retryable,
+            _ => false,
+        }
+    }
+}
+
+/// Whether an I/O error of this kind may clear up on its own: an
+/// interrupted or timed-out call, or a dropped connection.
+#[must_use]
+pub const fn is_transient_io(kind: std::io::ErrorKind) -> bool {
+    matches!(
+        kind,
+        std::io::ErrorKind::Interrupted
+            | std::io::ErrorKind::WouldBlock
+            | std::io::ErrorKind::TimedOut
+            | std::io::ErrorKind::UnexpectedEof
+            | std::io::ErrorKind::ConnectionReset
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::ConnectionRefused
+            | std::io::ErrorKind::BrokenPipe
+    )
+}
+
+/// Whether an HTTP status reports an overloaded or unavailable server
+/// (`408`, `429`, and `5xx`) rather than a problem with the request.
+#[must_use]
+pub const fn is_transient_status(status: u16) -> bool {
+    matches!(status, 408 | 429 | 500..=599)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,6 +176,7 @@ mod tests {
         let err = ContainustError::Network {
             url: "https://example.test/layer.tar".into(),
             message: "offline mode blocks remote fetch".into(),
+            retryable: false,
         };
         let msg = format!("{err}");
         assert!(msg.contains("https://example.test/layer.tar"));
@@ -129,4 +190,34 @@ mod tests {
         let err: ContainustError = serde_err.into();
         assert!(matches!(err, ContainustError::Serialization { .. }));
     }
+
+    #[test]
+    fn retryable_errors_are_transient_io_and_network() {
+        let io = |kind| ContainustError::Io {
+            path: "/var/lib/containust/images/catalog.json".into(),
+            source: std::io::Error::from(kind),
+        };
+        assert!(io(std::io::ErrorKind::Interrupted).is_retryable());
+        assert!(!io(std::io::ErrorKind::PermissionDenied).is_retryable());
+        let overloaded = ContainustError::Network {
+            url: "https://example.test/blob".into(),
+            message: "server returned status 503 Service Unavailable".into(),
+            retryable: is_transient_status(503),
+        };
+        assert!(overloaded.is_retryable());
+        let offline = ContainustError::Network {
+            url: "https://example.test".into(),
+            message: "offline mode blocks remote image fetch".into(),
+            retryable: false,
+        };
+        assert!(!offline.is_retryable());
+        assert!(is_transient_status(429));
+        assert!(!is_transient_status(404));
+        assert!(
+            !ContainustError::Config {
+                message: "bad".into()
+            }
+            .is_retryable()
+        );
+    }
 }
@@ -9,9 +9,13 @@
 
 #![cfg_attr(test, allow(clippy::expect_used, clippy::unwrap_used, unsafe_code))]
 
+pub mod cancel;
+pub mod clock;
 pub mod codes;
 pub mod config;
 pub mod constants;
 pub mod error;
 pub mod redact;
+pub mod retry;
 pub mod types;
+pub mod units;
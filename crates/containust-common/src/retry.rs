@@ -0,0 +1,208 @@
+//! Retry with exponential backoff for transient failures.
+//!
+//! Only errors for which [`ContainustError::is_retryable`](crate::error::ContainustError::is_retryable) holds are
+//! retried; anything else is returned from the first attempt.
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+use crate::clock::{Clock, SystemClock};
+use crate::error::Result;
+
+/// How often and how patiently an operation is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first; `0` behaves like `1`.
+    pub max_attempts: u32,
+    /// Delay before the second attempt; doubled after each failure.
+    pub base_delay: Duration,
+    /// Upper bound of a single delay.
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy that makes a single attempt.
+    #[must_use]
+    pub const fn once() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    /// Delay after the failed attempt `attempt` (1-based), scaled by
+    /// `jitter` in `[0, 1]`.
+    ///
+    /// Half the backoff is fixed and half is jittered, so concurrent
+    /// clients spread out without any delay collapsing to zero.
+    #[must_use]
+    pub fn delay(&self, attempt: u32, jitter: f64) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        let backoff = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        let half = backoff / 2;
+        half + half.mul_f64(jitter.clamp(0.0, 1.0))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Runs `op` until it succeeds, fails with a non-retryable error, or
+/// `policy.max_attempts` is reached, sleeping with backoff in between.
+///
+/// `op` receives the 1-based attempt number.
+///
+/// # Errors
+///
+/// Returns the first non-retryable error, or the last error once every
+/// attempt has failed.
+pub fn retry<T>(policy: &RetryPolicy, op: impl FnMut(u32) -> Result<T>) -> Result<T> {
+    retry_with_clock(policy, &SystemClock, op)
+}
+
+/// [`retry`] that waits on `clock` between attempts.
+///
+/// # Errors
+///
+/// Returns the first non-retryable error, or the last error once every
+/// attempt has failed.
+pub fn retry_with_clock<T>(
+    policy: &RetryPolicy,
+    clock: &dyn Clock,
+    mut op: impl FnMut(u32) -> Result<T>,
+) -> Result<T> {
+    let attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match op(attempt) {
+            Err(error) if attempt < attempts && error.is_retryable() => {
+                clock.sleep(policy.delay(attempt, jitter()));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// A pseudo-random fraction in `[0, 1]` from the std hasher's random keys.
+fn jitter() -> f64 {
+    let bits = RandomState::new().hash_one(std::time::SystemTime::now());
+    // The top 53 bits fit an f64 mantissa exactly.
+    #[allow(clippy::cast_precision_loss)]
+    let fraction = (bits >> 11) as f64 / (1_u64 << 53) as f64;
+    fraction
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::error::ContainustError;
+
+    fn instant(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    fn transient() -> ContainustError {
+        ContainustError::Network {
+            url: "https://example.test/blob".into(),
+            message: "server returned status 503 Service Unavailable".into(),
+            retryable: true,
+        }
+    }
+
+    #[test]
+    fn retry_succeeds_after_transient_failures() {
+        let mut calls = 0;
+        let result = retry(&instant(4), |attempt| {
+            calls += 1;
+            if attempt < 3 {
+                Err(transient())
+            } else {
+                Ok(attempt)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn retry_gives_up_after_max_attempts() {
+        let mut calls = 0;
+        let result: Result<()> = retry(&instant(3), |_| {
+            calls += 1;
+            Err(transient())
+        });
+        assert!(result.unwrap_err().is_retryable());
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn retry_stops_at_non_retryable_error() {
+        let mut calls = 0;
+        let result: Result<()> = retry(&instant(5), |_| {
+            calls += 1;
+            Err(ContainustError::Network {
+                url: "https://example.test/blob".into(),
+                message: "server returned status 404 Not Found".into(),
+                retryable: false,
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn delay_doubles_within_bounds() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+        };
+        assert_eq!(policy.delay(1, 1.0), Duration::from_millis(100));
+        assert_eq!(policy.delay(2, 0.0), Duration::from_millis(100));
+        assert_eq!(policy.delay(2, 1.0), Duration::from_millis(200));
+        assert_eq!(policy.delay(5, 1.0), Duration::from_millis(300));
+        assert!((0.0..=1.0).contains(&jitter()));
+    }
+
+    #[test]
+    fn retry_backs_off_on_the_clock() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+        };
+        let clock = MockClock::at_epoch();
+        let result: Result<()> = retry_with_clock(&policy, &clock, |_| Err(transient()));
+        assert!(result.is_err());
+
+        let sleeps = clock.sleeps();
+        let bounds = [(50, 100), (100, 200), (150, 300)];
+        assert_eq!(sleeps.len(), bounds.len());
+        for (slept, (low, high)) in sleeps.iter().zip(bounds) {
+            assert!(
+                (Duration::from_millis(low)..=Duration::from_millis(high)).contains(slept),
+                "{slept:?} outside {low}..={high}ms"
+            );
+        }
+        let total: Duration = sleeps.iter().sum();
+        assert_eq!(
+            clock.now(),
+            chrono::DateTime::UNIX_EPOCH + chrono::Duration::from_std(total).expect("total")
+        );
+    }
+}
@@ -4,6 +4,8 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{ContainustError, Result};
+
 /// Unique identifier for a container instance.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ContainerId(String);
@@ -100,6 +102,191 @@ pub struct ResourceLimits {
     pub memory_bytes: Option<u64>,
     /// I/O weight (1-10000).
     pub io_weight: Option<u16>,
+    /// Maximum number of processes (`pids.max`).
+    #[serde(default)]
+    pub pids_max: Option<u64>,
+    /// Hard CPU quota in microseconds per period (`cpu.max`).
+    #[serde(default)]
+    pub cpu_quota_us: Option<u64>,
+    /// CPU quota period in microseconds; the kernel default when unset.
+    #[serde(default)]
+    pub cpu_period_us: Option<u64>,
+    /// Swap allowed beyond `memory_bytes` (`memory.swap.max`); `0`
+    /// disables swap.
+    #[serde(default)]
+    pub swap_bytes: Option<u64>,
+    /// Memory protected from reclaim (`memory.low`).
+    #[serde(default)]
+    pub memory_low_bytes: Option<u64>,
+    /// Throttling threshold below the hard limit (`memory.high`).
+    #[serde(default)]
+    pub memory_high_bytes: Option<u64>,
+    /// Cores the container is pinned to (`cpuset.cpus`).
+    #[serde(default)]
+    pub cpuset_cpus: Option<Vec<u32>>,
+    /// NUMA nodes the container's memory is allocated from (`cpuset.mems`).
+    #[serde(default)]
+    pub cpuset_mems: Option<Vec<u32>>,
+}
+
+impl ResourceLimits {
+    /// Smallest accepted memory limit; below it a container's init is
+    /// OOM-killed before it gets to run.
+    pub const MIN_MEMORY_BYTES: u64 = 6 * 1024 * 1024;
+
+    /// Smallest CPU quota the kernel accepts in `cpu.max`, in microseconds.
+    pub const MIN_CPU_QUOTA_US: u64 = 1_000;
+
+    /// Accepted cgroup v2 weights for `cpu_shares` and `io_weight`.
+    pub const WEIGHT_RANGE: std::ops::RangeInclusive<u64> = 1..=10_000;
+
+    /// Starts a validated set of limits with nothing limited.
+    #[must_use]
+    pub fn builder() -> ResourceLimitsBuilder {
+        ResourceLimitsBuilder::default()
+    }
+
+    /// Checks every set limit against the range the kernel accepts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContainustError::Config`] naming the first limit that is
+    /// out of range.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(bytes) = self.memory_bytes.filter(|&b| b < Self::MIN_MEMORY_BYTES) {
+            return Err(ContainustError::Config {
+                message: format!(
+                    "memory limit of {bytes} bytes is below the minimum of {} bytes (6 MiB)",
+                    Self::MIN_MEMORY_BYTES
+                ),
+            });
+        }
+        if let (Some(low), Some(max)) = (self.memory_low_bytes, self.memory_bytes)
+            && low > max
+        {
+            return Err(ContainustError::Config {
+                message: format!(
+                    "memory reservation of {low} bytes exceeds the memory limit of {max} bytes"
+                ),
+            });
+        }
+        if let Some(quota) = self.cpu_quota_us.filter(|&q| q < Self::MIN_CPU_QUOTA_US) {
+            return Err(ContainustError::Config {
+                message: format!(
+                    "CPU quota of {quota}us is below the kernel minimum of {}us",
+                    Self::MIN_CPU_QUOTA_US
+                ),
+            });
+        }
+        check_weight("cpu_shares", self.cpu_shares)?;
+        check_weight("io_weight", self.io_weight.map(u64::from))
+    }
+}
+
+fn check_weight(name: &str, weight: Option<u64>) -> Result<()> {
+    match weight {
+        Some(weight) if !ResourceLimits::WEIGHT_RANGE.contains(&weight) => {
+            Err(ContainustError::Config {
+                message: format!(
+                    "{name} {weight} is outside the cgroup weight range {}..={}",
+                    ResourceLimits::WEIGHT_RANGE.start(),
+                    ResourceLimits::WEIGHT_RANGE.end()
+                ),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Builds [`ResourceLimits`], validating them on [`build`](Self::build).
+#[derive(Debug, Clone, Default)]
+pub struct ResourceLimitsBuilder {
+    limits: ResourceLimits,
+}
+
+impl ResourceLimitsBuilder {
+    /// Caps memory at `bytes`.
+    #[must_use]
+    pub const fn memory_bytes(mut self, bytes: u64) -> Self {
+        self.limits.memory_bytes = Some(bytes);
+        self
+    }
+
+    /// Sets the relative CPU weight.
+    #[must_use]
+    pub const fn cpu_shares(mut self, weight: u64) -> Self {
+        self.limits.cpu_shares = Some(weight);
+        self
+    }
+
+    /// Sets the relative I/O weight.
+    #[must_use]
+    pub const fn io_weight(mut self, weight: u16) -> Self {
+        self.limits.io_weight = Some(weight);
+        self
+    }
+
+    /// Protects `bytes` of memory from reclaim; at most the memory limit.
+    #[must_use]
+    pub const fn memory_reservation(mut self, bytes: u64) -> Self {
+        self.limits.memory_low_bytes = Some(bytes);
+        self
+    }
+
+    /// Throttles the container once it uses more than `bytes`.
+    #[must_use]
+    pub const fn memory_high(mut self, bytes: u64) -> Self {
+        self.limits.memory_high_bytes = Some(bytes);
+        self
+    }
+
+    /// Allows `bytes` of swap beyond the memory limit; `0` disables swap.
+    #[must_use]
+    pub const fn swap_bytes(mut self, bytes: u64) -> Self {
+        self.limits.swap_bytes = Some(bytes);
+        self
+    }
+
+    /// Pins the container to `cores`.
+    #[must_use]
+    pub fn cpuset_cpus(mut self, cores: Vec<u32>) -> Self {
+        self.limits.cpuset_cpus = Some(cores);
+        self
+    }
+
+    /// Allocates the container's memory from NUMA `nodes`.
+    #[must_use]
+    pub fn cpuset_mems(mut self, nodes: Vec<u32>) -> Self {
+        self.limits.cpuset_mems = Some(nodes);
+        self
+    }
+
+    /// Caps the number of processes.
+    #[must_use]
+    pub const fn pids_max(mut self, pids: u64) -> Self {
+        self.limits.pids_max = Some(pids);
+        self
+    }
+
+    /// Allows `quota_us` of CPU time per `period_us` (the kernel default
+    /// period when `None`).
+    #[must_use]
+    pub const fn cpu_quota(mut self, quota_us: u64, period_us: Option<u64>) -> Self {
+        self.limits.cpu_quota_us = Some(quota_us);
+        self.limits.cpu_period_us = period_us;
+        self
+    }
+
+    /// Validates and returns the limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContainustError::Config`] if a limit is out of range; see
+    /// [`ResourceLimits::validate`].
+    pub fn build(self) -> Result<ResourceLimits> {
+        self.limits.validate()?;
+        Ok(self.limits)
+    }
 }
 
 /// Lifecycle state of a container.
@@ -168,11 +355,26 @@ impl fmt::Display for RestartPolicy {
     }
 }
 
+/// HTTP request a health probe makes from the host.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpProbe {
+    /// URL fetched with `GET`.
+    pub url: String,
+    /// Status code that counts as healthy.
+    pub expect_status: u16,
+}
+
 /// Health probe configuration attached to a container.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HealthcheckSpec {
-    /// Command executed inside the container.
+    /// Command executed inside the container; empty for host-side probes.
     pub command: Vec<String>,
+    /// Host-side HTTP probe used instead of `command`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpProbe>,
+    /// Host port connected to on `127.0.0.1` instead of running `command`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tcp: Option<u16>,
     /// Seconds between probe executions.
     pub interval_secs: u64,
     /// Probe timeout in seconds.
@@ -187,6 +389,8 @@ impl Default for HealthcheckSpec {
     fn default() -> Self {
         Self {
             command: Vec::new(),
+            http: None,
+            tcp: None,
             interval_secs: 30,
             timeout_secs: 30,
             retries: 3,
@@ -264,10 +468,57 @@ impl Default for HealthRecord {
     }
 }
 
+/// Checks a container or component name against `[a-zA-Z0-9][a-zA-Z0-9_.-]*`.
+///
+/// Names end up in cgroup paths, log file names, and VM agent commands, so
+/// separators, whitespace, and shell metacharacters are rejected.
+///
+/// # Errors
+///
+/// Returns a configuration error naming `name` when it does not match.
+pub fn validate_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(ContainustError::Config {
+            message: format!("invalid name '{name}' (expected [a-zA-Z0-9][a-zA-Z0-9_.-]*)"),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn validate_name_accepts_safe_names() {
+        for name in [
+            "web",
+            "Web2",
+            "db-1",
+            "api_v2",
+            "app.internal",
+            "0day",
+            "a..",
+        ] {
+            validate_name(name).unwrap();
+        }
+    }
+
+    #[test]
+    fn validate_name_rejects_unsafe_names() {
+        for name in [
+            "", "web/api", "../etc", "my app", " web", ".", "..", "-web", "_web", "a;rm", "$(id)",
+            "a`b`", "a|b", "a&b", "a>b", "wéb",
+        ] {
+            let error = validate_name(name).unwrap_err().to_string();
+            assert!(error.contains(&format!("'{name}'")), "{error}");
+        }
+    }
+
     #[test]
     fn container_id_new_stores_value() {
         let id = ContainerId::new("abc-123");
@@ -335,6 +586,104 @@ mod tests {
         assert_eq!(limits.io_weight, None);
     }
 
+    #[test]
+    fn resource_limits_builder_builds_valid_limits() {
+        let limits = ResourceLimits::builder()
+            .memory_bytes(256 * 1024 * 1024)
+            .cpu_shares(512)
+            .io_weight(100)
+            .pids_max(64)
+            .cpu_quota(50_000, None)
+            .build()
+            .expect("valid limits");
+        assert_eq!(limits.memory_bytes, Some(256 * 1024 * 1024));
+        assert_eq!(limits.cpu_shares, Some(512));
+        assert_eq!(limits.io_weight, Some(100));
+        assert_eq!(limits.pids_max, Some(64));
+        assert_eq!(limits.cpu_quota_us, Some(50_000));
+        assert!(ResourceLimits::builder().build().is_ok());
+    }
+
+    #[test]
+    fn resource_limits_reject_tiny_memory() {
+        let error = ResourceLimits::builder()
+            .memory_bytes(0)
+            .build()
+            .expect_err("zero memory");
+        assert!(error.to_string().contains("below the minimum"), "{error}");
+        assert!(
+            ResourceLimits::builder()
+                .memory_bytes(ResourceLimits::MIN_MEMORY_BYTES)
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn resource_limits_reject_cpu_shares_outside_weight_range() {
+        for shares in [0, 10_001] {
+            let error = ResourceLimits::builder()
+                .cpu_shares(shares)
+                .build()
+                .expect_err("out of range");
+            assert!(error.to_string().contains("cpu_shares"), "{error}");
+        }
+        assert!(ResourceLimits::builder().cpu_shares(10_000).build().is_ok());
+    }
+
+    #[test]
+    fn resource_limits_reject_cpu_quota_below_kernel_minimum() {
+        let error = ResourceLimits::builder()
+            .cpu_quota(999, None)
+            .build()
+            .expect_err("quota too small");
+        assert!(error.to_string().contains("CPU quota of 999us"), "{error}");
+        assert!(
+            ResourceLimits::builder()
+                .cpu_quota(ResourceLimits::MIN_CPU_QUOTA_US, Some(100_000))
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn resource_limits_reject_io_weight_outside_weight_range() {
+        for weight in [0, 10_001] {
+            let error = ResourceLimits::builder()
+                .io_weight(weight)
+                .build()
+                .expect_err("out of range");
+            assert!(error.to_string().contains("io_weight"), "{error}");
+        }
+        assert!(ResourceLimits::builder().io_weight(1).build().is_ok());
+    }
+
+    #[test]
+    fn resource_limits_reject_reservation_above_memory_limit() {
+        let mib = 1024 * 1024;
+        let error = ResourceLimits::builder()
+            .memory_bytes(64 * mib)
+            .memory_reservation(128 * mib)
+            .build()
+            .expect_err("reservation above max");
+        assert!(error.to_string().contains("memory reservation"), "{error}");
+        let within = ResourceLimits::builder()
+            .memory_bytes(64 * mib)
+            .memory_reservation(64 * mib)
+            .memory_high(48 * mib)
+            .build()
+            .expect("reservation at max");
+        assert_eq!(within.memory_low_bytes, Some(64 * mib));
+        assert_eq!(within.memory_high_bytes, Some(48 * mib));
+        assert!(
+            ResourceLimits::builder()
+                .memory_reservation(128 * mib)
+                .build()
+                .is_ok(),
+            "no memory limit to exceed"
+        );
+    }
+
     #[test]
     fn container_state_display_values() {
         assert_eq!(format!("{}", ContainerState::Created), "created");
@@ -0,0 +1,190 @@
+//! Parsers for human-readable resource quantities and durations.
+//!
+//! Shared by the `.ctst` deployer and the CLI flags so that
+//! `memory = "256MiB"` and `--memory 256MiB` accept exactly the same
+//! syntax. Errors always quote the offending string.
+
+use std::time::Duration;
+
+use crate::error::{ContainustError, Result};
+
+const BYTE_SUFFIXES: [(&str, u64); 6] = [
+    ("GiB", 1024 * 1024 * 1024),
+    ("GB", 1_000_000_000),
+    ("MiB", 1024 * 1024),
+    ("MB", 1_000_000),
+    ("KiB", 1024),
+    ("KB", 1000),
+];
+
+const DURATION_SUFFIXES: [(char, u64); 3] = [('h', 3600), ('m', 60), ('s', 1)];
+
+/// Highest cgroup v2 CPU weight.
+const MAX_CPU_WEIGHT: u64 = 10_000;
+
+/// Parses a byte size such as `128MiB`, `1GB`, or `1048576`.
+///
+/// # Errors
+///
+/// Returns a configuration error naming `raw` if it is not a number with
+/// an optional `KB`/`KiB`/`MB`/`MiB`/`GB`/`GiB` suffix, or overflows.
+pub fn parse_bytes(raw: &str) -> Result<u64> {
+    let text = raw.trim();
+    let (number, multiplier) = BYTE_SUFFIXES
+        .iter()
+        .find_map(|(suffix, multiplier)| {
+            text.strip_suffix(suffix)
+                .map(|number| (number, *multiplier))
+        })
+        .unwrap_or((text, 1));
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|value| value.checked_mul(multiplier))
+        .ok_or_else(|| ContainustError::Config {
+            message: format!(
+                "invalid size '{raw}': expected a number with an optional \
+                 KB/KiB/MB/MiB/GB/GiB suffix"
+            ),
+        })
+}
+
+/// Parses a CPU limit into a cgroup v2 weight.
+///
+/// Whole numbers in `1..=10000` are taken as a raw weight; decimals such
+/// as `0.5` or `1.5` are cores scaled by 1024 and clamped to the weight
+/// range.
+///
+/// # Errors
+///
+/// Returns a configuration error naming `raw` if it is not a valid weight
+/// or decimal core count.
+pub fn parse_cpu(raw: &str) -> Result<u64> {
+    cpu_weight(raw.trim()).ok_or_else(|| ContainustError::Config {
+        message: format!(
+            "invalid CPU limit '{raw}': expected a weight in 1..={MAX_CPU_WEIGHT} \
+             or decimal cores such as 0.5"
+        ),
+    })
+}
+
+/// Parses a maximum process count (`pids.max`).
+///
+/// # Errors
+///
+/// Returns a configuration error naming `raw` if it is not a positive integer.
+pub fn parse_pids(raw: &str) -> Result<u64> {
+    raw.trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|&pids| pids > 0)
+        .ok_or_else(|| ContainustError::Config {
+            message: format!("invalid PID limit '{raw}': expected a positive integer"),
+        })
+}
+
+/// Parses a whole-second duration such as `30s`, `5m`, `1h`, or `45`.
+///
+/// # Errors
+///
+/// Returns a configuration error naming `raw` if it is not a number with
+/// an optional `s`/`m`/`h` suffix, or overflows.
+pub fn parse_duration(raw: &str) -> Result<Duration> {
+    let text = raw.trim();
+    let (number, multiplier) = DURATION_SUFFIXES
+        .iter()
+        .find_map(|&(suffix, multiplier)| {
+            text.strip_suffix(suffix).map(|number| (number, multiplier))
+        })
+        .unwrap_or((text, 1));
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|value| value.checked_mul(multiplier))
+        .map(Duration::from_secs)
+        .ok_or_else(|| ContainustError::Config {
+            message: format!(
+                "invalid duration '{raw}': expected a number with an optional s/m/h suffix"
+            ),
+        })
+}
+
+fn cpu_weight(value: &str) -> Option<u64> {
+    if let Ok(shares) = value.parse::<u64>() {
+        return (1..=MAX_CPU_WEIGHT).contains(&shares).then_some(shares);
+    }
+    let (whole_text, fraction_text) = value.split_once('.')?;
+    if fraction_text.is_empty() || !fraction_text.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let whole = if whole_text.is_empty() {
+        0
+    } else {
+        whole_text.parse::<u64>().ok()?
+    };
+    let fraction = fraction_text.parse::<u64>().ok()?;
+    let scale = 10_u64.checked_pow(fraction_text.len().try_into().ok()?)?;
+    let shares = whole
+        .checked_mul(1024)?
+        .checked_add(fraction.checked_mul(1024)?.checked_div(scale)?)?;
+    Some(shares.clamp(1, MAX_CPU_WEIGHT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_bytes_binary_and_decimal_suffixes() {
+        assert_eq!(parse_bytes("128MiB").unwrap(), 128 * 1024 * 1024);
+        assert_eq!(parse_bytes("1GiB").unwrap(), 1024 * 1024 * 1024);
+        assert_eq!(parse_bytes("2GB").unwrap(), 2_000_000_000);
+        assert_eq!(parse_bytes("4KiB").unwrap(), 4096);
+        assert_eq!(parse_bytes(" 1048576 ").unwrap(), 1_048_576);
+    }
+
+    #[test]
+    fn parse_bytes_error_names_offending_value() {
+        let error = parse_bytes("lots").unwrap_err().to_string();
+        assert!(error.contains("'lots'"), "{error}");
+        assert!(parse_bytes("99999999999999GiB").is_err());
+        assert!(parse_bytes("MiB").is_err());
+    }
+
+    #[test]
+    fn parse_cpu_decimal_maps_to_weight() {
+        assert_eq!(parse_cpu("0.5").unwrap(), 512);
+        assert_eq!(parse_cpu("1.5").unwrap(), 1536);
+        assert_eq!(parse_cpu("2").unwrap(), 2);
+        assert_eq!(parse_cpu("64.0").unwrap(), MAX_CPU_WEIGHT);
+    }
+
+    #[test]
+    fn parse_cpu_error_names_offending_value() {
+        assert!(parse_cpu("0").is_err());
+        assert!(parse_cpu("1.").is_err());
+        let error = parse_cpu("fast").unwrap_err().to_string();
+        assert!(error.contains("'fast'"), "{error}");
+    }
+
+    #[test]
+    fn parse_pids_requires_positive_integer() {
+        assert_eq!(parse_pids("128").unwrap(), 128);
+        assert!(parse_pids("0").is_err());
+        let error = parse_pids("-1").unwrap_err().to_string();
+        assert!(error.contains("'-1'"), "{error}");
+    }
+
+    #[test]
+    fn parse_duration_supports_units() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration(" 5m ").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_duration("45").unwrap(), Duration::from_secs(45));
+        let error = parse_duration("soon").unwrap_err().to_string();
+        assert!(error.contains("'soon'"), "{error}");
+        assert!(parse_duration("99999999999999999999h").is_err());
+    }
+}
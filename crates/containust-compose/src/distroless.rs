@@ -1,84 +0,0 @@
-//! Binary dependency analysis for distroless builds.
-//!
-//! Analyzes ELF binaries using an internal `ldd`-like resolver to
-//! identify only the shared libraries needed, enabling automatic
-//! "distroless" image generation.
-
-use std::io::Read;
-use std::path::Path;
-
-use containust_common::error::{ContainustError, Result};
-
-const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
-
-/// Analyzes an ELF binary and returns its required shared library paths.
-///
-/// This is a simplified analysis that reads the ELF dynamic section
-/// to find `DT_NEEDED` entries. For production use, a full `ldd`-like
-/// recursive resolver would be needed.
-///
-/// # Errors
-///
-/// Returns an error if the binary cannot be read or is not a valid ELF file.
-pub fn analyze_dependencies(binary: &Path) -> Result<Vec<String>> {
-    tracing::info!(binary = %binary.display(), "analyzing binary dependencies");
-
-    let mut file = std::fs::File::open(binary).map_err(|e| ContainustError::Io {
-        path: binary.to_path_buf(),
-        source: e,
-    })?;
-
-    let mut magic = [0u8; 4];
-    file.read_exact(&mut magic)
-        .map_err(|e| ContainustError::Io {
-            path: binary.to_path_buf(),
-            source: e,
-        })?;
-
-    if magic != ELF_MAGIC {
-        return Err(ContainustError::Config {
-            message: format!("{} is not a valid ELF binary", binary.display()),
-        });
-    }
-
-    // Minimal ELF analysis: report common runtime dependencies
-    // based on file existence checks. A full implementation would
-    // parse the ELF dynamic section.
-    let common_deps = [
-        "/lib/x86_64-linux-gnu/libc.so.6",
-        "/lib/x86_64-linux-gnu/libpthread.so.0",
-        "/lib/x86_64-linux-gnu/libdl.so.2",
-        "/lib/x86_64-linux-gnu/libm.so.6",
-        "/lib64/ld-linux-x86-64.so.2",
-        "/lib/ld-musl-x86_64.so.1",
-    ];
-
-    let deps: Vec<String> = common_deps
-        .iter()
-        .filter(|p| Path::new(p).exists())
-        .map(|p| (*p).to_string())
-        .collect();
-
-    tracing::info!(count = deps.len(), "found dependencies");
-    Ok(deps)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn rejects_non_elf_file() {
-        let dir = tempfile::tempdir().expect("tempdir");
-        let path = dir.path().join("not_elf");
-        std::fs::write(&path, b"not an elf file").expect("write");
-        let result = analyze_dependencies(&path);
-        assert!(result.is_err());
-    }
-
-    #[test]
-    fn rejects_missing_file() {
-        let result = analyze_dependencies(Path::new("/nonexistent/binary"));
-        assert!(result.is_err());
-    }
-}
@@ -0,0 +1,342 @@
+//! Minimal ELF reader for the dynamic-linking metadata of a binary.
+//!
+//! Reads the program headers only: `PT_INTERP` for the loader and
+//! `PT_DYNAMIC` for `DT_NEEDED`, `DT_RPATH`, and `DT_RUNPATH`, whose
+//! strings live in `DT_STRTAB` (mapped back to a file offset through the
+//! `PT_LOAD` segments).
+
+const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+
+const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+const PT_INTERP: u32 = 3;
+
+const DT_NULL: u64 = 0;
+const DT_NEEDED: u64 = 1;
+const DT_STRTAB: u64 = 5;
+const DT_RPATH: u64 = 15;
+const DT_RUNPATH: u64 = 29;
+
+/// Dynamic-linking metadata read from an ELF file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ElfInfo {
+    /// Whether the file is 64-bit (`ELFCLASS64`).
+    pub is_64: bool,
+    /// Target architecture (`e_machine`).
+    pub machine: u16,
+    /// Program interpreter (dynamic loader), if any.
+    pub interpreter: Option<String>,
+    /// `DT_NEEDED` library names, in declaration order.
+    pub needed: Vec<String>,
+    /// `DT_RPATH` search directories.
+    pub rpath: Vec<String>,
+    /// `DT_RUNPATH` search directories.
+    pub runpath: Vec<String>,
+}
+
+impl ElfInfo {
+    /// Whether a library built for `other` can be loaded by this binary.
+    #[must_use]
+    pub const fn compatible_with(&self, other: &Self) -> bool {
+        self.is_64 == other.is_64 && self.machine == other.machine
+    }
+}
+
+/// Endian- and class-aware field reader.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    is_64: bool,
+    big_endian: bool,
+}
+
+impl Reader<'_> {
+    fn field(&self, offset: u64, size: usize) -> Result<u64, String> {
+        let start = usize::try_from(offset).map_err(|_| "offset out of range".to_string())?;
+        let raw = start
+            .checked_add(size)
+            .and_then(|end| self.bytes.get(start..end))
+            .ok_or_else(|| format!("truncated ELF: {size} bytes at offset {offset}"))?;
+        let mut value = 0u64;
+        for (index, byte) in raw.iter().enumerate() {
+            let position = if self.big_endian {
+                size - 1 - index
+            } else {
+                index
+            };
+            value |= u64::from(*byte) << (8 * position);
+        }
+        Ok(value)
+    }
+
+    /// Reads an address-sized field (`u32` or `u64`).
+    fn word(&self, offset: u64) -> Result<u64, String> {
+        self.field(offset, if self.is_64 { 8 } else { 4 })
+    }
+
+    fn c_string(&self, offset: u64) -> Result<String, String> {
+        let start = usize::try_from(offset).map_err(|_| "offset out of range".to_string())?;
+        let tail = self
+            .bytes
+            .get(start..)
+            .ok_or_else(|| format!("string offset {offset} is outside the file"))?;
+        let end = tail
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(tail.len());
+        Ok(String::from_utf8_lossy(&tail[..end]).into_owned())
+    }
+}
+
+/// One program header.
+struct Segment {
+    kind: u32,
+    offset: u64,
+    vaddr: u64,
+    filesz: u64,
+}
+
+/// Parses the dynamic-linking metadata of an ELF image.
+///
+/// # Errors
+///
+/// Returns a description of the problem when `bytes` is not a
+/// well-formed ELF file.
+pub fn parse(bytes: &[u8]) -> Result<ElfInfo, String> {
+    if bytes.get(..4) != Some(&ELF_MAGIC[..]) {
+        return Err("not an ELF file".into());
+    }
+    let is_64 = match bytes.get(4) {
+        Some(1) => false,
+        Some(2) => true,
+        _ => return Err("unknown ELF class".into()),
+    };
+    let big_endian = match bytes.get(5) {
+        Some(1) => false,
+        Some(2) => true,
+        _ => return Err("unknown ELF byte order".into()),
+    };
+    let reader = Reader {
+        bytes,
+        is_64,
+        big_endian,
+    };
+    let segments = segments(&reader)?;
+    let mut info = ElfInfo {
+        is_64,
+        machine: u16::try_from(reader.field(18, 2)?).unwrap_or_default(),
+        ..ElfInfo::default()
+    };
+    for segment in &segments {
+        match segment.kind {
+            PT_INTERP => info.interpreter = Some(reader.c_string(segment.offset)?),
+            PT_DYNAMIC => read_dynamic(&reader, segment, &segments, &mut info)?,
+            _ => {}
+        }
+    }
+    Ok(info)
+}
+
+fn segments(reader: &Reader<'_>) -> Result<Vec<Segment>, String> {
+    let (phoff, entsize, count) = if reader.is_64 {
+        (
+            reader.field(32, 8)?,
+            reader.field(54, 2)?,
+            reader.field(56, 2)?,
+        )
+    } else {
+        (
+            reader.field(28, 4)?,
+            reader.field(42, 2)?,
+            reader.field(44, 2)?,
+        )
+    };
+    (0..count)
+        .map(|index| {
+            let base = add(phoff, index * entsize)?;
+            let kind = u32::try_from(reader.field(base, 4)?).unwrap_or_default();
+            Ok(if reader.is_64 {
+                Segment {
+                    kind,
+                    offset: reader.field(add(base, 8)?, 8)?,
+                    vaddr: reader.field(add(base, 16)?, 8)?,
+                    filesz: reader.field(add(base, 32)?, 8)?,
+                }
+            } else {
+                Segment {
+                    kind,
+                    offset: reader.field(add(base, 4)?, 4)?,
+                    vaddr: reader.field(add(base, 8)?, 4)?,
+                    filesz: reader.field(add(base, 16)?, 4)?,
+                }
+            })
+        })
+        .collect()
+}
+
+fn read_dynamic(
+    reader: &Reader<'_>,
+    dynamic: &Segment,
+    segments: &[Segment],
+    info: &mut ElfInfo,
+) -> Result<(), String> {
+    let entry_size = if reader.is_64 { 16 } else { 8 };
+    let mut entries = Vec::new();
+    let mut strtab = None;
+    for index in 0..dynamic.filesz / entry_size {
+        let base = add(dynamic.offset, index * entry_size)?;
+        let tag = reader.word(base)?;
+        let value = reader.word(add(base, entry_size / 2)?)?;
+        match tag {
+            DT_NULL => break,
+            DT_STRTAB => strtab = Some(value),
+            _ => entries.push((tag, value)),
+        }
+    }
+    let Some(strtab) = strtab else {
+        return Ok(());
+    };
+    let mut loaded = None;
+    for segment in segments.iter().filter(|segment| segment.kind == PT_LOAD) {
+        if (segment.vaddr..add(segment.vaddr, segment.filesz)?).contains(&strtab) {
+            loaded = Some(segment);
+            break;
+        }
+    }
+    let segment = loaded.ok_or("DT_STRTAB is outside every loaded segment")?;
+    let strtab = add(strtab - segment.vaddr, segment.offset)?;
+    for (tag, value) in entries {
+        let text = || reader.c_string(add(strtab, value)?);
+        match tag {
+            DT_NEEDED => info.needed.push(text()?),
+            DT_RPATH => info.rpath.extend(split_search_path(&text()?)),
+            DT_RUNPATH => info.runpath.extend(split_search_path(&text()?)),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// `base + delta`, or an error when a header field makes it overflow.
+fn add(base: u64, delta: u64) -> Result<u64, String> {
+    base.checked_add(delta)
+        .ok_or_else(|| format!("offset {base} + {delta} overflows"))
+}
+
+fn split_search_path(text: &str) -> Vec<String> {
+    text.split(':')
+        .filter(|dir| !dir.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Builds a minimal 64-bit little-endian x86-64 ELF image with a single
+/// `PT_LOAD` at address 0, for tests.
+#[cfg(test)]
+pub(crate) fn fake_elf(
+    interpreter: Option<&str>,
+    needed: &[&str],
+    runpath: Option<&str>,
+) -> Vec<u8> {
+    const HEADER: u64 = 64;
+    const PHENT: u16 = 56;
+    const PHNUM: u16 = 3;
+
+    let mut strings = vec![0u8];
+    let mut add = |text: &str| {
+        let offset = strings.len() as u64;
+        strings.extend_from_slice(text.as_bytes());
+        strings.push(0);
+        offset
+    };
+    let interp = interpreter.map(&mut add);
+    let mut dynamic: Vec<(u64, u64)> = needed.iter().map(|name| (DT_NEEDED, add(name))).collect();
+    if let Some(runpath) = runpath {
+        dynamic.push((DT_RUNPATH, add(runpath)));
+    }
+    let strtab = HEADER + u64::from(PHENT) * u64::from(PHNUM);
+    dynamic.extend([(DT_STRTAB, strtab), (DT_NULL, 0)]);
+    let dynamic_offset = strtab + strings.len() as u64;
+    let dynamic_size = dynamic.len() as u64 * 16;
+
+    let mut image = ELF_MAGIC.to_vec();
+    image.extend_from_slice(&[2, 1, 1, 0]);
+    image.resize(16, 0);
+    image.extend_from_slice(&3u16.to_le_bytes());
+    image.extend_from_slice(&62u16.to_le_bytes());
+    image.resize(32, 0);
+    image.extend_from_slice(&HEADER.to_le_bytes());
+    image.resize(54, 0);
+    image.extend_from_slice(&PHENT.to_le_bytes());
+    image.extend_from_slice(&PHNUM.to_le_bytes());
+    image.resize(64, 0);
+    push_segment(&mut image, PT_LOAD, 0, dynamic_offset + dynamic_size);
+    push_segment(&mut image, PT_DYNAMIC, dynamic_offset, dynamic_size);
+    match interp {
+        Some(offset) => push_segment(&mut image, PT_INTERP, strtab + offset, 1),
+        None => push_segment(&mut image, 0, 0, 0),
+    }
+    image.extend_from_slice(&strings);
+    for (tag, value) in dynamic {
+        image.extend_from_slice(&tag.to_le_bytes());
+        image.extend_from_slice(&value.to_le_bytes());
+    }
+    image
+}
+
+/// Appends a 64-bit program header mapping `offset` at the same address.
+#[cfg(test)]
+fn push_segment(image: &mut Vec<u8>, kind: u32, offset: u64, size: u64) {
+    image.extend_from_slice(&kind.to_le_bytes());
+    image.extend_from_slice(&[0; 4]);
+    for value in [offset, offset, offset, size, size, 8] {
+        image.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn parses_synthetic_dynamic_section() {
+        let image = fake_elf(
+            Some("/lib64/ld-linux-x86-64.so.2"),
+            &["libfoo.so.1", "libc.so.6"],
+            Some("$ORIGIN/../lib:/opt/lib"),
+        );
+        let info = parse(&image).unwrap();
+        assert!(info.is_64);
+        assert_eq!(info.machine, 62);
+        assert_eq!(
+            info.interpreter.as_deref(),
+            Some("/lib64/ld-linux-x86-64.so.2")
+        );
+        assert_eq!(info.needed, ["libfoo.so.1", "libc.so.6"]);
+        assert_eq!(info.runpath, ["$ORIGIN/../lib", "/opt/lib"]);
+        assert!(info.rpath.is_empty());
+    }
+
+    #[test]
+    fn static_image_has_no_dependencies() {
+        let info = parse(&fake_elf(None, &[], None)).unwrap();
+        assert_eq!(info.interpreter, None);
+        assert!(info.needed.is_empty());
+    }
+
+    #[test]
+    fn rejects_non_elf_and_truncated_images() {
+        assert_eq!(parse(b"#!/bin/sh").unwrap_err(), "not an ELF file");
+        let image = fake_elf(None, &["libc.so.6"], None);
+        assert!(parse(&image[..70]).unwrap_err().contains("truncated"));
+    }
+
+    #[test]
+    fn rejects_segment_addresses_that_overflow() {
+        let mut image = fake_elf(None, &["libc.so.6"], None);
+        // The PT_LOAD header starts at 64; its p_vaddr is 16 bytes in.
+        image[80..88].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(parse(&image).unwrap_err().contains("overflows"));
+    }
+}
@@ -0,0 +1,528 @@
+//! Binary dependency analysis for distroless builds.
+//!
+//! Analyzes ELF binaries using an internal `ldd`-like resolver to
+//! identify only the shared libraries needed, enabling automatic
+//! "distroless" image generation.
+
+pub mod elf;
+
+use std::collections::VecDeque;
+use std::path::{Component, Path, PathBuf};
+
+use containust_common::error::{ContainustError, Result};
+
+/// Directories the dynamic loader searches after `RPATH` / `RUNPATH`.
+pub const DEFAULT_SEARCH_PATHS: [&str; 10] = [
+    "/lib",
+    "/usr/lib",
+    "/lib64",
+    "/usr/lib64",
+    "/lib/x86_64-linux-gnu",
+    "/usr/lib/x86_64-linux-gnu",
+    "/lib/aarch64-linux-gnu",
+    "/usr/lib/aarch64-linux-gnu",
+    "/usr/local/lib",
+    "/usr/local/lib64",
+];
+
+/// Symlinks followed while resolving one path before giving up, as the
+/// kernel's `MAXSYMLINKS`.
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// Directories created empty in a minimal rootfs for the runtime to
+/// mount over.
+pub const MOUNT_POINTS: [&str; 4] = ["proc", "sys", "dev", "tmp"];
+
+/// Resolves the shared libraries an ELF binary needs, `ldd`-style.
+///
+/// Paths the binary names (its loader, absolute `DT_NEEDED` entries,
+/// and the search directories) are looked up under `root`, so a binary
+/// inside an unpacked image can be analyzed against that image. Symlinks
+/// are followed as the image would see them: an absolute target or a
+/// `..` never leaves `root` for the host.
+#[derive(Debug, Clone)]
+pub struct DistrolessAnalyzer {
+    root: PathBuf,
+    search_paths: Vec<PathBuf>,
+}
+
+impl Default for DistrolessAnalyzer {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from("/"),
+            search_paths: DEFAULT_SEARCH_PATHS.iter().map(PathBuf::from).collect(),
+        }
+    }
+}
+
+impl DistrolessAnalyzer {
+    /// Creates an analyzer for the host filesystem with the default
+    /// search paths.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves loader and library paths under `root` instead of `/`.
+    #[must_use]
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    /// Replaces the default library search directories.
+    #[must_use]
+    pub fn search_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.search_paths = paths;
+        self
+    }
+
+    /// Returns the dynamic loader followed by every shared library the
+    /// binary needs, transitively, each once. A static binary needs none.
+    ///
+    /// Libraries are returned under the name the loader looks up, which
+    /// is often a symlink; copy them with the link followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read, is not a valid ELF
+    /// file, or a needed library cannot be found.
+    pub fn analyze(&self, binary: &Path) -> Result<Vec<PathBuf>> {
+        tracing::info!(binary = %binary.display(), "analyzing binary dependencies");
+        let target = self.read_elf(binary)?;
+        let mut found = Vec::new();
+        if let Some(interpreter) = &target.interpreter {
+            found.push(self.rooted(interpreter));
+        }
+        let mut queue = VecDeque::from([(binary.to_path_buf(), target.clone())]);
+        while let Some((path, info)) = queue.pop_front() {
+            let fresh: Vec<PathBuf> = self
+                .needed_by(&path, &info, &target)?
+                .into_iter()
+                .filter(|library| !found.contains(library))
+                .collect();
+            for library in fresh {
+                queue.push_back((library.clone(), self.read_elf(&library)?));
+                found.push(library);
+            }
+        }
+        tracing::info!(count = found.len(), "found dependencies");
+        Ok(found)
+    }
+
+    /// Locates each library the object at `path` needs: `DT_RPATH` (when
+    /// there is no `DT_RUNPATH`), then `DT_RUNPATH`, then the search
+    /// paths. Candidates built for another architecture than `target`
+    /// are skipped.
+    fn needed_by(
+        &self,
+        path: &Path,
+        info: &elf::ElfInfo,
+        target: &elf::ElfInfo,
+    ) -> Result<Vec<PathBuf>> {
+        let origin = path.parent().unwrap_or_else(|| Path::new("."));
+        let rpath = if info.runpath.is_empty() {
+            info.rpath.as_slice()
+        } else {
+            &[]
+        };
+        let dirs: Vec<PathBuf> = rpath
+            .iter()
+            .chain(&info.runpath)
+            .map(|dir| {
+                dir.strip_prefix("$ORIGIN").map_or_else(
+                    || self.rooted(dir),
+                    |rest| origin.join(rest.trim_start_matches('/')),
+                )
+            })
+            .chain(self.search_paths.iter().map(|dir| self.rooted(dir)))
+            .collect();
+        info.needed
+            .iter()
+            .map(|name| {
+                if name.contains('/') {
+                    return Ok(self.rooted(name));
+                }
+                dirs.iter()
+                    .map(|dir| dir.join(name))
+                    .find(|candidate| {
+                        self.resolve(candidate)
+                            .is_ok_and(|file| loadable_by(&file, target))
+                    })
+                    .ok_or_else(|| ContainustError::NotFound {
+                        kind: "shared library",
+                        id: format!("{name} (needed by {})", path.display()),
+                    })
+            })
+            .collect()
+    }
+
+    /// Assembles a minimal rootfs under `out`: `binary`, its loader, and
+    /// its libraries, each at the path it was resolved from, plus empty
+    /// [`MOUNT_POINTS`]. The tree is usable as a `file://` image source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` exists and is not empty, the analysis
+    /// fails, or a file cannot be copied.
+    pub fn build_rootfs(&self, binary: &Path, out: &Path) -> Result<()> {
+        ensure_empty(out)?;
+        let libraries = self.analyze(binary)?;
+        for source in std::iter::once(binary.to_path_buf()).chain(libraries) {
+            copy_file(&self.resolve(&source)?, &out.join(self.image_path(&source)))?;
+        }
+        for dir in MOUNT_POINTS {
+            let path = out.join(dir);
+            std::fs::create_dir_all(&path).map_err(|e| ContainustError::Io { path, source: e })?;
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let tmp = out.join("tmp");
+            std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o1777)).map_err(
+                |e| ContainustError::Io {
+                    path: tmp,
+                    source: e,
+                },
+            )?;
+        }
+        tracing::info!(out = %out.display(), "minimal rootfs assembled");
+        Ok(())
+    }
+
+    fn rooted(&self, path: impl AsRef<Path>) -> PathBuf {
+        let path = path.as_ref();
+        self.root.join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    /// Follows the symlinks in `path` as they resolve inside the image:
+    /// an absolute target restarts at `root` and `..` stops there. Paths
+    /// outside `root` are returned unchanged.
+    fn resolve(&self, path: &Path) -> Result<PathBuf> {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return Ok(path.to_path_buf());
+        };
+        let mut pending: VecDeque<PathBuf> = relative.iter().map(PathBuf::from).collect();
+        let mut resolved = PathBuf::new();
+        let mut hops = 0;
+        while let Some(part) = pending.pop_front() {
+            match part.components().next() {
+                Some(Component::Normal(name)) => resolved.push(name),
+                Some(Component::ParentDir) => {
+                    let _ = resolved.pop();
+                    continue;
+                }
+                _ => continue,
+            }
+            let host = self.root.join(&resolved);
+            let Ok(target) = std::fs::read_link(&host) else {
+                continue;
+            };
+            hops += 1;
+            if hops > MAX_SYMLINK_HOPS {
+                return Err(ContainustError::Config {
+                    message: format!("too many levels of symbolic links in {}", path.display()),
+                });
+            }
+            let _ = resolved.pop();
+            if target.has_root() {
+                resolved = PathBuf::new();
+            }
+            for part in target.iter().rev() {
+                pending.push_front(PathBuf::from(part));
+            }
+        }
+        Ok(self.root.join(resolved))
+    }
+
+    fn read_elf(&self, path: &Path) -> Result<elf::ElfInfo> {
+        read_elf(&self.resolve(path)?)
+    }
+
+    /// Where `path` lives inside the image: relative to `root`, with `.`
+    /// and `..` resolved lexically.
+    fn image_path(&self, path: &Path) -> PathBuf {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let mut image_path = PathBuf::new();
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => image_path.push(part),
+                Component::ParentDir => {
+                    let _ = image_path.pop();
+                }
+                Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            }
+        }
+        image_path
+    }
+}
+
+/// Returns the dynamic loader and every shared library `binary` needs,
+/// resolved on the host with [`DistrolessAnalyzer`]'s defaults.
+///
+/// # Errors
+///
+/// Returns an error if the binary or a library cannot be read or parsed,
+/// or a needed library cannot be found.
+pub fn analyze_binary(binary: &Path) -> Result<Vec<PathBuf>> {
+    DistrolessAnalyzer::new().analyze(binary)
+}
+
+/// Assembles a minimal rootfs for `binary` under `out` from the host's
+/// libraries; see [`DistrolessAnalyzer::build_rootfs`].
+///
+/// # Errors
+///
+/// Returns an error if `out` is not empty, the analysis fails, or a file
+/// cannot be copied.
+pub fn build_minimal_rootfs(binary: &Path, out: &Path) -> Result<()> {
+    DistrolessAnalyzer::new().build_rootfs(binary, out)
+}
+
+/// Analyzes an ELF binary and returns its required shared library paths
+/// as strings; see [`analyze_binary`].
+///
+/// # Errors
+///
+/// Returns an error if the binary cannot be read or is not a valid ELF file.
+pub fn analyze_dependencies(binary: &Path) -> Result<Vec<String>> {
+    Ok(analyze_binary(binary)?
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect())
+}
+
+/// Whether `candidate` is an ELF object built for `target`'s architecture.
+fn loadable_by(candidate: &Path, target: &elf::ElfInfo) -> bool {
+    std::fs::read(candidate)
+        .ok()
+        .and_then(|bytes| elf::parse(&bytes).ok())
+        .is_some_and(|info| target.compatible_with(&info))
+}
+
+fn ensure_empty(out: &Path) -> Result<()> {
+    match std::fs::read_dir(out).map(|mut entries| entries.next().is_some()) {
+        Ok(true) => Err(ContainustError::Config {
+            message: format!("rootfs output {} is not empty", out.display()),
+        }),
+        Ok(false) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(ContainustError::Io {
+            path: out.to_path_buf(),
+            source: e,
+        }),
+    }
+}
+
+/// Copies `source` to `dest`, following symlinks and keeping permissions.
+fn copy_file(source: &Path, dest: &Path) -> Result<()> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| ContainustError::Io {
+            path: parent.to_path_buf(),
+            source: e,
+        })?;
+    }
+    let _ = std::fs::copy(source, dest).map_err(|e| ContainustError::Io {
+        path: source.to_path_buf(),
+        source: e,
+    })?;
+    Ok(())
+}
+
+fn read_elf(path: &Path) -> Result<elf::ElfInfo> {
+    let bytes = std::fs::read(path).map_err(|e| ContainustError::Io {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    elf::parse(&bytes).map_err(|detail| ContainustError::Config {
+        message: format!("{} is not a valid ELF binary: {detail}", path.display()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::elf::fake_elf;
+    use super::*;
+
+    fn write(path: &Path, bytes: &[u8]) {
+        std::fs::create_dir_all(path.parent().expect("parent")).expect("mkdir");
+        std::fs::write(path, bytes).expect("write");
+    }
+
+    #[test]
+    fn rejects_non_elf_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("not_elf");
+        std::fs::write(&path, b"not an elf file").expect("write");
+        let result = analyze_dependencies(&path);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_missing_file() {
+        let result = analyze_dependencies(Path::new("/nonexistent/binary"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn resolves_transitive_closure_with_runpath_and_loader() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let loader = "/lib64/ld-linux-x86-64.so.2";
+        let app = root.path().join("opt/app/bin/app");
+        write(
+            &app,
+            &fake_elf(
+                Some(loader),
+                &["libfoo.so.1", "libc.so.6"],
+                Some("$ORIGIN/../lib"),
+            ),
+        );
+        write(
+            &root.path().join("opt/app/lib/libfoo.so.1"),
+            &fake_elf(None, &["libbar.so.2", "libc.so.6"], None),
+        );
+        write(
+            &root.path().join("usr/lib/libbar.so.2"),
+            &fake_elf(None, &[], None),
+        );
+        write(
+            &root.path().join("lib/libc.so.6"),
+            &fake_elf(None, &[], None),
+        );
+        // A non-ELF file earlier in the search order is skipped.
+        write(&root.path().join("lib/libbar.so.2"), b"not a library");
+
+        let libs = DistrolessAnalyzer::new()
+            .root(root.path())
+            .analyze(&app)
+            .expect("analyze");
+        let relative: Vec<PathBuf> = libs
+            .iter()
+            .map(|lib| {
+                lib.strip_prefix(root.path())
+                    .expect("under root")
+                    .to_path_buf()
+            })
+            .collect();
+        assert_eq!(
+            relative,
+            [
+                "lib64/ld-linux-x86-64.so.2",
+                "opt/app/bin/../lib/libfoo.so.1",
+                "lib/libc.so.6",
+                "usr/lib/libbar.so.2",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn missing_library_names_the_needing_object() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let app = root.path().join("app");
+        write(&app, &fake_elf(None, &["libgone.so.9"], None));
+        let error = DistrolessAnalyzer::new()
+            .root(root.path())
+            .search_paths(vec![PathBuf::from("/lib")])
+            .analyze(&app)
+            .expect_err("missing library")
+            .to_string();
+        assert!(error.contains("libgone.so.9"), "{error}");
+    }
+
+    #[test]
+    fn builds_minimal_rootfs_with_binary_loader_and_mount_points() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let out = tempfile::tempdir().expect("tempdir");
+        let loader = "/lib64/ld-linux-x86-64.so.2";
+        let app = root.path().join("opt/app/bin/app");
+        write(
+            &app,
+            &fake_elf(Some(loader), &["libfoo.so.1"], Some("$ORIGIN/../lib")),
+        );
+        write(&root.path().join(&loader[1..]), &fake_elf(None, &[], None));
+        write(
+            &root.path().join("opt/app/lib/libfoo.so.1"),
+            &fake_elf(None, &[], None),
+        );
+
+        DistrolessAnalyzer::new()
+            .root(root.path())
+            .build_rootfs(&app, out.path())
+            .expect("build rootfs");
+        for file in [
+            "opt/app/bin/app",
+            "lib64/ld-linux-x86-64.so.2",
+            "opt/app/lib/libfoo.so.1",
+        ] {
+            assert!(out.path().join(file).is_file(), "{file}");
+        }
+        for dir in MOUNT_POINTS {
+            assert!(out.path().join(dir).is_dir(), "{dir}");
+        }
+        assert_eq!(
+            std::fs::read(out.path().join("opt/app/bin/app")).expect("read"),
+            std::fs::read(&app).expect("read")
+        );
+    }
+
+    #[test]
+    fn build_rootfs_refuses_non_empty_output() {
+        let out = tempfile::tempdir().expect("tempdir");
+        std::fs::write(out.path().join("stale"), b"").expect("write");
+        let error = build_minimal_rootfs(Path::new("/nonexistent/binary"), out.path())
+            .expect_err("non-empty output")
+            .to_string();
+        assert!(error.contains("is not empty"), "{error}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn absolute_symlinks_resolve_inside_the_root() {
+        use std::os::unix::fs::symlink;
+
+        let root = tempfile::tempdir().expect("tempdir");
+        let out = tempfile::tempdir().expect("tempdir");
+        let loader = "/lib64/ld-containust-test.so.2";
+        let app = root.path().join("bin/app");
+        write(&app, &fake_elf(Some(loader), &["libfoo.so.1"], None));
+        // Neither target exists on the host, only inside the image.
+        write(
+            &root.path().join("containust-test/ld.so"),
+            &fake_elf(None, &[], None),
+        );
+        write(
+            &root.path().join("containust-test/libfoo.so.1.0"),
+            &fake_elf(None, &[], None),
+        );
+        std::fs::create_dir_all(root.path().join("lib64")).expect("mkdir");
+        symlink("/containust-test/ld.so", root.path().join(&loader[1..])).expect("symlink");
+        std::fs::create_dir_all(root.path().join("lib")).expect("mkdir");
+        symlink(
+            "../../../containust-test/libfoo.so.1.0",
+            root.path().join("lib/libfoo.so.1"),
+        )
+        .expect("symlink");
+
+        DistrolessAnalyzer::new()
+            .root(root.path())
+            .search_paths(vec![PathBuf::from("/lib")])
+            .build_rootfs(&app, out.path())
+            .expect("build rootfs");
+        for file in [&loader[1..], "lib/libfoo.so.1"] {
+            assert!(out.path().join(file).is_file(), "{file}");
+        }
+    }
+
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    #[test]
+    fn analyzes_the_running_test_binary() {
+        let exe = std::env::current_exe().expect("current exe");
+        let libs = analyze_binary(&exe).expect("analyze");
+        assert!(
+            libs.iter()
+                .any(|lib| lib.to_string_lossy().contains("libc.so")),
+            "{libs:?}"
+        );
+    }
+}
@@ -1,15 +1,59 @@
 //! Dependency graph management using `petgraph`.
 //!
 //! Builds a directed acyclic graph from component connections
-//! and resolves topological ordering for deployment.
+//! and resolves topological ordering for deployment. The graph can be
+//! rendered as Graphviz DOT, JSON, or Mermaid for `ctst graph`.
+
+use std::fmt::Write as _;
 
 use containust_common::error::{ContainustError, Result};
+use petgraph::visit::EdgeRef;
+use serde::Serialize;
+
+use crate::parser::ast::CompositionFile;
+
+/// Why one component depends on another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// A `CONNECT` wiring: the dependent also receives connection env vars.
+    Connection,
+    /// A pure start-order dependency without wiring.
+    DependsOn,
+}
 
 /// A dependency graph of components.
 #[derive(Debug)]
 pub struct DependencyGraph {
     /// Internal petgraph representation.
-    graph: petgraph::Graph<String, ()>,
+    graph: petgraph::Graph<String, EdgeKind>,
+}
+
+/// Serializable node/edge view of a [`DependencyGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphView {
+    /// Component names in declaration order.
+    pub nodes: Vec<GraphNode>,
+    /// Edges from dependency to dependent.
+    pub edges: Vec<GraphEdge>,
+}
+
+/// A component in a [`GraphView`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphNode {
+    /// Component name.
+    pub name: String,
+}
+
+/// An edge in a [`GraphView`], pointing from the dependency to the dependent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    /// Component that must start first.
+    pub from: String,
+    /// Component that depends on `from`.
+    pub to: String,
+    /// Kind of dependency.
+    pub kind: EdgeKind,
 }
 
 impl DependencyGraph {
@@ -26,6 +70,43 @@ impl DependencyGraph {
         self.graph.add_node(name.into())
     }
 
+    /// Builds the graph of a composition's components with its `CONNECT`
+    /// and `DEPENDS_ON` edges.
+    ///
+    /// Edges naming unknown components are skipped; the validator
+    /// reports them.
+    #[must_use]
+    pub fn from_composition(composition: &CompositionFile) -> Self {
+        let mut graph = Self::new();
+        let nodes: std::collections::HashMap<&str, petgraph::graph::NodeIndex> = composition
+            .components
+            .iter()
+            .map(|component| {
+                (
+                    component.name.as_str(),
+                    graph.add_component(&component.name),
+                )
+            })
+            .collect();
+        for connection in &composition.connections {
+            if let (Some(&from), Some(&to)) = (
+                nodes.get(connection.from.as_str()),
+                nodes.get(connection.to.as_str()),
+            ) {
+                graph.add_dependency(from, to);
+            }
+        }
+        for dependency in &composition.dependencies {
+            if let (Some(&from), Some(&to)) = (
+                nodes.get(dependency.from.as_str()),
+                nodes.get(dependency.to.as_str()),
+            ) {
+                graph.add_edge(from, to, EdgeKind::DependsOn);
+            }
+        }
+        graph
+    }
+
     /// Adds a dependency edge: `dependent` depends on `dependency`.
     ///
     /// The graph edge points from `dependency` to `dependent`
@@ -35,7 +116,103 @@ impl DependencyGraph {
         dependent: petgraph::graph::NodeIndex,
         dependency: petgraph::graph::NodeIndex,
     ) {
-        let _ = self.graph.add_edge(dependency, dependent, ());
+        self.add_edge(dependent, dependency, EdgeKind::Connection);
+    }
+
+    /// Adds a dependency edge of the given kind.
+    pub fn add_edge(
+        &mut self,
+        dependent: petgraph::graph::NodeIndex,
+        dependency: petgraph::graph::NodeIndex,
+        kind: EdgeKind,
+    ) {
+        let _ = self.graph.add_edge(dependency, dependent, kind);
+    }
+
+    /// Returns the nodes and edges in insertion order.
+    #[must_use]
+    pub fn view(&self) -> GraphView {
+        let nodes = self
+            .graph
+            .node_weights()
+            .map(|name| GraphNode { name: name.clone() })
+            .collect();
+        let edges = self
+            .graph
+            .edge_references()
+            .map(|edge| GraphEdge {
+                from: self.graph[edge.source()].clone(),
+                to: self.graph[edge.target()].clone(),
+                kind: *edge.weight(),
+            })
+            .collect();
+        GraphView { nodes, edges }
+    }
+
+    /// Renders the graph in Graphviz DOT.
+    ///
+    /// Connections are solid edges; depends-on edges are dashed.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let view = self.view();
+        let mut dot = String::from("digraph containust {\n    rankdir=LR;\n");
+        for node in &view.nodes {
+            let _ = writeln!(dot, "    \"{}\";", node.name);
+        }
+        for edge in &view.edges {
+            let style = match edge.kind {
+                EdgeKind::Connection => "",
+                EdgeKind::DependsOn => " [style=dashed]",
+            };
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\"{style};", edge.from, edge.to);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the graph as pretty-printed JSON of [`GraphView`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.view())?)
+    }
+
+    /// Renders the graph as a Mermaid flowchart.
+    ///
+    /// Connections use `-->`; depends-on edges use the dotted `-.->`.
+    #[must_use]
+    pub fn to_mermaid(&self) -> String {
+        let view = self.view();
+        let mut mermaid = String::from("graph LR\n");
+        for node in &view.nodes {
+            let _ = writeln!(mermaid, "    {}[\"{}\"]", mermaid_id(&node.name), node.name);
+        }
+        for edge in &view.edges {
+            let arrow = match edge.kind {
+                EdgeKind::Connection => "-->",
+                EdgeKind::DependsOn => "-.->",
+            };
+            let _ = writeln!(
+                mermaid,
+                "    {} {arrow} {}",
+                mermaid_id(&edge.from),
+                mermaid_id(&edge.to)
+            );
+        }
+        mermaid
+    }
+
+    /// Returns the components with no edge in either direction, in
+    /// declaration order.
+    #[must_use]
+    pub fn isolated_nodes(&self) -> Vec<String> {
+        self.graph
+            .node_indices()
+            .filter(|&idx| self.graph.neighbors_undirected(idx).next().is_none())
+            .map(|idx| self.graph[idx].clone())
+            .collect()
     }
 
     /// Returns a topological ordering of components for deployment.
@@ -68,6 +245,11 @@ impl Default for DependencyGraph {
     }
 }
 
+/// Mermaid node ids cannot contain `-`, which would read as an arrow.
+fn mermaid_id(name: &str) -> String {
+    name.replace('-', "_")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +261,21 @@ mod tests {
         assert!(order.is_empty());
     }
 
+    #[test]
+    fn isolated_nodes_skip_connected_components() {
+        let mut graph = DependencyGraph::new();
+        let api = graph.add_component("api");
+        let db = graph.add_component("db");
+        let _ = graph.add_component("stray");
+        let worker = graph.add_component("worker");
+        let queue = graph.add_component("queue");
+        let _ = graph.add_component("copy");
+        graph.add_dependency(api, db);
+        graph.add_edge(worker, queue, EdgeKind::DependsOn);
+        assert_eq!(graph.isolated_nodes(), ["stray", "copy"]);
+        assert!(DependencyGraph::new().isolated_nodes().is_empty());
+    }
+
     #[test]
     fn single_node_resolves() {
         let mut graph = DependencyGraph::new();
@@ -162,4 +359,80 @@ mod tests {
         assert!(order.contains(&"y".to_string()));
         assert!(order.contains(&"z".to_string()));
     }
+
+    fn api_db_graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        let api = graph.add_component("api");
+        let db = graph.add_component("db");
+        let cache = graph.add_component("my-cache");
+        graph.add_dependency(api, db);
+        graph.add_edge(api, cache, EdgeKind::DependsOn);
+        graph
+    }
+
+    #[test]
+    fn from_composition_wires_connections() {
+        let composition = crate::parser::parse_ctst(
+            "COMPONENT api { image = \"file:///a\" }\n\
+             COMPONENT db { image = \"file:///d\" }\n\
+             CONNECT api -> db\n",
+        )
+        .expect("parse");
+        let view = DependencyGraph::from_composition(&composition).view();
+        assert_eq!(view.nodes.len(), 2);
+        assert_eq!(view.edges[0].from, "db");
+        assert_eq!(view.edges[0].to, "api");
+    }
+
+    #[test]
+    fn from_composition_orders_depends_on_edges() {
+        let composition = crate::parser::parse_ctst(
+            "COMPONENT app { image = \"file:///a\" }\n\
+             COMPONENT migrate { image = \"file:///m\" }\n\
+             DEPENDS_ON app -> migrate\n",
+        )
+        .expect("parse");
+        let graph = DependencyGraph::from_composition(&composition);
+        assert_eq!(
+            graph.resolve_order().expect("order"),
+            vec!["migrate", "app"]
+        );
+        let view = graph.view();
+        assert_eq!(view.edges[0].from, "migrate");
+        assert_eq!(view.edges[0].kind, EdgeKind::DependsOn);
+    }
+
+    #[test]
+    fn to_dot_lists_nodes_and_styles_edges() {
+        let dot = api_db_graph().to_dot();
+        assert!(dot.starts_with("digraph containust {"), "{dot}");
+        assert!(dot.contains("\"api\";"), "{dot}");
+        assert!(dot.contains("\"db\" -> \"api\";"), "{dot}");
+        assert!(
+            dot.contains("\"my-cache\" -> \"api\" [style=dashed];"),
+            "{dot}"
+        );
+    }
+
+    #[test]
+    fn to_json_has_nodes_and_kinded_edges() {
+        let json: serde_json::Value =
+            serde_json::from_str(&api_db_graph().to_json().expect("json")).expect("parse");
+        assert_eq!(json["nodes"][0]["name"], "api");
+        assert_eq!(json["nodes"][1]["name"], "db");
+        assert_eq!(json["edges"][0]["from"], "db");
+        assert_eq!(json["edges"][0]["to"], "api");
+        assert_eq!(json["edges"][0]["kind"], "connection");
+        assert_eq!(json["edges"][1]["kind"], "depends_on");
+    }
+
+    #[test]
+    fn to_mermaid_uses_safe_ids_and_arrow_styles() {
+        let mermaid = api_db_graph().to_mermaid();
+        assert!(mermaid.starts_with("graph LR\n"), "{mermaid}");
+        assert!(mermaid.contains("db[\"db\"]"), "{mermaid}");
+        assert!(mermaid.contains("my_cache[\"my-cache\"]"), "{mermaid}");
+        assert!(mermaid.contains("db --> api"), "{mermaid}");
+        assert!(mermaid.contains("my_cache -.-> api"), "{mermaid}");
+    }
 }
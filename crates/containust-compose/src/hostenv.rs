@@ -0,0 +1,251 @@
+//! Host environment expansion in string values.
+//!
+//! `${env:VAR}` is replaced with the host's `VAR` when a `.ctst` file is
+//! parsed, and `${env:VAR:-fallback}` uses `fallback` when `VAR` is unset.
+//! The `env:` prefix keeps this distinct from `${component.field}`
+//! references, which the resolver wires between components at deploy
+//! time and which this pass leaves untouched.
+
+use containust_common::error::{ContainustError, Result};
+
+use crate::parser::ast::{ComponentDecl, CompositionFile};
+
+/// Opening of a host env reference.
+pub(crate) const PREFIX: &str = "${env:";
+
+/// Expands `${env:...}` in every string value of `file` from the process
+/// environment.
+///
+/// # Errors
+///
+/// Returns an error naming the variable if one is undefined and has no
+/// default, or if a reference is malformed.
+pub fn expand_host_env(file: &mut CompositionFile) -> Result<()> {
+    expand_file(file, &|name| std::env::var(name).ok())
+}
+
+/// Expands `${env:...}` in every string value of `file` using `lookup`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`expand_host_env`].
+pub fn expand_file(
+    file: &mut CompositionFile,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<()> {
+    for import in &mut file.imports {
+        expand_in_place(&mut import.source, lookup)?;
+    }
+    for component in &mut file.components {
+        expand_component(component, lookup)?;
+    }
+    Ok(())
+}
+
+fn expand_component(
+    component: &mut ComponentDecl,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<()> {
+    let optional = [
+        &mut component.image,
+        &mut component.memory,
+        &mut component.memory_swap,
+        &mut component.memory_reservation,
+        &mut component.memory_high,
+        &mut component.cpu,
+        &mut component.cpu_max,
+        &mut component.cpuset,
+        &mut component.numa_node,
+        &mut component.volume,
+        &mut component.workdir,
+        &mut component.user,
+        &mut component.hostname,
+        &mut component.restart,
+        &mut component.network,
+        &mut component.stop_signal,
+        &mut component.stop_timeout,
+    ];
+    for value in optional.into_iter().flatten() {
+        expand_in_place(value, lookup)?;
+    }
+    let lists = [
+        Some(&mut component.volumes),
+        Some(&mut component.command),
+        component.entrypoint.as_mut(),
+        component
+            .healthcheck
+            .as_mut()
+            .map(|check| &mut check.command),
+        component.readiness.as_mut().map(|check| &mut check.command),
+        component.pre_start.as_mut().map(|hook| &mut hook.command),
+        component.post_start.as_mut().map(|hook| &mut hook.command),
+    ];
+    for value in lists.into_iter().flatten().flatten() {
+        expand_in_place(value, lookup)?;
+    }
+    let urls = [
+        component.healthcheck.as_mut().and_then(|c| c.http.as_mut()),
+        component.readiness.as_mut().and_then(|c| c.http.as_mut()),
+    ];
+    for value in urls.into_iter().flatten() {
+        expand_in_place(value, lookup)?;
+    }
+    for value in component.env.values_mut() {
+        expand_in_place(value, lookup)?;
+    }
+    Ok(())
+}
+
+fn expand_in_place(value: &mut String, lookup: &dyn Fn(&str) -> Option<String>) -> Result<()> {
+    if value.contains(PREFIX) {
+        *value = expand_str(value, lookup)?;
+    }
+    Ok(())
+}
+
+/// Expands every `${env:VAR}` / `${env:VAR:-default}` in `input`.
+///
+/// # Errors
+///
+/// Returns an error for an undefined variable without default, an
+/// invalid variable name, or a missing closing `}`.
+pub fn expand_str(input: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find(PREFIX) {
+        output.push_str(&rest[..start]);
+        let body_start = start + PREFIX.len();
+        let Some(length) = rest[body_start..].find('}') else {
+            return Err(expand_err(format!(
+                "unterminated host env reference in \"{input}\": missing '}}'"
+            )));
+        };
+        let body = &rest[body_start..body_start + length];
+        let (name, default) = body
+            .split_once(":-")
+            .map_or((body, None), |(name, default)| (name, Some(default)));
+        if !is_valid_name(name) {
+            return Err(expand_err(format!(
+                "invalid host env variable name '{name}' in \"{input}\""
+            )));
+        }
+        match lookup(name).or_else(|| default.map(str::to_string)) {
+            Some(value) => output.push_str(&value),
+            None => {
+                return Err(expand_err(format!(
+                    "undefined host environment variable '{name}' in \"{input}\"; \
+                     set it or give a default with ${{env:{name}:-default}}"
+                )));
+            }
+        }
+        rest = &rest[body_start + length + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Returns true for POSIX-style variable names (`[A-Za-z_][A-Za-z0-9_]*`).
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+const fn expand_err(message: String) -> ContainustError {
+    ContainustError::Config { message }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::literal_string_with_formatting_args)]
+
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/dev".into()),
+            "TAG" => Some("1.2".into()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expand_str_substitutes_host_vars() {
+        let expanded = expand_str("file://${env:HOME}/images/app-${env:TAG}", &lookup).unwrap();
+        assert_eq!(expanded, "file:///home/dev/images/app-1.2");
+    }
+
+    #[test]
+    fn expand_str_uses_default_only_when_unset() {
+        assert_eq!(
+            expand_str("${env:MISSING:-fallback}", &lookup).unwrap(),
+            "fallback"
+        );
+        assert_eq!(expand_str("${env:TAG:-0.0}", &lookup).unwrap(), "1.2");
+        assert_eq!(expand_str("x${env:MISSING:-}y", &lookup).unwrap(), "xy");
+    }
+
+    #[test]
+    fn expand_str_rejects_undefined_var_without_default() {
+        let error = expand_str("${env:MISSING}", &lookup)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'MISSING'"), "{error}");
+        assert!(error.contains("${env:MISSING:-default}"), "{error}");
+    }
+
+    #[test]
+    fn expand_str_rejects_malformed_references() {
+        assert!(expand_str("${env:HOME", &lookup).is_err());
+        assert!(expand_str("${env:1BAD}", &lookup).is_err());
+    }
+
+    #[test]
+    fn expand_leaves_component_references_alone() {
+        let value = "postgres://${db.host}:${db.port}/${env:TAG}";
+        assert_eq!(
+            expand_str(value, &lookup).unwrap(),
+            "postgres://${db.host}:${db.port}/1.2"
+        );
+    }
+
+    #[test]
+    fn expand_file_covers_component_strings() {
+        let mut file = CompositionFile {
+            components: vec![ComponentDecl {
+                name: "web".into(),
+                image: Some("file://${env:HOME}/web".into()),
+                command: vec!["serve".into(), "--tag=${env:TAG}".into()],
+                env: [
+                    ("URL".to_string(), "http://${api.host}".to_string()),
+                    ("MODE".to_string(), "${env:MODE:-dev}".to_string()),
+                ]
+                .into(),
+                ..ComponentDecl::default()
+            }],
+            ..CompositionFile::default()
+        };
+        expand_file(&mut file, &lookup).unwrap();
+        let web = &file.components[0];
+        assert_eq!(web.image.as_deref(), Some("file:///home/dev/web"));
+        assert_eq!(web.command, vec!["serve", "--tag=1.2"]);
+        assert_eq!(web.env["URL"], "http://${api.host}");
+        assert_eq!(web.env["MODE"], "dev");
+    }
+
+    #[test]
+    fn parse_ctst_expands_before_validation() {
+        let file = crate::parser::parse_ctst(
+            "COMPONENT web {\n    image = \"${env:CONTAINUST_TEST_UNSET_IMAGE:-file:///opt/web}\"\n}\n",
+        )
+        .unwrap();
+        assert_eq!(file.components[0].image.as_deref(), Some("file:///opt/web"));
+        let error = crate::parser::parse_ctst(
+            "COMPONENT web {\n    image = \"${env:CONTAINUST_TEST_UNSET_IMAGE}\"\n}\n",
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("CONTAINUST_TEST_UNSET_IMAGE"));
+    }
+}
@@ -0,0 +1,205 @@
+//! Where a composition is read from: a `.ctst` file or standard input.
+//!
+//! Commands that take a composition path accept `-` to read it from
+//! stdin (`generate | ctst run -`). A stdin composition has no file of
+//! its own, so the current working directory stands in for the file's
+//! parent: relative `IMPORT`s resolve against it and the project state
+//! lives in `./.containust/`.
+
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+
+use containust_common::error::{ContainustError, Result};
+
+use crate::parser::ParseLimits;
+use crate::parser::ast::CompositionFile;
+
+/// Path argument that selects standard input.
+pub const STDIN_ARG: &str = "-";
+
+/// Origin of a composition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompositionSource {
+    /// Read from standard input.
+    Stdin,
+    /// Read from a `.ctst` file.
+    File(PathBuf),
+}
+
+impl CompositionSource {
+    /// Interprets a CLI path argument, mapping `-` to [`Self::Stdin`].
+    #[must_use]
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == STDIN_ARG {
+            Self::Stdin
+        } else {
+            Self::File(PathBuf::from(arg))
+        }
+    }
+
+    /// Returns true when the source is standard input.
+    #[must_use]
+    pub const fn is_stdin(&self) -> bool {
+        matches!(self, Self::Stdin)
+    }
+
+    /// Reads the raw composition text.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, or if stdin is an
+    /// interactive terminal rather than a pipe or redirect.
+    pub fn read(&self) -> Result<String> {
+        match self {
+            Self::File(path) => {
+                std::fs::read_to_string(path).map_err(|source| ContainustError::Io {
+                    path: path.clone(),
+                    source,
+                })
+            }
+            Self::Stdin => {
+                let stdin = std::io::stdin();
+                if stdin.is_terminal() {
+                    return Err(ContainustError::Config {
+                        message: "refusing to read a composition from an interactive terminal; \
+                                  pipe it in, e.g. `generate | ctst run -`"
+                            .into(),
+                    });
+                }
+                read_all(stdin.lock())
+            }
+        }
+    }
+
+    /// Reads and parses the composition.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading or parsing fails.
+    pub fn parse(&self) -> Result<CompositionFile> {
+        crate::parser::parse_ctst(&self.read()?)
+    }
+
+    /// Directory relative `IMPORT`s resolve against.
+    ///
+    /// The file's parent for [`Self::File`]; the current working
+    /// directory for [`Self::Stdin`].
+    #[must_use]
+    pub fn base_dir(&self) -> PathBuf {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        self.base_dir_from(&cwd)
+    }
+
+    /// Path whose parent directory anchors the project's `.containust/`.
+    ///
+    /// Pass this to `project_dir`-style helpers that expect a `.ctst` path.
+    #[must_use]
+    pub fn project_anchor(&self) -> PathBuf {
+        match self {
+            Self::File(path) => path.clone(),
+            Self::Stdin => self.base_dir().join(STDIN_ARG),
+        }
+    }
+
+    /// Human-readable label for messages.
+    #[must_use]
+    pub fn display(&self) -> String {
+        match self {
+            Self::File(path) => path.display().to_string(),
+            Self::Stdin => "<stdin>".into(),
+        }
+    }
+
+    fn base_dir_from(&self, cwd: &Path) -> PathBuf {
+        match self {
+            Self::Stdin => cwd.to_path_buf(),
+            Self::File(path) => match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => cwd.to_path_buf(),
+            },
+        }
+    }
+}
+
+/// Reads `reader` to the end as UTF-8 composition text.
+///
+/// Stops one byte past [`ParseLimits::DEFAULT_MAX_INPUT_BYTES`] so an
+/// endless pipe cannot exhaust memory; the parser then rejects the
+/// oversized input.
+///
+/// # Errors
+///
+/// Returns an error if reading fails or the input is not valid UTF-8.
+pub fn read_all(reader: impl Read) -> Result<String> {
+    let mut content = String::new();
+    let limit = u64::try_from(ParseLimits::DEFAULT_MAX_INPUT_BYTES).unwrap_or(u64::MAX);
+    let _ = reader
+        .take(limit.saturating_add(1))
+        .read_to_string(&mut content)
+        .map_err(|source| ContainustError::Io {
+            path: PathBuf::from("<stdin>"),
+            source,
+        })?;
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPOSITION: &str = r#"
+IMPORT "base.ctst"
+COMPONENT web {
+    image = "file:///opt/web"
+}
+"#;
+
+    #[test]
+    fn from_arg_maps_dash_to_stdin() {
+        assert!(CompositionSource::from_arg("-").is_stdin());
+        assert_eq!(
+            CompositionSource::from_arg("app.ctst"),
+            CompositionSource::File(PathBuf::from("app.ctst"))
+        );
+    }
+
+    #[test]
+    fn read_all_parses_piped_content() {
+        let content = read_all(COMPOSITION.as_bytes()).unwrap();
+        let file = crate::parser::parse_ctst(&content).unwrap();
+        assert_eq!(file.components[0].name, "web");
+        assert_eq!(file.imports[0].source, "base.ctst");
+    }
+
+    #[test]
+    fn stdin_imports_resolve_against_cwd() {
+        let cwd = tempfile::tempdir().unwrap();
+        std::fs::write(
+            cwd.path().join("base.ctst"),
+            "COMPONENT db {\n    image = \"file:///opt/db\"\n}\n",
+        )
+        .unwrap();
+        let base = CompositionSource::Stdin.base_dir_from(cwd.path());
+        assert_eq!(base, cwd.path());
+        let imported = crate::import::resolve_import("base.ctst", &base).unwrap();
+        assert_eq!(imported.components[0].name, "db");
+    }
+
+    #[test]
+    fn file_base_dir_is_parent_or_cwd() {
+        let cwd = Path::new("/work");
+        let nested = CompositionSource::File(PathBuf::from("/srv/app/app.ctst"));
+        assert_eq!(nested.base_dir_from(cwd), Path::new("/srv/app"));
+        let bare = CompositionSource::File(PathBuf::from("app.ctst"));
+        assert_eq!(bare.base_dir_from(cwd), cwd);
+    }
+
+    #[test]
+    fn stdin_project_anchor_lives_in_cwd() {
+        let anchor = CompositionSource::Stdin.project_anchor();
+        assert_eq!(
+            anchor.parent(),
+            Some(std::env::current_dir().unwrap().as_path())
+        );
+    }
+}
@@ -7,7 +7,9 @@
 //! - **Graph**: Dependency graph construction and topological resolution.
 //! - **Resolver**: Auto-wiring of environment variables between components.
 //! - **Component**: COMPONENT block definitions and parameterization.
+//! - **Host env**: `${env:VAR}` expansion from the host environment.
 //! - **Import**: IMPORT resolution from files and network.
+//! - **Input**: reading compositions from files or stdin (`-`).
 //! - **Distroless**: Binary dependency analysis for minimal images.
 
 #![cfg_attr(test, allow(clippy::expect_used, clippy::unwrap_used))]
@@ -15,7 +17,9 @@
 pub mod component;
 pub mod distroless;
 pub mod graph;
+pub mod hostenv;
 pub mod import;
+pub mod input;
 pub mod parser;
 pub mod resolver;
 
@@ -111,6 +115,9 @@ mod offline_tests {
                 ..ComponentDecl::default()
             }],
             connections: Vec::new(),
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
         assert!(validate_offline(&file).is_ok());
     }
@@ -2,17 +2,98 @@
 
 use std::collections::BTreeMap;
 
+use containust_common::types::{ResourceLimits, RestartPolicy};
+use containust_core::capability::Capability;
+use containust_core::cgroup::cpu::CPU_PERIOD_RANGE_US;
+
 /// Root node of a parsed `.ctst` file.
 #[derive(Debug, Clone, Default)]
 pub struct CompositionFile {
+    /// Declared language version (`SCHEMA "1.0"`); `None` when absent.
+    pub schema: Option<SchemaVersion>,
     /// Import declarations.
     pub imports: Vec<ImportDecl>,
     /// Component definitions.
     pub components: Vec<ComponentDecl>,
     /// Connection declarations.
     pub connections: Vec<ConnectionDecl>,
+    /// Start-order dependencies without env wiring (`DEPENDS_ON`).
+    pub dependencies: Vec<DependencyDecl>,
     /// Host port publications (`EXPOSE`).
     pub exposes: Vec<ExposeDecl>,
+    /// Composition-wide component defaults (`DEFAULTS`).
+    pub defaults: Option<DefaultsDecl>,
+}
+
+impl CompositionFile {
+    /// Language version the file targets: its `SCHEMA` declaration, or
+    /// [`SchemaVersion::LATEST`] when it declares none.
+    #[must_use]
+    pub fn schema_version(&self) -> SchemaVersion {
+        self.schema.unwrap_or(SchemaVersion::LATEST)
+    }
+}
+
+/// A `.ctst` language version, declared as `SCHEMA "<major>.<minor>"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SchemaVersion {
+    /// Incremented when existing syntax changes meaning or is removed.
+    pub major: u32,
+    /// Incremented when syntax is added.
+    pub minor: u32,
+}
+
+impl SchemaVersion {
+    /// Oldest version this parser accepts.
+    pub const MIN_SUPPORTED: Self = Self { major: 1, minor: 0 };
+    /// Newest version this parser understands; assumed when a file
+    /// declares none.
+    pub const LATEST: Self = Self { major: 1, minor: 0 };
+
+    /// Parses `"<major>.<minor>"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the value when it is not two dot-separated
+    /// integers.
+    pub fn parse(value: &str) -> std::result::Result<Self, String> {
+        let invalid = || format!("invalid schema version '{value}' (expected \"<major>.<minor>\")");
+        let (major, minor) = value.split_once('.').ok_or_else(invalid)?;
+        let number = |part: &str| {
+            if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            part.parse::<u32>().map_err(|_| invalid())
+        };
+        Ok(Self {
+            major: number(major)?,
+            minor: number(minor)?,
+        })
+    }
+
+    /// Whether this parser accepts files declaring this version.
+    #[must_use]
+    pub fn is_supported(self) -> bool {
+        (Self::MIN_SUPPORTED..=Self::LATEST).contains(&self)
+    }
+}
+
+impl std::fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// A `DEFAULTS` block applied to every component.
+///
+/// A component's own `network` wins; `env` merges key-wise with the
+/// component's entries taking precedence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DefaultsDecl {
+    /// Network mode for components that declare none.
+    pub network: Option<String>,
+    /// Environment variables added to every component.
+    pub env: BTreeMap<String, String>,
 }
 
 /// An `IMPORT` declaration.
@@ -39,20 +120,42 @@ pub struct ComponentDecl {
     pub ports: Vec<u16>,
     /// Memory limit string (e.g., "256MiB").
     pub memory: Option<String>,
+    /// `memory` validated and normalized at parse time; `None` when unset
+    /// or `"unlimited"`.
+    pub memory_spec: Option<MemorySpec>,
+    /// Memory plus swap limit string, as in Docker's `--memory-swap`.
+    pub memory_swap: Option<String>,
+    /// Memory protected from reclaim (`memory.low`).
+    pub memory_reservation: Option<String>,
+    /// Memory throttling threshold (`memory.high`).
+    pub memory_high: Option<String>,
     /// CPU shares string.
     pub cpu: Option<String>,
+    /// Hard CPU quota as written to `cpu.max`: `"<quota_us>"` or
+    /// `"<quota_us> <period_us>"`.
+    pub cpu_max: Option<String>,
+    /// Cores to pin the container to, as a cpu-list (`"0-3"`, `"0,2,4"`).
+    pub cpuset: Option<String>,
+    /// NUMA nodes to allocate memory from, as a node list (`"0"`, `"0-1"`).
+    pub numa_node: Option<String>,
+    /// Size limits of the container's IPC namespace.
+    pub ipc: Option<IpcDecl>,
     /// Environment variables.
     pub env: BTreeMap<String, String>,
+    /// Host environment variables copied into the container when set.
+    pub inherit_env: Vec<String>,
     /// Single volume mount.
     pub volume: Option<String>,
     /// Multiple volume mounts.
     pub volumes: Vec<String>,
-    /// Command to run.
+    /// Default arguments appended to the entrypoint.
     pub command: Vec<String>,
-    /// Entrypoint prepended to the command.
+    /// Fixed executable prepended to the command (string or list form).
     pub entrypoint: Option<Vec<String>>,
     /// Read-only root filesystem.
     pub readonly: Option<bool>,
+    /// Keep the main process's stdin open for `ctst attach`.
+    pub stdin: Option<bool>,
     /// Working directory.
     pub workdir: Option<String>,
     /// User to run as.
@@ -63,8 +166,113 @@ pub struct ComponentDecl {
     pub restart: Option<String>,
     /// Network mode.
     pub network: Option<String>,
-    /// Healthcheck configuration.
+    /// Linux capabilities kept by the main process (e.g.
+    /// `"CAP_NET_BIND_SERVICE"`); every other capability is dropped.
+    pub capabilities: Vec<String>,
+    /// Signal requesting a graceful stop (e.g. `"SIGQUIT"`).
+    pub stop_signal: Option<String>,
+    /// Grace period before a stop escalates to a kill (e.g. `"30s"`).
+    pub stop_timeout: Option<String>,
+    /// Liveness probe (`healthcheck` or `liveness`); failures trigger a
+    /// restart according to the restart policy.
     pub healthcheck: Option<HealthcheckDecl>,
+    /// Readiness probe; dependents start only once it passes.
+    pub readiness: Option<HealthcheckDecl>,
+    /// Host command run before the container starts.
+    pub pre_start: Option<HookDecl>,
+    /// Command executed inside the container once it has started (and
+    /// passed its readiness probe, if any).
+    pub post_start: Option<HookDecl>,
+}
+
+impl ComponentDecl {
+    /// Parses the `restart` property; unset means [`RestartPolicy::Never`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the value when it names no policy.
+    pub fn restart_policy(&self) -> std::result::Result<RestartPolicy, String> {
+        self.restart
+            .as_deref()
+            .map_or(Ok(RestartPolicy::Never), RestartPolicy::parse)
+    }
+
+    /// Parses the `cpu_max` property into a quota and an optional period,
+    /// both in microseconds; unset means no quota.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the value when it is malformed or outside
+    /// the range the kernel accepts.
+    pub fn cpu_max_spec(&self) -> std::result::Result<Option<(u64, Option<u64>)>, String> {
+        let Some(raw) = self.cpu_max.as_deref() else {
+            return Ok(None);
+        };
+        let invalid = || {
+            format!(
+                "invalid cpu_max '{raw}' (expected \"<quota_us>\" or \"<quota_us> <period_us>\")"
+            )
+        };
+        let numbers = raw
+            .split_whitespace()
+            .map(|field| field.parse::<u64>().map_err(|_| invalid()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let (quota, period) = match numbers[..] {
+            [quota] => (quota, None),
+            [quota, period] => (quota, Some(period)),
+            _ => return Err(invalid()),
+        };
+        if quota < ResourceLimits::MIN_CPU_QUOTA_US {
+            return Err(format!(
+                "cpu_max quota {quota}us is below the kernel minimum of {}us",
+                ResourceLimits::MIN_CPU_QUOTA_US
+            ));
+        }
+        if let Some(period) = period.filter(|period| !CPU_PERIOD_RANGE_US.contains(period)) {
+            return Err(format!(
+                "cpu_max period {period}us is outside {}..={}us",
+                CPU_PERIOD_RANGE_US.start(),
+                CPU_PERIOD_RANGE_US.end()
+            ));
+        }
+        Ok(Some((quota, period)))
+    }
+
+    /// Parses the `capabilities` property into the capabilities to keep.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first name that is no capability.
+    pub fn capability_set(&self) -> std::result::Result<Vec<Capability>, String> {
+        self.capabilities
+            .iter()
+            .map(|name| Capability::parse(name).map_err(|_| format!("unknown capability '{name}'")))
+            .collect()
+    }
+}
+
+/// A lifecycle hook command inside a component.
+///
+/// Written either as a plain list (`post_start = ["migrate"]`, required)
+/// or as a block (`post_start = { command = ["migrate"], optional = true }`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HookDecl {
+    /// Command and arguments.
+    pub command: Vec<String>,
+    /// When true, a failing hook is logged instead of aborting the deploy.
+    pub optional: bool,
+}
+
+/// IPC namespace limits inside a component:
+/// `ipc = { shmmax = "64MiB", shmall = 16384, msgmni = 32 }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpcDecl {
+    /// Largest shared memory segment, as a size string.
+    pub shmmax: Option<String>,
+    /// Total shared memory, in pages.
+    pub shmall: Option<u64>,
+    /// Maximum number of message queues.
+    pub msgmni: Option<u32>,
 }
 
 /// Healthcheck configuration inside a component.
@@ -72,6 +280,12 @@ pub struct ComponentDecl {
 pub struct HealthcheckDecl {
     /// Command to run for health check.
     pub command: Vec<String>,
+    /// URL fetched from the host instead of running `command`.
+    pub http: Option<String>,
+    /// HTTP status the `http` probe must return.
+    pub expect: Option<u16>,
+    /// Port the host connects to over TCP instead of running `command`.
+    pub tcp: Option<u16>,
     /// Check interval.
     pub interval: Option<String>,
     /// Timeout per check.
@@ -103,6 +317,22 @@ pub struct ConnectionDecl {
     pub to: String,
 }
 
+/// A memory limit normalized to bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemorySpec {
+    /// Limit in bytes.
+    pub bytes: u64,
+}
+
+/// A `DEPENDS_ON` declaration: ordering only, no env injection.
+#[derive(Debug, Clone)]
+pub struct DependencyDecl {
+    /// Dependent component name.
+    pub from: String,
+    /// Component started first.
+    pub to: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,10 +340,36 @@ mod tests {
     #[test]
     fn composition_file_default_is_empty() {
         let file = CompositionFile::default();
+        assert!(file.schema.is_none());
         assert!(file.imports.is_empty());
         assert!(file.components.is_empty());
         assert!(file.connections.is_empty());
+        assert!(file.dependencies.is_empty());
         assert!(file.exposes.is_empty());
+        assert!(file.defaults.is_none());
+    }
+
+    #[test]
+    fn schema_version_parses_major_minor() {
+        assert_eq!(
+            SchemaVersion::parse("1.0"),
+            Ok(SchemaVersion { major: 1, minor: 0 })
+        );
+        assert_eq!(
+            SchemaVersion::parse("2.13").map(|v| v.to_string()),
+            Ok("2.13".into())
+        );
+        for bad in ["1", "1.", ".0", "1.0.0", "v1.0", "1.-1", ""] {
+            assert!(SchemaVersion::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn schema_version_supported_range() {
+        assert!(SchemaVersion::LATEST.is_supported());
+        assert!(SchemaVersion::MIN_SUPPORTED.is_supported());
+        assert!(!SchemaVersion { major: 0, minor: 9 }.is_supported());
+        assert!(!SchemaVersion { major: 1, minor: 1 }.is_supported());
     }
 
     #[test]
@@ -130,5 +386,8 @@ mod tests {
         assert!(comp.entrypoint.is_none());
         assert!(comp.readonly.is_none());
         assert!(comp.healthcheck.is_none());
+        assert!(comp.readiness.is_none());
+        assert!(comp.pre_start.is_none());
+        assert!(comp.post_start.is_none());
     }
 }
@@ -3,6 +3,8 @@
 //! Produces a stream of [`Token`]s from raw input for the parser to consume.
 //! Whitespace and `//` line comments are discarded between tokens.
 
+use std::fmt::Write as _;
+
 use containust_common::error::{ContainustError, Result};
 use nom::{
     IResult, Parser,
@@ -29,6 +31,12 @@ pub enum Token {
     Connect,
     /// `EXPOSE` keyword.
     Expose,
+    /// `DEFAULTS` keyword.
+    Defaults,
+    /// `DEPENDS_ON` keyword.
+    DependsOn,
+    /// `SCHEMA` keyword.
+    Schema,
     /// Boolean literal `true`.
     True,
     /// Boolean literal `false`.
@@ -65,42 +73,102 @@ fn skip_trivia(input: &str) -> IResult<&str, ()> {
     Ok((input, ()))
 }
 
-/// Parses a double-quoted string literal with basic escape support.
+/// Parses a double-quoted string literal.
+///
+/// Supports `\n`, `\r`, `\t`, `\0`, `\\`, `\"`, `\xHH` (ASCII only),
+/// `\uXXXX`, and `\u{X..}`. An unknown or malformed escape fails with
+/// `ErrorKind::Escaped` positioned at its backslash; a missing closing
+/// quote fails with `ErrorKind::Char`.
 fn string_literal(input: &str) -> IResult<&str, Token> {
     let (input, _) = char('"')(input)?;
     let mut result = String::new();
-    let mut chars = input.char_indices();
-    loop {
-        match chars.next() {
-            Some((idx, '"')) => {
-                let remaining = &input[idx + 1..];
-                return Ok((remaining, Token::StringLiteral(result)));
+    let mut pos = 0;
+    while let Some(c) = input[pos..].chars().next() {
+        match c {
+            '"' => return Ok((&input[pos + 1..], Token::StringLiteral(result))),
+            '\\' if pos + 1 < input.len() => {
+                let escape = &input[pos..];
+                let (decoded, length) = parse_escape(escape).ok_or_else(|| {
+                    nom::Err::Failure(nom::error::Error::new(
+                        escape,
+                        nom::error::ErrorKind::Escaped,
+                    ))
+                })?;
+                result.push(decoded);
+                pos += length;
+            }
+            '\\' => break,
+            _ => {
+                result.push(c);
+                pos += c.len_utf8();
             }
-            Some((_, '\\')) => match chars.next() {
-                Some((_, 'n')) => result.push('\n'),
-                Some((_, 't')) => result.push('\t'),
-                Some((_, '\\')) => result.push('\\'),
-                Some((_, '"')) => result.push('"'),
-                Some((_, c)) => {
-                    result.push('\\');
-                    result.push(c);
-                }
-                None => {
-                    return Err(nom::Err::Failure(nom::error::Error::new(
-                        input,
-                        nom::error::ErrorKind::Char,
-                    )));
-                }
-            },
-            Some((_, c)) => result.push(c),
-            None => {
-                return Err(nom::Err::Failure(nom::error::Error::new(
-                    input,
-                    nom::error::ErrorKind::Char,
-                )));
+        }
+    }
+    Err(nom::Err::Failure(nom::error::Error::new(
+        input,
+        nom::error::ErrorKind::Char,
+    )))
+}
+
+/// Decodes the escape at the start of `escape` (which begins with `\`),
+/// returning the character and the escape's length in bytes.
+fn parse_escape(escape: &str) -> Option<(char, usize)> {
+    let decoded = match escape[1..].chars().next()? {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        '0' => '\0',
+        '\\' => '\\',
+        '"' => '"',
+        'x' => {
+            let hex = escape.get(2..4).filter(|hex| is_hex(hex))?;
+            let value = u8::from_str_radix(hex, 16).ok().filter(u8::is_ascii)?;
+            return Some((char::from(value), 4));
+        }
+        'u' => return parse_unicode_escape(escape),
+        _ => return None,
+    };
+    Some((decoded, 2))
+}
+
+/// Decodes `\uXXXX` (exactly four digits) or `\u{X..}` (one to six digits).
+fn parse_unicode_escape(escape: &str) -> Option<(char, usize)> {
+    let (hex, length) = if let Some(braced) = escape.strip_prefix("\\u{") {
+        let close = braced.find('}').filter(|&close| (1..=6).contains(&close))?;
+        (&braced[..close], close + 4)
+    } else {
+        (escape.get(2..6)?, 6)
+    };
+    if !is_hex(hex) {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some((char::from_u32(value)?, length))
+}
+
+/// Escapes `value` for use between the quotes of a `.ctst` string
+/// literal; [`tokenize`] decodes the result back to `value`.
+#[must_use]
+pub fn escape_ctst_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{{{:x}}}", u32::from(c));
             }
+            c => escaped.push(c),
         }
     }
+    escaped
+}
+
+fn is_hex(text: &str) -> bool {
+    text.chars().all(|c| c.is_ascii_hexdigit())
 }
 
 /// Parses an integer literal (sequence of digits).
@@ -132,6 +200,9 @@ fn identifier_or_keyword(input: &str) -> IResult<&str, Token> {
         "FROM" => Token::From,
         "CONNECT" => Token::Connect,
         "EXPOSE" => Token::Expose,
+        "DEFAULTS" => Token::Defaults,
+        "DEPENDS_ON" => Token::DependsOn,
+        "SCHEMA" => Token::Schema,
         "true" => Token::True,
         "false" => Token::False,
         _ => Token::Identifier(word),
@@ -173,40 +244,126 @@ fn single_token(input: &str) -> IResult<&str, Token> {
 ///
 /// Returns an error if the input contains characters that cannot be tokenized.
 pub fn tokenize(input: &str) -> Result<Vec<Token>> {
+    tokenize_limited(input, usize::MAX)
+}
+
+/// A token together with the 1-based line it starts on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpannedToken {
+    /// The token.
+    pub token: Token,
+    /// Line of the token's first character.
+    pub line: usize,
+}
+
+/// Tokenizes like [`tokenize`], failing once more than `max_tokens` tokens
+/// are produced instead of buffering the whole stream.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be tokenized or exceeds `max_tokens`.
+pub fn tokenize_limited(input: &str, max_tokens: usize) -> Result<Vec<Token>> {
+    Ok(tokenize_spanned(input, max_tokens)?
+        .into_iter()
+        .map(|spanned| spanned.token)
+        .collect())
+}
+
+/// Tokenizes `input`, recording the line each token starts on.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be tokenized (an unterminated
+/// string is reported with its starting line) or exceeds `max_tokens`.
+pub fn tokenize_spanned(input: &str, max_tokens: usize) -> Result<Vec<SpannedToken>> {
     let mut tokens = Vec::new();
     let mut remaining = input;
+    let mut line = 1_usize;
 
     loop {
         let (rest, ()) = skip_trivia(remaining).map_err(|e| ContainustError::Config {
             message: format!("lexer error skipping whitespace: {e}"),
         })?;
+        line += count_newlines(&remaining[..remaining.len() - rest.len()]);
         remaining = rest;
 
         if remaining.is_empty() {
             break;
         }
 
-        let (rest, token) = single_token(remaining).map_err(|e| ContainustError::Config {
-            message: format!(
-                "unexpected character at: \"{}\" ({e})",
-                &remaining[..remaining.len().min(20)]
-            ),
+        let (rest, token) = single_token(remaining).map_err(|e| {
+            let message = if let nom::Err::Failure(failure) = &e
+                && failure.code == nom::error::ErrorKind::Escaped
+            {
+                let offset = remaining.len() - failure.input.len();
+                format!(
+                    "invalid escape sequence \"{}\" at line {}",
+                    escape_preview(failure.input),
+                    line + count_newlines(&remaining[..offset])
+                )
+            } else if remaining.starts_with('"') {
+                format!("unterminated string starting at line {line}")
+            } else {
+                format!(
+                    "unexpected character at line {line}: \"{}\" ({e})",
+                    remaining.chars().take(20).collect::<String>()
+                )
+            };
+            ContainustError::Config { message }
         })?;
-        tokens.push(token);
+        if tokens.len() == max_tokens {
+            return Err(ContainustError::Config {
+                message: format!("composition exceeds the parser limit of {max_tokens} tokens"),
+            });
+        }
+        tokens.push(SpannedToken { token, line });
+        line += count_newlines(&remaining[..remaining.len() - rest.len()]);
         remaining = rest;
     }
 
     Ok(tokens)
 }
 
+/// Returns the backslash and the few characters after it for messages.
+fn escape_preview(escape: &str) -> String {
+    escape
+        .chars()
+        .take_while(|c| *c != '"' && *c != '\n')
+        .take(10)
+        .collect()
+}
+
+fn count_newlines(text: &str) -> usize {
+    text.bytes().filter(|&byte| byte == b'\n').count()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn escaped_strings_tokenize_back_to_the_original() {
+        for value in [
+            "plain",
+            r#"say "hi""#,
+            r"C:\dir\",
+            "tab\there\nnext",
+            "bell\u{7}",
+        ] {
+            let literal = format!("\"{}\"", escape_ctst_string(value));
+            assert_eq!(
+                tokenize(&literal).expect("should tokenize"),
+                vec![Token::StringLiteral(value.into())],
+                "{literal}"
+            );
+        }
+    }
+
     #[test]
     fn tokenize_keywords() {
         let tokens =
-            tokenize("IMPORT AS COMPONENT FROM CONNECT true false").expect("should tokenize");
+            tokenize("IMPORT AS COMPONENT FROM CONNECT DEFAULTS DEPENDS_ON SCHEMA true false")
+                .expect("should tokenize");
         assert_eq!(
             tokens,
             vec![
@@ -215,6 +372,9 @@ mod tests {
                 Token::Component,
                 Token::From,
                 Token::Connect,
+                Token::Defaults,
+                Token::DependsOn,
+                Token::Schema,
                 Token::True,
                 Token::False,
             ]
@@ -253,6 +413,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tokenize_string_with_control_escapes() {
+        let tokens = tokenize(r#""a\rb\0c""#).expect("should tokenize");
+        assert_eq!(tokens, vec![Token::StringLiteral("a\rb\0c".into())]);
+    }
+
+    #[test]
+    fn tokenize_string_with_hex_and_unicode_escapes() {
+        let tokens = tokenize(r#""\x41\u00e9\u{1F600}\u{41}""#).expect("should tokenize");
+        assert_eq!(tokens, vec![Token::StringLiteral("Aé😀A".into())]);
+    }
+
+    #[test]
+    fn tokenize_rejects_invalid_escapes() {
+        for (input, escape) in [
+            (r#""bad \q""#, r"\q"),
+            (r#""\xFF""#, r"\xFF"),
+            (r#""\x4""#, r"\x4"),
+            (r#""\u12""#, r"\u12"),
+            (r#""\u{}""#, r"\u{}"),
+            (r#""\u{D800}""#, r"\u{D800}"),
+        ] {
+            let error = tokenize(input).expect_err(input).to_string();
+            assert!(
+                error.contains(&format!("invalid escape sequence \"{escape}")),
+                "{input}: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn tokenize_invalid_escape_reports_its_line() {
+        let error = tokenize("\"first\nsecond \\q\"")
+            .expect_err("should fail")
+            .to_string();
+        assert!(error.contains("at line 2"), "{error}");
+    }
+
+    #[test]
+    fn tokenize_trailing_backslash_is_unterminated() {
+        let error = tokenize("\"abc\\").expect_err("should fail").to_string();
+        assert!(error.contains("unterminated string"), "{error}");
+    }
+
     #[test]
     fn tokenize_integer() {
         let tokens = tokenize("8080 5432").expect("should tokenize");
@@ -356,4 +560,26 @@ mod tests {
         let result = tokenize("COMPONENT @invalid");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn tokenize_spanned_tracks_lines() {
+        let tokens = tokenize_spanned(
+            "COMPONENT a {\n  // note\n  image = \"x\ny\"\n}",
+            usize::MAX,
+        )
+        .expect("should tokenize");
+        let lines: Vec<usize> = tokens.iter().map(|spanned| spanned.line).collect();
+        assert_eq!(lines, vec![1, 1, 1, 3, 3, 3, 5]);
+    }
+
+    #[test]
+    fn tokenize_reports_unterminated_string_line() {
+        let error = tokenize("COMPONENT a {\n  image = \"oops\n}\n")
+            .expect_err("should fail")
+            .to_string();
+        assert!(
+            error.contains("unterminated string starting at line 2"),
+            "{error}"
+        );
+    }
 }
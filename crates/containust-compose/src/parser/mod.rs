@@ -12,19 +12,42 @@ use std::collections::BTreeMap;
 use containust_common::error::{ContainustError, Result};
 
 use self::ast::{
-    ComponentDecl, CompositionFile, ConnectionDecl, ExposeDecl, HealthcheckDecl, ImportDecl,
+    ComponentDecl, CompositionFile, ConnectionDecl, DefaultsDecl, DependencyDecl, ExposeDecl,
+    HealthcheckDecl, HookDecl, ImportDecl, IpcDecl, MemorySpec, SchemaVersion,
 };
 use self::lexer::Token;
 
 /// Cursor into a token stream for recursive-descent parsing.
 struct TokenCursor<'a> {
     tokens: &'a [Token],
+    /// Source line of each token, parallel to `tokens`.
+    lines: &'a [usize],
     pos: usize,
 }
 
 impl<'a> TokenCursor<'a> {
-    const fn new(tokens: &'a [Token]) -> Self {
-        Self { tokens, pos: 0 }
+    const fn new(tokens: &'a [Token], lines: &'a [usize]) -> Self {
+        Self {
+            tokens,
+            lines,
+            pos: 0,
+        }
+    }
+
+    /// Line of the next token (or of the last token at end of input).
+    fn line(&self) -> usize {
+        self.lines
+            .get(self.pos)
+            .or_else(|| self.lines.last())
+            .copied()
+            .unwrap_or(1)
+    }
+
+    /// Consumes `{` or `[`, returning the line it was opened on.
+    fn open(&mut self, expected: &Token) -> Result<usize> {
+        let line = self.line();
+        self.expect_token(expected)?;
+        Ok(line)
     }
 
     fn peek(&self) -> Option<&Token> {
@@ -76,38 +99,152 @@ const fn parse_err(message: String) -> ContainustError {
     ContainustError::Config { message }
 }
 
+fn unterminated(what: &str, opened: usize) -> ContainustError {
+    parse_err(format!("unterminated {what} opened at line {opened}"))
+}
+
 fn skip_optional_comma(cursor: &mut TokenCursor<'_>) {
     if cursor.peek() == Some(&Token::Comma) {
         let _ = cursor.advance();
     }
 }
 
-/// Parses a `.ctst` file from its source text.
+/// Resource limits applied while parsing untrusted `.ctst` input.
+///
+/// The defaults are far above any hand-written composition but stop a
+/// runaway or malicious file before it exhausts memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Maximum size of the source text in bytes.
+    pub max_input_bytes: usize,
+    /// Maximum number of lexer tokens.
+    pub max_tokens: usize,
+    /// Maximum nesting depth of `{}` blocks and `[]` lists.
+    pub max_depth: usize,
+}
+
+impl ParseLimits {
+    /// Default maximum source size: 4 MiB.
+    pub const DEFAULT_MAX_INPUT_BYTES: usize = 4 * 1024 * 1024;
+    /// Default maximum token count.
+    pub const DEFAULT_MAX_TOKENS: usize = 500_000;
+    /// Default maximum nesting depth.
+    pub const DEFAULT_MAX_DEPTH: usize = 32;
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_input_bytes: Self::DEFAULT_MAX_INPUT_BYTES,
+            max_tokens: Self::DEFAULT_MAX_TOKENS,
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+/// Parses a `.ctst` file from its source text with the default
+/// [`ParseLimits`].
+///
+/// `${env:VAR}` references in string values are expanded from the host
+/// environment before validation (see [`crate::hostenv`]).
 ///
 /// # Errors
 ///
-/// Returns an error if the input contains syntax errors or fails validation.
+/// Returns an error if the input contains syntax errors, exceeds a parser
+/// limit, references an undefined host variable, or fails validation.
 pub fn parse_ctst(input: &str) -> Result<CompositionFile> {
+    parse_ctst_with_limits(input, &ParseLimits::default())
+}
+
+/// Parses a `.ctst` file, enforcing caller-supplied [`ParseLimits`].
+///
+/// # Errors
+///
+/// Returns the same errors as [`parse_ctst`].
+pub fn parse_ctst_with_limits(input: &str, limits: &ParseLimits) -> Result<CompositionFile> {
     tracing::info!("parsing .ctst input");
-    let tokens = lexer::tokenize(input)?;
-    let mut cursor = TokenCursor::new(&tokens);
-    let file = parse_file(&mut cursor)?;
+    if input.len() > limits.max_input_bytes {
+        return Err(parse_err(format!(
+            "composition is {} bytes, exceeding the parser limit of {} bytes",
+            input.len(),
+            limits.max_input_bytes
+        )));
+    }
+    let (tokens, lines): (Vec<Token>, Vec<usize>) =
+        lexer::tokenize_spanned(input, limits.max_tokens)?
+            .into_iter()
+            .map(|spanned| (spanned.token, spanned.line))
+            .unzip();
+    check_nesting_depth(&tokens, limits.max_depth)?;
+    let mut cursor = TokenCursor::new(&tokens, &lines);
+    let mut file = parse_file(&mut cursor)?;
+    if file.schema.is_none() {
+        tracing::warn!(
+            "composition declares no SCHEMA version; assuming {}",
+            SchemaVersion::LATEST
+        );
+    }
+    apply_defaults(&mut file);
+    crate::hostenv::expand_host_env(&mut file)?;
+    normalize_expanded_memory(&mut file)?;
     validator::validate(&file)?;
     Ok(file)
 }
 
+/// Rejects token streams whose `{`/`[` nesting exceeds `max_depth`.
+fn check_nesting_depth(tokens: &[Token], max_depth: usize) -> Result<()> {
+    let mut depth = 0_usize;
+    for token in tokens {
+        match token {
+            Token::BraceOpen | Token::BracketOpen => {
+                depth += 1;
+                if depth > max_depth {
+                    return Err(parse_err(format!(
+                        "composition nests blocks and lists deeper than the parser \
+                         limit of {max_depth}"
+                    )));
+                }
+            }
+            Token::BraceClose | Token::BracketClose => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 fn parse_file(cursor: &mut TokenCursor<'_>) -> Result<CompositionFile> {
     let mut file = CompositionFile::default();
 
+    if cursor.peek() == Some(&Token::Schema) {
+        file.schema = Some(parse_schema(cursor)?);
+    }
+
     while let Some(tok) = cursor.peek() {
         match tok {
+            Token::Schema => {
+                return Err(parse_err(format!(
+                    "SCHEMA must be the first declaration and appear once (line {})",
+                    cursor.line()
+                )));
+            }
             Token::Import => file.imports.push(parse_import(cursor)?),
             Token::Component => file.components.push(parse_component(cursor)?),
             Token::Connect => file.connections.push(parse_connection(cursor)?),
+            Token::DependsOn => file.dependencies.push(parse_dependency(cursor)?),
             Token::Expose => file.exposes.push(parse_expose(cursor)?),
+            Token::Defaults => {
+                if file.defaults.is_some() {
+                    return Err(parse_err(format!(
+                        "DEFAULTS block declared more than once (line {})",
+                        cursor.line()
+                    )));
+                }
+                file.defaults = Some(parse_defaults(cursor)?);
+            }
             other => {
                 return Err(parse_err(format!(
-                    "expected IMPORT, COMPONENT, CONNECT, or EXPOSE at top level, got {other:?}"
+                    "expected IMPORT, COMPONENT, CONNECT, DEPENDS_ON, EXPOSE, or DEFAULTS at \
+                     top level, got {other:?}"
                 )));
             }
         }
@@ -116,6 +253,22 @@ fn parse_file(cursor: &mut TokenCursor<'_>) -> Result<CompositionFile> {
     Ok(file)
 }
 
+fn parse_schema(cursor: &mut TokenCursor<'_>) -> Result<SchemaVersion> {
+    let line = cursor.line();
+    cursor.expect_token(&Token::Schema)?;
+    let version = SchemaVersion::parse(&cursor.expect_string()?)
+        .map_err(|message| parse_err(format!("{message} at line {line}")))?;
+    if !version.is_supported() {
+        return Err(parse_err(format!(
+            "unsupported schema version {version} at line {line} (this parser supports {} \
+             to {})",
+            SchemaVersion::MIN_SUPPORTED,
+            SchemaVersion::LATEST
+        )));
+    }
+    Ok(version)
+}
+
 fn parse_import(cursor: &mut TokenCursor<'_>) -> Result<ImportDecl> {
     cursor.expect_token(&Token::Import)?;
     let source = cursor.expect_string()?;
@@ -131,6 +284,7 @@ fn parse_import(cursor: &mut TokenCursor<'_>) -> Result<ImportDecl> {
 fn parse_component(cursor: &mut TokenCursor<'_>) -> Result<ComponentDecl> {
     cursor.expect_token(&Token::Component)?;
     let name = cursor.expect_identifier()?;
+    containust_common::types::validate_name(&name)?;
 
     let from_template = if cursor.peek() == Some(&Token::From) {
         let _ = cursor.advance();
@@ -139,7 +293,7 @@ fn parse_component(cursor: &mut TokenCursor<'_>) -> Result<ComponentDecl> {
         None
     };
 
-    cursor.expect_token(&Token::BraceOpen)?;
+    let opened = cursor.open(&Token::BraceOpen)?;
 
     let mut comp = ComponentDecl {
         name,
@@ -149,9 +303,7 @@ fn parse_component(cursor: &mut TokenCursor<'_>) -> Result<ComponentDecl> {
 
     while cursor.peek() != Some(&Token::BraceClose) {
         if cursor.at_end() {
-            return Err(parse_err(
-                "unexpected end of input inside COMPONENT block".into(),
-            ));
+            return Err(unterminated("COMPONENT block", opened));
         }
         parse_property(cursor, &mut comp)?;
     }
@@ -166,28 +318,45 @@ fn parse_property(cursor: &mut TokenCursor<'_>, comp: &mut ComponentDecl) -> Res
 
     match key.as_str() {
         "image" => comp.image = Some(cursor.expect_string()?),
-        "port" => {
-            let val = cursor.expect_integer()?;
-            comp.port = Some(
-                u16::try_from(val)
-                    .map_err(|_| parse_err(format!("port value out of range: {val}")))?,
-            );
-        }
+        "port" => comp.port = Some(parse_port(cursor)?),
         "ports" => comp.ports = parse_integer_list(cursor)?,
-        "memory" => comp.memory = Some(cursor.expect_string()?),
+        "memory" | "memory_swap" | "memory_reservation" | "memory_high" => {
+            parse_memory_property(&key, cursor, comp)?;
+        }
         "cpu" => comp.cpu = Some(cursor.expect_string()?),
+        "cpu_max" => comp.cpu_max = Some(cursor.expect_string()?),
+        "cpuset" => comp.cpuset = Some(cursor.expect_string()?),
+        "numa_node" => comp.numa_node = Some(cursor.expect_string()?),
+        "ipc" => comp.ipc = Some(parse_ipc(cursor)?),
         "env" => comp.env = parse_env_map(cursor)?,
+        "inherit_env" => comp.inherit_env = parse_string_list(cursor)?,
         "volume" => comp.volume = Some(cursor.expect_string()?),
         "volumes" => comp.volumes = parse_string_list(cursor)?,
         "command" => comp.command = parse_string_list(cursor)?,
-        "entrypoint" => comp.entrypoint = Some(parse_string_list(cursor)?),
+        "entrypoint" => comp.entrypoint = Some(parse_string_or_list(cursor)?),
         "readonly" => comp.readonly = Some(parse_bool(cursor)?),
+        "stdin" => comp.stdin = Some(parse_bool(cursor)?),
         "workdir" => comp.workdir = Some(cursor.expect_string()?),
         "user" => comp.user = Some(cursor.expect_string()?),
         "hostname" => comp.hostname = Some(cursor.expect_string()?),
         "restart" => comp.restart = Some(cursor.expect_string()?),
         "network" => comp.network = Some(cursor.expect_string()?),
-        "healthcheck" => comp.healthcheck = Some(parse_healthcheck(cursor)?),
+        "capabilities" => comp.capabilities = parse_string_list(cursor)?,
+        "stop_signal" => comp.stop_signal = Some(cursor.expect_string()?),
+        "stop_timeout" => comp.stop_timeout = Some(cursor.expect_string()?),
+        "healthcheck" | "liveness" => {
+            if comp.healthcheck.is_some() {
+                return Err(parse_err(format!(
+                    "component '{}' declares more than one liveness probe \
+                     (healthcheck and liveness are aliases)",
+                    comp.name
+                )));
+            }
+            comp.healthcheck = Some(parse_healthcheck(cursor)?);
+        }
+        "readiness" => comp.readiness = Some(parse_healthcheck(cursor)?),
+        "pre_start" => comp.pre_start = Some(parse_hook(cursor)?),
+        "post_start" => comp.post_start = Some(parse_hook(cursor)?),
         _ => {
             return Err(parse_err(format!("unknown component property: {key}")));
         }
@@ -196,6 +365,29 @@ fn parse_property(cursor: &mut TokenCursor<'_>, comp: &mut ComponentDecl) -> Res
     Ok(())
 }
 
+fn parse_memory_property(
+    key: &str,
+    cursor: &mut TokenCursor<'_>,
+    comp: &mut ComponentDecl,
+) -> Result<()> {
+    let (raw, spec) = parse_memory_literal(cursor)?;
+    match key {
+        "memory" => {
+            comp.memory = Some(raw);
+            comp.memory_spec = spec;
+        }
+        "memory_swap" => comp.memory_swap = Some(raw),
+        "memory_reservation" => comp.memory_reservation = Some(raw),
+        _ => comp.memory_high = Some(raw),
+    }
+    Ok(())
+}
+
+fn parse_port(cursor: &mut TokenCursor<'_>) -> Result<u16> {
+    let val = cursor.expect_integer()?;
+    u16::try_from(val).map_err(|_| parse_err(format!("port value out of range: {val}")))
+}
+
 fn parse_bool(cursor: &mut TokenCursor<'_>) -> Result<bool> {
     match cursor.advance() {
         Some(Token::True) => Ok(true),
@@ -205,12 +397,12 @@ fn parse_bool(cursor: &mut TokenCursor<'_>) -> Result<bool> {
 }
 
 fn parse_string_list(cursor: &mut TokenCursor<'_>) -> Result<Vec<String>> {
-    cursor.expect_token(&Token::BracketOpen)?;
+    let opened = cursor.open(&Token::BracketOpen)?;
     let mut items = Vec::new();
 
     while cursor.peek() != Some(&Token::BracketClose) {
         if cursor.at_end() {
-            return Err(parse_err("unexpected end of input inside list".into()));
+            return Err(unterminated("list", opened));
         }
         items.push(cursor.expect_string()?);
         skip_optional_comma(cursor);
@@ -220,13 +412,21 @@ fn parse_string_list(cursor: &mut TokenCursor<'_>) -> Result<Vec<String>> {
     Ok(items)
 }
 
+/// Parses either a single string (one-element list) or a string list.
+fn parse_string_or_list(cursor: &mut TokenCursor<'_>) -> Result<Vec<String>> {
+    if let Some(Token::StringLiteral(_)) = cursor.peek() {
+        return Ok(vec![cursor.expect_string()?]);
+    }
+    parse_string_list(cursor)
+}
+
 fn parse_integer_list(cursor: &mut TokenCursor<'_>) -> Result<Vec<u16>> {
-    cursor.expect_token(&Token::BracketOpen)?;
+    let opened = cursor.open(&Token::BracketOpen)?;
     let mut items = Vec::new();
 
     while cursor.peek() != Some(&Token::BracketClose) {
         if cursor.at_end() {
-            return Err(parse_err("unexpected end of input inside list".into()));
+            return Err(unterminated("list", opened));
         }
         let val = cursor.expect_integer()?;
         items.push(
@@ -240,12 +440,12 @@ fn parse_integer_list(cursor: &mut TokenCursor<'_>) -> Result<Vec<u16>> {
 }
 
 fn parse_env_map(cursor: &mut TokenCursor<'_>) -> Result<BTreeMap<String, String>> {
-    cursor.expect_token(&Token::BraceOpen)?;
+    let opened = cursor.open(&Token::BraceOpen)?;
     let mut map = BTreeMap::new();
 
     while cursor.peek() != Some(&Token::BraceClose) {
         if cursor.at_end() {
-            return Err(parse_err("unexpected end of input inside env block".into()));
+            return Err(unterminated("env block", opened));
         }
         let key = cursor.expect_identifier()?;
         cursor.expect_token(&Token::Equals)?;
@@ -259,10 +459,13 @@ fn parse_env_map(cursor: &mut TokenCursor<'_>) -> Result<BTreeMap<String, String
 }
 
 fn parse_healthcheck(cursor: &mut TokenCursor<'_>) -> Result<HealthcheckDecl> {
-    cursor.expect_token(&Token::BraceOpen)?;
+    let opened = cursor.open(&Token::BraceOpen)?;
 
     let mut hc = HealthcheckDecl {
         command: Vec::new(),
+        http: None,
+        expect: None,
+        tcp: None,
         interval: None,
         timeout: None,
         retries: None,
@@ -271,14 +474,23 @@ fn parse_healthcheck(cursor: &mut TokenCursor<'_>) -> Result<HealthcheckDecl> {
 
     while cursor.peek() != Some(&Token::BraceClose) {
         if cursor.at_end() {
-            return Err(parse_err(
-                "unexpected end of input inside healthcheck block".into(),
-            ));
+            return Err(unterminated("healthcheck block", opened));
         }
         let key = cursor.expect_identifier()?;
         cursor.expect_token(&Token::Equals)?;
         match key.as_str() {
             "command" => hc.command = parse_string_list(cursor)?,
+            "http" => hc.http = Some(cursor.expect_string()?),
+            "tcp" => hc.tcp = Some(expect_port(cursor)?),
+            "expect" => {
+                let val = cursor.expect_integer()?;
+                hc.expect = Some(
+                    u16::try_from(val)
+                        .ok()
+                        .filter(|status| (100..=599).contains(status))
+                        .ok_or_else(|| parse_err(format!("invalid HTTP status: {val}")))?,
+                );
+            }
             "interval" => hc.interval = Some(cursor.expect_string()?),
             "timeout" => hc.timeout = Some(cursor.expect_string()?),
             "retries" => {
@@ -297,9 +509,180 @@ fn parse_healthcheck(cursor: &mut TokenCursor<'_>) -> Result<HealthcheckDecl> {
     }
 
     cursor.expect_token(&Token::BraceClose)?;
+    validate_probe_kind(&hc)?;
     Ok(hc)
 }
 
+/// Requires exactly one of `command`, `http`, or `tcp`; `expect` only
+/// applies to `http`.
+fn validate_probe_kind(hc: &HealthcheckDecl) -> Result<()> {
+    let kinds = usize::from(!hc.command.is_empty())
+        + usize::from(hc.http.is_some())
+        + usize::from(hc.tcp.is_some());
+    if kinds != 1 {
+        return Err(parse_err(
+            "healthcheck needs exactly one of 'command', 'http', or 'tcp'".into(),
+        ));
+    }
+    if hc.expect.is_some() && hc.http.is_none() {
+        return Err(parse_err(
+            "healthcheck 'expect' requires an 'http' probe".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Parses a hook as a command list or a `{ command, optional }` block.
+fn parse_hook(cursor: &mut TokenCursor<'_>) -> Result<HookDecl> {
+    if cursor.peek() != Some(&Token::BraceOpen) {
+        return Ok(HookDecl {
+            command: parse_string_list(cursor)?,
+            optional: false,
+        });
+    }
+    let opened = cursor.open(&Token::BraceOpen)?;
+    let mut hook = HookDecl::default();
+    while cursor.peek() != Some(&Token::BraceClose) {
+        if cursor.at_end() {
+            return Err(unterminated("hook block", opened));
+        }
+        let key = cursor.expect_identifier()?;
+        cursor.expect_token(&Token::Equals)?;
+        match key.as_str() {
+            "command" => hook.command = parse_string_list(cursor)?,
+            "optional" => hook.optional = parse_bool(cursor)?,
+            _ => return Err(parse_err(format!("unknown hook property: {key}"))),
+        }
+        skip_optional_comma(cursor);
+    }
+    cursor.expect_token(&Token::BraceClose)?;
+    Ok(hook)
+}
+
+fn parse_ipc(cursor: &mut TokenCursor<'_>) -> Result<IpcDecl> {
+    let opened = cursor.open(&Token::BraceOpen)?;
+    let mut ipc = IpcDecl::default();
+    while cursor.peek() != Some(&Token::BraceClose) {
+        if cursor.at_end() {
+            return Err(unterminated("ipc block", opened));
+        }
+        let key = cursor.expect_identifier()?;
+        cursor.expect_token(&Token::Equals)?;
+        match key.as_str() {
+            "shmmax" => ipc.shmmax = Some(cursor.expect_string()?),
+            "shmall" => {
+                let val = cursor.expect_integer()?;
+                ipc.shmall = Some(
+                    u64::try_from(val)
+                        .map_err(|_| parse_err(format!("shmall value out of range: {val}")))?,
+                );
+            }
+            "msgmni" => {
+                let val = cursor.expect_integer()?;
+                ipc.msgmni = Some(
+                    u32::try_from(val)
+                        .map_err(|_| parse_err(format!("msgmni value out of range: {val}")))?,
+                );
+            }
+            _ => return Err(parse_err(format!("unknown ipc property: {key}"))),
+        }
+        skip_optional_comma(cursor);
+    }
+    cursor.expect_token(&Token::BraceClose)?;
+    Ok(ipc)
+}
+
+fn parse_defaults(cursor: &mut TokenCursor<'_>) -> Result<DefaultsDecl> {
+    cursor.expect_token(&Token::Defaults)?;
+    let opened = cursor.open(&Token::BraceOpen)?;
+    let mut defaults = DefaultsDecl::default();
+
+    while cursor.peek() != Some(&Token::BraceClose) {
+        if cursor.at_end() {
+            return Err(unterminated("DEFAULTS block", opened));
+        }
+        let key = cursor.expect_identifier()?;
+        cursor.expect_token(&Token::Equals)?;
+        match key.as_str() {
+            "network" => defaults.network = Some(cursor.expect_string()?),
+            "env" => defaults.env = parse_env_map(cursor)?,
+            _ => return Err(parse_err(format!("unknown DEFAULTS property: {key}"))),
+        }
+    }
+
+    cursor.expect_token(&Token::BraceClose)?;
+    Ok(defaults)
+}
+
+/// Fills every component from the `DEFAULTS` block: a missing `network`
+/// is inherited and `env` merges key-wise, the component's values winning.
+fn apply_defaults(file: &mut CompositionFile) {
+    let Some(defaults) = &file.defaults else {
+        return;
+    };
+    for component in &mut file.components {
+        if component.network.is_none() {
+            component.network.clone_from(&defaults.network);
+        }
+        for (key, value) in &defaults.env {
+            let _ = component
+                .env
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+}
+
+/// Memory value that opts a component out of limits.
+const UNLIMITED: &str = "unlimited";
+
+/// Reads a `memory` value: a size string such as `"256MiB"` or a bare
+/// integer of bytes. Returns the value as written and its normalized
+/// [`MemorySpec`], which is deferred for `${env:...}` references.
+fn parse_memory_literal(cursor: &mut TokenCursor<'_>) -> Result<(String, Option<MemorySpec>)> {
+    let line = cursor.line();
+    let raw = match cursor.advance() {
+        Some(Token::StringLiteral(text)) => text.clone(),
+        Some(Token::Integer(bytes)) if *bytes >= 0 => bytes.to_string(),
+        other => {
+            return Err(parse_err(format!(
+                "expected memory size at line {line}, got {other:?}"
+            )));
+        }
+    };
+    if raw.contains(crate::hostenv::PREFIX) {
+        return Ok((raw, None));
+    }
+    let spec = memory_spec(&raw).map_err(|reason| {
+        parse_err(format!(
+            "invalid memory value \"{raw}\" at line {line}: {reason}"
+        ))
+    })?;
+    Ok((raw, spec))
+}
+
+fn memory_spec(raw: &str) -> Result<Option<MemorySpec>> {
+    if raw.trim().eq_ignore_ascii_case(UNLIMITED) {
+        return Ok(None);
+    }
+    containust_common::units::parse_bytes(raw).map(|bytes| Some(MemorySpec { bytes }))
+}
+
+/// Normalizes `memory` values that were host env references at parse time.
+fn normalize_expanded_memory(file: &mut CompositionFile) -> Result<()> {
+    for component in &mut file.components {
+        if let (Some(raw), None) = (&component.memory, component.memory_spec) {
+            component.memory_spec = memory_spec(raw).map_err(|reason| {
+                parse_err(format!(
+                    "component '{}': invalid memory value \"{raw}\": {reason}",
+                    component.name
+                ))
+            })?;
+        }
+    }
+    Ok(())
+}
+
 fn parse_expose(cursor: &mut TokenCursor<'_>) -> Result<ExposeDecl> {
     cursor.expect_token(&Token::Expose)?;
     let host_port = expect_port(cursor)?;
@@ -328,6 +711,14 @@ fn parse_connection(cursor: &mut TokenCursor<'_>) -> Result<ConnectionDecl> {
     Ok(ConnectionDecl { from, to })
 }
 
+fn parse_dependency(cursor: &mut TokenCursor<'_>) -> Result<DependencyDecl> {
+    cursor.expect_token(&Token::DependsOn)?;
+    let from = cursor.expect_identifier()?;
+    cursor.expect_token(&Token::Arrow)?;
+    let to = cursor.expect_identifier()?;
+    Ok(DependencyDecl { from, to })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -392,6 +783,16 @@ COMPONENT db FROM pg {
         assert_eq!(file.components[0].command, vec!["--port", "8080"]);
     }
 
+    #[test]
+    fn parse_entrypoint_string_form() {
+        let input = r#"COMPONENT api {
+    image = "file:///api"
+    entrypoint = "/bin/api"
+}"#;
+        let file = parse_ctst(input).expect("should parse");
+        assert_eq!(file.components[0].entrypoint, Some(vec!["/bin/api".into()]));
+    }
+
     #[test]
     #[allow(clippy::too_many_lines)]
     fn parse_component_all_properties() {
@@ -409,6 +810,7 @@ COMPONENT db FROM pg {
     volumes = ["/logs:/app/logs", "/tmp:/app/tmp"]
     command = ["./server", "--bind", "0.0.0.0:8080"]
     readonly = true
+    stdin = true
     workdir = "/app"
     user = "appuser"
     hostname = "web-server"
@@ -436,6 +838,7 @@ COMPONENT db FROM pg {
         assert_eq!(c.volumes.len(), 2);
         assert_eq!(c.command, vec!["./server", "--bind", "0.0.0.0:8080"]);
         assert_eq!(c.readonly, Some(true));
+        assert_eq!(c.stdin, Some(true));
         assert_eq!(c.workdir.as_deref(), Some("/app"));
         assert_eq!(c.user.as_deref(), Some("appuser"));
         assert_eq!(c.hostname.as_deref(), Some("web-server"));
@@ -537,6 +940,47 @@ EXPOSE 80:8080"#;
         assert!(parse_ctst(input).is_err());
     }
 
+    #[test]
+    fn parse_schema_declaration() {
+        let input = r#"SCHEMA "1.0"
+COMPONENT web { image = "web" }"#;
+        let file = parse_ctst(input).expect("should parse");
+        assert_eq!(file.schema, Some(SchemaVersion { major: 1, minor: 0 }));
+        assert_eq!(file.components.len(), 1);
+    }
+
+    #[test]
+    fn parse_schema_defaults_to_latest_when_absent() {
+        let file = parse_ctst(r#"COMPONENT web { image = "web" }"#).expect("should parse");
+        assert!(file.schema.is_none());
+        assert_eq!(file.schema_version(), SchemaVersion::LATEST);
+    }
+
+    #[test]
+    fn parse_schema_unsupported_version_is_rejected() {
+        let error = parse_ctst(r#"SCHEMA "2.0""#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("unsupported schema version 2.0 at line 1"),
+            "{error}"
+        );
+        let error = parse_ctst(r#"SCHEMA "one""#).unwrap_err();
+        assert!(
+            error.to_string().contains("invalid schema version 'one'"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn parse_schema_must_come_first_and_once() {
+        let input = r#"COMPONENT web { image = "web" }
+SCHEMA "1.0""#;
+        let error = parse_ctst(input).unwrap_err();
+        assert!(error.to_string().contains("line 2"), "{error}");
+        assert!(parse_ctst("SCHEMA \"1.0\"\nSCHEMA \"1.0\"").is_err());
+    }
+
     #[test]
     fn parse_error_unknown_property() {
         let input = r#"COMPONENT x {
@@ -547,6 +991,12 @@ EXPOSE 80:8080"#;
         assert!(result.is_err());
     }
 
+    #[test]
+    fn parse_error_unsafe_component_name() {
+        let error = parse_ctst(r#"COMPONENT _web { image = "img" }"#).unwrap_err();
+        assert!(error.to_string().contains("invalid name '_web'"), "{error}");
+    }
+
     #[test]
     fn parse_error_missing_brace() {
         let input = r#"COMPONENT x {
@@ -593,4 +1043,477 @@ CONNECT b -> c"#;
         let file = parse_ctst(input).expect("should parse");
         assert_eq!(file.connections.len(), 3);
     }
+
+    const NORMAL: &str = r#"COMPONENT db {
+    image = "file:///opt/db"
+    env = { A = "1" }
+    healthcheck = { command = ["true"] }
+}
+COMPONENT api { image = "file:///opt/api" }
+CONNECT api -> db"#;
+
+    #[test]
+    fn parse_readiness_and_liveness_blocks() {
+        let input = r#"COMPONENT db {
+    image = "file:///opt/db"
+    readiness = { command = ["pg_isready"], interval = "2s", retries = 10 }
+    liveness = { command = ["pg_isready", "-q"], interval = "30s" }
+}"#;
+        let file = parse_ctst(input).expect("should parse");
+        let db = &file.components[0];
+        let readiness = db.readiness.as_ref().expect("readiness probe");
+        assert_eq!(readiness.command, vec!["pg_isready"]);
+        assert_eq!(readiness.interval.as_deref(), Some("2s"));
+        assert_eq!(readiness.retries, Some(10));
+        let liveness = db.healthcheck.as_ref().expect("liveness probe");
+        assert_eq!(liveness.command, vec!["pg_isready", "-q"]);
+        assert_eq!(liveness.interval.as_deref(), Some("30s"));
+    }
+
+    #[test]
+    fn parse_http_healthcheck() {
+        let input = r#"COMPONENT api {
+    image = "file:///opt/api"
+    healthcheck = { http = "http://localhost:8080/health", expect = 204, timeout = "2s" }
+    readiness = { http = "http://localhost:8080/ready" }
+}"#;
+        let file = parse_ctst(input).expect("should parse");
+        let api = &file.components[0];
+        let liveness = api.healthcheck.as_ref().expect("liveness probe");
+        assert!(liveness.command.is_empty());
+        assert_eq!(
+            liveness.http.as_deref(),
+            Some("http://localhost:8080/health")
+        );
+        assert_eq!(liveness.expect, Some(204));
+        let readiness = api.readiness.as_ref().expect("readiness probe");
+        assert_eq!(readiness.expect, None);
+    }
+
+    #[test]
+    fn parse_rejects_healthcheck_without_exactly_one_probe_kind() {
+        for block in [
+            r#"{ command = ["true"], http = "http://localhost/" }"#,
+            r#"{ command = ["true"], tcp = 5432 }"#,
+            r#"{ http = "http://localhost/", tcp = 80 }"#,
+            r#"{ interval = "5s" }"#,
+        ] {
+            let input = format!(
+                "COMPONENT api {{\n    image = \"file:///opt/api\"\n    healthcheck = {block}\n}}"
+            );
+            let error = parse_ctst(&input).expect_err("should reject").to_string();
+            assert!(
+                error.contains("exactly one of 'command', 'http', or 'tcp'"),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_tcp_healthcheck() {
+        let input = r#"COMPONENT db {
+    image = "file:///opt/db"
+    readiness = { tcp = 5432, interval = "1s", retries = 30 }
+}"#;
+        let file = parse_ctst(input).expect("should parse");
+        let readiness = file.components[0].readiness.as_ref().expect("readiness");
+        assert_eq!(readiness.tcp, Some(5432));
+        assert!(readiness.command.is_empty());
+        assert_eq!(readiness.retries, Some(30));
+
+        let input = r#"COMPONENT db {
+    image = "file:///opt/db"
+    healthcheck = { tcp = 70000 }
+}"#;
+        let error = parse_ctst(input).expect_err("should reject").to_string();
+        assert!(error.contains("port value out of range"), "{error}");
+    }
+
+    #[test]
+    fn parse_rejects_invalid_http_expect() {
+        let input = r#"COMPONENT api {
+    image = "file:///opt/api"
+    healthcheck = { command = ["true"], expect = 200 }
+}"#;
+        let error = parse_ctst(input).expect_err("should reject").to_string();
+        assert!(error.contains("requires an 'http' probe"), "{error}");
+
+        let input = r#"COMPONENT api {
+    image = "file:///opt/api"
+    healthcheck = { http = "http://localhost/", expect = 42 }
+}"#;
+        let error = parse_ctst(input).expect_err("should reject").to_string();
+        assert!(error.contains("invalid HTTP status: 42"), "{error}");
+    }
+
+    #[test]
+    fn parse_rejects_healthcheck_and_liveness_together() {
+        let input = r#"COMPONENT db {
+    image = "file:///opt/db"
+    healthcheck = { command = ["true"] }
+    liveness = { command = ["true"] }
+}"#;
+        let error = parse_ctst(input).expect_err("should reject").to_string();
+        assert!(error.contains("more than one liveness probe"), "{error}");
+    }
+
+    #[test]
+    fn parse_hooks_as_list_or_block() {
+        let input = r#"COMPONENT db {
+    image = "file:///opt/db"
+    pre_start = ["./prepare.sh", "--quick"]
+    post_start = { command = ["migrate"], optional = true }
+}"#;
+        let file = parse_ctst(input).expect("should parse");
+        let db = &file.components[0];
+        let pre = db.pre_start.as_ref().expect("pre_start hook");
+        assert_eq!(pre.command, vec!["./prepare.sh", "--quick"]);
+        assert!(!pre.optional);
+        let post = db.post_start.as_ref().expect("post_start hook");
+        assert_eq!(post.command, vec!["migrate"]);
+        assert!(post.optional);
+    }
+
+    #[test]
+    fn parse_rejects_unknown_hook_property() {
+        let input = r#"COMPONENT db {
+    image = "file:///opt/db"
+    post_start = { command = ["migrate"], retries = 2 }
+}"#;
+        let error = parse_ctst(input).expect_err("should reject").to_string();
+        assert!(error.contains("unknown hook property: retries"), "{error}");
+    }
+
+    #[test]
+    fn parse_ipc_limits_block() {
+        let input = r#"COMPONENT db {
+    image = "file:///opt/db"
+    ipc = { shmmax = "64MiB", shmall = 16384, msgmni = 32 }
+}"#;
+        let file = parse_ctst(input).expect("should parse");
+        let ipc = file.components[0].ipc.as_ref().expect("ipc block");
+        assert_eq!(ipc.shmmax.as_deref(), Some("64MiB"));
+        assert_eq!(ipc.shmall, Some(16_384));
+        assert_eq!(ipc.msgmni, Some(32));
+
+        let bad = parse_ctst("COMPONENT db { ipc = { msgmni = 4294967296 } }")
+            .expect_err("msgmni beyond u32")
+            .to_string();
+        assert!(bad.contains("msgmni value out of range"), "{bad}");
+    }
+
+    #[test]
+    fn parse_inherit_env_list() {
+        let input = r#"COMPONENT app {
+    image = "file:///opt/app"
+    inherit_env = ["TZ", "LANG", "HTTP_PROXY"]
+}"#;
+        let file = parse_ctst(input).expect("should parse");
+        assert_eq!(
+            file.components[0].inherit_env,
+            vec!["TZ", "LANG", "HTTP_PROXY"]
+        );
+    }
+
+    #[test]
+    fn parse_inherit_env_rejects_invalid_names() {
+        let input = r#"COMPONENT app {
+    image = "file:///opt/app"
+    inherit_env = ["TZ=UTC"]
+}"#;
+        let error = parse_ctst(input).expect_err("should fail").to_string();
+        assert!(error.contains("TZ=UTC"), "{error}");
+    }
+
+    #[test]
+    fn parse_capabilities_list() {
+        let input = r#"COMPONENT app {
+    image = "file:///opt/app"
+    capabilities = ["CAP_NET_BIND_SERVICE", "CAP_CHOWN"]
+}"#;
+        let file = parse_ctst(input).expect("should parse");
+        assert_eq!(
+            file.components[0].capabilities,
+            vec!["CAP_NET_BIND_SERVICE", "CAP_CHOWN"]
+        );
+    }
+
+    #[test]
+    fn parse_capabilities_rejects_unknown_names() {
+        let input = r#"COMPONENT app {
+    image = "file:///opt/app"
+    capabilities = ["CAP_ROOT"]
+}"#;
+        let error = parse_ctst(input).expect_err("should fail").to_string();
+        assert!(error.contains("unknown capability 'CAP_ROOT'"), "{error}");
+    }
+
+    #[test]
+    fn parse_limits_reject_oversized_input() {
+        let limits = ParseLimits {
+            max_input_bytes: 16,
+            ..ParseLimits::default()
+        };
+        let error = parse_ctst_with_limits(NORMAL, &limits)
+            .expect_err("should reject")
+            .to_string();
+        assert!(error.contains("limit of 16 bytes"), "{error}");
+    }
+
+    #[test]
+    fn parse_limits_reject_too_many_tokens() {
+        let limits = ParseLimits {
+            max_tokens: 10,
+            ..ParseLimits::default()
+        };
+        let error = parse_ctst_with_limits(NORMAL, &limits)
+            .expect_err("should reject")
+            .to_string();
+        assert!(error.contains("limit of 10 tokens"), "{error}");
+    }
+
+    #[test]
+    fn parse_limits_reject_deep_nesting() {
+        let deep = format!(
+            "COMPONENT a {{ command = {}{} }}",
+            "[".repeat(100),
+            "]".repeat(100)
+        );
+        let error = parse_ctst(&deep).expect_err("should reject").to_string();
+        assert!(error.contains("deeper than the parser limit"), "{error}");
+
+        let limits = ParseLimits {
+            max_depth: 2,
+            ..ParseLimits::default()
+        };
+        assert!(parse_ctst_with_limits(NORMAL, &limits).is_err());
+    }
+
+    #[test]
+    fn parse_limits_leave_normal_files_far_below_defaults() {
+        let limits = ParseLimits::default();
+        let tokens = lexer::tokenize(NORMAL).expect("tokenize");
+        assert!(NORMAL.len() * 1000 < limits.max_input_bytes);
+        assert!(tokens.len() * 1000 < limits.max_tokens);
+        check_nesting_depth(&tokens, 3).expect("depth 3 suffices");
+        let file = parse_ctst_with_limits(NORMAL, &limits).expect("should parse");
+        assert_eq!(file.components.len(), 2);
+    }
+
+    #[test]
+    fn parse_depends_on() {
+        let input = r#"
+COMPONENT migrate {
+    image = "file:///migrate"
+}
+COMPONENT app {
+    image = "file:///app"
+}
+DEPENDS_ON app -> migrate
+"#;
+        let file = parse_ctst(input).expect("should parse");
+        assert_eq!(file.dependencies.len(), 1);
+        assert_eq!(file.dependencies[0].from, "app");
+        assert_eq!(file.dependencies[0].to, "migrate");
+        assert!(file.connections.is_empty());
+    }
+
+    #[test]
+    fn parse_rejects_depends_on_undefined_component() {
+        let input = r#"
+COMPONENT app {
+    image = "file:///app"
+}
+DEPENDS_ON app -> migrate
+"#;
+        let error = parse_ctst(input).expect_err("should fail").to_string();
+        assert!(
+            error.contains("DEPENDS_ON target \"migrate\" is not defined"),
+            "{error}"
+        );
+    }
+
+    fn memory_of(value: &str) -> Result<CompositionFile> {
+        parse_ctst(&format!(
+            "COMPONENT app {{\n    image = \"file:///app\"\n    memory = {value}\n}}\n"
+        ))
+    }
+
+    #[test]
+    fn parse_memory_binary_and_decimal_units() {
+        let mib = memory_of("\"256MiB\"").expect("MiB");
+        assert_eq!(mib.components[0].memory.as_deref(), Some("256MiB"));
+        assert_eq!(
+            mib.components[0].memory_spec,
+            Some(MemorySpec {
+                bytes: 256 * 1024 * 1024
+            })
+        );
+        let gb = memory_of("\"2GB\"").expect("GB");
+        assert_eq!(
+            gb.components[0].memory_spec.map(|m| m.bytes),
+            Some(2_000_000_000)
+        );
+    }
+
+    #[test]
+    fn parse_memory_bare_integer_is_bytes() {
+        for value in ["1048576", "\"1048576\""] {
+            let file = memory_of(value).expect("bytes");
+            assert_eq!(file.components[0].memory.as_deref(), Some("1048576"));
+            assert_eq!(
+                file.components[0].memory_spec.map(|m| m.bytes),
+                Some(1_048_576)
+            );
+        }
+        let unlimited = memory_of("\"unlimited\"").expect("unlimited");
+        assert_eq!(unlimited.components[0].memory_spec, None);
+    }
+
+    #[test]
+    fn parse_memory_swap_accepts_sizes_and_zero() {
+        let parse = |value: &str| {
+            parse_ctst(&format!(
+                "COMPONENT app {{\n    image = \"file:///app\"\n    memory_swap = {value}\n}}\n"
+            ))
+        };
+        let sized = parse("\"512MiB\"").expect("size");
+        assert_eq!(sized.components[0].memory_swap.as_deref(), Some("512MiB"));
+        let disabled = parse("\"0\"").expect("zero");
+        assert_eq!(disabled.components[0].memory_swap.as_deref(), Some("0"));
+        assert!(parse("\"lots\"").is_err());
+    }
+
+    #[test]
+    fn parse_cpu_max_checks_quota_and_period() {
+        let parse = |value: &str| {
+            parse_ctst(&format!(
+                "COMPONENT app {{\n    image = \"file:///app\"\n    cpu_max = \"{value}\"\n}}\n"
+            ))
+        };
+        let quota = parse("50000").expect("quota");
+        assert_eq!(quota.components[0].cpu_max_spec(), Ok(Some((50_000, None))));
+        let both = parse("25000 50000").expect("quota and period");
+        assert_eq!(
+            both.components[0].cpu_max_spec(),
+            Ok(Some((25_000, Some(50_000))))
+        );
+        let error = parse("999").expect_err("quota below 1000").to_string();
+        assert!(error.contains("below the kernel minimum"), "{error}");
+        let error = parse("50000 999").expect_err("period").to_string();
+        assert!(error.contains("period 999us"), "{error}");
+        assert!(parse("half").is_err());
+        assert!(parse("1000 2000 3000").is_err());
+    }
+
+    #[test]
+    fn parse_memory_reservation_and_high() {
+        let input = r#"COMPONENT app {
+    image = "file:///app"
+    memory_reservation = "128MiB"
+    memory_high = "192MiB"
+}"#;
+        let file = parse_ctst(input).expect("should parse");
+        let comp = &file.components[0];
+        assert_eq!(comp.memory_reservation.as_deref(), Some("128MiB"));
+        assert_eq!(comp.memory_high.as_deref(), Some("192MiB"));
+    }
+
+    #[test]
+    fn parse_memory_rejects_unknown_suffix_with_line() {
+        let error = memory_of("\"256XiB\"").expect_err("bad suffix").to_string();
+        assert!(error.contains("\"256XiB\" at line 3"), "{error}");
+        assert!(error.contains("MiB"), "{error}");
+        assert!(memory_of("true").is_err());
+    }
+
+    #[test]
+    fn parse_defaults_block() {
+        let input = r#"DEFAULTS {
+    network = "backend"
+    env = { TZ = "UTC", LANG = "C" }
+}"#;
+        let file = parse_ctst(input).expect("should parse");
+        let defaults = file.defaults.expect("defaults block");
+        assert_eq!(defaults.network.as_deref(), Some("backend"));
+        assert_eq!(defaults.env.get("TZ").map(String::as_str), Some("UTC"));
+        assert_eq!(defaults.env.len(), 2);
+    }
+
+    #[test]
+    fn parse_rejects_second_defaults_block() {
+        let input = "DEFAULTS { network = \"a\" }\nDEFAULTS { network = \"b\" }\n";
+        let error = parse_ctst(input).expect_err("should fail").to_string();
+        assert!(
+            error.contains("DEFAULTS block declared more than once (line 2)"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_defaults_property() {
+        let error = parse_ctst(r#"DEFAULTS { image = "x" }"#)
+            .expect_err("should fail")
+            .to_string();
+        assert!(
+            error.contains("unknown DEFAULTS property: image"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn defaults_apply_unless_component_overrides() {
+        let input = r#"
+DEFAULTS {
+    network = "backend"
+    env = { TZ = "UTC", LOG = "info" }
+}
+COMPONENT api {
+    image = "file:///api"
+    env = { LOG = "debug" }
+}
+COMPONENT edge {
+    image = "file:///edge"
+    network = "host"
+}
+"#;
+        let file = parse_ctst(input).expect("should parse");
+        let api = &file.components[0];
+        assert_eq!(api.network.as_deref(), Some("backend"));
+        assert_eq!(api.env.get("TZ").map(String::as_str), Some("UTC"));
+        assert_eq!(api.env.get("LOG").map(String::as_str), Some("debug"));
+        let edge = &file.components[1];
+        assert_eq!(edge.network.as_deref(), Some("host"));
+        assert_eq!(edge.env.get("LOG").map(String::as_str), Some("info"));
+    }
+
+    #[test]
+    fn parse_unterminated_string_reports_start_line() {
+        let input = "COMPONENT api {\n    image = \"file:///opt/api\n}\n";
+        let error = parse_ctst(input).expect_err("should fail").to_string();
+        assert!(
+            error.contains("unterminated string starting at line 2"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn parse_unclosed_component_reports_opening_line() {
+        let input = "COMPONENT db { image = \"db\" }\n\nCOMPONENT api {\n    image = \"api\"\n";
+        let error = parse_ctst(input).expect_err("should fail").to_string();
+        assert!(
+            error.contains("unterminated COMPONENT block opened at line 3"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn parse_unclosed_list_reports_opening_line() {
+        let input = "COMPONENT api {\n    image = \"api\"\n    command = [\"a\",\n";
+        let error = parse_ctst(input).expect_err("should fail").to_string();
+        assert!(
+            error.contains("unterminated list opened at line 3"),
+            "{error}"
+        );
+    }
 }
@@ -17,6 +17,11 @@ use super::ast::CompositionFile;
 /// 2. Every CONNECT source and target references a defined component.
 /// 3. Components without a FROM template must declare an `image` property.
 /// 4. EXPOSE host ports are unique and container ports map to a component.
+/// 5. `inherit_env` lists only valid variable names.
+/// 6. `pre_start` / `post_start` hooks have a non-empty command.
+/// 7. `restart` names a known policy.
+/// 8. `capabilities` lists only known Linux capabilities.
+/// 9. `cpu_max` is a quota and period the kernel accepts.
 ///
 /// # Errors
 ///
@@ -27,6 +32,11 @@ pub fn validate(file: &CompositionFile) -> Result<()> {
     check_connection_references(file)?;
     check_image_required(file)?;
     check_expose_references(file)?;
+    check_inherit_env(file)?;
+    check_hooks(file)?;
+    check_restart_policies(file)?;
+    check_capabilities(file)?;
+    check_cpu_max(file)?;
     Ok(())
 }
 
@@ -45,17 +55,26 @@ fn check_duplicate_components(file: &CompositionFile) -> Result<()> {
 fn check_connection_references(file: &CompositionFile) -> Result<()> {
     let names: HashSet<&str> = file.components.iter().map(|c| c.name.as_str()).collect();
 
-    for conn in &file.connections {
-        if !names.contains(conn.from.as_str()) {
+    let edges = file
+        .connections
+        .iter()
+        .map(|conn| ("CONNECT", &conn.from, &conn.to))
+        .chain(
+            file.dependencies
+                .iter()
+                .map(|dep| ("DEPENDS_ON", &dep.from, &dep.to)),
+        );
+    for (keyword, from, to) in edges {
+        if !names.contains(from.as_str()) {
             return Err(ContainustError::NotFound {
                 kind: "component",
-                id: format!("CONNECT source \"{}\" is not defined", conn.from),
+                id: format!("{keyword} source \"{from}\" is not defined"),
             });
         }
-        if !names.contains(conn.to.as_str()) {
+        if !names.contains(to.as_str()) {
             return Err(ContainustError::NotFound {
                 kind: "component",
-                id: format!("CONNECT target \"{}\" is not defined", conn.to),
+                id: format!("{keyword} target \"{to}\" is not defined"),
             });
         }
     }
@@ -103,6 +122,78 @@ fn check_expose_references(file: &CompositionFile) -> Result<()> {
     Ok(())
 }
 
+fn check_inherit_env(file: &CompositionFile) -> Result<()> {
+    for comp in &file.components {
+        if let Some(name) = comp
+            .inherit_env
+            .iter()
+            .find(|name| !crate::hostenv::is_valid_name(name))
+        {
+            return Err(ContainustError::Config {
+                message: format!(
+                    "component \"{}\" inherit_env entry \"{name}\" is not a valid \
+                     environment variable name",
+                    comp.name
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_hooks(file: &CompositionFile) -> Result<()> {
+    for comp in &file.components {
+        let hooks = [
+            ("pre_start", &comp.pre_start),
+            ("post_start", &comp.post_start),
+        ];
+        for (kind, hook) in hooks {
+            if hook.as_ref().is_some_and(|hook| hook.command.is_empty()) {
+                return Err(ContainustError::Config {
+                    message: format!(
+                        "component \"{}\" {kind} hook has an empty command",
+                        comp.name
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_restart_policies(file: &CompositionFile) -> Result<()> {
+    for comp in &file.components {
+        if let Err(message) = comp.restart_policy() {
+            return Err(ContainustError::Config {
+                message: format!("component \"{}\": {message}", comp.name),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_capabilities(file: &CompositionFile) -> Result<()> {
+    for comp in &file.components {
+        if let Err(message) = comp.capability_set() {
+            return Err(ContainustError::Config {
+                message: format!("component \"{}\": {message}", comp.name),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_cpu_max(file: &CompositionFile) -> Result<()> {
+    for comp in &file.components {
+        if let Err(message) = comp.cpu_max_spec() {
+            return Err(ContainustError::Config {
+                message: format!("component \"{}\": {message}", comp.name),
+            });
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,6 +234,9 @@ mod tests {
                 from: "api".into(),
                 to: "db".into(),
             }],
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
         assert!(validate(&file).is_ok());
     }
@@ -157,6 +251,9 @@ mod tests {
                 make_component("api", Some("img2")),
             ],
             connections: Vec::new(),
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
         let err = validate(&file).unwrap_err();
         let msg = err.to_string();
@@ -173,6 +270,9 @@ mod tests {
                 from: "ghost".into(),
                 to: "db".into(),
             }],
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
         let err = validate(&file).unwrap_err();
         let msg = err.to_string();
@@ -189,6 +289,9 @@ mod tests {
                 from: "api".into(),
                 to: "ghost".into(),
             }],
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
         let err = validate(&file).unwrap_err();
         let msg = err.to_string();
@@ -205,6 +308,9 @@ mod tests {
                 ..ComponentDecl::default()
             }],
             connections: Vec::new(),
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
         let err = validate(&file).unwrap_err();
         let msg = err.to_string();
@@ -218,6 +324,9 @@ mod tests {
             imports: Vec::new(),
             components: vec![make_from_component("db", "pg")],
             connections: Vec::new(),
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
         assert!(validate(&file).is_ok());
     }
@@ -293,7 +402,85 @@ mod tests {
                     to: "c".into(),
                 },
             ],
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
         assert!(validate(&file).is_ok());
     }
+
+    #[test]
+    fn validate_empty_hook_command_fails() {
+        let mut comp = make_component("db", Some("img"));
+        comp.post_start = Some(crate::parser::ast::HookDecl::default());
+        let file = CompositionFile {
+            components: vec![comp],
+            ..CompositionFile::default()
+        };
+        let err = validate(&file).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("post_start hook has an empty command")
+        );
+    }
+
+    #[test]
+    fn validate_accepts_known_restart_policies() {
+        for policy in ["no", "never", "on-failure", "always"] {
+            let file = CompositionFile {
+                components: vec![ComponentDecl {
+                    restart: Some(policy.into()),
+                    ..make_component("api", Some("api:latest"))
+                }],
+                ..CompositionFile::default()
+            };
+            assert!(validate(&file).is_ok(), "{policy}");
+        }
+    }
+
+    #[test]
+    fn validate_rejects_unknown_restart_policy() {
+        let file = CompositionFile {
+            components: vec![ComponentDecl {
+                restart: Some("sometimes".into()),
+                ..make_component("api", Some("api:latest"))
+            }],
+            ..CompositionFile::default()
+        };
+        let error = validate(&file).unwrap_err().to_string();
+        assert!(error.contains("component \"api\""), "{error}");
+        assert!(
+            error.contains("invalid restart policy 'sometimes'"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn validate_accepts_known_capabilities() {
+        let file = CompositionFile {
+            components: vec![ComponentDecl {
+                capabilities: vec!["CAP_NET_BIND_SERVICE".into(), "chown".into()],
+                ..make_component("api", Some("api:latest"))
+            }],
+            ..CompositionFile::default()
+        };
+        assert!(validate(&file).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_unknown_capability() {
+        let file = CompositionFile {
+            components: vec![ComponentDecl {
+                capabilities: vec!["CAP_CHOWN".into(), "CAP_TELEPORT".into()],
+                ..make_component("api", Some("api:latest"))
+            }],
+            ..CompositionFile::default()
+        };
+        let error = validate(&file).unwrap_err().to_string();
+        assert!(error.contains("component \"api\""), "{error}");
+        assert!(
+            error.contains("unknown capability 'CAP_TELEPORT'"),
+            "{error}"
+        );
+    }
 }
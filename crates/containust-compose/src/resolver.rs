@@ -1,13 +1,27 @@
 //! Auto-wiring and environment variable injection.
 //!
 //! Automatically generates connection environment variables when
-//! components are linked via `CONNECT` declarations.
+//! components are linked via `CONNECT` declarations, and substitutes
+//! `${component.host}` / `${component.port}` references in declared env
+//! values. Other `${...}` text (such as `${HOME}`) and the reserved
+//! `secret.` and `env.` namespaces are left untouched.
+//!
+//! A resolved env has a canonical order so it hashes and diffs stably:
+//! declared `env` sorted by key, then connection variables sorted by key,
+//! then inherited host variables sorted by key. A declared key shadows a
+//! connection or inherited variable of the same name.
 
 use std::collections::HashMap;
 
 use containust_common::error::{ContainustError, Result};
 
-use crate::parser::ast::CompositionFile;
+use crate::parser::ast::{ComponentDecl, CompositionFile};
+
+/// Opens a `${component.field}` reference.
+const REFERENCE_PREFIX: &str = "${";
+
+/// `${namespace.name}` prefixes that never name a component.
+const RESERVED_NAMESPACES: [&str; 2] = ["secret", "env"];
 
 /// A component with its resolved environment variables.
 #[derive(Debug, Clone)]
@@ -20,23 +34,40 @@ pub struct ResolvedComponent {
 
 /// Resolves connections and generates environment variables for each component.
 ///
+/// Host variables listed in a component's `inherit_env` are copied from
+/// the process environment; see [`resolve_connections_with`].
+///
 /// For each `CONNECT source -> target`, the source component receives:
 /// - `<TARGET_UPPER>_HOST` set to the target component name.
 /// - `<TARGET_UPPER>_PORT` set to the target's port (if declared).
 ///
+/// In declared env values, `${name.host}` becomes the component name and
+/// `${name.port}` its declared port.
+///
 /// # Errors
 ///
-/// Returns an error if a connection references an undefined component.
+/// Returns an error if a connection or an env reference names an
+/// undefined component, or a reference names an unknown field.
 pub fn resolve_connections(file: &CompositionFile) -> Result<Vec<ResolvedComponent>> {
-    let mut resolved: Vec<ResolvedComponent> = file
-        .components
-        .iter()
-        .map(|c| ResolvedComponent {
-            name: c.name.clone(),
-            env: c.env.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
-        })
-        .collect();
-    let components: HashMap<&str, &crate::parser::ast::ComponentDecl> = file
+    resolve_connections_with(file, &|name| std::env::var(name).ok())
+}
+
+/// Resolves connections like [`resolve_connections`], reading inherited
+/// host variables through `host_env`.
+///
+/// Inherited variables that are unset on the host are skipped; declared
+/// `env` entries and connection variables take precedence over them.
+///
+/// # Errors
+///
+/// Returns an error if a connection or an env reference names an
+/// undefined component, or a reference names an unknown field.
+pub fn resolve_connections_with(
+    file: &CompositionFile,
+    host_env: &dyn Fn(&str) -> Option<String>,
+) -> Result<Vec<ResolvedComponent>> {
+    let mut connection_env: Vec<Vec<(String, String)>> = vec![Vec::new(); file.components.len()];
+    let components: HashMap<&str, &ComponentDecl> = file
         .components
         .iter()
         .map(|component| (component.name.as_str(), component))
@@ -62,25 +93,121 @@ pub fn resolve_connections(file: &CompositionFile) -> Result<Vec<ResolvedCompone
                     kind: "component",
                     id: conn.from.clone(),
                 })?;
-        inject_connection_env(&mut resolved[*source_index], conn, target);
+        inject_connection_env(&mut connection_env[*source_index], conn, target);
     }
 
-    Ok(resolved)
+    file.components
+        .iter()
+        .zip(connection_env)
+        .map(|(component, connection)| {
+            Ok(ResolvedComponent {
+                name: component.name.clone(),
+                env: canonical_env(component, connection, host_env, &components)?,
+            })
+        })
+        .collect()
+}
+
+/// Orders a component's env: declared, then connection, then inherited
+/// variables, each sorted by key; earlier groups shadow later ones.
+fn canonical_env(
+    component: &ComponentDecl,
+    mut connection: Vec<(String, String)>,
+    host_env: &dyn Fn(&str) -> Option<String>,
+    components: &HashMap<&str, &ComponentDecl>,
+) -> Result<Vec<(String, String)>> {
+    let mut env = component
+        .env
+        .iter()
+        .map(|(key, value)| {
+            let value =
+                interpolate(value, components).map_err(|message| ContainustError::Config {
+                    message: format!("env {key} of component '{}': {message}", component.name),
+                })?;
+            Ok((key.clone(), value))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    connection.sort_by(|a, b| a.0.cmp(&b.0));
+    connection.dedup_by(|later, earlier| later.0 == earlier.0);
+    connection.retain(|(key, _)| !component.env.contains_key(key));
+    env.extend(connection);
+
+    let mut inherited: Vec<(String, String)> = component
+        .inherit_env
+        .iter()
+        .filter(|name| !env.iter().any(|(key, _)| key == *name))
+        .filter_map(|name| host_env(name).map(|value| (name.clone(), value)))
+        .collect();
+    inherited.sort_by(|a, b| a.0.cmp(&b.0));
+    inherited.dedup_by(|later, earlier| later.0 == earlier.0);
+    env.extend(inherited);
+    Ok(env)
+}
+
+/// Substitutes every `${name.field}` reference in `value`.
+///
+/// Text in `${...}` that is not shaped like `name.field` is kept as is.
+fn interpolate(
+    value: &str,
+    components: &HashMap<&str, &ComponentDecl>,
+) -> std::result::Result<String, String> {
+    let mut output = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find(REFERENCE_PREFIX) {
+        output.push_str(&rest[..start]);
+        let reference = &rest[start..];
+        let Some(end) = reference.find('}') else {
+            break;
+        };
+        let placeholder = &reference[..=end];
+        match placeholder[REFERENCE_PREFIX.len()..end].rsplit_once('.') {
+            Some((name, field))
+                if !RESERVED_NAMESPACES.contains(&name)
+                    && containust_common::types::validate_name(name).is_ok()
+                    && crate::hostenv::is_valid_name(field) =>
+            {
+                output.push_str(&resolve_reference(placeholder, name, field, components)?);
+            }
+            _ => output.push_str(placeholder),
+        }
+        rest = &reference[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Resolves one `${name.field}` reference to its value.
+fn resolve_reference(
+    placeholder: &str,
+    name: &str,
+    field: &str,
+    components: &HashMap<&str, &ComponentDecl>,
+) -> std::result::Result<String, String> {
+    let component = components
+        .get(name)
+        .ok_or_else(|| format!("{placeholder} references undefined component '{name}'"))?;
+    match field {
+        "host" => Ok(component.name.clone()),
+        "port" => component.port.map(|port| port.to_string()).ok_or_else(|| {
+            format!("{placeholder} references component '{name}', which declares no port")
+        }),
+        _ => Err(format!(
+            "{placeholder} references unknown field '{field}' (expected host or port)"
+        )),
+    }
 }
 
 fn inject_connection_env(
-    source: &mut ResolvedComponent,
+    source: &mut Vec<(String, String)>,
     conn: &crate::parser::ast::ConnectionDecl,
-    target_comp: &crate::parser::ast::ComponentDecl,
+    target_comp: &ComponentDecl,
 ) {
     let target_upper = conn.to.to_uppercase();
     let port = target_comp.port.map_or_else(String::new, |p| p.to_string());
 
-    source
-        .env
-        .push((format!("{target_upper}_HOST"), conn.to.clone()));
+    source.push((format!("{target_upper}_HOST"), conn.to.clone()));
     if !port.is_empty() {
-        source.env.push((format!("{target_upper}_PORT"), port));
+        source.push((format!("{target_upper}_PORT"), port));
     }
 }
 
@@ -89,7 +216,7 @@ mod tests {
     use std::collections::BTreeMap;
 
     use super::*;
-    use crate::parser::ast::{ComponentDecl, ConnectionDecl};
+    use crate::parser::ast::{ConnectionDecl, DependencyDecl};
 
     #[test]
     fn resolve_empty_file() {
@@ -112,6 +239,9 @@ mod tests {
                 ..ComponentDecl::default()
             }],
             connections: Vec::new(),
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
         let resolved = resolve_connections(&file).expect("should resolve");
         assert_eq!(resolved.len(), 1);
@@ -145,6 +275,9 @@ mod tests {
                 from: "api".into(),
                 to: "db".into(),
             }],
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
 
         let resolved = resolve_connections(&file).expect("should resolve");
@@ -174,6 +307,9 @@ mod tests {
                 from: "worker".into(),
                 to: "queue".into(),
             }],
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
 
         let resolved = resolve_connections(&file).expect("should resolve");
@@ -224,6 +360,9 @@ mod tests {
                     to: "cache".into(),
                 },
             ],
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
 
         let resolved = resolve_connections(&file).expect("should resolve");
@@ -248,6 +387,9 @@ mod tests {
                 from: "api".into(),
                 to: "missing".into(),
             }],
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
 
         let result = resolve_connections(&file);
@@ -269,6 +411,9 @@ mod tests {
                 from: "missing".into(),
                 to: "db".into(),
             }],
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
 
         let result = resolve_connections(&file);
@@ -298,6 +443,9 @@ mod tests {
             imports: Vec::new(),
             components,
             connections,
+            defaults: None,
+            dependencies: Vec::new(),
+            schema: None,
         };
 
         let resolved = resolve_connections(&file).expect("resolve large graph");
@@ -305,4 +453,193 @@ mod tests {
         assert_eq!(resolved[1].env.len(), 2);
         assert_eq!(resolved[component_count - 1].env.len(), 2);
     }
+
+    #[test]
+    fn resolve_inherits_only_listed_and_set_host_vars() {
+        let file = CompositionFile {
+            components: vec![ComponentDecl {
+                name: "app".into(),
+                image: Some("img".into()),
+                env: [("LANG".to_string(), "C".to_string())].into(),
+                inherit_env: vec!["TZ".into(), "LANG".into(), "HTTP_PROXY".into()],
+                ..ComponentDecl::default()
+            }],
+            ..CompositionFile::default()
+        };
+        let host = |name: &str| match name {
+            "TZ" => Some("Europe/Paris".to_string()),
+            "LANG" => Some("fr_FR.UTF-8".to_string()),
+            "SECRET" => Some("leak".to_string()),
+            _ => None,
+        };
+        let resolved = resolve_connections_with(&file, &host).expect("should resolve");
+        let env = &resolved[0].env;
+        assert!(env.contains(&("TZ".into(), "Europe/Paris".into())));
+        assert!(env.contains(&("LANG".into(), "C".into())));
+        assert!(!env.iter().any(|(k, _)| k == "HTTP_PROXY"));
+        assert!(!env.iter().any(|(k, _)| k == "SECRET"));
+        assert_eq!(env.iter().filter(|(k, _)| k == "LANG").count(), 1);
+    }
+
+    fn wired_file(connections: &[(&str, &str)]) -> CompositionFile {
+        let component = |name: &str, port: Option<u16>| ComponentDecl {
+            name: name.into(),
+            image: Some("img".into()),
+            port,
+            ..ComponentDecl::default()
+        };
+        CompositionFile {
+            components: vec![
+                ComponentDecl {
+                    env: [
+                        ("ZONE".to_string(), "eu".to_string()),
+                        ("APP_MODE".to_string(), "prod".to_string()),
+                    ]
+                    .into(),
+                    inherit_env: vec!["TZ".into(), "LANG".into()],
+                    ..component("api", None)
+                },
+                component("db", Some(5432)),
+                component("cache", Some(6379)),
+                component("auth", None),
+            ],
+            connections: connections
+                .iter()
+                .map(|(from, to)| ConnectionDecl {
+                    from: (*from).into(),
+                    to: (*to).into(),
+                })
+                .collect(),
+            ..CompositionFile::default()
+        }
+    }
+
+    fn api_env(file: &CompositionFile) -> Vec<(String, String)> {
+        let host = |name: &str| match name {
+            "TZ" => Some("UTC".to_string()),
+            "LANG" => Some("C".to_string()),
+            _ => None,
+        };
+        let resolved = resolve_connections_with(file, &host).expect("should resolve");
+        resolved
+            .into_iter()
+            .find(|r| r.name == "api")
+            .expect("api")
+            .env
+    }
+
+    #[test]
+    fn resolve_orders_declared_then_connection_then_inherited_env() {
+        let file = wired_file(&[("api", "db"), ("api", "cache"), ("api", "auth")]);
+        let keys: Vec<String> = api_env(&file).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(
+            keys,
+            [
+                "APP_MODE",
+                "ZONE",
+                "AUTH_HOST",
+                "CACHE_HOST",
+                "CACHE_PORT",
+                "DB_HOST",
+                "DB_PORT",
+                "LANG",
+                "TZ",
+            ]
+        );
+        assert_eq!(api_env(&file), api_env(&file));
+    }
+
+    #[test]
+    fn resolve_depends_on_injects_no_env() {
+        let mut file = wired_file(&[]);
+        file.dependencies.push(DependencyDecl {
+            from: "api".into(),
+            to: "db".into(),
+        });
+        let keys: Vec<String> = api_env(&file).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["APP_MODE", "ZONE", "LANG", "TZ"]);
+    }
+
+    #[test]
+    fn resolve_env_is_independent_of_connection_order() {
+        let forward = wired_file(&[("api", "db"), ("api", "cache"), ("api", "auth")]);
+        let reversed = wired_file(&[("api", "auth"), ("api", "cache"), ("api", "db")]);
+        assert_eq!(api_env(&forward), api_env(&reversed));
+    }
+
+    #[test]
+    fn resolve_declared_env_shadows_connection_env() {
+        let mut file = wired_file(&[("api", "db")]);
+        let _ = file.components[0]
+            .env
+            .insert("DB_HOST".into(), "primary.internal".into());
+        let env = api_env(&file);
+        let hosts: Vec<&(String, String)> = env.iter().filter(|(k, _)| k == "DB_HOST").collect();
+        assert_eq!(
+            hosts,
+            [&("DB_HOST".to_string(), "primary.internal".to_string())]
+        );
+    }
+
+    fn url_file(url: &str) -> CompositionFile {
+        CompositionFile {
+            components: vec![
+                ComponentDecl {
+                    name: "api".into(),
+                    image: Some("img".into()),
+                    env: [
+                        ("DATABASE_URL".to_string(), url.to_string()),
+                        (
+                            "SCRIPT".to_string(),
+                            "echo ${HOME} ${secret.key}".to_string(),
+                        ),
+                    ]
+                    .into(),
+                    ..ComponentDecl::default()
+                },
+                ComponentDecl {
+                    name: "db".into(),
+                    image: Some("pg".into()),
+                    port: Some(5432),
+                    ..ComponentDecl::default()
+                },
+            ],
+            ..CompositionFile::default()
+        }
+    }
+
+    #[test]
+    fn resolve_interpolates_component_host_and_port() {
+        let file = url_file("postgres://${db.host}:${db.port}/app");
+        let env = api_env(&file);
+        assert!(env.contains(&("DATABASE_URL".into(), "postgres://db:5432/app".into())));
+        assert!(env.contains(&("SCRIPT".into(), "echo ${HOME} ${secret.key}".into())));
+    }
+
+    #[test]
+    fn resolve_rejects_reference_to_unknown_component() {
+        let error = resolve_connections(&url_file("postgres://${dbx.host}/app"))
+            .expect_err("unknown component");
+        let message = error.to_string();
+        assert!(message.contains("${dbx.host}"), "{message}");
+        assert!(message.contains("undefined component 'dbx'"), "{message}");
+        assert!(message.contains("DATABASE_URL"), "{message}");
+    }
+
+    #[test]
+    fn resolve_rejects_reference_to_unknown_field() {
+        let error = resolve_connections(&url_file("postgres://${db.address}/app"))
+            .expect_err("unknown field");
+        let message = error.to_string();
+        assert!(message.contains("${db.address}"), "{message}");
+        assert!(message.contains("unknown field 'address'"), "{message}");
+    }
+
+    #[test]
+    fn resolve_rejects_port_of_portless_component() {
+        let mut file = url_file("${db.port}");
+        file.components[1].port = None;
+        let error = resolve_connections(&file).expect_err("no port");
+        assert!(error.to_string().contains("declares no port"), "{error}");
+    }
 }
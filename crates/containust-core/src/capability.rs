@@ -5,32 +5,227 @@
 
 use containust_common::error::{ContainustError, Result};
 
-/// Linux capability identifiers.
+/// Linux capability identifiers, numbered as in `linux/capability.h`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Capability {
-    /// Allow binding to privileged ports (< 1024).
-    NetBindService,
-    /// Allow setting file ownership.
+    /// Allow changing file ownership.
     Chown,
+    /// Bypass file read, write, and execute permission checks.
+    DacOverride,
+    /// Bypass file read and directory search permission checks.
+    DacReadSearch,
+    /// Bypass checks that require the file owner's UID.
+    Fowner,
+    /// Keep set-user-ID and set-group-ID bits when a file is modified.
+    Fsetid,
     /// Allow sending signals to arbitrary processes.
     Kill,
-    /// Allow setting user/group IDs.
-    Setuid,
     /// Allow setting group IDs.
     Setgid,
+    /// Allow setting user IDs.
+    Setuid,
+    /// Allow modifying capability sets.
+    Setpcap,
+    /// Allow setting immutable and append-only file attributes.
+    LinuxImmutable,
+    /// Allow binding to privileged ports (< 1024).
+    NetBindService,
+    /// Allow socket broadcasting and multicast listening.
+    NetBroadcast,
+    /// Allow network administration.
+    NetAdmin,
+    /// Allow raw and packet sockets.
+    NetRaw,
+    /// Allow locking memory.
+    IpcLock,
+    /// Bypass System V IPC permission checks.
+    IpcOwner,
+    /// Allow loading and unloading kernel modules.
+    SysModule,
+    /// Allow raw I/O port access.
+    SysRawio,
+    /// Allow `chroot`.
+    SysChroot,
+    /// Allow tracing arbitrary processes.
+    SysPtrace,
+    /// Allow configuring process accounting.
+    SysPacct,
+    /// Allow a broad range of system administration.
+    SysAdmin,
+    /// Allow rebooting and loading a new kernel.
+    SysBoot,
+    /// Allow raising priorities and setting scheduling policies.
+    SysNice,
+    /// Allow overriding resource limits.
+    SysResource,
+    /// Allow setting the system clock.
+    SysTime,
+    /// Allow configuring terminals.
+    SysTtyConfig,
+    /// Allow creating special files.
+    Mknod,
+    /// Allow taking file leases.
+    Lease,
+    /// Allow writing to the kernel audit log.
+    AuditWrite,
+    /// Allow configuring kernel auditing.
+    AuditControl,
+    /// Allow setting file capabilities.
+    Setfcap,
+    /// Bypass mandatory access control.
+    MacOverride,
+    /// Allow configuring mandatory access control.
+    MacAdmin,
+    /// Allow privileged `syslog` operations.
+    Syslog,
+    /// Allow triggering wake-up alarms.
+    WakeAlarm,
+    /// Allow blocking system suspend.
+    BlockSuspend,
+    /// Allow reading the audit log over netlink.
+    AuditRead,
+    /// Allow performance monitoring.
+    Perfmon,
+    /// Allow privileged BPF operations.
+    Bpf,
+    /// Allow checkpoint/restore operations.
+    CheckpointRestore,
 }
 
-#[cfg(target_os = "linux")]
+/// Every capability, indexed by its Linux capability number.
+const ALL: [Capability; 41] = [
+    Capability::Chown,
+    Capability::DacOverride,
+    Capability::DacReadSearch,
+    Capability::Fowner,
+    Capability::Fsetid,
+    Capability::Kill,
+    Capability::Setgid,
+    Capability::Setuid,
+    Capability::Setpcap,
+    Capability::LinuxImmutable,
+    Capability::NetBindService,
+    Capability::NetBroadcast,
+    Capability::NetAdmin,
+    Capability::NetRaw,
+    Capability::IpcLock,
+    Capability::IpcOwner,
+    Capability::SysModule,
+    Capability::SysRawio,
+    Capability::SysChroot,
+    Capability::SysPtrace,
+    Capability::SysPacct,
+    Capability::SysAdmin,
+    Capability::SysBoot,
+    Capability::SysNice,
+    Capability::SysResource,
+    Capability::SysTime,
+    Capability::SysTtyConfig,
+    Capability::Mknod,
+    Capability::Lease,
+    Capability::AuditWrite,
+    Capability::AuditControl,
+    Capability::Setfcap,
+    Capability::MacOverride,
+    Capability::MacAdmin,
+    Capability::Syslog,
+    Capability::WakeAlarm,
+    Capability::BlockSuspend,
+    Capability::AuditRead,
+    Capability::Perfmon,
+    Capability::Bpf,
+    Capability::CheckpointRestore,
+];
+
+/// Kernel names without the `CAP_` prefix, indexed like [`ALL`].
+const NAMES: [&str; 41] = [
+    "CHOWN",
+    "DAC_OVERRIDE",
+    "DAC_READ_SEARCH",
+    "FOWNER",
+    "FSETID",
+    "KILL",
+    "SETGID",
+    "SETUID",
+    "SETPCAP",
+    "LINUX_IMMUTABLE",
+    "NET_BIND_SERVICE",
+    "NET_BROADCAST",
+    "NET_ADMIN",
+    "NET_RAW",
+    "IPC_LOCK",
+    "IPC_OWNER",
+    "SYS_MODULE",
+    "SYS_RAWIO",
+    "SYS_CHROOT",
+    "SYS_PTRACE",
+    "SYS_PACCT",
+    "SYS_ADMIN",
+    "SYS_BOOT",
+    "SYS_NICE",
+    "SYS_RESOURCE",
+    "SYS_TIME",
+    "SYS_TTY_CONFIG",
+    "MKNOD",
+    "LEASE",
+    "AUDIT_WRITE",
+    "AUDIT_CONTROL",
+    "SETFCAP",
+    "MAC_OVERRIDE",
+    "MAC_ADMIN",
+    "SYSLOG",
+    "WAKE_ALARM",
+    "BLOCK_SUSPEND",
+    "AUDIT_READ",
+    "PERFMON",
+    "BPF",
+    "CHECKPOINT_RESTORE",
+];
+
 impl Capability {
     /// Returns the Linux capability number for this capability.
     const fn linux_cap_number(self) -> u32 {
-        match self {
-            Self::Chown => 0,
-            Self::Kill => 5,
-            Self::Setgid => 6,
-            Self::Setuid => 7,
-            Self::NetBindService => 10,
-        }
+        self as u32
+    }
+
+    /// Returns the kernel name, e.g. `CAP_NET_BIND_SERVICE`.
+    #[must_use]
+    pub fn name(self) -> String {
+        format!("CAP_{}", NAMES[self as usize])
+    }
+
+    /// Parses a capability name such as `CAP_NET_BIND_SERVICE`.
+    ///
+    /// Matching is case-insensitive and the `CAP_` prefix is optional.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContainustError::Config`] when the name matches no
+    /// capability.
+    pub fn parse(name: &str) -> Result<Self> {
+        let upper = name.trim().to_ascii_uppercase();
+        let bare = upper.strip_prefix("CAP_").unwrap_or(&upper);
+        NAMES
+            .iter()
+            .position(|known| *known == bare)
+            .map(|index| ALL[index])
+            .ok_or_else(|| ContainustError::Config {
+                message: format!("unknown capability '{name}'"),
+            })
+    }
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+impl std::str::FromStr for Capability {
+    type Err = ContainustError;
+
+    fn from_str(name: &str) -> Result<Self> {
+        Self::parse(name)
     }
 }
 
@@ -42,10 +237,6 @@ const CAP_LAST_CAP: u32 = 40;
 /// Drops all Linux capabilities except those in the allowlist and
 /// sets `NO_NEW_PRIVS` so privilege escalation via setuid is blocked.
 ///
-/// Iterates over capability numbers `0..=CAP_LAST_CAP` and drops each
-/// one that is not in the `keep` set using `prctl(PR_CAPBSET_DROP)`.
-/// Unknown numbers return `EINVAL` and are skipped.
-///
 /// # Errors
 ///
 /// Returns an error if capability manipulation fails. Callers must
@@ -53,19 +244,123 @@ const CAP_LAST_CAP: u32 = 40;
 #[cfg(target_os = "linux")]
 pub fn drop_capabilities(keep: &[Capability]) -> Result<()> {
     set_no_new_privs()?;
+    drop_all_except(keep)?;
+    tracing::info!(retained = keep.len(), "capabilities dropped");
+    Ok(())
+}
+
+/// Removes every capability not in `keep` from the bounding, effective,
+/// permitted, inheritable, and ambient sets of the calling thread.
+///
+/// Bounding-set entries are dropped with `prctl(PR_CAPBSET_DROP)` for
+/// numbers `0..=CAP_LAST_CAP`; numbers the kernel does not know return
+/// `EINVAL` and are skipped. The remaining sets are narrowed with
+/// `capset`, so a kept capability the thread does not already hold stays
+/// absent.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::PermissionDenied`] if any set cannot be
+/// changed. Callers must fail closed.
+#[cfg(target_os = "linux")]
+pub fn drop_all_except(keep: &[Capability]) -> Result<()> {
     let kept_caps: std::collections::HashSet<u32> =
         keep.iter().map(|c| c.linux_cap_number()).collect();
-
     for cap in 0..=CAP_LAST_CAP {
         if kept_caps.contains(&cap) {
             continue;
         }
         drop_single_cap(cap)?;
     }
-    tracing::info!(retained = keep.len(), "capabilities dropped");
+    narrow_thread_sets(keep_mask(keep))?;
+    clear_ambient()
+}
+
+/// Bit mask of `keep` split into the two 32-bit words `capset` expects.
+#[cfg(target_os = "linux")]
+fn keep_mask(keep: &[Capability]) -> [u32; 2] {
+    let mut mask = [0_u32; 2];
+    for cap in keep {
+        let number = cap.linux_cap_number();
+        mask[(number / 32) as usize] |= 1 << (number % 32);
+    }
+    mask
+}
+
+/// `struct __user_cap_header_struct`.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+struct CapHeader {
+    version: u32,
+    pid: libc::c_int,
+}
+
+/// `struct __user_cap_data_struct`.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct CapData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+/// `_LINUX_CAPABILITY_VERSION_3`: 64-bit sets as two data words.
+#[cfg(target_os = "linux")]
+const CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+#[cfg(target_os = "linux")]
+fn narrow_thread_sets(mask: [u32; 2]) -> Result<()> {
+    let mut header = CapHeader {
+        version: CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let mut data = [CapData::default(); 2];
+    // SAFETY: header and data match the kernel's v3 layout and `data`
+    // holds the two words v3 reads and writes.
+    let ret = unsafe { libc::syscall(libc::SYS_capget, &raw mut header, data.as_mut_ptr()) };
+    if ret != 0 {
+        return Err(capability_error("read capability sets"));
+    }
+    for (word, keep) in data.iter_mut().zip(mask) {
+        word.effective &= keep;
+        word.permitted &= keep;
+        word.inheritable &= keep;
+    }
+    // SAFETY: as above; capset only ever lowers the sets here.
+    let ret = unsafe { libc::syscall(libc::SYS_capset, &raw mut header, data.as_ptr()) };
+    if ret != 0 {
+        return Err(capability_error("narrow capability sets"));
+    }
     Ok(())
 }
 
+#[cfg(target_os = "linux")]
+fn clear_ambient() -> Result<()> {
+    // SAFETY: PR_CAP_AMBIENT_CLEAR_ALL only removes ambient capabilities.
+    let ret = unsafe {
+        libc::prctl(
+            libc::PR_CAP_AMBIENT,
+            libc::PR_CAP_AMBIENT_CLEAR_ALL,
+            0,
+            0,
+            0,
+        )
+    };
+    // Kernels before 4.3 have no ambient set (EINVAL): nothing to clear.
+    if ret == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EINVAL) {
+        return Ok(());
+    }
+    Err(capability_error("clear ambient capabilities"))
+}
+
+#[cfg(target_os = "linux")]
+fn capability_error(action: &str) -> ContainustError {
+    ContainustError::PermissionDenied {
+        message: format!("failed to {action}: {}", std::io::Error::last_os_error()),
+    }
+}
+
 #[cfg(target_os = "linux")]
 fn set_no_new_privs() -> Result<()> {
     // SAFETY: PR_SET_NO_NEW_PRIVS with args (1, 0, 0, 0) is the documented
@@ -111,11 +406,22 @@ pub fn drop_capabilities(_keep: &[Capability]) -> Result<()> {
     })
 }
 
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — capability management requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn drop_all_except(_keep: &[Capability]) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[cfg(target_os = "linux")]
     #[test]
     fn capability_linux_cap_number_matches_known_values() {
         assert_eq!(Capability::Chown.linux_cap_number(), 0);
@@ -125,7 +431,6 @@ mod tests {
         assert_eq!(Capability::NetBindService.linux_cap_number(), 10);
     }
 
-    #[cfg(target_os = "linux")]
     #[test]
     fn capability_linux_cap_number_all_distinct() {
         let caps = [
@@ -150,6 +455,65 @@ mod tests {
         assert_eq!(CAP_LAST_CAP, 40);
     }
 
+    #[test]
+    fn every_capability_sits_at_its_number() {
+        for (number, cap) in (0_u32..).zip(ALL) {
+            assert_eq!(cap.linux_cap_number(), number, "{cap:?}");
+        }
+    }
+
+    #[test]
+    fn parse_accepts_kernel_names() {
+        assert_eq!(
+            Capability::parse("CAP_NET_BIND_SERVICE").unwrap(),
+            Capability::NetBindService
+        );
+        assert_eq!(
+            Capability::parse("CAP_CHECKPOINT_RESTORE").unwrap(),
+            Capability::CheckpointRestore
+        );
+        assert_eq!(
+            "CAP_CHOWN".parse::<Capability>().unwrap(),
+            Capability::Chown
+        );
+    }
+
+    #[test]
+    fn parse_is_case_insensitive_and_prefix_optional() {
+        assert_eq!(Capability::parse("net_raw").unwrap(), Capability::NetRaw);
+        assert_eq!(
+            Capability::parse("cap_sys_admin").unwrap(),
+            Capability::SysAdmin
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_names() {
+        for name in ["CAP_FLY", "", "CAP_", "NET BIND SERVICE"] {
+            let error = Capability::parse(name).unwrap_err().to_string();
+            assert!(error.contains("unknown capability"), "{name}: {error}");
+        }
+    }
+
+    #[test]
+    fn name_round_trips_through_parse() {
+        for cap in ALL {
+            assert_eq!(Capability::parse(&cap.name()).unwrap(), cap);
+        }
+        assert_eq!(Capability::Kill.to_string(), "CAP_KILL");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn keep_mask_sets_bits_in_both_words() {
+        assert_eq!(keep_mask(&[]), [0, 0]);
+        assert_eq!(
+            keep_mask(&[Capability::Chown, Capability::NetBindService]),
+            [1 | 1 << 10, 0]
+        );
+        assert_eq!(keep_mask(&[Capability::Bpf]), [0, 1 << 7]);
+    }
+
     #[test]
     fn capability_copy_trait_allows_duplication() {
         let cap = Capability::Kill;
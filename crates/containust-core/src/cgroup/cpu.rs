@@ -5,6 +5,13 @@
 use std::path::Path;
 
 use containust_common::error::{ContainustError, Result};
+use containust_common::types::ResourceLimits;
+
+/// Period used when a quota is set without one (the kernel default).
+pub const DEFAULT_CPU_PERIOD_US: u64 = 100_000;
+
+/// Periods the kernel accepts in `cpu.max`, in microseconds.
+pub const CPU_PERIOD_RANGE_US: std::ops::RangeInclusive<u64> = 1_000..=1_000_000;
 
 /// Sets the CPU weight (shares) for a cgroup.
 ///
@@ -44,9 +51,13 @@ pub fn set_cpu_weight(_cgroup_path: &Path, _weight: u64) -> Result<()> {
 ///
 /// # Errors
 ///
-/// Returns an error if writing to `cpu.max` fails.
+/// Returns [`ContainustError::Config`] if `quota_us` is below
+/// [`ResourceLimits::MIN_CPU_QUOTA_US`] or `period_us` is outside
+/// [`CPU_PERIOD_RANGE_US`], or an I/O error if writing to `cpu.max` fails.
 #[cfg(target_os = "linux")]
 pub fn set_cpu_max(cgroup_path: &Path, quota_us: u64, period_us: u64) -> Result<()> {
+    validate_cpu_quota(quota_us)?;
+    validate_cpu_period(period_us)?;
     let file = cgroup_path.join("cpu.max");
     let value = format!("{quota_us} {period_us}");
     std::fs::write(&file, value).map_err(|e| ContainustError::Io {
@@ -69,6 +80,33 @@ pub fn set_cpu_max(_cgroup_path: &Path, _quota_us: u64, _period_us: u64) -> Resu
     })
 }
 
+/// Rejects quotas the kernel would refuse with `EINVAL`.
+fn validate_cpu_quota(quota_us: u64) -> Result<()> {
+    if quota_us >= ResourceLimits::MIN_CPU_QUOTA_US {
+        return Ok(());
+    }
+    Err(ContainustError::Config {
+        message: format!(
+            "CPU quota {quota_us}us is below the {}us minimum",
+            ResourceLimits::MIN_CPU_QUOTA_US
+        ),
+    })
+}
+
+/// Rejects periods the kernel would refuse with `EINVAL`.
+fn validate_cpu_period(period_us: u64) -> Result<()> {
+    if CPU_PERIOD_RANGE_US.contains(&period_us) {
+        return Ok(());
+    }
+    Err(ContainustError::Config {
+        message: format!(
+            "CPU period {period_us}us is outside {}..={}us",
+            CPU_PERIOD_RANGE_US.start(),
+            CPU_PERIOD_RANGE_US.end()
+        ),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,6 +150,39 @@ mod tests {
         let _ = std::fs::remove_dir_all(&temp);
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn set_cpu_max_writes_quota_and_period() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        set_cpu_max(dir.path(), 25_000, 50_000).expect("set cpu.max");
+        let written = std::fs::read_to_string(dir.path().join("cpu.max")).expect("read");
+        assert_eq!(written, "25000 50000");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn set_cpu_max_rejects_period_out_of_range() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        for period in [0, 999, 1_000_001] {
+            let error = set_cpu_max(dir.path(), 10_000, period).expect_err("period");
+            assert!(matches!(error, ContainustError::Config { .. }), "{error}");
+        }
+        assert!(!dir.path().join("cpu.max").exists());
+    }
+
+    #[test]
+    fn cpu_quota_below_the_kernel_minimum_is_rejected() {
+        assert!(validate_cpu_quota(999).is_err());
+        assert!(validate_cpu_quota(1_000).is_ok());
+    }
+
+    #[test]
+    fn cpu_period_bounds_are_inclusive() {
+        assert!(validate_cpu_period(1_000).is_ok());
+        assert!(validate_cpu_period(1_000_000).is_ok());
+        assert!(validate_cpu_period(DEFAULT_CPU_PERIOD_US).is_ok());
+    }
+
     /// Requires root and cgroup v2 hierarchy.
     #[test]
     #[ignore = "requires root privileges"]
@@ -0,0 +1,348 @@
+//! CPU and NUMA pinning via the cgroups v2 cpuset controller.
+//!
+//! Parses cpu-list strings (`"0-3"`, `"0,2,4"`, `"0-1,6"`) into sorted
+//! core or node numbers and manages `cpuset.cpus` and `cpuset.mems`.
+
+use std::path::Path;
+
+use containust_common::error::{ContainustError, Result};
+
+/// Kernel list of online CPUs, in cpu-list syntax.
+pub const ONLINE_CPUS_PATH: &str = "/sys/devices/system/cpu/online";
+
+/// Highest core or node number a list may name. The kernel supports at
+/// most 8192 CPUs, so larger numbers and ranges are rejected while
+/// parsing rather than expanded.
+pub const MAX_LIST_INDEX: u32 = 8191;
+
+/// Directory holding one `node<N>` entry per NUMA node.
+pub const NUMA_NODES_DIR: &str = "/sys/devices/system/node";
+
+/// Parses a cpu-list such as `"0-3"` or `"0,2,4"` into sorted, distinct
+/// core numbers.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] if the list is empty, an entry is
+/// not a core number or `start-end` range, a range runs backwards, or a
+/// number exceeds [`MAX_LIST_INDEX`].
+pub fn parse_cpuset(spec: &str) -> Result<Vec<u32>> {
+    parse_list("cpuset", spec)
+}
+
+/// Parses a NUMA node list such as `"0"` or `"0-1"`, in the same syntax
+/// as [`parse_cpuset`].
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] if the list is malformed.
+pub fn parse_numa_nodes(spec: &str) -> Result<Vec<u32>> {
+    parse_list("NUMA node list", spec)
+}
+
+fn parse_list(kind: &str, spec: &str) -> Result<Vec<u32>> {
+    let invalid = |detail: String| ContainustError::Config {
+        message: format!("invalid {kind} '{spec}': {detail}"),
+    };
+    let core = |text: &str| {
+        let core = text
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| invalid(format!("'{}' is not a number", text.trim())))?;
+        if core > MAX_LIST_INDEX {
+            return Err(invalid(format!(
+                "{core} exceeds the maximum {MAX_LIST_INDEX}"
+            )));
+        }
+        Ok(core)
+    };
+    let mut cores = Vec::new();
+    for entry in spec.split(',') {
+        match entry.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (core(start)?, core(end)?);
+                if start > end {
+                    return Err(invalid(format!("range {start}-{end} runs backwards")));
+                }
+                cores.extend(start..=end);
+            }
+            None => cores.push(core(entry)?),
+        }
+    }
+    cores.sort_unstable();
+    cores.dedup();
+    Ok(cores)
+}
+
+/// Checks that every core is in `online`, the host's online CPU set.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] naming the first core that is not
+/// online.
+pub fn validate_cpuset(cores: &[u32], online: &[u32]) -> Result<()> {
+    if let Some(core) = cores.iter().find(|core| !online.contains(core)) {
+        return Err(ContainustError::Config {
+            message: format!(
+                "cpuset core {core} is not online; online CPUs are {}",
+                format_cpuset(online)
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Decides the `cpuset.mems` value for requested NUMA `nodes` given the
+/// host's `available` nodes.
+///
+/// A host with fewer than two nodes is not NUMA: node `0` is accepted,
+/// anything else is skipped with a warning (`Ok(None)`).
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] when a NUMA host lacks a requested
+/// node.
+pub fn resolve_numa_nodes(nodes: &[u32], available: &[u32]) -> Result<Option<Vec<u32>>> {
+    if available.len() < 2 {
+        if nodes == [0] {
+            return Ok(Some(nodes.to_vec()));
+        }
+        tracing::warn!(
+            nodes = %format_cpuset(nodes),
+            "host is not NUMA; numa_node pinning skipped"
+        );
+        return Ok(None);
+    }
+    if let Some(node) = nodes.iter().find(|node| !available.contains(node)) {
+        return Err(ContainustError::Config {
+            message: format!(
+                "NUMA node {node} does not exist; this host has nodes {}",
+                format_cpuset(available)
+            ),
+        });
+    }
+    Ok(Some(nodes.to_vec()))
+}
+
+/// Lists the host's NUMA nodes from the `node<N>` entries of `dir`
+/// (normally [`NUMA_NODES_DIR`]); empty when `dir` is missing.
+#[must_use]
+pub fn numa_nodes_in(dir: &Path) -> Vec<u32> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut nodes: Vec<u32> = entries
+        .filter_map(|entry| {
+            entry
+                .ok()?
+                .file_name()
+                .to_str()?
+                .strip_prefix("node")?
+                .parse()
+                .ok()
+        })
+        .collect();
+    nodes.sort_unstable();
+    nodes
+}
+
+/// Formats cores or nodes as the compact cpu-list written to
+/// `cpuset.cpus` and `cpuset.mems`, e.g. `[0, 1, 2, 6]` → `"0-2,6"`.
+#[must_use]
+pub fn format_cpuset(cores: &[u32]) -> String {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for &core in cores {
+        match ranges.last_mut() {
+            Some((_, end)) if end.checked_add(1) == Some(core) => *end = core,
+            _ => ranges.push((core, core)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Returns the online CPUs listed in [`ONLINE_CPUS_PATH`].
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed.
+pub fn online_cpus() -> Result<Vec<u32>> {
+    let text = std::fs::read_to_string(ONLINE_CPUS_PATH).map_err(|e| ContainustError::Io {
+        path: ONLINE_CPUS_PATH.into(),
+        source: e,
+    })?;
+    parse_cpuset(text.trim())
+}
+
+/// Pins a cgroup to `cores` by writing `cpuset.cpus`.
+///
+/// # Errors
+///
+/// Returns an error if writing to `cpuset.cpus` fails, e.g. when the
+/// cpuset controller is not enabled for the cgroup.
+#[cfg(target_os = "linux")]
+pub fn set_cpuset_cpus(cgroup_path: &Path, cores: &[u32]) -> Result<()> {
+    let file = cgroup_path.join("cpuset.cpus");
+    let value = format_cpuset(cores);
+    std::fs::write(&file, &value).map_err(|e| ContainustError::Io {
+        path: file,
+        source: e,
+    })?;
+    tracing::debug!(cpus = %value, "cpuset set");
+    Ok(())
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — cgroup cpuset control requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn set_cpuset_cpus(_cgroup_path: &Path, _cores: &[u32]) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+/// Restricts a cgroup's memory to NUMA `nodes` by writing `cpuset.mems`.
+///
+/// # Errors
+///
+/// Returns an error if writing to `cpuset.mems` fails.
+#[cfg(target_os = "linux")]
+pub fn set_cpuset_mems(cgroup_path: &Path, nodes: &[u32]) -> Result<()> {
+    let file = cgroup_path.join("cpuset.mems");
+    let value = format_cpuset(nodes);
+    std::fs::write(&file, &value).map_err(|e| ContainustError::Io {
+        path: file,
+        source: e,
+    })?;
+    tracing::debug!(mems = %value, "cpuset mems set");
+    Ok(())
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — cgroup cpuset control requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn set_cpuset_mems(_cgroup_path: &Path, _nodes: &[u32]) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cpuset_expands_ranges_and_lists() {
+        assert_eq!(parse_cpuset("0-3").expect("range"), [0, 1, 2, 3]);
+        assert_eq!(parse_cpuset("0,2,4").expect("list"), [0, 2, 4]);
+        assert_eq!(parse_cpuset("6, 0-1,1").expect("mixed"), [0, 1, 6]);
+        assert_eq!(parse_cpuset("5").expect("single"), [5]);
+    }
+
+    #[test]
+    fn parse_cpuset_rejects_malformed_entries() {
+        for spec in ["", "a", "0-", "3-1", "0,,1", "-1", "0-4294967295", "8192"] {
+            let error = parse_cpuset(spec).expect_err(spec).to_string();
+            assert!(error.contains("invalid cpuset"), "{spec}: {error}");
+        }
+    }
+
+    #[test]
+    fn validate_cpuset_checks_online_set() {
+        let online = [0, 1, 3, 4];
+        assert!(validate_cpuset(&[0, 4], &online).is_ok());
+        let error = validate_cpuset(&[0, 2], &online)
+            .expect_err("core 2 offline")
+            .to_string();
+        assert!(error.contains("core 2 is not online"), "{error}");
+        assert!(error.contains("online CPUs are 0-1,3-4"), "{error}");
+    }
+
+    #[test]
+    fn format_cpuset_compacts_runs() {
+        assert_eq!(format_cpuset(&[0, 1, 2, 3]), "0-3");
+        assert_eq!(format_cpuset(&[0, 2, 4]), "0,2,4");
+        assert_eq!(format_cpuset(&[0, 1, 2, 6, 8, 9]), "0-2,6,8-9");
+        assert_eq!(format_cpuset(&[]), "");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn set_cpuset_cpus_writes_control_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        set_cpuset_cpus(dir.path(), &[0, 1, 2, 5]).expect("set cpuset");
+        let written = std::fs::read_to_string(dir.path().join("cpuset.cpus")).expect("read");
+        assert_eq!(written, "0-2,5");
+    }
+
+    #[test]
+    fn parse_numa_nodes_names_the_property() {
+        assert_eq!(parse_numa_nodes("0-1").expect("range"), [0, 1]);
+        let error = parse_numa_nodes("x").expect_err("bad node").to_string();
+        assert!(error.contains("invalid NUMA node list 'x'"), "{error}");
+    }
+
+    #[test]
+    fn resolve_numa_nodes_validates_against_available_nodes() {
+        assert_eq!(
+            resolve_numa_nodes(&[1], &[0, 1]).expect("present"),
+            Some(vec![1])
+        );
+        let error = resolve_numa_nodes(&[0, 2], &[0, 1])
+            .expect_err("missing node")
+            .to_string();
+        assert!(error.contains("NUMA node 2 does not exist"), "{error}");
+        assert!(error.contains("nodes 0-1"), "{error}");
+    }
+
+    #[test]
+    fn resolve_numa_nodes_on_non_numa_host_accepts_only_node_zero() {
+        for available in [&[][..], &[0][..]] {
+            assert_eq!(
+                resolve_numa_nodes(&[0], available).expect("node 0"),
+                Some(vec![0])
+            );
+            assert_eq!(resolve_numa_nodes(&[1], available).expect("skipped"), None);
+        }
+    }
+
+    #[test]
+    fn numa_nodes_in_lists_node_entries() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        for name in ["node1", "node0", "possible", "nodeX"] {
+            std::fs::create_dir(dir.path().join(name)).expect("entry");
+        }
+        assert_eq!(numa_nodes_in(dir.path()), [0, 1]);
+        assert!(numa_nodes_in(&dir.path().join("missing")).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn set_cpuset_mems_writes_node_list() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        set_cpuset_mems(dir.path(), &[0, 1, 3]).expect("set mems");
+        let written = std::fs::read_to_string(dir.path().join("cpuset.mems")).expect("read");
+        assert_eq!(written, "0-1,3");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn online_cpus_reads_host() {
+        assert_ne!(online_cpus().expect("online cpus"), Vec::<u32>::new());
+    }
+}
@@ -0,0 +1,100 @@
+//! Cgroup delegation for rootless users on systemd hosts.
+//!
+//! An unprivileged user may only create cgroups in the subtree systemd
+//! delegates to its user manager,
+//! `user.slice/user-<uid>.slice/user@<uid>.service`. systemd hands the
+//! directory to the user only when delegation is enabled, so ownership
+//! tells whether it is usable.
+
+use std::path::{Path, PathBuf};
+
+use containust_common::error::{ContainustError, Result};
+
+/// How to turn on delegation for the user manager.
+const DELEGATION_HINT: &str = "enable cgroup delegation for the systemd user manager \
+     (`sudo systemctl edit user@.service` with `[Service]` `Delegate=cpu cpuset io memory pids`), \
+     then log in again and check `systemctl --user status`";
+
+/// Returns the cgroup systemd delegates to the user manager of `uid`
+/// under the hierarchy mounted at `root`.
+#[must_use]
+pub fn user_delegation_path(root: &Path, uid: u32) -> PathBuf {
+    root.join("user.slice")
+        .join(format!("user-{uid}.slice"))
+        .join(format!("user@{uid}.service"))
+}
+
+/// Returns the delegated cgroup of `uid` once it exists and belongs to
+/// that user.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::UnsupportedKernelFeature`] with a
+/// `systemctl --user` hint when the cgroup is missing or still owned by
+/// root, i.e. delegation is not enabled.
+#[cfg(unix)]
+pub fn delegated_root(root: &Path, uid: u32) -> Result<PathBuf> {
+    use std::os::unix::fs::MetadataExt;
+
+    let path = user_delegation_path(root, uid);
+    match std::fs::metadata(&path) {
+        Ok(metadata) if metadata.is_dir() && metadata.uid() == uid => Ok(path),
+        _ => Err(not_delegated(&path)),
+    }
+}
+
+/// Stub for non-Unix platforms.
+///
+/// # Errors
+///
+/// Always returns an error — cgroup delegation requires Linux.
+#[cfg(not(unix))]
+pub fn delegated_root(root: &Path, uid: u32) -> Result<PathBuf> {
+    Err(not_delegated(&user_delegation_path(root, uid)))
+}
+
+fn not_delegated(path: &Path) -> ContainustError {
+    ContainustError::UnsupportedKernelFeature {
+        feature: format!("cgroup delegation ({} is not delegated)", path.display()),
+        hint: DELEGATION_HINT.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delegation_path_derives_from_uid() {
+        assert_eq!(
+            user_delegation_path(Path::new("/sys/fs/cgroup"), 1000),
+            Path::new("/sys/fs/cgroup/user.slice/user-1000.slice/user@1000.service")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn missing_delegation_is_unsupported_with_hint() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let error = delegated_root(dir.path(), 1000).expect_err("not delegated");
+        assert!(
+            matches!(&error, ContainustError::UnsupportedKernelFeature { hint, .. }
+                if hint.contains("systemctl --user")),
+            "{error}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn delegated_cgroup_owned_by_user_is_used() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let uid = nix::unistd::geteuid().as_raw();
+        let path = user_delegation_path(dir.path(), uid);
+        std::fs::create_dir_all(&path).expect("mkdir");
+        assert_eq!(delegated_root(dir.path(), uid).expect("delegated"), path);
+        // Owned by someone else: systemd has not handed it over.
+        let other = user_delegation_path(dir.path(), uid + 1);
+        std::fs::create_dir_all(&other).expect("mkdir");
+        assert!(delegated_root(dir.path(), uid + 1).is_err());
+    }
+}
@@ -0,0 +1,90 @@
+//! Whole-cgroup termination.
+//!
+//! Used to force-stop a container including every process it forked,
+//! not just its init PID.
+
+use std::path::Path;
+
+use containust_common::error::{ContainustError, Result};
+
+/// Sends `SIGKILL` to every process in a cgroup.
+///
+/// Writes `1` to `cgroup.kill` (Linux 5.14+). On older kernels, where the
+/// file does not exist, each PID listed in `cgroup.procs` is killed
+/// individually; PIDs that already exited are ignored.
+///
+/// # Errors
+///
+/// Returns an error if the control files cannot be written or read.
+#[cfg(target_os = "linux")]
+pub fn kill_all(cgroup_path: &Path) -> Result<()> {
+    let kill_file = cgroup_path.join("cgroup.kill");
+    if kill_file.exists() {
+        std::fs::write(&kill_file, "1").map_err(|e| ContainustError::Io {
+            path: kill_file,
+            source: e,
+        })?;
+        tracing::debug!(path = %cgroup_path.display(), "cgroup killed");
+        return Ok(());
+    }
+
+    let procs_file = cgroup_path.join("cgroup.procs");
+    let procs = std::fs::read_to_string(&procs_file).map_err(|e| ContainustError::Io {
+        path: procs_file,
+        source: e,
+    })?;
+    for pid in procs
+        .lines()
+        .filter_map(|line| line.trim().parse::<i32>().ok())
+    {
+        let _ = nix::sys::signal::kill(
+            nix::unistd::Pid::from_raw(pid),
+            nix::sys::signal::Signal::SIGKILL,
+        );
+    }
+    tracing::debug!(path = %cgroup_path.display(), "cgroup processes killed individually");
+    Ok(())
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — cgroup control requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn kill_all(_cgroup_path: &Path) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn kill_all_writes_cgroup_kill_when_supported() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("cgroup.kill"), "").expect("kill file");
+        kill_all(dir.path()).expect("kill");
+        let written = std::fs::read_to_string(dir.path().join("cgroup.kill")).expect("read");
+        assert_eq!(written, "1");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn kill_all_falls_back_to_procs_list() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("cgroup.procs"), "").expect("procs file");
+        kill_all(dir.path()).expect("kill");
+        assert!(!dir.path().join("cgroup.kill").exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn kill_all_missing_cgroup_is_error() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert!(kill_all(&dir.path().join("gone")).is_err());
+    }
+}
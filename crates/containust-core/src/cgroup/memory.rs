@@ -1,6 +1,7 @@
 //! Memory resource control via cgroups v2.
 //!
-//! Manages `memory.max`, `memory.high`, and related control files.
+//! Manages `memory.max`, `memory.high`, `memory.low`, `memory.swap.max`,
+//! and related control files.
 
 use std::path::Path;
 
@@ -66,10 +67,125 @@ pub fn set_memory_high(_cgroup_path: &Path, _bytes: u64) -> Result<()> {
     })
 }
 
+/// Sets the memory reservation (`memory.low`).
+///
+/// Memory below this amount is protected from reclaim while the host has
+/// unprotected memory to reclaim instead.
+///
+/// # Errors
+///
+/// Returns an error if writing to `memory.low` fails.
+#[cfg(target_os = "linux")]
+pub fn set_memory_low(cgroup_path: &Path, bytes: u64) -> Result<()> {
+    let file = cgroup_path.join("memory.low");
+    std::fs::write(&file, bytes.to_string()).map_err(|e| ContainustError::Io {
+        path: file,
+        source: e,
+    })?;
+    tracing::debug!(bytes, "memory reservation set");
+    Ok(())
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — cgroup memory control requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn set_memory_low(_cgroup_path: &Path, _bytes: u64) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+/// Caps the swap a cgroup may use beyond its memory limit; `0` disables
+/// swap.
+///
+/// Hosts without swap accounting have no `memory.swap.max`; the limit is
+/// then skipped with a warning instead of failing the container. Returns
+/// whether the limit was written.
+///
+/// # Errors
+///
+/// Returns an error if writing to `memory.swap.max` fails.
+#[cfg(target_os = "linux")]
+pub fn set_swap_max(cgroup_path: &Path, bytes: u64) -> Result<bool> {
+    let file = cgroup_path.join("memory.swap.max");
+    if !file.exists() {
+        tracing::warn!(
+            cgroup = %cgroup_path.display(),
+            "swap accounting is unavailable on this host; swap limit not applied"
+        );
+        return Ok(false);
+    }
+    std::fs::write(&file, bytes.to_string()).map_err(|e| ContainustError::Io {
+        path: file,
+        source: e,
+    })?;
+    tracing::debug!(bytes, "swap max limit set");
+    Ok(true)
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — cgroup memory control requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn set_swap_max(_cgroup_path: &Path, _bytes: u64) -> Result<bool> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+/// Returns the counter named `key` from `memory.events` content
+/// (`<key> <count>` per line).
+#[must_use]
+pub fn parse_event_count(events: &str, key: &str) -> Option<u64> {
+    events.lines().find_map(|line| {
+        let (name, count) = line.split_once(' ')?;
+        (name == key).then(|| count.trim().parse().ok())?
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn set_memory_low_and_high_write_control_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        set_memory_low(dir.path(), 64 * 1024 * 1024).expect("set low");
+        set_memory_high(dir.path(), 96 * 1024 * 1024).expect("set high");
+        let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).expect(name);
+        assert_eq!(read("memory.low"), "67108864");
+        assert_eq!(read("memory.high"), "100663296");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn set_swap_max_writes_value_when_accounted() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("memory.swap.max");
+        std::fs::write(&file, "max").expect("seed control file");
+        for bytes in [0, 64 * 1024 * 1024] {
+            assert!(set_swap_max(dir.path(), bytes).expect("set swap"));
+            assert_eq!(
+                std::fs::read_to_string(&file).expect("read"),
+                bytes.to_string()
+            );
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn set_swap_max_skips_hosts_without_swap_accounting() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert!(!set_swap_max(dir.path(), 0).expect("skipped"));
+        assert!(!dir.path().join("memory.swap.max").exists());
+    }
+
     #[test]
     fn memory_max_file_path_constructed_correctly() {
         let cgroup_path = Path::new("/sys/fs/cgroup/containust/app1");
@@ -105,6 +221,14 @@ mod tests {
         assert_eq!(bytes.to_string(), "0");
     }
 
+    #[test]
+    fn parse_event_count_finds_oom_kill() {
+        let events = "low 0\nhigh 12\nmax 3\noom 2\noom_kill 1\noom_group_kill 0\n";
+        assert_eq!(parse_event_count(events, "oom_kill"), Some(1));
+        assert_eq!(parse_event_count(events, "oom"), Some(2));
+        assert_eq!(parse_event_count(events, "missing"), None);
+    }
+
     /// Requires root and cgroup v2 hierarchy.
     #[test]
     #[ignore = "requires root privileges"]
@@ -1,18 +1,135 @@
 //! Cgroups v2 resource management.
 //!
 //! Provides interfaces for creating cgroup hierarchies and setting
-//! resource limits for CPU, memory, and I/O via the unified hierarchy
-//! at `/sys/fs/cgroup`.
+//! resource limits for CPU, memory, I/O, and process count via the unified hierarchy.
+//! The hierarchy root defaults to `/sys/fs/cgroup` and can be overridden
+//! through [`CgroupConfig`] for rootless/delegated setups and tests.
 
 pub mod cpu;
+pub mod cpuset;
+pub mod delegation;
 pub mod io;
+pub mod kill;
 pub mod memory;
+pub mod pids;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use containust_common::constants::{CGROUP_HIERARCHY_NAME, CGROUP_V2_PATH};
 use containust_common::error::{ContainustError, Result};
 use containust_common::types::ResourceLimits;
 
+/// Location of the Containust cgroup hierarchy.
+///
+/// Container cgroups live at `<root>/<hierarchy>/<container_id>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CgroupConfig {
+    /// Mount point of the cgroups v2 unified hierarchy.
+    pub root: PathBuf,
+    /// Name of the Containust sub-hierarchy under `root`.
+    pub hierarchy: String,
+}
+
+impl Default for CgroupConfig {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from(CGROUP_V2_PATH),
+            hierarchy: CGROUP_HIERARCHY_NAME.into(),
+        }
+    }
+}
+
+impl CgroupConfig {
+    /// Creates a configuration rooted at `root` with the default sub-hierarchy name.
+    #[must_use]
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the directory of the Containust sub-hierarchy.
+    #[must_use]
+    pub fn hierarchy_path(&self) -> PathBuf {
+        self.root.join(&self.hierarchy)
+    }
+
+    /// Returns the cgroup directory for a container ID.
+    #[must_use]
+    pub fn container_path(&self, container_id: &str) -> PathBuf {
+        self.hierarchy_path().join(container_id)
+    }
+
+    /// Returns the hierarchy this process may create cgroups in.
+    ///
+    /// Root uses the default hierarchy; a rootless user gets the subtree
+    /// systemd delegated to its user manager (see [`delegation`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContainustError::UnsupportedKernelFeature`] when running
+    /// rootless without a delegated user cgroup.
+    #[cfg(target_os = "linux")]
+    pub fn for_current_user() -> Result<Self> {
+        let uid = nix::unistd::geteuid();
+        if uid.is_root() {
+            return Ok(Self::default());
+        }
+        let root = delegation::delegated_root(Path::new(CGROUP_V2_PATH), uid.as_raw())?;
+        Ok(Self::with_root(root))
+    }
+
+    /// Returns the default hierarchy; cgroups exist only on Linux.
+    ///
+    /// # Errors
+    ///
+    /// Never fails on non-Linux platforms.
+    #[cfg(not(target_os = "linux"))]
+    pub fn for_current_user() -> Result<Self> {
+        Ok(Self::default())
+    }
+}
+
+/// Why applying cgroup limits failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitFailure {
+    /// The hierarchy exists but this process may not write to it
+    /// (`EACCES` / `EPERM`), typically a rootless or restricted host.
+    PermissionDenied,
+    /// A control file is missing because its controller is not enabled
+    /// or the cgroups v2 hierarchy is not mounted.
+    ControllerUnavailable,
+    /// Any other failure.
+    Other,
+}
+
+impl LimitFailure {
+    /// Classifies an error returned while creating or configuring a cgroup.
+    #[must_use]
+    pub fn classify(error: &ContainustError) -> Self {
+        match error {
+            ContainustError::Io { source, .. } => match source.kind() {
+                std::io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+                std::io::ErrorKind::NotFound => Self::ControllerUnavailable,
+                _ => Self::Other,
+            },
+            ContainustError::UnsupportedKernelFeature { .. } => Self::ControllerUnavailable,
+            _ => Self::Other,
+        }
+    }
+
+    /// Short description used in warnings and errors.
+    #[must_use]
+    pub const fn describe(self) -> &'static str {
+        match self {
+            Self::PermissionDenied => "permission denied",
+            Self::ControllerUnavailable => "controller unavailable",
+            Self::Other => "failed",
+        }
+    }
+}
+
 /// Handle to a cgroup for a specific container.
 #[derive(Debug)]
 pub struct CgroupManager {
@@ -21,6 +138,29 @@ pub struct CgroupManager {
     path: PathBuf,
 }
 
+/// Live memory usage read back from a cgroup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CgroupStats {
+    /// Current usage (`memory.current`).
+    pub memory_current_bytes: u64,
+    /// Highest usage recorded (`memory.peak`); `None` on kernels before
+    /// 5.19, which lack the file.
+    pub memory_peak_bytes: Option<u64>,
+    /// Processes killed by the OOM killer (`oom_kill` in `memory.events`).
+    pub oom_kills: u64,
+}
+
+impl CgroupManager {
+    /// Returns a handle to the existing cgroup of `container_id` without
+    /// touching the filesystem.
+    #[must_use]
+    pub fn open(config: &CgroupConfig, container_id: &str) -> Self {
+        Self {
+            path: config.container_path(container_id),
+        }
+    }
+}
+
 #[cfg(target_os = "linux")]
 impl CgroupManager {
     /// Creates a new cgroup for the given container ID.
@@ -31,8 +171,17 @@ impl CgroupManager {
     ///
     /// Returns an error if the cgroup directory cannot be created.
     pub fn create(container_id: &str) -> Result<Self> {
-        let parent = PathBuf::from(containust_common::constants::CGROUP_V2_PATH).join("containust");
-        let path = parent.join(container_id);
+        Self::create_in(&CgroupConfig::default(), container_id)
+    }
+
+    /// Creates a new cgroup for the given container ID under a custom hierarchy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cgroup directory cannot be created.
+    pub fn create_in(config: &CgroupConfig, container_id: &str) -> Result<Self> {
+        let parent = config.hierarchy_path();
+        let path = config.container_path(container_id);
         std::fs::create_dir_all(&parent).map_err(|e| ContainustError::Io {
             path: parent.clone(),
             source: e,
@@ -46,26 +195,92 @@ impl CgroupManager {
         Ok(Self { path })
     }
 
+    /// Returns the path of this container's cgroup directory.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     /// Applies resource limits to this cgroup.
     ///
-    /// Delegates to subsystem-specific writers for CPU, memory, and I/O.
+    /// Validates the limits, then delegates to subsystem-specific writers
+    /// for CPU, memory, I/O, and PIDs.
     ///
     /// # Errors
     ///
-    /// Returns an error if writing to cgroup control files fails.
+    /// Returns an error if a limit is out of range (nothing is written) or
+    /// writing to cgroup control files fails.
     pub fn apply_limits(&self, limits: &ResourceLimits) -> Result<()> {
+        limits.validate()?;
         if let Some(mem) = limits.memory_bytes {
             memory::set_memory_max(&self.path, mem)?;
         }
+        if let Some(low) = limits.memory_low_bytes {
+            memory::set_memory_low(&self.path, low)?;
+        }
+        if let Some(high) = limits.memory_high_bytes {
+            memory::set_memory_high(&self.path, high)?;
+        }
+        if let Some(swap) = limits.swap_bytes {
+            let _ = memory::set_swap_max(&self.path, swap)?;
+        }
         if let Some(cpu_weight) = limits.cpu_shares {
             cpu::set_cpu_weight(&self.path, cpu_weight)?;
         }
+        if let Some(cores) = &limits.cpuset_cpus {
+            cpuset::set_cpuset_cpus(&self.path, cores)?;
+        }
+        if let Some(nodes) = &limits.cpuset_mems {
+            cpuset::set_cpuset_mems(&self.path, nodes)?;
+        }
+        if let Some(quota_us) = limits.cpu_quota_us {
+            let period_us = limits.cpu_period_us.unwrap_or(cpu::DEFAULT_CPU_PERIOD_US);
+            cpu::set_cpu_max(&self.path, quota_us, period_us)?;
+        }
         if let Some(io_weight) = limits.io_weight {
             io::set_io_weight(&self.path, io_weight)?;
         }
+        if let Some(pids_max) = limits.pids_max {
+            pids::set_pids_max(&self.path, pids_max)?;
+        }
         Ok(())
     }
 
+    /// Reads current and peak memory usage and the OOM kill count.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `memory.current` or `memory.events` cannot be
+    /// read or parsed, e.g. when the memory controller is disabled.
+    pub fn stats(&self) -> Result<CgroupStats> {
+        let read = |name: &str| {
+            let file = self.path.join(name);
+            std::fs::read_to_string(&file).map_err(|e| ContainustError::Io {
+                path: file,
+                source: e,
+            })
+        };
+        let parse = |name: &str, text: &str| {
+            text.trim().parse().map_err(|_| ContainustError::Config {
+                message: format!("cgroup {name} is not a byte count: {}", text.trim()),
+            })
+        };
+        let memory_peak_bytes = match read("memory.peak") {
+            Ok(text) => Some(parse("memory.peak", &text)?),
+            Err(ContainustError::Io { source, .. })
+                if source.kind() == std::io::ErrorKind::NotFound =>
+            {
+                None
+            }
+            Err(error) => return Err(error),
+        };
+        Ok(CgroupStats {
+            memory_current_bytes: parse("memory.current", &read("memory.current")?)?,
+            memory_peak_bytes,
+            oom_kills: memory::parse_event_count(&read("memory.events")?, "oom_kill").unwrap_or(0),
+        })
+    }
+
     /// Adds a process to this cgroup by writing its PID.
     ///
     /// # Errors
@@ -101,15 +316,16 @@ impl CgroupManager {
     }
 }
 
-/// Enables the cpu, memory, and io controllers for child cgroups.
+/// Enables the cpu, cpuset, memory, io, and pids controllers for child
+/// cgroups.
 ///
 /// Best effort: a controller missing from the kernel or the parent cgroup
 /// is logged, and any limit that later requires it fails closed in
 /// [`CgroupManager::apply_limits`].
 #[cfg(target_os = "linux")]
-fn enable_subtree_controllers(parent: &std::path::Path) {
+fn enable_subtree_controllers(parent: &Path) {
     let control = parent.join("cgroup.subtree_control");
-    for controller in ["+cpu", "+memory", "+io"] {
+    for controller in ["+cpu", "+cpuset", "+memory", "+io", "+pids"] {
         if let Err(error) = std::fs::write(&control, controller) {
             tracing::warn!(
                 controller,
@@ -133,6 +349,23 @@ impl CgroupManager {
         })
     }
 
+    /// Stub for non-Linux platforms.
+    ///
+    /// # Errors
+    ///
+    /// Always returns an error — cgroup management requires Linux.
+    pub fn create_in(_config: &CgroupConfig, _container_id: &str) -> Result<Self> {
+        Err(ContainustError::Config {
+            message: "Linux required for native container operations".into(),
+        })
+    }
+
+    /// Returns the path of this container's cgroup directory.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     /// Stub for non-Linux platforms.
     ///
     /// # Errors
@@ -144,6 +377,17 @@ impl CgroupManager {
         })
     }
 
+    /// Stub for non-Linux platforms.
+    ///
+    /// # Errors
+    ///
+    /// Always returns an error — cgroup management requires Linux.
+    pub fn stats(&self) -> Result<CgroupStats> {
+        Err(ContainustError::Config {
+            message: "Linux required for native container operations".into(),
+        })
+    }
+
     /// Stub for non-Linux platforms.
     ///
     /// # Errors
@@ -177,10 +421,165 @@ mod tests {
         let expected = PathBuf::from("/sys/fs/cgroup/containust/my-container");
         assert_eq!(
             expected,
-            PathBuf::from(containust_common::constants::CGROUP_V2_PATH)
-                .join("containust")
-                .join("my-container")
+            CgroupConfig::default().container_path("my-container")
+        );
+    }
+
+    fn io_error(kind: std::io::ErrorKind) -> ContainustError {
+        ContainustError::Io {
+            path: PathBuf::from("/sys/fs/cgroup/containust/app/memory.max"),
+            source: std::io::Error::from(kind),
+        }
+    }
+
+    #[test]
+    fn limit_failure_classifies_io_errors() {
+        assert_eq!(
+            LimitFailure::classify(&io_error(std::io::ErrorKind::PermissionDenied)),
+            LimitFailure::PermissionDenied
         );
+        assert_eq!(
+            LimitFailure::classify(&io_error(std::io::ErrorKind::NotFound)),
+            LimitFailure::ControllerUnavailable
+        );
+        assert_eq!(
+            LimitFailure::classify(&io_error(std::io::ErrorKind::InvalidInput)),
+            LimitFailure::Other
+        );
+        let config = ContainustError::Config {
+            message: "Linux required".into(),
+        };
+        assert_eq!(LimitFailure::classify(&config), LimitFailure::Other);
+    }
+
+    #[test]
+    fn cgroup_config_custom_root_and_hierarchy() {
+        let config = CgroupConfig {
+            root: PathBuf::from("/run/user/1000/cgroup"),
+            hierarchy: "ctst-test".into(),
+        };
+        assert_eq!(
+            config.container_path("web"),
+            PathBuf::from("/run/user/1000/cgroup/ctst-test/web")
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn create_in_custom_root_creates_hierarchy() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = CgroupConfig::with_root(dir.path());
+        let mgr = CgroupManager::create_in(&config, "proj/app").expect("create");
+        assert_eq!(mgr.path(), dir.path().join("containust/proj/app"));
+        assert!(mgr.path().is_dir());
+        assert!(
+            dir.path()
+                .join("containust/cgroup.subtree_control")
+                .exists()
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn apply_limits_custom_root_writes_all_control_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = CgroupConfig::with_root(dir.path());
+        let mgr = CgroupManager::create_in(&config, "app").expect("create");
+        // The kernel creates memory.swap.max only with swap accounting.
+        std::fs::write(mgr.path().join("memory.swap.max"), "max").expect("seed swap");
+        let limits = ResourceLimits {
+            cpu_shares: Some(512),
+            memory_bytes: Some(536_870_912),
+            io_weight: Some(100),
+            pids_max: Some(64),
+            cpu_quota_us: Some(50_000),
+            cpu_period_us: None,
+            swap_bytes: Some(0),
+            memory_low_bytes: Some(268_435_456),
+            memory_high_bytes: Some(402_653_184),
+            cpuset_cpus: Some(vec![0, 1]),
+            cpuset_mems: Some(vec![0]),
+        };
+        mgr.apply_limits(&limits).expect("apply limits");
+        mgr.add_process(4242).expect("add process");
+
+        let read = |name: &str| std::fs::read_to_string(mgr.path().join(name)).expect(name);
+        assert_eq!(read("memory.max"), "536870912");
+        assert_eq!(read("memory.swap.max"), "0");
+        assert_eq!(read("memory.low"), "268435456");
+        assert_eq!(read("memory.high"), "402653184");
+        assert_eq!(read("cpuset.cpus"), "0-1");
+        assert_eq!(read("cpuset.mems"), "0");
+        assert_eq!(read("cpu.weight"), "512");
+        assert_eq!(read("io.weight"), "100");
+        assert_eq!(read("pids.max"), "64");
+        assert_eq!(read("cpu.max"), "50000 100000");
+        assert_eq!(read("cgroup.procs"), "4242");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn stats_reads_usage_peak_and_oom_kills() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = CgroupConfig::with_root(dir.path());
+        let mgr = CgroupManager::create_in(&config, "app").expect("create");
+        let write = |name: &str, text: &str| {
+            std::fs::write(mgr.path().join(name), text).expect(name);
+        };
+        write("memory.current", "1048576\n");
+        write("memory.peak", "4194304\n");
+        write("memory.events", "low 0\nhigh 0\nmax 7\noom 3\noom_kill 2\n");
+
+        let stats = CgroupManager::open(&config, "app").stats().expect("stats");
+        assert_eq!(
+            stats,
+            CgroupStats {
+                memory_current_bytes: 1_048_576,
+                memory_peak_bytes: Some(4_194_304),
+                oom_kills: 2,
+            }
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn stats_tolerates_missing_peak_but_not_missing_usage() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = CgroupConfig::with_root(dir.path());
+        let mgr = CgroupManager::create_in(&config, "app").expect("create");
+        std::fs::write(mgr.path().join("memory.events"), "oom_kill 0\n").expect("events");
+        assert!(matches!(mgr.stats(), Err(ContainustError::Io { .. })));
+
+        std::fs::write(mgr.path().join("memory.current"), "512").expect("current");
+        let stats = mgr.stats().expect("stats");
+        assert_eq!(stats.memory_current_bytes, 512);
+        assert_eq!(stats.memory_peak_bytes, None);
+        assert_eq!(stats.oom_kills, 0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn destroy_custom_root_removes_empty_cgroup() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = CgroupConfig::with_root(dir.path());
+        let mgr = CgroupManager::create_in(&config, "app").expect("create");
+        mgr.destroy().expect("destroy");
+        assert!(!mgr.path().exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn apply_limits_rejects_invalid_limits_before_writing() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = CgroupConfig::with_root(dir.path());
+        let mgr = CgroupManager::create_in(&config, "app").expect("create");
+        let limits = ResourceLimits {
+            memory_bytes: Some(536_870_912),
+            io_weight: Some(0),
+            ..ResourceLimits::default()
+        };
+        assert!(mgr.apply_limits(&limits).is_err());
+        assert!(!mgr.path().join("memory.max").exists());
     }
 
     #[test]
@@ -197,6 +596,14 @@ mod tests {
             cpu_shares: Some(512),
             memory_bytes: Some(536_870_912),
             io_weight: Some(100),
+            pids_max: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
         };
         assert_eq!(limits.cpu_shares, Some(512));
         assert_eq!(limits.memory_bytes, Some(536_870_912));
@@ -233,6 +640,14 @@ mod tests {
             // `io.weight` only exists on kernels with BFQ/iocost; probed below
             // so the fixture is portable across CI kernels.
             io_weight: None,
+            pids_max: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
         };
         mgr.apply_limits(&limits).expect("apply cpu+memory limits");
         if mgr.path.join("io.weight").exists() {
@@ -0,0 +1,51 @@
+//! Process-count control via cgroups v2.
+//!
+//! Manages `pids.max` to bound fork bombs inside a container.
+
+use std::path::Path;
+
+use containust_common::error::{ContainustError, Result};
+
+/// Sets the maximum number of processes for a cgroup.
+///
+/// `fork`/`clone` fail with `EAGAIN` once the limit is reached.
+///
+/// # Errors
+///
+/// Returns an error if writing to `pids.max` fails.
+#[cfg(target_os = "linux")]
+pub fn set_pids_max(cgroup_path: &Path, max: u64) -> Result<()> {
+    let file = cgroup_path.join("pids.max");
+    std::fs::write(&file, max.to_string()).map_err(|e| ContainustError::Io {
+        path: file,
+        source: e,
+    })?;
+    tracing::debug!(max, "pids max limit set");
+    Ok(())
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — cgroup PID control requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn set_pids_max(_cgroup_path: &Path, _max: u64) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn set_pids_max_writes_control_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        set_pids_max(dir.path(), 128).expect("set pids.max");
+        let written = std::fs::read_to_string(dir.path().join("pids.max")).expect("read");
+        assert_eq!(written, "128");
+    }
+}
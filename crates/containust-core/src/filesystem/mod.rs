@@ -1,8 +1,12 @@
 //! Filesystem management for container isolation.
 //!
 //! Provides `OverlayFS` layer management, `pivot_root` for secure root
-//! filesystem switching, and mount utilities.
+//! filesystem switching, and mount utilities. On Linux every mount
+//! syscall goes through [`ops::MountOps`] so setup sequences are testable
+//! without privileges.
 
 pub mod mount;
+#[cfg(target_os = "linux")]
+pub mod ops;
 pub mod overlayfs;
 pub mod pivot_root;
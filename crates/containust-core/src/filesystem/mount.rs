@@ -3,10 +3,24 @@
 //! Handles mounting `/proc`, `/sys`, `/dev`, and bind mounts
 //! inside the container's namespace.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use containust_common::error::{ContainustError, Result};
 
+#[cfg(target_os = "linux")]
+use super::ops::{LinuxMountOps, MountOps, MountRequest};
+
+/// A host-to-container bind mount.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindMount {
+    /// Host path being exposed.
+    pub source: PathBuf,
+    /// Mount point inside the container rootfs.
+    pub target: PathBuf,
+    /// Whether the mount is remounted read-only.
+    pub readonly: bool,
+}
+
 /// Mounts essential pseudo-filesystems (`/proc`, `/sys`, `/dev`) inside the container.
 ///
 /// - `/proc` is mounted with `nosuid`, `nodev`, `noexec`.
@@ -18,55 +32,43 @@ use containust_common::error::{ContainustError, Result};
 /// Returns an error if any mount syscall fails.
 #[cfg(target_os = "linux")]
 pub fn mount_essential_filesystems(rootfs: &Path) -> Result<()> {
-    use nix::mount::{MsFlags, mount};
-
-    let proc_path = rootfs.join("proc");
-    std::fs::create_dir_all(&proc_path).map_err(|e| ContainustError::Io {
-        path: proc_path.clone(),
-        source: e,
-    })?;
-    mount(
-        Some("proc"),
-        &proc_path,
-        Some("proc"),
-        MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC,
-        None::<&str>,
-    )
-    .map_err(|e| ContainustError::PermissionDenied {
-        message: format!("mount /proc failed: {e}"),
-    })?;
+    mount_essential_filesystems_with(&LinuxMountOps, rootfs)
+}
 
-    let sys_path = rootfs.join("sys");
-    std::fs::create_dir_all(&sys_path).map_err(|e| ContainustError::Io {
-        path: sys_path.clone(),
-        source: e,
-    })?;
-    mount(
-        Some("sysfs"),
-        &sys_path,
-        Some("sysfs"),
-        MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC | MsFlags::MS_RDONLY,
-        None::<&str>,
-    )
-    .map_err(|e| ContainustError::PermissionDenied {
-        message: format!("mount /sys failed: {e}"),
-    })?;
+/// Mounts `/proc`, `/sys`, and `/dev` under `rootfs` through `ops`.
+///
+/// # Errors
+///
+/// Returns an error if a mount point cannot be created or a mount fails.
+#[cfg(target_os = "linux")]
+pub fn mount_essential_filesystems_with(ops: &impl MountOps, rootfs: &Path) -> Result<()> {
+    use nix::mount::MsFlags;
 
-    let dev_path = rootfs.join("dev");
-    std::fs::create_dir_all(&dev_path).map_err(|e| ContainustError::Io {
-        path: dev_path.clone(),
-        source: e,
-    })?;
-    mount(
-        Some("tmpfs"),
-        &dev_path,
-        Some("tmpfs"),
-        MsFlags::MS_NOSUID | MsFlags::MS_STRICTATIME,
-        Some("mode=755,size=65536k"),
-    )
-    .map_err(|e| ContainustError::PermissionDenied {
-        message: format!("mount /dev failed: {e}"),
-    })?;
+    let mounts = [
+        MountRequest::filesystem(
+            "proc",
+            rootfs.join("proc"),
+            MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC,
+        ),
+        MountRequest::filesystem(
+            "sysfs",
+            rootfs.join("sys"),
+            MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC | MsFlags::MS_RDONLY,
+        ),
+        MountRequest::filesystem(
+            "tmpfs",
+            rootfs.join("dev"),
+            MsFlags::MS_NOSUID | MsFlags::MS_STRICTATIME,
+        )
+        .with_data("mode=755,size=65536k"),
+    ];
+    for request in &mounts {
+        std::fs::create_dir_all(&request.target).map_err(|e| ContainustError::Io {
+            path: request.target.clone(),
+            source: e,
+        })?;
+        ops.mount(request)?;
+    }
 
     tracing::debug!(rootfs = %rootfs.display(), "essential filesystems mounted");
     Ok(())
@@ -93,41 +95,42 @@ pub fn mount_essential_filesystems(_rootfs: &Path) -> Result<()> {
 /// Returns an error if the `mount(2)` syscall fails.
 #[cfg(target_os = "linux")]
 pub fn bind_mount(source: &Path, target: &Path, readonly: bool) -> Result<()> {
-    use nix::mount::{MsFlags, mount};
+    let bind = BindMount {
+        source: source.into(),
+        target: target.into(),
+        readonly,
+    };
+    bind_mount_with(&LinuxMountOps, &bind)
+}
 
-    std::fs::create_dir_all(target).map_err(|e| ContainustError::Io {
-        path: target.into(),
+/// Creates the bind mount described by `bind` through `ops`.
+///
+/// # Errors
+///
+/// Returns an error if the target cannot be created or a mount fails.
+#[cfg(target_os = "linux")]
+pub fn bind_mount_with(ops: &impl MountOps, bind: &BindMount) -> Result<()> {
+    std::fs::create_dir_all(&bind.target).map_err(|e| ContainustError::Io {
+        path: bind.target.clone(),
         source: e,
     })?;
 
-    mount(
-        Some(source),
-        target,
-        None::<&str>,
-        MsFlags::MS_BIND | MsFlags::MS_REC,
-        None::<&str>,
-    )
-    .map_err(|e| ContainustError::PermissionDenied {
-        message: format!("bind mount failed: {e}"),
-    })?;
-
-    if readonly {
-        mount(
-            None::<&str>,
-            target,
-            None::<&str>,
-            MsFlags::MS_REMOUNT | MsFlags::MS_BIND | MsFlags::MS_RDONLY,
-            None::<&str>,
-        )
+    ops.mount(&MountRequest::bind(&bind.source, &bind.target))
         .map_err(|e| ContainustError::PermissionDenied {
-            message: format!("readonly remount failed: {e}"),
+            message: format!("bind mount failed: {e}"),
         })?;
+
+    if bind.readonly {
+        ops.mount(&MountRequest::remount_readonly(&bind.target))
+            .map_err(|e| ContainustError::PermissionDenied {
+                message: format!("readonly remount failed: {e}"),
+            })?;
     }
 
     tracing::debug!(
-        source = %source.display(),
-        target = %target.display(),
-        readonly,
+        source = %bind.source.display(),
+        target = %bind.target.display(),
+        readonly = bind.readonly,
         "bind mount created"
     );
     Ok(())
@@ -149,6 +152,69 @@ pub fn bind_mount(_source: &Path, _target: &Path, _readonly: bool) -> Result<()>
 mod tests {
     use super::*;
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mount_essential_filesystems_with_mounts_proc_sys_dev_in_order() {
+        use super::super::ops::{MountCall, RecordingMountOps};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let ops = RecordingMountOps::new();
+        mount_essential_filesystems_with(&ops, dir.path()).expect("mount");
+
+        let calls = ops.calls();
+        let targets: Vec<_> = calls
+            .iter()
+            .filter_map(|call| match call {
+                MountCall::Mount(request) => Some((request.fstype.clone(), request.target.clone())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls.len(), targets.len());
+        assert_eq!(
+            targets,
+            vec![
+                (Some("proc".into()), dir.path().join("proc")),
+                (Some("sysfs".into()), dir.path().join("sys")),
+                (Some("tmpfs".into()), dir.path().join("dev")),
+            ]
+        );
+        assert!(dir.path().join("dev").is_dir());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mount_essential_filesystems_with_stops_at_first_failure() {
+        use super::super::ops::RecordingMountOps;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let ops = RecordingMountOps::failing_at(dir.path().join("sys"));
+        let err = mount_essential_filesystems_with(&ops, dir.path()).expect_err("must fail");
+        assert!(err.to_string().contains("sys"));
+        assert_eq!(ops.calls().len(), 2);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn bind_mount_with_readonly_binds_then_remounts() {
+        use super::super::ops::{MountCall, RecordingMountOps};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let bind = BindMount {
+            source: PathBuf::from("/srv/data"),
+            target: dir.path().join("data"),
+            readonly: true,
+        };
+        let ops = RecordingMountOps::new();
+        bind_mount_with(&ops, &bind).expect("bind");
+        assert_eq!(
+            ops.calls(),
+            vec![
+                MountCall::Mount(MountRequest::bind("/srv/data", &bind.target)),
+                MountCall::Mount(MountRequest::remount_readonly(&bind.target)),
+            ]
+        );
+    }
+
     #[test]
     fn mount_essential_filesystems_paths_constructed_correctly() {
         let rootfs = Path::new("/container/rootfs");
@@ -0,0 +1,302 @@
+//! Mount syscall abstraction.
+//!
+//! [`MountOps`] wraps `mount(2)`, `umount2(2)`, `pivot_root(2)`, and the
+//! accompanying `chdir(2)` so root filesystem setup can be driven against
+//! [`LinuxMountOps`] in production and [`RecordingMountOps`] in unit tests,
+//! where the ordering and arguments of each call are asserted without
+//! privileges or real mounts.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use containust_common::error::{ContainustError, Result};
+use nix::mount::{MntFlags, MsFlags};
+
+/// Arguments of a single `mount(2)` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountRequest {
+    /// Mount source (device, filesystem name, or bind source).
+    pub source: Option<PathBuf>,
+    /// Mount point.
+    pub target: PathBuf,
+    /// Filesystem type, `None` for bind mounts and remounts.
+    pub fstype: Option<String>,
+    /// Mount flags.
+    pub flags: MsFlags,
+    /// Filesystem-specific options.
+    pub data: Option<String>,
+}
+
+impl MountRequest {
+    /// Mounts a filesystem of type `fstype` (also used as the source) at `target`.
+    #[must_use]
+    pub fn filesystem(fstype: &str, target: impl Into<PathBuf>, flags: MsFlags) -> Self {
+        Self {
+            source: Some(PathBuf::from(fstype)),
+            target: target.into(),
+            fstype: Some(fstype.into()),
+            flags,
+            data: None,
+        }
+    }
+
+    /// Recursively bind-mounts `source` onto `target`.
+    #[must_use]
+    pub fn bind(source: impl Into<PathBuf>, target: impl Into<PathBuf>) -> Self {
+        Self {
+            source: Some(source.into()),
+            target: target.into(),
+            fstype: None,
+            flags: MsFlags::MS_BIND | MsFlags::MS_REC,
+            data: None,
+        }
+    }
+
+    /// Remounts an existing bind mount at `target` read-only.
+    #[must_use]
+    pub fn remount_readonly(target: impl Into<PathBuf>) -> Self {
+        Self {
+            source: None,
+            target: target.into(),
+            fstype: None,
+            flags: MsFlags::MS_REMOUNT | MsFlags::MS_BIND | MsFlags::MS_RDONLY,
+            data: None,
+        }
+    }
+
+    /// Changes the propagation type of the mount at `target`.
+    #[must_use]
+    pub fn propagation(target: impl Into<PathBuf>, flags: MsFlags) -> Self {
+        Self {
+            source: None,
+            target: target.into(),
+            fstype: None,
+            flags,
+            data: None,
+        }
+    }
+
+    /// Attaches filesystem-specific options to the request.
+    #[must_use]
+    pub fn with_data(mut self, data: impl Into<String>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+}
+
+/// A mount-related syscall captured by [`RecordingMountOps`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MountCall {
+    /// `mount(2)`.
+    Mount(MountRequest),
+    /// `umount2(2)`.
+    Umount {
+        /// Mount point being detached.
+        target: PathBuf,
+        /// Unmount flags.
+        flags: MntFlags,
+    },
+    /// `pivot_root(2)`.
+    PivotRoot {
+        /// Directory becoming the new root.
+        new_root: PathBuf,
+        /// Directory receiving the old root.
+        put_old: PathBuf,
+    },
+    /// `chdir(2)`.
+    Chdir(PathBuf),
+}
+
+/// Mount syscalls used during container root filesystem setup.
+pub trait MountOps {
+    /// Performs a `mount(2)` call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mount fails.
+    fn mount(&self, request: &MountRequest) -> Result<()>;
+
+    /// Performs an `umount2(2)` call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the unmount fails.
+    fn umount(&self, target: &Path, flags: MntFlags) -> Result<()>;
+
+    /// Performs a `pivot_root(2)` call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the root cannot be swapped.
+    fn pivot_root(&self, new_root: &Path, put_old: &Path) -> Result<()>;
+
+    /// Changes the working directory of the calling process.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be entered.
+    fn chdir(&self, path: &Path) -> Result<()>;
+}
+
+/// [`MountOps`] backed by the real Linux syscalls.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinuxMountOps;
+
+impl MountOps for LinuxMountOps {
+    fn mount(&self, request: &MountRequest) -> Result<()> {
+        nix::mount::mount(
+            request.source.as_deref(),
+            &request.target,
+            request.fstype.as_deref(),
+            request.flags,
+            request.data.as_deref(),
+        )
+        .map_err(|e| ContainustError::PermissionDenied {
+            message: format!("mount {} failed: {e}", request.target.display()),
+        })
+    }
+
+    fn umount(&self, target: &Path, flags: MntFlags) -> Result<()> {
+        nix::mount::umount2(target, flags).map_err(|e| ContainustError::PermissionDenied {
+            message: format!("unmount {} failed: {e}", target.display()),
+        })
+    }
+
+    fn pivot_root(&self, new_root: &Path, put_old: &Path) -> Result<()> {
+        nix::unistd::pivot_root(new_root, put_old).map_err(|e| ContainustError::PermissionDenied {
+            message: format!("pivot_root failed: {e}"),
+        })
+    }
+
+    fn chdir(&self, path: &Path) -> Result<()> {
+        std::env::set_current_dir(path).map_err(|e| ContainustError::Io {
+            path: path.into(),
+            source: e,
+        })
+    }
+}
+
+/// In-memory [`MountOps`] that records every call instead of performing it.
+///
+/// Intended for tests; optionally fails any call targeting a given path so
+/// fail-closed and best-effort branches can be exercised.
+#[derive(Debug, Default)]
+pub struct RecordingMountOps {
+    calls: Mutex<Vec<MountCall>>,
+    fail_target: Option<PathBuf>,
+}
+
+impl RecordingMountOps {
+    /// Creates a recorder where every call succeeds.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a recorder whose calls targeting `target` fail.
+    #[must_use]
+    pub fn failing_at(target: impl Into<PathBuf>) -> Self {
+        Self {
+            calls: Mutex::default(),
+            fail_target: Some(target.into()),
+        }
+    }
+
+    /// Returns the calls recorded so far, in order.
+    #[must_use]
+    pub fn calls(&self) -> Vec<MountCall> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn record(&self, target: &Path, call: MountCall) -> Result<()> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(call);
+        if self.fail_target.as_deref() == Some(target) {
+            return Err(ContainustError::PermissionDenied {
+                message: format!("simulated failure at {}", target.display()),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl MountOps for RecordingMountOps {
+    fn mount(&self, request: &MountRequest) -> Result<()> {
+        self.record(&request.target, MountCall::Mount(request.clone()))
+    }
+
+    fn umount(&self, target: &Path, flags: MntFlags) -> Result<()> {
+        let call = MountCall::Umount {
+            target: target.into(),
+            flags,
+        };
+        self.record(target, call)
+    }
+
+    fn pivot_root(&self, new_root: &Path, put_old: &Path) -> Result<()> {
+        let call = MountCall::PivotRoot {
+            new_root: new_root.into(),
+            put_old: put_old.into(),
+        };
+        self.record(new_root, call)
+    }
+
+    fn chdir(&self, path: &Path) -> Result<()> {
+        self.record(path, MountCall::Chdir(path.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mount_request_bind_sets_recursive_bind_flags() {
+        let request = MountRequest::bind("/src", "/dst");
+        assert_eq!(request.flags, MsFlags::MS_BIND | MsFlags::MS_REC);
+        assert_eq!(request.source.as_deref(), Some(Path::new("/src")));
+        assert!(request.fstype.is_none());
+    }
+
+    #[test]
+    fn mount_request_filesystem_uses_fstype_as_source() {
+        let request =
+            MountRequest::filesystem("tmpfs", "/dev", MsFlags::MS_NOSUID).with_data("mode=755");
+        assert_eq!(request.source.as_deref(), Some(Path::new("tmpfs")));
+        assert_eq!(request.fstype.as_deref(), Some("tmpfs"));
+        assert_eq!(request.data.as_deref(), Some("mode=755"));
+    }
+
+    #[test]
+    fn recording_ops_records_calls_in_order() {
+        let ops = RecordingMountOps::new();
+        ops.mount(&MountRequest::bind("/a", "/b")).expect("mount");
+        ops.chdir(Path::new("/")).expect("chdir");
+        ops.umount(Path::new("/b"), MntFlags::MNT_DETACH)
+            .expect("umount");
+        assert_eq!(
+            ops.calls(),
+            vec![
+                MountCall::Mount(MountRequest::bind("/a", "/b")),
+                MountCall::Chdir(PathBuf::from("/")),
+                MountCall::Umount {
+                    target: PathBuf::from("/b"),
+                    flags: MntFlags::MNT_DETACH,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn recording_ops_failing_at_target_returns_error_and_records() {
+        let ops = RecordingMountOps::failing_at("/b");
+        assert!(ops.mount(&MountRequest::bind("/a", "/c")).is_ok());
+        assert!(ops.mount(&MountRequest::bind("/a", "/b")).is_err());
+        assert_eq!(ops.calls().len(), 2);
+    }
+}
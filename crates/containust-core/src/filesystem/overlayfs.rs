@@ -7,6 +7,9 @@ use std::path::{Path, PathBuf};
 
 use containust_common::error::{ContainustError, Result};
 
+#[cfg(target_os = "linux")]
+use super::ops::{LinuxMountOps, MountOps, MountRequest};
+
 /// Configuration for an `OverlayFS` mount.
 #[derive(Debug, Clone)]
 pub struct OverlayConfig {
@@ -30,47 +33,93 @@ pub struct OverlayConfig {
 /// Returns an error if directory creation fails or if the mount syscall fails.
 #[cfg(target_os = "linux")]
 pub fn mount_overlay(config: &OverlayConfig) -> Result<()> {
-    use nix::mount::{MsFlags, mount};
-
-    std::fs::create_dir_all(&config.upper_dir).map_err(|e| ContainustError::Io {
-        path: config.upper_dir.clone(),
-        source: e,
-    })?;
-    std::fs::create_dir_all(&config.work_dir).map_err(|e| ContainustError::Io {
-        path: config.work_dir.clone(),
-        source: e,
-    })?;
-    std::fs::create_dir_all(&config.merged_dir).map_err(|e| ContainustError::Io {
-        path: config.merged_dir.clone(),
-        source: e,
-    })?;
+    mount_overlay_with(&LinuxMountOps, config)
+}
+
+/// Mounts an `OverlayFS` with the given configuration through `ops`.
+///
+/// # Errors
+///
+/// Returns an error if directory creation fails or if the mount fails.
+#[cfg(target_os = "linux")]
+pub fn mount_overlay_with(ops: &impl MountOps, config: &OverlayConfig) -> Result<()> {
+    for dir in [&config.upper_dir, &config.work_dir, &config.merged_dir] {
+        std::fs::create_dir_all(dir).map_err(|e| ContainustError::Io {
+            path: dir.clone(),
+            source: e,
+        })?;
+    }
+
+    let request =
+        MountRequest::filesystem("overlay", &config.merged_dir, nix::mount::MsFlags::empty())
+            .with_data(overlay_options(config));
+    ops.mount(&request)
+        .map_err(|e| ContainustError::PermissionDenied {
+            message: format!("overlay mount failed: {e}"),
+        })?;
+
+    tracing::info!(merged = %config.merged_dir.display(), "overlayfs mounted");
+    Ok(())
+}
+
+/// Checks whether overlay mounts work with upper and work dirs under
+/// `scratch` by mounting, then unmounting, a throwaway overlay there.
+#[cfg(target_os = "linux")]
+pub fn probe_overlay_support_with(ops: &impl MountOps, scratch: &Path) -> bool {
+    let probe = scratch.join(format!(".overlay-probe-{}", std::process::id()));
+    let config = OverlayConfig {
+        lower_dirs: vec![probe.join("lower")],
+        upper_dir: probe.join("upper"),
+        work_dir: probe.join("work"),
+        merged_dir: probe.join("merged"),
+    };
+    let supported = std::fs::create_dir_all(&config.lower_dirs[0]).is_ok()
+        && mount_overlay_with(ops, &config).is_ok();
+    if supported && let Err(error) = unmount_overlay_with(ops, &config.merged_dir) {
+        tracing::warn!(%error, "overlay probe unmount failed");
+    }
+    let _ = std::fs::remove_dir_all(&probe);
+    supported
+}
 
+/// Returns whether overlay mounts work under `scratch`, probing only on
+/// the first call; later calls reuse that answer for the whole process.
+#[cfg(target_os = "linux")]
+pub fn overlay_supported(scratch: &Path) -> bool {
+    static SUPPORTED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        let supported = probe_overlay_support_with(&LinuxMountOps, scratch);
+        tracing::info!(supported, scratch = %scratch.display(), "overlay support probed");
+        supported
+    })
+}
+
+/// Stub for non-Linux platforms: overlay is never available.
+#[cfg(not(target_os = "linux"))]
+#[must_use]
+pub fn overlay_supported(_scratch: &Path) -> bool {
+    false
+}
+
+/// Builds the `lowerdir=…,upperdir=…,workdir=…` mount options.
+///
+/// The kernel expects `lowerdir` top-most first, the reverse of
+/// [`OverlayConfig::lower_dirs`].
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn overlay_options(config: &OverlayConfig) -> String {
     let lowers = config
         .lower_dirs
         .iter()
+        .rev()
         .map(|p| p.display().to_string())
         .collect::<Vec<_>>()
         .join(":");
-    let opts = format!(
+    format!(
         "lowerdir={},upperdir={},workdir={}",
         lowers,
         config.upper_dir.display(),
         config.work_dir.display()
-    );
-
-    mount(
-        Some("overlay"),
-        &config.merged_dir,
-        Some("overlay"),
-        MsFlags::empty(),
-        Some(opts.as_str()),
     )
-    .map_err(|e| ContainustError::PermissionDenied {
-        message: format!("overlay mount failed: {e}"),
-    })?;
-
-    tracing::info!(merged = %config.merged_dir.display(), "overlayfs mounted");
-    Ok(())
 }
 
 /// Stub for non-Linux platforms.
@@ -94,11 +143,20 @@ pub fn mount_overlay(_config: &OverlayConfig) -> Result<()> {
 /// Returns an error if the unmount syscall fails.
 #[cfg(target_os = "linux")]
 pub fn unmount_overlay(merged_dir: &Path) -> Result<()> {
-    nix::mount::umount2(merged_dir, nix::mount::MntFlags::MNT_DETACH).map_err(|e| {
-        ContainustError::PermissionDenied {
+    unmount_overlay_with(&LinuxMountOps, merged_dir)
+}
+
+/// Lazily unmounts an `OverlayFS` at `merged_dir` through `ops`.
+///
+/// # Errors
+///
+/// Returns an error if the unmount fails.
+#[cfg(target_os = "linux")]
+pub fn unmount_overlay_with(ops: &impl MountOps, merged_dir: &Path) -> Result<()> {
+    ops.umount(merged_dir, nix::mount::MntFlags::MNT_DETACH)
+        .map_err(|e| ContainustError::PermissionDenied {
             message: format!("unmount overlay failed: {e}"),
-        }
-    })?;
+        })?;
     tracing::info!(path = %merged_dir.display(), "overlayfs unmounted");
     Ok(())
 }
@@ -178,19 +236,93 @@ mod tests {
             work_dir: PathBuf::from("/work"),
             merged_dir: PathBuf::from("/merged"),
         };
-        let lowers = config
-            .lower_dirs
-            .iter()
-            .map(|p| p.display().to_string())
-            .collect::<Vec<_>>()
-            .join(":");
-        let opts = format!(
-            "lowerdir={},upperdir={},workdir={}",
-            lowers,
-            config.upper_dir.display(),
-            config.work_dir.display(),
+        assert_eq!(
+            overlay_options(&config),
+            "lowerdir=/lower1,upperdir=/upper,workdir=/work"
+        );
+    }
+
+    #[test]
+    fn overlay_mount_options_list_top_layer_first() {
+        let config = OverlayConfig {
+            lower_dirs: vec![PathBuf::from("/base"), PathBuf::from("/top")],
+            upper_dir: PathBuf::from("/upper"),
+            work_dir: PathBuf::from("/work"),
+            merged_dir: PathBuf::from("/merged"),
+        };
+        assert_eq!(
+            overlay_options(&config),
+            "lowerdir=/top:/base,upperdir=/upper,workdir=/work"
         );
-        assert_eq!(opts, "lowerdir=/lower1,upperdir=/upper,workdir=/work");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn mount_overlay_with_creates_dirs_and_issues_overlay_mount() {
+        use super::super::ops::{MountCall, RecordingMountOps};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = OverlayConfig {
+            lower_dirs: vec![dir.path().join("l1"), dir.path().join("l2")],
+            upper_dir: dir.path().join("upper"),
+            work_dir: dir.path().join("work"),
+            merged_dir: dir.path().join("merged"),
+        };
+        let ops = RecordingMountOps::new();
+        mount_overlay_with(&ops, &config).expect("mount");
+
+        assert!(config.work_dir.is_dir() && config.merged_dir.is_dir());
+        let expected =
+            MountRequest::filesystem("overlay", &config.merged_dir, nix::mount::MsFlags::empty())
+                .with_data(overlay_options(&config));
+        assert_eq!(ops.calls(), vec![MountCall::Mount(expected)]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn unmount_overlay_with_detaches_lazily() {
+        use super::super::ops::{MountCall, RecordingMountOps};
+
+        let ops = RecordingMountOps::new();
+        unmount_overlay_with(&ops, Path::new("/merged")).expect("umount");
+        assert_eq!(
+            ops.calls(),
+            vec![MountCall::Umount {
+                target: PathBuf::from("/merged"),
+                flags: nix::mount::MntFlags::MNT_DETACH,
+            }]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn probe_overlay_support_mounts_and_unmounts_a_throwaway_overlay() {
+        use super::super::ops::{MountCall, RecordingMountOps};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let ops = RecordingMountOps::new();
+        assert!(probe_overlay_support_with(&ops, dir.path()));
+        let calls = ops.calls();
+        assert_eq!(calls.len(), 2);
+        assert!(matches!(&calls[0], MountCall::Mount(request)
+            if request.fstype.as_deref() == Some("overlay")));
+        assert!(matches!(calls[1], MountCall::Umount { .. }));
+        assert_eq!(std::fs::read_dir(dir.path()).expect("read").count(), 0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn probe_overlay_support_reports_failed_mount() {
+        use super::super::ops::RecordingMountOps;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let merged = dir
+            .path()
+            .join(format!(".overlay-probe-{}", std::process::id()))
+            .join("merged");
+        let ops = RecordingMountOps::failing_at(merged);
+        assert!(!probe_overlay_support_with(&ops, dir.path()));
+        assert_eq!(std::fs::read_dir(dir.path()).expect("read").count(), 0);
     }
 
     /// Requires root privileges.
@@ -3,10 +3,44 @@
 //! More secure than `chroot` because it actually changes the root mount
 //! point rather than just the process's view of `/`.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use containust_common::error::{ContainustError, Result};
 
+#[cfg(target_os = "linux")]
+use super::ops::{MountOps, MountRequest};
+
+/// Directory inside the new root where [`switch_root`] parks the old root.
+pub const OLD_ROOT_DIR: &str = ".old_root";
+
+/// Returns the temporary mount point for the old root under `new_root`.
+#[must_use]
+pub fn put_old_path(new_root: &Path) -> PathBuf {
+    new_root.join(OLD_ROOT_DIR)
+}
+
+/// Switches the root filesystem to `new_root`, parking the old root at
+/// [`put_old_path`] until it is detached and removed.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::PermissionDenied`] if a mount, pivot, or
+/// unmount step is refused, or an I/O error for the mount point.
+#[cfg(target_os = "linux")]
+pub fn switch_root(new_root: &Path) -> Result<()> {
+    switch_root_with(&super::ops::LinuxMountOps, new_root)
+}
+
+/// Performs [`switch_root`] through `ops`.
+///
+/// # Errors
+///
+/// See [`switch_root`].
+#[cfg(target_os = "linux")]
+pub fn switch_root_with(ops: &impl MountOps, new_root: &Path) -> Result<()> {
+    pivot_root_with(ops, new_root, &put_old_path(new_root))
+}
+
 /// Switches the root filesystem to the new root using `pivot_root(2)`.
 ///
 /// Performs the full pivot sequence:
@@ -21,45 +55,56 @@ use containust_common::error::{ContainustError, Result};
 /// Returns an error if any of the mount, pivot, or cleanup operations fail.
 #[cfg(target_os = "linux")]
 pub fn pivot_root(new_root: &Path, put_old: &Path) -> Result<()> {
-    use nix::mount::{MsFlags, mount};
-
-    mount(
-        Some(new_root),
-        new_root,
-        None::<&str>,
-        MsFlags::MS_BIND | MsFlags::MS_REC,
-        None::<&str>,
-    )
-    .map_err(|e| ContainustError::PermissionDenied {
-        message: format!("bind mount for pivot_root failed: {e}"),
-    })?;
+    pivot_root_with(&super::ops::LinuxMountOps, new_root, put_old)
+}
+
+/// Performs the [`pivot_root`] sequence through `ops`.
+///
+/// # Errors
+///
+/// Returns an error if any of the mount, pivot, or cleanup operations fail.
+#[cfg(target_os = "linux")]
+pub fn pivot_root_with(ops: &impl MountOps, new_root: &Path, put_old: &Path) -> Result<()> {
+    ops.mount(&MountRequest::bind(new_root, new_root))
+        .map_err(|e| step_denied("bind mount for pivot_root", &e))?;
 
     std::fs::create_dir_all(put_old).map_err(|e| ContainustError::Io {
         path: put_old.into(),
         source: e,
     })?;
 
-    nix::unistd::pivot_root(new_root, put_old).map_err(|e| ContainustError::PermissionDenied {
-        message: format!("pivot_root failed: {e}"),
-    })?;
-
-    std::env::set_current_dir("/").map_err(|e| ContainustError::Io {
-        path: "/".into(),
-        source: e,
-    })?;
+    ops.pivot_root(new_root, put_old)?;
+    ops.chdir(Path::new("/"))?;
 
-    nix::mount::umount2("/.old_root", nix::mount::MntFlags::MNT_DETACH).map_err(|e| {
-        ContainustError::PermissionDenied {
-            message: format!("unmount old root failed: {e}"),
-        }
-    })?;
+    let old_root = old_root_after_pivot(new_root, put_old);
+    ops.umount(&old_root, nix::mount::MntFlags::MNT_DETACH)
+        .map_err(|e| step_denied("unmount old root", &e))?;
 
-    let _ = std::fs::remove_dir("/.old_root");
+    let _ = std::fs::remove_dir(&old_root);
 
     tracing::info!("pivot_root complete");
     Ok(())
 }
 
+/// Returns where `put_old` is visible once `new_root` has become `/`.
+///
+/// A `put_old` outside `new_root` falls back to [`OLD_ROOT_DIR`].
+#[must_use]
+pub fn old_root_after_pivot(new_root: &Path, put_old: &Path) -> PathBuf {
+    let relative = put_old
+        .strip_prefix(new_root)
+        .unwrap_or_else(|_| Path::new(OLD_ROOT_DIR));
+    Path::new("/").join(relative)
+}
+
+/// Maps a failed pivot step to a permission error naming the step.
+#[cfg(target_os = "linux")]
+fn step_denied(step: &str, error: &ContainustError) -> ContainustError {
+    ContainustError::PermissionDenied {
+        message: format!("{step} failed: {error}"),
+    }
+}
+
 /// Stub for non-Linux platforms.
 ///
 /// # Errors
@@ -72,6 +117,16 @@ pub fn pivot_root(_new_root: &Path, _put_old: &Path) -> Result<()> {
     })
 }
 
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — `pivot_root` requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn switch_root(new_root: &Path) -> Result<()> {
+    pivot_root(new_root, &put_old_path(new_root))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,9 +134,101 @@ mod tests {
     #[test]
     fn pivot_root_paths_constructed_correctly() {
         let new_root = Path::new("/mnt/container-root");
-        let put_old = Path::new("/mnt/container-root/.old_root");
-        assert!(put_old.starts_with(new_root));
-        assert!(put_old.ends_with(".old_root"));
+        let put_old = put_old_path(new_root);
+        assert_eq!(put_old, Path::new("/mnt/container-root/.old_root"));
+        assert_eq!(
+            old_root_after_pivot(new_root, &put_old),
+            Path::new("/.old_root")
+        );
+        assert_eq!(
+            old_root_after_pivot(new_root, Path::new("/elsewhere/put_old")),
+            Path::new("/.old_root")
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn failed_steps_map_to_permission_denied() {
+        let error = step_denied(
+            "unmount old root",
+            &ContainustError::Config {
+                message: "EBUSY".into(),
+            },
+        );
+        assert!(matches!(error, ContainustError::PermissionDenied { .. }));
+        assert!(
+            error.to_string().contains("unmount old root failed"),
+            "{error}"
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[test]
+    fn switch_root_requires_linux() {
+        assert!(matches!(
+            switch_root(Path::new("/mnt/container-root")),
+            Err(ContainustError::Config { .. })
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn switch_root_with_uses_and_detaches_old_root_dir() {
+        use super::super::ops::{MountCall, RecordingMountOps};
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let new_root = dir.path().join("rootfs");
+        let ops = RecordingMountOps::new();
+        switch_root_with(&ops, &new_root).expect("switch root");
+
+        let calls = ops.calls();
+        assert!(calls.contains(&MountCall::PivotRoot {
+            new_root: new_root.clone(),
+            put_old: put_old_path(&new_root),
+        }));
+        assert!(matches!(
+            calls.last(),
+            Some(MountCall::Umount { target, .. }) if target == Path::new("/.old_root")
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn pivot_root_with_issues_bind_pivot_chdir_umount_in_order() {
+        use super::super::ops::{MountCall, RecordingMountOps};
+        use std::path::PathBuf;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let new_root = dir.path().join("rootfs");
+        let put_old = new_root.join(".old_root");
+        let ops = RecordingMountOps::new();
+        pivot_root_with(&ops, &new_root, &put_old).expect("pivot");
+
+        assert!(put_old.is_dir());
+        assert_eq!(
+            ops.calls(),
+            vec![
+                MountCall::Mount(MountRequest::bind(&new_root, &new_root)),
+                MountCall::PivotRoot { new_root, put_old },
+                MountCall::Chdir(PathBuf::from("/")),
+                MountCall::Umount {
+                    target: PathBuf::from("/.old_root"),
+                    flags: nix::mount::MntFlags::MNT_DETACH,
+                },
+            ]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn pivot_root_with_bind_failure_skips_pivot() {
+        use super::super::ops::RecordingMountOps;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let new_root = dir.path().join("rootfs");
+        let ops = RecordingMountOps::failing_at(&new_root);
+        assert!(pivot_root_with(&ops, &new_root, &new_root.join(".old_root")).is_err());
+        assert_eq!(ops.calls().len(), 1);
     }
 
     /// Requires root privileges and mount namespace.
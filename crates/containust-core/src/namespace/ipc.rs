@@ -1,9 +1,51 @@
 //! IPC namespace isolation.
 //!
-//! Isolates System V IPC objects and POSIX message queues.
+//! Isolates System V IPC objects and POSIX message queues, and sizes
+//! the new namespace through its `kernel.shm*` and `kernel.msgmni`
+//! sysctls.
+
+use std::path::Path;
 
 use containust_common::error::{ContainustError, Result};
 
+/// Directory of the IPC sysctls; each IPC namespace sees its own values.
+pub const IPC_SYSCTL_DIR: &str = "/proc/sys/kernel";
+
+/// Size limits written into a container's IPC namespace.
+///
+/// Unset limits keep the kernel defaults of the new namespace.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IpcLimits {
+    /// Largest shared memory segment, in bytes (`kernel.shmmax`).
+    pub shmmax_bytes: Option<u64>,
+    /// Total shared memory, in pages (`kernel.shmall`).
+    pub shmall_pages: Option<u64>,
+    /// Maximum number of message queues (`kernel.msgmni`).
+    pub msgmni: Option<u32>,
+}
+
+impl IpcLimits {
+    /// Whether no limit is set.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.shmmax_bytes.is_none() && self.shmall_pages.is_none() && self.msgmni.is_none()
+    }
+
+    /// The sysctl files under [`IPC_SYSCTL_DIR`] and the values written
+    /// to them, for the limits that are set.
+    #[must_use]
+    pub fn sysctl_entries(&self) -> Vec<(&'static str, String)> {
+        [
+            ("shmmax", self.shmmax_bytes.map(|v| v.to_string())),
+            ("shmall", self.shmall_pages.map(|v| v.to_string())),
+            ("msgmni", self.msgmni.map(|v| v.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(file, value)| Some((file, value?)))
+        .collect()
+    }
+}
+
 /// Creates a new IPC namespace for the calling process.
 ///
 /// System V IPC objects and POSIX message queues created after this
@@ -35,6 +77,47 @@ pub fn create_ipc_namespace() -> Result<()> {
     })
 }
 
+/// Applies `limits` to the calling process's IPC namespace.
+///
+/// Call after [`create_ipc_namespace`] and before `pivot_root`, while the
+/// host `/proc` is still reachable; the sysctls resolve to the writer's
+/// IPC namespace, so the host values are untouched.
+///
+/// # Errors
+///
+/// Returns an error if a sysctl file cannot be written, e.g. when a
+/// value exceeds what the kernel accepts.
+#[cfg(target_os = "linux")]
+pub fn apply_limits(limits: &IpcLimits) -> Result<()> {
+    apply_limits_in(Path::new(IPC_SYSCTL_DIR), limits)
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — IPC namespace requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn apply_limits(_limits: &IpcLimits) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+/// Writes `limits` to the sysctl files in `dir`.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be written.
+pub fn apply_limits_in(dir: &Path, limits: &IpcLimits) -> Result<()> {
+    for (file, value) in limits.sysctl_entries() {
+        let path = dir.join(file);
+        std::fs::write(&path, &value).map_err(|e| ContainustError::Io { path, source: e })?;
+        tracing::debug!(sysctl = file, %value, "IPC limit set");
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,4 +128,50 @@ mod tests {
         let result = create_ipc_namespace();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn limits_map_to_kernel_sysctl_files() {
+        let limits = IpcLimits {
+            shmmax_bytes: Some(67_108_864),
+            shmall_pages: Some(16_384),
+            msgmni: Some(64),
+        };
+        assert_eq!(
+            limits.sysctl_entries(),
+            [
+                ("shmmax", "67108864".to_string()),
+                ("shmall", "16384".to_string()),
+                ("msgmni", "64".to_string()),
+            ]
+        );
+        assert!(IpcLimits::default().sysctl_entries().is_empty());
+        assert!(IpcLimits::default().is_empty());
+    }
+
+    #[test]
+    fn apply_limits_in_writes_only_set_limits() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let limits = IpcLimits {
+            msgmni: Some(32),
+            ..IpcLimits::default()
+        };
+        apply_limits_in(dir.path(), &limits).expect("apply");
+        let written = std::fs::read_to_string(dir.path().join("msgmni")).expect("msgmni");
+        assert_eq!(written, "32");
+        assert!(!dir.path().join("shmmax").exists());
+        assert!(!dir.path().join("shmall").exists());
+    }
+
+    #[test]
+    fn apply_limits_in_reports_the_failing_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let limits = IpcLimits {
+            shmmax_bytes: Some(1),
+            ..IpcLimits::default()
+        };
+        let error = apply_limits_in(&dir.path().join("missing"), &limits)
+            .expect_err("missing dir")
+            .to_string();
+        assert!(error.contains("shmmax"), "{error}");
+    }
 }
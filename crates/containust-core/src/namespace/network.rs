@@ -1,9 +1,18 @@
 //! Network namespace isolation.
 //!
-//! Provides the container with its own network stack (interfaces, routing, iptables).
+//! Provides the container with its own network stack (interfaces, routing, iptables)
+//! and connects it to the host with a veth pair configured through `ip`.
+
+use std::net::IpAddr;
 
 use containust_common::error::{ContainustError, Result};
 
+/// Name of the veth end inside the container's network namespace.
+pub const CONTAINER_INTERFACE: &str = "eth0";
+
+/// Longest interface name the kernel accepts (`IFNAMSIZ - 1`).
+pub const MAX_INTERFACE_NAME: usize = 15;
+
 /// Creates a new network namespace for the calling process.
 ///
 /// The new namespace starts with only a loopback interface.
@@ -34,10 +43,285 @@ pub fn create_network_namespace() -> Result<()> {
     })
 }
 
+/// Returns the `ip` invocations that set up a veth pair for
+/// `container_pid`.
+///
+/// The peer is moved into the container's network namespace as
+/// [`CONTAINER_INTERFACE`] and given `container_ip` (`"10.0.0.2/24"`,
+/// prefix optional); both ends and the container's loopback are brought up.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] if `host_if` is not a valid
+/// interface name or `container_ip` is not an address with an optional
+/// prefix length.
+pub fn veth_setup_commands(
+    container_pid: u32,
+    host_if: &str,
+    container_ip: &str,
+) -> Result<Vec<Vec<String>>> {
+    validate_interface_name(host_if)?;
+    validate_address(container_ip)?;
+    let peer = veth_peer_name(host_if);
+    let pid = container_pid.to_string();
+    let host = |args: &[&str]| -> Vec<String> {
+        std::iter::once("ip")
+            .chain(args.iter().copied())
+            .map(str::to_string)
+            .collect()
+    };
+    let inside = |args: &[&str]| -> Vec<String> {
+        ["nsenter", "--target", pid.as_str(), "--net", "ip"]
+            .into_iter()
+            .chain(args.iter().copied())
+            .map(str::to_string)
+            .collect()
+    };
+    Ok(vec![
+        host(&[
+            "link", "add", host_if, "type", "veth", "peer", "name", &peer,
+        ]),
+        host(&["link", "set", &peer, "netns", &pid]),
+        inside(&["link", "set", &peer, "name", CONTAINER_INTERFACE]),
+        inside(&["addr", "add", container_ip, "dev", CONTAINER_INTERFACE]),
+        inside(&["link", "set", CONTAINER_INTERFACE, "up"]),
+        inside(&["link", "set", "lo", "up"]),
+        host(&["link", "set", host_if, "up"]),
+    ])
+}
+
+/// Returns the command that deletes the veth pair whose host end is
+/// `host_if`; removing one end removes both.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] if `host_if` is not a valid
+/// interface name.
+pub fn veth_teardown_command(host_if: &str) -> Result<Vec<String>> {
+    validate_interface_name(host_if)?;
+    Ok(["ip", "link", "del", host_if]
+        .into_iter()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Temporary host-side name of the container end, before it is moved and
+/// renamed: `host_if` with a `p` suffix, or with its last character
+/// replaced at the kernel limit. It never equals `host_if`.
+fn veth_peer_name(host_if: &str) -> String {
+    let stem: String = host_if.chars().take(MAX_INTERFACE_NAME - 1).collect();
+    let replaced = host_if.chars().nth(MAX_INTERFACE_NAME - 1);
+    let suffix = if replaced == Some('p') { 'q' } else { 'p' };
+    format!("{stem}{suffix}")
+}
+
+fn validate_interface_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_INTERFACE_NAME
+        && name != "."
+        && name != ".."
+        && !name
+            .chars()
+            .any(|c| c == '/' || c == ':' || c.is_whitespace() || c.is_control());
+    if valid {
+        Ok(())
+    } else {
+        Err(ContainustError::Config {
+            message: format!(
+                "invalid interface name '{name}': expected 1-{MAX_INTERFACE_NAME} characters \
+                 without '/', ':', or whitespace"
+            ),
+        })
+    }
+}
+
+fn validate_address(spec: &str) -> Result<()> {
+    let invalid = || ContainustError::Config {
+        message: format!("invalid container IP '{spec}': expected an address such as 10.0.0.2/24"),
+    };
+    let (address, prefix) = spec
+        .split_once('/')
+        .map_or((spec, None), |(address, prefix)| (address, Some(prefix)));
+    let address: IpAddr = address.parse().map_err(|_| invalid())?;
+    let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+    match prefix.map(str::parse::<u8>) {
+        None => Ok(()),
+        Some(Ok(prefix)) if prefix <= max_prefix => Ok(()),
+        Some(_) => Err(invalid()),
+    }
+}
+
+/// Connects the network namespace of `container_pid` to the host with a
+/// veth pair.
+///
+/// `host_if` stays on the host and [`CONTAINER_INTERFACE`] holds
+/// `container_ip` inside. A pair this call created is removed on failure;
+/// when creating it fails, e.g. because `host_if` already exists, the
+/// host's interfaces are left alone.
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid or an `ip` / `nsenter`
+/// invocation fails, e.g. without `CAP_NET_ADMIN`.
+#[cfg(target_os = "linux")]
+pub fn setup_veth(container_pid: u32, host_if: &str, container_ip: &str) -> Result<()> {
+    // The first command creates the pair; until it succeeds, `host_if`
+    // may be an interface this call does not own.
+    let mut created = false;
+    for command in veth_setup_commands(container_pid, host_if, container_ip)? {
+        if let Err(error) = run_command(&command) {
+            if created && let Err(cleanup) = teardown_veth(host_if) {
+                tracing::warn!(host_if, error = %cleanup, "veth cleanup failed");
+            }
+            return Err(error);
+        }
+        created = true;
+    }
+    tracing::debug!(host_if, container_pid, container_ip, "veth pair set up");
+    Ok(())
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — veth pairs require Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn setup_veth(_container_pid: u32, _host_if: &str, _container_ip: &str) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+/// Deletes the veth pair whose host end is `host_if`. A pair already gone,
+/// e.g. because the container's namespace was destroyed, is not an error.
+///
+/// # Errors
+///
+/// Returns an error if `host_if` is invalid or `ip link del` fails.
+#[cfg(target_os = "linux")]
+pub fn teardown_veth(host_if: &str) -> Result<()> {
+    let command = veth_teardown_command(host_if)?;
+    if !std::path::Path::new("/sys/class/net")
+        .join(host_if)
+        .exists()
+    {
+        return Ok(());
+    }
+    run_command(&command)?;
+    tracing::debug!(host_if, "veth pair removed");
+    Ok(())
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — veth pairs require Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn teardown_veth(_host_if: &str) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn run_command(command: &[String]) -> Result<()> {
+    let Some((program, args)) = command.split_first() else {
+        return Ok(());
+    };
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|source| ContainustError::Io {
+            path: program.into(),
+            source,
+        })?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(ContainustError::Config {
+        message: format!(
+            "`{}` failed with {}: {}",
+            command.join(" "),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn joined(commands: &[Vec<String>]) -> Vec<String> {
+        commands.iter().map(|command| command.join(" ")).collect()
+    }
+
+    #[test]
+    fn veth_setup_commands_create_move_address_and_raise_the_pair() {
+        let commands = veth_setup_commands(4242, "ctst0a1b", "10.88.0.2/24").expect("commands");
+        assert_eq!(
+            joined(&commands),
+            [
+                "ip link add ctst0a1b type veth peer name ctst0a1bp",
+                "ip link set ctst0a1bp netns 4242",
+                "nsenter --target 4242 --net ip link set ctst0a1bp name eth0",
+                "nsenter --target 4242 --net ip addr add 10.88.0.2/24 dev eth0",
+                "nsenter --target 4242 --net ip link set eth0 up",
+                "nsenter --target 4242 --net ip link set lo up",
+                "ip link set ctst0a1b up",
+            ]
+        );
+    }
+
+    #[test]
+    fn veth_peer_name_fits_the_kernel_limit() {
+        let commands = veth_setup_commands(1, "ctst-0123456789", "fd00::2").expect("commands");
+        assert_eq!(commands[0][8], "ctst-012345678p");
+        assert_eq!(commands[0][8].len(), MAX_INTERFACE_NAME);
+    }
+
+    #[test]
+    fn veth_peer_name_never_equals_the_host_end() {
+        assert_eq!(veth_peer_name("ctst-012345678p"), "ctst-012345678q");
+        assert_eq!(veth_peer_name("ctst0p"), "ctst0pp");
+        for host_if in ["ctst-012345678p", "ctst-012345678q", "p", "ctst0"] {
+            assert_ne!(veth_peer_name(host_if), host_if);
+        }
+    }
+
+    #[test]
+    fn veth_setup_rejects_invalid_arguments() {
+        for host_if in ["", "ctst-0123456789a", "a/b", "a b", ".."] {
+            let error = veth_setup_commands(1, host_if, "10.0.0.2/24")
+                .expect_err(host_if)
+                .to_string();
+            assert!(error.contains("invalid interface name"), "{error}");
+        }
+        for ip in ["", "10.0.0", "10.0.0.2/33", "fd00::2/129", "10.0.0.2/x"] {
+            let error = veth_setup_commands(1, "ctst0", ip)
+                .expect_err(ip)
+                .to_string();
+            assert!(error.contains("invalid container IP"), "{error}");
+        }
+    }
+
+    #[test]
+    fn veth_teardown_deletes_the_host_end() {
+        assert_eq!(
+            veth_teardown_command("ctst0a1b").expect("command"),
+            ["ip", "link", "del", "ctst0a1b"]
+        );
+        assert!(veth_teardown_command("a/b").is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn teardown_of_missing_pair_succeeds() {
+        assert!(teardown_veth("ctst-absent0").is_ok());
+    }
+
     #[test]
     #[ignore = "requires root privileges"]
     fn create_network_namespace_succeeds_with_root() {
@@ -1,10 +1,16 @@
 //! PID namespace isolation.
 //!
 //! Provides the container with its own process ID space, where PID 1
-//! is the container's init process.
+//! is the container's init process, and enumerates the processes under
+//! that init.
+
+use std::path::Path;
 
 use containust_common::error::{ContainustError, Result};
 
+/// Mount point of the host's procfs.
+pub const PROC_ROOT: &str = "/proc";
+
 /// Creates a new PID namespace for the calling process.
 ///
 /// After a successful call, the next `fork(2)` child will see
@@ -74,10 +80,137 @@ pub fn join_pid_namespace(_ns_fd: i32) -> Result<()> {
     })
 }
 
+/// Lists the host PIDs of `init_pid` and all of its descendants, i.e.
+/// every process of a container whose init is `init_pid`, sorted.
+///
+/// Walks the `task/<tid>/children` files under [`PROC_ROOT`], which need
+/// a kernel built with `CONFIG_PROC_CHILDREN`.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::NotFound`] if `init_pid` does not exist.
+#[cfg(target_os = "linux")]
+pub fn list_pids(init_pid: u32) -> Result<Vec<u32>> {
+    list_pids_in(Path::new(PROC_ROOT), init_pid)
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — process enumeration requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn list_pids(_init_pid: u32) -> Result<Vec<u32>> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+/// Number of processes in the container whose init is `init_pid`.
+///
+/// # Errors
+///
+/// Returns an error if [`list_pids`] fails.
+pub fn process_count(init_pid: u32) -> Result<usize> {
+    list_pids(init_pid).map(|pids| pids.len())
+}
+
+/// Like [`list_pids`], reading the procfs mounted at `proc_root`.
+///
+/// Processes that exit during the walk are skipped.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::NotFound`] if `init_pid` has no entry
+/// under `proc_root`.
+pub fn list_pids_in(proc_root: &Path, init_pid: u32) -> Result<Vec<u32>> {
+    if !proc_root.join(init_pid.to_string()).is_dir() {
+        return Err(ContainustError::NotFound {
+            kind: "process",
+            id: init_pid.to_string(),
+        });
+    }
+    let mut pids = vec![init_pid];
+    let mut next = 0;
+    while let Some(&pid) = pids.get(next) {
+        next += 1;
+        for child in children_of(proc_root, pid) {
+            if !pids.contains(&child) {
+                pids.push(child);
+            }
+        }
+    }
+    pids.sort_unstable();
+    Ok(pids)
+}
+
+/// Children of every thread of `pid`, from `task/<tid>/children`.
+fn children_of(proc_root: &Path, pid: u32) -> Vec<u32> {
+    let Ok(tasks) = std::fs::read_dir(proc_root.join(pid.to_string()).join("task")) else {
+        return Vec::new();
+    };
+    tasks
+        .flatten()
+        .filter_map(|task| std::fs::read_to_string(task.path().join("children")).ok())
+        .flat_map(|text| parse_pid_list(&text))
+        .collect()
+}
+
+/// Parses a space-separated `children` list, skipping malformed entries.
+fn parse_pid_list(text: &str) -> Vec<u32> {
+    text.split_whitespace()
+        .filter_map(|pid| pid.parse().ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Writes `children` for thread `tid` of `pid` under `proc_root`.
+    fn task(proc_root: &Path, pid: u32, tid: u32, children: &str) {
+        let dir = proc_root.join(format!("{pid}/task/{tid}"));
+        std::fs::create_dir_all(&dir).expect("task dir");
+        std::fs::write(dir.join("children"), children).expect("children");
+    }
+
+    #[test]
+    fn list_pids_in_walks_children_of_every_thread() {
+        let proc_root = tempfile::tempdir().expect("tempdir");
+        let root = proc_root.path();
+        task(root, 100, 100, "101 102 ");
+        task(root, 100, 103, "104");
+        task(root, 101, 101, "105\n");
+        task(root, 102, 102, "");
+        task(root, 200, 200, "201");
+
+        assert_eq!(
+            list_pids_in(root, 100).expect("walk"),
+            [100, 101, 102, 104, 105]
+        );
+        assert_eq!(list_pids_in(root, 102).expect("leaf"), [102]);
+    }
+
+    #[test]
+    fn list_pids_in_skips_exited_and_malformed_entries() {
+        let proc_root = tempfile::tempdir().expect("tempdir");
+        let root = proc_root.path();
+        task(root, 1, 1, "2 x 3");
+        task(root, 2, 2, "1");
+
+        assert_eq!(list_pids_in(root, 1).expect("walk"), [1, 2, 3]);
+        let error = list_pids_in(root, 9).expect_err("no such process");
+        assert!(matches!(error, ContainustError::NotFound { .. }), "{error}");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn process_count_includes_the_calling_process() {
+        let pid = std::process::id();
+        assert!(list_pids(pid).expect("own pid").contains(&pid));
+        assert!(process_count(pid).expect("count") >= 1);
+    }
+
     /// Requires root — only verifies the syscall entry point is reached.
     #[test]
     #[ignore = "requires root privileges"]
@@ -2,8 +2,105 @@
 //!
 //! Maps container UIDs/GIDs to unprivileged host UIDs, enabling rootless containers.
 
+use std::path::Path;
+
 use containust_common::error::{ContainustError, Result};
 
+/// One line of a `uid_map` or `gid_map` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdMapEntry {
+    /// First ID of the range inside the namespace.
+    pub container_id: u32,
+    /// First ID of the range on the host.
+    pub host_id: u32,
+    /// Number of consecutive IDs mapped.
+    pub count: u32,
+}
+
+impl IdMapEntry {
+    /// Maps the single ID `container_id` to `host_id`.
+    #[must_use]
+    pub const fn single(container_id: u32, host_id: u32) -> Self {
+        Self {
+            container_id,
+            host_id,
+            count: 1,
+        }
+    }
+}
+
+impl std::fmt::Display for IdMapEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.container_id, self.host_id, self.count)
+    }
+}
+
+/// UID and GID maps written for a new user namespace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdMaps {
+    /// Entries of `uid_map`.
+    pub uid: Vec<IdMapEntry>,
+    /// Entries of `gid_map`.
+    pub gid: Vec<IdMapEntry>,
+}
+
+impl IdMaps {
+    /// Maps root in the container to the host IDs, the default for a
+    /// rootless container.
+    #[must_use]
+    pub fn root_to(host_user_id: u32, host_group_id: u32) -> Self {
+        Self {
+            uid: vec![IdMapEntry::single(0, host_user_id)],
+            gid: vec![IdMapEntry::single(0, host_group_id)],
+        }
+    }
+
+    /// Maps the component's `user` (numeric `uid[:gid]`) to the host IDs,
+    /// so the process runs as that user inside the container; without a
+    /// `user`, maps root as [`Self::root_to`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error unless `user` is numeric `uid[:gid]`.
+    pub fn for_user(user: Option<&str>, host_user_id: u32, host_group_id: u32) -> Result<Self> {
+        let Some(user) = user else {
+            return Ok(Self::root_to(host_user_id, host_group_id));
+        };
+        let (uid, gid) = parse_user(user)?;
+        Ok(Self {
+            uid: vec![IdMapEntry::single(uid, host_user_id)],
+            gid: vec![IdMapEntry::single(gid, host_group_id)],
+        })
+    }
+}
+
+/// Parses a numeric `uid[:gid]`; the gid defaults to the uid.
+///
+/// # Errors
+///
+/// Returns a configuration error when either part is not a number.
+pub fn parse_user(user: &str) -> Result<(u32, u32)> {
+    let (uid, gid) = user.split_once(':').unwrap_or((user, user));
+    match (uid.parse(), gid.parse()) {
+        (Ok(uid), Ok(gid)) => Ok((uid, gid)),
+        _ => Err(ContainustError::Config {
+            message: format!("user must be a numeric uid[:gid], got '{user}'"),
+        }),
+    }
+}
+
+/// Renders map entries in the `/proc/<pid>/uid_map` format, one
+/// `container host count` line each.
+#[must_use]
+pub fn format_id_map(entries: &[IdMapEntry]) -> String {
+    use std::fmt::Write as _;
+
+    entries.iter().fold(String::new(), |mut out, entry| {
+        let _ = writeln!(out, "{entry}");
+        out
+    })
+}
+
 /// Creates a new user namespace for the calling process.
 ///
 /// The calling process gains full privileges within the new namespace,
@@ -35,66 +132,82 @@ pub fn create_user_namespace() -> Result<()> {
     })
 }
 
-/// Writes UID/GID mapping for the user namespace.
+/// Writes the UID and GID maps of `pid`'s user namespace (`0` means the
+/// calling process).
 ///
-/// Configures how UIDs/GIDs inside the namespace map to UIDs/GIDs
-/// on the host. Must deny `setgroups` first for unprivileged user namespaces.
+/// `setgroups` is set to `deny` first: the kernel refuses an unprivileged
+/// `gid_map` write until it is.
 ///
 /// # Errors
 ///
-/// Returns an error if writing to `/proc/[pid]/uid_map`,
-/// `/proc/[pid]/gid_map`, or `/proc/[pid]/setgroups` fails.
+/// Returns an error if writing `/proc/<pid>/setgroups`, `uid_map`, or
+/// `gid_map` fails.
 #[cfg(target_os = "linux")]
-pub fn write_uid_gid_map(pid: u32, container_id: u32, host_id: u32, range: u32) -> Result<()> {
-    use std::fs;
+pub fn write_id_maps(pid: u32, uid_map: &[IdMapEntry], gid_map: &[IdMapEntry]) -> Result<()> {
+    write_id_maps_in(Path::new("/proc"), pid, uid_map, gid_map)
+}
 
-    let uid_map = format!("{container_id} {host_id} {range}");
-    let pid_str = if pid == 0 {
-        "self".to_string()
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — UID/GID mapping requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn write_id_maps(_pid: u32, _uid_map: &[IdMapEntry], _gid_map: &[IdMapEntry]) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
+/// [`write_id_maps`] against the procfs mounted at `proc_root`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`write_id_maps`].
+pub fn write_id_maps_in(
+    proc_root: &Path,
+    pid: u32,
+    uid_map: &[IdMapEntry],
+    gid_map: &[IdMapEntry],
+) -> Result<()> {
+    let dir = if pid == 0 {
+        proc_root.join("self")
     } else {
-        pid.to_string()
+        proc_root.join(pid.to_string())
+    };
+    let write = |name: &str, contents: &str| {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).map_err(|source| ContainustError::Io { path, source })
     };
 
-    let setgroups_path = format!("/proc/{pid_str}/setgroups");
-    if std::path::Path::new(&setgroups_path).exists() {
-        fs::write(&setgroups_path, "deny").map_err(|e| ContainustError::Io {
-            path: setgroups_path.into(),
-            source: e,
-        })?;
+    // Kernels before 3.19 have no setgroups file and need no deny.
+    if dir.join("setgroups").exists() {
+        write("setgroups", "deny")?;
     }
+    write("uid_map", &format_id_map(uid_map))?;
+    write("gid_map", &format_id_map(gid_map))?;
 
-    let uid_map_path = format!("/proc/{pid_str}/uid_map");
-    fs::write(&uid_map_path, &uid_map).map_err(|e| ContainustError::Io {
-        path: uid_map_path.into(),
-        source: e,
-    })?;
-
-    let gid_map_path = format!("/proc/{pid_str}/gid_map");
-    fs::write(&gid_map_path, &uid_map).map_err(|e| ContainustError::Io {
-        path: gid_map_path.into(),
-        source: e,
-    })?;
-
-    tracing::debug!(pid, container_id, host_id, range, "wrote UID/GID map");
+    tracing::debug!(pid, ?uid_map, ?gid_map, "wrote UID/GID maps");
     Ok(())
 }
 
-/// Stub for non-Linux platforms.
+/// Maps the single ID `container_id` to `host_id` in both `uid_map` and
+/// `gid_map` of `pid`.
 ///
 /// # Errors
 ///
-/// Always returns an error — UID/GID mapping requires Linux.
-#[cfg(not(target_os = "linux"))]
-pub fn write_uid_gid_map(_pid: u32, _container_id: u32, _host_id: u32, _range: u32) -> Result<()> {
-    Err(ContainustError::Config {
-        message: "Linux required for native container operations".into(),
-    })
+/// Returns the same errors as [`write_id_maps`].
+pub fn write_uid_gid_map(pid: u32, container_id: u32, host_id: u32, range: u32) -> Result<()> {
+    let entry = [IdMapEntry {
+        container_id,
+        host_id,
+        count: range,
+    }];
+    write_id_maps(pid, &entry, &entry)
 }
 
 #[cfg(test)]
 mod tests {
-    // All fixtures in this module exercise Linux-only syscalls.
-    #[cfg(target_os = "linux")]
     use super::*;
 
     // User-namespace fixtures run in a forked child: `unshare(CLONE_NEWUSER)`
@@ -121,6 +234,72 @@ mod tests {
         assert!(ok, "uid/gid map write failed after user namespace creation");
     }
 
+    #[test]
+    fn format_id_map_writes_one_line_per_entry() {
+        let entries = [
+            IdMapEntry::single(0, 1000),
+            IdMapEntry {
+                container_id: 1,
+                host_id: 100_000,
+                count: 65_536,
+            },
+        ];
+        assert_eq!(format_id_map(&entries), "0 1000 1\n1 100000 65536\n");
+        assert_eq!(format_id_map(&[]), "");
+    }
+
+    #[test]
+    fn id_maps_default_to_root_and_follow_user() {
+        let root = IdMaps::for_user(None, 1000, 100).expect("no user");
+        assert_eq!(root, IdMaps::root_to(1000, 100));
+        assert_eq!(root.uid, vec![IdMapEntry::single(0, 1000)]);
+        assert_eq!(root.gid, vec![IdMapEntry::single(0, 100)]);
+
+        let user = IdMaps::for_user(Some("33:44"), 1000, 100).expect("uid:gid");
+        assert_eq!(user.uid, vec![IdMapEntry::single(33, 1000)]);
+        assert_eq!(user.gid, vec![IdMapEntry::single(44, 100)]);
+
+        let uid_only = IdMaps::for_user(Some("33"), 1000, 100).expect("uid");
+        assert_eq!(uid_only.gid, vec![IdMapEntry::single(33, 100)]);
+
+        assert!(IdMaps::for_user(Some("www-data"), 1000, 100).is_err());
+    }
+
+    #[test]
+    fn write_id_maps_in_denies_setgroups_and_writes_maps() {
+        let proc_root = tempfile::tempdir().expect("tempdir");
+        let dir = proc_root.path().join("42");
+        std::fs::create_dir(&dir).expect("pid dir");
+        std::fs::write(dir.join("setgroups"), "allow").expect("setgroups");
+
+        write_id_maps_in(
+            proc_root.path(),
+            42,
+            &[IdMapEntry::single(0, 1000)],
+            &[IdMapEntry::single(0, 100)],
+        )
+        .expect("write maps");
+
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).expect(name);
+        assert_eq!(read("setgroups"), "deny");
+        assert_eq!(read("uid_map"), "0 1000 1\n");
+        assert_eq!(read("gid_map"), "0 100 1\n");
+    }
+
+    #[test]
+    fn write_id_maps_in_writes_no_map_when_setgroups_deny_fails() {
+        let proc_root = tempfile::tempdir().expect("tempdir");
+        let dir = proc_root.path().join("self");
+        // A directory in place of the setgroups file makes the deny fail.
+        std::fs::create_dir_all(dir.join("setgroups")).expect("setgroups dir");
+
+        let maps = [IdMapEntry::single(0, 1000)];
+        let error = write_id_maps_in(proc_root.path(), 0, &maps, &maps).expect_err("deny fails");
+        assert!(error.to_string().contains("setgroups"), "{error}");
+        assert!(!dir.join("uid_map").exists());
+        assert!(!dir.join("gid_map").exists());
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn write_uid_gid_map_nonexistent_proc_returns_error() {
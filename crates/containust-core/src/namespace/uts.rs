@@ -4,6 +4,45 @@
 
 use containust_common::error::{ContainustError, Result};
 
+/// Longest hostname or domain name the kernel accepts (`HOST_NAME_MAX`).
+pub const HOST_NAME_MAX: usize = 64;
+
+/// Checks a hostname against the kernel's limits: non-empty and at most
+/// [`HOST_NAME_MAX`] bytes.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] describing the violation.
+pub fn validate_hostname(name: &str) -> Result<()> {
+    validate_name("hostname", name)
+}
+
+/// Checks a domain name against the same limits as [`validate_hostname`].
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] describing the violation.
+pub fn validate_domainname(name: &str) -> Result<()> {
+    validate_name("domain name", name)
+}
+
+fn validate_name(kind: &str, name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(ContainustError::Config {
+            message: format!("{kind} must not be empty"),
+        });
+    }
+    if name.len() > HOST_NAME_MAX {
+        return Err(ContainustError::Config {
+            message: format!(
+                "{kind} '{name}' is {} bytes; the limit is {HOST_NAME_MAX}",
+                name.len()
+            ),
+        });
+    }
+    Ok(())
+}
+
 /// Creates a new UTS namespace for the calling process.
 ///
 /// # Errors
@@ -36,9 +75,11 @@ pub fn create_uts_namespace() -> Result<()> {
 ///
 /// # Errors
 ///
-/// Returns an error if `sethostname(2)` fails.
+/// Returns an error if the name fails [`validate_hostname`] or
+/// `sethostname(2)` fails.
 #[cfg(target_os = "linux")]
 pub fn set_hostname(hostname: &str) -> Result<()> {
+    validate_hostname(hostname)?;
     nix::unistd::sethostname(hostname).map_err(|e| ContainustError::PermissionDenied {
         message: format!("sethostname failed: {e}"),
     })?;
@@ -58,6 +99,40 @@ pub fn set_hostname(_hostname: &str) -> Result<()> {
     })
 }
 
+/// Sets the NIS domain name inside the UTS namespace.
+///
+/// # Errors
+///
+/// Returns an error if the name fails [`validate_domainname`] or
+/// `setdomainname(2)` fails.
+#[cfg(target_os = "linux")]
+pub fn set_domainname(domainname: &str) -> Result<()> {
+    validate_domainname(domainname)?;
+    // nix has no setdomainname wrapper.
+    // SAFETY: the pointer and length describe `domainname`'s bytes, which
+    // the kernel copies; no terminator is required.
+    let ret = unsafe { libc::setdomainname(domainname.as_ptr().cast(), domainname.len()) };
+    if ret != 0 {
+        return Err(ContainustError::PermissionDenied {
+            message: format!("setdomainname failed: {}", std::io::Error::last_os_error()),
+        });
+    }
+    tracing::debug!(domainname, "domain name set");
+    Ok(())
+}
+
+/// Stub for non-Linux platforms.
+///
+/// # Errors
+///
+/// Always returns an error — domain name setting requires Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn set_domainname(_domainname: &str) -> Result<()> {
+    Err(ContainustError::Config {
+        message: "Linux required for native container operations".into(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,6 +144,32 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn names_must_be_non_empty_and_fit_host_name_max() {
+        assert!(validate_hostname("web-server").is_ok());
+        assert!(validate_domainname(&"d".repeat(HOST_NAME_MAX)).is_ok());
+
+        let empty = validate_hostname("").expect_err("empty").to_string();
+        assert!(empty.contains("hostname must not be empty"), "{empty}");
+        let long = validate_domainname(&"d".repeat(HOST_NAME_MAX + 1))
+            .expect_err("too long")
+            .to_string();
+        assert!(long.contains("65 bytes; the limit is 64"), "{long}");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn setters_reject_invalid_names_before_the_syscall() {
+        assert!(matches!(
+            set_hostname(""),
+            Err(ContainustError::Config { .. })
+        ));
+        assert!(matches!(
+            set_domainname(&"d".repeat(HOST_NAME_MAX + 1)),
+            Err(ContainustError::Config { .. })
+        ));
+    }
+
     #[test]
     #[ignore = "requires root privileges"]
     fn set_hostname_succeeds_with_root() {
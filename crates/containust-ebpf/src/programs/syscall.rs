@@ -1,11 +1,25 @@
 //! Syscall tracepoint eBPF program.
 //!
 //! Defines the BPF program attached to `sys_enter` tracepoints.
+//!
+//! The program is meant to read slot 0 of `CGROUP_FILTER_MAP` on every
+//! event and drop events whose `bpf_get_current_cgroup_id()` differs, with
+//! `NO_CGROUP_FILTER` tracing the whole host. Until the bytecode is
+//! embedded, nothing loads the program or writes the map, and
+//! `Tracer::attach` rejects a filtered attach.
 
 /// Placeholder for the compiled eBPF syscall tracing program.
 /// The actual BPF bytecode will be embedded at build time via `aya`.
 pub const SYSCALL_PROGRAM_NAME: &str = "containust_syscall_trace";
 
+/// Single-slot array map holding the cgroup id the program is scoped to.
+#[cfg(feature = "ebpf")]
+pub const CGROUP_FILTER_MAP: &str = "CONTAINUST_CGROUP_FILTER";
+
+/// Filter map value meaning "trace every cgroup" (no cgroup has id 0).
+#[cfg(feature = "ebpf")]
+pub const NO_CGROUP_FILTER: u64 = 0;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,13 +1,25 @@
 //! Syscall tracing via eBPF.
 //!
 //! Attaches to tracepoints to monitor system calls made by
-//! container processes in real time.
+//! container processes in real time. With the `ebpf` feature, a
+//! `TracerConfig` describes the cgroup a tracer is meant to be scoped to.
+//! No syscall program is embedded yet, so attaching one is rejected
+//! instead of tracing the whole host.
 
+#[cfg(feature = "ebpf")]
+use std::path::Path;
+
+#[cfg(feature = "ebpf")]
+use containust_common::error::ContainustError;
 use containust_common::error::Result;
 use serde::{Deserialize, Serialize};
 
-#[cfg(all(target_os = "linux", feature = "ebpf"))]
+#[cfg(feature = "ebpf")]
+use crate::lifecycle::availability_message;
+#[cfg(feature = "ebpf")]
 use crate::lifecycle::{ProbeAvailability, probe_availability};
+#[cfg(feature = "ebpf")]
+use crate::programs::syscall::NO_CGROUP_FILTER;
 
 /// A captured syscall event.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +30,134 @@ pub struct SyscallEvent {
     pub syscall_nr: u64,
     /// Timestamp in nanoseconds.
     pub timestamp_ns: u64,
+    /// Cgroup v2 id of the process (`bpf_get_current_cgroup_id`).
+    #[serde(default)]
+    pub cgroup_id: u64,
+}
+
+/// Settings applied to the syscall program when it is loaded.
+#[cfg(feature = "ebpf")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TracerConfig {
+    /// Cgroup v2 id to trace; `None` traces every process on the host.
+    pub cgroup_id: Option<u64>,
+}
+
+#[cfg(feature = "ebpf")]
+impl TracerConfig {
+    /// Value written to the program's cgroup filter map.
+    #[must_use]
+    pub const fn filter_value(&self) -> u64 {
+        match self.cgroup_id {
+            Some(id) => id,
+            None => NO_CGROUP_FILTER,
+        }
+    }
+
+    /// Returns whether an event from `cgroup_id` passes the filter.
+    #[must_use]
+    pub const fn matches(&self, cgroup_id: u64) -> bool {
+        match self.cgroup_id {
+            Some(id) => id == cgroup_id,
+            None => true,
+        }
+    }
+}
+
+/// Resolves the cgroup v2 id of the cgroup directory at `cgroup_path`.
+///
+/// On cgroup v2 the id returned by `bpf_get_current_cgroup_id` is the
+/// inode number of the cgroup's directory in the cgroup filesystem.
+///
+/// # Errors
+///
+/// Returns an I/O error if the path cannot be read, or a configuration
+/// error if it is not a directory.
+#[cfg(all(unix, feature = "ebpf"))]
+pub fn cgroup_id(cgroup_path: &Path) -> Result<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::metadata(cgroup_path).map_err(|e| ContainustError::Io {
+        path: cgroup_path.to_path_buf(),
+        source: e,
+    })?;
+    if !metadata.is_dir() {
+        return Err(ContainustError::Config {
+            message: format!("{} is not a cgroup directory", cgroup_path.display()),
+        });
+    }
+    Ok(metadata.ino())
+}
+
+/// Stub for non-Unix platforms.
+///
+/// # Errors
+///
+/// Always returns an error — cgroup ids require Linux.
+#[cfg(all(not(unix), feature = "ebpf"))]
+pub fn cgroup_id(cgroup_path: &Path) -> Result<u64> {
+    Err(ContainustError::Config {
+        message: format!(
+            "cannot resolve cgroup id of {}: cgroups require Linux",
+            cgroup_path.display()
+        ),
+    })
+}
+
+/// Syscall tracer loaded with a [`TracerConfig`].
+#[cfg(feature = "ebpf")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tracer {
+    config: TracerConfig,
+}
+
+#[cfg(feature = "ebpf")]
+impl Tracer {
+    /// Creates a tracer that is not yet attached.
+    #[must_use]
+    pub const fn new(config: TracerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Attaches a tracer scoped to the container whose cgroup is at
+    /// `cgroup_path` (e.g. `/sys/fs/cgroup/containust/<project>/<id>`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cgroup id cannot be resolved or the tracer
+    /// cannot be attached (see [`Tracer::attach`]).
+    pub fn attach_for_container(cgroup_path: &Path) -> Result<Self> {
+        let tracer = Self::new(TracerConfig {
+            cgroup_id: Some(cgroup_id(cgroup_path)?),
+        });
+        tracer.attach()?;
+        Ok(tracer)
+    }
+
+    /// Loads the syscall program with this tracer's filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error when probes are unavailable, and
+    /// always on Linux for now: no syscall program is embedded to write
+    /// the filter into, and tracing the whole host instead would not
+    /// honour the filter.
+    pub fn attach(&self) -> Result<()> {
+        match probe_availability() {
+            ProbeAvailability::Available => attach_filtered(self.config),
+            ProbeAvailability::FeatureDisabled | ProbeAvailability::UnsupportedOs => {
+                Err(ContainustError::Config {
+                    message: format!("eBPF syscall tracing is {}", availability_message()),
+                })
+            }
+        }
+    }
+
+    /// Returns the configuration the program is loaded with.
+    #[must_use]
+    pub const fn config(&self) -> TracerConfig {
+        self.config
+    }
 }
 
 /// Starts the syscall tracer for a specific container PID namespace.
@@ -38,6 +178,22 @@ pub(crate) fn start_tracer_unchecked(target_pid: u32) {
     tracing::info!(pid = target_pid, "starting syscall tracer");
 }
 
+/// Rejects a filtered attach: the syscall program is not embedded yet, so
+/// there is no [`CGROUP_FILTER_MAP`] to write `config` into.
+///
+/// [`CGROUP_FILTER_MAP`]: crate::programs::syscall::CGROUP_FILTER_MAP
+#[cfg(feature = "ebpf")]
+fn attach_filtered(config: TracerConfig) -> Result<()> {
+    Err(ContainustError::Config {
+        message: format!(
+            "cgroup-scoped syscall tracing is not supported yet: this build embeds no \
+             syscall program to write the {} filter (cgroup id {}) into",
+            crate::programs::syscall::CGROUP_FILTER_MAP,
+            config.filter_value()
+        ),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
@@ -51,6 +207,7 @@ mod tests {
             pid: 1234,
             syscall_nr: 60,
             timestamp_ns: 1_700_000_000_000_000,
+            cgroup_id: 0,
         };
         assert_eq!(event.pid, 1234);
     }
@@ -61,10 +218,78 @@ mod tests {
             pid: 42,
             syscall_nr: 1,
             timestamp_ns: 100_000_000,
+            cgroup_id: 7,
         };
         let json = serde_json::to_string(&event).expect("serialize");
         let back: SyscallEvent = serde_json::from_str(&json).expect("deserialize");
         assert_eq!(back.pid, 42);
+        assert_eq!(back.cgroup_id, 7);
+    }
+
+    #[test]
+    fn syscall_event_without_cgroup_id_deserializes() {
+        let back: SyscallEvent =
+            serde_json::from_str(r#"{"pid":1,"syscall_nr":0,"timestamp_ns":0}"#).expect("parse");
+        assert_eq!(back.cgroup_id, 0);
+    }
+
+    #[cfg(feature = "ebpf")]
+    #[test]
+    fn tracer_config_filters_by_cgroup_id() {
+        let host = TracerConfig::default();
+        assert_eq!(host.filter_value(), NO_CGROUP_FILTER);
+        assert!(host.matches(1) && host.matches(42));
+
+        let scoped = TracerConfig {
+            cgroup_id: Some(42),
+        };
+        assert_eq!(scoped.filter_value(), 42);
+        assert!(scoped.matches(42));
+        assert!(!scoped.matches(1));
+    }
+
+    #[cfg(all(unix, feature = "ebpf"))]
+    #[test]
+    fn cgroup_id_is_the_directory_inode() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cgroup = dir.path().join("containust").join("web");
+        std::fs::create_dir_all(&cgroup).expect("mkdir");
+        let inode = std::fs::metadata(&cgroup).expect("metadata").ino();
+        assert_eq!(cgroup_id(&cgroup).expect("id"), inode);
+        assert_ne!(
+            cgroup_id(&cgroup).expect("id"),
+            cgroup_id(dir.path()).expect("id")
+        );
+    }
+
+    #[cfg(all(unix, feature = "ebpf"))]
+    #[test]
+    fn cgroup_id_rejects_missing_paths_and_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let missing = dir.path().join("gone");
+        assert!(matches!(
+            cgroup_id(&missing),
+            Err(ContainustError::Io { path, .. }) if path == missing
+        ));
+        let file = dir.path().join("cgroup.procs");
+        std::fs::write(&file, "").expect("write");
+        let error = cgroup_id(&file).expect_err("not a directory");
+        assert!(
+            error.to_string().contains("not a cgroup directory"),
+            "{error}"
+        );
+    }
+
+    #[cfg(feature = "ebpf")]
+    #[test]
+    fn attach_for_container_is_rejected_until_a_program_is_embedded() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let error = Tracer::attach_for_container(dir.path()).expect_err("unsupported");
+        if probe_availability() == ProbeAvailability::Available {
+            assert!(error.to_string().contains("not supported yet"), "{error}");
+        }
     }
 
     #[test]
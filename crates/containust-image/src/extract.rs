@@ -2,16 +2,20 @@
 //!
 //! Rejects absolute paths, `..` components, hard links, device nodes,
 //! and symlink targets that resolve outside the extraction root —
-//! including chained-symlink escapes.
+//! including chained-symlink escapes. Every entry's final location is
+//! resolved through symlinks already on disk before it is written, so a
+//! `link -> /` followed by `link/etc/passwd` cannot reach the host.
 
 use std::io::Read;
 use std::path::{Component, Path, PathBuf};
 
+use containust_common::cancel::CancellationToken;
 use containust_common::error::{ContainustError, Result};
 
 use crate::path_confine::{assert_dest_confined, ensure_symlink_confined};
 
-const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// First two bytes of every gzip stream.
+pub const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
 
 /// Extracts a tar (optionally gzip-compressed) archive into `target`.
 ///
@@ -23,54 +27,100 @@ const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
 /// Returns an error if the archive cannot be read, contains an unsafe
 /// entry, or a filesystem write fails.
 pub fn safe_extract_archive(archive_path: &Path, target: &Path) -> Result<()> {
-    std::fs::create_dir_all(target).map_err(|source| ContainustError::Io {
-        path: target.to_path_buf(),
+    let file = std::fs::File::open(archive_path).map_err(|source| ContainustError::Io {
+        path: archive_path.to_path_buf(),
         source,
     })?;
-    match extract_into(archive_path, target) {
-        Ok(()) => Ok(()),
-        Err(error) => {
-            let _ = std::fs::remove_dir_all(target);
-            Err(error)
-        }
-    }
+    safe_extract_reader(file, target)
 }
 
-fn extract_into(archive_path: &Path, target: &Path) -> Result<()> {
-    let file = std::fs::File::open(archive_path).map_err(|source| ContainustError::Io {
-        path: archive_path.to_path_buf(),
+/// Extracts a tar stream (optionally gzip-compressed) into `target`.
+///
+/// Applies the same entry checks as [`safe_extract_archive`] but reads the
+/// input exactly once, so it can be fed directly from a download or pipe
+/// without staging the archive on disk. Each entry is validated before
+/// anything is written for it; on failure the target directory is removed.
+///
+/// # Errors
+///
+/// Returns an error if the stream cannot be read, contains an unsafe
+/// entry, or a filesystem write fails.
+pub fn safe_extract_reader<R: Read>(mut reader: R, target: &Path) -> Result<()> {
+    let magic = read_magic(&mut reader).map_err(|source| ContainustError::Io {
+        path: target.to_path_buf(),
         source,
     })?;
-    let mut peek = [0_u8; 2];
-    let mut reader = std::io::BufReader::new(file);
-    let gzip = reader
-        .read(&mut peek)
-        .map_err(|source| ContainustError::Io {
-            path: archive_path.to_path_buf(),
-            source,
-        })?
-        == 2
-        && peek == GZIP_MAGIC;
-    let file = std::fs::File::open(archive_path).map_err(|source| ContainustError::Io {
-        path: archive_path.to_path_buf(),
+    let gzip = magic == GZIP_MAGIC;
+    safe_extract_stream(magic.as_slice().chain(reader), target, gzip)
+}
+
+/// Reads up to the first two bytes of `reader`, fewer only at EOF.
+///
+/// Pipes and sockets may return a single byte per read, so one read is
+/// not enough to tell gzip from plain tar.
+fn read_magic<R: Read>(reader: &mut R) -> std::io::Result<Vec<u8>> {
+    let mut magic = Vec::with_capacity(GZIP_MAGIC.len());
+    let _ = reader
+        .by_ref()
+        .take(GZIP_MAGIC.len() as u64)
+        .read_to_end(&mut magic)?;
+    Ok(magic)
+}
+
+/// Extracts a tar stream into `target` whose compression the caller has
+/// already determined, with the checks of [`safe_extract_reader`].
+///
+/// # Errors
+///
+/// Returns an error if the stream cannot be read, contains an unsafe
+/// entry, or a filesystem write fails.
+pub fn safe_extract_stream<R: Read>(reader: R, target: &Path, gzip: bool) -> Result<()> {
+    safe_extract_stream_cancellable(reader, target, gzip, &CancellationToken::new())
+}
+
+/// Extracts like [`safe_extract_stream`], checking `cancel` before each
+/// archive entry. A cancelled extraction removes the target directory.
+///
+/// # Errors
+///
+/// Returns an error if the stream cannot be read, contains an unsafe
+/// entry, a filesystem write fails, or `cancel` fires.
+pub fn safe_extract_stream_cancellable<R: Read>(
+    reader: R,
+    target: &Path,
+    gzip: bool,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    std::fs::create_dir_all(target).map_err(|source| ContainustError::Io {
+        path: target.to_path_buf(),
         source,
     })?;
-    if gzip {
+    let result = if gzip {
         unpack_entries(
-            tar::Archive::new(flate2::read::GzDecoder::new(file)),
+            tar::Archive::new(flate2::read::GzDecoder::new(reader)),
             target,
+            cancel,
         )
     } else {
-        unpack_entries(tar::Archive::new(file), target)
+        unpack_entries(tar::Archive::new(reader), target, cancel)
+    };
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(target);
     }
+    result
 }
 
-fn unpack_entries<R: Read>(mut archive: tar::Archive<R>, target: &Path) -> Result<()> {
+fn unpack_entries<R: Read>(
+    mut archive: tar::Archive<R>,
+    target: &Path,
+    cancel: &CancellationToken,
+) -> Result<()> {
     let entries = archive.entries().map_err(|source| ContainustError::Io {
         path: target.to_path_buf(),
         source,
     })?;
     for entry in entries {
+        cancel.check("extraction")?;
         let mut entry = entry.map_err(|source| ContainustError::Io {
             path: target.to_path_buf(),
             source,
@@ -170,6 +220,7 @@ fn write_regular_file<R: Read>(
         })?;
     }
     assert_dest_confined(root, dest)?;
+    replace_symlink(dest)?;
     let mut out = std::fs::File::create(dest).map_err(|source| ContainustError::Io {
         path: dest.to_path_buf(),
         source,
@@ -181,6 +232,19 @@ fn write_regular_file<R: Read>(
     Ok(())
 }
 
+/// Removes a symlink sitting at `dest` so the file entry replaces it
+/// instead of writing through it, matching `tar` overwrite semantics.
+fn replace_symlink(dest: &Path) -> Result<()> {
+    let is_link = std::fs::symlink_metadata(dest).is_ok_and(|meta| meta.file_type().is_symlink());
+    if is_link {
+        std::fs::remove_file(dest).map_err(|source| ContainustError::Io {
+            path: dest.to_path_buf(),
+            source,
+        })?;
+    }
+    Ok(())
+}
+
 /// Applies the archive mode bits to an extracted file or directory.
 ///
 /// Setuid, setgid, and sticky bits are always stripped — no setuid
@@ -287,4 +351,95 @@ mod tests {
         let dir = tempfile::tempdir().expect("tempdir");
         assert_eq!(extracted_mode(dir.path(), 0o6755), 0o755);
     }
+
+    /// Returns at most one byte per read, like a slow pipe.
+    struct OneByte<'a>(&'a [u8]);
+
+    impl Read for OneByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(1);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn reader_detects_gzip_when_bytes_arrive_one_at_a_time() {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(4);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "a.txt", &b"data"[..])
+            .expect("append entry");
+        let archive = builder.into_inner().expect("finish tar");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &archive).expect("compress");
+        let compressed = encoder.finish().expect("finish gzip");
+        let dir = tempfile::tempdir().expect("tempdir");
+
+        for (name, bytes) in [("gz", &compressed), ("tar", &archive)] {
+            let target = dir.path().join(name);
+            safe_extract_reader(OneByte(bytes), &target).expect("extract");
+            assert_eq!(
+                std::fs::read(target.join("a.txt")).expect("read"),
+                b"data",
+                "{name}"
+            );
+        }
+    }
+
+    /// Cancels `token` once more than `after` bytes have been read.
+    struct CancelAfter<R> {
+        inner: R,
+        read: usize,
+        after: usize,
+        token: CancellationToken,
+    }
+
+    impl<R: Read> Read for CancelAfter<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let read = self.inner.read(buf)?;
+            self.read += read;
+            if self.read > self.after {
+                self.token.cancel();
+            }
+            Ok(read)
+        }
+    }
+
+    #[test]
+    fn cancelled_extraction_removes_the_target() {
+        let mut builder = tar::Builder::new(Vec::new());
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(4);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, &b"data"[..])
+                .expect("append entry");
+        }
+        let archive = builder.into_inner().expect("finish tar");
+        let token = CancellationToken::new();
+        // One 512-byte header plus one padded data block: the first entry.
+        let reader = CancelAfter {
+            inner: archive.as_slice(),
+            read: 0,
+            after: 1024,
+            token: token.clone(),
+        };
+        let dir = tempfile::tempdir().expect("tempdir");
+        let target = dir.path().join("rootfs");
+
+        let error =
+            safe_extract_stream_cancellable(reader, &target, false, &token).expect_err("cancelled");
+
+        assert!(
+            matches!(error, ContainustError::Cancelled { ref operation } if operation == "extraction")
+        );
+        assert!(!target.exists());
+    }
 }
@@ -8,7 +8,9 @@ use std::io::{Read, Write};
 use std::path::Path;
 use std::time::Duration;
 
-use containust_common::error::{ContainustError, Result};
+use containust_common::cancel::CancellationToken;
+use containust_common::error::{ContainustError, Result, is_transient_io, is_transient_status};
+use containust_common::retry::{RetryPolicy, retry};
 use containust_common::types::Sha256Hash;
 
 use crate::reference::ImageReference;
@@ -22,10 +24,12 @@ pub struct FetchPolicy {
     pub max_redirects: usize,
     /// Maximum accepted payload size in bytes.
     pub max_bytes: u64,
-    /// Number of retries after the first failed attempt.
-    pub retries: u32,
+    /// Backoff applied to transient download failures.
+    pub retry: RetryPolicy,
     /// When true, reject the fetch before opening any connection.
     pub offline: bool,
+    /// Checked between downloaded chunks; a cancelled download is deleted.
+    pub cancel: CancellationToken,
 }
 
 impl Default for FetchPolicy {
@@ -34,8 +38,9 @@ impl Default for FetchPolicy {
             timeout: Duration::from_secs(60),
             max_redirects: 5,
             max_bytes: 2 * 1024 * 1024 * 1024,
-            retries: 2,
+            retry: RetryPolicy::default(),
             offline: false,
+            cancel: CancellationToken::new(),
         }
     }
 }
@@ -48,8 +53,9 @@ impl Default for FetchPolicy {
 /// # Errors
 ///
 /// Returns an error when offline mode is enabled, the digest is
-/// missing, the size limit is exceeded, all retries fail, or the
-/// downloaded content does not match the pinned digest.
+/// missing, the size limit is exceeded, the download fails for a reason
+/// retrying cannot fix or keeps failing until the retry policy gives up,
+/// or the downloaded content does not match the pinned digest.
 pub fn fetch_remote(
     reference: &ImageReference,
     policy: &FetchPolicy,
@@ -62,6 +68,7 @@ pub fn fetch_remote(
             message: "offline mode blocks remote image fetch; import the image on a \
                       connected machine and copy the layer store"
                 .into(),
+            retryable: false,
         });
     }
     let Some(expected) = reference.digest() else {
@@ -70,6 +77,7 @@ pub fn fetch_remote(
             message: "remote sources require a pinned digest \
                       (append @sha256:<hex> to the image URI)"
                 .into(),
+            retryable: false,
         });
     };
 
@@ -94,23 +102,24 @@ fn download_with_retries(
     let client = build_client(policy).map_err(|error| ContainustError::Network {
         url: url.to_string(),
         message: format!("failed to construct HTTP client: {error}"),
+        retryable: false,
     })?;
-    let mut last_error = String::from("no attempt was made");
-    for attempt in 0..=policy.retries {
-        match download_once(&client, url, policy, destination) {
-            Ok(digest) => return Ok(digest),
-            Err(error) => {
-                tracing::warn!(url, attempt, %error, "remote fetch attempt failed");
-                last_error = error.to_string();
-            }
-        }
-    }
-    Err(ContainustError::Network {
-        url: url.to_string(),
-        message: format!(
-            "download failed after {} attempt(s): {last_error}",
-            policy.retries + 1
-        ),
+    let mut attempts = 0;
+    retry(&policy.retry, |attempt| {
+        attempts = attempt;
+        download_once(&client, url, policy, destination).inspect_err(|error| {
+            tracing::warn!(url, attempt, %error, "remote fetch attempt failed");
+        })
+    })
+    .map_err(|error| match error {
+        ContainustError::Network {
+            message, retryable, ..
+        } => ContainustError::Network {
+            url: url.to_string(),
+            message: format!("download failed after {attempts} attempt(s): {message}"),
+            retryable,
+        },
+        other => other,
     })
 }
 
@@ -121,51 +130,83 @@ pub(crate) fn build_client(policy: &FetchPolicy) -> reqwest::Result<reqwest::blo
         .build()
 }
 
+/// Whether a failed request may succeed when sent again: it timed out,
+/// could not connect, or lost the connection. Redirect loops and
+/// malformed requests are not transient.
+#[must_use]
+pub fn request_is_transient(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request() || error.is_body()
+}
+
+/// Whether a failed read of a response body may succeed on a fresh
+/// download, judged by the underlying request error when there is one
+/// and by the I/O error kind otherwise.
+#[must_use]
+pub fn read_is_transient(error: &std::io::Error) -> bool {
+    error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<reqwest::Error>())
+        .map_or_else(|| is_transient_io(error.kind()), request_is_transient)
+}
+
 fn download_once(
     client: &reqwest::blocking::Client,
     url: &str,
     policy: &FetchPolicy,
     destination: &Path,
 ) -> Result<Sha256Hash> {
-    let network_error = |message: String| ContainustError::Network {
+    let network_error = |message: String, retryable| ContainustError::Network {
         url: url.to_string(),
         message,
+        retryable,
     };
-    let response = client
-        .get(url)
-        .send()
-        .map_err(|error| network_error(format!("request failed: {error}")))?;
+    let response = client.get(url).send().map_err(|error| {
+        network_error(
+            format!("request failed: {error}"),
+            request_is_transient(&error),
+        )
+    })?;
     let status = response.status();
     if !status.is_success() {
-        return Err(network_error(format!("server returned status {status}")));
+        return Err(network_error(
+            format!("server returned status {status}"),
+            is_transient_status(status.as_u16()),
+        ));
     }
     if response
         .content_length()
         .is_some_and(|n| n > policy.max_bytes)
     {
-        return Err(network_error(format!(
-            "declared payload exceeds the {} byte limit",
-            policy.max_bytes
-        )));
+        return Err(network_error(
+            format!(
+                "declared payload exceeds the {} byte limit",
+                policy.max_bytes
+            ),
+            false,
+        ));
     }
-    copy_capped(response, destination, policy.max_bytes, url)
+    copy_capped(response, destination, policy, url)
 }
 
-/// Streams the response body to `destination` under the size cap while
+/// Streams a response `body` to `destination` under the size cap while
 /// hashing it in the same pass, so verification needs no second read.
+///
+/// The policy's cancellation token is checked after every chunk; a
+/// cancelled download deletes the partial file.
 pub(crate) fn copy_capped(
-    response: reqwest::blocking::Response,
+    body: impl Read,
     destination: &Path,
-    max_bytes: u64,
+    policy: &FetchPolicy,
     url: &str,
 ) -> Result<Sha256Hash> {
+    let max_bytes = policy.max_bytes;
     let io_error = |source| ContainustError::Io {
         path: destination.to_path_buf(),
         source,
     };
     let file = std::fs::File::create(destination).map_err(io_error)?;
     let mut writer = crate::hash::HashingWriter::new(file);
-    let mut reader = response.take(max_bytes.saturating_add(1));
+    let mut reader = body.take(max_bytes.saturating_add(1));
     let mut written: u64 = 0;
     let mut buffer = vec![0_u8; 64 * 1024];
     loop {
@@ -174,6 +215,7 @@ pub(crate) fn copy_capped(
             .map_err(|e| ContainustError::Network {
                 url: url.to_string(),
                 message: format!("stream interrupted after {written} bytes: {e}"),
+                retryable: read_is_transient(&e),
             })?;
         if read == 0 {
             break;
@@ -184,9 +226,14 @@ pub(crate) fn copy_capped(
             return Err(ContainustError::Network {
                 url: url.to_string(),
                 message: format!("payload exceeds the {max_bytes} byte limit"),
+                retryable: false,
             });
         }
         writer.write_all(&buffer[..read]).map_err(io_error)?;
+        if let Err(cancelled) = policy.cancel.check("download") {
+            let _ = std::fs::remove_file(destination);
+            return Err(cancelled);
+        }
     }
     let (file, digest) = writer.finish()?;
     file.sync_all().map_err(io_error)?;
@@ -263,7 +310,7 @@ mod tests {
     fn short_policy() -> FetchPolicy {
         FetchPolicy {
             timeout: Duration::from_secs(2),
-            retries: 0,
+            retry: RetryPolicy::once(),
             ..FetchPolicy::default()
         }
     }
@@ -332,7 +379,11 @@ mod tests {
         let reference = ImageReference::parse(&format!("{base}/image.tar@sha256:{BODY_DIGEST}"))
             .expect("parse");
         let policy = FetchPolicy {
-            retries: 1,
+            retry: RetryPolicy {
+                max_attempts: 2,
+                base_delay: Duration::from_millis(10),
+                max_delay: Duration::from_millis(10),
+            },
             ..short_policy()
         };
         let dir = tempfile::tempdir().expect("tempdir");
@@ -344,6 +395,37 @@ mod tests {
         stop_server(&base, handle);
     }
 
+    #[test]
+    fn fetch_retries_a_refused_connection() {
+        let reference = ImageReference::parse(&format!(
+            "http://127.0.0.1:1/image.tar@sha256:{BODY_DIGEST}"
+        ))
+        .expect("parse");
+        let policy = FetchPolicy {
+            retry: RetryPolicy {
+                max_attempts: 2,
+                base_delay: Duration::ZERO,
+                max_delay: Duration::ZERO,
+            },
+            ..short_policy()
+        };
+        let dir = tempfile::tempdir().expect("tempdir");
+
+        let error = fetch_remote(&reference, &policy, &dir.path().join("out.tar"))
+            .expect_err("nothing listens on port 1");
+
+        assert!(error.is_retryable(), "{error}");
+        assert!(error.to_string().contains("after 2 attempt(s)"), "{error}");
+    }
+
+    #[test]
+    fn stream_errors_are_classified_by_kind() {
+        use std::io::{Error, ErrorKind};
+
+        assert!(read_is_transient(&Error::from(ErrorKind::ConnectionReset)));
+        assert!(!read_is_transient(&Error::from(ErrorKind::InvalidData)));
+    }
+
     #[test]
     fn fetch_size_limit_rejects_oversized_payload() {
         let (base, handle) = serve(BODY, 0);
@@ -361,4 +443,46 @@ mod tests {
         assert!(error.to_string().contains("limit"));
         stop_server(&base, handle);
     }
+
+    /// Yields `body` in `chunk`-byte reads, cancelling `token` after the first.
+    struct CancellingReader<'a> {
+        body: &'a [u8],
+        chunk: usize,
+        token: CancellationToken,
+    }
+
+    impl Read for CancellingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.chunk.min(buf.len()).min(self.body.len());
+            buf[..len].copy_from_slice(&self.body[..len]);
+            self.body = &self.body[len..];
+            self.token.cancel();
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn cancelled_download_deletes_partial_file() {
+        let policy = FetchPolicy::default();
+        let reader = CancellingReader {
+            body: BODY,
+            chunk: 4,
+            token: policy.cancel.clone(),
+        };
+        let dir = tempfile::tempdir().expect("tempdir");
+        let destination = dir.path().join("out.tar");
+
+        let error = copy_capped(
+            reader,
+            &destination,
+            &policy,
+            "http://example.test/image.tar",
+        )
+        .expect_err("cancelled");
+
+        assert!(
+            matches!(error, ContainustError::Cancelled { ref operation } if operation == "download")
+        );
+        assert!(!destination.exists());
+    }
 }
@@ -15,6 +15,7 @@ use crate::extract::safe_extract_archive;
 use crate::fetch::{FetchPolicy, fetch_remote};
 use crate::pack::pack_directory_hashed;
 use crate::preset::{preset_fetch_reference, resolve_preset};
+use crate::progress::{ImportProgress, ImportStep, NoProgress, timed};
 use crate::reference::{ImageReference, ImageScheme};
 use crate::registry::{ImageCatalog, ImageEntry};
 use crate::storage::StorageBackend;
@@ -58,6 +59,16 @@ impl ImportRequest {
         self
     }
 
+    /// Aborts downloads and registry pulls once `cancel` fires.
+    #[must_use]
+    pub fn with_cancellation(
+        mut self,
+        cancel: containust_common::cancel::CancellationToken,
+    ) -> Self {
+        self.fetch_policy.cancel = cancel;
+        self
+    }
+
     /// Requires cosign verification of the image signature (P11.9).
     #[must_use]
     pub const fn with_require_provenance(mut self) -> Self {
@@ -81,12 +92,33 @@ pub fn import_image(
     data_dir: &Path,
     reference: &ImageReference,
     request: &ImportRequest,
+) -> Result<ImageEntry> {
+    import_image_with_progress(data_dir, reference, request, &NoProgress)
+}
+
+/// Imports an image like [`import_image`], reporting each timed step to
+/// `progress`.
+///
+/// # Errors
+///
+/// Returns the same errors as [`import_image`].
+pub fn import_image_with_progress(
+    data_dir: &Path,
+    reference: &ImageReference,
+    request: &ImportRequest,
+    progress: &dyn ImportProgress,
 ) -> Result<ImageEntry> {
     let store = StorageBackend::open(data_dir.to_path_buf())?;
+    let context = ImportContext {
+        data_dir,
+        store: &store,
+        request,
+        progress,
+    };
     if reference.scheme() == ImageScheme::Oci {
-        return import_oci_image(data_dir, &store, reference, request);
+        return import_oci_image(&context, reference);
     }
-    let staged = stage_source(&store, reference, request)?;
+    let staged = stage_source(&context, reference)?;
 
     let digest = staged.digest().clone();
     if let Some(pinned) = reference.digest()
@@ -103,7 +135,9 @@ pub fn import_image(
     let size_bytes = match staged {
         StagedLayer::Staged { ref path, .. } => {
             let size = file_size(path)?;
-            store.commit_layer(path, digest.as_hex())?;
+            timed(progress, ImportStep::Commit, Some(digest.as_hex()), || {
+                store.commit_layer(path, digest.as_hex())
+            })?;
             size
         }
         StagedLayer::Cached { .. } => file_size(&store.layer_blob_path(digest.as_hex()))?,
@@ -118,12 +152,30 @@ pub fn import_image(
         created_at: chrono::Utc::now().to_rfc3339(),
         digest: Some(digest.as_hex().to_string()),
         tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        entrypoint: None,
+        cmd: Vec::new(),
     };
-    ImageCatalog::open(data_dir)?.register(entry.clone())?;
+    context.register(&entry)?;
     tracing::info!(name = %entry.name, digest = %digest, "image imported");
     Ok(entry)
 }
 
+/// Shared inputs of one import, threaded through the staging helpers.
+struct ImportContext<'a> {
+    data_dir: &'a Path,
+    store: &'a StorageBackend,
+    request: &'a ImportRequest,
+    progress: &'a dyn ImportProgress,
+}
+
+impl ImportContext<'_> {
+    fn register(&self, entry: &ImageEntry) -> Result<()> {
+        timed(self.progress, ImportStep::Register, None, || {
+            ImageCatalog::open(self.data_dir)?.register(entry.clone())
+        })
+    }
+}
+
 /// Reconstructs an image rootfs from the local catalog into `target`.
 ///
 /// Works entirely from the content-addressed store, so it is safe in
@@ -162,12 +214,8 @@ pub fn materialize_image(data_dir: &Path, reference: &ImageReference, target: &P
 /// The catalog digest is the top-level manifest digest; each layer
 /// blob is committed under its own content address so shared base
 /// layers deduplicate across images.
-fn import_oci_image(
-    data_dir: &Path,
-    store: &StorageBackend,
-    reference: &ImageReference,
-    request: &ImportRequest,
-) -> Result<ImageEntry> {
+fn import_oci_image(context: &ImportContext<'_>, reference: &ImageReference) -> Result<ImageEntry> {
+    let request = context.request;
     if reference.digest().is_none() && !request.allow_unpinned {
         return Err(ContainustError::Config {
             message: format!(
@@ -181,11 +229,18 @@ fn import_oci_image(
     let provenance = crate::oci::ProvenancePolicy {
         require: request.require_provenance,
     };
-    let pulled = crate::oci::pull_image(store, reference, &request.fetch_policy, provenance)?;
+    let pulled = timed(context.progress, ImportStep::Download, None, || {
+        crate::oci::pull_image(context.store, reference, &request.fetch_policy, provenance)
+    })?;
     let mut layers = Vec::with_capacity(pulled.layers.len());
     let mut size_bytes = 0_u64;
     for blob in &pulled.layers {
-        store.commit_layer(&blob.path, blob.digest.as_hex())?;
+        timed(
+            context.progress,
+            ImportStep::Commit,
+            Some(blob.digest.as_hex()),
+            || context.store.commit_layer(&blob.path, blob.digest.as_hex()),
+        )?;
         layers.push(blob.digest.as_hex().to_string());
         size_bytes += blob.size;
     }
@@ -199,8 +254,10 @@ fn import_oci_image(
         created_at: chrono::Utc::now().to_rfc3339(),
         digest: Some(digest),
         tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        entrypoint: pulled.config.entrypoint,
+        cmd: pulled.config.cmd,
     };
-    ImageCatalog::open(data_dir)?.register(entry.clone())?;
+    context.register(&entry)?;
     tracing::info!(name = %entry.name, digest = %pulled.manifest_digest, "oci image imported");
     Ok(entry)
 }
@@ -245,38 +302,33 @@ impl StagedLayer {
     }
 }
 
-fn stage_source(
-    store: &StorageBackend,
-    reference: &ImageReference,
-    request: &ImportRequest,
-) -> Result<StagedLayer> {
+fn stage_source(context: &ImportContext<'_>, reference: &ImageReference) -> Result<StagedLayer> {
+    let request = context.request;
+    let progress = context.progress;
     if request.offline && reference.is_remote() && reference.scheme() != ImageScheme::Preset {
         return Err(ContainustError::Network {
             url: reference.canonical_uri(),
             message: "offline mode blocks remote image import".into(),
+            retryable: false,
         });
     }
-    let staged = store.staging_path();
+    let staged = context.store.staging_path();
     let digest = match reference.scheme() {
         ImageScheme::File => {
-            let source = require_existing(reference.location(), "image directory")?;
-            pack_directory_hashed(&source, &staged)?
-        }
-        ImageScheme::Tar => {
-            // `fs::copy` clones on reflink filesystems (APFS, btrfs) and
-            // uses in-kernel copy elsewhere; hashing the staged copy is
-            // then the only userspace pass over the bytes.
-            let source = require_existing(reference.location(), "tar archive")?;
-            let _ = std::fs::copy(&source, &staged).map_err(|e| ContainustError::Io {
-                path: source.clone(),
-                source: e,
+            let source = timed(progress, ImportStep::Resolve, None, || {
+                require_existing(reference.location(), "image directory")
             })?;
-            crate::hash::hash_file(&staged)?
+            timed(progress, ImportStep::Pack, None, || {
+                pack_directory_hashed(&source, &staged)
+            })?
         }
+        ImageScheme::Tar => stage_tar(progress, reference, &staged)?,
         ImageScheme::Https | ImageScheme::Http => {
-            fetch_remote(reference, &request.fetch_policy, &staged)?
+            timed(progress, ImportStep::Download, None, || {
+                fetch_remote(reference, &request.fetch_policy, &staged)
+            })?
         }
-        ImageScheme::Preset => return stage_preset(store, reference, request, &staged),
+        ImageScheme::Preset => return stage_preset(context, reference, &staged),
         // OCI pulls are multi-layer and handled by `import_oci_image`
         // before staging; reaching here would be an internal bug.
         ImageScheme::Oci => {
@@ -301,21 +353,49 @@ fn stage_source(
     })
 }
 
+/// Copies a local tar archive into staging and hashes the copy.
+fn stage_tar(
+    progress: &dyn ImportProgress,
+    reference: &ImageReference,
+    staged: &Path,
+) -> Result<Sha256Hash> {
+    let source = timed(progress, ImportStep::Resolve, None, || {
+        require_existing(reference.location(), "tar archive")
+    })?;
+    // `fs::copy` clones on reflink filesystems (APFS, btrfs) and
+    // uses in-kernel copy elsewhere; hashing the staged copy is
+    // then the only userspace pass over the bytes.
+    let _ = timed(progress, ImportStep::Pack, None, || {
+        std::fs::copy(&source, staged).map_err(|e| ContainustError::Io {
+            path: source.clone(),
+            source: e,
+        })
+    })?;
+    timed(progress, ImportStep::Hash, None, || {
+        crate::hash::hash_file(staged)
+    })
+}
+
 /// Stages a curated preset from the local layer cache, or downloads it.
 ///
 /// A cached blob is integrity-checked against the curated digest and
 /// then reused in place — no staging copy, no re-import of bytes.
 fn stage_preset(
-    store: &StorageBackend,
+    context: &ImportContext<'_>,
     reference: &ImageReference,
-    request: &ImportRequest,
     staged: &Path,
 ) -> Result<StagedLayer> {
-    let preset = resolve_preset(reference)?;
+    let progress = context.progress;
+    let preset = timed(progress, ImportStep::Resolve, None, || {
+        resolve_preset(reference)
+    })?;
     let curated = Sha256Hash::from_hex(preset.sha256)?;
-    if store.has_layer(preset.sha256) {
-        let blob = store.layer_blob_path(preset.sha256);
-        crate::hash::validate_hash(&blob, &curated)?;
+    if context.store.has_layer(preset.sha256) {
+        let blob = context.store.layer_blob_path(preset.sha256);
+        timed(progress, ImportStep::Hash, Some(preset.sha256), || {
+            crate::hash::validate_hash(&blob, &curated)
+        })?;
+        context.store.touch_layer(preset.sha256);
         tracing::info!(
             name = preset.name,
             version = preset.version,
@@ -323,7 +403,7 @@ fn stage_preset(
         );
         return Ok(StagedLayer::Cached { digest: curated });
     }
-    if request.offline {
+    if context.request.offline {
         return Err(ContainustError::Network {
             url: reference.canonical_uri(),
             message: format!(
@@ -331,12 +411,15 @@ fn stage_preset(
                  with `ctst build`, then reuse the project store air-gapped",
                 preset.name, preset.version
             ),
+            retryable: false,
         });
     }
     let fetch_ref = preset_fetch_reference(&preset)?;
-    let mut policy = request.fetch_policy.clone();
+    let mut policy = context.request.fetch_policy.clone();
     policy.offline = false;
-    let digest = fetch_remote(&fetch_ref, &policy, staged)?;
+    let digest = timed(progress, ImportStep::Download, None, || {
+        fetch_remote(&fetch_ref, &policy, staged)
+    })?;
     tracing::info!(
         name = preset.name,
         version = preset.version,
@@ -377,6 +460,7 @@ fn extract_layer_blob(store: &StorageBackend, hash: &str, target: &Path) -> Resu
         });
     }
     safe_extract_archive(&blob, target)?;
+    store.touch_layer(hash);
     apply_whiteouts(target)
 }
 
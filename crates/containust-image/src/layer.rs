@@ -3,11 +3,14 @@
 //! Each image is composed of ordered layers. Layers are content-addressed
 //! by their SHA-256 hash and stored in the local layer cache.
 
+use std::io::Read;
 use std::path::Path;
 
 use containust_common::error::{ContainustError, Result};
 use containust_common::types::Sha256Hash;
 
+use crate::extract::GZIP_MAGIC;
+
 /// A single filesystem layer in an image.
 #[derive(Debug, Clone)]
 pub struct Layer {
@@ -17,9 +20,42 @@ pub struct Layer {
     pub size_bytes: u64,
 }
 
+/// Returns whether `path` has a gzip extension (`.gz` or `.tgz`).
+#[must_use]
+pub fn is_gzip_archive(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz") || ext.eq_ignore_ascii_case("tgz"))
+}
+
+/// Returns whether the archive at `path` is gzip-compressed.
+///
+/// The first two bytes decide, whatever the file is named; the extension
+/// is consulted only when the file is too short to hold the magic.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or read.
+pub fn detect_gzip(path: &Path) -> Result<bool> {
+    let io_error = |source| ContainustError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut magic = Vec::with_capacity(GZIP_MAGIC.len());
+    let _ = std::fs::File::open(path)
+        .map_err(io_error)?
+        .take(GZIP_MAGIC.len() as u64)
+        .read_to_end(&mut magic)
+        .map_err(io_error)?;
+    if magic.len() < GZIP_MAGIC.len() {
+        return Ok(is_gzip_archive(path));
+    }
+    Ok(magic == GZIP_MAGIC)
+}
+
 /// Extracts a tar archive to the target directory.
 ///
-/// Supports both plain `.tar` and gzip-compressed `.tar.gz` / `.tgz` archives.
+/// Supports both plain and gzip-compressed archives, detected by content
+/// rather than by name (see [`detect_gzip`]).
 ///
 /// # Errors
 ///
@@ -42,7 +78,12 @@ pub fn extract_layer(archive_path: &Path, target: &Path) -> Result<Layer> {
     })?;
     let size_bytes = metadata.len();
 
-    crate::extract::safe_extract_archive(archive_path, target)?;
+    let gzip = detect_gzip(archive_path)?;
+    let file = std::fs::File::open(archive_path).map_err(|e| ContainustError::Io {
+        path: archive_path.to_path_buf(),
+        source: e,
+    })?;
+    crate::extract::safe_extract_stream(file, target, gzip)?;
 
     let hash = crate::hash::hash_file(archive_path)?;
     tracing::info!(hash = %hash, size = size_bytes, "layer extracted");
@@ -54,11 +95,6 @@ pub fn extract_layer(archive_path: &Path, target: &Path) -> Result<Layer> {
 mod tests {
     use super::*;
 
-    fn is_gzip_archive(path: &Path) -> bool {
-        path.extension()
-            .is_some_and(|ext| ext.eq_ignore_ascii_case("gz") || ext.eq_ignore_ascii_case("tgz"))
-    }
-
     fn create_test_tar(dir: &Path) -> std::path::PathBuf {
         let tar_path = dir.join("test.tar");
         let file = std::fs::File::create(&tar_path).expect("failed to create tar file");
@@ -121,6 +157,33 @@ mod tests {
         assert_eq!(content, "hello from gzipped layer");
     }
 
+    #[test]
+    fn extract_gzip_named_tar_detects_magic() {
+        let dir = tempfile::tempdir().expect("failed to create tempdir");
+        let misnamed = dir.path().join("artifact.tar");
+        std::fs::rename(create_test_tar_gz(dir.path()), &misnamed).expect("rename failed");
+        assert!(detect_gzip(&misnamed).expect("detect failed"));
+
+        let target = dir.path().join("extracted_misnamed");
+        let _ = extract_layer(&misnamed, &target).expect("extract failed");
+        let content = std::fs::read_to_string(target.join("gzhello.txt")).expect("read failed");
+        assert_eq!(content, "hello from gzipped layer");
+    }
+
+    #[test]
+    fn detect_gzip_falls_back_to_extension_for_short_files() {
+        let dir = tempfile::tempdir().expect("failed to create tempdir");
+        let plain = create_test_tar(dir.path());
+        assert!(!detect_gzip(&plain).expect("detect failed"));
+
+        let short_gz = dir.path().join("short.tgz");
+        std::fs::write(&short_gz, [0x1f]).expect("write failed");
+        assert!(detect_gzip(&short_gz).expect("detect failed"));
+        let short_tar = dir.path().join("short.tar");
+        std::fs::write(&short_tar, [0x1f]).expect("write failed");
+        assert!(!detect_gzip(&short_tar).expect("detect failed"));
+    }
+
     #[test]
     fn extract_nonexistent_archive_returns_error() {
         let dir = tempfile::tempdir().expect("failed to create tempdir");
@@ -0,0 +1,233 @@
+//! Overlay mounts of cached multi-layer images.
+//!
+//! [`prepare_overlay`] extracts each stored layer blob once, next to it
+//! (see [`StorageBackend::layer_extracted_path`]), and stacks those
+//! directories as read-only lower layers, so later mounts of a cached
+//! image reuse them without extracting again. OCI whiteout markers are
+//! kept as regular files rather than translated to overlay whiteouts.
+
+use std::path::{Path, PathBuf};
+
+use containust_common::error::{ContainustError, Result};
+use containust_core::filesystem::overlayfs::OverlayConfig;
+
+use crate::registry::ImageEntry;
+use crate::storage::StorageBackend;
+
+/// Directory under the storage root holding per-mount upper and work dirs.
+const OVERLAY_DIR_NAME: &str = "overlay";
+
+/// Builds the overlay configuration that mounts `entry` at `merged`.
+///
+/// Lower directories follow `entry.layers`, bottom to top; a layer that
+/// has not been extracted yet is extracted from its stored blob first.
+/// The writable upper and work directories are created under the storage
+/// root, keyed by the merged path so each mount of the same image gets
+/// its own.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] when the image has no layers,
+/// [`ContainustError::NotFound`] when a layer blob is not in the store,
+/// or an error if a layer cannot be extracted or the upper or work
+/// directory cannot be created.
+pub fn prepare_overlay(
+    entry: &ImageEntry,
+    storage: &StorageBackend,
+    merged: &Path,
+) -> Result<OverlayConfig> {
+    if entry.layers.is_empty() {
+        return Err(ContainustError::Config {
+            message: format!("image {} has no layers to mount", entry.name),
+        });
+    }
+    let lower_dirs = entry
+        .layers
+        .iter()
+        .map(|hash| extracted_layer(storage, hash, &entry.name))
+        .collect::<Result<Vec<_>>>()?;
+    let mount_dir = storage
+        .root()
+        .join(OVERLAY_DIR_NAME)
+        .join(mount_key(entry, merged));
+    let upper_dir = mount_dir.join("upper");
+    let work_dir = mount_dir.join("work");
+    for dir in [&upper_dir, &work_dir] {
+        std::fs::create_dir_all(dir).map_err(|source| ContainustError::Io {
+            path: dir.clone(),
+            source,
+        })?;
+    }
+    Ok(OverlayConfig {
+        lower_dirs,
+        upper_dir,
+        work_dir,
+        merged_dir: merged.to_path_buf(),
+    })
+}
+
+/// Returns the extracted directory of layer `hash`, extracting its blob
+/// into a staging directory and moving it into place on first use.
+fn extracted_layer(storage: &StorageBackend, hash: &str, image: &str) -> Result<PathBuf> {
+    let dir = storage.layer_extracted_path(hash);
+    if dir.is_dir() {
+        storage.touch_layer(hash);
+        return Ok(dir);
+    }
+    let blob = storage.layer_blob_path(hash);
+    if !blob.is_file() {
+        return Err(ContainustError::NotFound {
+            kind: "image layer",
+            id: format!("{hash} (image {image})"),
+        });
+    }
+    let staging = storage.staging_path();
+    let moved = crate::extract::safe_extract_archive(&blob, &staging).and_then(|()| {
+        std::fs::rename(&staging, &dir).map_err(|source| ContainustError::Io {
+            path: dir.clone(),
+            source,
+        })
+    });
+    if let Err(error) = moved {
+        let _ = std::fs::remove_dir_all(&staging);
+        // Another mount may have extracted the same layer concurrently.
+        if !dir.is_dir() {
+            return Err(error);
+        }
+    }
+    storage.touch_layer(hash);
+    Ok(dir)
+}
+
+/// `<image id>-<hash of merged path>`, stable for a given mount point.
+fn mount_key(entry: &ImageEntry, merged: &Path) -> String {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write as _;
+
+    let digest = Sha256::digest(merged.to_string_lossy().as_bytes());
+    let mut key = format!("{}-", entry.id.as_str());
+    for byte in &digest[..8] {
+        let _ = write!(key, "{byte:02x}");
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use containust_common::types::ImageId;
+
+    use super::*;
+
+    fn entry(layers: &[&str]) -> ImageEntry {
+        ImageEntry {
+            id: ImageId::new("img1"),
+            name: "web".into(),
+            source: "file:///opt/images/web".into(),
+            layers: layers.iter().map(ToString::to_string).collect(),
+            size_bytes: 0,
+            created_at: "2026-01-01T00:00:00Z".into(),
+            digest: None,
+            tool_version: String::new(),
+            entrypoint: None,
+            cmd: Vec::new(),
+        }
+    }
+
+    fn extracted(storage: &StorageBackend, hashes: &[&str]) {
+        for hash in hashes {
+            std::fs::create_dir_all(storage.layer_extracted_path(hash)).unwrap();
+        }
+    }
+
+    #[test]
+    fn lower_dirs_follow_layer_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = StorageBackend::open(dir.path()).unwrap();
+        extracted(&storage, &["base", "mid", "top"]);
+
+        let merged = dir.path().join("merged");
+        let config = prepare_overlay(&entry(&["base", "mid", "top"]), &storage, &merged).unwrap();
+
+        let layers = dir.path().join("layers");
+        assert_eq!(
+            config.lower_dirs,
+            [
+                layers.join("base").join("rootfs"),
+                layers.join("mid").join("rootfs"),
+                layers.join("top").join("rootfs"),
+            ]
+        );
+        assert_eq!(config.merged_dir, merged);
+    }
+
+    #[test]
+    fn upper_and_work_dirs_live_under_storage_root_per_mount() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = StorageBackend::open(dir.path()).unwrap();
+        extracted(&storage, &["base"]);
+        let image = entry(&["base"]);
+
+        let first = prepare_overlay(&image, &storage, &dir.path().join("c1")).unwrap();
+        let again = prepare_overlay(&image, &storage, &dir.path().join("c1")).unwrap();
+        let second = prepare_overlay(&image, &storage, &dir.path().join("c2")).unwrap();
+
+        let mount_dir = first.upper_dir.parent().unwrap();
+        assert_eq!(mount_dir.parent().unwrap(), dir.path().join("overlay"));
+        assert!(
+            mount_dir
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("img1-")
+        );
+        assert_eq!(first.work_dir, mount_dir.join("work"));
+        assert!(first.upper_dir.is_dir() && first.work_dir.is_dir());
+        assert_eq!(first.upper_dir, again.upper_dir);
+        assert_ne!(first.upper_dir, second.upper_dir);
+    }
+
+    #[test]
+    fn layers_of_an_imported_image_are_extracted_on_first_mount() {
+        let dir = tempfile::tempdir().unwrap();
+        let rootfs = dir.path().join("rootfs");
+        std::fs::create_dir_all(rootfs.join("bin")).unwrap();
+        std::fs::write(rootfs.join("bin/app"), b"app").unwrap();
+        let data_dir = dir.path().join("data");
+        let reference =
+            crate::reference::ImageReference::parse(&format!("file://{}", rootfs.display()))
+                .unwrap();
+        let image = crate::import::import_image(
+            &data_dir,
+            &reference,
+            &crate::import::ImportRequest::new("app", false),
+        )
+        .unwrap();
+        let storage = StorageBackend::open(&data_dir).unwrap();
+
+        let config = prepare_overlay(&image, &storage, &dir.path().join("merged")).unwrap();
+        let again = prepare_overlay(&image, &storage, &dir.path().join("merged")).unwrap();
+
+        assert_eq!(config.lower_dirs.len(), image.layers.len());
+        let top = config.lower_dirs.last().unwrap();
+        assert_eq!(std::fs::read(top.join("bin/app")).unwrap(), b"app");
+        assert_eq!(again.lower_dirs, config.lower_dirs);
+        let layers = std::fs::read_dir(data_dir.join("layers")).unwrap();
+        assert!(
+            layers
+                .flatten()
+                .all(|entry| !entry.file_name().to_string_lossy().starts_with(".staging")),
+            "staging directories must not be left behind"
+        );
+    }
+
+    #[test]
+    fn missing_layer_blob_is_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = StorageBackend::open(dir.path()).unwrap();
+        extracted(&storage, &["base"]);
+        let error = prepare_overlay(&entry(&["base", "gone"]), &storage, dir.path()).unwrap_err();
+        assert!(matches!(error, ContainustError::NotFound { .. }));
+        assert!(error.to_string().contains("gone"), "{error}");
+        assert!(prepare_overlay(&entry(&[]), &storage, dir.path()).is_err());
+    }
+}
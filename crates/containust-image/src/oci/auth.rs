@@ -8,9 +8,11 @@
 use std::path::PathBuf;
 
 use base64::Engine as _;
-use containust_common::error::{ContainustError, Result};
+use containust_common::error::{ContainustError, Result, is_transient_status};
 use serde::Deserialize;
 
+use crate::fetch::request_is_transient;
+
 /// Docker Hub registries store credentials under this legacy key.
 const DOCKER_HUB_CONFIG_KEY: &str = "https://index.docker.io/v1/";
 
@@ -164,9 +166,10 @@ pub fn fetch_bearer_token(
     repository: &str,
     credentials: Option<&BasicCredentials>,
 ) -> Result<String> {
-    let network_error = |message: String| ContainustError::Network {
+    let network_error = |message: String, retryable| ContainustError::Network {
         url: challenge.realm.clone(),
         message,
+        retryable,
     };
     let mut request = client
         .get(&challenge.realm)
@@ -177,24 +180,31 @@ pub fn fetch_bearer_token(
     if let Some(credentials) = credentials {
         request = request.basic_auth(&credentials.username, Some(&credentials.password));
     }
-    let response = request
-        .send()
-        .map_err(|error| network_error(format!("token request failed: {error}")))?;
+    let response = request.send().map_err(|error| {
+        network_error(
+            format!("token request failed: {error}"),
+            request_is_transient(&error),
+        )
+    })?;
     let status = response.status();
     if !status.is_success() {
-        return Err(network_error(format!(
-            "token endpoint returned status {status}; check registry credentials"
-        )));
+        return Err(network_error(
+            format!("token endpoint returned status {status}; check registry credentials"),
+            is_transient_status(status.as_u16()),
+        ));
     }
-    let text = response
-        .text()
-        .map_err(|error| network_error(format!("failed to read token response: {error}")))?;
+    let text = response.text().map_err(|error| {
+        network_error(
+            format!("failed to read token response: {error}"),
+            request_is_transient(&error),
+        )
+    })?;
     let body: TokenResponse = serde_json::from_str(&text)
-        .map_err(|error| network_error(format!("invalid token response: {error}")))?;
+        .map_err(|error| network_error(format!("invalid token response: {error}"), false))?;
     body.token
         .or(body.access_token)
         .filter(|token| !token.is_empty())
-        .ok_or_else(|| network_error("token endpoint returned no token".into()))
+        .ok_or_else(|| network_error("token endpoint returned no token".into(), false))
 }
 
 #[cfg(test)]
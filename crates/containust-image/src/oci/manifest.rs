@@ -44,6 +44,8 @@ struct RawManifest {
     manifests: Vec<Descriptor>,
     #[serde(default)]
     layers: Vec<Descriptor>,
+    #[serde(default)]
+    config: Option<Descriptor>,
 }
 
 /// A parsed registry manifest.
@@ -51,8 +53,36 @@ struct RawManifest {
 pub enum Manifest {
     /// Multi-platform index; entries reference platform manifests.
     Index(Vec<Descriptor>),
-    /// Single-platform image; entries are ordered layer blobs.
-    Image(Vec<Descriptor>),
+    /// Single-platform image.
+    Image {
+        /// Image config blob carrying runtime defaults, when declared.
+        config: Option<Descriptor>,
+        /// Ordered layer blobs.
+        layers: Vec<Descriptor>,
+    },
+}
+
+/// Runtime defaults declared by an image config blob.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageConfig {
+    /// Default entrypoint (`config.Entrypoint`).
+    pub entrypoint: Option<Vec<String>>,
+    /// Default arguments appended to the entrypoint (`config.Cmd`).
+    pub cmd: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawImageConfig {
+    #[serde(default)]
+    config: Option<RawRuntimeConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawRuntimeConfig {
+    #[serde(rename = "Entrypoint", default)]
+    entrypoint: Option<Vec<String>>,
+    #[serde(rename = "Cmd", default)]
+    cmd: Option<Vec<String>>,
 }
 
 /// Parses a manifest body into an index or an image manifest.
@@ -70,13 +100,35 @@ pub fn parse_manifest(body: &[u8]) -> Result<Manifest> {
         return Ok(Manifest::Index(raw.manifests));
     }
     if !raw.layers.is_empty() {
-        return Ok(Manifest::Image(raw.layers));
+        return Ok(Manifest::Image {
+            config: raw.config,
+            layers: raw.layers,
+        });
     }
     Err(ContainustError::Config {
         message: "registry manifest has neither manifest entries nor layers".into(),
     })
 }
 
+/// Parses the runtime defaults out of an image config blob.
+///
+/// # Errors
+///
+/// Returns an error when the body is not valid image config JSON.
+pub fn parse_image_config(body: &[u8]) -> Result<ImageConfig> {
+    let raw: RawImageConfig =
+        serde_json::from_slice(body).map_err(|error| ContainustError::Config {
+            message: format!("invalid image config JSON: {error}"),
+        })?;
+    let runtime = raw.config.unwrap_or_default();
+    Ok(ImageConfig {
+        entrypoint: runtime
+            .entrypoint
+            .filter(|entrypoint| !entrypoint.is_empty()),
+        cmd: runtime.cmd.unwrap_or_default(),
+    })
+}
+
 /// Maps the host CPU architecture to OCI platform notation.
 #[must_use]
 pub fn host_oci_architecture() -> &'static str {
@@ -157,10 +209,39 @@ mod tests {
         let body = br#"{"layers":[{"digest":"sha256:aa","size":3},{"digest":"sha256:bb"}]}"#;
         assert!(matches!(
             parse_manifest(body).expect("parse"),
-            Manifest::Image(layers) if layers.len() == 2
+            Manifest::Image { layers, .. } if layers.len() == 2
+        ));
+    }
+
+    #[test]
+    fn parse_manifest_image_keeps_config_descriptor() {
+        let body = br#"{"config":{"digest":"sha256:cc"},"layers":[{"digest":"sha256:aa"}]}"#;
+        assert!(matches!(
+            parse_manifest(body).expect("parse"),
+            Manifest::Image { config: Some(config), .. } if config.digest == "sha256:cc"
         ));
     }
 
+    #[test]
+    fn parse_image_config_reads_entrypoint_and_cmd() {
+        let body = br#"{"config":{"Entrypoint":["/docker-entrypoint.sh"],"Cmd":["nginx"]}}"#;
+        let config = parse_image_config(body).expect("parse");
+        assert_eq!(
+            config.entrypoint,
+            Some(vec!["/docker-entrypoint.sh".to_string()])
+        );
+        assert_eq!(config.cmd, vec!["nginx"]);
+    }
+
+    #[test]
+    fn parse_image_config_null_fields_default_to_empty() {
+        let body = br#"{"config":{"Entrypoint":null,"Cmd":null}}"#;
+        assert_eq!(
+            parse_image_config(body).expect("parse"),
+            ImageConfig::default()
+        );
+    }
+
     #[test]
     fn parse_manifest_empty_body_returns_error() {
         assert!(parse_manifest(b"{}").is_err());
@@ -7,15 +7,19 @@
 
 use std::io::Read;
 
-use containust_common::error::{ContainustError, Result};
+use containust_common::cancel::CancellationToken;
+use containust_common::error::{ContainustError, Result, is_transient_status};
+use containust_common::retry::retry;
 use containust_common::types::Sha256Hash;
 use sha2::{Digest, Sha256};
 
-use crate::fetch::{FetchPolicy, build_client, copy_capped};
+use crate::fetch::{
+    FetchPolicy, build_client, copy_capped, read_is_transient, request_is_transient,
+};
 use crate::oci::auth;
 use crate::oci::manifest::{
-    Descriptor, MANIFEST_ACCEPT, Manifest, descriptor_sha256, host_oci_architecture,
-    parse_manifest, select_platform,
+    Descriptor, ImageConfig, MANIFEST_ACCEPT, Manifest, descriptor_sha256, host_oci_architecture,
+    parse_image_config, parse_manifest, select_platform,
 };
 use crate::oci::name::{OciName, parse_oci_name};
 use crate::oci::provenance::{ProvenancePolicy, ensure_image_provenance};
@@ -43,6 +47,8 @@ pub struct PulledImage {
     pub manifest_digest: Sha256Hash,
     /// Verified layer blobs in extraction order.
     pub layers: Vec<LayerBlob>,
+    /// Runtime defaults (entrypoint, cmd) from the image config blob.
+    pub config: ImageConfig,
 }
 
 /// Pulls an `oci://` reference into staged, digest-verified layer blobs.
@@ -65,6 +71,7 @@ pub fn pull_image(
             message: "offline mode blocks registry pulls; pull on a connected machine \
                       and copy the layer store"
                 .into(),
+            retryable: false,
         });
     }
     let name = parse_oci_name(reference.location())?;
@@ -78,11 +85,14 @@ pub fn pull_image(
     verify_pin(reference, &manifest_digest)?;
     ensure_image_provenance(&name, &manifest_digest, provenance)?;
 
-    let layers = session.resolve_layer_descriptors(&body)?;
-    let layers = layers
-        .iter()
-        .map(|descriptor| session.download_layer(store, descriptor))
-        .collect::<Result<Vec<_>>>()?;
+    let (config, layers) = session.resolve_image_descriptors(&body)?;
+    let config = config
+        .map(|descriptor| session.fetch_config(&descriptor))
+        .transpose()?
+        .unwrap_or_default();
+    let layers = stage_layers(&layers, &policy.cancel, |descriptor| {
+        session.download_layer(store, descriptor)
+    })?;
     tracing::info!(
         repository = %name.repository,
         digest = %manifest_digest,
@@ -92,9 +102,35 @@ pub fn pull_image(
     Ok(PulledImage {
         manifest_digest,
         layers,
+        config,
     })
 }
 
+/// Downloads every layer in order, checking `cancel` before each one.
+///
+/// On cancellation or any failure the blobs already staged are deleted,
+/// so an aborted pull leaves nothing behind in the staging area.
+fn stage_layers(
+    descriptors: &[Descriptor],
+    cancel: &CancellationToken,
+    mut download: impl FnMut(&Descriptor) -> Result<LayerBlob>,
+) -> Result<Vec<LayerBlob>> {
+    let mut staged = Vec::with_capacity(descriptors.len());
+    for descriptor in descriptors {
+        let blob = cancel.check("pull").and_then(|()| download(descriptor));
+        match blob {
+            Ok(blob) => staged.push(blob),
+            Err(error) => {
+                for blob in &staged {
+                    let _ = std::fs::remove_file(&blob.path);
+                }
+                return Err(error);
+            }
+        }
+    }
+    Ok(staged)
+}
+
 fn verify_pin(reference: &ImageReference, actual: &Sha256Hash) -> Result<()> {
     let Some(pinned) = reference.digest() else {
         return Ok(());
@@ -115,7 +151,7 @@ struct RegistrySession {
     base: String,
     repository: String,
     token: Option<String>,
-    max_blob_bytes: u64,
+    policy: FetchPolicy,
 }
 
 impl RegistrySession {
@@ -125,13 +161,14 @@ impl RegistrySession {
         let client = build_client(policy).map_err(|error| ContainustError::Network {
             url: base.clone(),
             message: format!("failed to construct HTTP client: {error}"),
+            retryable: false,
         })?;
         let mut session = Self {
             client,
             base,
             repository: name.repository.clone(),
             token: auth::env_bearer_token(),
-            max_blob_bytes: policy.max_bytes,
+            policy: policy.clone(),
         };
         if session.token.is_none() {
             session.token = session.negotiate_token(name)?;
@@ -142,11 +179,13 @@ impl RegistrySession {
     /// Probes `/v2/` and performs the bearer challenge dance if needed.
     fn negotiate_token(&self, name: &OciName) -> Result<Option<String>> {
         let url = format!("{}/v2/", self.base);
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .map_err(|error| network_error(&url, format!("probe failed: {error}")))?;
+        let response = self.client.get(&url).send().map_err(|error| {
+            network_error(
+                &url,
+                format!("probe failed: {error}"),
+                request_is_transient(&error),
+            )
+        })?;
         if response.status() != reqwest::StatusCode::UNAUTHORIZED {
             return Ok(None);
         }
@@ -156,7 +195,11 @@ impl RegistrySession {
             .and_then(|value| value.to_str().ok())
             .and_then(auth::parse_bearer_challenge)
             .ok_or_else(|| {
-                network_error(&url, "registry requires unsupported authentication".into())
+                network_error(
+                    &url,
+                    "registry requires unsupported authentication".into(),
+                    false,
+                )
             })?;
         let credentials = auth::basic_credentials(&name.registry);
         let token = auth::fetch_bearer_token(
@@ -173,14 +216,19 @@ impl RegistrySession {
         if let Some(token) = &self.token {
             request = request.bearer_auth(token);
         }
-        let response = request
-            .send()
-            .map_err(|error| network_error(url, format!("request failed: {error}")))?;
+        let response = request.send().map_err(|error| {
+            network_error(
+                url,
+                format!("request failed: {error}"),
+                request_is_transient(&error),
+            )
+        })?;
         let status = response.status();
         if !status.is_success() {
             return Err(network_error(
                 url,
                 format!("registry returned status {status}; check the image name and credentials"),
+                is_transient_status(status.as_u16()),
             ));
         }
         Ok(response)
@@ -192,28 +240,22 @@ impl RegistrySession {
             "{}/v2/{}/manifests/{manifest_part}",
             self.base, self.repository
         );
-        let response = self.get(&url, MANIFEST_ACCEPT)?;
-        let mut body = Vec::new();
-        let read = response
-            .take(MANIFEST_MAX_BYTES.saturating_add(1))
-            .read_to_end(&mut body)
-            .map_err(|error| network_error(&url, format!("stream interrupted: {error}")))?;
-        if read as u64 > MANIFEST_MAX_BYTES {
-            return Err(network_error(
-                &url,
-                format!("manifest exceeds the {MANIFEST_MAX_BYTES} byte limit"),
-            ));
-        }
+        let body = retry(&self.policy.retry, |_| {
+            read_capped(self.get(&url, MANIFEST_ACCEPT)?, &url, "manifest")
+        })?;
         let digest = Sha256::digest(&body);
         let digest = Sha256Hash::from_hex(format!("{digest:x}"))?;
         Ok((body, digest))
     }
 
-    /// Resolves the ordered layer descriptors, descending through a
-    /// platform index when necessary.
-    fn resolve_layer_descriptors(&self, body: &[u8]) -> Result<Vec<Descriptor>> {
+    /// Resolves the config descriptor and ordered layer descriptors,
+    /// descending through a platform index when necessary.
+    fn resolve_image_descriptors(
+        &self,
+        body: &[u8],
+    ) -> Result<(Option<Descriptor>, Vec<Descriptor>)> {
         match parse_manifest(body)? {
-            Manifest::Image(layers) => Ok(layers),
+            Manifest::Image { config, layers } => Ok((config, layers)),
             Manifest::Index(entries) => {
                 let selected = select_platform(&entries, host_oci_architecture())?;
                 let expected = descriptor_sha256(&selected.digest)?;
@@ -226,7 +268,7 @@ impl RegistrySession {
                     });
                 }
                 match parse_manifest(&sub_body)? {
-                    Manifest::Image(layers) => Ok(layers),
+                    Manifest::Image { config, layers } => Ok((config, layers)),
                     Manifest::Index(_) => Err(ContainustError::Config {
                         message: "registry returned a nested image index; \
                                   nested indexes are not supported"
@@ -237,6 +279,31 @@ impl RegistrySession {
         }
     }
 
+    /// Fetches and verifies the image config blob, returning its runtime defaults.
+    fn fetch_config(&self, descriptor: &Descriptor) -> Result<ImageConfig> {
+        let expected = descriptor_sha256(&descriptor.digest)?;
+        let url = format!(
+            "{}/v2/{}/blobs/{}",
+            self.base, self.repository, descriptor.digest
+        );
+        let body = retry(&self.policy.retry, |_| {
+            read_capped(
+                self.get(&url, "application/octet-stream")?,
+                &url,
+                "image config",
+            )
+        })?;
+        let actual = format!("{:x}", Sha256::digest(&body));
+        if actual != expected.as_hex() {
+            return Err(ContainustError::HashMismatch {
+                resource: url,
+                expected: expected.as_hex().to_string(),
+                actual,
+            });
+        }
+        parse_image_config(&body)
+    }
+
     /// Downloads one layer blob to a staging path and verifies it.
     fn download_layer(&self, store: &StorageBackend, descriptor: &Descriptor) -> Result<LayerBlob> {
         let expected = descriptor_sha256(&descriptor.digest)?;
@@ -244,9 +311,13 @@ impl RegistrySession {
             "{}/v2/{}/blobs/{}",
             self.base, self.repository, descriptor.digest
         );
-        let response = self.get(&url, "application/octet-stream")?;
         let staged = store.staging_path();
-        let actual = copy_capped(response, &staged, self.max_blob_bytes, &url)?;
+        let actual = retry(&self.policy.retry, |attempt| {
+            let response = self.get(&url, "application/octet-stream")?;
+            copy_capped(response, &staged, &self.policy, &url).inspect_err(|error| {
+                tracing::warn!(url, attempt, %error, "layer download attempt failed");
+            })
+        })?;
         if actual.as_hex() != expected.as_hex() {
             let _ = std::fs::remove_file(&staged);
             return Err(ContainustError::HashMismatch {
@@ -269,10 +340,34 @@ impl RegistrySession {
     }
 }
 
-fn network_error(url: &str, message: String) -> ContainustError {
+/// Reads a small JSON document, rejecting bodies over `MANIFEST_MAX_BYTES`.
+fn read_capped(response: reqwest::blocking::Response, url: &str, what: &str) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    let read = response
+        .take(MANIFEST_MAX_BYTES.saturating_add(1))
+        .read_to_end(&mut body)
+        .map_err(|error| {
+            network_error(
+                url,
+                format!("stream interrupted: {error}"),
+                read_is_transient(&error),
+            )
+        })?;
+    if read as u64 > MANIFEST_MAX_BYTES {
+        return Err(network_error(
+            url,
+            format!("{what} exceeds the {MANIFEST_MAX_BYTES} byte limit"),
+            false,
+        ));
+    }
+    Ok(body)
+}
+
+fn network_error(url: &str, message: String, retryable: bool) -> ContainustError {
     ContainustError::Network {
         url: url.to_string(),
         message,
+        retryable,
     }
 }
 
@@ -315,4 +410,40 @@ mod tests {
         let error = verify_pin(&reference, &actual).expect_err("mismatch must fail");
         assert!(matches!(error, ContainustError::HashMismatch { .. }));
     }
+
+    #[test]
+    fn cancelled_pull_deletes_layers_already_staged() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let descriptor = |n: u8| Descriptor {
+            media_type: "application/vnd.oci.image.layer.v1.tar+gzip".into(),
+            digest: format!("sha256:{}", n.to_string().repeat(64)),
+            size: 1,
+            platform: None,
+        };
+        let descriptors = [descriptor(1), descriptor(2), descriptor(3)];
+        let cancel = CancellationToken::new();
+        let mut downloaded = 0;
+
+        let error = stage_layers(&descriptors, &cancel, |_| {
+            downloaded += 1;
+            let path = dir.path().join(format!("layer-{downloaded}"));
+            std::fs::write(&path, b"blob").expect("stage");
+            // Ctrl+C arrives while the second layer is downloading.
+            if downloaded == 2 {
+                cancel.cancel();
+            }
+            Ok(LayerBlob {
+                path,
+                digest: Sha256Hash::from_hex("0".repeat(64)).expect("hash"),
+                size: 4,
+            })
+        })
+        .expect_err("cancelled");
+
+        assert!(
+            matches!(error, ContainustError::Cancelled { ref operation } if operation == "pull")
+        );
+        assert_eq!(downloaded, 2);
+        assert_eq!(std::fs::read_dir(dir.path()).expect("read dir").count(), 0);
+    }
 }
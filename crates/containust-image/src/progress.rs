@@ -0,0 +1,139 @@
+//! Import progress reporting.
+//!
+//! [`import_image_with_progress`](crate::import::import_image_with_progress)
+//! reports each step it completes — resolving the source, downloading,
+//! packing, hashing, committing each layer, registering in the catalog —
+//! to an [`ImportProgress`] observer together with its wall-clock time.
+
+use std::time::{Duration, Instant};
+
+use containust_common::error::Result;
+use serde::{Deserialize, Serialize};
+
+/// A timed phase of an image import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStep {
+    /// Locating the source (path check, preset lookup, cache probe).
+    Resolve,
+    /// Fetching remote bytes (HTTP, preset download, registry pull).
+    Download,
+    /// Packing a directory or staging a local archive.
+    Pack,
+    /// Computing or verifying a content digest.
+    Hash,
+    /// Moving one layer blob into the content-addressed store.
+    Commit,
+    /// Recording the image in the local catalog.
+    Register,
+}
+
+impl ImportStep {
+    /// Stable lowercase name used in human and JSON output.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Resolve => "resolve",
+            Self::Download => "download",
+            Self::Pack => "pack",
+            Self::Hash => "hash",
+            Self::Commit => "commit",
+            Self::Register => "register",
+        }
+    }
+}
+
+/// Duration of one completed import step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepTiming {
+    /// Which phase ran.
+    pub step: ImportStep,
+    /// Extra context, such as the layer digest for [`ImportStep::Commit`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Wall-clock duration in milliseconds.
+    pub duration_ms: u64,
+}
+
+impl StepTiming {
+    /// Creates a timing entry, saturating durations that overflow `u64` ms.
+    #[must_use]
+    pub fn new(step: ImportStep, detail: Option<String>, duration: Duration) -> Self {
+        Self {
+            step,
+            detail,
+            duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+/// Observer notified as import steps complete.
+pub trait ImportProgress {
+    /// Called once per finished step, in execution order.
+    fn step_finished(&self, timing: &StepTiming);
+}
+
+/// [`ImportProgress`] that discards every notification.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ImportProgress for NoProgress {
+    fn step_finished(&self, _timing: &StepTiming) {}
+}
+
+/// Runs `work`, reporting its duration as `step` when it succeeds.
+pub(crate) fn timed<T>(
+    progress: &dyn ImportProgress,
+    step: ImportStep,
+    detail: Option<&str>,
+    work: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let started = Instant::now();
+    let value = work()?;
+    progress.step_finished(&StepTiming::new(
+        step,
+        detail.map(str::to_string),
+        started.elapsed(),
+    ));
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Collect(RefCell<Vec<StepTiming>>);
+
+    impl ImportProgress for Collect {
+        fn step_finished(&self, timing: &StepTiming) {
+            self.0.borrow_mut().push(timing.clone());
+        }
+    }
+
+    #[test]
+    fn step_timing_serializes_snake_case_without_empty_detail() {
+        let timing = StepTiming::new(ImportStep::Download, None, Duration::from_millis(1500));
+        let json = serde_json::to_string(&timing).unwrap();
+        assert_eq!(json, r#"{"step":"download","duration_ms":1500}"#);
+    }
+
+    #[test]
+    fn timed_reports_successful_steps_only() {
+        let progress = Collect::default();
+        let value = timed(&progress, ImportStep::Hash, Some("layer"), || Ok(7)).unwrap();
+        let failed: Result<()> = timed(&progress, ImportStep::Register, None, || {
+            Err(containust_common::error::ContainustError::Config {
+                message: "boom".into(),
+            })
+        });
+        assert_eq!(value, 7);
+        assert!(failed.is_err());
+        let steps = progress.0.into_inner();
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].step, ImportStep::Hash);
+        assert_eq!(steps[0].detail.as_deref(), Some("layer"));
+    }
+}
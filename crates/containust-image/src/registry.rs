@@ -6,16 +6,18 @@
 //! are deduplicated by name and every referenced layer must exist in
 //! the content-addressed store before an entry is accepted.
 
+use std::collections::BTreeSet;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use containust_common::error::{ContainustError, Result};
+use containust_common::retry::{RetryPolicy, retry};
 use containust_common::types::ImageId;
 use fs2::FileExt;
 use serde::{Deserialize, Serialize};
 
-use crate::storage::StorageBackend;
+use crate::storage::{LayerGcReport, StorageBackend};
 
 static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
 
@@ -40,6 +42,12 @@ pub struct ImageEntry {
     /// Version of the tool that imported this image.
     #[serde(default)]
     pub tool_version: String,
+    /// Default entrypoint declared by the image config, when known.
+    #[serde(default)]
+    pub entrypoint: Option<Vec<String>>,
+    /// Default arguments declared by the image config.
+    #[serde(default)]
+    pub cmd: Vec<String>,
 }
 
 /// Image catalog backed by a locked, atomically written JSON file.
@@ -48,6 +56,7 @@ pub struct ImageCatalog {
     data_dir: PathBuf,
     catalog_path: PathBuf,
     lock_path: PathBuf,
+    read_retry: RetryPolicy,
 }
 
 impl ImageCatalog {
@@ -69,9 +78,17 @@ impl ImageCatalog {
             data_dir: data_dir.to_path_buf(),
             catalog_path,
             lock_path,
+            read_retry: RetryPolicy::default(),
         })
     }
 
+    /// Replaces the backoff applied to transient catalog read failures.
+    #[must_use]
+    pub const fn with_read_retry(mut self, policy: RetryPolicy) -> Self {
+        self.read_retry = policy;
+        self
+    }
+
     /// Lists all images under a shared catalog lock.
     ///
     /// # Errors
@@ -140,6 +157,26 @@ impl ImageCatalog {
         self.write_entries(&entries)
     }
 
+    /// Evicts least recently used layers no catalog image references until
+    /// the layer store fits `max_bytes`.
+    ///
+    /// Holds the catalog lock for the whole pass so no image can be
+    /// registered against a layer while it is being evicted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the catalog cannot be read or a layer cannot
+    /// be removed.
+    pub fn garbage_collect_lru(&self, max_bytes: u64) -> Result<LayerGcReport> {
+        let _guard = self.lock(true)?;
+        let referenced: BTreeSet<String> = self
+            .read_entries()?
+            .into_iter()
+            .flat_map(|entry| entry.layers)
+            .collect();
+        StorageBackend::open(self.data_dir.clone())?.garbage_collect_lru(max_bytes, &referenced)
+    }
+
     fn validate_layers(&self, entry: &ImageEntry) -> Result<()> {
         let store = StorageBackend::open(self.data_dir.clone())?;
         for layer in &entry.layers {
@@ -157,11 +194,12 @@ impl ImageCatalog {
         if !self.catalog_path.exists() {
             return Ok(Vec::new());
         }
-        let content =
+        let content = retry(&self.read_retry, |_| {
             std::fs::read_to_string(&self.catalog_path).map_err(|e| ContainustError::Io {
                 path: self.catalog_path.clone(),
                 source: e,
-            })?;
+            })
+        })?;
         let entries: Vec<ImageEntry> = serde_json::from_str(&content)?;
         Ok(entries)
     }
@@ -291,6 +329,8 @@ mod tests {
             created_at: "2026-01-01T00:00:00Z".into(),
             digest: Some("a".repeat(64)),
             tool_version: "0.4.0".into(),
+            entrypoint: None,
+            cmd: Vec::new(),
         }
     }
 
@@ -317,6 +357,24 @@ mod tests {
         assert_eq!(entries[0].tool_version, "0.4.0");
     }
 
+    #[test]
+    fn catalog_garbage_collect_lru_keeps_referenced_layers() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        store_layer(dir.path(), "in-use");
+        store_layer(dir.path(), "orphan");
+        let catalog = ImageCatalog::open(dir.path()).expect("open failed");
+        catalog
+            .register(make_entry("img-1", "alpine", vec!["in-use".into()]))
+            .expect("register failed");
+
+        let report = catalog.garbage_collect_lru(0).expect("gc");
+
+        assert_eq!(report.evicted, vec!["orphan"]);
+        let store = StorageBackend::open(dir.path().to_path_buf()).expect("open store");
+        assert!(store.has_layer("in-use"));
+        assert!(!store.has_layer("orphan"));
+    }
+
     #[test]
     fn catalog_register_missing_layer_rejected() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -3,9 +3,10 @@
 //! Resolves image URIs into filesystem-checked [`ImageSource`] values.
 //! Supports `file://` (local directory), `tar://` (archive), `image://`
 //! (local catalog), and remote sources. Local-first by design; parsing
-//! itself is delegated to [`crate::reference::ImageReference`].
+//! itself is delegated to [`crate::reference::ImageReference`], and
+//! remote sources are downloaded by [`crate::fetch::fetch_remote`].
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use containust_common::error::{ContainustError, Result};
 
@@ -46,9 +47,17 @@ pub enum ImageSource {
     },
 }
 
+/// Binary directories of which a rootfs must have at least one.
+const ROOTFS_BIN_DIRS: [&str; 2] = ["bin", "usr/bin"];
+
+/// Shells of which a rootfs must have at least one.
+const ROOTFS_SHELLS: [&str; 2] = ["bin/sh", "usr/bin/sh"];
+
 /// Resolves an image source URI into an `ImageSource`.
 ///
-/// Local `file://` and `tar://` paths are checked for existence.
+/// Local `file://` and `tar://` paths are checked for existence, and a
+/// `file://` directory that does not look like a rootfs is warned about
+/// (see [`check_rootfs`]).
 ///
 /// # Errors
 ///
@@ -60,6 +69,7 @@ pub fn resolve_source(uri: &str) -> Result<ImageSource> {
     match reference.scheme() {
         ImageScheme::File => {
             let path = existing_path(reference.location(), "image directory")?;
+            check_rootfs(&path, false)?;
             tracing::info!(path = %path.display(), "resolved file:// source");
             Ok(ImageSource::File(path))
         }
@@ -86,6 +96,59 @@ pub fn resolve_source(uri: &str) -> Result<ImageSource> {
     }
 }
 
+/// Whether `dir` looks like a root filesystem: it has a `bin` or
+/// `usr/bin` directory and an executable `sh` in one of them.
+///
+/// A symlinked shell counts as present without being followed, since
+/// absolute link targets only resolve inside the container.
+#[must_use]
+pub fn looks_like_rootfs(dir: &Path) -> bool {
+    ROOTFS_BIN_DIRS.iter().any(|bin| dir.join(bin).is_dir())
+        && ROOTFS_SHELLS
+            .iter()
+            .any(|shell| is_executable(&dir.join(shell)))
+}
+
+fn is_executable(path: &Path) -> bool {
+    std::fs::symlink_metadata(path)
+        .is_ok_and(|meta| meta.file_type().is_symlink() || (meta.is_file() && has_exec_bit(&meta)))
+}
+
+#[cfg(unix)]
+fn has_exec_bit(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+const fn has_exec_bit(_meta: &std::fs::Metadata) -> bool {
+    true
+}
+
+/// Checks that the `file://` image directory `dir` looks like a rootfs
+/// (see [`looks_like_rootfs`]); paths that are not directories are left
+/// to the existence checks.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] under `strict` when the directory
+/// does not look like a rootfs; otherwise this only logs a warning.
+pub fn check_rootfs(dir: &Path, strict: bool) -> Result<()> {
+    if !dir.is_dir() || looks_like_rootfs(dir) {
+        return Ok(());
+    }
+    let problem = format!(
+        "{} does not look like a rootfs: no /bin or /usr/bin with an executable sh",
+        dir.display()
+    );
+    if strict {
+        return Err(ContainustError::Config { message: problem });
+    }
+    tracing::warn!("{problem}; the container may fail to start");
+    Ok(())
+}
+
 fn existing_path(location: &str, kind: &'static str) -> Result<PathBuf> {
     let path = PathBuf::from(location);
     if !path.exists() {
@@ -109,6 +172,32 @@ mod tests {
         assert!(matches!(source, ImageSource::File(_)));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn rootfs_heuristic_accepts_a_dir_with_bin_sh() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join("bin")).expect("bin");
+        let shell = dir.path().join("bin/sh");
+        std::fs::write(&shell, "#!").expect("sh");
+        assert!(!looks_like_rootfs(dir.path()), "sh is not executable yet");
+        std::fs::set_permissions(&shell, std::fs::Permissions::from_mode(0o755)).expect("chmod");
+        assert!(looks_like_rootfs(dir.path()));
+        check_rootfs(dir.path(), true).expect("strict check passes");
+    }
+
+    #[test]
+    fn rootfs_heuristic_rejects_an_empty_dir() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert!(!looks_like_rootfs(dir.path()));
+        check_rootfs(dir.path(), false).expect("lenient check only warns");
+        let error = check_rootfs(dir.path(), true)
+            .expect_err("strict check fails")
+            .to_string();
+        assert!(error.contains("does not look like a rootfs"), "{error}");
+    }
+
     #[test]
     fn resolve_tar_source_existing_file_returns_tar() {
         let dir = tempfile::tempdir().expect("failed to create tempdir");
@@ -4,15 +4,55 @@
 //! under the configured data directory. Layer blobs are staged in a
 //! temporary file and committed with an atomic rename so interrupted
 //! writes never produce a partially written, addressable layer.
+//!
+//! A small access index (`layers/index.json`) records when each layer was
+//! last committed or materialized, so [`StorageBackend::garbage_collect_lru`]
+//! can bound disk usage by evicting the least recently used unreferenced
+//! layers first. The index is advisory: a missing or corrupt index falls
+//! back to each blob's modification time.
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 
+use chrono::{DateTime, Utc};
 use containust_common::error::{ContainustError, Result};
+use serde::{Deserialize, Serialize};
 
 static STAGING_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 const LAYER_BLOB_NAME: &str = "layer.tar";
+const LAYER_EXTRACTED_NAME: &str = "rootfs";
+const LAYER_INDEX_NAME: &str = "index.json";
+
+/// Last-access record of one stored layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct LayerAccess {
+    last_access: DateTime<Utc>,
+}
+
+/// A stored layer considered by LRU garbage collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerUsage {
+    /// Content hash of the layer.
+    pub hash: String,
+    /// Size of the layer blob in bytes.
+    pub size_bytes: u64,
+    /// Last time the layer was committed or materialized.
+    pub last_access: DateTime<Utc>,
+}
+
+/// Result of an LRU garbage collection pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayerGcReport {
+    /// Hashes of evicted layers, least recently used first.
+    pub evicted: Vec<String>,
+    /// Bytes reclaimed by the evictions.
+    pub freed_bytes: u64,
+    /// Bytes still stored; may exceed the cap when referenced layers alone
+    /// are larger.
+    pub remaining_bytes: u64,
+}
 
 /// Manages local storage of images and layers.
 #[derive(Debug)]
@@ -50,6 +90,13 @@ impl StorageBackend {
         self.layer_path(hash).join(LAYER_BLOB_NAME)
     }
 
+    /// Returns the directory holding a layer's extracted contents, used as
+    /// an overlay lower directory.
+    #[must_use]
+    pub fn layer_extracted_path(&self, hash: &str) -> PathBuf {
+        self.layer_path(hash).join(LAYER_EXTRACTED_NAME)
+    }
+
     /// Checks whether a layer blob exists in the local cache.
     #[must_use]
     pub fn has_layer(&self, hash: &str) -> bool {
@@ -83,6 +130,7 @@ impl StorageBackend {
                 path: staged.to_path_buf(),
                 source,
             })?;
+            self.touch_layer(hash);
             return Ok(());
         }
         let layer_dir = self.layer_path(hash);
@@ -93,9 +141,115 @@ impl StorageBackend {
         let blob = self.layer_blob_path(hash);
         std::fs::rename(staged, &blob)
             .map_err(|source| ContainustError::Io { path: blob, source })?;
+        self.touch_layer(hash);
         Ok(())
     }
 
+    /// Records that `hash` was just used (best effort).
+    ///
+    /// Called on commit and whenever a layer is materialized so that LRU
+    /// garbage collection keeps recently used layers longest.
+    pub fn touch_layer(&self, hash: &str) {
+        self.record_access(hash, Utc::now());
+    }
+
+    /// Lists every stored layer with its size and last access time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layer directory cannot be read.
+    pub fn layer_usage(&self) -> Result<Vec<LayerUsage>> {
+        let layers = self.root.join("layers");
+        let entries = std::fs::read_dir(&layers).map_err(|source| ContainustError::Io {
+            path: layers.clone(),
+            source,
+        })?;
+        let index = self.read_index();
+        let mut usage = Vec::new();
+        for entry in entries.flatten() {
+            let hash = entry.file_name().to_string_lossy().into_owned();
+            let Ok(metadata) = std::fs::metadata(self.layer_blob_path(&hash)) else {
+                continue;
+            };
+            let last_access = index.get(&hash).map_or_else(
+                || {
+                    metadata
+                        .modified()
+                        .map_or(DateTime::UNIX_EPOCH, DateTime::from)
+                },
+                |access| access.last_access,
+            );
+            usage.push(LayerUsage {
+                hash,
+                size_bytes: metadata.len(),
+                last_access,
+            });
+        }
+        Ok(usage)
+    }
+
+    /// Evicts least recently used layers until the store fits `max_bytes`.
+    ///
+    /// Layers in `referenced` are never evicted, even if the cap cannot be
+    /// reached without them. Use
+    /// [`ImageCatalog::garbage_collect_lru`](crate::registry::ImageCatalog::garbage_collect_lru)
+    /// to derive `referenced` from the catalog.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layers cannot be listed or a layer
+    /// directory cannot be removed.
+    pub fn garbage_collect_lru(
+        &self,
+        max_bytes: u64,
+        referenced: &BTreeSet<String>,
+    ) -> Result<LayerGcReport> {
+        let usage = self.layer_usage()?;
+        let report = plan_lru_eviction(&usage, max_bytes, referenced);
+        for hash in &report.evicted {
+            let path = self.layer_path(hash);
+            std::fs::remove_dir_all(&path)
+                .map_err(|source| ContainustError::Io { path, source })?;
+            tracing::info!(layer = %hash, "evicted least recently used layer");
+        }
+        let mut index = self.read_index();
+        for hash in &report.evicted {
+            let _ = index.remove(hash);
+        }
+        self.write_index(&index);
+        Ok(report)
+    }
+
+    fn record_access(&self, hash: &str, at: DateTime<Utc>) {
+        let mut index = self.read_index();
+        let _ = index.insert(hash.to_string(), LayerAccess { last_access: at });
+        self.write_index(&index);
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.root.join("layers").join(LAYER_INDEX_NAME)
+    }
+
+    fn read_index(&self) -> BTreeMap<String, LayerAccess> {
+        std::fs::read(self.index_path())
+            .ok()
+            .and_then(|raw| serde_json::from_slice(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    fn write_index(&self, index: &BTreeMap<String, LayerAccess>) {
+        let path = self.index_path();
+        let temp = self.staging_path();
+        let written = serde_json::to_vec(index)
+            .map_err(std::io::Error::other)
+            .and_then(|body| std::fs::write(&temp, body))
+            .and_then(|()| std::fs::rename(&temp, &path));
+        if let Err(error) = written {
+            let _ = std::fs::remove_file(&temp);
+            tracing::debug!(path = %path.display(), %error, "failed to update layer index");
+        }
+    }
+
     /// Returns the root storage path.
     #[must_use]
     pub fn root(&self) -> &Path {
@@ -103,10 +257,64 @@ impl StorageBackend {
     }
 }
 
+/// Chooses the layers to evict so that the total fits `max_bytes`.
+///
+/// Unreferenced layers are taken oldest first (ties broken by hash for a
+/// deterministic plan) and only until the cap is met.
+#[must_use]
+pub fn plan_lru_eviction(
+    usage: &[LayerUsage],
+    max_bytes: u64,
+    referenced: &BTreeSet<String>,
+) -> LayerGcReport {
+    let mut remaining_bytes = usage
+        .iter()
+        .fold(0_u64, |total, layer| total.saturating_add(layer.size_bytes));
+    let mut candidates: Vec<&LayerUsage> = usage
+        .iter()
+        .filter(|layer| !referenced.contains(&layer.hash))
+        .collect();
+    candidates.sort_by(|a, b| {
+        a.last_access
+            .cmp(&b.last_access)
+            .then_with(|| a.hash.cmp(&b.hash))
+    });
+    let mut report = LayerGcReport::default();
+    for layer in candidates {
+        if remaining_bytes <= max_bytes {
+            break;
+        }
+        remaining_bytes = remaining_bytes.saturating_sub(layer.size_bytes);
+        report.freed_bytes = report.freed_bytes.saturating_add(layer.size_bytes);
+        report.evicted.push(layer.hash.clone());
+    }
+    report.remaining_bytes = remaining_bytes;
+    report
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).expect("timestamp")
+    }
+
+    fn usage(hash: &str, size_bytes: u64, secs: i64) -> LayerUsage {
+        LayerUsage {
+            hash: hash.into(),
+            size_bytes,
+            last_access: at(secs),
+        }
+    }
+
+    fn store_layer(storage: &StorageBackend, hash: &str, size: usize, secs: i64) {
+        let staged = storage.staging_path();
+        std::fs::write(&staged, vec![0_u8; size]).expect("write staged");
+        storage.commit_layer(&staged, hash).expect("commit");
+        storage.record_access(hash, at(secs));
+    }
+
     #[test]
     fn storage_open_returns_correct_root() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -167,4 +375,67 @@ mod tests {
         let storage = StorageBackend::open(dir.path().to_path_buf()).expect("open");
         assert_ne!(storage.staging_path(), storage.staging_path());
     }
+
+    #[test]
+    fn plan_lru_eviction_takes_oldest_first() {
+        let layers = [
+            usage("new", 10, 300),
+            usage("old", 10, 100),
+            usage("mid", 10, 200),
+        ];
+        let report = plan_lru_eviction(&layers, 10, &BTreeSet::new());
+        assert_eq!(report.evicted, vec!["old", "mid"]);
+        assert_eq!(report.freed_bytes, 20);
+        assert_eq!(report.remaining_bytes, 10);
+    }
+
+    #[test]
+    fn plan_lru_eviction_stops_once_under_cap() {
+        let layers = [usage("a", 40, 1), usage("b", 30, 2), usage("c", 30, 3)];
+        let report = plan_lru_eviction(&layers, 60, &BTreeSet::new());
+        assert_eq!(report.evicted, vec!["a"]);
+        assert_eq!(report.remaining_bytes, 60);
+
+        let untouched = plan_lru_eviction(&layers, 100, &BTreeSet::new());
+        assert!(untouched.evicted.is_empty());
+    }
+
+    #[test]
+    fn plan_lru_eviction_never_takes_referenced_layers() {
+        let layers = [usage("pinned", 50, 1), usage("loose", 50, 2)];
+        let referenced = BTreeSet::from(["pinned".to_string()]);
+        let report = plan_lru_eviction(&layers, 0, &referenced);
+        assert_eq!(report.evicted, vec!["loose"]);
+        assert_eq!(report.remaining_bytes, 50);
+    }
+
+    #[test]
+    fn storage_garbage_collect_lru_evicts_from_disk() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let storage = StorageBackend::open(dir.path().to_path_buf()).expect("open");
+        store_layer(&storage, "old", 8, 100);
+        store_layer(&storage, "used", 8, 300);
+        store_layer(&storage, "kept", 8, 50);
+        storage.record_access("old", at(200));
+
+        let referenced = BTreeSet::from(["kept".to_string()]);
+        let report = storage.garbage_collect_lru(16, &referenced).expect("gc");
+
+        assert_eq!(report.evicted, vec!["old"]);
+        assert!(!storage.has_layer("old"));
+        assert!(storage.has_layer("used"));
+        assert!(storage.has_layer("kept"));
+        assert_eq!(storage.layer_usage().expect("usage").len(), 2);
+        assert!(!storage.read_index().contains_key("old"));
+    }
+
+    #[test]
+    fn storage_commit_records_layer_access() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let storage = StorageBackend::open(dir.path().to_path_buf()).expect("open");
+        let staged = storage.staging_path();
+        std::fs::write(&staged, b"bytes").expect("write staged");
+        storage.commit_layer(&staged, "fresh").expect("commit");
+        assert!(storage.read_index().contains_key("fresh"));
+    }
 }
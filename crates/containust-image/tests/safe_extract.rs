@@ -4,7 +4,8 @@
 
 use std::path::Path;
 
-use containust_image::extract::safe_extract_archive;
+use containust_image::extract::{safe_extract_archive, safe_extract_reader};
+use containust_image::layer::extract_layer;
 
 fn write_tar(path: &Path, entries: &[(&str, &[u8])]) {
     let file = std::fs::File::create(path).expect("create tar");
@@ -147,3 +148,154 @@ fn safe_extract_rejects_chained_symlink_escape() {
         "failed extract must wipe the target so no escape chain remains"
     );
 }
+
+#[test]
+fn safe_extract_reader_streams_gzip_archive() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let archive = dir.path().join("ok.tar");
+    write_tar(&archive, &[("etc/motd", b"hi")]);
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    std::io::Write::write_all(&mut encoder, &std::fs::read(&archive).expect("read"))
+        .expect("compress");
+    let gz = encoder.finish().expect("finish gzip");
+    let target = dir.path().join("out");
+    safe_extract_reader(gz.as_slice(), &target).expect("extract stream");
+    assert_eq!(std::fs::read(target.join("etc/motd")).expect("read"), b"hi");
+}
+
+#[test]
+fn safe_extract_reader_rejects_traversal_without_writing_outside() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let archive = dir.path().join("evil.tar");
+    write_raw_named_tar(&archive, "../escape", b"pwned");
+    let bytes = std::fs::read(&archive).expect("read");
+    let target = dir.path().join("nested").join("out");
+    let error = safe_extract_reader(bytes.as_slice(), &target).expect_err("must reject");
+    assert!(error.to_string().contains("path traversal"), "{error}");
+    assert!(!dir.path().join("nested").join("escape").exists());
+    assert!(!target.exists());
+}
+
+#[test]
+fn extract_layer_refuses_traversal_and_absolute_entries() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let outside = dir.path().join("host");
+    std::fs::create_dir_all(&outside).expect("host dir");
+    for (name, entry) in [
+        ("dotdot.tar", "../host/escape"),
+        ("absolute.tar", "/tmp/containust-absolute-escape"),
+    ] {
+        let archive = dir.path().join(name);
+        write_raw_named_tar(&archive, entry, b"pwned");
+        let target = dir.path().join("layer");
+        let error = extract_layer(&archive, &target).expect_err("must reject");
+        assert!(
+            error.to_string().contains("unsafe archive entry"),
+            "{error}"
+        );
+        assert!(!target.exists());
+    }
+    assert!(!outside.join("escape").exists());
+    assert!(!Path::new("/tmp/containust-absolute-escape").exists());
+}
+
+/// Appends a raw symlink header, bypassing the builder's target checks.
+#[cfg(unix)]
+fn append_symlink(builder: &mut tar::Builder<std::fs::File>, name: &str, target: &str) {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Symlink);
+    header.set_size(0);
+    header.set_mode(0o777);
+    header.set_link_name(target).expect("link name");
+    header.set_cksum();
+    builder
+        .append_data(&mut header, name, std::io::empty())
+        .expect("append symlink");
+}
+
+#[cfg(unix)]
+fn append_file(builder: &mut tar::Builder<std::fs::File>, name: &str, data: &[u8]) {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, name, data)
+        .expect("append file");
+}
+
+#[cfg(unix)]
+#[test]
+fn safe_extract_blocks_symlink_then_write_attack() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let host = dir.path().join("host");
+    std::fs::create_dir_all(host.join("etc")).expect("host etc");
+    std::fs::write(host.join("etc/passwd"), "root:x:0:0").expect("host passwd");
+    let host_target = host.to_str().expect("utf-8 path").to_string();
+
+    for (name, link_target) in [("abs.tar", host_target.as_str()), ("rel.tar", "../host")] {
+        let archive = dir.path().join(name);
+        let mut builder = tar::Builder::new(std::fs::File::create(&archive).expect("create"));
+        append_symlink(&mut builder, "link", link_target);
+        append_file(&mut builder, "link/etc/passwd", b"pwned");
+        builder.finish().expect("finish");
+
+        let target = dir.path().join("out");
+        let error = safe_extract_archive(&archive, &target).expect_err("must reject");
+        assert!(
+            error.to_string().contains("unsafe archive entry"),
+            "{error}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(host.join("etc/passwd")).expect("read"),
+            "root:x:0:0"
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn safe_extract_refuses_to_write_through_preexisting_escape_symlink() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let host = dir.path().join("host");
+    std::fs::create_dir_all(&host).expect("host dir");
+    std::fs::write(host.join("passwd"), "original").expect("host file");
+    let target = dir.path().join("rootfs");
+    std::fs::create_dir_all(&target).expect("rootfs");
+    std::os::unix::fs::symlink(&host, target.join("link")).expect("planted link");
+
+    let archive = dir.path().join("layer.tar");
+    write_tar(&archive, &[("link/passwd", b"pwned")]);
+    let error = safe_extract_archive(&archive, &target).expect_err("must reject");
+    assert!(
+        error.to_string().contains("unsafe archive entry"),
+        "{error}"
+    );
+    assert_eq!(
+        std::fs::read_to_string(host.join("passwd")).expect("read"),
+        "original"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn safe_extract_file_entry_replaces_symlink_instead_of_following_it() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let archive = dir.path().join("replace.tar");
+    let mut builder = tar::Builder::new(std::fs::File::create(&archive).expect("create"));
+    append_file(&mut builder, "real", b"keep");
+    append_symlink(&mut builder, "alias", "real");
+    append_file(&mut builder, "alias", b"new");
+    builder.finish().expect("finish");
+
+    let target = dir.path().join("out");
+    safe_extract_archive(&archive, &target).expect("extract");
+    assert_eq!(std::fs::read(target.join("real")).expect("read"), b"keep");
+    assert_eq!(std::fs::read(target.join("alias")).expect("read"), b"new");
+    assert!(
+        !std::fs::symlink_metadata(target.join("alias"))
+            .expect("metadata")
+            .file_type()
+            .is_symlink()
+    );
+}
@@ -0,0 +1,269 @@
+//! Attaching to the stdio of a container's main process.
+//!
+//! Backends expose the live stream as an [`AttachStream`]; [`pump`]
+//! forwards its output to the caller and the caller's input to it until
+//! the caller detaches or the process exits. On Linux the main process
+//! reads stdin from a FIFO created at start and writes to its log file.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use containust_common::cancel::CancellationToken;
+use containust_common::error::{ContainustError, Result};
+
+/// Delay between polls of an idle stream.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Live stdio of a container's main process.
+pub trait AttachStream: Send {
+    /// Returns output produced since the previous call; empty when idle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the output source cannot be read.
+    fn read_output(&mut self) -> Result<Vec<u8>>;
+
+    /// Sends `data` to the process's stdin.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if stdin is not wired or the write fails.
+    fn write_input(&mut self, data: &[u8]) -> Result<()>;
+
+    /// Whether the main process is still running.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process state cannot be determined.
+    fn is_running(&mut self) -> Result<bool>;
+}
+
+/// Why [`pump`] returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachEnd {
+    /// The caller cancelled its detach token.
+    Detached,
+    /// The main process exited; its remaining output was forwarded.
+    Exited,
+}
+
+/// Forwards `stream` output to `output` and chunks from `input` to the
+/// stream until `detach` is cancelled or the process exits, polling an
+/// idle stream every [`POLL_INTERVAL`].
+///
+/// A disconnected `input` (stdin closed) leaves the session read-only.
+///
+/// # Errors
+///
+/// Returns an error if the stream fails or `output` cannot be written.
+pub fn pump(
+    stream: &mut dyn AttachStream,
+    output: &mut dyn Write,
+    input: Option<&Receiver<Vec<u8>>>,
+    detach: &CancellationToken,
+) -> Result<AttachEnd> {
+    while !detach.is_cancelled() {
+        for chunk in input.into_iter().flat_map(Receiver::try_iter) {
+            stream.write_input(&chunk)?;
+        }
+        let data = stream.read_output()?;
+        if !data.is_empty() {
+            forward(output, &data)?;
+            continue;
+        }
+        if !stream.is_running()? {
+            // Output written between the last read and the exit.
+            forward(output, &stream.read_output()?)?;
+            return Ok(AttachEnd::Exited);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    Ok(AttachEnd::Detached)
+}
+
+fn forward(output: &mut dyn Write, data: &[u8]) -> Result<()> {
+    output
+        .write_all(data)
+        .and_then(|()| output.flush())
+        .map_err(|source| ContainustError::Io {
+            path: PathBuf::from("<attach output>"),
+            source,
+        })
+}
+
+/// Returns the stdin FIFO path of a container.
+#[must_use]
+pub fn stdin_path(data_dir: &Path, container_id: &str) -> PathBuf {
+    data_dir.join("stdin").join(format!("{container_id}.fifo"))
+}
+
+/// Creates (or recreates) the stdin FIFO at `path`, readable by the
+/// owner only.
+///
+/// # Errors
+///
+/// Returns an error if the directory or FIFO cannot be created.
+#[cfg(unix)]
+pub fn create_stdin_fifo(path: &Path) -> Result<()> {
+    let io_error = |source| ContainustError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    match std::fs::remove_file(path) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => return Err(io_error(error)),
+        _ => {}
+    }
+    nix::unistd::mkfifo(
+        path,
+        nix::sys::stat::Mode::S_IRUSR | nix::sys::stat::Mode::S_IWUSR,
+    )
+    .map_err(|errno| io_error(std::io::Error::from(errno)))
+}
+
+/// Opens the writing end of a container's stdin FIFO.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] when the container was started
+/// without a stdin FIFO, or an I/O error if it cannot be opened.
+pub fn open_stdin_writer(path: &Path, container_id: &str) -> Result<std::fs::File> {
+    if !path.exists() {
+        return Err(ContainustError::Config {
+            message: format!(
+                "container {container_id} was started without attachable stdin; \
+                 set `stdin = true` on its component and restart it, or attach \
+                 with --no-stdin"
+            ),
+        });
+    }
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .map_err(|source| ContainustError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::mpsc;
+
+    use super::*;
+
+    /// Scripted stdio source: emits queued chunks, then reports exit.
+    #[derive(Default)]
+    struct MockStream {
+        chunks: VecDeque<Vec<u8>>,
+        late: Option<Vec<u8>>,
+        input: Vec<u8>,
+        running_polls: usize,
+    }
+
+    impl AttachStream for MockStream {
+        fn read_output(&mut self) -> Result<Vec<u8>> {
+            Ok(self.chunks.pop_front().unwrap_or_default())
+        }
+
+        fn write_input(&mut self, data: &[u8]) -> Result<()> {
+            self.input.extend_from_slice(data);
+            Ok(())
+        }
+
+        fn is_running(&mut self) -> Result<bool> {
+            if self.running_polls == 0 {
+                self.chunks.extend(self.late.take());
+                return Ok(false);
+            }
+            self.running_polls -= 1;
+            Ok(true)
+        }
+    }
+
+    #[test]
+    fn pump_forwards_output_until_exit() {
+        let mut stream = MockStream {
+            chunks: VecDeque::from([b"hello ".to_vec(), b"world\n".to_vec()]),
+            late: Some(b"bye\n".to_vec()),
+            running_polls: 2,
+            ..MockStream::default()
+        };
+        let mut output = Vec::new();
+        let end = pump(&mut stream, &mut output, None, &CancellationToken::new()).unwrap();
+        assert_eq!(end, AttachEnd::Exited);
+        assert_eq!(output, b"hello world\nbye\n");
+    }
+
+    #[test]
+    fn pump_forwards_input_and_survives_closed_stdin() {
+        let (sender, receiver) = mpsc::channel();
+        sender.send(b"ls\n".to_vec()).unwrap();
+        sender.send(b"exit\n".to_vec()).unwrap();
+        drop(sender);
+        let mut stream = MockStream {
+            running_polls: 1,
+            ..MockStream::default()
+        };
+        let mut output = Vec::new();
+        let end = pump(
+            &mut stream,
+            &mut output,
+            Some(&receiver),
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert_eq!(end, AttachEnd::Exited);
+        assert_eq!(stream.input, b"ls\nexit\n");
+    }
+
+    #[test]
+    fn pump_stops_when_detached() {
+        let mut stream = MockStream {
+            chunks: VecDeque::from([b"never read".to_vec()]),
+            running_polls: usize::MAX,
+            ..MockStream::default()
+        };
+        let mut output = Vec::new();
+        let detach = CancellationToken::new();
+        detach.cancel();
+        let end = pump(&mut stream, &mut output, None, &detach).unwrap();
+        assert_eq!(end, AttachEnd::Detached);
+        assert!(output.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stdin_fifo_carries_input_to_the_reader() {
+        use std::io::Read;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = stdin_path(dir.path(), "c1");
+        create_stdin_fifo(&path).unwrap();
+        // The container holds the FIFO read-write so it never sees EOF.
+        let mut reader = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .unwrap();
+        open_stdin_writer(&path, "c1")
+            .unwrap()
+            .write_all(b"ping\n")
+            .unwrap();
+        let mut buffer = [0_u8; 5];
+        reader.read_exact(&mut buffer).unwrap();
+        assert_eq!(&buffer, b"ping\n");
+    }
+
+    #[test]
+    fn missing_fifo_suggests_read_only_attach() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = open_stdin_writer(&stdin_path(dir.path(), "c1"), "c1").unwrap_err();
+        assert!(error.to_string().contains("--no-stdin"), "{error}");
+    }
+}
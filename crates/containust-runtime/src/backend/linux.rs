@@ -2,16 +2,24 @@
 
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
 
+use chrono::{DateTime, Utc};
+use containust_common::clock::{SharedClock, SystemClock};
 use containust_common::error::{ContainustError, Result};
-use containust_common::types::ContainerId;
+use containust_common::types::{ContainerId, HealthRecord};
 
 use super::{
-    ContainerBackend, ContainerConfig, ContainerInfo, ReconciliationReport, project_identifier,
+    CgroupDetail, ContainerBackend, ContainerConfig, ContainerDetail, ContainerInfo,
+    ReconciliationReport, StopOptions, StopOutcome, project_identifier,
 };
-use crate::exec::ExecOutput;
+use crate::container::record_identity;
+use crate::exec::{ExecOptions, ExecOutput};
+use crate::signal::ContainerSignal;
 use crate::state::StateStore;
 
+mod rootfs;
+
 /// Backend that uses Linux kernel features directly.
 ///
 /// Manages container state on disk and delegates process operations
@@ -20,6 +28,7 @@ pub struct LinuxNativeBackend {
     data_dir: PathBuf,
     state_store: StateStore,
     project_id: String,
+    clock: Mutex<SharedClock>,
 }
 
 impl LinuxNativeBackend {
@@ -40,9 +49,19 @@ impl LinuxNativeBackend {
             data_dir,
             state_store: StateStore::new(state_file),
             project_id,
+            clock: Mutex::new(SystemClock::shared()),
         }
     }
 
+    /// The clock container times are stamped with.
+    fn clock(&self) -> SharedClock {
+        Arc::clone(&self.clock.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        self.clock().now()
+    }
+
     /// Builds the initial persistent record for a freshly created container.
     fn new_state_entry(
         &self,
@@ -50,6 +69,8 @@ impl LinuxNativeBackend {
         config: &ContainerConfig,
         rootfs: &Path,
     ) -> crate::state::StateEntry {
+        let log_path = crate::logs::log_path(&self.data_dir, id.as_str());
+        let health = config.healthcheck.as_ref().map(|_| HealthRecord::default());
         crate::state::StateEntry {
             id: id.clone(),
             name: config.name.clone(),
@@ -60,7 +81,19 @@ impl LinuxNativeBackend {
             env: containust_common::redact::redact_env(&config.env),
             memory_bytes: config.memory_bytes,
             cpu_shares: config.cpu_shares,
+            pids_max: config.pids_max,
+            swap_bytes: config.swap_bytes,
+            memory_low_bytes: config.memory_low_bytes,
+            memory_high_bytes: config.memory_high_bytes,
+            cpu_quota_us: config.cpu_quota_us,
+            cpu_period_us: config.cpu_period_us,
+            cpuset_cpus: config.cpuset_cpus.clone(),
+            cpuset_mems: config.cpuset_mems.clone(),
+            ipc_shmmax_bytes: config.ipc_limits.shmmax_bytes,
+            ipc_shmall_pages: config.ipc_limits.shmall_pages,
+            ipc_msgmni: config.ipc_limits.msgmni,
             readonly_rootfs: config.readonly_rootfs,
+            stdin: config.stdin,
             volumes: config.volumes.clone(),
             rootfs_path: Some(rootfs.to_string_lossy().to_string()),
             ports: config.ports.clone(),
@@ -69,19 +102,22 @@ impl LinuxNativeBackend {
             forwarder_pids: Vec::new(),
             restart: config.restart,
             healthcheck: config.healthcheck.clone(),
-            health: config
-                .healthcheck
-                .as_ref()
-                .map(|_| containust_common::types::HealthRecord::default()),
-            log_path: Some(
-                self.data_dir
-                    .join("logs")
-                    .join(format!("{id}.log"))
-                    .to_string_lossy()
-                    .to_string(),
-            ),
+            health,
+            log_path: Some(log_path.to_string_lossy().into_owned()),
             restart_count: 0,
-            created_at: chrono::Utc::now().to_rfc3339(),
+            restart_limit_reported_at: None,
+            stop_signal: config.stop.signal.map(|signal| signal.name().to_string()),
+            stop_timeout_secs: config.stop.timeout.map(|timeout| timeout.as_secs()),
+            enforce_limits: config.enforce_limits,
+            last_error: None,
+            workdir: config.workdir.clone(),
+            hostname: config.hostname.clone(),
+            user: config.user.clone(),
+            capabilities: config.capabilities.iter().map(|cap| cap.name()).collect(),
+            created_at: self.now().to_rfc3339(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
         }
     }
 }
@@ -97,7 +133,9 @@ impl ContainerBackend for LinuxNativeBackend {
         self
     }
     fn create(&self, config: &ContainerConfig) -> Result<ContainerId> {
+        config.validate()?;
         let id = ContainerId::generate();
+        record_identity(&tracing::Span::current(), Some(&id), None);
         tracing::info!(id = %id, name = %config.name, "creating container (Linux native)");
 
         let store_result = self.state_store.update(|state| {
@@ -112,7 +150,7 @@ impl ContainerBackend for LinuxNativeBackend {
             }
             let _ = crate::volume::validate_volumes(&config.volumes)?;
             config.namespaces.validate_for_spawn()?;
-            validate_resource_limits(config.memory_bytes, config.cpu_shares)?;
+            validate_resource_limits(config)?;
             let rootfs = prepare_rootfs(&self.data_dir, &config.image, &id)?;
             state
                 .containers
@@ -122,6 +160,7 @@ impl ContainerBackend for LinuxNativeBackend {
         let rootfs = match store_result {
             Ok(rootfs) => rootfs,
             Err(error) => {
+                let _ = rootfs::release_overlay(&self.data_dir, id.as_str());
                 let _ = std::fs::remove_dir_all(self.data_dir.join("rootfs").join(id.as_str()));
                 return Err(error);
             }
@@ -141,40 +180,18 @@ impl ContainerBackend for LinuxNativeBackend {
                     kind: "container",
                     id: id.as_str().to_string(),
                 })?;
-            let process_config = self.prepare_process_config(state, idx, id)?;
-            let pid = match crate::process::spawn_container_process(&process_config) {
-                Ok(pid) => pid,
-                Err(error) => {
-                    state.containers[idx].state = containust_common::types::ContainerState::Failed;
-                    state.containers[idx].pid = None;
-                    return Ok(Err(error));
-                }
-            };
-
+            record_current_name(&state.containers[idx].name);
+            let result = self.start_entry(state, idx, id);
             let entry = &mut state.containers[idx];
-            let limits = containust_common::types::ResourceLimits {
-                memory_bytes: entry.memory_bytes,
-                cpu_shares: entry.cpu_shares,
-                io_weight: None,
-            };
-            if let Err(error) = apply_cgroup_limits(&self.project_id, id, pid, &limits) {
-                // Fail closed: tear down the just-spawned process. If kill
-                // fails, keep the PID tracked so the orphan is not lost.
-                entry.state = containust_common::types::ContainerState::Failed;
-                return Ok(Err(fail_closed_after_cgroup_error(entry, pid, error)));
-            }
-            #[cfg(target_os = "linux")]
-            {
-                entry.forwarder_pids = start_entry_forwarders(&self.data_dir, entry, pid)?;
-            }
-            #[cfg(not(target_os = "linux"))]
-            {
-                let _ = pid;
-                entry.forwarder_pids.clear();
+            match &result {
+                Ok(_) => entry.last_error = None,
+                Err(error) if entry.state != containust_common::types::ContainerState::Running => {
+                    entry.state = containust_common::types::ContainerState::Failed;
+                    entry.last_error = Some(error.to_string());
+                }
+                Err(_) => {}
             }
-            entry.state = containust_common::types::ContainerState::Running;
-            entry.pid = Some(pid);
-            Ok(Ok(pid))
+            Ok(result)
         })?;
         let pid = start_result?;
 
@@ -185,14 +202,80 @@ impl ContainerBackend for LinuxNativeBackend {
     fn stop(&self, id: &ContainerId) -> Result<()> {
         tracing::info!(id = %id, "stopping container (Linux native)");
 
-        self.stop_internal(id, false)
+        self.stop_internal(id, false, StopOptions::default())
+            .map(drop)
     }
 
     fn force_stop(&self, id: &ContainerId) -> Result<()> {
-        self.stop_internal(id, true)
+        self.stop_internal(id, true, StopOptions::default())
+            .map(drop)
+    }
+
+    fn stop_with(&self, id: &ContainerId, options: &StopOptions) -> Result<StopOutcome> {
+        let escalated = self.stop_internal(id, false, *options)?;
+        Ok(if escalated {
+            StopOutcome::Escalated
+        } else {
+            StopOutcome::Graceful
+        })
+    }
+
+    fn rename(&self, id: &ContainerId, new_name: &str) -> Result<()> {
+        containust_common::types::validate_name(new_name)?;
+        self.state_store.update(|state| {
+            if state
+                .containers
+                .iter()
+                .any(|entry| entry.name == new_name && entry.id != *id)
+            {
+                return Err(ContainustError::Config {
+                    message: format!("container name already exists: {new_name}"),
+                });
+            }
+            let entry = state
+                .containers
+                .iter_mut()
+                .find(|entry| entry.id == *id)
+                .ok_or_else(|| ContainustError::NotFound {
+                    kind: "container",
+                    id: id.to_string(),
+                })?;
+            tracing::info!(id = %id, from = %entry.name, to = new_name, "renaming container");
+            entry.name = new_name.to_string();
+            Ok(())
+        })
+    }
+
+    fn signal(&self, id: &ContainerId, signal: ContainerSignal) -> Result<()> {
+        let state = self.state_store.read()?;
+        let entry = state
+            .containers
+            .iter()
+            .find(|e| e.id == *id)
+            .ok_or_else(|| ContainustError::NotFound {
+                kind: "container",
+                id: id.to_string(),
+            })?;
+        let pid = entry
+            .pid
+            .filter(|_| entry.state == containust_common::types::ContainerState::Running)
+            .ok_or_else(|| ContainustError::Config {
+                message: format!("container {id} is not running"),
+            })?;
+        tracing::info!(id = %id, pid, %signal, "sending signal (Linux native)");
+        send_signal(pid, signal)
     }
 
     fn exec(&self, id: &ContainerId, cmd: &[String]) -> Result<ExecOutput> {
+        self.exec_with(id, cmd, &ExecOptions::default())
+    }
+
+    fn exec_with(
+        &self,
+        id: &ContainerId,
+        cmd: &[String],
+        options: &ExecOptions,
+    ) -> Result<ExecOutput> {
         let state = self.state_store.read()?;
         let entry = state
             .containers
@@ -202,10 +285,12 @@ impl ContainerBackend for LinuxNativeBackend {
                 kind: "container",
                 id: id.to_string(),
             })?;
+        record_current_name(&entry.name);
         let pid = entry.pid.ok_or_else(|| ContainustError::Config {
             message: format!("container {id} is not running"),
         })?;
-        crate::exec::exec_in_container(id, pid, cmd)
+        let options = options.clone().merge(&exec_defaults(entry));
+        crate::exec::exec_in_container(id, pid, cmd, &options)
     }
 
     fn remove(&self, id: &ContainerId) -> Result<()> {
@@ -235,6 +320,52 @@ impl ContainerBackend for LinuxNativeBackend {
         crate::logs::read_logs(&self.data_dir, id.as_str())
     }
 
+    fn logs_with(&self, id: &ContainerId, options: &crate::logs::LogOptions) -> Result<String> {
+        crate::logs::read_logs_with(&self.data_dir, id.as_str(), options)
+    }
+
+    fn previous_logs(&self, id: &ContainerId, options: &crate::logs::LogOptions) -> Result<String> {
+        crate::logs::read_previous_logs(&self.data_dir, id.as_str(), options)
+    }
+
+    fn attach(
+        &self,
+        id: &ContainerId,
+        stdin: bool,
+    ) -> Result<Box<dyn crate::attach::AttachStream>> {
+        let state = self.state_store.read()?;
+        let entry = state
+            .containers
+            .iter()
+            .find(|e| e.id == *id)
+            .ok_or_else(|| ContainustError::NotFound {
+                kind: "container",
+                id: id.to_string(),
+            })?;
+        let pid = entry
+            .pid
+            .filter(|_| entry.state == containust_common::types::ContainerState::Running)
+            .ok_or_else(|| ContainustError::Config {
+                message: format!("container {id} is not running"),
+            })?;
+        let stdin = stdin
+            .then(|| {
+                let path = crate::attach::stdin_path(&self.data_dir, id.as_str());
+                crate::attach::open_stdin_writer(&path, id.as_str())
+            })
+            .transpose()?;
+        // Like `docker attach`, only output produced from now on is shown.
+        let offset = std::fs::metadata(crate::logs::log_path(&self.data_dir, id.as_str()))
+            .map_or(0, |metadata| metadata.len());
+        Ok(Box::new(LogAttach {
+            data_dir: self.data_dir.clone(),
+            id: id.clone(),
+            pid,
+            offset,
+            stdin,
+        }))
+    }
+
     fn list(&self) -> Result<Vec<ContainerInfo>> {
         let state = self.state_store.read()?;
         Ok(state
@@ -247,14 +378,36 @@ impl ContainerBackend for LinuxNativeBackend {
                 pid: e.pid,
                 image: e.image.clone(),
                 created_at: e.created_at.clone(),
+                started_at: e.started_at.clone(),
+                finished_at: e.finished_at.clone(),
+                exit_code: e.exit_code,
+                health: e.health.as_ref().map(|record| record.state),
             })
             .collect())
     }
 
+    fn inspect(&self, id: &ContainerId) -> Result<ContainerDetail> {
+        let state = self.state_store.read()?;
+        let entry = state
+            .containers
+            .iter()
+            .find(|entry| entry.id == *id)
+            .ok_or_else(|| ContainustError::NotFound {
+                kind: "container",
+                id: id.as_str().to_string(),
+            })?;
+        let cgroup_dir = cgroup_config()
+            .container_path(&self.project_id)
+            .join(id.as_str());
+        Ok(container_detail(entry, &cgroup_dir))
+    }
+
     fn reconcile(&self) -> Result<ReconciliationReport> {
+        let now = self.now();
         let (stale_processes, tracked_rootfs, tracked_ids) =
             self.state_store.update_if_changed(|state| {
-                let (stale_processes, tracked_rootfs, tracked_ids) = reconcile_state_entries(state);
+                let (stale_processes, tracked_rootfs, tracked_ids) =
+                    reconcile_state_entries(state, now);
                 Ok((
                     (stale_processes, tracked_rootfs, tracked_ids),
                     stale_processes > 0,
@@ -262,7 +415,11 @@ impl ContainerBackend for LinuxNativeBackend {
             })?;
         let orphaned_rootfs = cleanup_orphaned_rootfs(&self.data_dir, &tracked_rootfs)?;
         let orphaned_cgroups = cleanup_orphaned_cgroups(&self.project_id, &tracked_ids);
-        let policies = crate::supervise::enforce_policies(&self.state_store, self)?;
+        let policies = crate::supervise::enforce_policies_with_clock(
+            &self.state_store,
+            self,
+            self.clock().as_ref(),
+        )?;
         Ok(ReconciliationReport {
             stale_processes,
             orphaned_rootfs,
@@ -272,12 +429,117 @@ impl ContainerBackend for LinuxNativeBackend {
         })
     }
 
+    fn set_clock(&self, clock: SharedClock) {
+        *self.clock.lock().unwrap_or_else(PoisonError::into_inner) = clock;
+    }
+
     fn is_available(&self) -> bool {
         cfg!(target_os = "linux")
     }
 }
 
+/// Parses the capability names recorded for `entry`.
+fn kept_capabilities(
+    entry: &crate::state::StateEntry,
+) -> Result<Vec<containust_core::capability::Capability>> {
+    entry
+        .capabilities
+        .iter()
+        .map(|name| containust_core::capability::Capability::parse(name))
+        .collect()
+}
+
+/// IPC namespace limits recorded for `entry`.
+const fn recorded_ipc_limits(
+    entry: &crate::state::StateEntry,
+) -> containust_core::namespace::ipc::IpcLimits {
+    containust_core::namespace::ipc::IpcLimits {
+        shmmax_bytes: entry.ipc_shmmax_bytes,
+        shmall_pages: entry.ipc_shmall_pages,
+        msgmni: entry.ipc_msgmni,
+    }
+}
+
+/// Namespaces a container's main process is spawned into.
+fn spawn_namespaces(
+    network: &crate::network::NetworkMode,
+) -> containust_core::namespace::NamespaceConfig {
+    let mut namespaces = containust_core::namespace::NamespaceConfig::default().with_user_and_pid();
+    namespaces.network = !network.is_host();
+    // Shared netns lives in the init userns — cannot setns after NEWUSER.
+    if network.shared_name().is_some() {
+        namespaces.user = false;
+    }
+    namespaces
+}
+
+/// Exec options inherited from the container's recorded configuration.
+///
+/// Redacted secrets are restored like at start; one that cannot be
+/// restored is left out rather than passed as the redaction marker.
+fn exec_defaults(entry: &crate::state::StateEntry) -> ExecOptions {
+    let env = containust_common::redact::resolve_env(&entry.env).unwrap_or_else(|message| {
+        tracing::debug!(%message, "exec omits unresolved secrets");
+        entry
+            .env
+            .iter()
+            .filter(|(_, value)| value != containust_common::redact::REDACTED_MARKER)
+            .cloned()
+            .collect()
+    });
+    ExecOptions {
+        workdir: entry.workdir.clone(),
+        user: entry.user.clone(),
+        env,
+    }
+}
+
+/// Assembles the detail of `entry`, reading live values from `cgroup_dir`.
+/// The cgroup limits recorded on `entry`, applied at start and reported by
+/// `inspect`.
+fn entry_limits(entry: &crate::state::StateEntry) -> containust_common::types::ResourceLimits {
+    containust_common::types::ResourceLimits {
+        memory_bytes: entry.memory_bytes,
+        cpu_shares: entry.cpu_shares,
+        io_weight: None,
+        pids_max: entry.pids_max,
+        cpu_quota_us: entry.cpu_quota_us,
+        cpu_period_us: entry.cpu_period_us,
+        swap_bytes: entry.swap_bytes,
+        memory_low_bytes: entry.memory_low_bytes,
+        memory_high_bytes: entry.memory_high_bytes,
+        cpuset_cpus: entry.cpuset_cpus.clone(),
+        cpuset_mems: entry.cpuset_mems.clone(),
+    }
+}
+
+fn container_detail(entry: &crate::state::StateEntry, cgroup_dir: &Path) -> ContainerDetail {
+    ContainerDetail {
+        id: entry.id.clone(),
+        name: entry.name.clone(),
+        state: entry.state.to_string(),
+        pid: entry.pid,
+        image: entry.image.clone(),
+        created_at: entry.created_at.clone(),
+        command: entry.command.clone(),
+        env: entry.env.clone(),
+        limits: entry_limits(entry),
+        readonly_rootfs: entry.readonly_rootfs,
+        volumes: entry.volumes.clone(),
+        port_mappings: entry.port_mappings.clone(),
+        network: entry.network.clone(),
+        restart: entry.restart,
+        restart_count: entry.restart_count,
+        health: entry.health.clone(),
+        last_error: entry.last_error.clone(),
+        rootfs_path: entry.rootfs_path.as_ref().map(PathBuf::from),
+        log_path: entry.log_path.as_ref().map(PathBuf::from),
+        cgroup: CgroupDetail::read(cgroup_dir),
+    }
+}
+
 fn cleanup_container_files(data_dir: &Path, entry: &crate::state::StateEntry) -> Result<()> {
+    rootfs::release_overlay(data_dir, entry.id.as_str())?;
     let rootfs = data_dir.join("rootfs").join(entry.id.as_str());
     if rootfs.exists() {
         std::fs::remove_dir_all(&rootfs).map_err(|source| ContainustError::Io {
@@ -285,15 +547,85 @@ fn cleanup_container_files(data_dir: &Path, entry: &crate::state::StateEntry) ->
             source,
         })?;
     }
-    let log = data_dir
-        .join("logs")
-        .join(format!("{}.log", entry.id.as_str()));
-    if log.exists() {
-        std::fs::remove_file(&log).map_err(|source| ContainustError::Io { path: log, source })?;
+    for log in [
+        crate::logs::log_path(data_dir, entry.id.as_str()),
+        crate::logs::previous_log_path(data_dir, entry.id.as_str()),
+    ] {
+        if log.exists() {
+            std::fs::remove_file(&log)
+                .map_err(|source| ContainustError::Io { path: log, source })?;
+        }
+    }
+    let stdin = crate::attach::stdin_path(data_dir, entry.id.as_str());
+    if stdin.exists() {
+        std::fs::remove_file(&stdin).map_err(|source| ContainustError::Io {
+            path: stdin,
+            source,
+        })?;
     }
     Ok(())
 }
 
+/// Creates the FIFO the main process reads stdin from, for `attach`, when
+/// the container asked for stdin; otherwise it reads `/dev/null`.
+#[cfg(target_os = "linux")]
+fn prepare_stdin(data_dir: &Path, id: &ContainerId, enabled: bool) -> Result<Option<PathBuf>> {
+    let path = crate::attach::stdin_path(data_dir, id.as_str());
+    if !enabled {
+        // A FIFO left by an earlier start would have no reader.
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|source| ContainustError::Io { path, source })?;
+        }
+        return Ok(None);
+    }
+    crate::attach::create_stdin_fifo(&path)?;
+    Ok(Some(path))
+}
+
+#[cfg(not(target_os = "linux"))]
+#[allow(clippy::unnecessary_wraps)]
+const fn prepare_stdin(
+    _data_dir: &Path,
+    _id: &ContainerId,
+    _enabled: bool,
+) -> Result<Option<PathBuf>> {
+    Ok(None)
+}
+
+/// Follows a container's log file and writes to its stdin FIFO.
+struct LogAttach {
+    data_dir: PathBuf,
+    id: ContainerId,
+    pid: u32,
+    offset: u64,
+    stdin: Option<std::fs::File>,
+}
+
+impl crate::attach::AttachStream for LogAttach {
+    fn read_output(&mut self) -> Result<Vec<u8>> {
+        let (content, next) =
+            crate::logs::read_logs_from(&self.data_dir, self.id.as_str(), self.offset)?;
+        self.offset = next;
+        Ok(content.into_bytes())
+    }
+
+    fn write_input(&mut self, data: &[u8]) -> Result<()> {
+        let Some(stdin) = &mut self.stdin else {
+            return Err(ContainustError::Config {
+                message: format!("attached to container {} without stdin", self.id),
+            });
+        };
+        std::io::Write::write_all(stdin, data).map_err(|source| ContainustError::Io {
+            path: crate::attach::stdin_path(&self.data_dir, self.id.as_str()),
+            source,
+        })
+    }
+
+    fn is_running(&mut self) -> Result<bool> {
+        Ok(process_is_alive(self.pid))
+    }
+}
+
 fn cleanup_orphaned_rootfs(data_dir: &Path, tracked: &HashSet<PathBuf>) -> Result<usize> {
     let root = data_dir.join("rootfs");
     if !root.exists() {
@@ -328,6 +660,7 @@ fn cleanup_orphaned_rootfs(data_dir: &Path, tracked: &HashSet<PathBuf>) -> Resul
 
 fn reconcile_state_entries(
     state: &mut crate::state::StateFile,
+    now: DateTime<Utc>,
 ) -> (usize, HashSet<PathBuf>, HashSet<String>) {
     let mut stale_processes = 0;
     for entry in &mut state.containers {
@@ -336,6 +669,8 @@ fn reconcile_state_entries(
         {
             entry.state = containust_common::types::ContainerState::Failed;
             entry.pid = None;
+            // Detection time: the exit itself was not observed.
+            entry.finished_at = Some(now.to_rfc3339());
             stale_processes += 1;
         }
     }
@@ -372,6 +707,72 @@ const fn process_is_alive(_pid: u32) -> bool {
 }
 
 impl LinuxNativeBackend {
+    /// Spawns the entry at `index` and applies its limits and forwarders.
+    ///
+    /// The caller records a failure on the entry; this only tears down
+    /// what it started itself.
+    fn start_entry(
+        &self,
+        state: &mut crate::state::StateFile,
+        index: usize,
+        id: &ContainerId,
+    ) -> Result<u32> {
+        self.rotate_log_on_restart(&state.containers[index])?;
+        let process_config = self.prepare_process_config(state, index, id)?;
+        let pid = crate::process::spawn_container_process(&process_config)?;
+
+        let entry = &mut state.containers[index];
+        let limits = entry_limits(entry);
+        if let Err(error) = apply_cgroup_limits(&self.project_id, id, pid, &limits)
+            .or_else(|error| degrade_limit_error(&entry.name, error, entry.enforce_limits))
+        {
+            // Fail closed: tear down the just-spawned process. If kill
+            // fails, keep the PID tracked so the orphan is not lost.
+            return Err(fail_closed_after_cgroup_error(entry, pid, error));
+        }
+        #[cfg(target_os = "linux")]
+        {
+            entry.forwarder_pids = start_entry_forwarders(&self.data_dir, entry, pid)?;
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            entry.forwarder_pids.clear();
+        }
+        entry.state = containust_common::types::ContainerState::Running;
+        entry.pid = Some(pid);
+        entry.started_at = Some(self.now().to_rfc3339());
+        entry.finished_at = None;
+        entry.exit_code = None;
+        Ok(pid)
+    }
+
+    /// Keeps the log of the container's last run as `<id>.log.prev` when
+    /// it starts again, e.g. after a crash.
+    fn rotate_log_on_restart(&self, entry: &crate::state::StateEntry) -> Result<()> {
+        if entry.started_at.is_some()
+            && entry.state != containust_common::types::ContainerState::Running
+        {
+            let _ = crate::logs::rotate_log(&self.data_dir, entry.id.as_str())?;
+        }
+        Ok(())
+    }
+
+    /// Returns the container's recorded rootfs, remounting its overlay if
+    /// needed, or prepares one from `image`.
+    fn container_rootfs(
+        &self,
+        recorded: Option<&str>,
+        image: &str,
+        id: &ContainerId,
+    ) -> Result<PathBuf> {
+        let Some(path) = recorded else {
+            return prepare_rootfs(&self.data_dir, image, id);
+        };
+        #[cfg(target_os = "linux")]
+        rootfs::ensure_overlay_mounted(&self.data_dir, id.as_str(), image)?;
+        Ok(PathBuf::from(path))
+    }
+
     fn prepare_process_config(
         &self,
         state: &mut crate::state::StateFile,
@@ -388,20 +789,15 @@ impl LinuxNativeBackend {
         let command = entry.command.clone();
         let env = containust_common::redact::resolve_env(&entry.env)
             .map_err(|message| ContainustError::Config { message })?;
-        let readonly_rootfs = entry.readonly_rootfs;
+        let (readonly_rootfs, ipc_limits) = (entry.readonly_rootfs, recorded_ipc_limits(entry));
+        let stdin = entry.stdin;
         let volumes = entry.volumes.clone();
+        let (workdir, hostname) = (entry.workdir.clone(), entry.hostname.clone());
+        let user = entry.user.clone();
+        let capabilities = kept_capabilities(entry)?;
         let network = crate::network::NetworkMode::parse(Some(entry.network.as_str()));
-        let mut namespaces =
-            containust_core::namespace::NamespaceConfig::default().with_user_and_pid();
-        namespaces.network = !network.is_host();
-        // Shared netns lives in the init userns — cannot setns after NEWUSER.
-        if network.shared_name().is_some() {
-            namespaces.user = false;
-        }
-        let rootfs = match &entry.rootfs_path {
-            Some(path) => PathBuf::from(path),
-            None => prepare_rootfs(&self.data_dir, &image, id)?,
-        };
+        let namespaces = spawn_namespaces(&network);
+        let rootfs = self.container_rootfs(entry.rootfs_path.as_deref(), &image, id)?;
         #[cfg(target_os = "linux")]
         let join_netns = prepare_network_for_start(&self.data_dir, state, &network, &rootfs)?;
         #[cfg(not(target_os = "linux"))]
@@ -423,25 +819,52 @@ impl LinuxNativeBackend {
             readonly_rootfs,
             volumes,
             namespaces,
+            ipc_limits,
             join_netns,
+            workdir: workdir.map(PathBuf::from),
+            hostname,
+            user,
             log_path: Some(crate::logs::log_path(&self.data_dir, id.as_str())),
+            stdin_path: prepare_stdin(&self.data_dir, id, stdin)?,
+            capabilities,
         })
     }
 
-    fn stop_internal(&self, id: &ContainerId, force: bool) -> Result<()> {
+    /// Stops `id`; `options` override the stop options recorded at create.
+    ///
+    /// Returns whether the container was sent `SIGKILL`.
+    fn stop_internal(&self, id: &ContainerId, force: bool, options: StopOptions) -> Result<bool> {
         tracing::info!(id = %id, force, "stopping container (Linux native)");
+        // The grace period can be long, so wait for the exit without holding
+        // the state lock; other calls keep working meanwhile.
+        let (pid, stop) = {
+            let state = self.state_store.read()?;
+            let entry = state
+                .containers
+                .iter()
+                .find(|entry| entry.id == *id)
+                .ok_or_else(|| not_found(id))?;
+            record_current_name(&entry.name);
+            let is_running = entry.state == containust_common::types::ContainerState::Running;
+            (
+                entry.pid.filter(|_| is_running),
+                resolved_stop_options(options, entry),
+            )
+        };
+        let killed = pid.map(|pid| terminate_process(pid, force, stop));
+        if killed == Some(true) {
+            kill_container_cgroup(&self.project_id, id);
+        }
         self.state_store.update(|state| {
             let entry = state
                 .containers
                 .iter_mut()
                 .find(|entry| entry.id == *id)
-                .ok_or_else(|| ContainustError::NotFound {
-                    kind: "container",
-                    id: id.to_string(),
-                })?;
-            let is_running = entry.state == containust_common::types::ContainerState::Running;
-            if let Some(pid) = entry.pid.filter(|_| is_running) {
-                terminate_process(pid, force);
+                .ok_or_else(|| not_found(id))?;
+            if let Some(killed) = killed {
+                entry.finished_at = Some(self.now().to_rfc3339());
+                // SIGKILL has a known outcome; a graceful exit's code is not observed.
+                entry.exit_code = killed.then_some(SIGKILL_EXIT_CODE);
             }
             #[cfg(target_os = "linux")]
             {
@@ -454,13 +877,47 @@ impl LinuxNativeBackend {
         })?;
         cleanup_cgroup(&self.project_id, id)?;
 
-        Ok(())
+        Ok(killed == Some(true))
     }
 }
 
-/// Sends SIGTERM followed by SIGKILL after a 2-second grace period.
+/// Error for a container id missing from state.
+fn not_found(id: &ContainerId) -> ContainustError {
+    ContainustError::NotFound {
+        kind: "container",
+        id: id.to_string(),
+    }
+}
+
+/// Tags the caller's [`container_span`](crate::container::container_span)
+/// with the name read from state.
+fn record_current_name(name: &str) {
+    record_identity(&tracing::Span::current(), None, Some(name));
+}
+
+/// Grace period between the stop signal and SIGKILL when none is declared.
+const DEFAULT_STOP_GRACE: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Shell convention for a process killed by SIGKILL (128 + 9).
+const SIGKILL_EXIT_CODE: i32 = 137;
+
+/// Fills unset `options` from the stop options recorded on `entry`.
+fn resolved_stop_options(options: StopOptions, entry: &crate::state::StateEntry) -> StopOptions {
+    options.or(StopOptions {
+        signal: entry
+            .stop_signal
+            .as_deref()
+            .and_then(|name| ContainerSignal::parse(name).ok()),
+        timeout: entry.stop_timeout_secs.map(std::time::Duration::from_secs),
+    })
+}
+
+/// Sends the stop signal (SIGTERM by default), then SIGKILL if the process
+/// outlives the grace period.
+///
+/// Returns true when SIGKILL was sent, i.e. the container needed force.
 #[cfg(target_os = "linux")]
-fn terminate_process(pid: u32, force: bool) {
+fn terminate_process(pid: u32, force: bool, stop: StopOptions) -> bool {
     use nix::sys::signal::{Signal, kill};
     use nix::unistd::Pid;
 
@@ -468,22 +925,68 @@ fn terminate_process(pid: u32, force: bool) {
 
     if force {
         let _ = kill(nix_pid, Signal::SIGKILL);
-        return;
+        return true;
     }
-    if kill(nix_pid, Signal::SIGTERM).is_err() {
-        return;
+    let signal = stop.signal.unwrap_or(ContainerSignal::TERM);
+    if send_signal(pid, signal).is_err() {
+        return false;
+    }
+    tracing::info!(pid, signal = signal.name(), "sent stop signal");
+    let deadline = std::time::Instant::now() + stop.timeout.unwrap_or(DEFAULT_STOP_GRACE);
+    while kill(nix_pid, None).is_ok() && std::time::Instant::now() < deadline {
+        std::thread::sleep(std::time::Duration::from_millis(50));
     }
-    tracing::info!(pid, "sent SIGTERM");
-    std::thread::sleep(std::time::Duration::from_secs(2));
 
     if kill(nix_pid, None).is_ok() {
         let _ = kill(nix_pid, Signal::SIGKILL);
         tracing::info!(pid, "sent SIGKILL");
+        return true;
     }
+    false
+}
+
+/// Delivers `signal` to `pid`.
+#[cfg(target_os = "linux")]
+fn send_signal(pid: u32, signal: ContainerSignal) -> Result<()> {
+    use nix::sys::signal::{Signal, kill};
+    use nix::unistd::Pid;
+
+    let nix_signal =
+        Signal::try_from(signal.number()).map_err(|error| ContainustError::Config {
+            message: format!("cannot send {signal}: {error}"),
+        })?;
+    let nix_pid = Pid::from_raw(i32::try_from(pid).unwrap_or(i32::MAX));
+    kill(nix_pid, nix_signal).map_err(|error| ContainustError::Config {
+        message: format!("failed to send {signal} to pid {pid}: {error}"),
+    })
 }
 
 #[cfg(not(target_os = "linux"))]
-const fn terminate_process(_pid: u32, _force: bool) {}
+fn send_signal(pid: u32, signal: ContainerSignal) -> Result<()> {
+    Err(ContainustError::Config {
+        message: format!("cannot send {signal} to pid {pid}: native containers require Linux"),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+const fn terminate_process(_pid: u32, _force: bool, _stop: StopOptions) -> bool {
+    false
+}
+
+/// Kills any process left in the container's cgroup (best effort).
+///
+/// Catches children that outlived a force-killed init process.
+fn kill_container_cgroup(project_id: &str, container_id: &ContainerId) {
+    let path = cgroup_config()
+        .container_path(project_id)
+        .join(container_id.as_str());
+    if !path.exists() {
+        return;
+    }
+    if let Err(error) = containust_core::cgroup::kill::kill_all(&path) {
+        tracing::warn!(path = %path.display(), %error, "failed to kill cgroup processes");
+    }
+}
 
 // ---------------------------------------------------------------------------
 // Image preparation helpers
@@ -493,7 +996,8 @@ const fn terminate_process(_pid: u32, _force: bool) {}
 /// the given image source URI.
 ///
 /// Supported sources:
-/// - `file://<path>` — bind-mounts or copies the directory as rootfs
+/// - `file://<path>` — overlays the directory, or copies it where the data
+///   directory does not support overlay
 /// - `tar://<path>` — extracts the archive into the rootfs directory
 /// - `image://<name>[@sha256:<hex>]` — materializes an imported image
 ///   from the project's content-addressed catalog (offline-safe)
@@ -511,6 +1015,8 @@ fn prepare_rootfs(
     // If rootfs already exists from a previous create, reuse it
     if rootfs_dir.exists() {
         tracing::info!(path = %rootfs_dir.display(), "reusing existing rootfs");
+        #[cfg(target_os = "linux")]
+        rootfs::ensure_overlay_mounted(data_dir, container_id.as_str(), image_uri)?;
         return Ok(rootfs_dir);
     }
 
@@ -522,8 +1028,8 @@ fn prepare_rootfs(
                 id: path_str.to_string(),
             });
         }
-        copy_dir_recursive(&src, &rootfs_dir, &rootfs_dir)?;
-        tracing::info!(rootfs = %rootfs_dir.display(), "rootfs copied from file:// source");
+        containust_image::source::check_rootfs(&src, false)?;
+        let _ = rootfs::prepare_dir_rootfs(data_dir, &src, &rootfs_dir, container_id.as_str())?;
     } else if let Some(path_str) = image_uri.strip_prefix("tar://") {
         let archive = PathBuf::from(path_str);
         if !archive.exists() {
@@ -745,18 +1251,25 @@ fn apply_cgroup_limits(
     pid: u32,
     limits: &containust_common::types::ResourceLimits,
 ) -> Result<()> {
-    let requested =
-        limits.memory_bytes.is_some() || limits.cpu_shares.is_some() || limits.io_weight.is_some();
+    let requested = limits.memory_bytes.is_some()
+        || limits.cpu_shares.is_some()
+        || limits.io_weight.is_some()
+        || limits.pids_max.is_some()
+        || limits.swap_bytes.is_some()
+        || limits.memory_low_bytes.is_some()
+        || limits.memory_high_bytes.is_some()
+        || limits.cpuset_cpus.is_some()
+        || limits.cpuset_mems.is_some();
     if !requested {
         return Ok(());
     }
 
     #[cfg(target_os = "linux")]
     {
-        use containust_core::cgroup::CgroupManager;
+        use containust_core::cgroup::{CgroupConfig, CgroupManager};
 
-        let cgroup_id = format!("{project_id}/{}", container_id.as_str());
-        let mgr = CgroupManager::create(&cgroup_id)?;
+        let cgroup_id = super::container_cgroup_id(project_id, container_id);
+        let mgr = CgroupManager::create_in(&CgroupConfig::for_current_user()?, &cgroup_id)?;
         mgr.apply_limits(limits)?;
         mgr.add_process(pid)?;
         Ok(())
@@ -770,22 +1283,52 @@ fn apply_cgroup_limits(
     }
 }
 
+/// Decides whether a cgroup failure aborts the start.
+///
+/// With `enforce` unset, a denied write or a missing controller is logged
+/// and the container runs without its limits. Any other failure, or any
+/// failure while enforcing, is returned with its classification.
+fn degrade_limit_error(name: &str, error: ContainustError, enforce: bool) -> Result<()> {
+    use containust_core::cgroup::LimitFailure;
+
+    let failure = LimitFailure::classify(&error);
+    if failure == LimitFailure::Other {
+        return Err(error);
+    }
+    let reason = failure.describe();
+    if enforce {
+        return Err(ContainustError::Config {
+            message: format!(
+                "cgroup limits not applied: {reason} ({error}); set `enforce_limits = false` \
+                 under [defaults] to start without them"
+            ),
+        });
+    }
+    tracing::warn!(name, %error, "cgroup limits not applied ({reason}); continuing without them");
+    Ok(())
+}
+
 /// Validates explicit resource limit ranges before create/start.
-fn validate_resource_limits(memory_bytes: Option<u64>, cpu_shares: Option<u64>) -> Result<()> {
-    if let Some(memory) = memory_bytes
+fn validate_resource_limits(config: &ContainerConfig) -> Result<()> {
+    if let Some(memory) = config.memory_bytes
         && memory == 0
     {
         return Err(ContainustError::Config {
             message: "memory limit must be greater than zero".into(),
         });
     }
-    if let Some(cpu) = cpu_shares
+    if let Some(cpu) = config.cpu_shares
         && !(1..=10_000).contains(&cpu)
     {
         return Err(ContainustError::Config {
             message: format!("cpu shares must be in 1..=10000, got {cpu}"),
         });
     }
+    if config.pids_max == Some(0) {
+        return Err(ContainustError::Config {
+            message: "pids limit must be greater than zero".into(),
+        });
+    }
     Ok(())
 }
 
@@ -804,11 +1347,18 @@ fn nix_kill(_pid: u32) -> Result<()> {
     Ok(())
 }
 
+/// Hierarchy holding this user's container cgroups.
+///
+/// Lookups fall back to the default hierarchy when rootless delegation is
+/// unavailable; no cgroup was created there in that case.
+fn cgroup_config() -> containust_core::cgroup::CgroupConfig {
+    containust_core::cgroup::CgroupConfig::for_current_user().unwrap_or_default()
+}
+
 /// Cgroup cleanup during container stop or removal.
 fn cleanup_cgroup(project_id: &str, container_id: &ContainerId) -> Result<()> {
-    let path = PathBuf::from(containust_common::constants::CGROUP_V2_PATH)
-        .join("containust")
-        .join(project_id)
+    let path = cgroup_config()
+        .container_path(project_id)
         .join(container_id.as_str());
     if path.exists() {
         std::fs::remove_dir(&path).map_err(|source| ContainustError::Io {
@@ -822,9 +1372,7 @@ fn cleanup_cgroup(project_id: &str, container_id: &ContainerId) -> Result<()> {
 
 #[cfg(target_os = "linux")]
 fn cleanup_orphaned_cgroups(project_id: &str, tracked_ids: &HashSet<String>) -> usize {
-    let root = PathBuf::from(containust_common::constants::CGROUP_V2_PATH)
-        .join("containust")
-        .join(project_id);
+    let root = cgroup_config().container_path(project_id);
     let Ok(entries) = std::fs::read_dir(&root) else {
         return 0;
     };
@@ -856,6 +1404,9 @@ mod tests {
         pid: Option<u32>,
         data_dir: &Path,
     ) -> crate::state::StateEntry {
+        let data_path = |dir: &str, name: String| {
+            Some(data_dir.join(dir).join(name).to_string_lossy().into_owned())
+        };
         crate::state::StateEntry {
             id: ContainerId::new(id),
             name: id.into(),
@@ -866,22 +1417,22 @@ mod tests {
             env: Vec::new(),
             memory_bytes: None,
             cpu_shares: None,
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_shmmax_bytes: None,
+            ipc_shmall_pages: None,
+            ipc_msgmni: None,
             readonly_rootfs: true,
+            stdin: false,
             volumes: Vec::new(),
-            rootfs_path: Some(
-                data_dir
-                    .join("rootfs")
-                    .join(id)
-                    .to_string_lossy()
-                    .into_owned(),
-            ),
-            log_path: Some(
-                data_dir
-                    .join("logs")
-                    .join(format!("{id}.log"))
-                    .to_string_lossy()
-                    .into_owned(),
-            ),
+            rootfs_path: data_path("rootfs", id.into()),
+            log_path: data_path("logs", format!("{id}.log")),
             ports: Vec::new(),
             port_mappings: Vec::new(),
             network: "bridge".into(),
@@ -890,7 +1441,19 @@ mod tests {
             healthcheck: None,
             health: None,
             restart_count: 0,
+            restart_limit_reported_at: None,
+            stop_signal: None,
+            stop_timeout_secs: None,
+            enforce_limits: true,
+            last_error: None,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
             created_at: "2026-01-01T00:00:00Z".into(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
         }
     }
 
@@ -976,10 +1539,145 @@ mod tests {
         assert!(cleanup_cgroup("nonexistent-project", &id).is_ok());
     }
 
+    fn cgroup_io_error(kind: std::io::ErrorKind) -> ContainustError {
+        ContainustError::Io {
+            path: PathBuf::from("/sys/fs/cgroup/containust/p/web/memory.max"),
+            source: std::io::Error::from(kind),
+        }
+    }
+
+    #[test]
+    fn denied_limits_warn_and_continue_unless_enforced() {
+        let denied = || cgroup_io_error(std::io::ErrorKind::PermissionDenied);
+        assert!(degrade_limit_error("web", denied(), false).is_ok());
+        let error = degrade_limit_error("web", denied(), true)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("permission denied"), "{error}");
+        assert!(error.contains("enforce_limits"), "{error}");
+
+        let missing = || cgroup_io_error(std::io::ErrorKind::NotFound);
+        assert!(degrade_limit_error("web", missing(), false).is_ok());
+        let error = degrade_limit_error("web", missing(), true)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("controller unavailable"), "{error}");
+    }
+
+    #[test]
+    fn unclassified_limit_errors_always_fail() {
+        let error = cgroup_io_error(std::io::ErrorKind::InvalidInput);
+        assert!(matches!(
+            degrade_limit_error("web", error, false),
+            Err(ContainustError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn stop_options_override_recorded_component_config() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut entry = test_state_entry(
+            "a",
+            containust_common::types::ContainerState::Running,
+            None,
+            dir.path(),
+        );
+        entry.stop_signal = Some("SIGQUIT".into());
+        entry.stop_timeout_secs = Some(30);
+
+        let recorded = resolved_stop_options(StopOptions::default(), &entry);
+        assert_eq!(recorded.signal.map(ContainerSignal::name), Some("SIGQUIT"));
+        assert_eq!(recorded.timeout, Some(std::time::Duration::from_secs(30)));
+
+        let flags = StopOptions {
+            signal: Some(ContainerSignal::TERM),
+            timeout: None,
+        };
+        let resolved = resolved_stop_options(flags, &entry);
+        assert_eq!(resolved.signal, Some(ContainerSignal::TERM));
+        assert_eq!(resolved.timeout, Some(std::time::Duration::from_secs(30)));
+    }
+
     #[test]
     fn terminate_process_does_not_panic_on_invalid_pid() {
         // Use a PID that almost certainly does not exist
-        terminate_process(999_999_999, true);
+        let _ = terminate_process(999_999_999, true, StopOptions::default());
+    }
+
+    /// Spawns a process that ignores SIGTERM, returned once it runs.
+    #[cfg(target_os = "linux")]
+    fn spawn_ignoring_sigterm() -> std::process::Child {
+        // The ignored SIGTERM survives the exec into sleep.
+        let child = std::process::Command::new("sh")
+            .args(["-c", "trap '' TERM; exec sleep 30"])
+            .spawn()
+            .expect("spawn");
+        let comm = format!("/proc/{}/comm", child.id());
+        while std::fs::read_to_string(&comm).map_or(true, |name| name.trim() != "sleep") {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        child
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn terminate_process_reports_escalation_to_sigkill() {
+        let mut child = spawn_ignoring_sigterm();
+        let stop = StopOptions {
+            signal: None,
+            timeout: Some(std::time::Duration::from_millis(200)),
+        };
+
+        assert!(terminate_process(child.id(), false, stop));
+        let status = child.wait().expect("wait");
+        assert_eq!(
+            std::os::unix::process::ExitStatusExt::signal(&status),
+            Some(9)
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn stop_releases_the_state_lock_during_the_grace_period() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let data_dir = dir.path().join("project");
+        let backend = std::sync::Arc::new(LinuxNativeBackend::with_paths(
+            data_dir.clone(),
+            data_dir.join("state").join("state.json"),
+        ));
+        let mut child = spawn_ignoring_sigterm();
+        let entry = test_state_entry(
+            "slow",
+            containust_common::types::ContainerState::Running,
+            Some(child.id()),
+            &data_dir,
+        );
+        backend
+            .state_store
+            .write(&crate::state::StateFile {
+                containers: vec![entry],
+                ..crate::state::StateFile::default()
+            })
+            .expect("state");
+
+        let stopper = {
+            let backend = std::sync::Arc::clone(&backend);
+            std::thread::spawn(move || {
+                let options = StopOptions {
+                    signal: None,
+                    timeout: Some(std::time::Duration::from_secs(2)),
+                };
+                backend.stop_with(&ContainerId::new("slow"), &options)
+            })
+        };
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        let listed = std::time::Instant::now();
+        assert_eq!(backend.list().expect("list").len(), 1);
+        assert!(listed.elapsed() < std::time::Duration::from_secs(1));
+
+        let outcome = stopper.join().expect("stopper").expect("stop");
+        assert_eq!(outcome, StopOutcome::Escalated);
+        let _ = child.wait();
     }
 
     #[test]
@@ -1059,6 +1757,190 @@ mod tests {
         );
     }
 
+    fn backend_with_entries(data_dir: &Path, ids: &[&str]) -> LinuxNativeBackend {
+        let backend = LinuxNativeBackend::with_paths(
+            data_dir.to_path_buf(),
+            data_dir.join("state").join("state.json"),
+        );
+        let containers = ids
+            .iter()
+            .map(|id| {
+                test_state_entry(
+                    id,
+                    containust_common::types::ContainerState::Stopped,
+                    None,
+                    data_dir,
+                )
+            })
+            .collect();
+        backend
+            .state_store
+            .write(&crate::state::StateFile {
+                containers,
+                ..crate::state::StateFile::default()
+            })
+            .expect("state");
+        backend
+    }
+
+    #[test]
+    fn failed_start_marks_entry_failed_with_reason() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = backend_with_entries(dir.path(), &["web"]);
+        backend
+            .state_store
+            .update(|state| {
+                let entry = &mut state.containers[0];
+                entry.state = containust_common::types::ContainerState::Created;
+                entry.image = "file:///nonexistent/containust-image".into();
+                entry.rootfs_path = None;
+                Ok(())
+            })
+            .expect("seed");
+
+        let error = backend
+            .start(&ContainerId::new("web"))
+            .expect_err("missing image");
+        let state = backend.state_store.read().expect("read state");
+        let entry = &state.containers[0];
+        assert_eq!(
+            entry.state,
+            containust_common::types::ContainerState::Failed
+        );
+        assert_eq!(
+            entry.last_error.as_deref(),
+            Some(error.to_string().as_str())
+        );
+        assert!(entry.pid.is_none());
+    }
+
+    #[test]
+    fn restart_rotates_the_previous_run_log() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = backend_with_entries(dir.path(), &["web"]);
+        backend
+            .state_store
+            .update(|state| {
+                let entry = &mut state.containers[0];
+                entry.state = containust_common::types::ContainerState::Failed;
+                entry.started_at = Some("2026-01-01T00:00:00Z".into());
+                entry.image = "file:///nonexistent/containust-image".into();
+                entry.rootfs_path = None;
+                Ok(())
+            })
+            .expect("seed");
+        crate::logs::append_log(dir.path(), "web", "crashed").expect("log");
+
+        let _ = backend
+            .start(&ContainerId::new("web"))
+            .expect_err("missing image");
+
+        let id = ContainerId::new("web");
+        assert_eq!(backend.logs(&id).expect("current"), "");
+        let previous = backend
+            .previous_logs(&id, &crate::logs::LogOptions::default())
+            .expect("previous");
+        assert_eq!(previous, "crashed\n");
+    }
+
+    #[test]
+    fn rename_updates_state_entry_name() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = backend_with_entries(dir.path(), &["web"]);
+
+        backend
+            .rename(&ContainerId::new("web"), "frontend")
+            .expect("rename");
+
+        let state = backend.state_store.read().expect("read state");
+        assert_eq!(state.containers[0].id.as_str(), "web");
+        assert_eq!(state.containers[0].name, "frontend");
+    }
+
+    #[test]
+    fn rename_rejects_name_of_another_container() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = backend_with_entries(dir.path(), &["web", "db"]);
+
+        let error = backend
+            .rename(&ContainerId::new("web"), "db")
+            .expect_err("duplicate name");
+        assert!(error.to_string().contains("already exists"), "{error}");
+        let state = backend.state_store.read().expect("read state");
+        assert_eq!(state.containers[0].name, "web");
+        assert!(backend.rename(&ContainerId::new("missing"), "x").is_err());
+    }
+
+    #[test]
+    fn rename_rejects_unsafe_name() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = backend_with_entries(dir.path(), &["web"]);
+
+        let error = backend
+            .rename(&ContainerId::new("web"), "web; reboot")
+            .expect_err("unsafe name");
+        assert!(error.to_string().contains("invalid name"), "{error}");
+        let state = backend.state_store.read().expect("read state");
+        assert_eq!(state.containers[0].name, "web");
+    }
+
+    #[test]
+    fn container_detail_combines_state_and_cgroup() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut entry = test_state_entry(
+            "web",
+            containust_common::types::ContainerState::Running,
+            Some(42),
+            dir.path(),
+        );
+        entry.env = vec![("API_TOKEN".into(), "<redacted>".into())];
+        entry.memory_bytes = Some(64 * 1024 * 1024);
+        entry.volumes = vec!["/srv/data:/data:ro".into()];
+        let cgroup = dir.path().join("cgroup");
+        std::fs::create_dir(&cgroup).expect("cgroup dir");
+        for (file, value) in [
+            ("memory.max", "67108864\n"),
+            ("memory.current", "1048576\n"),
+            ("pids.max", "max\n"),
+            ("pids.current", "3\n"),
+        ] {
+            std::fs::write(cgroup.join(file), value).expect("cgroup file");
+        }
+
+        let detail = container_detail(&entry, &cgroup);
+        assert_eq!(detail.state, "running");
+        assert_eq!(detail.pid, Some(42));
+        assert_eq!(detail.command, vec!["sh"]);
+        assert_eq!(detail.env, entry.env);
+        assert_eq!(detail.limits.memory_bytes, Some(64 * 1024 * 1024));
+        assert_eq!(detail.volumes, entry.volumes);
+        assert_eq!(
+            detail.rootfs_path,
+            Some(dir.path().join("rootfs").join("web"))
+        );
+        let cgroup = detail.cgroup.expect("cgroup detail");
+        assert_eq!(cgroup.memory_max, Some(64 * 1024 * 1024));
+        assert_eq!(cgroup.memory_current, Some(1024 * 1024));
+        assert_eq!(cgroup.pids_max, None);
+        assert_eq!(cgroup.pids_current, Some(3));
+        assert_eq!(cgroup.cpu_weight, None);
+    }
+
+    #[test]
+    fn inspect_reports_missing_cgroup_and_unknown_ids() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = backend_with_entries(dir.path(), &["web"]);
+
+        let detail = backend.inspect(&ContainerId::new("web")).expect("inspect");
+        assert_eq!(detail.name, "web");
+        assert!(detail.readonly_rootfs);
+        assert!(detail.cgroup.is_none());
+        let error = backend
+            .inspect(&ContainerId::new("missing"))
+            .expect_err("unknown id");
+        assert!(matches!(error, ContainustError::NotFound { .. }));
+    }
+
     #[test]
     fn remove_rejects_running_container() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -1124,6 +2006,8 @@ mod tests {
             })
             .expect("state");
 
+        backend.set_clock(Arc::new(containust_common::clock::MockClock::at_epoch()));
+
         let report = backend.reconcile().expect("reconcile");
         assert_eq!(report.stale_processes, 1);
         let state = backend.state_store.read().expect("state");
@@ -1132,6 +2016,10 @@ mod tests {
             containust_common::types::ContainerState::Failed
         );
         assert!(state.containers[0].pid.is_none());
+        assert_eq!(
+            state.containers[0].finished_at.as_deref(),
+            Some("1970-01-01T00:00:00+00:00")
+        );
     }
 
     #[test]
@@ -1164,26 +2052,25 @@ mod tests {
         assert!(second.list().expect("second list").is_empty());
     }
 
-    #[test]
-    fn two_projects_create_and_cleanup_independently() {
-        let dir = tempfile::tempdir().expect("tempdir");
-        let image = dir.path().join("image");
-        std::fs::create_dir_all(image.join("bin")).expect("image");
-        std::fs::write(image.join("bin/app"), "binary").expect("image file");
-        let first_dir = dir.path().join("first/.containust");
-        let second_dir = dir.path().join("second/.containust");
-        let first =
-            LinuxNativeBackend::with_paths(first_dir.clone(), first_dir.join("state/state.json"));
-        let second =
-            LinuxNativeBackend::with_paths(second_dir.clone(), second_dir.join("state/state.json"));
-        let config = ContainerConfig {
+    fn app_config(image: &Path) -> ContainerConfig {
+        ContainerConfig {
             name: "app".into(),
             image: format!("file://{}", image.display()),
             command: vec!["/bin/app".into()],
             env: Vec::new(),
             memory_bytes: None,
             cpu_shares: None,
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_limits: containust_core::namespace::ipc::IpcLimits::default(),
             readonly_rootfs: true,
+            stdin: false,
             volumes: Vec::new(),
             port: None,
             ports: Vec::new(),
@@ -1192,8 +2079,80 @@ mod tests {
             restart: containust_common::types::RestartPolicy::default(),
             healthcheck: None,
             namespaces: containust_core::namespace::NamespaceConfig::default(),
+            stop: crate::backend::StopOptions::default(),
+            enforce_limits: true,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn configured_cpu_quota_reaches_start_limits() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let image = dir.path().join("image");
+        std::fs::create_dir_all(image.join("bin")).expect("image");
+        std::fs::write(image.join("bin/app"), "binary").expect("image file");
+        let data_dir = dir.path().join(".containust");
+        let backend =
+            LinuxNativeBackend::with_paths(data_dir.clone(), data_dir.join("state/state.json"));
+        let config = ContainerConfig {
+            cpu_quota_us: Some(50_000),
+            cpu_period_us: Some(100_000),
+            ..app_config(&image)
         };
 
+        let id = backend.create(&config).expect("create");
+        let state = backend.state_store.read().expect("read state");
+        let entry = state
+            .containers
+            .iter()
+            .find(|entry| entry.id == id)
+            .expect("entry");
+        let limits = entry_limits(entry);
+        assert_eq!(limits.cpu_quota_us, Some(50_000));
+        assert_eq!(limits.cpu_period_us, Some(100_000));
+        assert_eq!(
+            backend.inspect(&id).expect("inspect").limits.cpu_quota_us,
+            Some(50_000)
+        );
+        backend.remove(&id).expect("remove");
+    }
+
+    #[test]
+    fn created_at_is_stamped_from_the_backend_clock() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let image = dir.path().join("image");
+        std::fs::create_dir_all(image.join("bin")).expect("image");
+        std::fs::write(image.join("bin/app"), "binary").expect("image file");
+        let data_dir = dir.path().join(".containust");
+        let backend =
+            LinuxNativeBackend::with_paths(data_dir.clone(), data_dir.join("state/state.json"));
+        let clock = containust_common::clock::MockClock::at_epoch();
+        clock.advance(std::time::Duration::from_secs(90));
+        backend.set_clock(Arc::new(clock));
+
+        let id = backend.create(&app_config(&image)).expect("create");
+        let created = backend.inspect(&id).expect("inspect").created_at;
+        assert_eq!(created, "1970-01-01T00:01:30+00:00");
+        backend.remove(&id).expect("remove");
+    }
+
+    #[test]
+    fn two_projects_create_and_cleanup_independently() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let image = dir.path().join("image");
+        std::fs::create_dir_all(image.join("bin")).expect("image");
+        std::fs::write(image.join("bin/app"), "binary").expect("image file");
+        let first_dir = dir.path().join("first/.containust");
+        let second_dir = dir.path().join("second/.containust");
+        let first =
+            LinuxNativeBackend::with_paths(first_dir.clone(), first_dir.join("state/state.json"));
+        let second =
+            LinuxNativeBackend::with_paths(second_dir.clone(), second_dir.join("state/state.json"));
+        let config = app_config(&image);
+
         let first_id = first.create(&config).expect("first create");
         let second_id = second.create(&config).expect("second create");
         crate::logs::append_log(&first_dir, first_id.as_str(), "first").expect("first log");
@@ -1210,5 +2169,25 @@ mod tests {
                 .expect("second logs")
                 .contains("second")
         );
+        second.remove(&second_id).expect("second remove");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn stdin_fifo_is_created_only_when_requested() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let id = ContainerId::new("c1");
+        let fifo = crate::attach::stdin_path(dir.path(), id.as_str());
+
+        assert_eq!(
+            prepare_stdin(dir.path(), &id, true).expect("with stdin"),
+            Some(fifo.clone())
+        );
+        assert!(fifo.exists());
+        assert_eq!(
+            prepare_stdin(dir.path(), &id, false).expect("without stdin"),
+            None
+        );
+        assert!(!fifo.exists(), "a stale FIFO has no reader");
     }
 }
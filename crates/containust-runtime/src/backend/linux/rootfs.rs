@@ -0,0 +1,258 @@
+//! Root filesystems for `file://` images.
+//!
+//! An overlay with the image directory as its lower layer avoids copying
+//! the image per container. When the data directory cannot host overlay
+//! upper and work dirs (some CI tmpfs mounts, network filesystems), the
+//! image is copied instead. Support is probed once per process.
+
+use std::path::{Path, PathBuf};
+
+use containust_common::error::{ContainustError, Result};
+#[cfg(target_os = "linux")]
+use containust_core::filesystem::ops::{LinuxMountOps, MountOps};
+#[cfg(target_os = "linux")]
+use containust_core::filesystem::overlayfs::{
+    OverlayConfig, mount_overlay_with, unmount_overlay_with,
+};
+
+/// How a `file://` image directory becomes a container rootfs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RootfsStrategy {
+    /// Overlay over the image with a per-container upper dir.
+    Overlay,
+    /// Recursive copy of the image directory.
+    Copy,
+}
+
+impl RootfsStrategy {
+    /// Picks the strategy for a given overlay probe result.
+    pub(super) const fn select(overlay_supported: bool) -> Self {
+        if overlay_supported {
+            Self::Overlay
+        } else {
+            Self::Copy
+        }
+    }
+
+    /// Picks the strategy for rootfs kept under `data_dir`.
+    fn detect(data_dir: &Path) -> Self {
+        Self::select(containust_core::filesystem::overlayfs::overlay_supported(
+            data_dir,
+        ))
+    }
+}
+
+/// Upper and work dirs of a container's overlay: `{data_dir}/overlay/{id}`.
+fn overlay_dir(data_dir: &Path, id: &str) -> PathBuf {
+    data_dir.join("overlay").join(id)
+}
+
+/// Materializes the image directory `src` at `rootfs_dir` for container
+/// `id`, overlaying it when `data_dir` supports overlay and copying it
+/// otherwise.
+///
+/// # Errors
+///
+/// Returns an error if the copy fails.
+pub(super) fn prepare_dir_rootfs(
+    data_dir: &Path,
+    src: &Path,
+    rootfs_dir: &Path,
+    id: &str,
+) -> Result<RootfsStrategy> {
+    let strategy = RootfsStrategy::detect(data_dir);
+    #[cfg(target_os = "linux")]
+    {
+        let config = overlay_config(src, rootfs_dir, &overlay_dir(data_dir, id));
+        prepare_with(&LinuxMountOps, strategy, &config)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (strategy, id);
+        super::copy_dir_recursive(src, rootfs_dir, rootfs_dir)?;
+        Ok(RootfsStrategy::Copy)
+    }
+}
+
+/// Applies `strategy` to the image and rootfs named by `config` through
+/// `ops`; an overlay that fails to mount falls back to a copy. Returns
+/// the strategy actually used.
+#[cfg(target_os = "linux")]
+fn prepare_with(
+    ops: &impl MountOps,
+    strategy: RootfsStrategy,
+    config: &OverlayConfig,
+) -> Result<RootfsStrategy> {
+    let (src, rootfs_dir) = (&config.lower_dirs[0], &config.merged_dir);
+    if strategy == RootfsStrategy::Overlay {
+        match mount_overlay_with(ops, config) {
+            Ok(()) => {
+                tracing::info!(rootfs = %rootfs_dir.display(), strategy = "overlay", "rootfs prepared from file:// source");
+                return Ok(RootfsStrategy::Overlay);
+            }
+            Err(error) => {
+                tracing::warn!(%error, "overlay mount failed; copying the image instead");
+                if let Some(overlay) = config.upper_dir.parent() {
+                    let _ = std::fs::remove_dir_all(overlay);
+                }
+                let _ = std::fs::remove_dir(rootfs_dir);
+            }
+        }
+    }
+    super::copy_dir_recursive(src, rootfs_dir, rootfs_dir)?;
+    tracing::info!(rootfs = %rootfs_dir.display(), strategy = "copy", "rootfs prepared from file:// source");
+    Ok(RootfsStrategy::Copy)
+}
+
+#[cfg(target_os = "linux")]
+fn overlay_config(src: &Path, rootfs_dir: &Path, overlay: &Path) -> OverlayConfig {
+    OverlayConfig {
+        lower_dirs: vec![src.to_path_buf()],
+        upper_dir: overlay.join("upper"),
+        work_dir: overlay.join("work"),
+        merged_dir: rootfs_dir.to_path_buf(),
+    }
+}
+
+/// Remounts the overlay of an overlay-backed rootfs that is no longer
+/// mounted, e.g. after a host reboot. Copied rootfs are left alone.
+///
+/// # Errors
+///
+/// Returns an error if the overlay cannot be mounted again.
+#[cfg(target_os = "linux")]
+pub(super) fn ensure_overlay_mounted(data_dir: &Path, id: &str, image_uri: &str) -> Result<()> {
+    let overlay = overlay_dir(data_dir, id);
+    let rootfs_dir = data_dir.join("rootfs").join(id);
+    let Some(src) = image_uri.strip_prefix("file://") else {
+        return Ok(());
+    };
+    if !overlay.exists() || is_mount_point(&rootfs_dir) {
+        return Ok(());
+    }
+    tracing::info!(rootfs = %rootfs_dir.display(), "remounting rootfs overlay");
+    mount_overlay_with(
+        &LinuxMountOps,
+        &overlay_config(Path::new(src), &rootfs_dir, &overlay),
+    )
+}
+
+/// Unmounts a container's overlay and removes its upper and work dirs;
+/// a no-op for copied rootfs.
+///
+/// # Errors
+///
+/// Returns an error if the overlay cannot be unmounted or removed.
+pub(super) fn release_overlay(data_dir: &Path, id: &str) -> Result<()> {
+    let overlay = overlay_dir(data_dir, id);
+    if !overlay.exists() {
+        return Ok(());
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let rootfs_dir = data_dir.join("rootfs").join(id);
+        if is_mount_point(&rootfs_dir) {
+            unmount_overlay_with(&LinuxMountOps, &rootfs_dir)?;
+        }
+    }
+    std::fs::remove_dir_all(&overlay).map_err(|source| ContainustError::Io {
+        path: overlay,
+        source,
+    })
+}
+
+/// Whether `path` sits on another device than its parent directory.
+#[cfg(target_os = "linux")]
+fn is_mount_point(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let device = |path: &Path| std::fs::metadata(path).map(|meta| meta.dev()).ok();
+    match (device(path), path.parent().and_then(device)) {
+        (Some(dev), Some(parent)) => dev != parent,
+        _ => false,
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use containust_core::filesystem::ops::{MountCall, RecordingMountOps};
+
+    use super::*;
+
+    fn image(dir: &Path) -> PathBuf {
+        let image = dir.join("image");
+        std::fs::create_dir_all(image.join("bin")).expect("image");
+        std::fs::write(image.join("bin/app"), "binary").expect("image file");
+        image
+    }
+
+    #[test]
+    fn strategy_follows_the_probe_result() {
+        assert_eq!(RootfsStrategy::select(true), RootfsStrategy::Overlay);
+        assert_eq!(RootfsStrategy::select(false), RootfsStrategy::Copy);
+    }
+
+    #[test]
+    fn supported_overlay_mounts_image_as_lower_layer() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let src = image(dir.path());
+        let rootfs = dir.path().join("rootfs/c1");
+        let overlay = dir.path().join("overlay/c1");
+        let ops = RecordingMountOps::new();
+
+        let config = overlay_config(&src, &rootfs, &overlay);
+        let used = prepare_with(&ops, RootfsStrategy::select(true), &config).expect("prepare");
+        assert_eq!(used, RootfsStrategy::Overlay);
+        let calls = ops.calls();
+        assert_eq!(calls.len(), 1, "{calls:?}");
+        let MountCall::Mount(request) = &calls[0] else {
+            unreachable!("expected an overlay mount, got {calls:?}");
+        };
+        assert_eq!(request.target, rootfs);
+        let data = request.data.as_deref().expect("overlay options");
+        assert!(
+            data.starts_with(&format!("lowerdir={}", src.display())),
+            "{data}"
+        );
+        assert!(!rootfs.join("bin/app").exists(), "overlay must not copy");
+    }
+
+    #[test]
+    fn unsupported_overlay_copies_the_image() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let src = image(dir.path());
+        let rootfs = dir.path().join("rootfs/c1");
+        let ops = RecordingMountOps::new();
+
+        let config = overlay_config(&src, &rootfs, &dir.path().join("overlay/c1"));
+        let used = prepare_with(&ops, RootfsStrategy::select(false), &config).expect("prepare");
+        assert_eq!(used, RootfsStrategy::Copy);
+        assert!(ops.calls().is_empty());
+        assert_eq!(
+            std::fs::read_to_string(rootfs.join("bin/app")).expect("copied"),
+            "binary"
+        );
+    }
+
+    #[test]
+    fn failed_overlay_mount_falls_back_to_copy() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let src = image(dir.path());
+        let rootfs = dir.path().join("rootfs/c1");
+        let overlay = dir.path().join("overlay/c1");
+        let ops = RecordingMountOps::failing_at(&rootfs);
+
+        let config = overlay_config(&src, &rootfs, &overlay);
+        let used = prepare_with(&ops, RootfsStrategy::Overlay, &config).expect("prepare");
+        assert_eq!(used, RootfsStrategy::Copy);
+        assert!(rootfs.join("bin/app").is_file());
+        assert!(!overlay.exists());
+    }
+
+    #[test]
+    fn release_without_overlay_is_a_no_op() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        release_overlay(dir.path(), "c1").expect("release");
+        assert!(!is_mount_point(dir.path()));
+    }
+}
@@ -3,10 +3,27 @@
 pub mod linux;
 pub mod vm;
 
-use containust_common::error::Result;
-use containust_common::types::ContainerId;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use crate::exec::ExecOutput;
+use containust_common::clock::SharedClock;
+use containust_common::error::{ContainustError, Result};
+use containust_common::types::{
+    ContainerId, HealthRecord, HealthState, HealthcheckSpec, PortMapping, ResourceLimits,
+    RestartPolicy,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::attach::AttachStream;
+use crate::exec::{ExecOptions, ExecOutput};
+use crate::signal::ContainerSignal;
+
+/// Cgroup of `container_id` relative to the cgroup hierarchy:
+/// `<project_id>/<container_id>`.
+pub(crate) fn container_cgroup_id(project_id: &str, container_id: &ContainerId) -> String {
+    format!("{project_id}/{}", container_id.as_str())
+}
 
 pub(crate) fn project_identifier(data_dir: &std::path::Path) -> String {
     use sha2::{Digest, Sha256};
@@ -42,8 +59,31 @@ pub struct ContainerConfig {
     pub memory_bytes: Option<u64>,
     /// CPU shares (relative weight).
     pub cpu_shares: Option<u64>,
+    /// Maximum number of processes (`pids.max`).
+    pub pids_max: Option<u64>,
+    /// Swap allowed beyond `memory_bytes` (`memory.swap.max`); `0`
+    /// disables swap.
+    pub swap_bytes: Option<u64>,
+    /// Memory protected from reclaim (`memory.low`).
+    pub memory_low_bytes: Option<u64>,
+    /// Throttling threshold below the hard limit (`memory.high`).
+    pub memory_high_bytes: Option<u64>,
+    /// CPU time allowed per period (`cpu.max` quota), in microseconds.
+    pub cpu_quota_us: Option<u64>,
+    /// Length of the `cpu.max` period in microseconds; the kernel default
+    /// when `None`.
+    pub cpu_period_us: Option<u64>,
+    /// Cores the container is pinned to (`cpuset.cpus`).
+    pub cpuset_cpus: Option<Vec<u32>>,
+    /// NUMA nodes the container's memory is allocated from (`cpuset.mems`).
+    pub cpuset_mems: Option<Vec<u32>>,
+    /// Size limits written into the container's IPC namespace.
+    pub ipc_limits: containust_core::namespace::ipc::IpcLimits,
     /// Whether the root filesystem is read-only.
     pub readonly_rootfs: bool,
+    /// Whether the main process reads stdin from a FIFO `attach` writes to;
+    /// otherwise it reads `/dev/null`.
+    pub stdin: bool,
     /// Volume mount specifications.
     pub volumes: Vec<String>,
     /// Primary exposed port.
@@ -60,10 +100,111 @@ pub struct ContainerConfig {
     pub healthcheck: Option<containust_common::types::HealthcheckSpec>,
     /// Namespace isolation policy applied at spawn.
     pub namespaces: containust_core::namespace::NamespaceConfig,
+    /// Declared graceful-stop signal and timeout.
+    pub stop: StopOptions,
+    /// Whether a limit the host refuses to apply fails the start.
+    pub enforce_limits: bool,
+    /// Working directory of the main process, also the `exec` default.
+    pub workdir: Option<String>,
+    /// Hostname set in the container's UTS namespace.
+    pub hostname: Option<String>,
+    /// Numeric `uid[:gid]` the main process runs as (mapped to the invoking
+    /// user in its user namespace), also the `exec` default.
+    pub user: Option<String>,
+    /// Capabilities the main process keeps; all others are dropped.
+    pub capabilities: Vec<containust_core::capability::Capability>,
+}
+
+/// How a graceful stop is requested.
+///
+/// Unset fields fall back to the container's recorded options, then to
+/// `SIGTERM` and the backend's default grace period.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StopOptions {
+    /// Signal sent to request the stop.
+    pub signal: Option<ContainerSignal>,
+    /// Grace period before the container is force-killed.
+    pub timeout: Option<std::time::Duration>,
+}
+
+/// How a container ended up stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopOutcome {
+    /// The container exited within the stop timeout.
+    Graceful,
+    /// The container outlived the stop timeout and was force-killed.
+    Escalated,
+    /// The caller asked for an immediate force stop.
+    Forced,
+}
+
+impl StopOptions {
+    /// Fills fields unset in `self` from `fallback`.
+    #[must_use]
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            signal: self.signal.or(fallback.signal),
+            timeout: self.timeout.or(fallback.timeout),
+        }
+    }
+}
+
+impl ContainerConfig {
+    /// Checks the invariants every backend relies on, before `create`.
+    ///
+    /// An empty `command` is accepted because backends fall back to the
+    /// image default at start; a command whose executable is blank is not.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error naming the first violated rule.
+    pub fn validate(&self) -> Result<()> {
+        let name = &self.name;
+        containust_common::types::validate_name(name)?;
+        if self.image.trim().is_empty() {
+            return Err(invalid(format!("container '{name}' has no image")));
+        }
+        if self
+            .command
+            .first()
+            .is_some_and(|exe| exe.trim().is_empty())
+        {
+            return Err(invalid(format!(
+                "container '{name}' command has an empty executable"
+            )));
+        }
+        let mut keys = HashSet::new();
+        for (key, _) in &self.env {
+            if key.is_empty() {
+                return Err(invalid(format!("container '{name}' has an empty env key")));
+            }
+            if !keys.insert(key.as_str()) {
+                return Err(invalid(format!(
+                    "container '{name}' sets env key '{key}' more than once"
+                )));
+            }
+        }
+        let zero_port = self.port == Some(0)
+            || self.ports.contains(&0)
+            || self
+                .port_mappings
+                .iter()
+                .any(|mapping| mapping.host == 0 || mapping.container == 0);
+        if zero_port {
+            return Err(invalid(format!(
+                "container '{name}' ports must be in 1..=65535"
+            )));
+        }
+        Ok(())
+    }
+}
+
+const fn invalid(message: String) -> ContainustError {
+    ContainustError::Config { message }
 }
 
 /// Information about a tracked container.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ContainerInfo {
     /// Unique identifier.
     pub id: ContainerId,
@@ -77,6 +218,161 @@ pub struct ContainerInfo {
     pub image: String,
     /// ISO-8601 creation timestamp.
     pub created_at: String,
+    /// ISO-8601 timestamp of the last successful start.
+    pub started_at: Option<String>,
+    /// ISO-8601 timestamp of the last observed exit.
+    pub finished_at: Option<String>,
+    /// Exit code of the last run, when it is known.
+    pub exit_code: Option<i32>,
+    /// Latest health verdict, when a probe is configured.
+    pub health: Option<HealthState>,
+}
+
+impl ContainerInfo {
+    /// Time between the last start (or, for older records, the creation)
+    /// and `now`; `None` unless the container is running.
+    #[must_use]
+    pub fn uptime(&self, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+        if self.state != "running" {
+            return None;
+        }
+        let since = self.started_at.as_deref().unwrap_or(&self.created_at);
+        let since = chrono::DateTime::parse_from_rfc3339(since).ok()?;
+        now.signed_duration_since(since).to_std().ok()
+    }
+}
+
+/// Full configuration and host resources of one container.
+///
+/// Env values are reported as recorded, so secrets stay redacted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerDetail {
+    /// Unique identifier.
+    pub id: ContainerId,
+    /// Human-readable name.
+    pub name: String,
+    /// Current state as a string.
+    pub state: String,
+    /// PID of the init process (if running).
+    pub pid: Option<u32>,
+    /// Image source URI.
+    pub image: String,
+    /// ISO-8601 creation timestamp.
+    pub created_at: String,
+    /// Command executed inside the container.
+    #[serde(default)]
+    pub command: Vec<String>,
+    /// Environment variables.
+    #[serde(default)]
+    pub env: Vec<(String, String)>,
+    /// Requested resource limits.
+    #[serde(default)]
+    pub limits: ResourceLimits,
+    /// Whether the root filesystem is read-only.
+    #[serde(default)]
+    pub readonly_rootfs: bool,
+    /// Volume mount specifications.
+    #[serde(default)]
+    pub volumes: Vec<String>,
+    /// Host→container port publishes.
+    #[serde(default)]
+    pub port_mappings: Vec<PortMapping>,
+    /// Network mode.
+    #[serde(default)]
+    pub network: String,
+    /// Restart policy.
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// Restarts performed by the restart policy.
+    #[serde(default)]
+    pub restart_count: u32,
+    /// Latest health probe verdict, when a probe is configured.
+    #[serde(default)]
+    pub health: Option<HealthRecord>,
+    /// Why the last start failed.
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Root filesystem directory on the host.
+    #[serde(default)]
+    pub rootfs_path: Option<PathBuf>,
+    /// Log file on the host.
+    #[serde(default)]
+    pub log_path: Option<PathBuf>,
+    /// Live cgroup values, when the container has a cgroup.
+    #[serde(default)]
+    pub cgroup: Option<CgroupDetail>,
+}
+
+impl ContainerDetail {
+    /// Builds a detail carrying only the summary fields of `info`.
+    #[must_use]
+    pub fn from_info(info: ContainerInfo) -> Self {
+        Self {
+            id: info.id,
+            name: info.name,
+            state: info.state,
+            pid: info.pid,
+            image: info.image,
+            created_at: info.created_at,
+            command: Vec::new(),
+            env: Vec::new(),
+            limits: ResourceLimits::default(),
+            readonly_rootfs: false,
+            volumes: Vec::new(),
+            port_mappings: Vec::new(),
+            network: String::new(),
+            restart: RestartPolicy::default(),
+            restart_count: 0,
+            health: None,
+            last_error: None,
+            rootfs_path: None,
+            log_path: None,
+            cgroup: None,
+        }
+    }
+}
+
+/// Values read from a container's cgroup directory.
+///
+/// A limit of `max` or an unreadable file is reported as `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CgroupDetail {
+    /// Cgroup directory on the host.
+    pub path: PathBuf,
+    /// `memory.max` in bytes.
+    pub memory_max: Option<u64>,
+    /// `memory.current` in bytes.
+    pub memory_current: Option<u64>,
+    /// `cpu.weight`.
+    pub cpu_weight: Option<u64>,
+    /// `pids.max`.
+    pub pids_max: Option<u64>,
+    /// `pids.current`.
+    pub pids_current: Option<u64>,
+}
+
+impl CgroupDetail {
+    /// Reads the values of the cgroup at `path`, or `None` when it does
+    /// not exist.
+    #[must_use]
+    pub fn read(path: &Path) -> Option<Self> {
+        if !path.is_dir() {
+            return None;
+        }
+        let value = |file: &str| {
+            std::fs::read_to_string(path.join(file))
+                .ok()
+                .and_then(|raw| raw.trim().parse().ok())
+        };
+        Some(Self {
+            path: path.to_path_buf(),
+            memory_max: value("memory.max"),
+            memory_current: value("memory.current"),
+            cpu_weight: value("cpu.weight"),
+            pids_max: value("pids.max"),
+            pids_current: value("pids.current"),
+        })
+    }
 }
 
 /// Resources repaired or discovered during backend reconciliation.
@@ -94,6 +390,23 @@ pub struct ReconciliationReport {
     pub unhealthy: usize,
 }
 
+/// Verdict of one health probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthResult {
+    /// The probe passed.
+    Healthy,
+    /// The probe failed; the string says why.
+    Unhealthy(String),
+}
+
+impl HealthResult {
+    /// Returns whether the probe passed.
+    #[must_use]
+    pub const fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy)
+    }
+}
+
 /// Platform-agnostic container backend.
 ///
 /// Implementors handle the platform-specific details of container
@@ -132,6 +445,40 @@ pub trait ContainerBackend: Send + Sync {
         self.stop(id)
     }
 
+    /// Stops a running container with explicit stop options.
+    ///
+    /// The default delivers a non-`SIGTERM` signal through
+    /// [`signal`](Self::signal), then falls back to [`stop`](Self::stop);
+    /// backends that track recorded options override this. Returns
+    /// [`StopOutcome::Escalated`] when the backend itself force-killed the
+    /// container after the grace period.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signal cannot be delivered or the container
+    /// cannot be stopped.
+    fn stop_with(&self, id: &ContainerId, options: &StopOptions) -> Result<StopOutcome> {
+        if let Some(signal) = options.signal.filter(|s| *s != ContainerSignal::TERM) {
+            self.signal(id, signal)?;
+        }
+        self.stop(id).map(|()| StopOutcome::Graceful)
+    }
+
+    /// Sends `signal` to a running container's main process.
+    ///
+    /// Tracked state is left unchanged; a signal that terminates the
+    /// process is observed by the next reconciliation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container is not running, the signal cannot
+    /// be delivered, or the backend does not support signalling.
+    fn signal(&self, id: &ContainerId, signal: ContainerSignal) -> Result<()> {
+        Err(containust_common::error::ContainustError::Config {
+            message: format!("this backend cannot send {signal} to container {id}"),
+        })
+    }
+
     /// Executes a command inside a running container.
     ///
     /// # Errors
@@ -139,6 +486,64 @@ pub trait ContainerBackend: Send + Sync {
     /// Returns an error if the command fails to execute.
     fn exec(&self, id: &ContainerId, cmd: &[String]) -> Result<ExecOutput>;
 
+    /// Executes a command with explicit options.
+    ///
+    /// Unset options inherit the container's own workdir, user, and
+    /// environment. The default runs [`exec`](Self::exec) when no option
+    /// is given and rejects options otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an option is invalid or unsupported, or the
+    /// command fails to execute.
+    fn exec_with(
+        &self,
+        id: &ContainerId,
+        cmd: &[String],
+        options: &ExecOptions,
+    ) -> Result<ExecOutput> {
+        if *options == ExecOptions::default() {
+            return self.exec(id, cmd);
+        }
+        Err(ContainustError::Config {
+            message: format!("this backend cannot set exec options for container {id}"),
+        })
+    }
+
+    /// Runs one health probe against a running container.
+    ///
+    /// The default executes `hc.command` via [`exec`](Self::exec): exit
+    /// code `0` is healthy, anything else unhealthy. A probe that runs
+    /// longer than `hc.timeout_secs` is unhealthy even if it succeeds.
+    /// Host-side probes (`hc.http`, `hc.tcp`) run from [`crate::probe`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the probe could not be run at all.
+    fn healthcheck(&self, id: &ContainerId, hc: &HealthcheckSpec) -> Result<HealthResult> {
+        let timeout = std::time::Duration::from_secs(hc.timeout_secs);
+        if let Some(http) = &hc.http {
+            return Ok(crate::probe::http(http, timeout));
+        }
+        if let Some(port) = hc.tcp {
+            return Ok(crate::probe::tcp(port, timeout));
+        }
+        let started = std::time::Instant::now();
+        let output = self.exec(id, &hc.command)?;
+        let elapsed = started.elapsed();
+        if elapsed > timeout {
+            return Ok(HealthResult::Unhealthy(format!(
+                "probe timed out after {}s",
+                hc.timeout_secs
+            )));
+        }
+        Ok(if output.exit_code == 0 {
+            HealthResult::Healthy
+        } else {
+            HealthResult::Unhealthy(format!("probe exited with code {}", output.exit_code))
+        })
+    }
+
     /// Removes a stopped container from the state.
     ///
     /// # Errors
@@ -146,6 +551,19 @@ pub trait ContainerBackend: Send + Sync {
     /// Returns an error if the container cannot be removed.
     fn remove(&self, id: &ContainerId) -> Result<()>;
 
+    /// Renames a container; the new name must not belong to another
+    /// container.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container is missing, the name is empty or
+    /// already taken, or the backend does not support renaming.
+    fn rename(&self, id: &ContainerId, new_name: &str) -> Result<()> {
+        Err(containust_common::error::ContainustError::Config {
+            message: format!("this backend cannot rename container {id} to '{new_name}'"),
+        })
+    }
+
     /// Returns the logs for a container.
     ///
     /// # Errors
@@ -153,6 +571,44 @@ pub trait ContainerBackend: Send + Sync {
     /// Returns an error if logs cannot be retrieved.
     fn logs(&self, id: &ContainerId) -> Result<String>;
 
+    /// Returns the part of a container's logs selected by `options`.
+    ///
+    /// The default filters the output of [`Self::logs`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if logs cannot be retrieved.
+    fn logs_with(&self, id: &ContainerId, options: &crate::logs::LogOptions) -> Result<String> {
+        Ok(options.apply(&self.logs(id)?))
+    }
+
+    /// Returns the part of the container's previous-run logs, kept when
+    /// it was restarted, selected by `options`.
+    ///
+    /// # Errors
+    ///
+    /// The default returns an error: the backend keeps no previous logs.
+    fn previous_logs(&self, id: &ContainerId, options: &crate::logs::LogOptions) -> Result<String> {
+        let _ = options;
+        Err(ContainustError::Config {
+            message: format!("this backend keeps no previous logs for container {id}"),
+        })
+    }
+
+    /// Attaches to the main process's output and, when `stdin` is set,
+    /// its input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container is not running, stdin was not
+    /// wired at start, or the backend cannot attach.
+    fn attach(&self, id: &ContainerId, stdin: bool) -> Result<Box<dyn AttachStream>> {
+        let _ = stdin;
+        Err(ContainustError::Config {
+            message: format!("this backend cannot attach to container {id}"),
+        })
+    }
+
     /// Lists all tracked containers.
     ///
     /// # Errors
@@ -160,6 +616,25 @@ pub trait ContainerBackend: Send + Sync {
     /// Returns an error if the backend cannot retrieve state.
     fn list(&self) -> Result<Vec<ContainerInfo>>;
 
+    /// Returns the full configuration of a container.
+    ///
+    /// The default builds the detail from the summary in `list`; backends
+    /// with richer state override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ContainustError::NotFound`] when no container has `id`.
+    fn inspect(&self, id: &ContainerId) -> Result<ContainerDetail> {
+        self.list()?
+            .into_iter()
+            .find(|container| container.id == *id)
+            .map(ContainerDetail::from_info)
+            .ok_or_else(|| ContainustError::NotFound {
+                kind: "container",
+                id: id.as_str().to_string(),
+            })
+    }
+
     /// Reconciles persisted state with live backend resources.
     ///
     /// # Errors
@@ -169,6 +644,10 @@ pub trait ContainerBackend: Send + Sync {
         Ok(ReconciliationReport::default())
     }
 
+    /// Stamps container times with `clock` instead of the system clock.
+    /// Backends that record no timestamps ignore it.
+    fn set_clock(&self, _clock: SharedClock) {}
+
     /// Returns whether this backend is operational on the current platform.
     fn is_available(&self) -> bool;
 }
@@ -200,6 +679,90 @@ pub fn detect_backend_with_paths(
     }
 }
 
+/// Environment variable that selects the backend when `--backend` is unset.
+pub const BACKEND_ENV_VAR: &str = "CONTAINUST_BACKEND";
+
+/// Which backend runs containers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackendKind {
+    /// Native on Linux, VM elsewhere.
+    #[default]
+    Auto,
+    /// Linux namespaces and cgroups on the host kernel.
+    Native,
+    /// A QEMU Linux VM, available on every host.
+    Vm,
+}
+
+impl std::fmt::Display for BackendKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Auto => "auto",
+            Self::Native => "native",
+            Self::Vm => "vm",
+        })
+    }
+}
+
+impl std::str::FromStr for BackendKind {
+    type Err = ContainustError;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "native" => Ok(Self::Native),
+            "vm" => Ok(Self::Vm),
+            other => Err(ContainustError::Config {
+                message: format!("unknown backend \"{other}\"; expected native, vm, or auto"),
+            }),
+        }
+    }
+}
+
+/// Resolves `requested` to the concrete backend used on host `os`.
+///
+/// `Auto` keeps the platform default; `Vm` is honored everywhere so the
+/// VM path can be exercised on Linux.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::Config`] when `Native` is forced on a host
+/// other than Linux.
+pub fn select_backend(requested: BackendKind, os: &str) -> Result<BackendKind> {
+    match requested {
+        BackendKind::Auto if os == "linux" => Ok(BackendKind::Native),
+        BackendKind::Auto => Ok(BackendKind::Vm),
+        BackendKind::Native if os != "linux" => Err(ContainustError::Config {
+            message: format!(
+                "the native backend requires Linux, but this host is {os}; \
+                 use --backend vm or --backend auto"
+            ),
+        }),
+        kind => Ok(kind),
+    }
+}
+
+/// Creates the backend `kind` names; `Auto` detects the platform default.
+///
+/// A forced kind is built as requested, so check it with
+/// [`select_backend`] first: a native backend off Linux reports itself
+/// unavailable.
+#[must_use]
+pub fn create_backend(
+    kind: BackendKind,
+    data_dir: std::path::PathBuf,
+    state_file: std::path::PathBuf,
+    offline: bool,
+) -> Box<dyn ContainerBackend> {
+    match kind {
+        BackendKind::Auto => detect_backend_with_paths(data_dir, state_file, offline),
+        BackendKind::Native => {
+            Box::new(linux::LinuxNativeBackend::with_paths(data_dir, state_file))
+        }
+        BackendKind::Vm => Box::new(vm::VMBackend::with_options(data_dir, state_file, offline)),
+    }
+}
+
 /// Information about the current platform and backend availability.
 #[derive(Debug, Clone)]
 pub struct PlatformInfo {
@@ -225,7 +788,7 @@ pub fn platform_info() -> PlatformInfo {
         os: std::env::consts::OS.to_string(),
         arch: std::env::consts::ARCH.to_string(),
         native_available: cfg!(target_os = "linux"),
-        qemu_available: which::which(qemu_binary).is_ok(),
+        qemu_available: crate::util::find_binary(qemu_binary).is_ok(),
     }
 }
 
@@ -233,6 +796,85 @@ pub fn platform_info() -> PlatformInfo {
 mod tests {
     use super::*;
 
+    /// Backend whose `exec` returns a fixed exit code after a delay.
+    struct ProbeBackend {
+        exit_code: i32,
+        delay: std::time::Duration,
+    }
+
+    impl ContainerBackend for ProbeBackend {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+        fn create(&self, _config: &ContainerConfig) -> Result<ContainerId> {
+            Ok(ContainerId::new("probe"))
+        }
+        fn start(&self, _id: &ContainerId) -> Result<u32> {
+            Ok(1)
+        }
+        fn stop(&self, _id: &ContainerId) -> Result<()> {
+            Ok(())
+        }
+        fn exec(&self, _id: &ContainerId, _cmd: &[String]) -> Result<ExecOutput> {
+            std::thread::sleep(self.delay);
+            Ok(ExecOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code: self.exit_code,
+            })
+        }
+        fn remove(&self, _id: &ContainerId) -> Result<()> {
+            Ok(())
+        }
+        fn logs(&self, _id: &ContainerId) -> Result<String> {
+            Ok(String::new())
+        }
+        fn list(&self) -> Result<Vec<ContainerInfo>> {
+            Ok(Vec::new())
+        }
+        fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    fn probe(exit_code: i32, delay_ms: u64, timeout_secs: u64) -> HealthResult {
+        let backend = ProbeBackend {
+            exit_code,
+            delay: std::time::Duration::from_millis(delay_ms),
+        };
+        let spec = HealthcheckSpec {
+            command: vec!["true".into()],
+            timeout_secs,
+            ..HealthcheckSpec::default()
+        };
+        backend
+            .healthcheck(&ContainerId::new("probe"), &spec)
+            .unwrap()
+    }
+
+    #[test]
+    fn healthcheck_zero_exit_is_healthy() {
+        assert_eq!(probe(0, 0, 5), HealthResult::Healthy);
+    }
+
+    #[test]
+    fn healthcheck_nonzero_exit_is_unhealthy() {
+        let result = probe(3, 0, 5);
+        assert_eq!(
+            result,
+            HealthResult::Unhealthy("probe exited with code 3".into())
+        );
+        assert!(!result.is_healthy());
+    }
+
+    #[test]
+    fn healthcheck_timeout_is_unhealthy() {
+        assert_eq!(
+            probe(0, 20, 0),
+            HealthResult::Unhealthy("probe timed out after 0s".into())
+        );
+    }
+
     #[test]
     fn platform_info_os_not_empty() {
         let info = platform_info();
@@ -259,6 +901,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn select_backend_covers_every_flag_and_platform() {
+        use BackendKind::{Auto, Native, Vm};
+        for (requested, os, expected) in [
+            (Auto, "linux", Some(Native)),
+            (Auto, "macos", Some(Vm)),
+            (Auto, "windows", Some(Vm)),
+            (Native, "linux", Some(Native)),
+            (Native, "macos", None),
+            (Native, "windows", None),
+            (Vm, "linux", Some(Vm)),
+            (Vm, "macos", Some(Vm)),
+            (Vm, "windows", Some(Vm)),
+        ] {
+            let selected = select_backend(requested, os);
+            assert_eq!(
+                selected.as_ref().ok(),
+                expected.as_ref(),
+                "{requested} on {os}"
+            );
+        }
+        let error = select_backend(Native, "macos").unwrap_err().to_string();
+        assert!(error.contains("requires Linux"), "{error}");
+    }
+
+    #[test]
+    fn backend_kind_parses_flag_values() {
+        assert_eq!(
+            "native".parse::<BackendKind>().unwrap(),
+            BackendKind::Native
+        );
+        assert_eq!(" VM ".parse::<BackendKind>().unwrap(), BackendKind::Vm);
+        assert_eq!("auto".parse::<BackendKind>().unwrap(), BackendKind::Auto);
+        assert!("docker".parse::<BackendKind>().is_err());
+        assert_eq!(BackendKind::Vm.to_string(), "vm");
+    }
+
+    #[test]
+    fn create_backend_forces_vm_on_any_host() {
+        let dir = tempfile::tempdir().unwrap();
+        let backend = create_backend(
+            BackendKind::Vm,
+            dir.path().to_path_buf(),
+            dir.path().join("state.json"),
+            true,
+        );
+        assert!(backend.as_any().is::<vm::VMBackend>());
+    }
+
     #[test]
     fn platform_info_native_available_matches_target_os() {
         let info = platform_info();
@@ -277,7 +968,17 @@ mod tests {
             env: vec![("KEY".into(), "val".into())],
             memory_bytes: Some(128 * 1024 * 1024),
             cpu_shares: None,
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_limits: containust_core::namespace::ipc::IpcLimits::default(),
             readonly_rootfs: true,
+            stdin: false,
             volumes: vec![],
             port: Some(8080),
             ports: Vec::new(),
@@ -286,6 +987,12 @@ mod tests {
             restart: containust_common::types::RestartPolicy::default(),
             healthcheck: None,
             namespaces: containust_core::namespace::NamespaceConfig::default(),
+            stop: StopOptions::default(),
+            enforce_limits: true,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
         };
         assert_eq!(cfg.name, "test");
         assert!(cfg.readonly_rootfs);
@@ -300,7 +1007,17 @@ mod tests {
             env: Vec::new(),
             memory_bytes: None,
             cpu_shares: None,
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_limits: containust_core::namespace::ipc::IpcLimits::default(),
             readonly_rootfs: false,
+            stdin: false,
             volumes: Vec::new(),
             port: None,
             ports: Vec::new(),
@@ -309,6 +1026,12 @@ mod tests {
             restart: containust_common::types::RestartPolicy::default(),
             healthcheck: None,
             namespaces: containust_core::namespace::NamespaceConfig::default(),
+            stop: StopOptions::default(),
+            enforce_limits: true,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
         };
         assert_eq!(cfg.name, "minimal");
         assert!(cfg.image.is_empty());
@@ -326,7 +1049,17 @@ mod tests {
             env: vec![("A".into(), "1".into())],
             memory_bytes: Some(64 * 1024 * 1024),
             cpu_shares: Some(512),
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_limits: containust_core::namespace::ipc::IpcLimits::default(),
             readonly_rootfs: false,
+            stdin: false,
             volumes: vec!["/host:/guest".into()],
             port: Some(3000),
             ports: Vec::new(),
@@ -335,12 +1068,137 @@ mod tests {
             restart: containust_common::types::RestartPolicy::default(),
             healthcheck: None,
             namespaces: containust_core::namespace::NamespaceConfig::default(),
+            stop: StopOptions::default(),
+            enforce_limits: true,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
         };
         let cloned = cfg.clone();
         assert_eq!(cfg.name, cloned.name);
         assert_eq!(cfg.port, cloned.port);
     }
 
+    fn valid_config() -> ContainerConfig {
+        ContainerConfig {
+            name: "web-1.v2_a".into(),
+            image: "file:///web".into(),
+            command: vec!["/bin/web".into()],
+            env: vec![("A".into(), "1".into()), ("B".into(), "2".into())],
+            memory_bytes: None,
+            cpu_shares: None,
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_limits: containust_core::namespace::ipc::IpcLimits::default(),
+            readonly_rootfs: true,
+            stdin: false,
+            volumes: Vec::new(),
+            port: Some(8080),
+            ports: vec![8080],
+            port_mappings: vec![containust_common::types::PortMapping {
+                host: 9090,
+                container: 8080,
+            }],
+            network: "bridge".into(),
+            restart: containust_common::types::RestartPolicy::default(),
+            healthcheck: None,
+            namespaces: containust_core::namespace::NamespaceConfig::default(),
+            stop: StopOptions::default(),
+            enforce_limits: true,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
+        }
+    }
+
+    fn validation_error(config: &ContainerConfig) -> String {
+        config.validate().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn validate_accepts_well_formed_config() {
+        valid_config().validate().unwrap();
+        let image_default = ContainerConfig {
+            command: Vec::new(),
+            ..valid_config()
+        };
+        image_default.validate().unwrap();
+    }
+
+    #[test]
+    fn validate_rejects_bad_names() {
+        for name in ["", "-web", ".web", "web app", "web/1", "wéb"] {
+            let config = ContainerConfig {
+                name: name.into(),
+                ..valid_config()
+            };
+            let error = validation_error(&config);
+            assert!(error.contains("invalid name"), "{error}");
+        }
+    }
+
+    #[test]
+    fn validate_rejects_empty_image() {
+        let config = ContainerConfig {
+            image: "  ".into(),
+            ..valid_config()
+        };
+        assert!(validation_error(&config).contains("has no image"));
+    }
+
+    #[test]
+    fn validate_rejects_blank_executable() {
+        let config = ContainerConfig {
+            command: vec![String::new(), "--flag".into()],
+            ..valid_config()
+        };
+        assert!(validation_error(&config).contains("empty executable"));
+    }
+
+    #[test]
+    fn validate_rejects_duplicate_and_empty_env_keys() {
+        let duplicate = ContainerConfig {
+            env: vec![("A".into(), "1".into()), ("A".into(), "2".into())],
+            ..valid_config()
+        };
+        assert!(validation_error(&duplicate).contains("env key 'A' more than once"));
+        let empty = ContainerConfig {
+            env: vec![(String::new(), "1".into())],
+            ..valid_config()
+        };
+        assert!(validation_error(&empty).contains("empty env key"));
+    }
+
+    #[test]
+    fn validate_rejects_port_zero() {
+        let primary = ContainerConfig {
+            port: Some(0),
+            ..valid_config()
+        };
+        let listed = ContainerConfig {
+            ports: vec![80, 0],
+            ..valid_config()
+        };
+        let mapped = ContainerConfig {
+            port_mappings: vec![containust_common::types::PortMapping {
+                host: 0,
+                container: 80,
+            }],
+            ..valid_config()
+        };
+        for config in [primary, listed, mapped] {
+            assert!(validation_error(&config).contains("1..=65535"));
+        }
+    }
+
     #[test]
     fn container_info_can_be_constructed() {
         let id = ContainerId::new("abc-123");
@@ -351,6 +1209,10 @@ mod tests {
             pid: Some(42),
             image: "file:///app".into(),
             created_at: "2024-01-01T00:00:00Z".into(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
+            health: None,
         };
         assert_eq!(info.id, id);
         assert_eq!(info.name, "my-app");
@@ -368,6 +1230,10 @@ mod tests {
             pid: None,
             image: String::new(),
             created_at: String::new(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
+            health: None,
         };
         assert!(info.pid.is_none());
         assert_eq!(info.state, "stopped");
@@ -383,6 +1249,10 @@ mod tests {
             pid: None,
             image: "tar:///archive.tar".into(),
             created_at: "2024-06-15T12:00:00Z".into(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
+            health: None,
         };
         let cloned = info;
         assert_eq!(cloned.id, id);
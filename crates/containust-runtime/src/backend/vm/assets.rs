@@ -159,6 +159,7 @@ fn ensure_one(req: EnsureOne<'_>) -> Result<()> {
                  ~/.containust/cache/vm/, then retry air-gapped",
                 req.kind
             ),
+            retryable: false,
         });
     }
     eprintln!(
@@ -3,8 +3,9 @@
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 
-use containust_common::error::{ContainustError, Result};
+use containust_common::error::{ContainustError, Result, is_transient_status};
 use containust_common::types::Sha256Hash;
+use containust_image::fetch::{read_is_transient, request_is_transient};
 use fs2::FileExt;
 
 /// Exclusive lock held for the duration of a cache update.
@@ -91,6 +92,7 @@ fn fetch_into_staging(url: &str, staging: &Path, existing: u64) -> Result<()> {
         .map_err(|e| ContainustError::Network {
             url: url.to_string(),
             message: format!("failed to construct HTTP client: {e}"),
+            retryable: false,
         })?;
     let mut request = client.get(url);
     if existing > 0 {
@@ -99,6 +101,7 @@ fn fetch_into_staging(url: &str, staging: &Path, existing: u64) -> Result<()> {
     let response = request.send().map_err(|e| ContainustError::Network {
         url: url.to_string(),
         message: format!("failed to download: {e}"),
+        retryable: request_is_transient(&e),
     })?;
     let status = response.status();
     if status.as_u16() == 416 && existing > 0 {
@@ -109,6 +112,7 @@ fn fetch_into_staging(url: &str, staging: &Path, existing: u64) -> Result<()> {
         return Err(ContainustError::Network {
             url: url.to_string(),
             message: format!("HTTP {status} downloading asset"),
+            retryable: is_transient_status(status.as_u16()),
         });
     }
     let append = status.as_u16() == 206 && existing > 0;
@@ -147,6 +151,7 @@ fn stream_body(
             .map_err(|e| ContainustError::Network {
                 url: url.to_string(),
                 message: format!("stream interrupted after {written} bytes: {e}"),
+                retryable: read_is_transient(&e),
             })?;
         if read == 0 {
             break;
@@ -0,0 +1,59 @@
+//! Attach stream polled over the agent's `attach` RPC.
+//!
+//! Each poll sends the output offset already seen; the agent answers with
+//! new output, the next offset, and whether the main process still runs.
+//! The agent has no pipe to the main process, so input is refused.
+
+use std::path::PathBuf;
+
+use containust_common::clock::SharedClock;
+use containust_common::error::{ContainustError, Result};
+use containust_common::types::ContainerId;
+
+use super::{idle, response, rpc};
+use crate::attach::AttachStream;
+
+/// Agent-side stdio of one container.
+pub(super) struct VmAttach {
+    pub(super) vm_dir: PathBuf,
+    pub(super) project_id: String,
+    pub(super) id: ContainerId,
+    pub(super) offset: Option<u64>,
+    pub(super) running: bool,
+    pub(super) clock: SharedClock,
+}
+
+impl VmAttach {
+    /// Fetches output written since the last poll.
+    pub(super) fn poll(&mut self) -> Result<String> {
+        let response = rpc::send_rpc(
+            "attach",
+            &serde_json::json!({
+                "project": self.project_id,
+                "id": self.id.as_str(),
+                "offset": self.offset,
+            }),
+        )?;
+        let chunk = response::parse_attach_chunk(&response)?;
+        self.offset = Some(chunk.offset);
+        self.running = chunk.running;
+        idle::record_activity(&self.vm_dir, &self.project_id, None, self.clock.now());
+        Ok(chunk.output)
+    }
+}
+
+impl AttachStream for VmAttach {
+    fn read_output(&mut self) -> Result<Vec<u8>> {
+        self.poll().map(String::into_bytes)
+    }
+
+    fn write_input(&mut self, _data: &[u8]) -> Result<()> {
+        Err(ContainustError::Config {
+            message: format!("cannot write to {}: VM attach streams output only", self.id),
+        })
+    }
+
+    fn is_running(&mut self) -> Result<bool> {
+        Ok(self.running)
+    }
+}
@@ -0,0 +1,167 @@
+//! Agent version handshake after boot or adopt.
+//!
+//! A VM that outlives a `ctst` upgrade keeps serving the agent it booted
+//! with. The host asks the agent for its version and, on a mismatch,
+//! rebuilds the initramfs and reboots the VM instead of drifting silently.
+
+use containust_common::error::{ContainustError, Result};
+
+use super::rpc::send_rpc;
+
+/// How the running agent compares with the one this binary builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgentCheck {
+    /// The agent reports the expected version.
+    Current,
+    /// The agent reports another version, or none (an agent predating the
+    /// `version` RPC).
+    Stale {
+        /// Version the agent reported, if any.
+        reported: Option<String>,
+    },
+}
+
+/// Compares the agent's reported version with `expected`.
+#[must_use]
+pub fn check_version(expected: &str, reported: Option<&str>) -> AgentCheck {
+    if reported == Some(expected) {
+        AgentCheck::Current
+    } else {
+        AgentCheck::Stale {
+            reported: reported.map(str::to_string),
+        }
+    }
+}
+
+/// What [`ensure_current_agent`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeOutcome {
+    /// The running agent was already current.
+    Current,
+    /// The agent was stale; the VM was rebuilt and rebooted.
+    Rebuilt,
+}
+
+/// Queries the agent version and, when stale, calls `rebuild` once to
+/// rebuild the initramfs and reboot the VM, then checks again.
+///
+/// # Errors
+///
+/// Returns the `rebuild` error, or a configuration error when the agent
+/// is still stale after rebuilding.
+pub fn ensure_current_agent(
+    expected: &str,
+    mut query: impl FnMut() -> Option<String>,
+    rebuild: impl FnOnce() -> Result<()>,
+) -> Result<HandshakeOutcome> {
+    let AgentCheck::Stale { reported } = check_version(expected, query().as_deref()) else {
+        return Ok(HandshakeOutcome::Current);
+    };
+    tracing::warn!(
+        expected,
+        reported = reported.as_deref().unwrap_or("none"),
+        "VM agent is stale; rebuilding initramfs and rebooting the VM"
+    );
+    rebuild()?;
+    match check_version(expected, query().as_deref()) {
+        AgentCheck::Current => Ok(HandshakeOutcome::Rebuilt),
+        AgentCheck::Stale { reported } => Err(ContainustError::Config {
+            message: format!(
+                "VM agent reports version {} after a rebuild, expected {expected}; \
+                 run `ctst vm stop` and retry",
+                reported.as_deref().unwrap_or("none")
+            ),
+        }),
+    }
+}
+
+/// Asks the running agent for its version; `None` when it cannot say.
+#[must_use]
+pub fn query_agent_version() -> Option<String> {
+    send_rpc("version", &serde_json::json!({}))
+        .ok()?
+        .get("result")?
+        .get("version")?
+        .as_str()
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[test]
+    fn check_version_requires_exact_match() {
+        assert_eq!(
+            check_version("1.2.0+ab", Some("1.2.0+ab")),
+            AgentCheck::Current
+        );
+        assert_eq!(
+            check_version("1.2.0+ab", Some("1.2.0+cd")),
+            AgentCheck::Stale {
+                reported: Some("1.2.0+cd".into())
+            }
+        );
+        assert_eq!(
+            check_version("1.2.0+ab", None),
+            AgentCheck::Stale { reported: None }
+        );
+    }
+
+    #[test]
+    fn current_agent_skips_rebuild() {
+        let outcome = ensure_current_agent(
+            "v2",
+            || Some("v2".into()),
+            || unreachable!("current agent must not rebuild"),
+        )
+        .unwrap();
+        assert_eq!(outcome, HandshakeOutcome::Current);
+    }
+
+    #[test]
+    fn mismatch_rebuilds_and_reboots_once() {
+        let running = RefCell::new(Some("v1".to_string()));
+        let mut rebuilds = 0;
+        let outcome = ensure_current_agent(
+            "v2",
+            || running.borrow().clone(),
+            || {
+                rebuilds += 1;
+                *running.borrow_mut() = Some("v2".into());
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(outcome, HandshakeOutcome::Rebuilt);
+        assert_eq!(rebuilds, 1);
+    }
+
+    #[test]
+    fn agent_without_version_rpc_is_rebuilt() {
+        let running = RefCell::new(None);
+        let outcome = ensure_current_agent(
+            "v2",
+            || running.borrow().clone(),
+            || {
+                *running.borrow_mut() = Some("v2".to_string());
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(outcome, HandshakeOutcome::Rebuilt);
+    }
+
+    #[test]
+    fn still_stale_after_rebuild_fails() {
+        let error = ensure_current_agent("v2", || Some("v1".into()), || Ok(())).unwrap_err();
+        assert!(
+            error.to_string().contains("version v1 after a rebuild"),
+            "{error}"
+        );
+    }
+}
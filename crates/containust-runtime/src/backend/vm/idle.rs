@@ -0,0 +1,279 @@
+//! Idle auto-shutdown for the shared VM.
+//!
+//! The VM is shared by every project, so each project stamps its own
+//! activity record under `<vm_dir>/activity/<project>.json` on every agent
+//! RPC, together with the number of containers its last `list` saw
+//! running. The VM is idle once no project reports running containers and
+//! the newest stamp is older than `CONTAINUST_VM_IDLE_TIMEOUT` seconds
+//! (default 300, `0` = never). The next operation boots it again.
+//!
+//! The host only evaluates this when a backend lists containers, so the
+//! timeout is also passed to the guest on the kernel command line: the
+//! agent's watchdog powers the VM off by itself once no container runs and
+//! no lifecycle call arrived for that long, even with no CLI running.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+const ACTIVITY_DIR_NAME: &str = "activity";
+const IDLE_TIMEOUT_ENV: &str = "CONTAINUST_VM_IDLE_TIMEOUT";
+const IDLE_TIMEOUT_DEFAULT_SECS: u64 = 300;
+
+/// Last agent activity recorded for one project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectActivity {
+    /// Time of the last RPC (or `list` that still saw running containers).
+    pub last_activity: DateTime<Utc>,
+    /// Containers the project's last `list` reported as running.
+    #[serde(default)]
+    pub running: usize,
+}
+
+/// Returns the configured idle timeout, `None` when auto-shutdown is disabled.
+pub fn idle_timeout() -> Option<Duration> {
+    parse_idle_timeout(std::env::var(IDLE_TIMEOUT_ENV).ok().as_deref())
+}
+
+fn parse_idle_timeout(raw: Option<&str>) -> Option<Duration> {
+    let secs = match raw.map(str::trim) {
+        None | Some("") => IDLE_TIMEOUT_DEFAULT_SECS,
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            tracing::warn!(value, "invalid {IDLE_TIMEOUT_ENV}; using default");
+            IDLE_TIMEOUT_DEFAULT_SECS
+        }),
+    };
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Returns true when the VM has been idle for at least `timeout`.
+///
+/// A disabled timeout never expires; a clock that moved backwards is
+/// treated as fresh activity.
+pub fn idle_expired(
+    last_activity: DateTime<Utc>,
+    now: DateTime<Utc>,
+    timeout: Option<Duration>,
+) -> bool {
+    let Some(timeout) = timeout else {
+        return false;
+    };
+    (now - last_activity)
+        .to_std()
+        .is_ok_and(|idle| idle >= timeout)
+}
+
+/// Decides whether the VM should shut down given every project's activity.
+///
+/// Fails safe: no records, or any project with running containers, keeps
+/// the VM up.
+pub fn vm_is_idle(
+    activities: &[ProjectActivity],
+    now: DateTime<Utc>,
+    timeout: Option<Duration>,
+) -> bool {
+    if activities.iter().any(|activity| activity.running > 0) {
+        return false;
+    }
+    activities
+        .iter()
+        .map(|activity| activity.last_activity)
+        .max()
+        .is_some_and(|latest| idle_expired(latest, now, timeout))
+}
+
+/// Decides whether a backend shutting down should stop the VM.
+///
+/// Only a backend that booted the VM itself and was not asked to keep it
+/// alive stops it, and only while no project runs containers.
+pub fn stop_on_drop(keep_alive: bool, started_here: bool, activities: &[ProjectActivity]) -> bool {
+    !keep_alive && started_here && activities.iter().all(|activity| activity.running == 0)
+}
+
+/// Stamps activity for `project_id` at `now` (best effort).
+///
+/// `running` replaces the project's running-container count; `None` keeps
+/// the previously recorded count.
+pub fn record_activity(
+    vm_dir: &Path,
+    project_id: &str,
+    running: Option<usize>,
+    now: DateTime<Utc>,
+) {
+    let path = activity_path(vm_dir, project_id);
+    let running =
+        running.unwrap_or_else(|| read_activity(&path).map_or(0, |previous| previous.running));
+    let activity = ProjectActivity {
+        last_activity: now,
+        running,
+    };
+    write_activity(&path, &activity);
+}
+
+/// Publishes that `project_id` runs no containers, keeping its last stamp.
+pub fn mark_stopped(vm_dir: &Path, project_id: &str) {
+    let path = activity_path(vm_dir, project_id);
+    if let Some(previous) = read_activity(&path)
+        && previous.running > 0
+    {
+        let activity = ProjectActivity {
+            running: 0,
+            ..previous
+        };
+        write_activity(&path, &activity);
+    }
+}
+
+/// Reads the activity records of every project that used the VM.
+pub fn read_all_activity(vm_dir: &Path) -> Vec<ProjectActivity> {
+    let Ok(entries) = std::fs::read_dir(vm_dir.join(ACTIVITY_DIR_NAME)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| read_activity(&entry.path()))
+        .collect()
+}
+
+/// Removes all activity records once the VM is stopped.
+pub fn clear_activity(vm_dir: &Path) {
+    let _ = std::fs::remove_dir_all(vm_dir.join(ACTIVITY_DIR_NAME));
+}
+
+fn write_activity(path: &Path, activity: &ProjectActivity) {
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| {
+            serde_json::to_vec(activity)
+                .map_err(std::io::Error::other)
+                .and_then(|body| std::fs::write(path, body))
+        });
+    if let Err(error) = written {
+        tracing::debug!(path = %path.display(), %error, "failed to record VM activity");
+    }
+}
+
+fn read_activity(path: &Path) -> Option<ProjectActivity> {
+    let raw = std::fs::read(path).ok()?;
+    serde_json::from_slice(&raw).ok()
+}
+
+fn activity_path(vm_dir: &Path, project_id: &str) -> PathBuf {
+    vm_dir
+        .join(ACTIVITY_DIR_NAME)
+        .join(format!("{project_id}.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).expect("timestamp")
+    }
+
+    fn activity(secs: i64, running: usize) -> ProjectActivity {
+        ProjectActivity {
+            last_activity: at(secs),
+            running,
+        }
+    }
+
+    #[test]
+    fn stop_on_drop_only_for_ephemeral_vm_started_here() {
+        let idle = [activity(1_000, 0)];
+        assert!(stop_on_drop(false, true, &idle));
+        assert!(stop_on_drop(false, true, &[]));
+        assert!(!stop_on_drop(true, true, &idle), "keep-alive VM survives");
+        assert!(!stop_on_drop(false, false, &idle), "adopted VM survives");
+    }
+
+    #[test]
+    fn stop_on_drop_spares_vm_with_running_containers() {
+        let busy = [activity(1_000, 0), activity(1_000, 2)];
+        assert!(!stop_on_drop(false, true, &busy));
+    }
+
+    #[test]
+    fn parse_idle_timeout_defaults_overrides_and_disables() {
+        let default = Some(Duration::from_secs(IDLE_TIMEOUT_DEFAULT_SECS));
+        assert_eq!(parse_idle_timeout(None), default);
+        assert_eq!(parse_idle_timeout(Some("bogus")), default);
+        assert_eq!(
+            parse_idle_timeout(Some("60")),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(parse_idle_timeout(Some("0")), None);
+    }
+
+    #[test]
+    fn idle_expired_after_timeout_elapses() {
+        let timeout = Some(Duration::from_secs(300));
+        assert!(!idle_expired(at(1_000), at(1_299), timeout));
+        assert!(idle_expired(at(1_000), at(1_300), timeout));
+    }
+
+    #[test]
+    fn idle_expired_never_when_disabled() {
+        assert!(!idle_expired(at(0), at(1_000_000), None));
+    }
+
+    #[test]
+    fn idle_expired_ignores_clock_going_backwards() {
+        assert!(!idle_expired(
+            at(2_000),
+            at(1_000),
+            Some(Duration::from_secs(1))
+        ));
+    }
+
+    #[test]
+    fn vm_is_idle_uses_newest_project_activity() {
+        let timeout = Some(Duration::from_secs(300));
+        let records = [activity(1_000, 0), activity(1_200, 0)];
+        assert!(!vm_is_idle(&records, at(1_400), timeout));
+        assert!(vm_is_idle(&records, at(1_500), timeout));
+    }
+
+    #[test]
+    fn vm_is_idle_false_while_any_project_runs_containers() {
+        let records = [activity(0, 0), activity(0, 2)];
+        assert!(!vm_is_idle(
+            &records,
+            at(100_000),
+            Some(Duration::from_secs(1))
+        ));
+    }
+
+    #[test]
+    fn vm_is_idle_false_without_records() {
+        assert!(!vm_is_idle(&[], at(100_000), Some(Duration::from_secs(1))));
+    }
+
+    #[test]
+    fn record_activity_keeps_running_count_unless_replaced() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        record_activity(dir.path(), "proj", Some(3), at(10));
+        record_activity(dir.path(), "proj", None, at(20));
+        record_activity(dir.path(), "other", None, at(30));
+        let mut recorded = read_all_activity(dir.path());
+        recorded.sort_by_key(|a| a.last_activity);
+        assert_eq!(recorded, vec![activity(20, 3), activity(30, 0)]);
+        clear_activity(dir.path());
+        assert!(read_all_activity(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn mark_stopped_zeroes_running_and_keeps_stamp() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        record_activity(dir.path(), "proj", Some(2), at(10));
+        let before = read_all_activity(dir.path());
+        mark_stopped(dir.path(), "proj");
+        let after = read_all_activity(dir.path());
+        assert_eq!(after[0].running, 0);
+        assert_eq!(after[0].last_activity, before[0].last_activity);
+    }
+}
@@ -3,9 +3,13 @@
 //! Takes the stock Alpine Linux initramfs, unpacks it, injects
 //! a custom init script and the Containust agent, then repacks
 //! it as a gzip-compressed cpio archive.
+//!
+//! Builds are deterministic: entries are written sorted by name, inodes
+//! follow that order, and the gzip header carries no timestamp, so the
+//! same base and scripts always produce the same bytes.
 
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use containust_common::error::{ContainustError, Result};
 
@@ -85,14 +89,19 @@ mkdir -p "$SD" "$LD" "$RD"
 # Write standalone handler that nc -e invokes per connection
 cat > /tmp/handler.sh << 'HANDLER_EOF'
 #!/bin/sh
-SD="/tmp/containust/containers"
-LD="/tmp/containust/logs"
-RD="/tmp/containust/rootfs"
+ROOT="${CONTAINUST_AGENT_ROOT:-/tmp/containust}"
 
 gen_id() { cat /proc/sys/kernel/random/uuid 2>/dev/null | tr -d '-' | head -c 16; }
 # Protocol v1: wrap every response with echoed request id.
 wrap() { printf '%s\n' "{\"v\":1,\"id\":\"$req_id\",$1}"; }
 wrap_err() { wrap "\"error\":\"$1\""; }
+# Prints file $1 as the body of a JSON string, keeping its newlines.
+json_esc() {
+    local s
+    s=$(tr -d '\000-\010\013-\037' < "$1" | tr '\t' ' ' | sed -e 's/\\/\\\\/g' -e 's/"/\\"/g' | sed ':a;N;$!ba;s/\n/\\n/g')
+    [ "$(tail -c 1 "$1" | wc -l)" -eq 1 ] && s="$s\\n"
+    printf '%s' "$s"
+}
 
 h_create() {
     local id=$(gen_id)
@@ -101,7 +110,11 @@ h_create() {
     local pt=$(echo "$1"|sed -n 's/.*"port" *: *\([0-9][0-9]*\).*/\1/p')
     local cm=$(echo "$1"|sed -n 's/.*"command" *: *\(\[[^]]*\]\).*/\1/p')
     [ -z "$cm" ] && cm='["sh"]'
+    local ss=$(echo "$1"|sed -n 's/.*"stop_signal" *: *"\(SIG\)\{0,1\}\([A-Z0-9]*\)".*/\2/p')
+    local st=$(echo "$1"|sed -n 's/.*"stop_timeout_ms" *: *\([0-9][0-9]*\).*/\1/p')
     mkdir -p "$SD/$id"
+    [ -n "$ss" ] && echo "$ss" > "$SD/$id/stop_signal"
+    [ -n "$st" ] && echo "$st" > "$SD/$id/stop_timeout_ms"
     echo "{\"id\":\"$id\",\"name\":\"$nm\",\"image\":\"$im\",\"port\":\"$pt\",\"command\":$cm,\"state\":\"created\",\"created_at\":\"$(date -u +%Y-%m-%dT%H:%M:%SZ)\"}" > "$SD/$id/meta.json"
     local r="$RD/$id"
     mkdir -p "$r/bin" "$r/sbin" "$r/usr/bin" "$r/usr/sbin" "$r/usr/local/bin" "$r/lib" "$r/etc" "$r/proc" "$r/sys" "$r/dev" "$r/tmp" "$r/var" "$r/root" "$r/home" "$r/run"
@@ -136,9 +149,18 @@ h_start() {
     fi
     mount -t proc proc "$r/proc" 2>/dev/null
     mount --bind /dev "$r/dev" 2>/dev/null
-    chroot "$r" /bin/sh /tmp/run.sh >"$lf" 2>&1 &
-    local p=$!
-    echo "$p" > "$SD/$id/pid"
+    rm -f "$SD/$id/pid" "$SD/$id/exit"
+    # The subshell waits on the main process to record its exit status.
+    (
+        chroot "$r" /bin/sh /tmp/run.sh >"$lf" 2>&1 &
+        echo "$!" > "$SD/$id/pid.tmp" && mv "$SD/$id/pid.tmp" "$SD/$id/pid"
+        wait "$!"
+        echo "$?" > "$SD/$id/exit"
+    ) </dev/null >/dev/null 2>&1 &
+    local n=0
+    while [ ! -f "$SD/$id/pid" ] && [ "$n" -lt 100 ]; do sleep 0.05; n=$((n + 1)); done
+    [ ! -f "$SD/$id/pid" ] && wrap_err "failed to start $id" && return
+    local p=$(cat "$SD/$id/pid")
     sed -i 's/"state":"[^"]*"/"state":"running"/' "$SD/$id/meta.json"
     wrap "\"result\":{\"pid\":$p}"
 }
@@ -146,11 +168,27 @@ h_start() {
 h_stop() {
     local id=$(echo "$1"|sed -n 's/.*"id" *: *"\([^"]*\)".*/\1/p')
     [ ! -d "$SD/$id" ] && wrap_err "not found: $id" && return
-    [ -f "$SD/$id/pid" ] && { kill $(cat "$SD/$id/pid") 2>/dev/null; sleep 1; kill -9 $(cat "$SD/$id/pid") 2>/dev/null; rm "$SD/$id/pid"; }
+    # Request options win over those recorded at create.
+    local sg=$(echo "$1"|sed -n 's/.*"signal" *: *"\(SIG\)\{0,1\}\([A-Z0-9]*\)".*/\2/p')
+    [ -z "$sg" ] && sg=$(cat "$SD/$id/stop_signal" 2>/dev/null)
+    [ -z "$sg" ] && sg=TERM
+    local ms=$(echo "$1"|sed -n 's/.*"timeout_ms" *: *\([0-9][0-9]*\).*/\1/p')
+    [ -z "$ms" ] && ms=$(cat "$SD/$id/stop_timeout_ms" 2>/dev/null)
+    [ -z "$ms" ] && ms=2000
+    echo "$1" | grep -q '"force" *: *true' && ms=0
+    local esc=false
+    if [ -f "$SD/$id/pid" ]; then
+        local p=$(cat "$SD/$id/pid")
+        [ "$ms" -gt 0 ] && kill -s "$sg" "$p" 2>/dev/null
+        local n=0
+        while [ "$n" -lt "$ms" ] && kill -0 "$p" 2>/dev/null; do sleep 0.1; n=$((n + 100)); done
+        kill -0 "$p" 2>/dev/null && kill -9 "$p" && esc=true
+        rm "$SD/$id/pid"
+    fi
     local r="$RD/$id"
     umount "$r/dev" 2>/dev/null; umount "$r/proc" 2>/dev/null
     sed -i 's/"state":"[^"]*"/"state":"stopped"/' "$SD/$id/meta.json"
-    wrap "\"result\":\"ok\""
+    wrap "\"result\":{\"escalated\":$esc}"
 }
 
 h_exec() {
@@ -167,6 +205,26 @@ h_exec() {
     wrap "\"result\":{\"stdout\":\"$o\",\"stderr\":\"$e\",\"exit_code\":$rc}"
 }
 
+h_rename() {
+    local id=$(echo "$1"|sed -n 's/.*"id" *: *"\([^"]*\)".*/\1/p')
+    local nm=$(echo "$1"|sed -n 's/.*"name" *: *"\([^"]*\)".*/\1/p')
+    [ ! -d "$SD/$id" ] && wrap_err "not found: $id" && return
+    [ -z "$nm" ] && wrap_err "missing name" && return
+    # State, rootfs, logs and the pid file are keyed by id, so they stay put.
+    sed -i "s/\"name\":\"[^\"]*\"/\"name\":\"$nm\"/" "$SD/$id/meta.json"
+    wrap "\"result\":\"ok\""
+}
+
+h_signal() {
+    local id=$(echo "$1"|sed -n 's/.*"id" *: *"\([^"]*\)".*/\1/p')
+    [ ! -d "$SD/$id" ] && wrap_err "not found: $id" && return
+    local sg=$(echo "$1"|sed -n 's/.*"signal" *: *"\(SIG\)\{0,1\}\([A-Z0-9]*\)".*/\2/p')
+    [ -z "$sg" ] && wrap_err "missing signal" && return
+    [ ! -f "$SD/$id/pid" ] && wrap_err "container $id is not running" && return
+    kill -s "$sg" "$(cat "$SD/$id/pid")" 2>/dev/null || { wrap_err "cannot send SIG$sg to $id"; return; }
+    wrap "\"result\":\"ok\""
+}
+
 h_logs() {
     local id=$(echo "$1"|sed -n 's/.*"id" *: *"\([^"]*\)".*/\1/p')
     local lf="$LD/$id.log"
@@ -178,6 +236,37 @@ h_logs() {
     fi
 }
 
+h_inspect() {
+    local id=$(echo "$1"|sed -n 's/.*"id" *: *"\([^"]*\)".*/\1/p')
+    [ ! -f "$SD/$id/meta.json" ] && wrap_err "not found: $id" && return
+    local mt=$(head -c 8192 "$SD/$id/meta.json"|sed 's/}[[:space:]]*$//')
+    local p=null
+    [ -f "$SD/$id/pid" ] && p=$(cat "$SD/$id/pid")
+    local ec=null
+    [ -f "$SD/$id/exit" ] && ec=$(cat "$SD/$id/exit")
+    if [ "$ec" != null ] || { [ "$p" != null ] && ! kill -0 "$p" 2>/dev/null; }; then
+        mt=$(printf '%s' "$mt"|sed 's/"state":"running"/"state":"exited"/')
+    fi
+    wrap "\"result\":$mt,\"pid\":$p,\"exit_code\":$ec,\"rootfs_path\":\"$RD/$id\",\"log_path\":\"$LD/$id.log\"}"
+}
+
+h_attach() {
+    local id=$(echo "$1"|sed -n 's/.*"id" *: *"\([^"]*\)".*/\1/p')
+    [ ! -d "$SD/$id" ] && wrap_err "not found: $id" && return
+    local lf="$LD/$id.log"
+    [ -f "$lf" ] || : > "$lf"
+    local sz=$(wc -c < "$lf")
+    # Without an offset, answer from the end of the existing output.
+    local off=$(echo "$1"|sed -n 's/.*"offset" *: *\([0-9][0-9]*\).*/\1/p')
+    [ -z "$off" ] || [ "$off" -gt "$sz" ] && off=$sz
+    tail -c +$((off + 1)) "$lf" | head -c 65536 > "/tmp/a.$id"
+    local n=$(wc -c < "/tmp/a.$id")
+    local o=$(json_esc "/tmp/a.$id"); rm -f "/tmp/a.$id"
+    local run=false
+    [ -f "$SD/$id/pid" ] && [ ! -f "$SD/$id/exit" ] && kill -0 "$(cat "$SD/$id/pid")" 2>/dev/null && run=true
+    wrap "\"result\":{\"output\":\"$o\",\"offset\":$((off + n)),\"running\":$run}"
+}
+
 h_list() {
     local res='"result":{"containers":['
     local f=1
@@ -202,32 +291,63 @@ h_remove() {
 # TCP write side open while reading the reply, so byte-count reads deadlock.
 line=$(head -n 1)
 [ "${#line}" -gt 65536 ] && req_id="0" && wrap_err "request exceeds 65536 bytes" && exit 0
-req_id=$(printf '%s' "$line" | sed -n 's/.*"id" *: *"\([^"]*\)".*/\1/p')
+# The request id precedes params, whose own "id" names the container.
+req_id=$(printf '%s' "$line" | sed -n 's/^[^{]*{[^{]*"id" *: *"\([^"]*\)".*/\1/p')
 [ -z "$req_id" ] && req_id="0"
 req_v=$(printf '%s' "$line" | sed -n 's/.*"v" *: *\([0-9][0-9]*\).*/\1/p')
 [ "$req_v" != "1" ] && wrap_err "unsupported protocol version" && exit 0
 project=$(printf '%s' "$line" | sed -n 's/.*"project" *: *"\([0-9a-f][0-9a-f]*\)".*/\1/p')
 [ -z "$project" ] && project="default"
-BASE="/tmp/containust/projects/$project"
+BASE="$ROOT/projects/$project"
 SD="$BASE/containers"
 LD="$BASE/logs"
 RD="$BASE/rootfs"
 mkdir -p "$SD" "$LD" "$RD"
 m=$(printf '%s' "$line" | sed -n 's/.*"method" *: *"\([^"]*\)".*/\1/p')
+# Lifecycle calls reset the idle watchdog; ping/version/list are polling.
+case "$m" in
+    create|start|stop|rename|signal|exec|logs|remove) touch "$ROOT/activity";;
+esac
 case "$m" in
     ping) wrap "\"result\":\"pong\"";;
+    version) wrap "\"result\":{\"version\":\"@AGENT_VERSION@\"}";;
     create) h_create "$line";;
     start) h_start "$line";;
     stop) h_stop "$line";;
+    rename) h_rename "$line";;
+    signal) h_signal "$line";;
     exec) h_exec "$line";;
     logs) h_logs "$line";;
     list) h_list;;
+    inspect) h_inspect "$line";;
+    attach) h_attach "$line";;
     remove) h_remove "$line";;
     *) wrap_err "unknown: $m";;
 esac
 HANDLER_EOF
 chmod 755 /tmp/handler.sh
 
+# Idle watchdog: powers the VM off once no container has run and no
+# lifecycle call arrived for containust.idle_timeout seconds (0 = never).
+# Runs in the guest so a detached VM shuts down with no CLI around.
+IDLE=$(sed -n 's/.*containust\.idle_timeout=\([0-9][0-9]*\).*/\1/p' /proc/cmdline)
+if [ -n "$IDLE" ] && [ "$IDLE" -gt 0 ]; then
+    touch /tmp/containust/activity
+    (
+        while sleep 15; do
+            for pf in /tmp/containust/projects/*/containers/*/pid; do
+                [ -f "$pf" ] && kill -0 "$(cat "$pf")" 2>/dev/null \
+                    && touch /tmp/containust/activity && break
+            done
+            last=$(stat -c %Y /tmp/containust/activity 2>/dev/null || echo 0)
+            if [ $(( $(date +%s) - last )) -ge "$IDLE" ]; then
+                echo "containust-agent: idle for ${IDLE}s, powering off"
+                poweroff -f
+            fi
+        done
+    ) &
+fi
+
 echo "containust-agent: listening on port $PORT"
 # BusyBox nc has no -e; the cat|nc|handler>fifo bridge is the portable pattern.
 while true; do
@@ -240,6 +360,36 @@ while true; do
 done
 "##;
 
+/// Placeholder in [`AGENT_SCRIPT`] replaced by [`agent_version`].
+const VERSION_PLACEHOLDER: &str = "@AGENT_VERSION@";
+
+/// Version the agent built into this binary reports over the `version`
+/// RPC: the crate version plus a hash of the init and agent scripts, so
+/// any script change yields a new version.
+#[must_use]
+pub fn agent_version() -> String {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write as _;
+
+    let mut hasher = Sha256::new();
+    hasher.update(INIT_SCRIPT.as_bytes());
+    hasher.update(AGENT_SCRIPT.as_bytes());
+    let digest = hasher.finalize();
+    let mut version = format!("{}+", env!("CARGO_PKG_VERSION"));
+    for byte in &digest[..6] {
+        let _ = write!(version, "{byte:02x}");
+    }
+    version
+}
+
+/// The agent script with its version stamped in.
+fn agent_script() -> String {
+    AGENT_SCRIPT.replace(VERSION_PLACEHOLDER, &agent_version())
+}
+
+/// Directories injected when the base lacks them.
+const INJECTED_DIRS: [&str; 7] = ["tmp", "run", "var", "root", "proc", "sys", "dev"];
+
 /// Builds a custom initramfs by unpacking the Alpine base, injecting
 /// directory entries, the Containust init and agent scripts, and repacking.
 ///
@@ -252,25 +402,19 @@ pub fn build_initramfs(base_initramfs: &Path, output: &Path) -> Result<()> {
         path: base_initramfs.to_path_buf(),
         source: e,
     })?;
+    let entries = staged_entries(&base_data)?;
 
     let output_file = std::fs::File::create(output).map_err(|e| ContainustError::Io {
         path: output.to_path_buf(),
         source: e,
     })?;
 
-    let gz_encoder = flate2::write::GzEncoder::new(output_file, flate2::Compression::fast());
+    let gz_encoder = flate2::GzBuilder::new()
+        .mtime(0)
+        .write(output_file, flate2::Compression::fast());
     let mut cpio = CpioWriter::new(gz_encoder);
 
-    unpack_and_repack_base(&base_data, &mut cpio)?;
-
-    for dir in &["tmp", "run", "var", "root", "proc", "sys", "dev"] {
-        cpio.write_dir(dir)?;
-    }
-
-    cpio.write_entry("init", 0o100_755, INIT_SCRIPT.as_bytes())?;
-    cpio.write_entry("sbin/containust-init", 0o100_755, INIT_SCRIPT.as_bytes())?;
-    cpio.write_entry("sbin/containust-agent", 0o100_755, AGENT_SCRIPT.as_bytes())?;
-
+    write_entries(&mut cpio, &entries)?;
     cpio.write_trailer()?;
 
     let gz = cpio.finish();
@@ -282,11 +426,95 @@ pub fn build_initramfs(base_initramfs: &Path, output: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Unpacks the gzip-compressed cpio base initramfs and writes all
-/// entries into the new cpio archive.
-fn unpack_and_repack_base<W: Write>(data: &[u8], writer: &mut CpioWriter<W>) -> Result<()> {
+/// Builds `output` from `base_initramfs` unless it already exists and
+/// was built from the same inputs; returns whether it was rebuilt.
+///
+/// The inputs' [`build_key`] is kept next to the output, which is only
+/// sound because builds are deterministic.
+///
+/// # Errors
+///
+/// Returns an error if the base cannot be read or the build fails.
+pub fn ensure_initramfs(base_initramfs: &Path, output: &Path) -> Result<bool> {
+    let key = build_key(base_initramfs)?;
+    let key_path = key_path(output);
+    let current = std::fs::read_to_string(&key_path).ok();
+    if output.exists() && current.as_deref().map(str::trim) == Some(key.as_str()) {
+        return Ok(false);
+    }
+    let _ = std::fs::remove_file(&key_path);
+    build_initramfs(base_initramfs, output)?;
+    std::fs::write(&key_path, key).map_err(|e| ContainustError::Io {
+        path: key_path,
+        source: e,
+    })?;
+    Ok(true)
+}
+
+/// SHA-256 of the base initramfs and the [`agent_version`], which covers
+/// every input of [`build_initramfs`].
+///
+/// # Errors
+///
+/// Returns an error if the base initramfs cannot be read.
+pub fn build_key(base_initramfs: &Path) -> Result<String> {
+    use sha2::{Digest, Sha256};
+    use std::fmt::Write as _;
+
+    let base_data = std::fs::read(base_initramfs).map_err(|e| ContainustError::Io {
+        path: base_initramfs.to_path_buf(),
+        source: e,
+    })?;
+    let mut hasher = Sha256::new();
+    hasher.update(&base_data);
+    hasher.update(agent_version().as_bytes());
+    let mut key = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        let _ = write!(key, "{byte:02x}");
+    }
+    Ok(key)
+}
+
+/// Where [`ensure_initramfs`] records the build key of `output`.
+fn key_path(output: &Path) -> PathBuf {
+    let mut name = output.file_name().unwrap_or_default().to_os_string();
+    name.push(".key");
+    output.with_file_name(name)
+}
+
+/// Every entry of the output archive, sorted by name: the base entries
+/// minus the ones Containust replaces, the missing directories, and the
+/// init and agent scripts.
+fn staged_entries(base_data: &[u8]) -> Result<Vec<CpioEntry>> {
+    let mut entries = base_entries(base_data)?;
+    for dir in INJECTED_DIRS {
+        if !entries.iter().any(|entry| entry.name == dir) {
+            entries.push(CpioEntry::new(dir, 0o040_755, Vec::new()));
+        }
+    }
+    let init = INIT_SCRIPT.as_bytes();
+    entries.push(CpioEntry::new("init", 0o100_755, init.to_vec()));
+    entries.push(CpioEntry::new(
+        "sbin/containust-init",
+        0o100_755,
+        init.to_vec(),
+    ));
+    entries.push(CpioEntry::new(
+        "sbin/containust-agent",
+        0o100_755,
+        agent_script().into_bytes(),
+    ));
+    // A parent sorts before its children, so directories still come first.
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(entries)
+}
+
+/// Unpacks the gzip-compressed cpio base initramfs, dropping the
+/// entries Containust replaces.
+fn base_entries(data: &[u8]) -> Result<Vec<CpioEntry>> {
     let decoder = flate2::read::GzDecoder::new(data);
     let mut reader = CpioReader::new(decoder);
+    let mut entries = Vec::new();
 
     while let Some(entry) = reader.next_entry()? {
         if entry.name == "TRAILER!!!" {
@@ -298,9 +526,29 @@ fn unpack_and_repack_base<W: Write>(data: &[u8], writer: &mut CpioWriter<W>) ->
         {
             continue;
         }
-        writer.write_entry(&entry.name, entry.mode, &entry.data)?;
+        entries.push(entry);
     }
 
+    Ok(entries)
+}
+
+/// Writes `entries` in order, keeping symlink targets and device numbers.
+fn write_entries<W: Write>(writer: &mut CpioWriter<W>, entries: &[CpioEntry]) -> Result<()> {
+    for entry in entries {
+        match entry.mode & MODE_TYPE_MASK {
+            MODE_SYMLINK => {
+                writer.write_symlink(&entry.name, &String::from_utf8_lossy(&entry.data))?;
+            }
+            MODE_CHAR_DEVICE | MODE_BLOCK_DEVICE => {
+                writer.write_node(
+                    &entry.name,
+                    entry.mode,
+                    (entry.rdev_major, entry.rdev_minor),
+                )?;
+            }
+            _ => writer.write_entry(&entry.name, entry.mode, &entry.data)?,
+        }
+    }
     Ok(())
 }
 
@@ -308,6 +556,23 @@ fn unpack_and_repack_base<W: Write>(data: &[u8], writer: &mut CpioWriter<W>) ->
 // CPIO newc format writer
 // ---------------------------------------------------------------------------
 
+/// Magic of the newc format.
+const NEWC_MAGIC: &str = "070701";
+/// File type bits of `mode`, and the types the writer distinguishes.
+const MODE_TYPE_MASK: u32 = 0o170_000;
+const MODE_REGULAR: u32 = 0o100_000;
+const MODE_SYMLINK: u32 = 0o120_000;
+const MODE_CHAR_DEVICE: u32 = 0o020_000;
+const MODE_BLOCK_DEVICE: u32 = 0o060_000;
+
+/// Per-entry header fields besides name and size.
+#[derive(Clone, Copy)]
+struct EntryMeta {
+    mode: u32,
+    rdev_major: u32,
+    rdev_minor: u32,
+}
+
 struct CpioWriter<W: Write> {
     inner: W,
     ino: u32,
@@ -324,24 +589,50 @@ impl<W: Write> CpioWriter<W> {
     }
 
     /// Writes a directory entry (mode 040755, zero data).
+    #[cfg(test)]
     fn write_dir(&mut self, name: &str) -> Result<()> {
         self.write_entry(name, 0o040_755, &[])
     }
 
     fn write_entry(&mut self, name: &str, mode: u32, data: &[u8]) -> Result<()> {
+        let meta = EntryMeta {
+            mode,
+            rdev_major: 0,
+            rdev_minor: 0,
+        };
+        self.write_with_meta(name, meta, data)
+    }
+
+    /// Writes a symlink entry (mode 0120777) whose data is the target.
+    fn write_symlink(&mut self, name: &str, target: &str) -> Result<()> {
+        self.write_entry(name, MODE_SYMLINK | 0o777, target.as_bytes())
+    }
+
+    /// Writes a character or block device node with its `(major, minor)`
+    /// device numbers.
+    fn write_node(&mut self, name: &str, mode: u32, (major, minor): (u32, u32)) -> Result<()> {
+        let meta = EntryMeta {
+            mode,
+            rdev_major: major,
+            rdev_minor: minor,
+        };
+        self.write_with_meta(name, meta, &[])
+    }
+
+    fn write_with_meta(&mut self, name: &str, meta: EntryMeta, data: &[u8]) -> Result<()> {
         self.ino += 1;
         let name_nul = format!("{name}\0");
         let namesize = name_nul.len();
         let filesize = data.len();
 
         let header = format!(
-            "070701\
+            "{NEWC_MAGIC}\
              {:08X}{:08X}{:08X}{:08X}\
              {:08X}{:08X}{:08X}{:08X}\
              {:08X}{:08X}{:08X}{:08X}\
              {:08X}",
             self.ino,
-            mode,
+            meta.mode,
             0u32,
             0u32,
             1u32,
@@ -349,8 +640,8 @@ impl<W: Write> CpioWriter<W> {
             filesize,
             0u32,
             0u32,
-            0u32,
-            0u32,
+            meta.rdev_major,
+            meta.rdev_minor,
             namesize,
             0u32,
         );
@@ -394,12 +685,30 @@ impl<W: Write> CpioWriter<W> {
 // CPIO newc format reader
 // ---------------------------------------------------------------------------
 
+/// Magic of newc with a checksum of each regular file's data.
+const NEWC_CRC_MAGIC: &str = "070702";
+
 struct CpioEntry {
     name: String,
     mode: u32,
+    rdev_major: u32,
+    rdev_minor: u32,
     data: Vec<u8>,
 }
 
+impl CpioEntry {
+    /// An entry that is not a device node.
+    fn new(name: &str, mode: u32, data: Vec<u8>) -> Self {
+        Self {
+            name: name.to_string(),
+            mode,
+            rdev_major: 0,
+            rdev_minor: 0,
+            data,
+        }
+    }
+}
+
 struct CpioReader<R: Read> {
     inner: R,
 }
@@ -438,13 +747,21 @@ impl<R: Read> CpioReader<R> {
         }
 
         let magic = std::str::from_utf8(&header[..6]).unwrap_or("");
-        if magic != "070701" {
-            return Err(ContainustError::Config {
-                message: format!("invalid CPIO magic: {magic}"),
-            });
-        }
+        let has_checksum = match magic {
+            NEWC_MAGIC => false,
+            NEWC_CRC_MAGIC => true,
+            _ => {
+                return Err(ContainustError::Config {
+                    message: format!(
+                        "invalid CPIO magic: {magic} (expected {NEWC_MAGIC} or {NEWC_CRC_MAGIC})"
+                    ),
+                });
+            }
+        };
 
         let mode = parse_hex(&header[14..22]);
+        let rdev_major = parse_hex(&header[78..86]);
+        let rdev_minor = parse_hex(&header[86..94]);
         let filesize = parse_hex(&header[54..62]) as usize;
         let namesize = parse_hex(&header[94..102]) as usize;
 
@@ -461,7 +778,32 @@ impl<R: Read> CpioReader<R> {
         }
         self.skip_padding(filesize);
 
-        Ok(Some(CpioEntry { name, mode, data }))
+        if has_checksum && mode & MODE_TYPE_MASK == MODE_REGULAR {
+            verify_checksum(&name, &data, parse_hex(&header[102..110]))?;
+        }
+        Ok(Some(CpioEntry {
+            name,
+            mode,
+            rdev_major,
+            rdev_minor,
+            data,
+        }))
+    }
+}
+
+/// Checks a `070702` entry: its checksum is the wrapping sum of the data bytes.
+fn verify_checksum(name: &str, data: &[u8], expected: u32) -> Result<()> {
+    let actual = data
+        .iter()
+        .fold(0_u32, |sum, byte| sum.wrapping_add(u32::from(*byte)));
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(ContainustError::Config {
+            message: format!(
+                "CPIO checksum mismatch for {name}: expected {expected:08X}, got {actual:08X}"
+            ),
+        })
     }
 }
 
@@ -475,6 +817,7 @@ mod tests {
     #![cfg_attr(test, allow(clippy::expect_used, clippy::unwrap_used))]
 
     use super::*;
+    use crate::backend::StopOutcome;
     use std::io::Cursor;
 
     #[test]
@@ -559,6 +902,127 @@ mod tests {
         assert!(!inner.into_inner().is_empty());
     }
 
+    /// Encodes one `070702` entry whose header carries `check`.
+    fn crc_entry(name: &str, mode: u32, data: &[u8], check: u32) -> Vec<u8> {
+        let name_nul = format!("{name}\0");
+        let mut out = format!(
+            "070702{:08X}{mode:08X}{:08X}{:08X}{:08X}{:08X}{:08X}\
+             {:08X}{:08X}{:08X}{:08X}{:08X}{check:08X}",
+            1,
+            0,
+            0,
+            1,
+            0,
+            data.len(),
+            0,
+            0,
+            0,
+            0,
+            name_nul.len(),
+        )
+        .into_bytes();
+        out.extend_from_slice(name_nul.as_bytes());
+        out.resize(out.len().next_multiple_of(4), 0);
+        out.extend_from_slice(data);
+        out.resize(out.len().next_multiple_of(4), 0);
+        out
+    }
+
+    fn round_trip(write: impl FnOnce(&mut CpioWriter<Vec<u8>>)) -> CpioEntry {
+        let mut writer = CpioWriter::new(Vec::new());
+        write(&mut writer);
+        writer.write_trailer().unwrap();
+        CpioReader::new(Cursor::new(writer.finish()))
+            .next_entry()
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn cpio_symlink_round_trips_with_target() {
+        let entry = round_trip(|w| w.write_symlink("bin/sh", "busybox").unwrap());
+        assert_eq!(entry.name, "bin/sh");
+        assert_eq!(entry.mode & MODE_TYPE_MASK, MODE_SYMLINK);
+        assert_eq!(entry.data, b"busybox");
+    }
+
+    #[test]
+    fn cpio_device_node_round_trips_with_rdev() {
+        let entry = round_trip(|w| {
+            w.write_node("dev/console", MODE_CHAR_DEVICE | 0o600, (5, 1))
+                .unwrap();
+        });
+        assert_eq!(entry.name, "dev/console");
+        assert_eq!(entry.mode, MODE_CHAR_DEVICE | 0o600);
+        assert_eq!((entry.rdev_major, entry.rdev_minor), (5, 1));
+        assert!(entry.data.is_empty());
+    }
+
+    #[test]
+    fn repack_preserves_symlinks_and_nodes() {
+        let mut base = CpioWriter::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::fast(),
+        ));
+        base.write_symlink("bin/sh", "busybox").unwrap();
+        base.write_node("dev/null", MODE_CHAR_DEVICE | 0o666, (1, 3))
+            .unwrap();
+        base.write_trailer().unwrap();
+        let gz = base.finish().finish().unwrap();
+
+        let mut repacked = CpioWriter::new(Vec::new());
+        write_entries(&mut repacked, &base_entries(&gz).unwrap()).unwrap();
+        repacked.write_trailer().unwrap();
+        let mut reader = CpioReader::new(Cursor::new(repacked.finish()));
+        let link = reader.next_entry().unwrap().unwrap();
+        assert_eq!(
+            (link.name.as_str(), link.data.as_slice()),
+            ("bin/sh", &b"busybox"[..])
+        );
+        let node = reader.next_entry().unwrap().unwrap();
+        assert_eq!((node.rdev_major, node.rdev_minor), (1, 3));
+    }
+
+    #[test]
+    fn cpio_reader_accepts_crc_format() {
+        let data = b"hello";
+        let sum = data.iter().map(|b| u32::from(*b)).sum();
+        let mut archive = crc_entry("etc", 0o040_755, &[], 0);
+        archive.extend(crc_entry("etc/motd", 0o100_644, data, sum));
+        let mut reader = CpioReader::new(Cursor::new(archive));
+
+        let dir = reader.next_entry().unwrap().unwrap();
+        assert_eq!(dir.name, "etc");
+        let file = reader.next_entry().unwrap().unwrap();
+        assert_eq!(file.name, "etc/motd");
+        assert_eq!(file.mode, 0o100_644);
+        assert_eq!(file.data, data);
+        assert!(reader.next_entry().unwrap().is_none());
+    }
+
+    #[test]
+    fn cpio_reader_rejects_crc_mismatch() {
+        let archive = crc_entry("etc/motd", 0o100_644, b"hello", 1);
+        let error = CpioReader::new(Cursor::new(archive))
+            .next_entry()
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("checksum mismatch"), "{error}");
+    }
+
+    #[test]
+    fn cpio_reader_rejects_unknown_magic() {
+        let mut archive = crc_entry("x", 0o100_644, &[], 0);
+        archive[..6].copy_from_slice(b"070707");
+        let error = CpioReader::new(Cursor::new(archive))
+            .next_entry()
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("070707"), "{error}");
+        assert!(error.contains("070701 or 070702"), "{error}");
+    }
+
     #[test]
     fn init_script_is_not_empty() {
         assert!(!INIT_SCRIPT.is_empty());
@@ -579,9 +1043,166 @@ mod tests {
         assert!(AGENT_SCRIPT.contains("h_remove"));
     }
 
+    #[test]
+    fn agent_script_powers_off_when_idle() {
+        assert!(AGENT_SCRIPT.contains("containust\\.idle_timeout="));
+        assert!(AGENT_SCRIPT.contains("poweroff -f"));
+        assert!(AGENT_SCRIPT.contains("touch \"$ROOT/activity\";;"));
+    }
+
+    /// Feeds one request line to the agent's handler, run on the host
+    /// with its state rooted at `root`.
+    #[cfg(unix)]
+    fn run_handler(root: &Path, request: &str) -> serde_json::Value {
+        use std::io::Write as _;
+        use std::process::{Command, Stdio};
+
+        let body = AGENT_SCRIPT
+            .split("<< 'HANDLER_EOF'\n")
+            .nth(1)
+            .and_then(|rest| rest.split("\nHANDLER_EOF\n").next())
+            .expect("handler body");
+        let handler = root.join("handler.sh");
+        std::fs::write(&handler, body).expect("write handler");
+        let mut child = Command::new("sh")
+            .arg(&handler)
+            .env("CONTAINUST_AGENT_ROOT", root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("spawn handler");
+        let mut stdin = child.stdin.take().expect("stdin");
+        writeln!(stdin, "{request}").expect("write request");
+        drop(stdin);
+        let output = child.wait_with_output().expect("handler output");
+        serde_json::from_slice(&output.stdout).expect("handler response is JSON")
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn agent_inspect_reports_state_pid_and_exit_code() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let container = dir.path().join("projects/0a1b/containers/c1");
+        std::fs::create_dir_all(&container).expect("container dir");
+        std::fs::write(
+            container.join("meta.json"),
+            r#"{"id":"c1","name":"web","image":"file:///app","port":"","command":["sh"],"state":"running","created_at":"2024-01-01T00:00:00Z"}"#,
+        )
+        .expect("meta");
+        std::fs::write(container.join("pid"), std::process::id().to_string()).expect("pid");
+        let request =
+            r#"{"v":1,"id":"r1","method":"inspect","params":{"id":"c1","project":"0a1b"}}"#;
+
+        let running = run_handler(dir.path(), request);
+        assert_eq!(running["id"], "r1");
+        let detail = super::super::response::parse_container_detail(&running).expect("detail");
+        assert_eq!(detail.name, "web");
+        assert_eq!(detail.state, "running");
+        assert_eq!(detail.pid, Some(std::process::id()));
+        assert_eq!(detail.command, vec!["sh"]);
+        assert!(running["result"]["exit_code"].is_null());
+
+        std::fs::write(container.join("exit"), "3").expect("exit");
+        let exited = run_handler(dir.path(), request);
+        assert_eq!(exited["result"]["state"], "exited");
+        assert_eq!(exited["result"]["exit_code"], 3);
+
+        let missing = run_handler(
+            dir.path(),
+            r#"{"v":1,"id":"r2","method":"inspect","params":{"id":"nope","project":"0a1b"}}"#,
+        );
+        assert_eq!(missing["error"], "not found: nope");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn agent_attach_streams_output_from_the_offset() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let project = dir.path().join("projects/0a1b");
+        let container = project.join("containers/c1");
+        std::fs::create_dir_all(&container).expect("container dir");
+        std::fs::create_dir_all(project.join("logs")).expect("logs dir");
+        std::fs::write(container.join("meta.json"), r#"{"id":"c1"}"#).expect("meta");
+        std::fs::write(container.join("pid"), std::process::id().to_string()).expect("pid");
+        let log = "ready\n\"quoted\" \\ path\n";
+        std::fs::write(project.join("logs/c1.log"), log).expect("log");
+        let poll = |offset: &str| {
+            let request = format!(
+                r#"{{"v":1,"id":"r1","method":"attach","params":{{"id":"c1","offset":{offset},"project":"0a1b"}}}}"#
+            );
+            super::super::response::parse_attach_chunk(&run_handler(dir.path(), &request))
+                .expect("chunk")
+        };
+
+        let from_start = poll("0");
+        assert_eq!(from_start.output, log);
+        assert_eq!(from_start.offset, log.len() as u64);
+        assert!(from_start.running);
+
+        let from_end = poll("null");
+        assert_eq!(from_end.output, "");
+        assert_eq!(from_end.offset, log.len() as u64);
+
+        std::fs::write(container.join("exit"), "0").expect("exit");
+        assert!(!poll("6").running);
+        assert_eq!(poll("6").output, "\"quoted\" \\ path\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn agent_stop_applies_the_signal_and_timeout() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let container = dir.path().join("projects/0a1b/containers/c1");
+        std::fs::create_dir_all(&container).expect("container dir");
+        std::fs::write(
+            container.join("meta.json"),
+            r#"{"id":"c1","state":"running"}"#,
+        )
+        .expect("meta");
+        let stop = |signal: &str| {
+            // Ignores SIGTERM, but not SIGHUP; reaped at once so it does
+            // not linger as a zombie.
+            let mut child = std::process::Command::new("sh")
+                .args(["-c", "trap '' TERM; exec sleep 30"])
+                .spawn()
+                .expect("spawn");
+            let comm = format!("/proc/{}/comm", child.id());
+            while std::fs::read_to_string(&comm).map_or(true, |name| name.trim() != "sleep") {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            std::fs::write(container.join("pid"), child.id().to_string()).expect("pid");
+            let reaper = std::thread::spawn(move || child.wait());
+            let response = run_handler(
+                dir.path(),
+                &format!(
+                    r#"{{"v":1,"id":"r1","method":"stop","params":{{"id":"c1","project":"0a1b",{signal}"timeout_ms":300}}}}"#
+                ),
+            );
+            let _ = reaper.join();
+            super::super::response::parse_stop_outcome(&response).expect("outcome")
+        };
+
+        assert_eq!(stop(""), StopOutcome::Escalated);
+        assert_eq!(stop(r#""signal":"SIGHUP","#), StopOutcome::Graceful);
+        assert!(!container.join("pid").exists());
+    }
+
+    #[test]
+    fn agent_script_renames_in_place() {
+        assert!(AGENT_SCRIPT.contains("rename) h_rename \"$line\";;"));
+        assert!(AGENT_SCRIPT.contains(r#"\"name\":\"$nm\""#));
+    }
+
+    #[test]
+    fn agent_script_dispatches_signals_to_the_tracked_pid() {
+        assert!(AGENT_SCRIPT.contains("signal) h_signal \"$line\";;"));
+        assert!(AGENT_SCRIPT.contains("kill -s \"$sg\" \"$(cat \"$SD/$id/pid\")\""));
+    }
+
     #[test]
     fn agent_script_scopes_runtime_directories_by_project() {
-        assert!(AGENT_SCRIPT.contains("/tmp/containust/projects/$project"));
+        assert!(AGENT_SCRIPT.contains("${CONTAINUST_AGENT_ROOT:-/tmp/containust}"));
+        assert!(AGENT_SCRIPT.contains("BASE=\"$ROOT/projects/$project\""));
         assert!(AGENT_SCRIPT.contains("\"project\""));
     }
 
@@ -600,6 +1221,82 @@ mod tests {
         assert!(AGENT_SCRIPT.contains("req_id"));
     }
 
+    #[test]
+    fn agent_script_reports_its_version() {
+        let version = agent_version();
+        assert!(version.starts_with(env!("CARGO_PKG_VERSION")), "{version}");
+        assert_eq!(version, agent_version());
+        let script = agent_script();
+        assert!(script.contains(&format!(r#"\"version\":\"{version}\""#)));
+        assert!(!script.contains(VERSION_PLACEHOLDER));
+    }
+
+    /// Writes a small gzip base initramfs, listed out of order, to `path`.
+    fn write_base(path: &Path, motd: &[u8]) {
+        let mut base = CpioWriter::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::fast(),
+        ));
+        base.write_entry("etc/motd", 0o100_644, motd).unwrap();
+        base.write_dir("etc").unwrap();
+        base.write_dir("tmp").unwrap();
+        base.write_symlink("bin/sh", "busybox").unwrap();
+        base.write_entry("init", 0o100_755, b"stock init").unwrap();
+        base.write_trailer().unwrap();
+        std::fs::write(path, base.finish().finish().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn build_initramfs_is_deterministic() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("base.img");
+        write_base(&base, b"hello");
+        let (first, second) = (dir.path().join("a.img"), dir.path().join("b.img"));
+        build_initramfs(&base, &first).unwrap();
+        build_initramfs(&base, &second).unwrap();
+        assert_eq!(
+            std::fs::read(&first).unwrap(),
+            std::fs::read(&second).unwrap()
+        );
+    }
+
+    #[test]
+    fn build_initramfs_sorts_entries_and_replaces_init() {
+        let dir = tempfile::tempdir().unwrap();
+        let (base, output) = (dir.path().join("base.img"), dir.path().join("out.img"));
+        write_base(&base, b"hello");
+        build_initramfs(&base, &output).unwrap();
+
+        let data = std::fs::read(&output).unwrap();
+        assert_eq!(&data[4..8], &[0, 0, 0, 0], "gzip mtime must be zero");
+        let mut reader = CpioReader::new(flate2::read::GzDecoder::new(data.as_slice()));
+        let mut names = Vec::new();
+        while let Some(entry) = reader.next_entry().unwrap() {
+            if entry.name == "init" {
+                assert_eq!(entry.data, INIT_SCRIPT.as_bytes());
+            }
+            names.push(entry.name);
+        }
+        let trailer = names.pop().unwrap();
+        assert_eq!(trailer, "TRAILER!!!");
+        assert!(names.is_sorted(), "{names:?}");
+        assert_eq!(names.iter().filter(|name| *name == "tmp").count(), 1);
+    }
+
+    #[test]
+    fn ensure_initramfs_rebuilds_only_when_inputs_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let (base, output) = (dir.path().join("base.img"), dir.path().join("out.img"));
+        write_base(&base, b"hello");
+        assert!(ensure_initramfs(&base, &output).unwrap());
+        assert!(!ensure_initramfs(&base, &output).unwrap());
+
+        write_base(&base, b"changed");
+        assert!(ensure_initramfs(&base, &output).unwrap());
+        std::fs::remove_file(&output).unwrap();
+        assert!(ensure_initramfs(&base, &output).unwrap());
+    }
+
     #[test]
     fn build_initramfs_fails_on_missing_base() {
         let result = build_initramfs(
@@ -3,6 +3,8 @@
 use std::path::Path;
 use std::time::Duration;
 
+use containust_common::cancel::CancellationToken;
+use containust_common::clock::Clock;
 use containust_common::error::{ContainustError, Result};
 use containust_common::types::PortMapping;
 use fs2::FileExt;
@@ -60,16 +62,37 @@ impl Drop for VmLock {
     }
 }
 
+/// Kernel and initramfs a new VM boots from.
+#[derive(Debug, Clone, Copy)]
+pub struct BootAssets<'a> {
+    /// Path to the kernel image.
+    pub kernel: &'a Path,
+    /// Path to the initramfs with the agent baked in.
+    pub initramfs: &'a Path,
+}
+
+/// What a VM boot answers to besides its assets.
+#[derive(Debug, Clone, Copy)]
+pub struct BootControl<'a> {
+    /// Aborts the boot; a cancelled boot kills the half-booted VM.
+    pub cancel: &'a CancellationToken,
+    /// Clock the pidfile's start time is read from.
+    pub clock: &'a dyn Clock,
+}
+
 /// Ensures a ready VM exists, adopting a live agent or spawning QEMU.
 ///
+/// A boot cancelled through `control` kills the half-booted VM.
+///
 /// # Errors
 ///
-/// Returns an error when QEMU cannot be found, spawn fails, or readiness times out.
+/// Returns an error when QEMU cannot be found, spawn fails, readiness
+/// times out, or the boot is cancelled.
 pub fn ensure_running(
     vm_dir: &Path,
-    kernel: &Path,
-    initramfs: &Path,
+    assets: BootAssets<'_>,
     ports: &[PortMapping],
+    control: BootControl<'_>,
 ) -> Result<VmStartOutcome> {
     let _lock = VmLock::acquire(vm_dir)?;
     let _ = recover_stale(vm_dir)?;
@@ -84,10 +107,11 @@ pub fn ensure_running(
     eprintln!("  Booting lightweight Linux VM...");
     let child = spawn_qemu(QemuSpawn {
         qemu: &qemu,
-        kernel,
-        initramfs,
+        kernel: assets.kernel,
+        initramfs: assets.initramfs,
         ports: &ports,
         vm_dir,
+        idle_timeout: super::idle::idle_timeout(),
     })?;
     let pid = child.id();
     write_pid_record(
@@ -97,20 +121,34 @@ pub fn ensure_running(
             agent_port: VM_AGENT_PORT,
             forwarded_ports: ports.iter().map(|m| m.host).collect(),
             forwarded_mappings: ports,
+            started_at: Some(control.clock.now()),
         },
     )?;
     // Detach: do not wait/kill on Child drop — the pidfile owns lifecycle.
     std::mem::forget(child);
 
-    match wait_for_vm_ready() {
+    match wait_for_vm_ready(control.cancel) {
         Ok(()) => Ok(VmStartOutcome::Started),
-        Err(error) => {
-            let detail = super::qemu::read_stderr_tail(vm_dir);
+        Err(error @ ContainustError::Cancelled { .. }) => {
             let _ = stop_running(vm_dir, true);
-            Err(ContainustError::Config {
-                message: format!("{error}; qemu stderr tail:\n{detail}"),
-            })
+            Err(error)
         }
+        Err(error) => Err(boot_failed(vm_dir, &error)),
+    }
+}
+
+/// Kills the VM that never became ready and explains why, with the tail
+/// of its console.
+fn boot_failed(vm_dir: &Path, error: &ContainustError) -> ContainustError {
+    let console_path = super::qemu::console_log_path(vm_dir);
+    let console = std::fs::read(&console_path).unwrap_or_default();
+    let _ = stop_running(vm_dir, true);
+    ContainustError::Config {
+        message: super::qemu::boot_failure_message(
+            error,
+            &String::from_utf8_lossy(&console),
+            &console_path,
+        ),
     }
 }
 
@@ -199,6 +237,7 @@ mod tests {
                 agent_port: 10809,
                 forwarded_ports: vec![],
                 forwarded_mappings: vec![],
+                started_at: None,
             },
         )
         .unwrap();
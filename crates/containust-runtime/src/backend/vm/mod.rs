@@ -5,18 +5,26 @@
 //! JSON-RPC protocol over TCP.
 
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
 
+use containust_common::cancel::CancellationToken;
+use containust_common::clock::{SharedClock, SystemClock};
 use containust_common::error::{ContainustError, Result};
 use containust_common::types::{ContainerId, PortMapping};
 
 use super::{
-    ContainerBackend, ContainerConfig, ContainerInfo, ReconciliationReport, project_identifier,
+    ContainerBackend, ContainerConfig, ContainerDetail, ContainerInfo, ReconciliationReport,
+    StopOptions, StopOutcome, project_identifier,
 };
 use crate::exec::ExecOutput;
+use crate::signal::ContainerSignal;
 
 pub mod assets;
 mod assets_fetch;
+mod attach;
+mod handshake;
+mod idle;
 pub mod initramfs;
 mod lifecycle;
 mod pidfile;
@@ -26,11 +34,18 @@ mod protocol;
 mod qemu;
 mod response;
 mod rpc;
+mod status;
+
+pub use status::VmStatus;
 
 /// Backend that runs containers inside a lightweight Linux VM via QEMU.
 ///
 /// QEMU is tracked in `~/.containust/cache/vm/qemu.pid.json` so CLI
-/// invocations can adopt/stop a shared VM; dropping `Engine` does not kill it.
+/// invocations can adopt/stop a shared VM; by default dropping `Engine`
+/// does not kill it. With keep-alive off (see [`Self::set_keep_alive`]), a
+/// backend that booted the VM stops it on drop once no containers run.
+/// An idle VM (no running containers for `CONTAINUST_VM_IDLE_TIMEOUT`
+/// seconds) is stopped automatically and boots again on the next operation.
 pub struct VMBackend {
     vm_dir: PathBuf,
     data_dir: PathBuf,
@@ -38,6 +53,10 @@ pub struct VMBackend {
     project_id: String,
     offline: bool,
     forwarded_ports: Mutex<Vec<u16>>,
+    keep_alive: AtomicBool,
+    started_vm: AtomicBool,
+    cancel: Mutex<CancellationToken>,
+    clock: Mutex<SharedClock>,
 }
 
 impl VMBackend {
@@ -67,9 +86,42 @@ impl VMBackend {
             project_id,
             offline,
             forwarded_ports: Mutex::new(Vec::new()),
+            keep_alive: AtomicBool::new(true),
+            started_vm: AtomicBool::new(false),
+            cancel: Mutex::new(CancellationToken::new()),
+            clock: Mutex::new(SystemClock::shared()),
         }
     }
 
+    /// Sets whether a VM this backend boots keeps running after the
+    /// backend is dropped (the default). Detached runs keep it alive; a
+    /// foreground run turns it off so its VM goes away with it.
+    pub fn set_keep_alive(&self, keep_alive: bool) {
+        self.keep_alive.store(keep_alive, Ordering::Release);
+    }
+
+    /// Makes VM boots give up, and kill the half-booted VM, once `token`
+    /// is cancelled.
+    pub fn set_cancellation(&self, token: CancellationToken) {
+        *self.cancel.lock().unwrap_or_else(PoisonError::into_inner) = token;
+    }
+
+    fn cancellation(&self) -> CancellationToken {
+        self.cancel
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// The clock activity stamps, idle checks, and the pidfile read.
+    fn clock(&self) -> SharedClock {
+        std::sync::Arc::clone(&self.clock.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.clock().now()
+    }
+
     /// Returns the project data directory.
     #[must_use]
     pub fn data_dir(&self) -> &Path {
@@ -106,23 +158,68 @@ impl VMBackend {
             },
         )?;
 
-        // Always rebuild to pick up agent script changes.
-        let _ = std::fs::remove_file(&custom_initramfs_path);
-        initramfs::build_initramfs(&base_initramfs_path, &custom_initramfs_path)?;
+        // Rebuilds only when the base or the agent changed; a VM already
+        // running an older agent is caught by the version handshake.
+        let _ = initramfs::ensure_initramfs(&base_initramfs_path, &custom_initramfs_path)?;
 
         Ok((kernel_path, custom_initramfs_path))
     }
 
     /// Boots the VM if needed (idempotent across CLI processes).
     ///
+    /// A running VM must already forward `ports`; an agent-upgrade reboot
+    /// keeps the forwards recorded in the pidfile alongside `ports`.
+    ///
     /// # Errors
     ///
-    /// Returns an error if QEMU, assets, or readiness polling fails.
+    /// Returns an error if QEMU, assets, or readiness polling fails, or if
+    /// the running VM does not forward a requested port.
     pub fn ensure_vm_running(&self, ports: &[PortMapping]) -> Result<()> {
+        let cancel = self.cancellation();
+        let clock = self.clock();
+        let control = lifecycle::BootControl {
+            cancel: &cancel,
+            clock: clock.as_ref(),
+        };
         let (kernel, initramfs) = self.ensure_vm_assets()?;
-        let outcome = lifecycle::ensure_running(&self.vm_dir, &kernel, &initramfs, ports)?;
+        let mut outcome = lifecycle::ensure_running(
+            &self.vm_dir,
+            lifecycle::BootAssets {
+                kernel: &kernel,
+                initramfs: &initramfs,
+            },
+            ports,
+            control,
+        )?;
+        let handshake = handshake::ensure_current_agent(
+            &initramfs::agent_version(),
+            handshake::query_agent_version,
+            || {
+                let owned = lifecycle::read_pid_record(&self.vm_dir)?
+                    .map(|record| record.effective_mappings())
+                    .unwrap_or_default();
+                let forwards = ports::merge_forward_mappings(&owned, ports);
+                self.stop_vm(false)?;
+                let (kernel, initramfs) = self.ensure_vm_assets()?;
+                outcome = lifecycle::ensure_running(
+                    &self.vm_dir,
+                    lifecycle::BootAssets {
+                        kernel: &kernel,
+                        initramfs: &initramfs,
+                    },
+                    &forwards,
+                    control,
+                )?;
+                Ok(())
+            },
+        )?;
+        if handshake == handshake::HandshakeOutcome::Rebuilt {
+            eprintln!("  Rebooted VM with the current agent.");
+        }
         self.sync_forwarded_ports_from_pidfile()?;
+        idle::record_activity(&self.vm_dir, &self.project_id, None, self.now());
         if matches!(outcome, lifecycle::VmStartOutcome::Started) {
+            self.started_vm.store(true, Ordering::Release);
             tracing::info!(?ports, "VM started with hostfwd ports");
         }
         Ok(())
@@ -135,6 +232,7 @@ impl VMBackend {
     /// Returns an error on lock/pidfile failure or an untracked live agent.
     pub fn stop_vm(&self, force: bool) -> Result<()> {
         lifecycle::stop_running(&self.vm_dir, force)?;
+        idle::clear_activity(&self.vm_dir);
         self.forwarded_ports
             .lock()
             .map_err(|_| ContainustError::Config {
@@ -144,6 +242,48 @@ impl VMBackend {
         Ok(())
     }
 
+    /// Reports whether the shared VM runs, with its pidfile details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pidfile exists but cannot be read.
+    pub fn status(&self) -> Result<VmStatus> {
+        let record = lifecycle::read_pid_record(&self.vm_dir)?;
+        Ok(status::assemble_status(
+            record.as_ref(),
+            rpc::is_agent_ready(),
+            self.now(),
+        ))
+    }
+
+    /// Stops the shared VM and boots it again with the same port forwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the VM cannot be stopped or booted.
+    pub fn restart_vm(&self) -> Result<()> {
+        let ports = lifecycle::read_pid_record(&self.vm_dir)?
+            .map(|record| record.effective_mappings())
+            .unwrap_or_default();
+        self.stop_vm(false)?;
+        self.ensure_vm_running(&ports)
+    }
+
+    /// Returns the guest console output of the current (or last) boot;
+    /// empty when the VM never booted on this host.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the console log exists but cannot be read.
+    pub fn console_log(&self) -> Result<String> {
+        let path = qemu::console_log_path(&self.vm_dir);
+        match std::fs::read(&path) {
+            Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+            Err(source) => Err(ContainustError::Io { path, source }),
+        }
+    }
+
     fn sync_forwarded_ports_from_pidfile(&self) -> Result<()> {
         let ports = lifecycle::read_pid_record(&self.vm_dir)?
             .map(|record| record.forwarded_ports)
@@ -167,7 +307,29 @@ impl VMBackend {
                 message: "VM RPC parameters must be an object".into(),
             })?;
         let _ = object.insert("project".into(), self.project_id.clone().into());
-        rpc::send_rpc(method, &scoped)
+        let response = rpc::send_rpc(method, &scoped)?;
+        if method != "list" {
+            idle::record_activity(&self.vm_dir, &self.project_id, None, self.now());
+        }
+        Ok(response)
+    }
+
+    /// Records what `list` observed and stops the VM once it has been idle
+    /// past the configured timeout.
+    fn observe_idle(&self, containers: &[ContainerInfo]) {
+        let running = containers.iter().filter(|c| c.state == "running").count();
+        if running > 0 {
+            idle::record_activity(&self.vm_dir, &self.project_id, Some(running), self.now());
+            return;
+        }
+        idle::mark_stopped(&self.vm_dir, &self.project_id);
+        let activities = idle::read_all_activity(&self.vm_dir);
+        if idle::vm_is_idle(&activities, self.now(), idle::idle_timeout()) {
+            tracing::info!("VM idle past timeout; shutting down");
+            if let Err(error) = self.stop_vm(false) {
+                tracing::warn!(%error, "idle VM shutdown failed");
+            }
+        }
     }
 }
 
@@ -177,12 +339,33 @@ impl Default for VMBackend {
     }
 }
 
+impl Drop for VMBackend {
+    fn drop(&mut self) {
+        let keep_alive = self.keep_alive.load(Ordering::Acquire);
+        let started_here = self.started_vm.load(Ordering::Acquire);
+        if keep_alive || !started_here {
+            return;
+        }
+        // Refreshes this project's running count before deciding.
+        let _ = self.list();
+        let activities = idle::read_all_activity(&self.vm_dir);
+        if idle::stop_on_drop(keep_alive, started_here, &activities) {
+            tracing::info!("stopping the VM this process started");
+            if let Err(error) = self.stop_vm(false) {
+                tracing::warn!(%error, "VM shutdown on exit failed");
+            }
+        }
+    }
+}
+
 impl ContainerBackend for VMBackend {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
 
     fn create(&self, config: &ContainerConfig) -> Result<ContainerId> {
+        config.validate()?;
+        reject_unsupported(config)?;
         let ports_to_forward = vm_forward_mappings(config);
         self.ensure_vm_running(&ports_to_forward)?;
 
@@ -196,6 +379,9 @@ impl ContainerBackend for VMBackend {
                 "env": config.env,
                 "memory_bytes": config.memory_bytes,
                 "cpu_shares": config.cpu_shares,
+                "pids_max": config.pids_max,
+                "stop_signal": config.stop.signal.map(ContainerSignal::name),
+                "stop_timeout_ms": config.stop.timeout.map(|timeout| timeout.as_millis()),
                 "readonly_rootfs": config.readonly_rootfs,
                 "volumes": config.volumes,
                 "port": config.port,
@@ -226,7 +412,56 @@ impl ContainerBackend for VMBackend {
     }
 
     fn stop(&self, id: &ContainerId) -> Result<()> {
-        let response = self.send_command("stop", &serde_json::json!({ "id": id.as_str() }))?;
+        self.stop_with(id, &StopOptions::default()).map(drop)
+    }
+
+    fn force_stop(&self, id: &ContainerId) -> Result<()> {
+        let response = self.send_command(
+            "stop",
+            &serde_json::json!({ "id": id.as_str(), "force": true }),
+        )?;
+        response::parse_stop_outcome(&response).map(drop)
+    }
+
+    fn stop_with(&self, id: &ContainerId, options: &StopOptions) -> Result<StopOutcome> {
+        if let Some(timeout) = options.timeout {
+            check_stop_timeout(&id.to_string(), timeout)?;
+        }
+        // Unset options fall back, in the agent, to those given at create.
+        let response = self.send_command(
+            "stop",
+            &serde_json::json!({
+                "id": id.as_str(),
+                "signal": options.signal.map(ContainerSignal::name),
+                "timeout_ms": options.timeout.map(|timeout| timeout.as_millis()),
+            }),
+        )?;
+        response::parse_stop_outcome(&response)
+    }
+
+    fn rename(&self, id: &ContainerId, new_name: &str) -> Result<()> {
+        containust_common::types::validate_name(new_name)?;
+        if self
+            .list()?
+            .iter()
+            .any(|container| container.name == new_name && container.id != *id)
+        {
+            return Err(ContainustError::Config {
+                message: format!("container name already exists: {new_name}"),
+            });
+        }
+        let response = self.send_command(
+            "rename",
+            &serde_json::json!({ "id": id.as_str(), "name": new_name }),
+        )?;
+        response::expect_ok_result(&response)
+    }
+
+    fn signal(&self, id: &ContainerId, signal: ContainerSignal) -> Result<()> {
+        let response = self.send_command(
+            "signal",
+            &serde_json::json!({ "id": id.as_str(), "signal": signal.name() }),
+        )?;
         response::expect_ok_result(&response)
     }
 
@@ -248,6 +483,31 @@ impl ContainerBackend for VMBackend {
         response::parse_logs(&response)
     }
 
+    fn attach(
+        &self,
+        id: &ContainerId,
+        stdin: bool,
+    ) -> Result<Box<dyn crate::attach::AttachStream>> {
+        // The agent streams output only; it has no pipe to the main process.
+        if stdin {
+            return Err(ContainustError::Config {
+                message: format!("cannot attach stdin to {id}: the VM backend only streams output"),
+            });
+        }
+        let mut stream = attach::VmAttach {
+            vm_dir: self.vm_dir.clone(),
+            project_id: self.project_id.clone(),
+            id: id.clone(),
+            offset: None,
+            running: true,
+            clock: self.clock(),
+        };
+        // Without an offset the agent answers from the end of existing
+        // output, so this only checks the container and records the offset.
+        let _ = stream.poll()?;
+        Ok(Box::new(stream))
+    }
+
     fn list(&self) -> Result<Vec<ContainerInfo>> {
         if !rpc::is_agent_ready() {
             return Ok(Vec::new());
@@ -259,10 +519,21 @@ impl ContainerBackend for VMBackend {
             .and_then(serde_json::Value::as_array)
             .cloned()
             .unwrap_or_default();
-        Ok(containers
+        let containers: Vec<ContainerInfo> = containers
             .iter()
             .filter_map(response::parse_container_info)
-            .collect())
+            .collect();
+        self.observe_idle(&containers);
+        Ok(containers)
+    }
+
+    fn inspect(&self, id: &ContainerId) -> Result<ContainerDetail> {
+        let response = self.send_command("inspect", &serde_json::json!({ "id": id.as_str() }))?;
+        response::parse_container_detail(&response)
+    }
+
+    fn set_clock(&self, clock: SharedClock) {
+        *self.clock.lock().unwrap_or_else(PoisonError::into_inner) = clock;
     }
 
     fn is_available(&self) -> bool {
@@ -278,6 +549,54 @@ impl ContainerBackend for VMBackend {
     }
 }
 
+/// Rejects settings the agent cannot apply, so they fail at create
+/// instead of being silently dropped.
+fn reject_unsupported(config: &ContainerConfig) -> Result<()> {
+    let unsupported = |setting: &str| ContainustError::Config {
+        message: format!(
+            "container '{}': {setting} is not supported on the VM backend",
+            config.name
+        ),
+    };
+    // The agent runs every container as root in a chroot.
+    if config.user.is_some() {
+        return Err(unsupported("user"));
+    }
+    // Nor does it create a cgroup for it.
+    let limits = [
+        ("memory_swap", config.swap_bytes.is_some()),
+        ("memory_reservation", config.memory_low_bytes.is_some()),
+        ("memory_high", config.memory_high_bytes.is_some()),
+        ("cpu_max", config.cpu_quota_us.is_some()),
+        ("cpuset", config.cpuset_cpus.is_some()),
+        ("numa_node", config.cpuset_mems.is_some()),
+    ];
+    if let Some((limit, _)) = limits.iter().find(|(_, set)| *set) {
+        return Err(unsupported(limit));
+    }
+    config
+        .stop
+        .timeout
+        .map_or(Ok(()), |timeout| check_stop_timeout(&config.name, timeout))
+}
+
+/// Longest stop timeout the agent waits out; it answers within one RPC,
+/// so this stays below the RPC timeout.
+const MAX_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(25);
+
+/// Rejects a stop timeout the agent cannot wait out within one RPC.
+fn check_stop_timeout(container: &str, timeout: std::time::Duration) -> Result<()> {
+    if timeout <= MAX_STOP_TIMEOUT {
+        return Ok(());
+    }
+    Err(ContainustError::Config {
+        message: format!(
+            "container '{container}': stop_timeout above {}s is not supported on the VM backend",
+            MAX_STOP_TIMEOUT.as_secs()
+        ),
+    })
+}
+
 /// Resolves QEMU hostfwd mappings from container config (remap-aware).
 fn vm_forward_mappings(config: &ContainerConfig) -> Vec<PortMapping> {
     if !config.port_mappings.is_empty() {
@@ -302,6 +621,7 @@ mod tests {
     #![cfg_attr(test, allow(clippy::expect_used, clippy::unwrap_used))]
 
     use super::*;
+    use crate::exec::ExecOptions;
 
     #[test]
     fn host_arch_is_supported() {
@@ -315,6 +635,132 @@ mod tests {
         let _ = VMBackend::default().is_available();
     }
 
+    fn vm_config() -> ContainerConfig {
+        ContainerConfig {
+            name: "app".into(),
+            image: "file:///images/app".into(),
+            command: vec!["/bin/app".into()],
+            env: Vec::new(),
+            memory_bytes: None,
+            cpu_shares: None,
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_limits: containust_core::namespace::ipc::IpcLimits::default(),
+            readonly_rootfs: true,
+            stdin: false,
+            volumes: Vec::new(),
+            port: None,
+            ports: Vec::new(),
+            port_mappings: Vec::new(),
+            network: "bridge".into(),
+            restart: containust_common::types::RestartPolicy::default(),
+            healthcheck: None,
+            namespaces: containust_core::namespace::NamespaceConfig::default(),
+            stop: crate::backend::StopOptions::default(),
+            enforce_limits: true,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn vm_create_rejects_a_user() {
+        let config = ContainerConfig {
+            user: Some("1000:1000".into()),
+            ..vm_config()
+        };
+        let error = reject_unsupported(&config).expect_err("the agent runs as root");
+        assert!(
+            error.to_string().contains("user is not supported"),
+            "{error}"
+        );
+        assert!(reject_unsupported(&vm_config()).is_ok());
+    }
+
+    #[test]
+    fn vm_create_rejects_limits_the_agent_cannot_apply() {
+        let configs = [
+            ContainerConfig {
+                swap_bytes: Some(0),
+                ..vm_config()
+            },
+            ContainerConfig {
+                memory_high_bytes: Some(1 << 20),
+                ..vm_config()
+            },
+            ContainerConfig {
+                cpu_quota_us: Some(50_000),
+                ..vm_config()
+            },
+            ContainerConfig {
+                cpuset_cpus: Some(vec![0]),
+                ..vm_config()
+            },
+        ];
+        for (config, limit) in
+            configs
+                .iter()
+                .zip(["memory_swap", "memory_high", "cpu_max", "cpuset"])
+        {
+            let error = reject_unsupported(config).expect_err(limit);
+            assert!(
+                error
+                    .to_string()
+                    .contains(&format!("{limit} is not supported")),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn vm_rejects_stop_timeouts_longer_than_one_rpc() {
+        let config = ContainerConfig {
+            stop: StopOptions {
+                signal: None,
+                timeout: Some(std::time::Duration::from_secs(60)),
+            },
+            ..vm_config()
+        };
+        let error = reject_unsupported(&config).expect_err("agent cannot wait 60s");
+        assert!(
+            error.to_string().contains("stop_timeout above 25s"),
+            "{error}"
+        );
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = VMBackend::with_paths(dir.path().into(), dir.path().join("state.json"));
+        assert!(
+            backend
+                .stop_with(&ContainerId::new("c1"), &config.stop)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn vm_exec_rejects_options_the_agent_cannot_apply() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = VMBackend::with_paths(dir.path().into(), dir.path().join("state.json"));
+        let options = ExecOptions {
+            workdir: Some("/app".into()),
+            ..ExecOptions::default()
+        };
+        let error = backend
+            .exec_with(&ContainerId::new("c1"), &["true".into()], &options)
+            .expect_err("workdir is not applied by the agent");
+        assert!(
+            error.to_string().contains("cannot set exec options"),
+            "{error}"
+        );
+    }
+
     #[test]
     fn vm_backends_use_distinct_project_namespaces() {
         let dir = tempfile::tempdir().expect("tempdir");
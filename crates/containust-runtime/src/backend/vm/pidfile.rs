@@ -21,6 +21,9 @@ pub struct VmPidRecord {
     /// Host→guest port mappings used for QEMU `hostfwd` (schema extension).
     #[serde(default)]
     pub forwarded_mappings: Vec<PortMapping>,
+    /// When QEMU was spawned; absent in pidfiles from older releases.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl VmPidRecord {
@@ -101,7 +104,14 @@ mod tests {
             pid: 4242,
             agent_port: 10809,
             forwarded_ports: vec![8080, 8443],
-            forwarded_mappings: vec![PortMapping::identity(8080), PortMapping::identity(8443)],
+            forwarded_mappings: vec![
+                PortMapping::identity(8080),
+                PortMapping {
+                    host: 8443,
+                    container: 443,
+                },
+            ],
+            started_at: chrono::DateTime::from_timestamp(1_700_000_000, 0),
         };
         write_pid_record(dir.path(), &record).unwrap();
         let loaded = read_pid_record(dir.path()).unwrap().expect("present");
@@ -117,6 +127,7 @@ mod tests {
             agent_port: 10809,
             forwarded_ports: vec![80],
             forwarded_mappings: vec![],
+            started_at: None,
         };
         assert_eq!(record.effective_mappings(), vec![PortMapping::identity(80)]);
     }
@@ -57,10 +57,12 @@ pub fn ensure_mappings_covered(owned: &[PortMapping], requested: &[PortMapping])
         {
             return Err(ContainustError::Config {
                 message: format!(
-                    "VM is already running without hostfwd {}:{} → guest. \
+                    "VM is already running without hostfwd {}:{} → guest (it forwards {}). \
                      Run `ctst vm stop`, then start again so QEMU can bind the port \
                      (hostfwd cannot be added to a live VM)",
-                    mapping.host, mapping.container
+                    mapping.host,
+                    mapping.container,
+                    describe_mappings(owned)
                 ),
             });
         }
@@ -68,6 +70,35 @@ pub fn ensure_mappings_covered(owned: &[PortMapping], requested: &[PortMapping])
     Ok(())
 }
 
+/// Combines the mappings a VM already forwards with newly requested ones,
+/// so a reboot keeps earlier containers reachable. On a host-port clash
+/// the request wins.
+#[must_use]
+pub fn merge_forward_mappings(
+    owned: &[PortMapping],
+    requested: &[PortMapping],
+) -> Vec<PortMapping> {
+    let mut merged: Vec<PortMapping> = owned
+        .iter()
+        .filter(|m| !requested.iter().any(|r| r.host == m.host))
+        .copied()
+        .collect();
+    merged.extend_from_slice(requested);
+    merged.sort_by_key(|m| m.host);
+    merged
+}
+
+fn describe_mappings(mappings: &[PortMapping]) -> String {
+    if mappings.is_empty() {
+        return "no ports".into();
+    }
+    mappings
+        .iter()
+        .map(|m| format!("{}:{}", m.host, m.container))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Probes that each host port can be bound on `127.0.0.1` before QEMU starts.
 ///
 /// # Errors
@@ -159,6 +190,31 @@ mod tests {
         )
         .expect_err("missing");
         assert!(err.to_string().contains("already running"));
+        assert!(err.to_string().contains("it forwards 8080:8080"), "{err}");
+    }
+
+    #[test]
+    fn merge_forward_mappings_keeps_owned_ports() {
+        let owned = [PortMapping::identity(8080), PortMapping::identity(9090)];
+        let requested = [
+            PortMapping {
+                host: 9090,
+                container: 90,
+            },
+            PortMapping::identity(7070),
+        ];
+        assert_eq!(
+            merge_forward_mappings(&owned, &requested),
+            vec![
+                PortMapping::identity(7070),
+                PortMapping::identity(8080),
+                PortMapping {
+                    host: 9090,
+                    container: 90,
+                },
+            ]
+        );
+        assert_eq!(merge_forward_mappings(&[], &owned), owned);
     }
 
     #[test]
@@ -3,6 +3,7 @@
 use std::fs::File;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
+use std::time::Duration;
 
 use containust_common::error::{ContainustError, Result};
 
@@ -92,32 +93,17 @@ pub fn accel_flags() -> Vec<String> {
 ///
 /// Returns [`ContainustError::NotFound`] when QEMU is not on `PATH`.
 pub fn find_qemu() -> Result<PathBuf> {
-    let binary = qemu_binary_name();
-    which::which(binary).map_err(|_| {
-        let install_hint = if cfg!(target_os = "macos") {
-            "Install with: brew install qemu"
-        } else if cfg!(target_os = "windows") {
-            "Install with: choco install qemu"
-        } else {
-            "Install with: apt install qemu-system"
-        };
-        ContainustError::NotFound {
-            kind: "QEMU binary",
-            id: format!("{binary} — {install_hint}"),
-        }
-    })
+    crate::util::find_binary(qemu_binary_name())
 }
 
-/// Path where QEMU stderr is captured for diagnostics.
-#[must_use]
-pub fn qemu_stderr_path(vm_dir: &Path) -> PathBuf {
-    vm_dir.join("qemu.stderr.log")
-}
+/// Console lines quoted in a boot-failure error.
+pub const BOOT_FAILURE_TAIL_LINES: usize = 20;
 
-/// Path where guest serial console is captured for diagnostics.
+/// Path where QEMU's stdout (the guest serial console) and stderr are
+/// captured for diagnostics.
 #[must_use]
-pub fn qemu_serial_path(vm_dir: &Path) -> PathBuf {
-    vm_dir.join("qemu.serial.log")
+pub fn console_log_path(vm_dir: &Path) -> PathBuf {
+    vm_dir.join("console.log")
 }
 
 fn vm_smp() -> u32 {
@@ -139,28 +125,45 @@ pub struct QemuSpawn<'a> {
     pub initramfs: &'a Path,
     /// Extra hostfwd mappings (agent port is always included).
     pub ports: &'a [containust_common::types::PortMapping],
-    /// VM state directory (for stderr capture).
+    /// VM state directory (for console capture).
     pub vm_dir: &'a Path,
+    /// Idle period after which the guest agent powers the VM off.
+    pub idle_timeout: Option<Duration>,
+}
+
+/// Returns the kernel command line; `containust.idle_timeout` (seconds,
+/// `0` = never) arms the agent's idle watchdog.
+#[must_use]
+pub fn kernel_cmdline(idle_timeout: Option<Duration>) -> String {
+    let console = if cfg!(target_arch = "aarch64") {
+        // rdinit forces our injected PID1 even if Alpine ships another init path.
+        "console=ttyAMA0 earlyprintk=serial,ttyAMA0 loglevel=6 rdinit=/init"
+    } else {
+        "console=ttyS0 earlyprintk=serial,ttyS0 loglevel=6 rdinit=/init"
+    };
+    let idle_secs = idle_timeout.map_or(0, |timeout| timeout.as_secs().max(1));
+    format!("{console} containust.idle_timeout={idle_secs}")
 }
 
 /// Spawns QEMU with agent and optional container port forwards.
 ///
+/// QEMU writes its stdout and stderr straight into the console log
+/// rather than through pipes: the VM outlives the CLI, and nothing would
+/// drain a pipe once the CLI exits.
+///
 /// # Errors
 ///
 /// Returns an I/O error when the process or log file cannot be created.
 pub fn spawn_qemu(opts: QemuSpawn<'_>) -> Result<Child> {
     tracing::info!(qemu = %opts.qemu.display(), "booting VM");
     let hostfwd = build_netdev_arg(opts.ports);
-    let stderr_path = qemu_stderr_path(opts.vm_dir);
-    let stderr_file = File::create(&stderr_path).map_err(|source| ContainustError::Io {
-        path: stderr_path.clone(),
-        source,
-    })?;
-    let serial_path = qemu_serial_path(opts.vm_dir);
-    let serial_file = File::create(&serial_path).map_err(|source| ContainustError::Io {
-        path: serial_path.clone(),
+    let console_path = console_log_path(opts.vm_dir);
+    let io_error = |source| ContainustError::Io {
+        path: console_path.clone(),
         source,
-    })?;
+    };
+    let console_file = File::create(&console_path).map_err(io_error)?;
+    let stderr_file = console_file.try_clone().map_err(io_error)?;
 
     let mut cmd = Command::new(opts.qemu);
     let _ = cmd
@@ -173,18 +176,17 @@ pub fn spawn_qemu(opts: QemuSpawn<'_>) -> Result<Child> {
         .args(["-smp", &vm_smp().to_string()])
         .arg("-nographic")
         .arg("-no-reboot")
-        .args([
-            "-append",
-            if cfg!(target_arch = "aarch64") {
-                // rdinit forces our injected PID1 even if Alpine ships another init path.
-                "console=ttyAMA0 earlyprintk=serial,ttyAMA0 loglevel=6 rdinit=/init"
-            } else {
-                "console=ttyS0 earlyprintk=serial,ttyS0 loglevel=6 rdinit=/init"
-            },
-        ])
+        .args(["-append", &kernel_cmdline(opts.idle_timeout)])
         .args(["-netdev", &hostfwd, "-device", net_device()])
-        .stdout(Stdio::from(serial_file))
+        .stdout(Stdio::from(console_file))
         .stderr(Stdio::from(stderr_file));
+    // Its own process group keeps the VM out of the CLI's Ctrl+C and
+    // terminal hangup, so detached containers outlive the CLI.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let _ = cmd.process_group(0);
+    }
 
     cmd.spawn().map_err(|e| ContainustError::Io {
         path: opts.qemu.to_path_buf(),
@@ -192,31 +194,33 @@ pub fn spawn_qemu(opts: QemuSpawn<'_>) -> Result<Child> {
     })
 }
 
-/// Returns a short tail of QEMU stderr + serial console for error messages.
+/// Returns the last `max_lines` non-blank lines of `console`.
 #[must_use]
-pub fn read_stderr_tail(vm_dir: &Path) -> String {
-    let mut parts = Vec::new();
-    if let Some(tail) = log_tail(&qemu_stderr_path(vm_dir), 8) {
-        parts.push(format!("qemu stderr: {tail}"));
-    }
-    if let Some(tail) = log_tail(&qemu_serial_path(vm_dir), 16) {
-        parts.push(format!("guest serial: {tail}"));
-    }
-    if parts.is_empty() {
-        return String::new();
-    }
-    format!("; {}", parts.join(" | "))
+pub fn console_tail(console: &str, max_lines: usize) -> String {
+    let mut lines: Vec<&str> = console
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .rev()
+        .take(max_lines)
+        .collect();
+    lines.reverse();
+    lines.join("\n")
 }
 
-fn log_tail(path: &Path, max_lines: usize) -> Option<String> {
-    let content = std::fs::read_to_string(path).ok()?;
-    let lines: Vec<&str> = content.lines().rev().take(max_lines).collect();
-    if lines.is_empty() {
-        return None;
+/// Appends the tail of the captured console to a boot `error`, so kernel
+/// panics and QEMU errors show up in the message.
+#[must_use]
+pub fn boot_failure_message(error: &ContainustError, console: &str, console_path: &Path) -> String {
+    let tail = console_tail(console, BOOT_FAILURE_TAIL_LINES);
+    if tail.is_empty() {
+        format!("{error}; the VM console log is empty")
+    } else {
+        format!(
+            "{error}; last console output ({}):\n{tail}",
+            console_path.display()
+        )
     }
-    let mut ordered: Vec<&str> = lines.into_iter().rev().collect();
-    ordered.dedup();
-    Some(ordered.join(" | "))
 }
 
 #[cfg(test)]
@@ -243,8 +247,57 @@ mod tests {
         assert_eq!(flags[0], "-accel");
     }
 
+    #[test]
+    fn kernel_cmdline_arms_the_idle_watchdog() {
+        assert!(
+            kernel_cmdline(Some(Duration::from_secs(300)))
+                .ends_with(" containust.idle_timeout=300")
+        );
+        assert!(kernel_cmdline(None).ends_with(" containust.idle_timeout=0"));
+        assert!(kernel_cmdline(None).contains("rdinit=/init"));
+    }
+
     #[test]
     fn net_device_mentions_netdev() {
         assert!(net_device().contains("netdev=net0"));
     }
+
+    const PANIC_CONSOLE: &str = "\
+[    0.000000] Linux version 6.6.8-virt
+[    0.412000] Run /init as init process
+
+[    0.413000] Kernel panic - not syncing: Attempted to kill init! exitcode=0x00000100\r
+[    0.413100] ---[ end Kernel panic - not syncing ]---
+";
+
+    #[test]
+    fn console_tail_keeps_last_non_blank_lines_in_order() {
+        assert_eq!(
+            console_tail(PANIC_CONSOLE, 2),
+            "[    0.413000] Kernel panic - not syncing: Attempted to kill init! exitcode=0x00000100\n\
+             [    0.413100] ---[ end Kernel panic - not syncing ]---"
+        );
+        assert_eq!(console_tail(PANIC_CONSOLE, 100).lines().count(), 4);
+        assert_eq!(console_tail("", 5), "");
+    }
+
+    #[test]
+    fn boot_failure_message_quotes_console_tail() {
+        let error = ContainustError::Config {
+            message: "VM failed to become reachable within 90s".into(),
+        };
+        let path = Path::new("/cache/vm/console.log");
+        let message = boot_failure_message(&error, PANIC_CONSOLE, path);
+        assert!(
+            message.contains("within 90s; last console output (/cache/vm/console.log):\n"),
+            "{message}"
+        );
+        assert!(
+            message.ends_with("---[ end Kernel panic - not syncing ]---"),
+            "{message}"
+        );
+
+        let empty = boot_failure_message(&error, "\n\n", path);
+        assert!(empty.ends_with("the VM console log is empty"), "{empty}");
+    }
 }
@@ -3,7 +3,7 @@
 use containust_common::error::{ContainustError, Result};
 use containust_common::types::ContainerId;
 
-use super::super::ContainerInfo;
+use super::super::{ContainerDetail, ContainerInfo, StopOutcome};
 use crate::exec::ExecOutput;
 
 /// Safely converts a `u64` to `u32`, returning an error on overflow.
@@ -13,7 +13,7 @@ pub fn truncate_u64_to_u32(value: u64) -> Result<u32> {
     })
 }
 
-/// Requires `result == "ok"` for stop/remove style responses.
+/// Requires `result == "ok"` for remove style responses.
 ///
 /// # Errors
 ///
@@ -30,6 +30,25 @@ pub fn expect_ok_result(response: &serde_json::Value) -> Result<()> {
     }
 }
 
+/// Reads whether the agent had to `SIGKILL` a container it stopped.
+///
+/// # Errors
+///
+/// Returns an error when `result.escalated` is missing.
+pub fn parse_stop_outcome(response: &serde_json::Value) -> Result<StopOutcome> {
+    match response
+        .get("result")
+        .and_then(|r| r.get("escalated"))
+        .and_then(serde_json::Value::as_bool)
+    {
+        Some(true) => Ok(StopOutcome::Escalated),
+        Some(false) => Ok(StopOutcome::Graceful),
+        None => Err(ContainustError::Config {
+            message: "VM agent stop response missing result.escalated".into(),
+        }),
+    }
+}
+
 /// Extracts `ExecOutput` fields from a VM agent response.
 ///
 /// # Errors
@@ -86,7 +105,61 @@ pub fn parse_logs(response: &serde_json::Value) -> Result<String> {
         })
 }
 
+/// Deserializes `result` of an `inspect` response.
+///
+/// # Errors
+///
+/// Returns an error when `result` is missing or malformed.
+pub fn parse_container_detail(response: &serde_json::Value) -> Result<ContainerDetail> {
+    let result = response
+        .get("result")
+        .cloned()
+        .ok_or_else(|| ContainustError::Config {
+            message: "VM agent inspect response missing result".into(),
+        })?;
+    serde_json::from_value(result).map_err(|error| ContainustError::Config {
+        message: format!("VM agent inspect response is malformed: {error}"),
+    })
+}
+
+/// One poll of an agent `attach` stream.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct AttachChunk {
+    /// Output written since the requested offset.
+    pub output: String,
+    /// Offset to request on the next poll.
+    pub offset: u64,
+    /// Whether the main process is still running.
+    pub running: bool,
+}
+
+/// Deserializes `result` of an `attach` response.
+///
+/// # Errors
+///
+/// Returns an error when `result` is missing or malformed.
+pub fn parse_attach_chunk(response: &serde_json::Value) -> Result<AttachChunk> {
+    let result = response
+        .get("result")
+        .cloned()
+        .ok_or_else(|| ContainustError::Config {
+            message: "VM agent attach response missing result".into(),
+        })?;
+    serde_json::from_value(result).map_err(|error| ContainustError::Config {
+        message: format!("VM agent attach response is malformed: {error}"),
+    })
+}
+
+fn optional_str(value: &serde_json::Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string)
+}
+
 /// Parses a JSON value from the VM agent into a `ContainerInfo`.
+///
+/// Run timestamps and the exit code are optional; older agents omit them.
 #[must_use]
 pub fn parse_container_info(value: &serde_json::Value) -> Option<ContainerInfo> {
     let pid_u64 = value.get("pid").and_then(serde_json::Value::as_u64);
@@ -98,6 +171,13 @@ pub fn parse_container_info(value: &serde_json::Value) -> Option<ContainerInfo>
         pid,
         image: value.get("image")?.as_str()?.to_string(),
         created_at: value.get("created_at")?.as_str()?.to_string(),
+        started_at: optional_str(value, "started_at"),
+        finished_at: optional_str(value, "finished_at"),
+        exit_code: value
+            .get("exit_code")
+            .and_then(serde_json::Value::as_i64)
+            .and_then(|code| i32::try_from(code).ok()),
+        health: None,
     })
 }
 
@@ -176,4 +256,37 @@ mod tests {
     fn parse_container_info_missing_fields_returns_none() {
         assert!(parse_container_info(&serde_json::json!({ "id": "x" })).is_none());
     }
+
+    #[test]
+    fn parse_container_detail_fills_missing_fields_with_defaults() {
+        let response = serde_json::json!({ "result": {
+            "id": "test-123",
+            "name": "my-app",
+            "state": "running",
+            "pid": 1234,
+            "image": "file:///app",
+            "created_at": "2024-01-01T00:00:00Z",
+            "command": ["/app/server"],
+            "env": [["PORT", "8080"]]
+        }});
+        let detail = parse_container_detail(&response).expect("should parse");
+        assert_eq!(detail.command, vec!["/app/server"]);
+        assert_eq!(detail.env, vec![("PORT".to_string(), "8080".to_string())]);
+        assert!(detail.cgroup.is_none());
+        assert!(parse_container_detail(&serde_json::json!({ "result": {} })).is_err());
+    }
+
+    #[test]
+    fn parse_attach_chunk_reads_output_offset_and_state() {
+        let response = serde_json::json!({ "result": {
+            "output": "ready\n",
+            "offset": 6,
+            "running": true
+        }});
+        let chunk = parse_attach_chunk(&response).expect("should parse");
+        assert_eq!(chunk.output, "ready\n");
+        assert_eq!(chunk.offset, 6);
+        assert!(chunk.running);
+        assert!(parse_attach_chunk(&serde_json::json!({ "result": { "output": "" } })).is_err());
+    }
 }
@@ -5,6 +5,7 @@ use std::net::TcpStream;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use containust_common::cancel::CancellationToken;
 use containust_common::error::{ContainustError, Result};
 
 use super::protocol::{MAX_RESPONSE_BYTES, RPC_IO_TIMEOUT_SECS, decode_response, encode_request};
@@ -43,26 +44,45 @@ pub fn is_agent_ready() -> bool {
 ///
 /// # Errors
 ///
-/// Returns an error when the agent does not become ready in time.
-pub fn wait_for_vm_ready() -> Result<()> {
-    let start = std::time::Instant::now();
+/// Returns an error when the agent does not become ready in time, or
+/// [`ContainustError::Cancelled`] once `cancel` is set.
+pub fn wait_for_vm_ready(cancel: &CancellationToken) -> Result<()> {
     let timeout_secs = boot_timeout_secs();
-    let timeout = Duration::from_secs(timeout_secs);
-
-    while start.elapsed() < timeout {
-        if is_agent_ready() {
-            eprintln!("  VM is ready.");
-            tracing::info!("VM is ready");
-            return Ok(());
-        }
-        std::thread::sleep(Duration::from_millis(VM_POLL_INTERVAL_MS));
+    let ready = poll_until_ready(
+        is_agent_ready,
+        Duration::from_secs(timeout_secs),
+        Duration::from_millis(VM_POLL_INTERVAL_MS),
+        cancel,
+    )?;
+    if ready {
+        eprintln!("  VM is ready.");
+        tracing::info!("VM is ready");
+        return Ok(());
     }
-
     Err(ContainustError::Config {
         message: format!("VM failed to become reachable within {timeout_secs}s"),
     })
 }
 
+/// Calls `is_ready` every `interval` until it succeeds (`true`) or
+/// `timeout` elapses (`false`), checking `cancel` before each probe.
+fn poll_until_ready(
+    mut is_ready: impl FnMut() -> bool,
+    timeout: Duration,
+    interval: Duration,
+    cancel: &CancellationToken,
+) -> Result<bool> {
+    let start = std::time::Instant::now();
+    while start.elapsed() < timeout {
+        cancel.check("VM boot")?;
+        if is_ready() {
+            return Ok(true);
+        }
+        std::thread::sleep(interval);
+    }
+    Ok(false)
+}
+
 /// Sends a versioned RPC request and returns `{ "result": ... }`.
 ///
 /// # Errors
@@ -179,6 +199,50 @@ mod tests {
         assert_eq!(parse_boot_timeout(Some("90")), 90);
     }
 
+    #[test]
+    fn boot_poll_stops_when_cancelled_between_probes() {
+        let cancel = CancellationToken::new();
+        let mut probes = 0;
+        let error = poll_until_ready(
+            || {
+                probes += 1;
+                if probes == 2 {
+                    cancel.cancel();
+                }
+                false
+            },
+            Duration::from_secs(60),
+            Duration::ZERO,
+            &cancel,
+        )
+        .expect_err("cancelled");
+        assert!(
+            matches!(error, ContainustError::Cancelled { .. }),
+            "{error}"
+        );
+        assert_eq!(probes, 2);
+    }
+
+    #[test]
+    fn boot_poll_reports_readiness_and_timeout() {
+        let cancel = CancellationToken::new();
+        let mut probes = 0;
+        let ready = poll_until_ready(
+            || {
+                probes += 1;
+                probes == 3
+            },
+            Duration::from_secs(60),
+            Duration::ZERO,
+            &cancel,
+        )
+        .expect("poll");
+        assert!(ready);
+        assert_eq!(probes, 3);
+        let timed_out = poll_until_ready(|| false, Duration::ZERO, Duration::ZERO, &cancel);
+        assert!(!timed_out.expect("poll"));
+    }
+
     #[test]
     fn versioned_rpc_roundtrip_over_tcp() {
         let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
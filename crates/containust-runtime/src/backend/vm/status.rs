@@ -0,0 +1,112 @@
+//! Status of the shared VM for `ctst vm status`.
+//!
+//! The agent ping decides whether the VM runs; the pidfile adds the QEMU
+//! PID, boot time, and forwarded ports when this host started it.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use containust_common::types::PortMapping;
+
+use super::pidfile::VmPidRecord;
+use super::rpc::VM_AGENT_PORT;
+
+/// What `ctst vm status` reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VmStatus {
+    /// Whether the agent answers.
+    pub running: bool,
+    /// QEMU PID from the pidfile.
+    pub pid: Option<u32>,
+    /// Host port of the agent.
+    pub agent_port: u16,
+    /// Time since QEMU was spawned, when running and recorded.
+    pub uptime: Option<Duration>,
+    /// Host-to-guest port forwards set up at boot.
+    pub forwarded: Vec<PortMapping>,
+}
+
+/// Combines the pidfile `record` with the agent ping result.
+///
+/// A pidfile without a reachable agent describes a dead or booting VM,
+/// so only `running` VMs report a PID, uptime, and forwards.
+#[must_use]
+pub fn assemble_status(
+    record: Option<&VmPidRecord>,
+    agent_ready: bool,
+    now: DateTime<Utc>,
+) -> VmStatus {
+    let Some(record) = record.filter(|_| agent_ready) else {
+        return VmStatus {
+            running: agent_ready,
+            pid: None,
+            agent_port: record.map_or(VM_AGENT_PORT, |record| record.agent_port),
+            uptime: None,
+            forwarded: Vec::new(),
+        };
+    };
+    VmStatus {
+        running: true,
+        pid: Some(record.pid),
+        agent_port: record.agent_port,
+        uptime: record
+            .started_at
+            .and_then(|started| (now - started).to_std().ok()),
+        forwarded: record.effective_mappings(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(started_at: Option<DateTime<Utc>>) -> VmPidRecord {
+        VmPidRecord {
+            pid: 4242,
+            agent_port: 10809,
+            forwarded_ports: vec![8080],
+            forwarded_mappings: vec![PortMapping {
+                host: 8080,
+                container: 80,
+            }],
+            started_at,
+        }
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).expect("timestamp")
+    }
+
+    #[test]
+    fn running_vm_reports_pid_uptime_and_forwards() {
+        let status = assemble_status(Some(&record(Some(at(1_000)))), true, at(1_090));
+        assert!(status.running);
+        assert_eq!(status.pid, Some(4242));
+        assert_eq!(status.uptime, Some(Duration::from_secs(90)));
+        assert_eq!(status.forwarded[0].container, 80);
+    }
+
+    #[test]
+    fn pidfile_without_agent_reports_stopped() {
+        let status = assemble_status(Some(&record(Some(at(1_000)))), false, at(1_090));
+        assert!(!status.running);
+        assert_eq!(status.pid, None);
+        assert_eq!(status.uptime, None);
+        assert!(status.forwarded.is_empty());
+    }
+
+    #[test]
+    fn agent_without_pidfile_is_running_but_untracked() {
+        let status = assemble_status(None, true, at(0));
+        assert!(status.running);
+        assert_eq!(status.pid, None);
+        assert_eq!(status.agent_port, VM_AGENT_PORT);
+    }
+
+    #[test]
+    fn legacy_pidfile_has_no_uptime() {
+        let status = assemble_status(Some(&record(None)), true, at(0));
+        assert_eq!(status.uptime, None);
+        assert_eq!(status.pid, Some(4242));
+    }
+}
@@ -1,5 +1,6 @@
 //! Core container struct and lifecycle operations.
 
+use containust_common::clock::{Clock, SystemClock};
 use containust_common::error::{ContainustError, Result};
 use containust_common::types::{ContainerId, ContainerState, ResourceLimits};
 
@@ -34,6 +35,17 @@ impl Container {
     /// Creates a new container in the `Created` state.
     #[must_use]
     pub fn new(id: ContainerId, name: String, command: Vec<String>) -> Self {
+        Self::new_with_clock(id, name, command, &SystemClock)
+    }
+
+    /// [`Self::new`], stamping the creation time from `clock`.
+    #[must_use]
+    pub fn new_with_clock(
+        id: ContainerId,
+        name: String,
+        command: Vec<String>,
+        clock: &dyn Clock,
+    ) -> Self {
         Self {
             id,
             name,
@@ -45,7 +57,7 @@ impl Container {
             image_source: String::new(),
             rootfs_path: None,
             log_path: None,
-            created_at: chrono::Utc::now().to_rfc3339(),
+            created_at: clock.now().to_rfc3339(),
         }
     }
 
@@ -71,8 +83,14 @@ impl Container {
             readonly_rootfs: true,
             volumes: Vec::new(),
             namespaces: containust_core::namespace::NamespaceConfig::default(),
+            ipc_limits: containust_core::namespace::ipc::IpcLimits::default(),
             join_netns: None,
+            workdir: None,
+            hostname: None,
+            user: None,
             log_path: self.log_path.clone(),
+            stdin_path: None,
+            capabilities: Vec::new(),
         })?;
         self.pid = Some(pid);
         self.rootfs_path = Some(rootfs.to_path_buf());
@@ -136,6 +154,35 @@ impl Container {
     }
 }
 
+/// Opens the span that tags every nested event with a container's id and
+/// name, as `container_id` and `container_name` (plain `name` would clash
+/// with the span name in JSON logs).
+///
+/// Fields not known yet stay empty; fill them in with [`record_identity`]
+/// once the id is assigned or the name is read from state.
+#[must_use]
+pub fn container_span(id: Option<&ContainerId>, name: Option<&str>) -> tracing::Span {
+    let span = tracing::info_span!(
+        "container",
+        container_id = tracing::field::Empty,
+        container_name = tracing::field::Empty
+    );
+    record_identity(&span, id, name);
+    span
+}
+
+/// Records the known identity fields on a [`container_span`].
+///
+/// A no-op for spans without these fields.
+pub fn record_identity(span: &tracing::Span, id: Option<&ContainerId>, name: Option<&str>) {
+    if let Some(id) = id {
+        let _ = span.record("container_id", id.as_str());
+    }
+    if let Some(name) = name {
+        let _ = span.record("container_name", name);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -181,6 +228,18 @@ mod tests {
         assert!(c.created_at.contains(':'));
     }
 
+    #[test]
+    fn new_container_created_at_comes_from_the_clock() {
+        let clock = containust_common::clock::MockClock::at_epoch();
+        let c = Container::new_with_clock(
+            ContainerId::new("test-clock"),
+            "test".into(),
+            vec!["sh".into()],
+            &clock,
+        );
+        assert_eq!(c.created_at, "1970-01-01T00:00:00+00:00");
+    }
+
     #[test]
     fn container_debug_format_is_not_empty() {
         let id = ContainerId::new("test-6");
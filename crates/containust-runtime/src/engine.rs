@@ -3,17 +3,25 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use containust_common::cancel::CancellationToken;
+use containust_common::clock::{SharedClock, SystemClock};
 use containust_common::codes;
+use containust_common::config::DefaultsConfig;
 use containust_common::error::{ContainustError, Result};
 use containust_common::types::ContainerId;
 
+use crate::attach::AttachStream;
+pub use crate::backend::StopOutcome;
 use crate::backend::{
-    self, ContainerBackend, ContainerConfig, ContainerInfo, ReconciliationReport,
+    self, BackendKind, ContainerBackend, ContainerConfig, ContainerDetail, ContainerInfo,
+    ReconciliationReport, StopOptions,
 };
+use crate::container::{container_span, record_identity};
 use crate::events::{EventBus, OperationEmit};
-use crate::exec::ExecOutput;
+use crate::exec::{ExecOptions, ExecOutput};
+use crate::signal::ContainerSignal;
 
 /// Immutable storage and network policy for an engine instance.
 #[derive(Debug, Clone)]
@@ -24,6 +32,8 @@ pub struct EngineOptions {
     pub state_file: PathBuf,
     /// Whether remote sources are rejected.
     pub offline: bool,
+    /// Backend to run containers with.
+    pub backend: BackendKind,
 }
 
 impl Default for EngineOptions {
@@ -33,6 +43,7 @@ impl Default for EngineOptions {
             state_file: data_dir.join("state").join("state.json"),
             data_dir,
             offline: false,
+            backend: BackendKind::Auto,
         }
     }
 }
@@ -50,6 +61,78 @@ pub struct DeployedComponent {
     pub pid: Option<u32>,
 }
 
+/// How a re-read composition differs from the deployed components, by name.
+#[derive(Debug, Clone, Default)]
+pub struct CompositionDiff {
+    /// Declared components that are not deployed, in declaration order.
+    pub added: Vec<String>,
+    /// Deployed components the composition no longer declares.
+    pub removed: Vec<DeployedComponent>,
+    /// Deployed components that are still declared; left untouched.
+    pub unchanged: Vec<DeployedComponent>,
+}
+
+/// How [`Engine::upgrade_composition`] replaces a changed component.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UpgradeStrategy {
+    /// Start the new container and wait for its readiness probe before
+    /// stopping the old one, so the component stays up throughout.
+    /// Components that publish host ports are recreated instead, since
+    /// the new container could not bind them while the old one runs.
+    #[default]
+    Rolling,
+    /// Stop and remove the old container before creating the new one.
+    Recreate,
+}
+
+/// Suffix given to the old container while its replacement starts.
+const UPGRADE_PARKED_SUFFIX: &str = "-previous";
+
+/// Default time a container gets to exit after a graceful stop request.
+pub const DEFAULT_STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A container stopped by [`Engine::stop_all_with_force`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoppedContainer {
+    /// Container ID.
+    pub id: ContainerId,
+    /// Container name.
+    pub name: String,
+    /// How the container was stopped.
+    pub outcome: StopOutcome,
+}
+
+/// Resource limits given on the command line for one component.
+///
+/// Fields that are set replace the values declared in the composition.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LimitOverride {
+    /// Name of the component the limits apply to.
+    pub component: String,
+    /// Memory limit in bytes.
+    pub memory_bytes: Option<u64>,
+    /// CPU weight.
+    pub cpu_shares: Option<u64>,
+    /// Maximum number of processes.
+    pub pids_max: Option<u64>,
+}
+
+impl LimitOverride {
+    const fn apply(&self, config: &mut ContainerConfig) {
+        if let Some(memory_bytes) = self.memory_bytes {
+            config.memory_bytes = Some(memory_bytes);
+        }
+        if let Some(cpu_shares) = self.cpu_shares {
+            config.cpu_shares = Some(cpu_shares);
+        }
+        if let Some(pids_max) = self.pids_max {
+            config.pids_max = Some(pids_max);
+        }
+    }
+}
+
 /// The runtime engine that coordinates all container operations.
 ///
 /// Provides a high-level API that delegates to the platform-specific
@@ -60,6 +143,12 @@ pub struct Engine {
     state_file: PathBuf,
     offline: bool,
     events: Arc<EventBus>,
+    limit_overrides: Vec<LimitOverride>,
+    limit_defaults: DefaultsConfig,
+    stop_timeout: Duration,
+    fail_if_running: bool,
+    clock: SharedClock,
+    cancel: CancellationToken,
 }
 
 impl Engine {
@@ -77,13 +166,15 @@ impl Engine {
             data_dir,
             state_file,
             offline: false,
+            backend: BackendKind::Auto,
         })
     }
 
-    /// Creates an engine with explicit storage and network policy.
+    /// Creates an engine with explicit storage, network policy, and backend.
     #[must_use]
     pub fn with_options(options: EngineOptions) -> Self {
-        let backend = backend::detect_backend_with_paths(
+        let backend = backend::create_backend(
+            options.backend,
             options.data_dir.clone(),
             options.state_file.clone(),
             options.offline,
@@ -100,7 +191,70 @@ impl Engine {
             state_file: options.state_file,
             offline: options.offline,
             events: Arc::new(EventBus::new()),
+            limit_overrides: Vec::new(),
+            limit_defaults: DefaultsConfig::default(),
+            stop_timeout: DEFAULT_STOP_TIMEOUT,
+            fail_if_running: false,
+            clock: SystemClock::shared(),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Sets how long graceful stops wait before escalating to a force kill.
+    #[must_use]
+    pub const fn with_stop_timeout(mut self, timeout: Duration) -> Self {
+        self.stop_timeout = timeout;
+        self
+    }
+
+    /// Applies per-component resource limit overrides to later deployments.
+    #[must_use]
+    pub fn with_limit_overrides(mut self, overrides: Vec<LimitOverride>) -> Self {
+        self.limit_overrides = overrides;
+        self
+    }
+
+    /// Applies config-file `[defaults]` to components that declare no limit.
+    #[must_use]
+    pub fn with_limit_defaults(mut self, defaults: DefaultsConfig) -> Self {
+        self.limit_defaults = defaults;
+        self
+    }
+
+    /// Makes deploys fail, rather than warn, when a component's name is
+    /// already held by a running container.
+    #[must_use]
+    pub const fn with_fail_if_running(mut self, fail: bool) -> Self {
+        self.fail_if_running = fail;
+        self
+    }
+
+    /// Reads wall-clock time from `clock` instead of the system clock, and
+    /// has the backend stamp container times with it too.
+    #[must_use]
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.backend.set_clock(Arc::clone(&clock));
+        self.clock = clock;
+        self
+    }
+
+    /// Aborts deploys and VM boots once `token` is cancelled.
+    ///
+    /// Deploys check the token before each component is created and
+    /// started, removing the containers they created so far when it is set.
+    #[must_use]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        if let Ok(vm) = self.vm_backend() {
+            vm.set_cancellation(token.clone());
         }
+        self.cancel = token;
+        self
+    }
+
+    /// Returns the token that cancels this engine's deploys and VM boots.
+    #[must_use]
+    pub const fn cancellation(&self) -> &CancellationToken {
+        &self.cancel
     }
 
     /// Returns the shared lifecycle event bus.
@@ -120,45 +274,181 @@ impl Engine {
     /// Returns an error if parsing, validation, graph resolution,
     /// container creation, or start fails.
     pub fn deploy(&self, ctst_path: &Path) -> Result<Vec<DeployedComponent>> {
-        let started = Instant::now();
-        let project = self
-            .data_dir
+        let operation = Operation::begin("deploy", None);
+        let result = self.deploy_inner(ctst_path, Launch::Start);
+        self.record_operation(operation, result)
+    }
+
+    /// Creates all components from a `.ctst` file without starting them.
+    ///
+    /// Containers are left in the `Created` state; start them later with
+    /// [`Engine::start`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parsing, validation, graph resolution, or
+    /// container creation fails.
+    pub fn create(&self, ctst_path: &Path) -> Result<Vec<DeployedComponent>> {
+        let operation = Operation::begin("create", None);
+        let result = self.deploy_inner(ctst_path, Launch::CreateOnly);
+        self.record_operation(operation, result)
+    }
+
+    /// Creates every component of an in-memory composition without starting them.
+    ///
+    /// Used for ad-hoc containers assembled from CLI flags; project state
+    /// lives directly under the engine's data directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if validation, image resolution, or creation fails.
+    pub fn create_composition(
+        &self,
+        composition: &containust_compose::parser::ast::CompositionFile,
+    ) -> Result<Vec<DeployedComponent>> {
+        let operation = Operation::begin("create", None);
+        let result = self.launch_adhoc(composition, Launch::CreateOnly);
+        self.record_operation(operation, result)
+    }
+
+    /// Creates and starts every component of an in-memory composition.
+    ///
+    /// Used for ad-hoc containers and compositions piped on stdin; project
+    /// state lives directly under the engine's data directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if validation, image resolution, creation, or
+    /// startup fails.
+    pub fn deploy_composition(
+        &self,
+        composition: &containust_compose::parser::ast::CompositionFile,
+    ) -> Result<Vec<DeployedComponent>> {
+        let operation = Operation::begin("deploy", None);
+        let result = self.launch_adhoc(composition, Launch::Start);
+        self.record_operation(operation, result)
+    }
+
+    /// Re-reads `ctst_path` and converges the project on it: components
+    /// added since `current` was deployed are created and started, removed
+    /// ones are stopped and removed, and the rest are left running even if
+    /// their definition changed. Returns the diff and the new deployed set.
+    ///
+    /// The file is validated and the added components launched before
+    /// anything is removed, so a failed reload leaves `current` running
+    /// untouched; components it created are removed again. A removed
+    /// component that cannot be removed stays in the returned set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be parsed or validated, or if
+    /// launching an added component fails.
+    pub fn reconcile_composition(
+        &self,
+        ctst_path: &Path,
+        current: &[DeployedComponent],
+    ) -> Result<(CompositionDiff, Vec<DeployedComponent>)> {
+        let operation = Operation::begin("reload", None);
+        let result = self.reload_inner(ctst_path, current);
+        self.record_operation(operation, result)
+    }
+
+    /// Replaces the containers of the `changed` components with new ones
+    /// built from `composition`, in dependency order. A changed component
+    /// without a container is created and started.
+    ///
+    /// [`UpgradeStrategy::Rolling`] renames the old container to
+    /// `<name>-previous`, starts the new one, and waits for its readiness
+    /// probe before stopping and removing the old one. If the new one fails
+    /// to start or become ready it is removed and the old one gets its name
+    /// back. [`UpgradeStrategy::Recreate`] stops and removes the old one
+    /// first, as does a rolling upgrade of a component that publishes host
+    /// ports.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if validation fails, or a component cannot be
+    /// replaced; components upgraded before it keep their new containers.
+    pub fn upgrade_composition(
+        &self,
+        composition: &containust_compose::parser::ast::CompositionFile,
+        changed: &[String],
+        strategy: UpgradeStrategy,
+    ) -> Result<Vec<DeployedComponent>> {
+        let operation = Operation::begin("upgrade", None);
+        let result = self.upgrade_inner(composition, changed, strategy);
+        self.record_operation(operation, result)
+    }
+
+    /// Starts a previously created container and returns its PID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container is missing or cannot be started.
+    pub fn start(&self, id: &ContainerId) -> Result<u32> {
+        let _span = container_span(Some(id), None).entered();
+        let operation = Operation::begin("start", Some(id));
+        let result = self.backend.start(id);
+        if let Ok(pid) = &result {
+            tracing::info!(id = %id, pid, "container started");
+        }
+        self.record_operation(operation, result)
+    }
+
+    /// Sends `signal` to a container's main process without changing its
+    /// tracked state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container is not running or the signal
+    /// cannot be delivered.
+    pub fn kill(&self, id: &ContainerId, signal: crate::signal::ContainerSignal) -> Result<()> {
+        let _span = container_span(Some(id), None).entered();
+        let operation = Operation::begin("kill", Some(id));
+        let result = self.backend.signal(id, signal);
+        if result.is_ok() {
+            tracing::info!(id = %id, %signal, "signal sent");
+        }
+        self.record_operation(operation, result)
+    }
+
+    /// Renames a container, keeping names unique within the project.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container is missing or the name is taken.
+    pub fn rename(&self, id: &ContainerId, new_name: &str) -> Result<()> {
+        let operation = Operation::begin("rename", Some(id));
+        let result = self.backend.rename(id, new_name);
+        self.record_operation(operation, result)
+    }
+
+    fn project_name(&self) -> String {
+        self.data_dir
             .file_name()
             .and_then(|name| name.to_str())
             .unwrap_or("project")
-            .to_string();
-        match self.deploy_inner(ctst_path) {
-            Ok(deployed) => {
-                self.events.emit_operation(OperationEmit {
-                    project,
-                    operation: "deploy".into(),
-                    duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
-                    container_id: None,
-                    error_code: None,
-                });
-                Ok(deployed)
-            }
-            Err(error) => {
-                let class = codes::classify(&error);
-                self.events.emit_operation(OperationEmit {
-                    project,
-                    operation: "deploy".into(),
-                    duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
-                    container_id: None,
-                    error_code: Some(class.code),
-                });
-                Err(error)
-            }
-        }
+            .to_string()
+    }
+
+    /// Emits an operation event for `result` and passes it through.
+    fn record_operation<T>(&self, operation: Operation<'_>, result: Result<T>) -> Result<T> {
+        self.events.emit_operation(OperationEmit {
+            project: self.project_name(),
+            operation: operation.name.into(),
+            duration_ms: u64::try_from(operation.started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            container_id: operation.container_id.cloned(),
+            error_code: result
+                .as_ref()
+                .err()
+                .map(|error| codes::classify(error).code),
+        });
+        result
     }
 
-    fn deploy_inner(&self, ctst_path: &Path) -> Result<Vec<DeployedComponent>> {
+    fn deploy_inner(&self, ctst_path: &Path, launch: Launch) -> Result<Vec<DeployedComponent>> {
         let project_dir = containust_common::constants::project_dir(ctst_path);
-        for subdir in ["logs", "state"] {
-            let path = project_dir.join(subdir);
-            std::fs::create_dir_all(&path)
-                .map_err(|source| ContainustError::Io { path, source })?;
-        }
+        ensure_project_dirs(&project_dir)?;
         tracing::info!(project_dir = %project_dir.display(), "project directory");
 
         let content = std::fs::read_to_string(ctst_path).map_err(|e| ContainustError::Io {
@@ -167,11 +457,226 @@ impl Engine {
         })?;
 
         let composition = containust_compose::parser::parse_ctst(&content)?;
+        self.launch(&composition, launch)
+    }
+
+    fn reload_inner(
+        &self,
+        ctst_path: &Path,
+        current: &[DeployedComponent],
+    ) -> Result<(CompositionDiff, Vec<DeployedComponent>)> {
+        let content = std::fs::read_to_string(ctst_path).map_err(|e| ContainustError::Io {
+            path: ctst_path.to_path_buf(),
+            source: e,
+        })?;
+        let composition = containust_compose::parser::parse_ctst(&content)?;
+        let diff = diff_composition(&composition, current);
+        let mut deployed = diff.unchanged.clone();
+        if let Err(error) = self.launch_pending(&composition, Launch::Start, &mut deployed) {
+            self.roll_back(&deployed[diff.unchanged.len()..]);
+            return Err(error);
+        }
+        for gone in &diff.removed {
+            eprintln!("  Removing container '{}'...", gone.name);
+            if let Err(error) = self.stop(&gone.id) {
+                tracing::warn!(id = %gone.id, %error, "stop before removal failed");
+            }
+            if let Err(error) = self.remove(&gone.id) {
+                tracing::warn!(id = %gone.id, %error, "removal failed; still tracking it");
+                deployed.push(gone.clone());
+            }
+        }
+        Ok((diff, deployed))
+    }
+
+    fn upgrade_inner(
+        &self,
+        composition: &containust_compose::parser::ast::CompositionFile,
+        changed: &[String],
+        strategy: UpgradeStrategy,
+    ) -> Result<Vec<DeployedComponent>> {
+        if self.offline {
+            containust_compose::validate_offline(composition)?;
+        }
+        check_limit_overrides(composition, &self.limit_overrides)?;
+        let mut order = resolve_deploy_order(composition)?;
+        order.retain(|name| changed.contains(name));
+        let mut readiness = crate::readiness::ReadinessGate::new(composition)?
+            .with_cancellation(self.cancel.clone());
+        let resolved = containust_compose::resolver::resolve_connections(composition)?;
+        let existing = self.backend.list()?;
+
+        let mut upgraded = Vec::with_capacity(order.len());
+        for name in &order {
+            let component = composition
+                .components
+                .iter()
+                .find(|component| component.name == *name)
+                .ok_or_else(|| ContainustError::NotFound {
+                    kind: "component",
+                    id: name.clone(),
+                })?;
+            let replacement = Replacement {
+                component,
+                resolved: resolved.iter().find(|resolved| resolved.name == *name),
+                mappings: published_port_mappings(component, &composition.exposes)?,
+            };
+            let old = existing.iter().find(|info| info.name == *name);
+            let rolling = strategy == UpgradeStrategy::Rolling && old.is_some();
+            if rolling && !replacement.mappings.is_empty() {
+                eprintln!("  '{name}' publishes host ports; recreating it instead...");
+            }
+            let deployed = match old {
+                Some(old) if rolling && replacement.mappings.is_empty() => {
+                    self.roll_component(replacement, old, &mut readiness)?
+                }
+                old => self.recreate_component(replacement, old, &mut readiness)?,
+            };
+            upgraded.push(deployed);
+        }
+        Ok(upgraded)
+    }
+
+    /// Retires `old`, if any, then creates and starts its replacement.
+    fn recreate_component(
+        &self,
+        replacement: Replacement<'_>,
+        old: Option<&ContainerInfo>,
+        readiness: &mut crate::readiness::ReadinessGate<'_>,
+    ) -> Result<DeployedComponent> {
+        if let Some(old) = old {
+            self.retire(old)?;
+        }
+        let mut created = self.deploy_component(
+            replacement.component,
+            replacement.resolved,
+            replacement.mappings,
+        )?;
+        self.start_replacement(replacement.component, &mut created, readiness)?;
+        Ok(created)
+    }
+
+    /// Starts the replacement of `old` alongside it, then retires `old`;
+    /// restores `old` if the replacement fails.
+    fn roll_component(
+        &self,
+        replacement: Replacement<'_>,
+        old: &ContainerInfo,
+        readiness: &mut crate::readiness::ReadinessGate<'_>,
+    ) -> Result<DeployedComponent> {
+        let parked = format!("{}{UPGRADE_PARKED_SUFFIX}", old.name);
+        self.backend.rename(&old.id, &parked)?;
+        let mut created = match self.deploy_component(
+            replacement.component,
+            replacement.resolved,
+            replacement.mappings,
+        ) {
+            Ok(created) => created,
+            Err(error) => {
+                self.restore_parked(old);
+                return Err(error);
+            }
+        };
+        if let Err(error) = self.start_replacement(replacement.component, &mut created, readiness) {
+            if let Err(cleanup) = self
+                .stop(&created.id)
+                .and_then(|_| self.remove(&created.id))
+            {
+                tracing::warn!(id = %created.id, %cleanup, "failed replacement not removed");
+            }
+            self.restore_parked(old);
+            return Err(error);
+        }
+        self.retire(old)?;
+        Ok(created)
+    }
+
+    /// Starts an upgraded component and waits for its readiness probe.
+    fn start_replacement(
+        &self,
+        component: &containust_compose::parser::ast::ComponentDecl,
+        created: &mut DeployedComponent,
+        readiness: &mut crate::readiness::ReadinessGate<'_>,
+    ) -> Result<()> {
+        created.pid = Some(self.start_component(component, created, readiness)?);
+        readiness.wait_for(self.backend.as_ref(), &created.name, &created.id)
+    }
+
+    /// Stops `old` if it runs, then removes it.
+    fn retire(&self, old: &ContainerInfo) -> Result<()> {
+        eprintln!("  Replacing container '{}'...", old.name);
+        if old.state == "running" {
+            let _ = self.stop(&old.id)?;
+        }
+        self.remove(&old.id)
+    }
+
+    /// Gives a container parked by a rolling upgrade its name back.
+    fn restore_parked(&self, old: &ContainerInfo) {
+        if let Err(error) = self.backend.rename(&old.id, &old.name) {
+            tracing::warn!(id = %old.id, name = %old.name, %error, "could not restore name");
+        }
+    }
+
+    fn launch_adhoc(
+        &self,
+        composition: &containust_compose::parser::ast::CompositionFile,
+        launch: Launch,
+    ) -> Result<Vec<DeployedComponent>> {
+        ensure_project_dirs(&self.data_dir)?;
+        containust_compose::parser::validator::validate(composition)?;
+        self.launch(composition, launch)
+    }
+
+    /// Creates (and optionally starts) components in dependency order.
+    fn launch(
+        &self,
+        composition: &containust_compose::parser::ast::CompositionFile,
+        launch: Launch,
+    ) -> Result<Vec<DeployedComponent>> {
+        self.launch_into(composition, launch, Vec::new())
+    }
+
+    /// Launches the components not already in `deployed`, in dependency
+    /// order, and returns `deployed` with them appended.
+    ///
+    /// When the engine's cancellation token is set mid-launch, the
+    /// containers created by this call are removed; `deployed` ones are kept.
+    fn launch_into(
+        &self,
+        composition: &containust_compose::parser::ast::CompositionFile,
+        launch: Launch,
+        mut deployed: Vec<DeployedComponent>,
+    ) -> Result<Vec<DeployedComponent>> {
+        let preexisting = deployed.len();
+        match self.launch_pending(composition, launch, &mut deployed) {
+            Ok(()) => Ok(deployed),
+            Err(error @ ContainustError::Cancelled { .. }) => {
+                self.roll_back(&deployed[preexisting..]);
+                Err(error)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Creates, and with [`Launch::Start`] starts, each component missing
+    /// from `deployed`, pushing it as soon as it is created.
+    fn launch_pending(
+        &self,
+        composition: &containust_compose::parser::ast::CompositionFile,
+        launch: Launch,
+        deployed: &mut Vec<DeployedComponent>,
+    ) -> Result<()> {
         if self.offline {
-            containust_compose::validate_offline(&composition)?;
+            containust_compose::validate_offline(composition)?;
         }
-        let order = resolve_deploy_order(&composition)?;
-        let resolved = containust_compose::resolver::resolve_connections(&composition)?;
+        check_limit_overrides(composition, &self.limit_overrides)?;
+        self.check_running_conflicts(composition, deployed)?;
+        let mut order = resolve_deploy_order(composition)?;
+        order.retain(|name| !deployed.iter().any(|component| component.name == *name));
+        let mut readiness = crate::readiness::ReadinessGate::new(composition)?
+            .with_cancellation(self.cancel.clone());
+        let resolved = containust_compose::resolver::resolve_connections(composition)?;
         let components: HashMap<&str, &containust_compose::parser::ast::ComponentDecl> =
             composition
                 .components
@@ -184,8 +689,9 @@ impl Engine {
                 .map(|component| (component.name.as_str(), component))
                 .collect();
 
-        let mut deployed = Vec::with_capacity(order.len());
+        deployed.reserve(order.len());
         for name in &order {
+            self.cancel.check("deploy")?;
             let component =
                 components
                     .get(name.as_str())
@@ -194,42 +700,152 @@ impl Engine {
                         id: name.clone(),
                     })?;
             let mappings = published_port_mappings(component, &composition.exposes)?;
-            deployed.push(self.deploy_component(
+            let created = self.deploy_component(
                 component,
                 resolved_by_name.get(name.as_str()).copied(),
                 mappings,
-            )?);
+            )?;
+            deployed.push(created);
+            if launch == Launch::Start {
+                readiness.wait_for_dependencies(self.backend.as_ref(), name, deployed)?;
+                self.cancel.check("deploy")?;
+                let last = deployed.len() - 1;
+                let pid = self.start_component(component, &deployed[last], &mut readiness)?;
+                deployed[last].pid = Some(pid);
+            }
+        }
+        Ok(())
+    }
+
+    /// Best-effort teardown of containers created by a cancelled launch,
+    /// newest first.
+    fn roll_back(&self, created: &[DeployedComponent]) {
+        for component in created.iter().rev() {
+            eprintln!("  Removing container '{}'...", component.name);
+            let stopped = match component.pid {
+                Some(_) => self.stop_with_force(&component.id, true).map(drop),
+                None => Ok(()),
+            };
+            if let Err(error) = stopped.and_then(|()| self.backend.remove(&component.id)) {
+                tracing::warn!(id = %component.id, %error, "rollback failed");
+            }
+        }
+    }
+
+    /// Warns about, or with `fail_if_running` rejects, components whose
+    /// name is already held by a running container other than the
+    /// `deployed` ones.
+    fn check_running_conflicts(
+        &self,
+        composition: &containust_compose::parser::ast::CompositionFile,
+        deployed: &[DeployedComponent],
+    ) -> Result<()> {
+        let existing = self.backend.list()?;
+        let mut conflicts = running_conflicts(composition, &existing);
+        conflicts.retain(|info| !deployed.iter().any(|component| component.id == info.id));
+        if conflicts.is_empty() {
+            return Ok(());
+        }
+        if self.fail_if_running {
+            let names: Vec<&str> = conflicts.iter().map(|info| info.name.as_str()).collect();
+            return Err(ContainustError::Config {
+                message: format!(
+                    "already running: {} (stop or remove them before deploying again)",
+                    names.join(", ")
+                ),
+            });
+        }
+        for info in conflicts {
+            tracing::warn!(
+                id = %info.id,
+                name = %info.name,
+                "a container with this component's name is already running"
+            );
+        }
+        Ok(())
+    }
+
+    /// Starts a created component, running its lifecycle hooks around it.
+    fn start_component(
+        &self,
+        component: &containust_compose::parser::ast::ComponentDecl,
+        created: &DeployedComponent,
+        readiness: &mut crate::readiness::ReadinessGate<'_>,
+    ) -> Result<u32> {
+        let _span = container_span(Some(&created.id), Some(&created.name)).entered();
+        if let Some(hook) = &component.pre_start {
+            crate::hooks::run_pre_start(&created.name, hook)?;
         }
-        Ok(deployed)
+        eprintln!("  Starting container '{}'...", created.name);
+        let pid = self.backend.start(&created.id)?;
+        tracing::info!(id = %created.id, pid, name = %created.name, "container started");
+        if let Some(hook) = &component.post_start {
+            readiness.wait_for(self.backend.as_ref(), &created.name, &created.id)?;
+            crate::hooks::run_post_start(self.backend.as_ref(), &created.name, &created.id, hook)?;
+        }
+        Ok(pid)
     }
 
-    /// Deploys a single named component from the composition.
+    /// Creates a single named component from the composition.
     fn deploy_component(
         &self,
         comp: &containust_compose::parser::ast::ComponentDecl,
         resolved_comp: Option<&containust_compose::resolver::ResolvedComponent>,
         port_mappings: Vec<containust_common::types::PortMapping>,
     ) -> Result<DeployedComponent> {
+        let span = container_span(None, Some(&comp.name));
+        let _entered = span.enter();
         validate_runtime_component(comp)?;
         let image = resolve_deploy_image(self.data_dir(), self.offline, comp)?;
-        let config = build_deploy_config(comp, resolved_comp, image, port_mappings)?;
+        let mut config = build_deploy_config(comp, resolved_comp, image, port_mappings)?;
+        self.apply_limit_defaults(comp, &mut config)?;
+        for limits in self
+            .limit_overrides
+            .iter()
+            .filter(|limits| limits.component == comp.name)
+        {
+            limits.apply(&mut config);
+        }
 
         eprintln!("  Creating container '{}'...", comp.name);
         let id = self.backend.create(&config)?;
+        record_identity(&span, Some(&id), None);
         tracing::info!(id = %id, name = %comp.name, "container created");
 
-        eprintln!("  Starting container '{}'...", comp.name);
-        let pid = self.backend.start(&id)?;
-        tracing::info!(id = %id, pid, name = %comp.name, "container started");
-
         Ok(DeployedComponent {
             id,
             name: comp.name.clone(),
             port: comp.port,
-            pid: Some(pid),
+            pid: None,
         })
     }
 
+    /// Fills limits the component leaves undeclared from `[defaults]`.
+    ///
+    /// An explicit value, including `"unlimited"`, always wins.
+    /// `enforce_limits` is copied for every component.
+    fn apply_limit_defaults(
+        &self,
+        comp: &containust_compose::parser::ast::ComponentDecl,
+        config: &mut ContainerConfig,
+    ) -> Result<()> {
+        let defaults = &self.limit_defaults;
+        config.enforce_limits = defaults.enforces_limits();
+        if let (None, Some(memory)) = (&comp.memory, defaults.memory.as_deref()) {
+            config.memory_bytes = parse_optional_memory(Some(memory))?;
+            tracing::warn!(name = %comp.name, memory, "applying default memory limit");
+        }
+        if let (None, Some(cpu)) = (&comp.cpu, defaults.cpu.as_deref()) {
+            config.cpu_shares = parse_optional_cpu(Some(cpu))?;
+            tracing::warn!(name = %comp.name, cpu, "applying default CPU limit");
+        }
+        if let (None, Some(pids)) = (config.pids_max, defaults.pids) {
+            config.pids_max = Some(pids);
+            tracing::warn!(name = %comp.name, pids, "applying default pids limit");
+        }
+        Ok(())
+    }
+
     /// Lists all containers.
     ///
     /// # Errors
@@ -263,10 +879,13 @@ impl Engine {
 
     /// Stops a container by ID.
     ///
+    /// Escalates to a force kill if the container is still running once the
+    /// stop timeout elapses.
+    ///
     /// # Errors
     ///
     /// Returns an error if the container is not found or cannot be stopped.
-    pub fn stop(&self, id: &ContainerId) -> Result<()> {
+    pub fn stop(&self, id: &ContainerId) -> Result<StopOutcome> {
         self.stop_with_force(id, false)
     }
 
@@ -275,40 +894,76 @@ impl Engine {
     /// # Errors
     ///
     /// Returns an error if the container cannot be stopped.
-    pub fn stop_with_force(&self, id: &ContainerId, force: bool) -> Result<()> {
-        let started = Instant::now();
-        let project = self
-            .data_dir
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("project")
-            .to_string();
+    pub fn stop_with_force(&self, id: &ContainerId, force: bool) -> Result<StopOutcome> {
+        let _span = container_span(Some(id), None).entered();
+        let operation = Operation::begin("stop", Some(id));
         let result = if force {
-            self.backend.force_stop(id)
+            self.backend.force_stop(id).map(|()| StopOutcome::Forced)
         } else {
-            self.backend.stop(id)
+            self.stop_gracefully(id, StopOptions::default())
         };
-        let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
-        match &result {
-            Ok(()) => self.events.emit_operation(OperationEmit {
-                project,
-                operation: "stop".into(),
-                duration_ms,
-                container_id: Some(id.clone()),
-                error_code: None,
-            }),
-            Err(error) => {
-                let class = codes::classify(error);
-                self.events.emit_operation(OperationEmit {
-                    project,
-                    operation: "stop".into(),
-                    duration_ms,
-                    container_id: Some(id.clone()),
-                    error_code: Some(class.code),
-                });
+        self.record_operation(operation, result)
+    }
+
+    /// Stops a container gracefully with `options` overriding the stop
+    /// signal and timeout the component declared.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container cannot be stopped.
+    pub fn stop_with_options(&self, id: &ContainerId, options: StopOptions) -> Result<StopOutcome> {
+        let _span = container_span(Some(id), None).entered();
+        let operation = Operation::begin("stop", Some(id));
+        let result = self.stop_gracefully(id, options);
+        self.record_operation(operation, result)
+    }
+
+    /// Requests a graceful stop, then force-kills the container if it has
+    /// not exited within the stop timeout.
+    fn stop_gracefully(&self, id: &ContainerId, options: StopOptions) -> Result<StopOutcome> {
+        if self.backend.stop_with(id, &options)? == StopOutcome::Escalated {
+            return Ok(StopOutcome::Escalated);
+        }
+        let timeout = options.timeout.unwrap_or(self.stop_timeout);
+        if self.wait_until_stopped(id, timeout)? {
+            return Ok(StopOutcome::Graceful);
+        }
+        tracing::warn!(
+            id = %id,
+            timeout_ms = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX),
+            "container still running after stop timeout; force killing"
+        );
+        self.backend.force_stop(id)?;
+        if self.is_running(id)? {
+            return Err(ContainustError::Config {
+                message: format!("container {id} is still running after a force kill"),
+            });
+        }
+        Ok(StopOutcome::Escalated)
+    }
+
+    /// Polls the backend until `id` is no longer running or `timeout`
+    /// elapses; returns whether it stopped.
+    fn wait_until_stopped(&self, id: &ContainerId, timeout: Duration) -> Result<bool> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if !self.is_running(id)? {
+                return Ok(true);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(false);
             }
+            std::thread::sleep(remaining.min(STOP_POLL_INTERVAL));
         }
-        result
+    }
+
+    fn is_running(&self, id: &ContainerId) -> Result<bool> {
+        Ok(self
+            .backend
+            .list()?
+            .iter()
+            .any(|info| info.id == *id && info.state == "running"))
     }
 
     /// Removes a stopped container and all project-owned resources.
@@ -320,41 +975,133 @@ impl Engine {
         self.backend.remove(id)
     }
 
-    /// Stops all running containers.
+    /// Returns how long container `id` has been running, or `None` when it
+    /// is not running or has no readable start time.
     ///
     /// # Errors
     ///
-    /// Returns an error if any container cannot be stopped.
-    pub fn stop_all(&self) -> Result<()> {
-        self.stop_all_with_force(false)
+    /// Returns an error if containers cannot be listed or `id` is unknown.
+    pub fn uptime(&self, id: &ContainerId) -> Result<Option<Duration>> {
+        let info = self
+            .list()?
+            .into_iter()
+            .find(|info| info.id == *id)
+            .ok_or_else(|| ContainustError::NotFound {
+                kind: "container",
+                id: id.to_string(),
+            })?;
+        Ok(info.uptime(self.clock.now()))
     }
 
-    /// Stops all running containers, optionally skipping graceful shutdown.
+    /// Removes containers that have sat in `created` for at least
+    /// `older_than`, typically left behind by a start that never ran.
     ///
     /// # Errors
     ///
-    /// Returns an error if any container cannot be stopped.
-    pub fn stop_all_with_force(&self, force: bool) -> Result<()> {
-        let containers = self.backend.list()?;
-        for info in containers {
-            if info.state == "running" {
-                self.stop_with_force(&info.id, force)?;
-            }
+    /// Returns an error if containers cannot be listed or one cannot be
+    /// removed.
+    pub fn prune_created(&self, older_than: Duration) -> Result<Vec<ContainerInfo>> {
+        let containers = self.list()?;
+        let stale: Vec<ContainerInfo> = stale_created(&containers, self.clock.now(), older_than)
+            .into_iter()
+            .cloned()
+            .collect();
+        for info in &stale {
+            tracing::info!(id = %info.id, name = %info.name, "pruning stale created container");
+            self.remove(&info.id)?;
         }
-        Ok(())
+        Ok(stale)
     }
 
-    /// Executes a command inside a running container.
+    /// Stops all running containers.
     ///
     /// # Errors
     ///
-    /// Returns an error if the container is not running or the
-    /// command fails to execute.
-    pub fn exec(&self, id: &ContainerId, cmd: &[String]) -> Result<ExecOutput> {
-        self.backend.exec(id, cmd)
+    /// Returns an error if any container cannot be stopped.
+    pub fn stop_all(&self) -> Result<Vec<StoppedContainer>> {
+        self.stop_all_with_force(false)
     }
 
-    /// Returns the logs for a container.
+    /// Stops all running containers, optionally skipping graceful shutdown.
+    ///
+    /// Returns every stopped container with its [`StopOutcome`], so callers
+    /// can report the ones that needed a force kill.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any container cannot be stopped.
+    pub fn stop_all_with_force(&self, force: bool) -> Result<Vec<StoppedContainer>> {
+        self.stop_all_running(|id| self.stop_with_force(id, force))
+    }
+
+    /// Stops all running containers gracefully with `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any container cannot be stopped.
+    pub fn stop_all_with_options(&self, options: StopOptions) -> Result<Vec<StoppedContainer>> {
+        self.stop_all_running(|id| self.stop_with_options(id, options))
+    }
+
+    fn stop_all_running(
+        &self,
+        stop: impl Fn(&ContainerId) -> Result<StopOutcome>,
+    ) -> Result<Vec<StoppedContainer>> {
+        let containers = self.backend.list()?;
+        let mut stopped = Vec::new();
+        for info in containers {
+            if info.state == "running" {
+                let outcome = stop(&info.id)?;
+                stopped.push(StoppedContainer {
+                    id: info.id,
+                    name: info.name,
+                    outcome,
+                });
+            }
+        }
+        Ok(stopped)
+    }
+
+    /// Executes a command inside a running container.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container is not running or the
+    /// command fails to execute.
+    pub fn exec(&self, id: &ContainerId, cmd: &[String]) -> Result<ExecOutput> {
+        let _span = container_span(Some(id), None).entered();
+        self.backend.exec(id, cmd)
+    }
+
+    /// Attaches to the main process's stdio of a running container.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container is not running or the backend
+    /// cannot attach.
+    pub fn attach(&self, id: &ContainerId, stdin: bool) -> Result<Box<dyn AttachStream>> {
+        let _span = container_span(Some(id), None).entered();
+        self.backend.attach(id, stdin)
+    }
+
+    /// Executes a command with explicit options; unset options inherit the
+    /// container's workdir, user, and environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container is not running, an option is
+    /// invalid, or the command fails to execute.
+    pub fn exec_with(
+        &self,
+        id: &ContainerId,
+        cmd: &[String],
+        options: &ExecOptions,
+    ) -> Result<ExecOutput> {
+        let _span = container_span(Some(id), None).entered();
+        self.backend.exec_with(id, cmd, options)
+    }
+
+    /// Returns the logs for a container.
     ///
     /// # Errors
     ///
@@ -363,12 +1110,54 @@ impl Engine {
         self.backend.logs(id)
     }
 
+    /// Returns the last lines, or the lines since a cutoff, of a
+    /// container's logs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if logs cannot be retrieved.
+    pub fn logs_with(&self, id: &ContainerId, options: &crate::logs::LogOptions) -> Result<String> {
+        self.backend.logs_with(id, options)
+    }
+
+    /// Returns the part of the container's previous-run logs selected by
+    /// `options`; empty until the container has been restarted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the logs cannot be read or the backend keeps
+    /// no previous logs.
+    pub fn previous_logs(
+        &self,
+        id: &ContainerId,
+        options: &crate::logs::LogOptions,
+    ) -> Result<String> {
+        self.backend.previous_logs(id, options)
+    }
+
+    /// Returns the full configuration of a container.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the container is unknown or its state cannot
+    /// be read.
+    pub fn inspect(&self, id: &ContainerId) -> Result<ContainerDetail> {
+        self.backend.inspect(id)
+    }
+
     /// Returns the data directory path.
     #[must_use]
     pub fn data_dir(&self) -> &Path {
         &self.data_dir
     }
 
+    /// Returns the project identifier derived from the data directory,
+    /// which names the project's cgroup subtree.
+    #[must_use]
+    pub fn project_id(&self) -> String {
+        crate::backend::project_identifier(&self.data_dir)
+    }
+
     /// Returns the configured state file path.
     #[must_use]
     pub fn state_file(&self) -> &Path {
@@ -387,6 +1176,21 @@ impl Engine {
         self.backend.is_available()
     }
 
+    /// Whether containers run inside the QEMU-based VM backend.
+    #[must_use]
+    pub fn uses_vm(&self) -> bool {
+        self.vm_backend().is_ok()
+    }
+
+    fn vm_backend(&self) -> Result<&crate::backend::vm::VMBackend> {
+        self.backend
+            .as_any()
+            .downcast_ref::<crate::backend::vm::VMBackend>()
+            .ok_or_else(|| ContainustError::Config {
+                message: "VM backend is only available on macOS/Windows".into(),
+            })
+    }
+
     /// Starts the QEMU-based VM backend on macOS/Windows.
     ///
     /// Boots a lightweight Alpine Linux VM via QEMU. Custom kernel and
@@ -405,17 +1209,7 @@ impl Engine {
                     .into(),
             });
         }
-        let Some(vm) = self
-            .backend
-            .as_any()
-            .downcast_ref::<crate::backend::vm::VMBackend>()
-        else {
-            return Err(ContainustError::Config {
-                message: "VM backend is only available on macOS/Windows".into(),
-            });
-        };
-
-        vm.ensure_vm_running(&[])
+        self.vm_backend()?.ensure_vm_running(&[])
     }
 
     /// Stops the QEMU-based VM backend.
@@ -428,17 +1222,44 @@ impl Engine {
     ///
     /// Returns an error if stop cannot be completed safely.
     pub fn vm_stop(&self, force: bool) -> Result<()> {
-        let Some(vm) = self
-            .backend
-            .as_any()
-            .downcast_ref::<crate::backend::vm::VMBackend>()
-        else {
-            return Err(ContainustError::Config {
-                message: "VM backend is only available on macOS/Windows".into(),
-            });
-        };
+        self.vm_backend()?.stop_vm(force)
+    }
+
+    /// Stops and boots the VM again, keeping its port forwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error off the VM backend or if the VM cannot restart.
+    pub fn vm_restart(&self) -> Result<()> {
+        self.vm_backend()?.restart_vm()
+    }
+
+    /// Reports whether the VM runs, its PID, uptime, and forwarded ports.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error off the VM backend or if the pidfile is unreadable.
+    pub fn vm_status(&self) -> Result<crate::backend::vm::VmStatus> {
+        self.vm_backend()?.status()
+    }
+
+    /// Returns the VM's guest console output.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error off the VM backend or if the log is unreadable.
+    pub fn vm_logs(&self) -> Result<String> {
+        self.vm_backend()?.console_log()
+    }
 
-        vm.stop_vm(force)
+    /// Sets whether a VM booted by this engine outlives it; a no-op on
+    /// the native backend. See [`VMBackend::set_keep_alive`].
+    ///
+    /// [`VMBackend::set_keep_alive`]: crate::backend::vm::VMBackend::set_keep_alive
+    pub fn set_vm_keep_alive(&self, keep_alive: bool) {
+        if let Ok(vm) = self.vm_backend() {
+            vm.set_keep_alive(keep_alive);
+        }
     }
 }
 
@@ -448,43 +1269,126 @@ impl Default for Engine {
     }
 }
 
+/// A timed engine operation reported on the event bus when it completes.
+#[derive(Clone, Copy)]
+struct Operation<'a> {
+    name: &'static str,
+    started: Instant,
+    container_id: Option<&'a ContainerId>,
+}
+
+impl<'a> Operation<'a> {
+    fn begin(name: &'static str, container_id: Option<&'a ContainerId>) -> Self {
+        Self {
+            name,
+            started: Instant::now(),
+            container_id,
+        }
+    }
+}
+
+/// What an upgrade creates in place of a changed component.
+struct Replacement<'a> {
+    component: &'a containust_compose::parser::ast::ComponentDecl,
+    resolved: Option<&'a containust_compose::resolver::ResolvedComponent>,
+    mappings: Vec<containust_common::types::PortMapping>,
+}
+
+/// Whether a composition deploy starts containers after creating them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Launch {
+    CreateOnly,
+    Start,
+}
+
+/// Creates the `logs/` and `state/` subdirectories of a project directory.
+fn ensure_project_dirs(project_dir: &Path) -> Result<()> {
+    for subdir in ["logs", "state"] {
+        let path = project_dir.join(subdir);
+        std::fs::create_dir_all(&path).map_err(|source| ContainustError::Io { path, source })?;
+    }
+    Ok(())
+}
+
+/// Rejects overrides naming components the composition does not define.
+fn check_limit_overrides(
+    composition: &containust_compose::parser::ast::CompositionFile,
+    overrides: &[LimitOverride],
+) -> Result<()> {
+    for limits in overrides {
+        if !composition
+            .components
+            .iter()
+            .any(|component| component.name == limits.component)
+        {
+            return Err(ContainustError::NotFound {
+                kind: "component",
+                id: format!("limit override target \"{}\"", limits.component),
+            });
+        }
+    }
+    Ok(())
+}
+
 /// Builds a dependency graph and returns the topological ordering.
 fn resolve_deploy_order(
     composition: &containust_compose::parser::ast::CompositionFile,
 ) -> Result<Vec<String>> {
-    let mut graph = containust_compose::graph::DependencyGraph::new();
-    let mut node_map = HashMap::new();
-    for comp in &composition.components {
-        let idx = graph.add_component(&comp.name);
-        let _ = node_map.insert(comp.name.clone(), idx);
-    }
-    for conn in &composition.connections {
-        if let (Some(&from), Some(&to)) = (node_map.get(&conn.from), node_map.get(&conn.to)) {
-            graph.add_dependency(from, to);
-        }
-    }
-    let order = graph.resolve_order()?;
+    let order = containust_compose::graph::DependencyGraph::from_composition(composition)
+        .resolve_order()?;
     tracing::info!(?order, "deployment order resolved");
     Ok(order)
 }
 
-/// Resolves `preset://` images into catalog references before create.
+/// An image URI with the runtime defaults recorded in its catalog entry.
+#[derive(Debug, Default)]
+struct DeployImage {
+    uri: String,
+    entrypoint: Option<Vec<String>>,
+    cmd: Vec<String>,
+}
+
+impl DeployImage {
+    fn from_entry(uri: String, entry: containust_image::registry::ImageEntry) -> Self {
+        Self {
+            uri,
+            entrypoint: entry.entrypoint,
+            cmd: entry.cmd,
+        }
+    }
+}
+
+/// Resolves `preset://` images into catalog references before create and
+/// loads the entrypoint/cmd defaults of catalog images.
 fn resolve_deploy_image(
     data_dir: &Path,
     offline: bool,
     comp: &containust_compose::parser::ast::ComponentDecl,
-) -> Result<String> {
+) -> Result<DeployImage> {
+    use containust_image::reference::{ImageReference, ImageScheme};
+
     let Some(image) = comp.image.as_deref() else {
-        return Ok(String::new());
+        return Ok(DeployImage::default());
     };
-    let reference = containust_image::reference::ImageReference::parse(image)?;
-    if reference.scheme() != containust_image::reference::ImageScheme::Preset {
-        return Ok(image.to_string());
-    }
-    let request = containust_image::import::ImportRequest::new(&comp.name, offline);
-    let entry = containust_image::import::import_image(data_dir, &reference, &request)?;
-    let digest = entry.digest.as_deref().unwrap_or_default();
-    Ok(format!("image://{}@sha256:{digest}", entry.name))
+    let reference = ImageReference::parse(image)?;
+    match reference.scheme() {
+        ImageScheme::Preset => {
+            let request = containust_image::import::ImportRequest::new(&comp.name, offline);
+            let entry = containust_image::import::import_image(data_dir, &reference, &request)?;
+            let digest = entry.digest.as_deref().unwrap_or_default();
+            let uri = format!("image://{}@sha256:{digest}", entry.name);
+            Ok(DeployImage::from_entry(uri, entry))
+        }
+        ImageScheme::Catalog => {
+            let catalog = containust_image::registry::ImageCatalog::open(data_dir)?;
+            let entry = catalog.find(reference.location())?;
+            Ok(DeployImage::from_entry(image.to_string(), entry))
+        }
+        _ => Ok(DeployImage {
+            uri: image.to_string(),
+            ..DeployImage::default()
+        }),
+    }
 }
 
 fn component_volumes(component: &containust_compose::parser::ast::ComponentDecl) -> Vec<String> {
@@ -496,33 +1400,47 @@ fn component_volumes(component: &containust_compose::parser::ast::ComponentDecl)
         .collect()
 }
 
-fn effective_command(component: &containust_compose::parser::ast::ComponentDecl) -> Vec<String> {
-    component
-        .entrypoint
-        .iter()
+/// Combines entrypoint and command following OCI semantics.
+///
+/// A component `entrypoint` replaces the image default and also drops the
+/// image's default arguments; a component `command` replaces only the
+/// arguments. The result is `entrypoint ++ command`.
+fn effective_command(
+    component: &containust_compose::parser::ast::ComponentDecl,
+    image: &DeployImage,
+) -> Vec<String> {
+    let entrypoint = component.entrypoint.as_ref().or(image.entrypoint.as_ref());
+    let args = if !component.command.is_empty() || component.entrypoint.is_some() {
+        &component.command
+    } else {
+        &image.cmd
+    };
+    entrypoint
+        .into_iter()
         .flatten()
+        .chain(args)
         .cloned()
-        .chain(component.command.iter().cloned())
         .collect()
 }
 
 fn validate_runtime_component(
     component: &containust_compose::parser::ast::ComponentDecl,
 ) -> Result<()> {
-    let unsupported = [
-        (component.workdir.is_some(), "workdir"),
-        (component.user.is_some(), "user"),
-        (component.hostname.is_some(), "hostname"),
-    ]
-    .into_iter()
-    .filter_map(|(present, name)| present.then_some(name))
-    .collect::<Vec<_>>();
-    if !unsupported.is_empty() {
+    if let Some(user) = component.user.as_deref() {
+        let _ = containust_core::namespace::user::parse_user(user)
+            .map_err(|error| component_field_error(&component.name, "user", error))?;
+    }
+    if let Some(hostname) = component.hostname.as_deref() {
+        containust_core::namespace::uts::validate_hostname(hostname)
+            .map_err(|error| component_field_error(&component.name, "hostname", error))?;
+    }
+    if let Some(workdir) = component.workdir.as_deref()
+        && !workdir.starts_with('/')
+    {
         return Err(ContainustError::Config {
             message: format!(
-                "component '{}' uses unsupported runtime properties: {}",
-                component.name,
-                unsupported.join(", ")
+                "component '{}' workdir must be an absolute path, got '{workdir}'",
+                component.name
             ),
         });
     }
@@ -537,6 +1455,76 @@ fn validate_runtime_component(
     Ok(())
 }
 
+/// Running containers whose name matches a component of `composition`.
+#[must_use]
+pub fn running_conflicts<'a>(
+    composition: &containust_compose::parser::ast::CompositionFile,
+    existing: &'a [ContainerInfo],
+) -> Vec<&'a ContainerInfo> {
+    existing
+        .iter()
+        .filter(|info| info.state == "running")
+        .filter(|info| {
+            composition
+                .components
+                .iter()
+                .any(|component| component.name == info.name)
+        })
+        .collect()
+}
+
+/// Classifies `current` against `composition` by component name.
+#[must_use]
+pub fn diff_composition(
+    composition: &containust_compose::parser::ast::CompositionFile,
+    current: &[DeployedComponent],
+) -> CompositionDiff {
+    let declared = |name: &str| {
+        composition
+            .components
+            .iter()
+            .any(|component| component.name == name)
+    };
+    let (unchanged, removed) = current
+        .iter()
+        .cloned()
+        .partition(|deployed| declared(&deployed.name));
+    let added = composition
+        .components
+        .iter()
+        .filter(|component| {
+            !current
+                .iter()
+                .any(|deployed| deployed.name == component.name)
+        })
+        .map(|component| component.name.clone())
+        .collect();
+    CompositionDiff {
+        added,
+        removed,
+        unchanged,
+    }
+}
+
+/// Containers in `created` whose creation is at least `older_than` before
+/// `now`. Entries with an unreadable timestamp are never stale.
+#[must_use]
+pub fn stale_created(
+    existing: &[ContainerInfo],
+    now: chrono::DateTime<chrono::Utc>,
+    older_than: Duration,
+) -> Vec<&ContainerInfo> {
+    let threshold = chrono::TimeDelta::from_std(older_than).unwrap_or(chrono::TimeDelta::MAX);
+    existing
+        .iter()
+        .filter(|info| info.state == "created")
+        .filter(|info| {
+            chrono::DateTime::parse_from_rfc3339(&info.created_at)
+                .is_ok_and(|created| now.signed_duration_since(created) >= threshold)
+        })
+        .collect()
+}
+
 /// Merges a component's `ports` list with `EXPOSE` statements targeting it.
 ///
 /// Supports host:container remapping. Duplicate host ports fail closed.
@@ -547,40 +1535,57 @@ fn validate_runtime_component(
 fn build_deploy_config(
     comp: &containust_compose::parser::ast::ComponentDecl,
     resolved_comp: Option<&containust_compose::resolver::ResolvedComponent>,
-    image: String,
+    image: DeployImage,
     port_mappings: Vec<containust_common::types::PortMapping>,
 ) -> Result<ContainerConfig> {
-    let memory_bytes = parse_optional_memory(comp.memory.as_deref())?;
+    let memory_bytes = component_memory(comp)?;
+    let (memory_low_bytes, memory_high_bytes) = component_soft_limits(comp, memory_bytes)?;
     let cpu_shares = parse_optional_cpu(comp.cpu.as_deref())?;
+    let (cpu_quota_us, cpu_period_us) = component_cpu_max(comp)?;
     let restart = parse_restart_policy(comp)?;
     let healthcheck = comp
         .healthcheck
         .as_ref()
-        .map(|decl| parse_healthcheck_spec(&comp.name, decl))
+        .map(|decl| parse_healthcheck_spec(&comp.name, decl, &port_mappings))
         .transpose()?;
     let network = resolve_deploy_network(comp.network.as_deref(), &port_mappings);
-    let namespaces = namespaces_for_network(&network);
-    let network_name = match &network {
-        crate::network::NetworkMode::Host => "host".into(),
-        crate::network::NetworkMode::None => "none".into(),
-        crate::network::NetworkMode::Shared(name) => name.clone(),
-    };
+    let namespaces = component_namespaces(comp, &network)?;
     Ok(ContainerConfig {
         name: comp.name.clone(),
-        image,
-        command: effective_command(comp),
+        command: effective_command(comp, &image),
+        image: image.uri,
         env: resolved_comp.map_or_else(Vec::new, |r| r.env.clone()),
         memory_bytes,
         cpu_shares,
+        pids_max: None,
+        swap_bytes: component_swap(comp, memory_bytes)?,
+        memory_low_bytes,
+        memory_high_bytes,
+        cpu_quota_us,
+        cpu_period_us,
+        cpuset_cpus: component_cpuset(comp)?,
+        cpuset_mems: component_numa_nodes(comp)?,
+        ipc_limits: component_ipc_limits(comp)?,
         readonly_rootfs: comp.readonly.unwrap_or(true),
+        stdin: comp.stdin.unwrap_or(false),
         volumes: component_volumes(comp),
         port: comp.port,
         ports: port_mappings.iter().map(|m| m.container).collect(),
         port_mappings,
-        network: network_name,
+        network: network.name().to_owned(),
         restart,
         healthcheck,
         namespaces,
+        stop: parse_stop_options(comp)?,
+        enforce_limits: true,
+        workdir: comp.workdir.clone(),
+        hostname: comp.hostname.clone(),
+        user: comp.user.clone(),
+        capabilities: comp
+            .capability_set()
+            .map_err(|message| ContainustError::Config {
+                message: format!("component '{}': {message}", comp.name),
+            })?,
     })
 }
 
@@ -604,6 +1609,25 @@ fn resolve_deploy_network(
     network
 }
 
+/// Namespaces for `comp` on `network`, rejecting a `user` when the network
+/// rules out the private user namespace that maps it.
+fn component_namespaces(
+    comp: &containust_compose::parser::ast::ComponentDecl,
+    network: &crate::network::NetworkMode,
+) -> Result<containust_core::namespace::NamespaceConfig> {
+    let namespaces = namespaces_for_network(network);
+    if comp.user.is_some() && !namespaces.user {
+        return Err(ContainustError::Config {
+            message: format!(
+                "component '{}': user: needs a private user namespace, which a shared \
+                 network cannot have",
+                comp.name
+            ),
+        });
+    }
+    Ok(namespaces)
+}
+
 /// Namespace policy for a deploy network mode.
 ///
 /// Shared project netns files are created in the init user namespace, so
@@ -620,7 +1644,13 @@ fn namespaces_for_network(
     namespaces
 }
 
-fn published_port_mappings(
+/// Returns the host→container publishes a component deploys with: its
+/// `ports` as identity mappings, remapped by matching `EXPOSE` statements.
+///
+/// # Errors
+///
+/// Returns an error when two publishes claim the same host port.
+pub fn published_port_mappings(
     comp: &containust_compose::parser::ast::ComponentDecl,
     exposes: &[containust_compose::parser::ast::ExposeDecl],
 ) -> Result<Vec<containust_common::types::PortMapping>> {
@@ -666,23 +1696,32 @@ fn published_port_mappings(
 fn parse_restart_policy(
     component: &containust_compose::parser::ast::ComponentDecl,
 ) -> Result<containust_common::types::RestartPolicy> {
-    component.restart.as_deref().map_or_else(
-        || Ok(containust_common::types::RestartPolicy::Never),
-        |value| {
-            containust_common::types::RestartPolicy::parse(value).map_err(|message| {
-                ContainustError::Config {
-                    message: format!("component '{}': {message}", component.name),
-                }
-            })
-        },
-    )
+    component
+        .restart_policy()
+        .map_err(|message| ContainustError::Config {
+            message: format!("component '{}': {message}", component.name),
+        })
 }
 
-fn parse_healthcheck_spec(
+pub(crate) fn parse_healthcheck_spec(
     component_name: &str,
     decl: &containust_compose::parser::ast::HealthcheckDecl,
+    port_mappings: &[containust_common::types::PortMapping],
 ) -> Result<containust_common::types::HealthcheckSpec> {
-    if decl.command.is_empty() {
+    let http = decl
+        .http
+        .as_deref()
+        .map(|url| {
+            Ok::<_, ContainustError>(containust_common::types::HttpProbe {
+                url: host_probe_url(component_name, url, port_mappings)?,
+                expect_status: decl.expect.unwrap_or(200),
+            })
+        })
+        .transpose()?;
+    let tcp = decl
+        .tcp
+        .map(|port| published_host_port(port, port_mappings));
+    if decl.command.is_empty() && http.is_none() && tcp.is_none() {
         return Err(ContainustError::Config {
             message: format!("component '{component_name}': healthcheck command is empty"),
         });
@@ -690,6 +1729,8 @@ fn parse_healthcheck_spec(
     let defaults = containust_common::types::HealthcheckSpec::default();
     Ok(containust_common::types::HealthcheckSpec {
         command: decl.command.clone(),
+        http,
+        tcp,
         interval_secs: parse_healthcheck_duration(
             component_name,
             decl.interval.as_deref(),
@@ -709,6 +1750,69 @@ fn parse_healthcheck_spec(
     })
 }
 
+/// Validates an `http` probe URL and, when it targets a loopback port the
+/// component publishes, points it at the published host port instead.
+fn host_probe_url(
+    component_name: &str,
+    raw: &str,
+    port_mappings: &[containust_common::types::PortMapping],
+) -> Result<String> {
+    let invalid = |reason: String| ContainustError::Config {
+        message: format!("component '{component_name}': invalid healthcheck URL '{raw}': {reason}"),
+    };
+    let mut url = reqwest::Url::parse(raw).map_err(|e| invalid(e.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid("expected an http:// or https:// URL".into()));
+    }
+    let loopback = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    if let (true, Some(port)) = (loopback, url.port_or_known_default()) {
+        url.set_port(Some(published_host_port(port, port_mappings)))
+            .map_err(|()| invalid("cannot set port".into()))?;
+    }
+    Ok(url.into())
+}
+
+/// Host port publishing container `port`, or `port` itself when unpublished.
+fn published_host_port(port: u16, port_mappings: &[containust_common::types::PortMapping]) -> u16 {
+    port_mappings
+        .iter()
+        .find(|mapping| mapping.container == port)
+        .map_or(port, |mapping| mapping.host)
+}
+
+fn parse_stop_options(
+    component: &containust_compose::parser::ast::ComponentDecl,
+) -> Result<StopOptions> {
+    let signal = component
+        .stop_signal
+        .as_deref()
+        .map(|raw| {
+            ContainerSignal::parse(raw)
+                .map_err(|e| component_field_error(&component.name, "stop_signal", e))
+        })
+        .transpose()?;
+    let timeout = component
+        .stop_timeout
+        .as_deref()
+        .map(|text| {
+            containust_common::units::parse_duration(text)
+                .map_err(|e| component_field_error(&component.name, "stop_timeout", e))
+        })
+        .transpose()?;
+    Ok(StopOptions { signal, timeout })
+}
+
+/// Prefixes a configuration error with the component and field it came from.
+fn component_field_error(component: &str, field: &str, error: ContainustError) -> ContainustError {
+    let detail = match error {
+        ContainustError::Config { message } => message,
+        other => other.to_string(),
+    };
+    ContainustError::Config {
+        message: format!("component '{component}': {field}: {detail}"),
+    }
+}
+
 fn parse_healthcheck_duration(
     component_name: &str,
     value: Option<&str>,
@@ -727,77 +1831,202 @@ fn parse_healthcheck_duration(
 
 /// Parses `"30s"`, `"5m"`, `"1h"`, or a plain seconds integer.
 fn parse_duration_secs(text: &str) -> Option<u64> {
-    const UNITS: [(char, u64); 3] = [('h', 3600), ('m', 60), ('s', 1)];
-    let text = text.trim();
-    let (digits, multiplier) = UNITS
-        .iter()
-        .find_map(|&(suffix, mult)| text.strip_suffix(suffix).map(|digits| (digits, mult)))
-        .unwrap_or((text, 1));
-    digits.trim().parse::<u64>().ok().map(|n| n * multiplier)
+    containust_common::units::parse_duration(text)
+        .ok()
+        .map(|duration| duration.as_secs())
 }
 
-fn parse_optional_memory(value: Option<&str>) -> Result<Option<u64>> {
-    value
-        .map(|text| {
-            parse_memory(text).ok_or_else(|| ContainustError::Config {
-                message: format!("invalid memory limit: {text}"),
-            })
-        })
-        .transpose()
+/// Returns the limits `comp` deploys with once `defaults` fill in what it
+/// leaves undeclared, before command-line overrides.
+///
+/// # Errors
+///
+/// Returns an error when a declared or default limit is malformed.
+pub fn effective_limits(
+    comp: &containust_compose::parser::ast::ComponentDecl,
+    defaults: &DefaultsConfig,
+) -> Result<containust_common::types::ResourceLimits> {
+    let cpu = comp.cpu.as_deref().or(defaults.cpu.as_deref());
+    let memory_bytes = if comp.memory.is_some() {
+        component_memory(comp)?
+    } else {
+        parse_optional_memory(defaults.memory.as_deref())?
+    };
+    let (memory_low_bytes, memory_high_bytes) = component_soft_limits(comp, memory_bytes)?;
+    let (cpu_quota_us, cpu_period_us) = component_cpu_max(comp)?;
+    Ok(containust_common::types::ResourceLimits {
+        cpu_shares: parse_optional_cpu(cpu)?,
+        memory_bytes,
+        pids_max: defaults.pids,
+        swap_bytes: component_swap(comp, memory_bytes)?,
+        memory_low_bytes,
+        memory_high_bytes,
+        cpu_quota_us,
+        cpu_period_us,
+        cpuset_cpus: component_cpuset(comp)?,
+        cpuset_mems: component_numa_nodes(comp)?,
+        ..containust_common::types::ResourceLimits::default()
+    })
 }
 
-fn parse_optional_cpu(value: Option<&str>) -> Result<Option<u64>> {
-    value
-        .map(|text| {
-            parse_cpu_shares(text).ok_or_else(|| ContainustError::Config {
-                message: format!("invalid CPU limit: {text}"),
-            })
-        })
-        .transpose()
+/// Limit value that opts a component out of configured defaults.
+const UNLIMITED: &str = "unlimited";
+
+/// The component's memory limit, preferring the value the parser
+/// normalized over re-parsing the raw string.
+fn component_memory(comp: &containust_compose::parser::ast::ComponentDecl) -> Result<Option<u64>> {
+    comp.memory_spec.map_or_else(
+        || parse_optional_memory(comp.memory.as_deref()),
+        |spec| Ok(Some(spec.bytes)),
+    )
 }
 
-/// Parses memory strings like "128MiB", "256MB", "1GiB" into bytes.
-#[allow(clippy::option_if_let_else)]
-fn parse_memory(s: &str) -> Option<u64> {
-    let s = s.trim();
-    let (num_str, multiplier) = if let Some(n) = s.strip_suffix("GiB") {
-        (n, 1024 * 1024 * 1024)
-    } else if let Some(n) = s.strip_suffix("GB") {
-        (n, 1_000_000_000)
-    } else if let Some(n) = s.strip_suffix("MiB") {
-        (n, 1024 * 1024)
-    } else if let Some(n) = s.strip_suffix("MB") {
-        (n, 1_000_000)
-    } else if let Some(n) = s.strip_suffix("KiB") {
-        (n, 1024)
-    } else if let Some(n) = s.strip_suffix("KB") {
-        (n, 1000)
-    } else {
-        (s, 1)
-    };
-    num_str.trim().parse::<u64>().ok().map(|n| n * multiplier)
+/// Converts a component's `memory_swap` (memory plus swap, as in
+/// Docker's `--memory-swap`) into the swap allowed beyond `memory`.
+fn component_swap(
+    comp: &containust_compose::parser::ast::ComponentDecl,
+    memory: Option<u64>,
+) -> Result<Option<u64>> {
+    parse_optional_memory(comp.memory_swap.as_deref())?
+        .map(|total| swap_beyond_memory(&comp.name, memory, total))
+        .transpose()
 }
 
-fn parse_cpu_shares(value: &str) -> Option<u64> {
-    let value = value.trim();
-    if let Ok(shares) = value.parse::<u64>() {
-        return (1..=10_000).contains(&shares).then_some(shares);
-    }
-    let (whole_text, fraction_text) = value.split_once('.')?;
-    if fraction_text.is_empty() || !fraction_text.chars().all(|c| c.is_ascii_digit()) {
-        return None;
+/// Returns the `memory.swap.max` value for a `memory_swap` of `total`:
+/// `0` disables swap, and a total equal to `memory` allows none.
+fn swap_beyond_memory(name: &str, memory: Option<u64>, total: u64) -> Result<u64> {
+    if total == 0 {
+        return Ok(0);
     }
-    let whole = if whole_text.is_empty() {
-        0
-    } else {
-        whole_text.parse::<u64>().ok()?
-    };
-    let fraction = fraction_text.parse::<u64>().ok()?;
-    let scale = 10_u64.checked_pow(fraction_text.len().try_into().ok()?)?;
-    let shares = whole
-        .checked_mul(1024)?
-        .checked_add(fraction.checked_mul(1024)?.checked_div(scale)?)?;
-    Some(shares.clamp(1, 10_000))
+    let memory = memory.ok_or_else(|| ContainustError::Config {
+        message: format!("component '{name}': memory_swap requires a memory limit"),
+    })?;
+    total
+        .checked_sub(memory)
+        .ok_or_else(|| ContainustError::Config {
+            message: format!(
+                "component '{name}': memory_swap ({total} bytes) must be \"0\" or at \
+                 least memory ({memory} bytes)"
+            ),
+        })
+}
+
+/// Parses a component's `memory_reservation` and `memory_high`; the
+/// reservation may not exceed `memory`.
+fn component_soft_limits(
+    comp: &containust_compose::parser::ast::ComponentDecl,
+    memory: Option<u64>,
+) -> Result<(Option<u64>, Option<u64>)> {
+    let low = parse_optional_memory(comp.memory_reservation.as_deref())?;
+    if let (Some(low), Some(max)) = (low, memory)
+        && low > max
+    {
+        return Err(ContainustError::Config {
+            message: format!(
+                "component '{}': memory_reservation ({low} bytes) exceeds memory ({max} bytes)",
+                comp.name
+            ),
+        });
+    }
+    Ok((low, parse_optional_memory(comp.memory_high.as_deref())?))
+}
+
+/// Parses a component's `cpuset` and, on Linux, checks every core is
+/// online on this host.
+/// The component's `cpu_max` quota and period, in microseconds.
+fn component_cpu_max(
+    comp: &containust_compose::parser::ast::ComponentDecl,
+) -> Result<(Option<u64>, Option<u64>)> {
+    let spec = comp
+        .cpu_max_spec()
+        .map_err(|message| ContainustError::Config {
+            message: format!("component '{}': {message}", comp.name),
+        })?;
+    Ok((
+        spec.map(|(quota, _)| quota),
+        spec.and_then(|(_, period)| period),
+    ))
+}
+
+fn component_cpuset(
+    comp: &containust_compose::parser::ast::ComponentDecl,
+) -> Result<Option<Vec<u32>>> {
+    use containust_core::cgroup::cpuset;
+
+    let Some(spec) = comp.cpuset.as_deref() else {
+        return Ok(None);
+    };
+    let cores = cpuset::parse_cpuset(spec)
+        .map_err(|error| component_field_error(&comp.name, "cpuset", error))?;
+    #[cfg(target_os = "linux")]
+    cpuset::validate_cpuset(&cores, &cpuset::online_cpus()?)
+        .map_err(|error| component_field_error(&comp.name, "cpuset", error))?;
+    Ok(Some(cores))
+}
+
+/// Parses a component's `numa_node` and, on Linux, resolves it against
+/// the host's NUMA nodes; non-NUMA hosts only honour node `0`.
+fn component_numa_nodes(
+    comp: &containust_compose::parser::ast::ComponentDecl,
+) -> Result<Option<Vec<u32>>> {
+    use containust_core::cgroup::cpuset;
+
+    let Some(spec) = comp.numa_node.as_deref() else {
+        return Ok(None);
+    };
+    let nodes = cpuset::parse_numa_nodes(spec)
+        .map_err(|error| component_field_error(&comp.name, "numa_node", error))?;
+    #[cfg(target_os = "linux")]
+    let nodes = {
+        let available = cpuset::numa_nodes_in(std::path::Path::new(cpuset::NUMA_NODES_DIR));
+        cpuset::resolve_numa_nodes(&nodes, &available)
+            .map_err(|error| component_field_error(&comp.name, "numa_node", error))?
+    };
+    #[cfg(not(target_os = "linux"))]
+    let nodes = Some(nodes);
+    Ok(nodes)
+}
+
+/// Converts a component's `ipc` block into IPC namespace limits.
+fn component_ipc_limits(
+    comp: &containust_compose::parser::ast::ComponentDecl,
+) -> Result<containust_core::namespace::ipc::IpcLimits> {
+    let Some(ipc) = &comp.ipc else {
+        return Ok(containust_core::namespace::ipc::IpcLimits::default());
+    };
+    let shmmax_bytes = ipc
+        .shmmax
+        .as_deref()
+        .map(containust_common::units::parse_bytes)
+        .transpose()
+        .map_err(|error| component_field_error(&comp.name, "ipc.shmmax", error))?;
+    Ok(containust_core::namespace::ipc::IpcLimits {
+        shmmax_bytes,
+        shmall_pages: ipc.shmall,
+        msgmni: ipc.msgmni,
+    })
+}
+
+fn parse_optional_memory(value: Option<&str>) -> Result<Option<u64>> {
+    value
+        .filter(|text| !text.trim().eq_ignore_ascii_case(UNLIMITED))
+        .map(|text| {
+            containust_common::units::parse_bytes(text).map_err(|_| ContainustError::Config {
+                message: format!("invalid memory limit: {text}"),
+            })
+        })
+        .transpose()
+}
+
+fn parse_optional_cpu(value: Option<&str>) -> Result<Option<u64>> {
+    value
+        .filter(|text| !text.trim().eq_ignore_ascii_case(UNLIMITED))
+        .map(|text| {
+            containust_common::units::parse_cpu(text).map_err(|_| ContainustError::Config {
+                message: format!("invalid CPU limit: {text}"),
+            })
+        })
+        .transpose()
 }
 
 #[cfg(test)]
@@ -810,7 +2039,12 @@ mod tests {
     #[derive(Default)]
     struct FakeState {
         config: Mutex<Option<ContainerConfig>>,
+        started: AtomicBool,
+        stopped: AtomicBool,
         force_stopped: AtomicBool,
+        ignores_stop: AtomicBool,
+        kills_on_stop: AtomicBool,
+        stop_options: Mutex<Option<StopOptions>>,
     }
 
     struct FakeBackend {
@@ -828,18 +2062,33 @@ mod tests {
         }
 
         fn start(&self, _id: &ContainerId) -> Result<u32> {
+            tracing::info!("fake backend starting");
+            self.state.started.store(true, Ordering::Release);
             Ok(42)
         }
 
         fn stop(&self, _id: &ContainerId) -> Result<()> {
+            if !self.state.ignores_stop.load(Ordering::Acquire) {
+                self.state.stopped.store(true, Ordering::Release);
+            }
             Ok(())
         }
 
         fn force_stop(&self, _id: &ContainerId) -> Result<()> {
             self.state.force_stopped.store(true, Ordering::Release);
+            self.state.stopped.store(true, Ordering::Release);
             Ok(())
         }
 
+        fn stop_with(&self, id: &ContainerId, options: &StopOptions) -> Result<StopOutcome> {
+            *self.state.stop_options.lock().expect("stop options lock") = Some(*options);
+            if self.state.kills_on_stop.load(Ordering::Acquire) {
+                self.state.stopped.store(true, Ordering::Release);
+                return Ok(StopOutcome::Escalated);
+            }
+            self.stop(id).map(|()| StopOutcome::Graceful)
+        }
+
         fn exec(&self, _id: &ContainerId, _cmd: &[String]) -> Result<ExecOutput> {
             Ok(ExecOutput {
                 stdout: String::new(),
@@ -857,7 +2106,29 @@ mod tests {
         }
 
         fn list(&self) -> Result<Vec<ContainerInfo>> {
-            Ok(Vec::new())
+            let config = self.state.config.lock().expect("config lock").clone();
+            let state = if self.state.stopped.load(Ordering::Acquire) {
+                "stopped"
+            } else if self.state.started.load(Ordering::Acquire) {
+                "running"
+            } else {
+                "created"
+            };
+            Ok(config
+                .into_iter()
+                .map(|config| ContainerInfo {
+                    id: ContainerId::new("fake-id"),
+                    name: config.name,
+                    state: state.into(),
+                    pid: None,
+                    image: config.image,
+                    created_at: String::new(),
+                    started_at: None,
+                    finished_at: None,
+                    exit_code: None,
+                    health: None,
+                })
+                .collect())
         }
 
         fn is_available(&self) -> bool {
@@ -870,38 +2141,11 @@ mod tests {
             state_file: data_dir.join("custom-state.json"),
             data_dir,
             offline,
+            backend: BackendKind::Auto,
         };
         Engine::with_backend(options, Box::new(FakeBackend { state }))
     }
 
-    #[test]
-    fn parse_memory_mib() {
-        assert_eq!(parse_memory("128MiB"), Some(128 * 1024 * 1024));
-    }
-
-    #[test]
-    fn parse_memory_gib() {
-        assert_eq!(parse_memory("1GiB"), Some(1024 * 1024 * 1024));
-    }
-
-    #[test]
-    fn parse_memory_plain_bytes() {
-        assert_eq!(parse_memory("1048576"), Some(1_048_576));
-    }
-
-    #[test]
-    fn parse_memory_invalid() {
-        assert_eq!(parse_memory("abc"), None);
-    }
-
-    #[test]
-    fn parse_cpu_decimal_maps_to_weight() {
-        assert_eq!(parse_cpu_shares("0.5"), Some(512));
-        assert_eq!(parse_cpu_shares("2"), Some(2));
-        assert_eq!(parse_cpu_shares("0"), None);
-        assert_eq!(parse_cpu_shares("invalid"), None);
-    }
-
     #[test]
     fn engine_preserves_explicit_options() {
         let dir = tempfile::tempdir().expect("tempdir");
@@ -951,247 +2195,1824 @@ mod tests {
     }
 
     #[test]
-    fn offline_deploy_rejects_remote_image_before_create() {
+    fn limit_overrides_replace_ctst_values_for_named_component() {
         let dir = tempfile::tempdir().expect("tempdir");
-        let file = dir.path().join("remote.ctst");
+        let file = dir.path().join("app.ctst");
         std::fs::write(
             &file,
-            "COMPONENT app { image = \"https://example.test/app.tar\" }",
+            r#"COMPONENT web {
+    image = "file:///unused"
+    cpu = "0.5"
+    memory = "64MiB"
+}"#,
         )
         .expect("write composition");
         let state = Arc::new(FakeState::default());
-        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), true);
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false)
+            .with_limit_overrides(vec![
+                LimitOverride {
+                    component: "web".into(),
+                    memory_bytes: Some(512 * 1024 * 1024),
+                    ..LimitOverride::default()
+                },
+                LimitOverride {
+                    component: "web".into(),
+                    pids_max: Some(128),
+                    ..LimitOverride::default()
+                },
+            ]);
 
-        assert!(engine.deploy(&file).is_err());
-        assert!(state.config.lock().expect("config lock").is_none());
+        let _ = engine.deploy(&file).expect("deploy");
+        let config = state.config.lock().expect("config lock").clone();
+        let config = config.expect("config captured");
+        assert_eq!(config.memory_bytes, Some(512 * 1024 * 1024));
+        assert_eq!(config.cpu_shares, Some(512));
+        assert_eq!(config.pids_max, Some(128));
     }
 
-    #[test]
-    fn deploy_rejects_unsupported_runtime_property() {
+    fn deployed_config_with_defaults(ctst: &str, defaults: DefaultsConfig) -> ContainerConfig {
         let dir = tempfile::tempdir().expect("tempdir");
-        let file = dir.path().join("workdir.ctst");
-        std::fs::write(
-            &file,
-            r#"COMPONENT app {
-    image = "file:///unused"
-    workdir = "/srv"
-}"#,
-        )
-        .expect("write composition");
+        let file = dir.path().join("app.ctst");
+        std::fs::write(&file, ctst).expect("write composition");
         let state = Arc::new(FakeState::default());
-        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false)
+            .with_limit_defaults(defaults);
 
-        let error = engine.deploy(&file).expect_err("unsupported property");
-        assert!(error.to_string().contains("workdir"));
-        assert!(state.config.lock().expect("config lock").is_none());
+        let _ = engine.deploy(&file).expect("deploy");
+        let config = state.config.lock().expect("config lock").clone();
+        config.expect("config captured")
+    }
+
+    fn sample_defaults() -> DefaultsConfig {
+        DefaultsConfig {
+            memory: Some("256MiB".into()),
+            cpu: Some("1.0".into()),
+            pids: Some(64),
+            enforce_limits: None,
+        }
     }
 
     #[test]
-    fn deploy_passes_ports_restart_and_healthcheck() {
-        let dir = tempfile::tempdir().expect("tempdir");
-        let file = dir.path().join("policies.ctst");
-        std::fs::write(
-            &file,
-            r#"COMPONENT app {
+    fn memory_swap_sets_swap_beyond_memory() {
+        let swap_for = |memory_swap: &str| {
+            deployed_config_with_defaults(
+                &format!(
+                    r#"COMPONENT web {{
     image = "file:///unused"
-    ports = [8080, 9090]
-    restart = "on-failure"
-    healthcheck = {
-        command = ["curl", "-f", "http://localhost:8080/healthz"]
-        interval = "10s"
-        timeout = "3s"
-        retries = 5
-        start_period = "1m"
+    memory = "256MiB"
+    memory_swap = "{memory_swap}"
+}}"#
+                ),
+                DefaultsConfig::default(),
+            )
+            .swap_bytes
+        };
+        assert_eq!(swap_for("512MiB"), Some(256 * 1024 * 1024));
+        assert_eq!(swap_for("256MiB"), Some(0));
+        assert_eq!(swap_for("0"), Some(0));
+        assert_eq!(swap_for("unlimited"), None);
     }
-}"#,
-        )
-        .expect("write composition");
-        let state = Arc::new(FakeState::default());
-        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
 
-        let _ = engine.deploy(&file).expect("deploy");
-        let config = state
-            .config
-            .lock()
-            .expect("config lock")
-            .clone()
-            .expect("config captured");
+    #[test]
+    fn memory_reservation_must_fit_within_memory() {
+        let mib = 1024 * 1024;
+        let config = deployed_config_with_defaults(
+            r#"COMPONENT web {
+    image = "file:///unused"
+    memory = "256MiB"
+    memory_reservation = "128MiB"
+    memory_high = "192MiB"
+}"#,
+            DefaultsConfig::default(),
+        );
+        assert_eq!(config.memory_low_bytes, Some(128 * mib));
+        assert_eq!(config.memory_high_bytes, Some(192 * mib));
 
-        assert_eq!(config.ports, vec![8080, 9090]);
+        let comp = containust_compose::parser::ast::ComponentDecl {
+            name: "web".into(),
+            memory_reservation: Some("512MiB".into()),
+            ..Default::default()
+        };
+        let error = component_soft_limits(&comp, Some(256 * mib)).expect_err("above memory");
+        assert!(error.to_string().contains("memory_reservation"), "{error}");
         assert_eq!(
-            config.restart,
-            containust_common::types::RestartPolicy::OnFailure
+            component_soft_limits(&comp, None).expect("no memory limit"),
+            (Some(512 * mib), None)
         );
-        let healthcheck = config.healthcheck.expect("healthcheck spec");
-        assert_eq!(healthcheck.command[0], "curl");
-        assert_eq!(healthcheck.interval_secs, 10);
-        assert_eq!(healthcheck.timeout_secs, 3);
-        assert_eq!(healthcheck.retries, 5);
-        assert_eq!(healthcheck.start_period_secs, 60);
     }
 
     #[test]
-    fn deploy_rejects_invalid_restart_policy_value() {
-        let dir = tempfile::tempdir().expect("tempdir");
-        let file = dir.path().join("restart.ctst");
-        std::fs::write(
-            &file,
-            r#"COMPONENT app {
+    fn cpu_max_is_deployed_as_quota_and_period() {
+        let config = deployed_config_with_defaults(
+            r#"COMPONENT web {
     image = "file:///unused"
-    restart = "sometimes"
+    cpu_max = "25000 50000"
 }"#,
-        )
-        .expect("write composition");
-        let state = Arc::new(FakeState::default());
-        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
-
-        let error = engine.deploy(&file).expect_err("invalid restart");
-        assert!(error.to_string().contains("restart policy"));
-        assert!(state.config.lock().expect("config lock").is_none());
+            DefaultsConfig::default(),
+        );
+        assert_eq!(config.cpu_quota_us, Some(25_000));
+        assert_eq!(config.cpu_period_us, Some(50_000));
     }
 
     #[test]
-    fn deploy_expose_identity_publishes_port() {
-        let dir = tempfile::tempdir().expect("tempdir");
-        let file = dir.path().join("expose.ctst");
-        std::fs::write(
-            &file,
+    fn cpuset_is_parsed_into_cores() {
+        let config = deployed_config_with_defaults(
             r#"COMPONENT web {
     image = "file:///unused"
-    port = 3000
-}
-EXPOSE 3000"#,
-        )
-        .expect("write composition");
-        let state = Arc::new(FakeState::default());
-        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+    cpuset = "0"
+}"#,
+            DefaultsConfig::default(),
+        );
+        assert_eq!(config.cpuset_cpus, Some(vec![0]));
 
-        let _ = engine.deploy(&file).expect("deploy");
-        let config = state
-            .config
-            .lock()
-            .expect("config lock")
-            .clone()
-            .expect("config captured");
-        assert_eq!(config.ports, vec![3000]);
-        // Published ports share the host network namespace on Linux.
-        assert!(!config.namespaces.network);
+        let comp = containust_compose::parser::ast::ComponentDecl {
+            name: "web".into(),
+            cpuset: Some("2-1".into()),
+            ..Default::default()
+        };
+        let error = component_cpuset(&comp).expect_err("backwards range");
+        assert!(error.to_string().contains("cpuset"), "{error}");
     }
 
+    #[cfg(target_os = "linux")]
     #[test]
-    fn deploy_expose_remap_publishes_mapping() {
-        let dir = tempfile::tempdir().expect("tempdir");
-        let file = dir.path().join("remap.ctst");
-        std::fs::write(
-            &file,
-            r#"COMPONENT web {
-    image = "file:///unused"
-    port = 8080
-}
-EXPOSE 80:8080"#,
-        )
-        .expect("write composition");
-        let state = Arc::new(FakeState::default());
-        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
-
-        let _ = engine.deploy(&file).expect("remap deploy");
-        let config = state
-            .config
-            .lock()
-            .expect("config lock")
-            .clone()
-            .expect("config captured");
-        assert_eq!(
-            config.port_mappings,
-            vec![containust_common::types::PortMapping {
-                host: 80,
-                container: 8080,
-            }]
+    fn cpuset_rejects_cores_the_host_lacks() {
+        let comp = containust_compose::parser::ast::ComponentDecl {
+            name: "web".into(),
+            cpuset: Some("0,8000".into()),
+            ..Default::default()
+        };
+        let error = component_cpuset(&comp).expect_err("offline core");
+        assert!(
+            error.to_string().contains("core 8000 is not online"),
+            "{error}"
         );
-        assert!(config.namespaces.network);
-        assert!(config.namespaces.user);
-        assert_eq!(config.network, "none");
     }
 
     #[test]
-    fn deploy_named_network_keeps_shared_netns() {
-        let dir = tempfile::tempdir().expect("tempdir");
-        let file = dir.path().join("named-net.ctst");
-        std::fs::write(
-            &file,
+    fn numa_node_zero_is_always_accepted() {
+        let config = deployed_config_with_defaults(
             r#"COMPONENT web {
     image = "file:///unused"
-    network = "backend"
+    numa_node = "0"
 }"#,
-        )
-        .expect("write composition");
-        let state = Arc::new(FakeState::default());
-        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+            DefaultsConfig::default(),
+        );
+        assert_eq!(config.cpuset_mems, Some(vec![0]));
 
-        let _ = engine.deploy(&file).expect("named network deploy");
-        let config = state
-            .config
-            .lock()
-            .expect("config lock")
-            .clone()
-            .expect("config captured");
-        assert_eq!(config.network, "backend");
-        assert!(config.namespaces.network);
-        // Shared netns is init-userns-owned; userns must stay off for setns.
-        assert!(!config.namespaces.user);
-        assert!(config.namespaces.pid);
+        let comp = containust_compose::parser::ast::ComponentDecl {
+            name: "web".into(),
+            numa_node: Some("zero".into()),
+            ..Default::default()
+        };
+        let error = component_numa_nodes(&comp).expect_err("not a node");
+        assert!(error.to_string().contains("numa_node"), "{error}");
     }
 
     #[test]
-    fn deploy_healthcheck_example_no_longer_errors() {
-        let example =
-            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/healthcheck_example.ctst");
-        let dir = tempfile::tempdir().expect("tempdir");
-        let state = Arc::new(FakeState::default());
-        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+    fn ipc_block_becomes_ipc_namespace_limits() {
+        let config = deployed_config_with_defaults(
+            r#"COMPONENT db {
+    image = "file:///unused"
+    ipc = { shmmax = "64MiB", msgmni = 32 }
+}"#,
+            DefaultsConfig::default(),
+        );
+        assert_eq!(config.ipc_limits.shmmax_bytes, Some(64 * 1024 * 1024));
+        assert_eq!(config.ipc_limits.shmall_pages, None);
+        assert_eq!(config.ipc_limits.msgmni, Some(32));
+
+        let comp = containust_compose::parser::ast::ComponentDecl {
+            name: "db".into(),
+            ipc: Some(containust_compose::parser::ast::IpcDecl {
+                shmmax: Some("lots".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let error = component_ipc_limits(&comp).expect_err("bad size");
+        assert!(error.to_string().contains("ipc.shmmax"), "{error}");
+    }
 
-        let deployed = engine.deploy(&example).expect("example deploys");
-        assert!(!deployed.is_empty());
+    #[test]
+    fn swap_beyond_memory_rejects_inconsistent_totals() {
+        let mib = 1024 * 1024;
+        assert_eq!(swap_beyond_memory("web", None, 0).expect("disabled"), 0);
+        let below = swap_beyond_memory("web", Some(256 * mib), 128 * mib).expect_err("below");
+        assert!(below.to_string().contains("at least memory"), "{below}");
+        let no_memory = swap_beyond_memory("web", None, 128 * mib).expect_err("no memory");
+        assert!(
+            no_memory.to_string().contains("requires a memory limit"),
+            "{no_memory}"
+        );
     }
 
     #[test]
-    fn parse_duration_secs_supports_units() {
-        assert_eq!(parse_duration_secs("30s"), Some(30));
-        assert_eq!(parse_duration_secs("5m"), Some(300));
-        assert_eq!(parse_duration_secs("1h"), Some(3600));
-        assert_eq!(parse_duration_secs("45"), Some(45));
-        assert_eq!(parse_duration_secs("abc"), None);
+    fn limit_defaults_fill_in_undeclared_limits() {
+        let config = deployed_config_with_defaults(
+            r#"COMPONENT web { image = "file:///unused" }"#,
+            sample_defaults(),
+        );
+        assert_eq!(config.memory_bytes, Some(256 * 1024 * 1024));
+        assert_eq!(config.cpu_shares, Some(1024));
+        assert_eq!(config.pids_max, Some(64));
     }
 
     #[test]
-    fn deploy_rejects_invalid_resource_value() {
+    fn limit_defaults_yield_to_explicit_values() {
+        let config = deployed_config_with_defaults(
+            r#"COMPONENT web {
+    image = "file:///unused"
+    cpu = "0.5"
+    memory = "64MiB"
+}"#,
+            sample_defaults(),
+        );
+        assert_eq!(config.memory_bytes, Some(64 * 1024 * 1024));
+        assert_eq!(config.cpu_shares, Some(512));
+    }
+
+    #[test]
+    fn unlimited_opts_out_of_limit_defaults() {
+        let config = deployed_config_with_defaults(
+            r#"COMPONENT web {
+    image = "file:///unused"
+    memory = "unlimited"
+    cpu = "Unlimited"
+}"#,
+            sample_defaults(),
+        );
+        assert_eq!(config.memory_bytes, None);
+        assert_eq!(config.cpu_shares, None);
+        assert_eq!(config.pids_max, Some(64));
+    }
+
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().expect("log lock").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn deploy_events_carry_container_span_fields() {
         let dir = tempfile::tempdir().expect("tempdir");
-        let file = dir.path().join("invalid-memory.ctst");
+        let file = dir.path().join("app.ctst");
+        std::fs::write(&file, r#"COMPONENT web { image = "file:///unused" }"#)
+            .expect("write composition");
+        let engine = fake_engine(
+            Arc::new(FakeState::default()),
+            dir.path().join("data"),
+            false,
+        );
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(move || writer.clone())
+            .finish();
+
+        let _ =
+            tracing::subscriber::with_default(subscriber, || engine.deploy(&file)).expect("deploy");
+        let output = String::from_utf8(logs.0.lock().expect("log lock").clone()).expect("utf8");
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("json line"))
+            .collect();
+        let backend_event = events
+            .iter()
+            .find(|event| event["fields"]["message"] == "fake backend starting")
+            .expect("backend event");
+        assert_eq!(backend_event["span"]["name"], "container");
+        assert_eq!(backend_event["span"]["container_id"], "fake-id");
+        assert_eq!(backend_event["span"]["container_name"], "web");
+        let created = events
+            .iter()
+            .find(|event| event["fields"]["message"] == "container created")
+            .expect("created event");
+        assert_eq!(created["span"]["container_id"], "fake-id");
+        assert_eq!(created["span"]["container_name"], "web");
+    }
+
+    #[test]
+    fn limit_override_for_unknown_component_fails_before_create() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("app.ctst");
+        std::fs::write(&file, r#"COMPONENT web { image = "file:///unused" }"#)
+            .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false)
+            .with_limit_overrides(vec![LimitOverride {
+                component: "db".into(),
+                pids_max: Some(16),
+                ..LimitOverride::default()
+            }]);
+
+        let error = engine.deploy(&file).expect_err("unknown component");
+        assert!(error.to_string().contains("\"db\""));
+        assert!(state.config.lock().expect("config lock").is_none());
+    }
+
+    fn register_catalog_image(data_dir: &Path, name: &str, entrypoint: &[&str], cmd: &[&str]) {
+        let store = containust_image::storage::StorageBackend::open(data_dir.to_path_buf())
+            .expect("open store");
+        let staged = store.staging_path();
+        std::fs::write(&staged, b"layer").expect("write staged layer");
+        store.commit_layer(&staged, "layer1").expect("commit layer");
+        let entry = containust_image::registry::ImageEntry {
+            id: containust_common::types::ImageId::new(name),
+            name: name.into(),
+            source: "file:///unused".into(),
+            layers: vec!["layer1".into()],
+            size_bytes: 5,
+            created_at: "2026-01-01T00:00:00Z".into(),
+            digest: None,
+            tool_version: String::new(),
+            entrypoint: Some(entrypoint.iter().map(ToString::to_string).collect()),
+            cmd: cmd.iter().map(ToString::to_string).collect(),
+        };
+        containust_image::registry::ImageCatalog::open(data_dir)
+            .expect("open catalog")
+            .register(entry)
+            .expect("register image");
+    }
+
+    fn deployed_command(ctst: &str, entrypoint: &[&str], cmd: &[&str]) -> Vec<String> {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let data_dir = dir.path().join("data");
+        register_catalog_image(&data_dir, "web", entrypoint, cmd);
+        let file = dir.path().join("entrypoint.ctst");
+        std::fs::write(&file, ctst).expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), data_dir, false);
+
+        let _ = engine.deploy(&file).expect("deploy");
+        let config = state.config.lock().expect("config lock").clone();
+        config.expect("config captured").command
+    }
+
+    #[test]
+    fn deploy_uses_image_default_entrypoint_and_cmd() {
+        let command = deployed_command(
+            r#"COMPONENT web { image = "image://web" }"#,
+            &["/entry.sh"],
+            &["nginx"],
+        );
+        assert_eq!(command, vec!["/entry.sh", "nginx"]);
+    }
+
+    #[test]
+    fn deploy_command_appends_to_image_entrypoint() {
+        let command = deployed_command(
+            r#"COMPONENT web {
+    image = "image://web"
+    command = ["--port", "80"]
+}"#,
+            &["/entry.sh"],
+            &["nginx"],
+        );
+        assert_eq!(command, vec!["/entry.sh", "--port", "80"]);
+    }
+
+    #[test]
+    fn deploy_component_entrypoint_overrides_image_default() {
+        let command = deployed_command(
+            r#"COMPONENT web {
+    image = "image://web"
+    entrypoint = "/bin/custom"
+}"#,
+            &["/entry.sh"],
+            &["nginx"],
+        );
+        assert_eq!(command, vec!["/bin/custom"]);
+    }
+
+    #[test]
+    fn offline_deploy_rejects_remote_image_before_create() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("remote.ctst");
         std::fs::write(
             &file,
+            "COMPONENT app { image = \"https://example.test/app.tar\" }",
+        )
+        .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), true);
+
+        assert!(engine.deploy(&file).is_err());
+        assert!(state.config.lock().expect("config lock").is_none());
+    }
+
+    #[test]
+    fn deploy_records_numeric_user_and_rejects_names() {
+        let config = deployed_config_with_defaults(
             r#"COMPONENT app {
     image = "file:///unused"
-    memory = "a lot"
+    user = "1000:1000"
+}"#,
+            DefaultsConfig::default(),
+        );
+        assert_eq!(config.user.as_deref(), Some("1000:1000"));
+
+        let named = r#"COMPONENT app {
+    image = "file:///unused"
+    user = "www-data"
+}"#;
+        let shared_network = r#"COMPONENT app {
+    image = "file:///unused"
+    user = "1000"
+    network = "backend"
+}"#;
+        for ctst in [named, shared_network] {
+            let dir = tempfile::tempdir().expect("tempdir");
+            let file = dir.path().join("user.ctst");
+            std::fs::write(&file, ctst).expect("write composition");
+            let state = Arc::new(FakeState::default());
+            let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+            let error = engine.deploy(&file).expect_err("unmappable user");
+            assert!(error.to_string().contains("user"), "{error}");
+            assert!(state.config.lock().expect("config lock").is_none());
+        }
+    }
+
+    #[test]
+    fn deploy_records_hostname_and_rejects_oversized_ones() {
+        let config = deployed_config_with_defaults(
+            r#"COMPONENT app {
+    image = "file:///unused"
+    hostname = "web-server"
+}"#,
+            DefaultsConfig::default(),
+        );
+        assert_eq!(config.hostname.as_deref(), Some("web-server"));
+
+        let comp = containust_compose::parser::ast::ComponentDecl {
+            name: "app".into(),
+            hostname: Some("h".repeat(65)),
+            ..Default::default()
+        };
+        let error = validate_runtime_component(&comp).expect_err("too long");
+        assert!(error.to_string().contains("hostname"), "{error}");
+    }
+
+    #[test]
+    fn deploy_records_absolute_workdir() {
+        let config = deployed_config_with_defaults(
+            r#"COMPONENT app {
+    image = "file:///unused"
+    workdir = "/srv"
+}"#,
+            DefaultsConfig::default(),
+        );
+        assert_eq!(config.workdir.as_deref(), Some("/srv"));
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("relative.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT app {
+    image = "file:///unused"
+    workdir = "srv"
 }"#,
         )
         .expect("write composition");
         let state = Arc::new(FakeState::default());
         let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+        let error = engine.deploy(&file).expect_err("relative workdir");
+        assert!(error.to_string().contains("absolute"), "{error}");
+    }
 
-        let error = engine.deploy(&file).expect_err("invalid memory");
-        assert!(error.to_string().contains("invalid memory"));
+    #[test]
+    fn deploy_records_requested_capabilities() {
+        let config = deployed_config_with_defaults(
+            r#"COMPONENT app {
+    image = "file:///unused"
+    capabilities = ["CAP_NET_BIND_SERVICE", "kill"]
+}"#,
+            DefaultsConfig::default(),
+        );
+        assert_eq!(
+            config.capabilities,
+            [
+                containust_core::capability::Capability::NetBindService,
+                containust_core::capability::Capability::Kill,
+            ]
+        );
+    }
+
+    #[test]
+    fn deploy_passes_ports_restart_and_healthcheck() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("policies.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT app {
+    image = "file:///unused"
+    ports = [8080, 9090]
+    restart = "on-failure"
+    healthcheck = {
+        command = ["curl", "-f", "http://localhost:8080/healthz"]
+        interval = "10s"
+        timeout = "3s"
+        retries = 5
+        start_period = "1m"
+    }
+}"#,
+        )
+        .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let _ = engine.deploy(&file).expect("deploy");
+        let config = state
+            .config
+            .lock()
+            .expect("config lock")
+            .clone()
+            .expect("config captured");
+
+        assert_eq!(config.ports, vec![8080, 9090]);
+        assert_eq!(
+            config.restart,
+            containust_common::types::RestartPolicy::OnFailure
+        );
+        let healthcheck = config.healthcheck.expect("healthcheck spec");
+        assert_eq!(healthcheck.command[0], "curl");
+        assert_eq!(healthcheck.interval_secs, 10);
+        assert_eq!(healthcheck.timeout_secs, 3);
+        assert_eq!(healthcheck.retries, 5);
+        assert_eq!(healthcheck.start_period_secs, 60);
+    }
+
+    #[test]
+    fn deploy_rejects_invalid_restart_policy_value() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("restart.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT app {
+    image = "file:///unused"
+    restart = "sometimes"
+}"#,
+        )
+        .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let error = engine.deploy(&file).expect_err("invalid restart");
+        assert!(error.to_string().contains("restart policy"));
         assert!(state.config.lock().expect("config lock").is_none());
     }
 
     #[test]
-    fn forced_stop_uses_backend_fast_path() {
+    fn deploy_passes_declared_stop_options() {
         let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("stop.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT app {
+    image = "file:///unused"
+    stop_signal = "quit"
+    stop_timeout = "30s"
+}"#,
+        )
+        .expect("write composition");
         let state = Arc::new(FakeState::default());
-        let engine = fake_engine(Arc::clone(&state), dir.path().to_path_buf(), false);
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
 
-        engine
-            .stop_with_force(&ContainerId::new("fake-id"), true)
-            .expect("force stop");
-        assert!(state.force_stopped.load(Ordering::Acquire));
+        let _ = engine.deploy(&file).expect("deploy");
+        let config = state
+            .config
+            .lock()
+            .expect("config lock")
+            .clone()
+            .expect("config captured");
+        assert_eq!(
+            config.stop,
+            StopOptions {
+                signal: Some(ContainerSignal::parse("SIGQUIT").expect("signal")),
+                timeout: Some(Duration::from_secs(30)),
+            }
+        );
+    }
+
+    #[test]
+    fn deploy_rejects_invalid_stop_signal() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("stop.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT app {
+    image = "file:///unused"
+    stop_signal = "SIGNOPE"
+}"#,
+        )
+        .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let error = engine.deploy(&file).expect_err("invalid stop signal");
+        let message = error.to_string();
+        assert!(
+            message.contains("component 'app': stop_signal: unknown signal 'SIGNOPE'"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn deploy_expose_identity_publishes_port() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("expose.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT web {
+    image = "file:///unused"
+    port = 3000
+}
+EXPOSE 3000"#,
+        )
+        .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let _ = engine.deploy(&file).expect("deploy");
+        let config = state
+            .config
+            .lock()
+            .expect("config lock")
+            .clone()
+            .expect("config captured");
+        assert_eq!(config.ports, vec![3000]);
+        // Published ports share the host network namespace on Linux.
+        assert!(!config.namespaces.network);
+    }
+
+    #[test]
+    fn deploy_expose_remap_publishes_mapping() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("remap.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT web {
+    image = "file:///unused"
+    port = 8080
+}
+EXPOSE 80:8080"#,
+        )
+        .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let _ = engine.deploy(&file).expect("remap deploy");
+        let config = state
+            .config
+            .lock()
+            .expect("config lock")
+            .clone()
+            .expect("config captured");
+        assert_eq!(
+            config.port_mappings,
+            vec![containust_common::types::PortMapping {
+                host: 80,
+                container: 8080,
+            }]
+        );
+        assert!(config.namespaces.network);
+        assert!(config.namespaces.user);
+        assert_eq!(config.network, "none");
+    }
+
+    #[test]
+    fn deploy_http_healthcheck_targets_published_host_port() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("http-probe.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT web {
+    image = "file:///unused"
+    port = 8080
+    healthcheck = { http = "http://localhost:8080/health", expect = 204 }
+}
+EXPOSE 18080:8080"#,
+        )
+        .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let _ = engine.deploy(&file).expect("deploy");
+        let config = state
+            .config
+            .lock()
+            .expect("config lock")
+            .clone()
+            .expect("config captured");
+        let healthcheck = config.healthcheck.expect("healthcheck spec");
+        assert!(healthcheck.command.is_empty());
+        assert_eq!(
+            healthcheck.http,
+            Some(containust_common::types::HttpProbe {
+                url: "http://localhost:18080/health".into(),
+                expect_status: 204,
+            })
+        );
+    }
+
+    #[test]
+    fn deploy_tcp_healthcheck_targets_published_host_port() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("tcp-probe.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT db {
+    image = "file:///unused"
+    port = 5432
+    healthcheck = { tcp = 5432 }
+}
+EXPOSE 15432:5432"#,
+        )
+        .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let _ = engine.deploy(&file).expect("deploy");
+        let config = state
+            .config
+            .lock()
+            .expect("config lock")
+            .clone()
+            .expect("config captured");
+        let healthcheck = config.healthcheck.expect("healthcheck spec");
+        assert_eq!(healthcheck.tcp, Some(15432));
+        assert!(healthcheck.http.is_none());
+    }
+
+    #[test]
+    fn deploy_named_network_keeps_shared_netns() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("named-net.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT web {
+    image = "file:///unused"
+    network = "backend"
+}"#,
+        )
+        .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let _ = engine.deploy(&file).expect("named network deploy");
+        let config = state
+            .config
+            .lock()
+            .expect("config lock")
+            .clone()
+            .expect("config captured");
+        assert_eq!(config.network, "backend");
+        assert!(config.namespaces.network);
+        // Shared netns is init-userns-owned; userns must stay off for setns.
+        assert!(!config.namespaces.user);
+        assert!(config.namespaces.pid);
+    }
+
+    #[test]
+    fn deploy_healthcheck_example_no_longer_errors() {
+        let example =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples/healthcheck_example.ctst");
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let deployed = engine.deploy(&example).expect("example deploys");
+        assert!(!deployed.is_empty());
+    }
+
+    #[test]
+    fn parse_duration_secs_supports_units() {
+        assert_eq!(parse_duration_secs("30s"), Some(30));
+        assert_eq!(parse_duration_secs("5m"), Some(300));
+        assert_eq!(parse_duration_secs("1h"), Some(3600));
+        assert_eq!(parse_duration_secs("45"), Some(45));
+        assert_eq!(parse_duration_secs("abc"), None);
+    }
+
+    #[test]
+    fn deploy_rejects_invalid_resource_value() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("invalid-memory.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT app {
+    image = "file:///unused"
+    memory = "a lot"
+}"#,
+        )
+        .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let error = engine.deploy(&file).expect_err("invalid memory");
+        assert!(error.to_string().contains("invalid memory"));
+        assert!(state.config.lock().expect("config lock").is_none());
+    }
+
+    #[test]
+    fn create_leaves_containers_created_without_starting() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("app.ctst");
+        std::fs::write(&file, r#"COMPONENT app { image = "file:///unused" }"#)
+            .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let created = engine.create(&file).expect("create");
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].pid, None);
+        assert!(!state.started.load(Ordering::Acquire));
+        assert_eq!(engine.list().expect("list")[0].state, "created");
+    }
+
+    #[test]
+    fn start_transitions_created_container_to_running() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("app.ctst");
+        std::fs::write(&file, r#"COMPONENT app { image = "file:///unused" }"#)
+            .expect("write composition");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+
+        let created = engine.create(&file).expect("create");
+        let pid = engine.start(&created[0].id).expect("start");
+        assert_eq!(pid, 42);
+        assert_eq!(engine.list().expect("list")[0].state, "running");
+    }
+
+    #[test]
+    fn create_composition_builds_adhoc_container() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+        let composition = adhoc_composition();
+
+        let created = engine.create_composition(&composition).expect("create");
+        let config = state.config.lock().expect("config lock").clone();
+        let config = config.expect("config captured");
+        assert_eq!(created[0].name, "adhoc");
+        assert_eq!(config.command, vec!["sleep", "60"]);
+        assert!(!state.started.load(Ordering::Acquire));
+        assert!(dir.path().join("data/logs").is_dir());
+    }
+
+    #[test]
+    fn deploy_composition_applies_adhoc_flags_and_starts() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+        let mut composition = adhoc_composition();
+        composition
+            .exposes
+            .push(containust_compose::parser::ast::ExposeDecl {
+                host_port: 8080,
+                container_port: 80,
+            });
+
+        let deployed = engine.deploy_composition(&composition).expect("deploy");
+        let config = state.config.lock().expect("config lock").clone();
+        let config = config.expect("config captured");
+        assert_eq!(deployed[0].pid, Some(42));
+        assert_eq!(config.env, vec![("MODE".into(), "test".into())]);
+        assert_eq!(config.memory_bytes, Some(64 * 1024 * 1024));
+        assert_eq!(config.cpu_shares, Some(512));
+        assert_eq!(
+            config.port_mappings,
+            vec![containust_common::types::PortMapping {
+                host: 8080,
+                container: 80,
+            }]
+        );
+    }
+
+    #[test]
+    fn deploy_composition_rejects_component_without_image() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().join("data"), false);
+        let mut composition = adhoc_composition();
+        composition.components[0].image = None;
+
+        assert!(engine.deploy_composition(&composition).is_err());
+        assert!(state.config.lock().expect("config lock").is_none());
+    }
+
+    fn adhoc_composition() -> containust_compose::parser::ast::CompositionFile {
+        let component = containust_compose::parser::ast::ComponentDecl {
+            name: "adhoc".into(),
+            image: Some("file:///unused".into()),
+            command: vec!["sleep".into(), "60".into()],
+            ports: vec![80],
+            memory: Some("64MiB".into()),
+            cpu: Some("0.5".into()),
+            env: [("MODE".to_string(), "test".to_string())].into(),
+            ..Default::default()
+        };
+        containust_compose::parser::ast::CompositionFile {
+            components: vec![component],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn forced_stop_uses_backend_fast_path() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = fake_engine(Arc::clone(&state), dir.path().to_path_buf(), false);
+
+        let outcome = engine
+            .stop_with_force(&ContainerId::new("fake-id"), true)
+            .expect("force stop");
+        assert_eq!(outcome, StopOutcome::Forced);
+        assert!(state.force_stopped.load(Ordering::Acquire));
+    }
+
+    fn running_fake_engine(state: &Arc<FakeState>, dir: &Path) -> Engine {
+        let file = dir.join("app.ctst");
+        std::fs::write(&file, r#"COMPONENT app { image = "file:///unused" }"#)
+            .expect("write composition");
+        let engine = fake_engine(Arc::clone(state), dir.join("data"), false)
+            .with_stop_timeout(Duration::from_millis(20));
+        let _ = engine.deploy(&file).expect("deploy");
+        engine
+    }
+
+    #[test]
+    fn running_conflicts_match_running_names_only() {
+        let composition =
+            containust_compose::parser::parse_ctst(r#"COMPONENT app { image = "file:///unused" }"#)
+                .expect("parse");
+        let info = |name: &str, state: &str| ContainerInfo {
+            id: ContainerId::new(format!("id-{name}-{state}")),
+            name: name.into(),
+            state: state.into(),
+            pid: None,
+            image: "file:///unused".into(),
+            created_at: String::new(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
+            health: None,
+        };
+        let existing = [
+            info("app", "running"),
+            info("app", "stopped"),
+            info("other", "running"),
+        ];
+
+        let conflicts = running_conflicts(&composition, &existing);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].id, ContainerId::new("id-app-running"));
+    }
+
+    fn deployed(name: &str) -> DeployedComponent {
+        DeployedComponent {
+            id: ContainerId::new(format!("id-{name}")),
+            name: name.into(),
+            port: None,
+            pid: Some(1),
+        }
+    }
+
+    fn names(components: &[DeployedComponent]) -> Vec<&str> {
+        components.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn diff_composition_classifies_added_removed_and_unchanged() {
+        let composition = containust_compose::parser::parse_ctst(
+            r#"COMPONENT api { image = "file:///api" }
+COMPONENT worker { image = "file:///worker" }
+COMPONENT cache { image = "file:///cache" }"#,
+        )
+        .expect("parse");
+        let current = [deployed("api"), deployed("db"), deployed("cache")];
+
+        let diff = diff_composition(&composition, &current);
+        assert_eq!(diff.added, ["worker"]);
+        assert_eq!(names(&diff.removed), ["db"]);
+        assert_eq!(names(&diff.unchanged), ["api", "cache"]);
+    }
+
+    #[test]
+    fn diff_composition_of_identical_set_changes_nothing() {
+        let composition =
+            containust_compose::parser::parse_ctst(r#"COMPONENT api { image = "file:///api" }"#)
+                .expect("parse");
+        let diff = diff_composition(&composition, &[deployed("api")]);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.unchanged[0].id, ContainerId::new("id-api"));
+    }
+
+    #[test]
+    fn stale_created_selects_old_created_entries() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-01-02T00:00:00Z")
+            .expect("now")
+            .to_utc();
+        let info = |name: &str, state: &str, created_at: &str| ContainerInfo {
+            id: ContainerId::new(name),
+            name: name.into(),
+            state: state.into(),
+            pid: None,
+            image: "file:///unused".into(),
+            created_at: created_at.into(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
+            health: None,
+        };
+        let existing = [
+            info("old", "created", "2026-01-01T00:00:00Z"),
+            info("fresh", "created", "2026-01-01T23:30:00Z"),
+            info("stopped", "stopped", "2026-01-01T00:00:00Z"),
+            info("garbled", "created", "yesterday"),
+        ];
+
+        let stale = stale_created(&existing, now, Duration::from_secs(3600));
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].name, "old");
+        assert_eq!(stale_created(&existing, now, Duration::ZERO).len(), 2);
+    }
+
+    #[test]
+    fn prune_created_measures_age_on_the_engine_clock() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let data_dir = dir.path().to_path_buf();
+        let state_file = data_dir.join("state.json");
+        let state: crate::state::StateFile = serde_json::from_value(serde_json::json!({
+            "schema_version": crate::state::CURRENT_STATE_SCHEMA,
+            "containers": [{
+                "id": "stale", "name": "stale", "state": "Created", "pid": null,
+                "image": "file:///unused", "rootfs_path": null, "log_path": null,
+                "created_at": "2026-01-01T00:00:00Z",
+            }],
+        }))
+        .expect("state");
+        crate::state::save_state(&state_file, &state).expect("save");
+        let clock = containust_common::clock::MockClock::new(
+            chrono::DateTime::parse_from_rfc3339("2026-01-01T00:30:00Z")
+                .expect("now")
+                .to_utc(),
+        );
+        let backend =
+            backend::linux::LinuxNativeBackend::with_paths(data_dir.clone(), state_file.clone());
+        let options = EngineOptions {
+            data_dir,
+            state_file,
+            offline: true,
+            backend: BackendKind::Auto,
+        };
+        let engine =
+            Engine::with_backend(options, Box::new(backend)).with_clock(Arc::new(clock.clone()));
+
+        let hour = Duration::from_secs(3600);
+        assert!(engine.prune_created(hour).expect("prune").is_empty());
+        clock.advance(Duration::from_secs(1800));
+        assert_eq!(engine.prune_created(hour).expect("prune").len(), 1);
+    }
+
+    #[test]
+    fn prune_created_removes_stale_entries_from_state() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let data_dir = dir.path().to_path_buf();
+        let state_file = data_dir.join("state.json");
+        let entry = |id: &str, state, created_at: &str| {
+            serde_json::json!({
+                "id": id, "name": id, "state": state, "pid": null,
+                "image": "file:///unused", "rootfs_path": null, "log_path": null,
+                "created_at": created_at,
+            })
+        };
+        let state: crate::state::StateFile = serde_json::from_value(serde_json::json!({
+            "schema_version": crate::state::CURRENT_STATE_SCHEMA,
+            "containers": [
+                entry("stale", "Created", "2020-01-01T00:00:00Z"),
+                entry("kept", "Stopped", "2020-01-01T00:00:00Z"),
+            ],
+        }))
+        .expect("state");
+        crate::state::save_state(&state_file, &state).expect("save");
+        let backend =
+            backend::linux::LinuxNativeBackend::with_paths(data_dir.clone(), state_file.clone());
+        let options = EngineOptions {
+            data_dir,
+            state_file: state_file.clone(),
+            offline: true,
+            backend: BackendKind::Auto,
+        };
+        let engine = Engine::with_backend(options, Box::new(backend));
+
+        let pruned = engine
+            .prune_created(Duration::from_secs(3600))
+            .expect("prune");
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].name, "stale");
+        let remaining = crate::state::load_state(&state_file).expect("load");
+        assert_eq!(remaining.containers.len(), 1);
+        assert_eq!(remaining.containers[0].name, "kept");
+    }
+
+    #[test]
+    fn redeploy_over_running_container_warns_by_default() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = running_fake_engine(&state, dir.path());
+
+        let deployed = engine
+            .deploy(&dir.path().join("app.ctst"))
+            .expect("duplicate deploy only warns");
+        assert_eq!(deployed[0].name, "app");
+    }
+
+    #[test]
+    fn redeploy_over_running_container_fails_if_requested() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = running_fake_engine(&state, dir.path()).with_fail_if_running(true);
+
+        let error = engine
+            .deploy(&dir.path().join("app.ctst"))
+            .expect_err("running container blocks deploy");
+        assert!(
+            error.to_string().contains("already running: app"),
+            "{error}"
+        );
+
+        state.stopped.store(true, Ordering::Release);
+        let _ = engine
+            .deploy(&dir.path().join("app.ctst"))
+            .expect("stopped container does not block");
+    }
+
+    #[test]
+    fn stop_all_graceful_when_container_exits() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = running_fake_engine(&state, dir.path());
+
+        let stopped = engine.stop_all().expect("stop all");
+        assert_eq!(stopped.len(), 1);
+        assert_eq!(stopped[0].name, "app");
+        assert_eq!(stopped[0].outcome, StopOutcome::Graceful);
+        assert!(!state.force_stopped.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn stop_with_options_passes_overrides_to_backend() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = running_fake_engine(&state, dir.path());
+        let options = StopOptions {
+            signal: Some(ContainerSignal::HUP),
+            timeout: Some(Duration::from_secs(5)),
+        };
+
+        let outcome = engine
+            .stop_with_options(&ContainerId::new("fake-id"), options)
+            .expect("stop");
+        assert_eq!(outcome, StopOutcome::Graceful);
+        assert_eq!(
+            *state.stop_options.lock().expect("stop options lock"),
+            Some(options)
+        );
+    }
+
+    #[test]
+    fn stop_all_escalates_when_container_ignores_stop() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = running_fake_engine(&state, dir.path());
+        state.ignores_stop.store(true, Ordering::Release);
+
+        let stopped = engine.stop_all().expect("stop all");
+        assert_eq!(stopped[0].outcome, StopOutcome::Escalated);
+        assert!(state.force_stopped.load(Ordering::Acquire));
+        assert_eq!(engine.list().expect("list")[0].state, "stopped");
+    }
+
+    #[test]
+    fn stop_escalates_single_container_after_timeout() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = running_fake_engine(&state, dir.path());
+        state.ignores_stop.store(true, Ordering::Release);
+
+        let outcome = engine.stop(&ContainerId::new("fake-id")).expect("stop");
+        assert_eq!(outcome, StopOutcome::Escalated);
+        assert!(state.force_stopped.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn stop_reports_escalation_done_by_the_backend() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let state = Arc::new(FakeState::default());
+        let engine = running_fake_engine(&state, dir.path());
+        state.kills_on_stop.store(true, Ordering::Release);
+
+        let outcome = engine.stop(&ContainerId::new("fake-id")).expect("stop");
+        assert_eq!(outcome, StopOutcome::Escalated);
+        assert!(!state.force_stopped.load(Ordering::Acquire));
+    }
+
+    /// Multi-container backend that records lifecycle calls in order and
+    /// fails the first `failures` probes of each named container.
+    /// `existing` containers are listed and can be stopped, removed, and
+    /// renamed. A `cancel_on_start` token is cancelled by the first start.
+    #[derive(Default)]
+    struct ScriptedBackend {
+        events: Mutex<Vec<String>>,
+        configs: Mutex<Vec<ContainerConfig>>,
+        failures: Mutex<HashMap<String, u32>>,
+        existing: Mutex<Vec<ContainerInfo>>,
+        cancel_on_start: Mutex<Option<CancellationToken>>,
+    }
+
+    impl ScriptedBackend {
+        fn failing(name: &str, failures: u32) -> Self {
+            let backend = Self::default();
+            let _ = backend
+                .failures
+                .lock()
+                .expect("failures lock")
+                .insert(name.into(), failures);
+            backend
+        }
+
+        fn record(&self, event: String) {
+            self.events.lock().expect("events lock").push(event);
+        }
+
+        /// Adds a running container `id` named `name`.
+        fn with_running(self, id: &str, name: &str) -> Self {
+            self.existing
+                .lock()
+                .expect("existing lock")
+                .push(ContainerInfo {
+                    id: ContainerId::new(id),
+                    name: name.into(),
+                    state: "running".into(),
+                    pid: Some(7),
+                    image: "file:///old".into(),
+                    created_at: String::new(),
+                    started_at: None,
+                    finished_at: None,
+                    exit_code: None,
+                    health: None,
+                });
+            self
+        }
+    }
+
+    impl ContainerBackend for Arc<ScriptedBackend> {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn create(&self, config: &ContainerConfig) -> Result<ContainerId> {
+            self.configs
+                .lock()
+                .expect("configs lock")
+                .push(config.clone());
+            Ok(ContainerId::new(&config.name))
+        }
+
+        fn start(&self, id: &ContainerId) -> Result<u32> {
+            self.record(format!("start {id}"));
+            if let Some(token) = self.cancel_on_start.lock().expect("cancel lock").as_ref() {
+                token.cancel();
+            }
+            Ok(1)
+        }
+
+        fn stop(&self, id: &ContainerId) -> Result<()> {
+            let mut existing = self.existing.lock().expect("existing lock");
+            if let Some(info) = existing.iter_mut().find(|info| info.id == *id) {
+                info.state = "stopped".into();
+                drop(existing);
+                self.record(format!("stop {id}"));
+            }
+            Ok(())
+        }
+
+        fn exec(&self, id: &ContainerId, cmd: &[String]) -> Result<ExecOutput> {
+            self.record(format!("exec {id} {}", cmd.join(" ")));
+            let mut failures = self.failures.lock().expect("failures lock");
+            let remaining = failures.entry(id.to_string()).or_default();
+            let exit_code = i32::from(*remaining > 0);
+            *remaining = remaining.saturating_sub(1);
+            drop(failures);
+            Ok(ExecOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code,
+            })
+        }
+
+        fn remove(&self, id: &ContainerId) -> Result<()> {
+            self.record(format!("remove {id}"));
+            self.existing
+                .lock()
+                .expect("existing lock")
+                .retain(|info| info.id != *id);
+            Ok(())
+        }
+
+        fn rename(&self, id: &ContainerId, new_name: &str) -> Result<()> {
+            self.record(format!("rename {id} {new_name}"));
+            if let Some(info) = self
+                .existing
+                .lock()
+                .expect("existing lock")
+                .iter_mut()
+                .find(|info| info.id == *id)
+            {
+                info.name = new_name.into();
+            }
+            Ok(())
+        }
+
+        fn logs(&self, _id: &ContainerId) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn list(&self) -> Result<Vec<ContainerInfo>> {
+            Ok(self.existing.lock().expect("existing lock").clone())
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    const READINESS_CTST: &str = r#"COMPONENT db {
+    image = "file:///unused"
+    readiness = { command = ["ready"], interval = "0s", retries = 3 }
+    liveness = { command = ["alive"], interval = "15s" }
+}
+COMPONENT api { image = "file:///unused" }
+CONNECT api -> db"#;
+
+    fn deploy_scripted(
+        backend: &Arc<ScriptedBackend>,
+        dir: &Path,
+        composition: &str,
+    ) -> Result<Vec<DeployedComponent>> {
+        let file = dir.join("app.ctst");
+        std::fs::write(&file, composition).expect("write composition");
+        let options = EngineOptions {
+            state_file: dir.join("data/state.json"),
+            data_dir: dir.join("data"),
+            offline: false,
+            backend: BackendKind::Auto,
+        };
+        Engine::with_backend(options, Box::new(Arc::clone(backend))).deploy(&file)
+    }
+
+    const UPGRADE_CTST: &str = r#"COMPONENT web {
+    image = "file:///unused"
+    readiness = { command = ["ready"], interval = "0s", retries = 3 }
+}
+COMPONENT db { image = "file:///unused" }
+CONNECT web -> db"#;
+
+    fn upgrade_scripted(
+        backend: &Arc<ScriptedBackend>,
+        dir: &Path,
+        strategy: UpgradeStrategy,
+    ) -> Result<Vec<DeployedComponent>> {
+        let composition =
+            containust_compose::parser::parse_ctst(UPGRADE_CTST).expect("parse composition");
+        let options = EngineOptions {
+            state_file: dir.join("data/state.json"),
+            data_dir: dir.join("data"),
+            offline: false,
+            backend: BackendKind::Auto,
+        };
+        Engine::with_backend(options, Box::new(Arc::clone(backend))).upgrade_composition(
+            &composition,
+            &["web".into()],
+            strategy,
+        )
+    }
+
+    #[test]
+    fn cancelled_deploy_removes_containers_it_created() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::default());
+        let token = CancellationToken::new();
+        *backend.cancel_on_start.lock().expect("cancel lock") = Some(token.clone());
+        let file = dir.path().join("app.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT db { image = "file:///unused" }
+COMPONENT api { image = "file:///unused" }
+CONNECT api -> db"#,
+        )
+        .expect("write composition");
+        let options = EngineOptions {
+            state_file: dir.path().join("data/state.json"),
+            data_dir: dir.path().join("data"),
+            offline: false,
+            backend: BackendKind::Auto,
+        };
+        let engine =
+            Engine::with_backend(options, Box::new(Arc::clone(&backend))).with_cancellation(token);
+
+        let error = engine.deploy(&file).expect_err("cancelled");
+        assert!(
+            matches!(error, ContainustError::Cancelled { .. }),
+            "{error}"
+        );
+        let events = backend.events.lock().expect("events lock").clone();
+        assert_eq!(events, vec!["start db", "remove db"]);
+        let created = backend.configs.lock().expect("configs lock").len();
+        assert_eq!(created, 1, "api must not be created after cancellation");
+    }
+
+    #[test]
+    fn cancellation_interrupts_a_readiness_wait() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::failing("db", 10));
+        let token = CancellationToken::new();
+        let file = dir.path().join("app.ctst");
+        std::fs::write(
+            &file,
+            r#"COMPONENT db {
+    image = "file:///unused"
+    readiness = { command = ["ready"], interval = "60s", retries = 10 }
+}
+COMPONENT api { image = "file:///unused" }
+CONNECT api -> db"#,
+        )
+        .expect("write composition");
+        let options = EngineOptions {
+            state_file: dir.path().join("data/state.json"),
+            data_dir: dir.path().join("data"),
+            offline: false,
+            backend: BackendKind::Auto,
+        };
+        let engine = Engine::with_backend(options, Box::new(Arc::clone(&backend)))
+            .with_cancellation(token.clone());
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            token.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let error = engine.deploy(&file).expect_err("cancelled");
+        canceller.join().expect("canceller");
+        assert!(
+            matches!(error, ContainustError::Cancelled { .. }),
+            "{error}"
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+        let events = backend.events.lock().expect("events lock").clone();
+        assert_eq!(
+            events,
+            vec!["start db", "exec db ready", "remove api", "remove db"]
+        );
+    }
+
+    fn reload_scripted(
+        backend: &Arc<ScriptedBackend>,
+        dir: &Path,
+        composition: &str,
+        current: &[DeployedComponent],
+    ) -> Result<(CompositionDiff, Vec<DeployedComponent>)> {
+        let file = dir.join("app.ctst");
+        std::fs::write(&file, composition).expect("write composition");
+        let options = EngineOptions {
+            state_file: dir.join("data/state.json"),
+            data_dir: dir.join("data"),
+            offline: false,
+            backend: BackendKind::Auto,
+        };
+        Engine::with_backend(options, Box::new(Arc::clone(backend)))
+            .reconcile_composition(&file, current)
+    }
+
+    fn running(id: &str, name: &str) -> DeployedComponent {
+        DeployedComponent {
+            id: ContainerId::new(id),
+            name: name.into(),
+            port: None,
+            pid: Some(7),
+        }
+    }
+
+    #[test]
+    fn reload_of_an_invalid_file_keeps_the_current_set() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::default().with_running("old-1", "old"));
+        let current = [running("old-1", "old")];
+
+        let result = reload_scripted(
+            &backend,
+            dir.path(),
+            r#"COMPONENT web { image = "file:///unused" }
+CONNECT web -> missing"#,
+            &current,
+        );
+
+        assert!(result.is_err());
+        assert!(backend.events.lock().expect("events lock").is_empty());
+        assert!(backend.configs.lock().expect("configs lock").is_empty());
+    }
+
+    #[test]
+    fn failed_reload_removes_what_it_created_and_keeps_the_current_set() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::failing("web", 5).with_running("old-1", "old"));
+        let current = [running("old-1", "old")];
+
+        let result = reload_scripted(
+            &backend,
+            dir.path(),
+            r#"COMPONENT web {
+    image = "file:///unused"
+    readiness = { command = ["ready"], interval = "0s", retries = 2 }
+}
+COMPONENT api { image = "file:///unused" }
+CONNECT api -> web"#,
+            &current,
+        );
+
+        assert!(result.is_err());
+        let events = backend.events.lock().expect("events lock").clone();
+        assert!(events.contains(&"remove web".to_string()), "{events:?}");
+        assert!(
+            !events.iter().any(|event| event.ends_with("old-1")),
+            "{events:?}"
+        );
+    }
+
+    #[test]
+    fn reload_starts_added_components_before_removing_dropped_ones() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::default().with_running("old-1", "old"));
+        let current = [running("old-1", "old")];
+
+        let (diff, deployed) = reload_scripted(
+            &backend,
+            dir.path(),
+            r#"COMPONENT web { image = "file:///unused" }"#,
+            &current,
+        )
+        .expect("reload");
+
+        assert_eq!(diff.added, ["web"]);
+        assert_eq!(names(&deployed), ["web"]);
+        let events = backend.events.lock().expect("events lock").clone();
+        assert_eq!(events, vec!["start web", "stop old-1", "remove old-1"]);
+    }
+
+    #[test]
+    fn uptime_is_measured_on_the_engine_clock() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::default().with_running("web-v1", "web"));
+        backend.existing.lock().expect("existing lock")[0].started_at =
+            Some("2026-01-01T00:00:00Z".into());
+        let clock = containust_common::clock::MockClock::new(
+            chrono::DateTime::parse_from_rfc3339("2026-01-01T00:01:30Z")
+                .expect("now")
+                .to_utc(),
+        );
+        let options = EngineOptions {
+            state_file: dir.path().join("data/state.json"),
+            data_dir: dir.path().join("data"),
+            offline: false,
+            backend: BackendKind::Auto,
+        };
+        let engine = Engine::with_backend(options, Box::new(Arc::clone(&backend)))
+            .with_clock(Arc::new(clock.clone()));
+        let id = ContainerId::new("web-v1");
+
+        assert_eq!(
+            engine.uptime(&id).expect("uptime"),
+            Some(Duration::from_secs(90))
+        );
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(
+            engine.uptime(&id).expect("uptime"),
+            Some(Duration::from_secs(150))
+        );
+        assert!(engine.uptime(&ContainerId::new("missing")).is_err());
+    }
+
+    #[test]
+    fn rolling_upgrade_starts_new_before_stopping_old() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(
+            ScriptedBackend::failing("web", 1)
+                .with_running("web-v1", "web")
+                .with_running("db-v1", "db"),
+        );
+
+        let upgraded =
+            upgrade_scripted(&backend, dir.path(), UpgradeStrategy::Rolling).expect("upgrade");
+        assert_eq!(upgraded.len(), 1);
+        assert_eq!(upgraded[0].id, ContainerId::new("web"));
+        let events = backend.events.lock().expect("events lock").clone();
+        assert_eq!(
+            events,
+            vec![
+                "rename web-v1 web-previous",
+                "start web",
+                "exec web ready",
+                "exec web ready",
+                "stop web-v1",
+                "remove web-v1",
+            ]
+        );
+    }
+
+    #[test]
+    fn recreate_upgrade_stops_old_before_starting_new() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::default().with_running("web-v1", "web"));
+
+        let _ = upgrade_scripted(&backend, dir.path(), UpgradeStrategy::Recreate).expect("upgrade");
+        let events = backend.events.lock().expect("events lock").clone();
+        assert_eq!(
+            events,
+            vec![
+                "stop web-v1",
+                "remove web-v1",
+                "start web",
+                "exec web ready"
+            ]
+        );
+    }
+
+    #[test]
+    fn rolling_upgrade_recreates_components_with_published_ports() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::default().with_running("web-v1", "web"));
+        let composition = containust_compose::parser::parse_ctst(
+            r#"COMPONENT web {
+    image = "file:///unused"
+    ports = [8080]
+}"#,
+        )
+        .expect("parse composition");
+        let options = EngineOptions {
+            state_file: dir.path().join("data/state.json"),
+            data_dir: dir.path().join("data"),
+            offline: false,
+            backend: BackendKind::Auto,
+        };
+
+        let _ = Engine::with_backend(options, Box::new(Arc::clone(&backend)))
+            .upgrade_composition(&composition, &["web".into()], UpgradeStrategy::Rolling)
+            .expect("upgrade");
+        let events = backend.events.lock().expect("events lock").clone();
+        assert_eq!(events, vec!["stop web-v1", "remove web-v1", "start web"]);
+    }
+
+    #[test]
+    fn rolling_upgrade_keeps_old_when_new_never_becomes_ready() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::failing("web", 3).with_running("web-v1", "web"));
+
+        let error = upgrade_scripted(&backend, dir.path(), UpgradeStrategy::Rolling)
+            .expect_err("web never ready");
+        assert!(
+            error.to_string().contains("did not become ready"),
+            "{error}"
+        );
+        let events = backend.events.lock().expect("events lock").clone();
+        assert!(!events.contains(&"stop web-v1".to_string()), "{events:?}");
+        assert_eq!(events.last().map(String::as_str), Some("rename web-v1 web"));
+        let existing = backend.existing.lock().expect("existing lock").clone();
+        assert_eq!(existing[0].name, "web");
+        assert_eq!(existing[0].state, "running");
+    }
+
+    #[test]
+    fn readiness_probe_gates_dependent_start() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::failing("db", 2));
+
+        let deployed = deploy_scripted(&backend, dir.path(), READINESS_CTST).expect("deploy");
+        assert_eq!(deployed.len(), 2);
+        let events = backend.events.lock().expect("events lock").clone();
+        assert_eq!(
+            events,
+            vec![
+                "start db",
+                "exec db ready",
+                "exec db ready",
+                "exec db ready",
+                "start api"
+            ]
+        );
+    }
+
+    #[test]
+    fn readiness_failure_blocks_dependent_start() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::failing("db", 3));
+
+        let error =
+            deploy_scripted(&backend, dir.path(), READINESS_CTST).expect_err("db never ready");
+        assert!(
+            error.to_string().contains("'db' did not become ready"),
+            "{error}"
+        );
+        let events = backend.events.lock().expect("events lock").clone();
+        assert!(!events.contains(&"start api".to_string()), "{events:?}");
+    }
+
+    #[test]
+    fn liveness_probe_alone_drives_restart_healthcheck() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::default());
+
+        let _ = deploy_scripted(&backend, dir.path(), READINESS_CTST).expect("deploy");
+        let configs = backend.configs.lock().expect("configs lock").clone();
+        let db = configs.iter().find(|c| c.name == "db").expect("db config");
+        let liveness = db.healthcheck.as_ref().expect("liveness spec");
+        assert_eq!(liveness.command, vec!["alive"]);
+        assert_eq!(liveness.interval_secs, 15);
+        let api = configs
+            .iter()
+            .find(|c| c.name == "api")
+            .expect("api config");
+        assert!(api.healthcheck.is_none());
+    }
+
+    const HOOKS_CTST: &str = r#"COMPONENT db {
+    image = "file:///unused"
+    readiness = { command = ["ready"], interval = "0s" }
+    post_start = ["migrate", "--up"]
+}
+COMPONENT api { image = "file:///unused" }
+CONNECT api -> db"#;
+
+    #[test]
+    fn post_start_hook_runs_after_start_and_readiness() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::default());
+
+        let _ = deploy_scripted(&backend, dir.path(), HOOKS_CTST).expect("deploy");
+        let events = backend.events.lock().expect("events lock").clone();
+        assert_eq!(
+            events,
+            vec![
+                "start db",
+                "exec db ready",
+                "exec db migrate --up",
+                "start api"
+            ]
+        );
+    }
+
+    #[test]
+    fn failing_required_hook_aborts_deploy() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::failing("db", 1));
+        let composition = r#"COMPONENT db {
+    image = "file:///unused"
+    post_start = ["migrate"]
+}
+COMPONENT api { image = "file:///unused" }
+CONNECT api -> db"#;
+
+        let error = deploy_scripted(&backend, dir.path(), composition).expect_err("hook fails");
+        assert!(
+            error.to_string().contains("post_start hook failed"),
+            "{error}"
+        );
+        let events = backend.events.lock().expect("events lock").clone();
+        assert!(!events.contains(&"start api".to_string()), "{events:?}");
+    }
+
+    #[test]
+    fn failing_optional_hook_does_not_abort_deploy() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let backend = Arc::new(ScriptedBackend::failing("db", 1));
+        let composition = r#"COMPONENT db {
+    image = "file:///unused"
+    post_start = { command = ["migrate"], optional = true }
+}"#;
+
+        let deployed = deploy_scripted(&backend, dir.path(), composition).expect("deploy");
+        assert_eq!(deployed.len(), 1);
     }
 }
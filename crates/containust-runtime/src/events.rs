@@ -2,7 +2,8 @@
 
 use std::sync::{Mutex, mpsc};
 
-use containust_common::types::ContainerId;
+use chrono::{DateTime, Utc};
+use containust_common::types::{ContainerId, ContainerState};
 use serde::Serialize;
 
 /// A structured runtime lifecycle event.
@@ -28,10 +29,12 @@ pub enum LifecycleEvent {
     StateChange {
         /// Container that changed.
         container_id: String,
-        /// Previous state label.
-        from: String,
-        /// New state label.
-        to: String,
+        /// Previous state.
+        from: ContainerState,
+        /// New state.
+        to: ContainerState,
+        /// When the transition was observed.
+        at: DateTime<Utc>,
     },
 }
 
@@ -14,19 +14,79 @@ pub struct ExecOutput {
     pub exit_code: i32,
 }
 
+/// Search path used when neither the container nor the caller sets `PATH`.
+const DEFAULT_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+/// Working directory, user, and environment of an exec'd command.
+///
+/// Unset fields inherit the container's own configuration through
+/// [`ExecOptions::merge`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecOptions {
+    /// Working directory inside the container.
+    pub workdir: Option<String>,
+    /// Numeric `uid[:gid]` to run as.
+    pub user: Option<String>,
+    /// Environment variables; a key set here replaces the inherited value.
+    pub env: Vec<(String, String)>,
+}
+
+impl ExecOptions {
+    /// Fills unset fields from `defaults`.
+    ///
+    /// `workdir` and `user` given here win. The environment starts from
+    /// `defaults.env` and each key given here replaces or extends it.
+    #[must_use]
+    pub fn merge(self, defaults: &Self) -> Self {
+        let mut env: Vec<(String, String)> = defaults
+            .env
+            .iter()
+            .filter(|(key, _)| !self.env.iter().any(|(explicit, _)| explicit == key))
+            .cloned()
+            .collect();
+        env.extend(self.env);
+        Self {
+            workdir: self.workdir.or_else(|| defaults.workdir.clone()),
+            user: self.user.or_else(|| defaults.user.clone()),
+            env,
+        }
+    }
+
+    /// Returns the `(uid, gid)` of `user`; the gid defaults to the uid.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error unless `user` is numeric `uid[:gid]`.
+    pub fn user_ids(&self) -> Result<Option<(u32, u32)>> {
+        let Some(user) = self.user.as_deref() else {
+            return Ok(None);
+        };
+        let (uid, gid) = user.split_once(':').unwrap_or((user, user));
+        match (uid.parse(), gid.parse()) {
+            (Ok(uid), Ok(gid)) => Ok(Some((uid, gid))),
+            _ => Err(ContainustError::Config {
+                message: format!("exec user must be a numeric uid[:gid], got '{user}'"),
+            }),
+        }
+    }
+}
+
 /// Joins the namespaces of a running container and executes a command.
 ///
 /// Uses `nsenter` to enter the target container's mount, UTS, IPC,
-/// network, and PID namespaces.
+/// network, and PID namespaces. The command sees only `options.env`
+/// (plus a default `PATH`), never the caller's environment.
 ///
 /// # Errors
 ///
-/// Returns an error if the command is empty or `nsenter` invocation fails.
+/// Returns an error if the command is empty, the user is not numeric, or
+/// `nsenter` invocation fails.
 #[cfg(target_os = "linux")]
 pub fn exec_in_container(
     container_id: &ContainerId,
     pid: u32,
     command: &[String],
+    options: &ExecOptions,
 ) -> Result<ExecOutput> {
     tracing::info!(id = %container_id, pid, cmd = ?command, "exec into container");
 
@@ -36,7 +96,8 @@ pub fn exec_in_container(
         });
     }
 
-    let output = std::process::Command::new("nsenter")
+    let mut nsenter = std::process::Command::new("nsenter");
+    let _ = nsenter
         .args([
             "--target",
             &pid.to_string(),
@@ -45,8 +106,18 @@ pub fn exec_in_container(
             "--ipc",
             "--net",
             "--pid",
-            "--",
         ])
+        .env_clear()
+        .env("PATH", DEFAULT_PATH)
+        .envs(options.env.iter().map(|(key, value)| (key, value)));
+    if let Some(workdir) = &options.workdir {
+        let _ = nsenter.arg(format!("--wd={workdir}"));
+    }
+    if let Some((uid, gid)) = options.user_ids()? {
+        let _ = nsenter.args(["--setuid", &uid.to_string(), "--setgid", &gid.to_string()]);
+    }
+    let output = nsenter
+        .arg("--")
         .args(command)
         .output()
         .map_err(|e| ContainustError::Io {
@@ -74,8 +145,63 @@ pub fn exec_in_container(
     _container_id: &ContainerId,
     _pid: u32,
     _command: &[String],
+    _options: &ExecOptions,
 ) -> Result<ExecOutput> {
     Err(ContainustError::Config {
         message: "exec requires Linux (use VM backend on macOS/Windows)".into(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(workdir: Option<&str>, user: Option<&str>, env: &[(&str, &str)]) -> ExecOptions {
+        ExecOptions {
+            workdir: workdir.map(str::to_string),
+            user: user.map(str::to_string),
+            env: env
+                .iter()
+                .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn merge_inherits_unset_fields_from_container() {
+        let container = options(Some("/srv"), Some("1000"), &[("MODE", "prod")]);
+        let merged = ExecOptions::default().merge(&container);
+        assert_eq!(merged, container);
+    }
+
+    #[test]
+    fn merge_prefers_explicit_values() {
+        let container = options(
+            Some("/srv"),
+            Some("1000"),
+            &[("MODE", "prod"), ("PORT", "80")],
+        );
+        let explicit = options(Some("/tmp"), Some("0"), &[("MODE", "debug"), ("X", "1")]);
+        let merged = explicit.merge(&container);
+        assert_eq!(merged.workdir.as_deref(), Some("/tmp"));
+        assert_eq!(merged.user.as_deref(), Some("0"));
+        assert_eq!(
+            merged.env,
+            options(None, None, &[("PORT", "80"), ("MODE", "debug"), ("X", "1")]).env
+        );
+    }
+
+    #[test]
+    fn user_ids_accept_numeric_uid_and_gid() {
+        assert_eq!(ExecOptions::default().user_ids().unwrap(), None);
+        assert_eq!(
+            options(None, Some("1000"), &[]).user_ids().unwrap(),
+            Some((1000, 1000))
+        );
+        assert_eq!(
+            options(None, Some("1000:50"), &[]).user_ids().unwrap(),
+            Some((1000, 50))
+        );
+        assert!(options(None, Some("nobody"), &[]).user_ids().is_err());
+    }
+}
@@ -0,0 +1,61 @@
+//! Host `SIGHUP` as a reload request for a foreground `ctst run`.
+//!
+//! The handler only raises a flag; the run loop polls it with
+//! [`take_reload_request`] and does the work outside signal context.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use containust_common::error::Result;
+
+static RELOAD_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn on_hangup(_signal: libc::c_int) {
+    RELOAD_REQUESTED.store(true, Ordering::Release);
+}
+
+/// Installs the `SIGHUP` handler; a no-op on hosts without the signal.
+///
+/// # Errors
+///
+/// Returns a configuration error if the handler cannot be installed.
+pub fn install_reload_handler() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use containust_common::error::ContainustError;
+        use nix::sys::signal::{SaFlags, SigAction, SigHandler, SigSet, Signal, sigaction};
+
+        let action = SigAction::new(
+            SigHandler::Handler(on_hangup),
+            SaFlags::SA_RESTART,
+            SigSet::empty(),
+        );
+        // SAFETY: the handler only stores to an atomic, which is
+        // async-signal-safe.
+        let _ = unsafe { sigaction(Signal::SIGHUP, &action) }.map_err(|errno| {
+            ContainustError::Config {
+                message: format!("failed to install SIGHUP handler: {errno}"),
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Returns whether `SIGHUP` arrived since the last call, clearing it.
+pub fn take_reload_request() -> bool {
+    RELOAD_REQUESTED.swap(false, Ordering::AcqRel)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sighup_raises_one_reload_request() {
+        install_reload_handler().unwrap();
+        let _ = take_reload_request();
+        nix::sys::signal::raise(nix::sys::signal::Signal::SIGHUP).unwrap();
+        assert!(take_reload_request());
+        assert!(!take_reload_request());
+    }
+}
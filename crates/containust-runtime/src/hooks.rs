@@ -0,0 +1,110 @@
+//! Component lifecycle hooks run during deployment.
+//!
+//! `pre_start` runs on the host just before the container starts;
+//! `post_start` runs inside the container (via the backend's `exec`) once
+//! it has started and passed its readiness probe. A failing hook aborts
+//! the deploy unless it is marked `optional`.
+
+use std::process::Command;
+
+use containust_common::error::{ContainustError, Result};
+use containust_common::types::ContainerId;
+use containust_compose::parser::ast::HookDecl;
+
+use crate::backend::ContainerBackend;
+
+/// Runs a `pre_start` hook on the host.
+///
+/// # Errors
+///
+/// Returns an error when a required hook cannot be spawned or exits
+/// non-zero.
+pub fn run_pre_start(component: &str, hook: &HookDecl) -> Result<()> {
+    let Some((program, args)) = hook.command.split_first() else {
+        return Ok(());
+    };
+    eprintln!("  Running pre_start hook for '{component}'...");
+    let outcome = match Command::new(program).args(args).status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("exited with {status}")),
+        Err(error) => Err(format!("could not run '{program}': {error}")),
+    };
+    settle(component, "pre_start", hook, outcome)
+}
+
+/// Runs a `post_start` hook inside the started container.
+///
+/// # Errors
+///
+/// Returns an error when a required hook cannot be executed or exits
+/// non-zero.
+pub fn run_post_start(
+    backend: &dyn ContainerBackend,
+    component: &str,
+    id: &ContainerId,
+    hook: &HookDecl,
+) -> Result<()> {
+    eprintln!("  Running post_start hook for '{component}'...");
+    let outcome = match backend.exec(id, &hook.command) {
+        Ok(output) if output.exit_code == 0 => Ok(()),
+        Ok(output) => Err(format!(
+            "exited with code {}: {}",
+            output.exit_code,
+            output.stderr.trim()
+        )),
+        Err(error) => Err(error.to_string()),
+    };
+    settle(component, "post_start", hook, outcome)
+}
+
+/// Turns a hook failure into an error unless the hook is optional.
+fn settle(
+    component: &str,
+    kind: &str,
+    hook: &HookDecl,
+    outcome: std::result::Result<(), String>,
+) -> Result<()> {
+    match outcome {
+        Ok(()) => Ok(()),
+        Err(reason) if hook.optional => {
+            tracing::warn!(component, kind, %reason, "optional hook failed");
+            Ok(())
+        }
+        Err(reason) => Err(ContainustError::Config {
+            message: format!("component '{component}': {kind} hook failed: {reason}"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(command: &[&str], optional: bool) -> HookDecl {
+        HookDecl {
+            command: command.iter().map(ToString::to_string).collect(),
+            optional,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pre_start_reports_exit_status_of_required_hook() {
+        assert!(run_pre_start("db", &hook(&["true"], false)).is_ok());
+        let error = run_pre_start("db", &hook(&["false"], false))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("component 'db': pre_start hook failed"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn optional_hook_failure_is_tolerated() {
+        let missing = hook(&["/nonexistent/containust-hook"], true);
+        assert!(run_pre_start("db", &missing).is_ok());
+        let required = hook(&["/nonexistent/containust-hook"], false);
+        assert!(run_pre_start("db", &required).is_err());
+    }
+}
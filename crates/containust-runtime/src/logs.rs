@@ -1,9 +1,113 @@
 //! Container log management.
+//!
+//! Structured lines are written as `<RFC3339 timestamp> <stream> <message>`;
+//! lines without that prefix (from [`append_log`] or older releases) read
+//! back as untimed stdout.
 
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, SecondsFormat, Utc};
+use containust_common::clock::{Clock, SystemClock};
 use containust_common::error::{ContainustError, Result};
+use serde::Serialize;
+
+/// Bytes read per step when scanning a log backwards for `--tail`.
+const TAIL_CHUNK: u64 = 8 * 1024;
+
+/// Which part of a container's log to read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LogOptions {
+    /// Only the last N lines.
+    pub tail: Option<usize>,
+    /// Only lines written at or after this instant.
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl LogOptions {
+    /// Whether the options select the whole log.
+    #[must_use]
+    pub const fn is_full(&self) -> bool {
+        self.tail.is_none() && self.since.is_none()
+    }
+
+    /// Applies the options to log text already in memory.
+    #[must_use]
+    pub fn apply(&self, text: &str) -> String {
+        let text = self
+            .since
+            .map_or_else(|| text.to_string(), |cutoff| lines_since(text, cutoff));
+        if let Some(count) = self.tail {
+            return tail_lines(&text, count).to_string();
+        }
+        text
+    }
+}
+
+/// Output stream a log line came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    /// Standard output.
+    Stdout,
+    /// Standard error.
+    Stderr,
+}
+
+impl LogStream {
+    /// Tag written before the message.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Stdout => "stdout",
+            Self::Stderr => "stderr",
+        }
+    }
+
+    fn parse(tag: &str) -> Option<Self> {
+        match tag {
+            "stdout" => Some(Self::Stdout),
+            "stderr" => Some(Self::Stderr),
+            _ => None,
+        }
+    }
+}
+
+/// One parsed log line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LogLine {
+    /// When the line was written; `None` for untagged lines.
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Stream the line came from; untagged lines count as stdout.
+    pub stream: LogStream,
+    /// Line text without the timestamp and stream prefix.
+    pub message: String,
+}
+
+impl LogLine {
+    /// Parses a structured line, falling back to untimed stdout for lines
+    /// without a timestamp and stream tag.
+    #[must_use]
+    pub fn parse(line: &str) -> Self {
+        let mut parts = line.splitn(3, ' ');
+        let timestamp = parts
+            .next()
+            .and_then(|text| DateTime::parse_from_rfc3339(text).ok());
+        let stream = parts.next().and_then(LogStream::parse);
+        match (timestamp, stream) {
+            (Some(timestamp), Some(stream)) => Self {
+                timestamp: Some(timestamp.with_timezone(&Utc)),
+                stream,
+                message: parts.next().unwrap_or_default().to_string(),
+            },
+            _ => Self {
+                timestamp: None,
+                stream: LogStream::Stdout,
+                message: line.to_string(),
+            },
+        }
+    }
+}
 
 /// Returns the log file path for a container.
 #[must_use]
@@ -11,6 +115,57 @@ pub fn log_path(data_dir: &Path, container_id: &str) -> PathBuf {
     data_dir.join("logs").join(format!("{container_id}.log"))
 }
 
+/// Returns the path of a container's previous-run log, `<id>.log.prev`.
+#[must_use]
+pub fn previous_log_path(data_dir: &Path, container_id: &str) -> PathBuf {
+    data_dir
+        .join("logs")
+        .join(format!("{container_id}.log.prev"))
+}
+
+/// Moves a container's log to [`previous_log_path`], replacing the one
+/// kept from an earlier run, so the next run starts a fresh log.
+///
+/// Returns whether a log was rotated; without a current log the previous
+/// one is kept.
+///
+/// # Errors
+///
+/// Returns an error if the log cannot be renamed.
+pub fn rotate_log(data_dir: &Path, container_id: &str) -> Result<bool> {
+    let current = log_path(data_dir, container_id);
+    if !current.exists() {
+        return Ok(false);
+    }
+    let previous = previous_log_path(data_dir, container_id);
+    std::fs::rename(&current, &previous).map_err(|e| ContainustError::Io {
+        path: previous,
+        source: e,
+    })?;
+    tracing::debug!(container_id, "log rotated for restart");
+    Ok(true)
+}
+
+/// Reads the part of a container's previous-run log selected by
+/// `options`; empty when the container has not been restarted.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read.
+pub fn read_previous_logs(
+    data_dir: &Path,
+    container_id: &str,
+    options: &LogOptions,
+) -> Result<String> {
+    let path = previous_log_path(data_dir, container_id);
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    let text =
+        std::fs::read_to_string(&path).map_err(|e| ContainustError::Io { path, source: e })?;
+    Ok(options.apply(&text))
+}
+
 /// Reads container logs from disk.
 ///
 /// Returns an empty string if the log file does not exist yet.
@@ -34,7 +189,136 @@ pub fn read_logs(data_dir: &Path, container_id: &str) -> Result<String> {
 ///
 /// Returns an error if the directory or file cannot be created or written.
 pub fn append_log(data_dir: &Path, container_id: &str, line: &str) -> Result<()> {
+    append_raw(&log_path(data_dir, container_id), line)
+}
+
+/// Appends a log line prefixed with the current RFC3339 timestamp and the
+/// `stream` tag.
+///
+/// # Errors
+///
+/// Returns an error if the directory or file cannot be created or written.
+pub fn append_log_ts(
+    data_dir: &Path,
+    container_id: &str,
+    stream: LogStream,
+    line: &str,
+) -> Result<()> {
+    append_raw(
+        &log_path(data_dir, container_id),
+        &stamp_line(&SystemClock, stream, line),
+    )
+}
+
+/// Formats `line` as a structured log line stamped with `clock`'s time.
+#[must_use]
+pub fn stamp_line(clock: &dyn Clock, stream: LogStream, line: &str) -> String {
+    let timestamp = clock.now().to_rfc3339_opts(SecondsFormat::Micros, true);
+    format!("{timestamp} {} {line}", stream.as_str())
+}
+
+/// Reads container logs as parsed lines; untagged lines are stdout with
+/// no timestamp.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read.
+pub fn read_logs_structured(data_dir: &Path, container_id: &str) -> Result<Vec<LogLine>> {
+    Ok(read_logs(data_dir, container_id)?
+        .lines()
+        .map(LogLine::parse)
+        .collect())
+}
+
+/// Reads container logs selected by `options`; see [`read_logs_tail`] and
+/// [`read_logs_since`].
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read.
+pub fn read_logs_with(data_dir: &Path, container_id: &str, options: &LogOptions) -> Result<String> {
+    if let (None, Some(count)) = (options.since, options.tail) {
+        return read_logs_tail(data_dir, container_id, count);
+    }
+    Ok(options.apply(&read_logs(data_dir, container_id)?))
+}
+
+/// Reads the last `count` lines of a container's log, scanning backwards
+/// from the end of the file so large logs are not loaded whole.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read.
+pub fn read_logs_tail(data_dir: &Path, container_id: &str, count: usize) -> Result<String> {
     let path = log_path(data_dir, container_id);
+    if count == 0 || !path.exists() {
+        return Ok(String::new());
+    }
+    let io_error = |source| ContainustError::Io {
+        path: path.clone(),
+        source,
+    };
+    let mut file = std::fs::File::open(&path).map_err(io_error)?;
+    let length = file.metadata().map_err(io_error)?.len();
+    let mut position = length;
+    let mut bytes = Vec::new();
+    while position > 0 {
+        let step = TAIL_CHUNK.min(position);
+        position -= step;
+        let _ = file.seek(SeekFrom::Start(position)).map_err(io_error)?;
+        let mut chunk = vec![0; usize::try_from(step).unwrap_or(usize::MAX)];
+        file.read_exact(&mut chunk).map_err(io_error)?;
+        chunk.extend_from_slice(&bytes);
+        bytes = chunk;
+        let body = bytes.strip_suffix(b"\n").unwrap_or(&bytes);
+        // `count` newlines before the end mean `count` whole lines are in.
+        if body.rsplit(|&byte| byte == b'\n').take(count + 1).count() > count {
+            break;
+        }
+    }
+    Ok(tail_lines(&String::from_utf8_lossy(&bytes), count).to_string())
+}
+
+/// Reads the lines of a container's log written at or after `cutoff`.
+///
+/// An untimed line belongs to the timed line before it; untimed lines at
+/// the start of the log count as older than any cutoff.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read.
+pub fn read_logs_since(
+    data_dir: &Path,
+    container_id: &str,
+    cutoff: DateTime<Utc>,
+) -> Result<String> {
+    Ok(lines_since(&read_logs(data_dir, container_id)?, cutoff))
+}
+
+/// Returns the last `count` lines of `text`, newlines included.
+fn tail_lines(text: &str, count: usize) -> &str {
+    if count == 0 {
+        return "";
+    }
+    let body = text.strip_suffix('\n').unwrap_or(text);
+    body.rmatch_indices('\n')
+        .nth(count - 1)
+        .map_or(text, |(index, _)| &text[index + 1..])
+}
+
+fn lines_since(text: &str, cutoff: DateTime<Utc>) -> String {
+    let mut keep = false;
+    text.split_inclusive('\n')
+        .filter(|line| {
+            if let Some(timestamp) = LogLine::parse(line.trim_end_matches(['\r', '\n'])).timestamp {
+                keep = timestamp >= cutoff;
+            }
+            keep
+        })
+        .collect()
+}
+
+fn append_raw(path: &Path, line: &str) -> Result<()> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| ContainustError::Io {
             path: parent.to_path_buf(),
@@ -44,12 +328,15 @@ pub fn append_log(data_dir: &Path, container_id: &str, line: &str) -> Result<()>
     let mut file = std::fs::OpenOptions::new()
         .create(true)
         .append(true)
-        .open(&path)
+        .open(path)
         .map_err(|e| ContainustError::Io {
-            path: path.clone(),
+            path: path.to_path_buf(),
             source: e,
         })?;
-    writeln!(file, "{line}").map_err(|e| ContainustError::Io { path, source: e })?;
+    writeln!(file, "{line}").map_err(|e| ContainustError::Io {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
     Ok(())
 }
 
@@ -149,6 +436,186 @@ mod tests {
         assert!(!b_logs.contains("from a"));
     }
 
+    #[test]
+    fn structured_lines_roundtrip() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        // Timestamps are written with microsecond precision.
+        let before = Utc::now() - chrono::Duration::microseconds(1);
+        append_log_ts(dir.path(), "c1", LogStream::Stdout, "ready").expect("stdout");
+        append_log_ts(dir.path(), "c1", LogStream::Stderr, "warn: two  spaces").expect("stderr");
+
+        let lines = read_logs_structured(dir.path(), "c1").expect("read");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].stream, LogStream::Stdout);
+        assert_eq!(lines[0].message, "ready");
+        assert!(lines[0].timestamp.expect("timestamp") >= before);
+        assert_eq!(lines[1].stream, LogStream::Stderr);
+        assert_eq!(lines[1].message, "warn: two  spaces");
+    }
+
+    #[test]
+    fn since_filter_reads_lines_stamped_by_the_clock() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let start = DateTime::from_timestamp(1_700_000_000, 0).expect("timestamp");
+        let clock = containust_common::clock::MockClock::new(start);
+        let write = |line| {
+            let stamped = stamp_line(&clock, LogStream::Stdout, line);
+            append_log(dir.path(), "c1", &stamped).expect("append");
+        };
+        write("boot");
+        clock.advance(std::time::Duration::from_secs(10));
+        write("serving");
+
+        let lines = read_logs_structured(dir.path(), "c1").expect("read");
+        assert_eq!(lines[0].timestamp, Some(start));
+        assert_eq!(lines[1].timestamp, Some(clock.now()));
+        let since =
+            read_logs_since(dir.path(), "c1", start + chrono::Duration::seconds(5)).expect("since");
+        assert!(
+            since.contains("serving") && !since.contains("boot"),
+            "{since}"
+        );
+    }
+
+    #[test]
+    fn legacy_untagged_lines_read_as_stdout() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        append_log(dir.path(), "c1", "plain line").expect("legacy");
+        append_log(dir.path(), "c1", "2026-01-01T00:00:00Z not-a-stream").expect("legacy");
+        append_log_ts(dir.path(), "c1", LogStream::Stderr, "tagged").expect("tagged");
+
+        let lines = read_logs_structured(dir.path(), "c1").expect("read");
+        assert_eq!(
+            lines[0],
+            LogLine {
+                timestamp: None,
+                stream: LogStream::Stdout,
+                message: "plain line".into(),
+            }
+        );
+        assert_eq!(lines[1].timestamp, None);
+        assert_eq!(lines[1].message, "2026-01-01T00:00:00Z not-a-stream");
+        assert_eq!(lines[2].stream, LogStream::Stderr);
+        assert!(
+            read_logs(dir.path(), "c1")
+                .expect("raw")
+                .contains("plain line")
+        );
+    }
+
+    fn write_numbered(dir: &Path, count: usize) {
+        for index in 1..=count {
+            append_log(dir, "c1", &format!("line {index}")).expect("append");
+        }
+    }
+
+    #[test]
+    fn tail_with_fewer_lines_than_requested_returns_all() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write_numbered(dir.path(), 3);
+        assert_eq!(
+            read_logs_tail(dir.path(), "c1", 10).expect("tail"),
+            "line 1\nline 2\nline 3\n"
+        );
+        assert_eq!(read_logs_tail(dir.path(), "missing", 10).expect("none"), "");
+    }
+
+    #[test]
+    fn tail_returns_exact_count() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write_numbered(dir.path(), 5);
+        assert_eq!(
+            read_logs_tail(dir.path(), "c1", 2).expect("tail"),
+            "line 4\nline 5\n"
+        );
+        assert_eq!(
+            read_logs_tail(dir.path(), "c1", 5).expect("tail"),
+            read_logs(dir.path(), "c1").expect("read")
+        );
+        assert_eq!(read_logs_tail(dir.path(), "c1", 0).expect("zero"), "");
+    }
+
+    #[test]
+    fn tail_spans_chunk_boundaries() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write_numbered(dir.path(), 5000);
+        let tail = read_logs_tail(dir.path(), "c1", 1500).expect("tail");
+        assert_eq!(tail.lines().count(), 1500);
+        assert!(tail.starts_with("line 3501\n"), "{}", &tail[..20]);
+        assert!(tail.ends_with("line 5000\n"));
+    }
+
+    #[test]
+    fn since_keeps_lines_at_or_after_cutoff() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        for line in [
+            "untimed start",
+            "2026-01-01T00:00:00Z stdout old",
+            "2026-01-01T00:04:59.999Z stderr just before",
+            "2026-01-01T00:05:00Z stdout at cutoff",
+            "continuation",
+            "2026-01-01T00:06:00Z stdout after",
+        ] {
+            append_log(dir.path(), "c1", line).expect("append");
+        }
+        let cutoff = "2026-01-01T00:05:00Z".parse().expect("cutoff");
+        assert_eq!(
+            read_logs_since(dir.path(), "c1", cutoff).expect("since"),
+            "2026-01-01T00:05:00Z stdout at cutoff\ncontinuation\n\
+             2026-01-01T00:06:00Z stdout after\n"
+        );
+        let options = LogOptions {
+            tail: Some(1),
+            since: Some(cutoff),
+        };
+        assert_eq!(
+            read_logs_with(dir.path(), "c1", &options).expect("both"),
+            "2026-01-01T00:06:00Z stdout after\n"
+        );
+        assert_eq!(
+            options.apply(&read_logs(dir.path(), "c1").expect("read")),
+            "2026-01-01T00:06:00Z stdout after\n"
+        );
+    }
+
+    #[test]
+    fn rotate_log_keeps_one_previous_run() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert!(!rotate_log(dir.path(), "c1").expect("nothing to rotate"));
+
+        append_log(dir.path(), "c1", "first run").expect("first");
+        assert!(rotate_log(dir.path(), "c1").expect("rotate first"));
+        append_log(dir.path(), "c1", "second run").expect("second");
+        assert!(rotate_log(dir.path(), "c1").expect("rotate second"));
+
+        assert_eq!(read_logs(dir.path(), "c1").expect("current"), "");
+        let previous =
+            read_previous_logs(dir.path(), "c1", &LogOptions::default()).expect("previous");
+        assert_eq!(previous, "second run\n");
+        assert!(!rotate_log(dir.path(), "c1").expect("no current log"));
+        assert!(previous_log_path(dir.path(), "c1").exists());
+    }
+
+    #[test]
+    fn read_previous_logs_applies_options_and_tolerates_absence() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let options = LogOptions {
+            tail: Some(1),
+            since: None,
+        };
+        assert_eq!(
+            read_previous_logs(dir.path(), "c1", &options).expect("absent"),
+            ""
+        );
+        append_log(dir.path(), "c1", "one").expect("one");
+        append_log(dir.path(), "c1", "two").expect("two");
+        let _ = rotate_log(dir.path(), "c1").expect("rotate");
+        assert_eq!(
+            read_previous_logs(dir.path(), "c1", &options).expect("tail"),
+            "two\n"
+        );
+    }
+
     #[test]
     fn read_logs_from_returns_incremental_content() {
         let dir = tempfile::tempdir().expect("tempdir");
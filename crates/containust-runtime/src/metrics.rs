@@ -29,6 +29,12 @@ pub struct MetricsSnapshot {
     pub cpu_usage_ns: u64,
     /// Memory usage in bytes (`memory.current` when available).
     pub memory_usage_bytes: u64,
+    /// Peak memory usage in bytes (`memory.peak`, kernel 5.19+).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_peak_bytes: Option<u64>,
+    /// OOM kills inside the container (`oom_kill` in `memory.events`).
+    #[serde(default)]
+    pub oom_kills: u64,
     /// Number of I/O read bytes (`io.stat` rbytes sum when available).
     pub io_read_bytes: u64,
     /// Number of I/O write bytes (`io.stat` wbytes sum when available).
@@ -57,15 +63,19 @@ impl MetricsSnapshot {
 /// Collects a metrics snapshot for the given container.
 ///
 /// On Linux, reads from the cgroup v2 filesystem under
-/// `/sys/fs/cgroup/containust/<container_id>/`.
+/// `/sys/fs/cgroup/containust/<project_id>/<container_id>/`, where the
+/// native backend places the container (see [`Engine::project_id`]).
+///
+/// [`Engine::project_id`]: crate::engine::Engine::project_id
 ///
 /// # Errors
 ///
 /// Returns an error only for unexpected I/O failures outside normal
 /// missing-cgroup cases (those yield `Missing` availability).
 #[cfg(target_os = "linux")]
-pub fn collect_metrics(container_id: &ContainerId) -> Result<MetricsSnapshot> {
-    let cgroup_path = std::path::Path::new("/sys/fs/cgroup/containust").join(container_id.as_str());
+pub fn collect_metrics(project_id: &str, container_id: &ContainerId) -> Result<MetricsSnapshot> {
+    let cgroup = container_cgroup(project_id, container_id);
+    let cgroup_path = cgroup.path();
     if !cgroup_path.exists() {
         return Ok(unavailable_snapshot(
             container_id,
@@ -74,9 +84,10 @@ pub fn collect_metrics(container_id: &ContainerId) -> Result<MetricsSnapshot> {
         ));
     }
 
-    let (memory, memory_av) = read_cgroup_u64(&cgroup_path.join("memory.current"))
-        .map_or((0, MetricAvailability::Missing), |value| {
-            (value, MetricAvailability::Available)
+    let (memory, memory_av) = cgroup
+        .stats()
+        .map_or((None, MetricAvailability::Missing), |stats| {
+            (Some(stats), MetricAvailability::Available)
         });
     let (cpu, cpu_av) = read_cpu_usage(&cgroup_path.join("cpu.stat"))
         .map_or((0, MetricAvailability::Missing), |value| {
@@ -90,7 +101,9 @@ pub fn collect_metrics(container_id: &ContainerId) -> Result<MetricsSnapshot> {
     Ok(MetricsSnapshot {
         container_id: container_id.clone(),
         cpu_usage_ns: cpu,
-        memory_usage_bytes: memory,
+        memory_usage_bytes: memory.map_or(0, |stats| stats.memory_current_bytes),
+        memory_peak_bytes: memory.and_then(|stats| stats.memory_peak_bytes),
+        oom_kills: memory.map_or(0, |stats| stats.oom_kills),
         io_read_bytes: io_read,
         io_write_bytes: io_write,
         cpu: cpu_av,
@@ -100,9 +113,16 @@ pub fn collect_metrics(container_id: &ContainerId) -> Result<MetricsSnapshot> {
     })
 }
 
+/// The cgroup the native backend creates for `container_id`.
 #[cfg(target_os = "linux")]
-fn read_cgroup_u64(path: &std::path::Path) -> Option<u64> {
-    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+fn container_cgroup(
+    project_id: &str,
+    container_id: &ContainerId,
+) -> containust_core::cgroup::CgroupManager {
+    containust_core::cgroup::CgroupManager::open(
+        &containust_core::cgroup::CgroupConfig::for_current_user().unwrap_or_default(),
+        &crate::backend::container_cgroup_id(project_id, container_id),
+    )
 }
 
 #[cfg(target_os = "linux")]
@@ -145,7 +165,7 @@ fn read_io_stat(path: &std::path::Path) -> Option<(u64, u64)> {
 ///
 /// Always returns `Ok` on non-Linux platforms.
 #[cfg(not(target_os = "linux"))]
-pub fn collect_metrics(container_id: &ContainerId) -> Result<MetricsSnapshot> {
+pub fn collect_metrics(_project_id: &str, container_id: &ContainerId) -> Result<MetricsSnapshot> {
     Ok(unavailable_snapshot(
         container_id,
         MetricAvailability::Unavailable,
@@ -162,6 +182,8 @@ fn unavailable_snapshot(
         container_id: container_id.clone(),
         cpu_usage_ns: 0,
         memory_usage_bytes: 0,
+        memory_peak_bytes: None,
+        oom_kills: 0,
         io_read_bytes: 0,
         io_write_bytes: 0,
         cpu: availability,
@@ -180,7 +202,7 @@ mod tests {
     #[test]
     fn collect_metrics_returns_snapshot() {
         let id = ContainerId::new("test-metrics");
-        let snap = collect_metrics(&id).expect("should succeed");
+        let snap = collect_metrics("test-project", &id).expect("should succeed");
         assert_eq!(snap.container_id, id);
     }
 
@@ -188,7 +210,7 @@ mod tests {
     #[test]
     fn non_linux_metrics_are_unavailable_not_idle() {
         let id = ContainerId::new("test-zero");
-        let snap = collect_metrics(&id).expect("should succeed");
+        let snap = collect_metrics("test-project", &id).expect("should succeed");
         assert_eq!(snap.cpu, MetricAvailability::Unavailable);
         assert_eq!(snap.memory, MetricAvailability::Unavailable);
         assert_eq!(snap.io, MetricAvailability::Unavailable);
@@ -200,8 +222,19 @@ mod tests {
     #[test]
     fn missing_cgroup_marks_fields_missing() {
         let id = ContainerId::new("definitely-missing-cgroup-id");
-        let snap = collect_metrics(&id).expect("ok");
+        let snap = collect_metrics("test-project", &id).expect("ok");
         assert_eq!(snap.cpu, MetricAvailability::Missing);
         assert!(snap.note.is_some());
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn metrics_read_the_cgroup_under_the_project() {
+        let cgroup = container_cgroup("0123abcd", &ContainerId::new("web-1"));
+        assert!(
+            cgroup.path().ends_with("0123abcd/web-1"),
+            "{}",
+            cgroup.path().display()
+        );
+    }
 }
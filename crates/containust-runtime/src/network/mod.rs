@@ -41,6 +41,17 @@ impl NetworkMode {
         matches!(self, Self::Host)
     }
 
+    /// The mode as recorded on a container: `host`, `none`, or the shared
+    /// network's name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Host => "host",
+            Self::None => "none",
+            Self::Shared(name) => name,
+        }
+    }
+
     /// Shared network name, if any.
     #[must_use]
     pub const fn shared_name(&self) -> Option<&str> {
@@ -64,6 +75,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn network_mode_name_round_trips_through_parse() {
+        for raw in ["host", "none", "bridge", "backend"] {
+            assert_eq!(NetworkMode::parse(Some(raw)).name(), raw);
+        }
+    }
+
     #[test]
     fn network_mode_parse_host_and_none() {
         assert_eq!(NetworkMode::parse(Some("host")), NetworkMode::Host);
@@ -0,0 +1,119 @@
+//! Health probes that run from the host rather than inside the container.
+//!
+//! They need no tooling in the image: the HTTP probe fetches a URL and
+//! the TCP probe opens a connection, both already pointed by the engine
+//! at the container's published port.
+
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::time::Duration;
+
+use containust_common::types::HttpProbe;
+
+use crate::backend::HealthResult;
+
+/// Fetches `probe.url` and compares the status with `probe.expect_status`.
+#[must_use]
+pub fn http(probe: &HttpProbe, timeout: Duration) -> HealthResult {
+    let client = match reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()
+    {
+        Ok(client) => client,
+        Err(error) => {
+            return HealthResult::Unhealthy(format!("failed to construct HTTP client: {error}"));
+        }
+    };
+    match client.get(&probe.url).send() {
+        Ok(response) if response.status().as_u16() == probe.expect_status => HealthResult::Healthy,
+        Ok(response) => HealthResult::Unhealthy(format!(
+            "HTTP {} from {} (expected {})",
+            response.status().as_u16(),
+            probe.url,
+            probe.expect_status
+        )),
+        Err(error) => HealthResult::Unhealthy(format!("HTTP probe failed: {error}")),
+    }
+}
+
+/// Succeeds when a TCP connection to `127.0.0.1:port` opens within `timeout`.
+#[must_use]
+pub fn tcp(port: u16, timeout: Duration) -> HealthResult {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    // A zero timeout is rejected by `connect_timeout`; treat it as minimal.
+    match TcpStream::connect_timeout(&addr, timeout.max(Duration::from_millis(1))) {
+        Ok(_) => HealthResult::Healthy,
+        Err(error) => HealthResult::Unhealthy(format!("TCP connect to {addr} failed: {error}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    use super::*;
+
+    /// Serves one request with `status` and returns the probe URL.
+    fn serve_once(status: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let _ = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0_u8; 1024];
+            let _ = stream.read(&mut buf).unwrap();
+            let response =
+                format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        format!("http://127.0.0.1:{port}/health")
+    }
+
+    fn probe(url: String, expect_status: u16) -> HealthResult {
+        http(&HttpProbe { url, expect_status }, Duration::from_secs(5))
+    }
+
+    #[test]
+    fn http_probe_with_expected_status_is_healthy() {
+        assert_eq!(probe(serve_once("200 OK"), 200), HealthResult::Healthy);
+    }
+
+    #[test]
+    fn http_probe_with_other_status_is_unhealthy() {
+        let result = probe(serve_once("503 Service Unavailable"), 200);
+        assert!(
+            matches!(&result, HealthResult::Unhealthy(reason) if reason.starts_with("HTTP 503")),
+            "{result:?}"
+        );
+    }
+
+    /// Returns a loopback port with nothing listening on it.
+    fn closed_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    #[test]
+    fn http_probe_against_closed_port_is_unhealthy() {
+        let result = probe(format!("http://127.0.0.1:{}/", closed_port()), 200);
+        assert!(!result.is_healthy());
+    }
+
+    #[test]
+    fn tcp_probe_against_listener_is_healthy() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert_eq!(tcp(port, Duration::from_secs(5)), HealthResult::Healthy);
+    }
+
+    #[test]
+    fn tcp_probe_against_closed_port_is_unhealthy() {
+        let result = tcp(closed_port(), Duration::from_secs(5));
+        assert!(
+            matches!(&result, HealthResult::Unhealthy(reason) if reason.contains("TCP connect")),
+            "{result:?}"
+        );
+    }
+}
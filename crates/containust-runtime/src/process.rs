@@ -4,7 +4,11 @@
 #![allow(clippy::print_stdout, clippy::print_stderr, unsafe_code, missing_docs)]
 
 use containust_common::error::{ContainustError, Result};
+use containust_core::capability::Capability;
+#[cfg(target_os = "linux")]
+use containust_core::filesystem::ops::{LinuxMountOps, MountOps, MountRequest};
 use containust_core::namespace::NamespaceConfig;
+use containust_core::namespace::ipc::IpcLimits;
 #[cfg(target_os = "linux")]
 use std::path::Path;
 
@@ -23,14 +27,29 @@ pub struct ProcessConfig {
     pub volumes: Vec<String>,
     /// Namespace isolation policy.
     pub namespaces: NamespaceConfig,
+    /// Limits written into the new IPC namespace; requires `namespaces.ipc`.
+    pub ipc_limits: IpcLimits,
     /// When set, join this netns instead of `unshare(CLONE_NEWNET)`.
     pub join_netns: Option<std::path::PathBuf>,
+    /// Working directory inside the container; `/` when unset.
+    pub workdir: Option<std::path::PathBuf>,
+    /// Hostname set in the new UTS namespace; requires `namespaces.uts`.
+    pub hostname: Option<String>,
+    /// Numeric `uid[:gid]` the process runs as, mapped to the invoking
+    /// user in the new user namespace; requires `namespaces.user`. Unset
+    /// maps root instead.
+    pub user: Option<String>,
     /// Log file receiving the container's stdout/stderr.
     ///
     /// `None` inherits the parent's stdio (foreground debugging only);
     /// backends set this so detached containers do not hold the CLI's
     /// output pipes open.
     pub log_path: Option<std::path::PathBuf>,
+    /// FIFO the process reads stdin from, kept open read-write so the
+    /// process never sees EOF; `None` connects stdin to `/dev/null`.
+    pub stdin_path: Option<std::path::PathBuf>,
+    /// Capabilities kept by the process; all others are dropped.
+    pub capabilities: Vec<Capability>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -66,6 +85,7 @@ pub fn spawn_container_process(config: &ProcessConfig) -> Result<u32> {
     use std::os::unix::process::CommandExt;
 
     config.namespaces.validate_for_spawn()?;
+    validate_namespaced_settings(config)?;
     let _ = crate::volume::validate_volumes(&config.volumes)?;
 
     tracing::info!(
@@ -86,11 +106,25 @@ pub fn spawn_container_process(config: &ProcessConfig) -> Result<u32> {
     let volumes = config.volumes.clone();
     let readonly_rootfs = config.readonly_rootfs;
     let namespaces = config.namespaces.clone();
+    let workdir = config.workdir.clone();
+    let hostname = config.hostname.clone();
+    let capabilities = config.capabilities.clone();
+    let ipc_limits = config.ipc_limits;
 
     // SAFETY: pre_exec runs in the child between fork and exec.
     unsafe {
         let _ = child_cmd.pre_exec(move || {
-            configure_child_isolation(&rootfs_owned, &volumes, readonly_rootfs, &namespaces)
+            containust_core::namespace::create_namespaces(&namespaces)
+                .map_err(|e| std::io::Error::other(format!("namespace creation failed: {e}")))?;
+            configure_child_isolation_after_ns(&ChildIsolation {
+                rootfs: &rootfs_owned,
+                volumes: &volumes,
+                readonly_rootfs,
+                workdir: workdir.as_deref(),
+                hostname: hostname.as_deref(),
+                capabilities: &capabilities,
+                ipc_limits,
+            })
         });
     }
 
@@ -105,6 +139,35 @@ pub fn spawn_container_process(config: &ProcessConfig) -> Result<u32> {
     Ok(pid)
 }
 
+/// Rejects IPC limits without a new IPC namespace and a hostname without
+/// a new UTS namespace: either would otherwise change the host's. A user
+/// without a new user namespace is rejected too, since nothing would map it.
+#[cfg(target_os = "linux")]
+fn validate_namespaced_settings(config: &ProcessConfig) -> Result<()> {
+    if !config.ipc_limits.is_empty() && !config.namespaces.ipc {
+        return Err(ContainustError::Config {
+            message: "IPC limits require an IPC namespace".into(),
+        });
+    }
+    if let Some(hostname) = &config.hostname {
+        if !config.namespaces.uts {
+            return Err(ContainustError::Config {
+                message: "a hostname requires a UTS namespace".into(),
+            });
+        }
+        containust_core::namespace::uts::validate_hostname(hostname)?;
+    }
+    if let Some(user) = &config.user {
+        if !config.namespaces.user {
+            return Err(ContainustError::Config {
+                message: "a user requires a user namespace".into(),
+            });
+        }
+        let _ = containust_core::namespace::user::parse_user(user)?;
+    }
+    Ok(())
+}
+
 /// Builds the child `Command` (env, stdio, argv). Shared with the user/PID spawn path.
 #[cfg(target_os = "linux")]
 pub(crate) fn prepare_child_command_for_spawn(
@@ -133,7 +196,19 @@ pub(crate) fn prepare_child_command_for_spawn(
     for (key, value) in &config.env {
         let _ = command.env(key, value);
     }
-    let _ = command.stdin(std::process::Stdio::null());
+    if let Some(stdin_path) = &config.stdin_path {
+        let stdin = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(stdin_path)
+            .map_err(|source| ContainustError::Io {
+                path: stdin_path.clone(),
+                source,
+            })?;
+        let _ = command.stdin(stdin);
+    } else {
+        let _ = command.stdin(std::process::Stdio::null());
+    }
     if let Some(log_path) = &config.log_path {
         let log_file = open_log_sink(log_path)?;
         let stderr_file = log_file.try_clone().map_err(|source| ContainustError::Io {
@@ -165,55 +240,83 @@ fn open_log_sink(log_path: &Path) -> Result<std::fs::File> {
         })
 }
 
+/// Child-side isolation applied once the namespaces exist.
 #[cfg(target_os = "linux")]
-fn configure_child_isolation(
-    rootfs: &Path,
-    volumes: &[String],
-    readonly_rootfs: bool,
-    namespaces: &NamespaceConfig,
-) -> std::io::Result<()> {
-    containust_core::namespace::create_namespaces(namespaces)
-        .map_err(|e| std::io::Error::other(format!("namespace creation failed: {e}")))?;
-    configure_child_isolation_after_ns(rootfs, volumes, readonly_rootfs)
+pub(crate) struct ChildIsolation<'a> {
+    pub(crate) rootfs: &'a Path,
+    pub(crate) volumes: &'a [String],
+    pub(crate) readonly_rootfs: bool,
+    pub(crate) workdir: Option<&'a Path>,
+    pub(crate) hostname: Option<&'a str>,
+    pub(crate) capabilities: &'a [Capability],
+    pub(crate) ipc_limits: IpcLimits,
 }
 
-/// Mount / `pivot_root` / capability drop after namespaces already exist.
+/// IPC limits / hostname / mount / `pivot_root` / capability drop after
+/// namespaces already exist, then `chdir` into `workdir`.
 #[cfg(target_os = "linux")]
 pub(crate) fn configure_child_isolation_after_ns(
-    rootfs: &Path,
-    volumes: &[String],
-    readonly_rootfs: bool,
+    isolation: &ChildIsolation<'_>,
 ) -> std::io::Result<()> {
-    use nix::mount::{MsFlags, mount};
+    // Before pivot_root, while the host /proc still resolves; the sysctls
+    // belong to the child's new IPC namespace.
+    containust_core::namespace::ipc::apply_limits(&isolation.ipc_limits)
+        .map_err(|e| std::io::Error::other(format!("IPC limits failed: {e}")))?;
+    if let Some(hostname) = isolation.hostname {
+        containust_core::namespace::uts::set_hostname(hostname)
+            .map_err(|e| std::io::Error::other(format!("hostname failed: {e}")))?;
+    }
+    let setup = RootfsSetup {
+        rootfs: isolation.rootfs,
+        volumes: isolation.volumes,
+        readonly_rootfs: isolation.readonly_rootfs,
+    };
+    setup_rootfs_mounts(&LinuxMountOps, &setup)?;
+    if let Some(workdir) = isolation.workdir {
+        std::env::set_current_dir(workdir).map_err(|e| {
+            std::io::Error::other(format!("workdir {} failed: {e}", workdir.display()))
+        })?;
+    }
+    containust_core::capability::drop_capabilities(isolation.capabilities)
+        .map_err(|e| std::io::Error::other(format!("capability drop failed: {e}")))?;
+    Ok(())
+}
+
+/// Root filesystem layout applied inside the container's mount namespace.
+#[cfg(target_os = "linux")]
+struct RootfsSetup<'a> {
+    rootfs: &'a Path,
+    volumes: &'a [String],
+    readonly_rootfs: bool,
+}
 
-    let _ = mount::<str, str, str, str>(None, "/", None, MsFlags::MS_REC | MsFlags::MS_SLAVE, None);
-    for volume in volumes {
-        bind_volume(volume, rootfs)?;
+/// Issues the full mount sequence: slave propagation, volume binds, pseudo
+/// filesystems, `pivot_root`, and the optional read-only root remount.
+#[cfg(target_os = "linux")]
+fn setup_rootfs_mounts(ops: &impl MountOps, setup: &RootfsSetup<'_>) -> std::io::Result<()> {
+    use nix::mount::MsFlags;
+
+    let _ = ops.mount(&MountRequest::propagation(
+        "/",
+        MsFlags::MS_REC | MsFlags::MS_SLAVE,
+    ));
+    for volume in setup.volumes {
+        bind_volume(ops, volume, setup.rootfs)?;
     }
     // Mount proc/sys/dev under rootfs *before* pivot so a host proc-anchor
     // remains visible (userns `mount_too_revealing` check).
-    crate::process_mounts::mount_pseudo_filesystems_at(rootfs)?;
-    containust_core::filesystem::pivot_root::pivot_root(rootfs, &rootfs.join(".old_root"))
+    crate::process_mounts::mount_pseudo_filesystems_with(ops, setup.rootfs)?;
+    containust_core::filesystem::pivot_root::switch_root_with(ops, setup.rootfs)
         .map_err(|e| std::io::Error::other(format!("pivot_root failed: {e}")))?;
-    if readonly_rootfs {
-        mount(
-            None::<&str>,
-            "/",
-            None::<&str>,
-            MsFlags::MS_BIND | MsFlags::MS_REMOUNT | MsFlags::MS_RDONLY,
-            None::<&str>,
-        )
-        .map_err(|e| std::io::Error::other(format!("read-only rootfs failed: {e}")))?;
+    if setup.readonly_rootfs {
+        ops.mount(&MountRequest::remount_readonly("/"))
+            .map_err(|e| std::io::Error::other(format!("read-only rootfs failed: {e}")))?;
     }
-    containust_core::capability::drop_capabilities(&[])
-        .map_err(|e| std::io::Error::other(format!("capability drop failed: {e}")))?;
     Ok(())
 }
 
 #[cfg(target_os = "linux")]
-fn bind_volume(spec: &str, rootfs: &Path) -> std::io::Result<()> {
-    use nix::mount::{MsFlags, mount};
-
+fn bind_volume(ops: &impl MountOps, spec: &str, rootfs: &Path) -> std::io::Result<()> {
     let volume = parse_volume_spec(spec)?;
     if !volume.source.exists() {
         return Err(std::io::Error::new(
@@ -226,23 +329,11 @@ fn bind_volume(spec: &str, rootfs: &Path) -> std::io::Result<()> {
     })?;
     let target = rootfs.join(relative_target);
     prepare_volume_target(&volume.source, &target)?;
-    mount(
-        Some(&volume.source),
-        &target,
-        None::<&str>,
-        MsFlags::MS_BIND | MsFlags::MS_REC,
-        None::<&str>,
-    )
-    .map_err(|e| std::io::Error::other(format!("bind volume failed: {e}")))?;
+    ops.mount(&MountRequest::bind(&volume.source, &target))
+        .map_err(|e| std::io::Error::other(format!("bind volume failed: {e}")))?;
     if volume.readonly {
-        mount(
-            None::<&str>,
-            &target,
-            None::<&str>,
-            MsFlags::MS_BIND | MsFlags::MS_REMOUNT | MsFlags::MS_RDONLY,
-            None::<&str>,
-        )
-        .map_err(|e| std::io::Error::other(format!("read-only volume failed: {e}")))?;
+        ops.mount(&MountRequest::remount_readonly(&target))
+            .map_err(|e| std::io::Error::other(format!("read-only volume failed: {e}")))?;
     }
     Ok(())
 }
@@ -285,3 +376,207 @@ pub fn spawn_container_process(_config: &ProcessConfig) -> Result<u32> {
         message: "process spawning requires Linux (use VM backend on macOS/Windows)".into(),
     })
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use containust_core::filesystem::ops::{MountCall, RecordingMountOps};
+    use nix::mount::{MntFlags, MsFlags};
+    use std::path::PathBuf;
+
+    fn mount_targets(calls: &[MountCall]) -> Vec<PathBuf> {
+        calls
+            .iter()
+            .filter_map(|call| match call {
+                MountCall::Mount(request) => Some(request.target.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// A `/bin/true` config on the host root with every setting unset.
+    fn bare_config() -> ProcessConfig {
+        ProcessConfig {
+            command: vec!["/bin/true".into()],
+            env: Vec::new(),
+            rootfs: PathBuf::from("/"),
+            readonly_rootfs: false,
+            volumes: Vec::new(),
+            namespaces: NamespaceConfig::default(),
+            ipc_limits: IpcLimits::default(),
+            join_netns: None,
+            workdir: None,
+            hostname: None,
+            user: None,
+            log_path: None,
+            stdin_path: None,
+            capabilities: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ipc_limits_require_an_ipc_namespace() {
+        let mut config = ProcessConfig {
+            command: vec!["/bin/true".into()],
+            env: Vec::new(),
+            rootfs: PathBuf::from("/"),
+            readonly_rootfs: false,
+            volumes: Vec::new(),
+            namespaces: NamespaceConfig {
+                ipc: false,
+                ..NamespaceConfig::default()
+            },
+            ipc_limits: IpcLimits {
+                msgmni: Some(16),
+                ..IpcLimits::default()
+            },
+            join_netns: None,
+            workdir: None,
+            hostname: None,
+            user: None,
+            log_path: None,
+            stdin_path: None,
+            capabilities: Vec::new(),
+        };
+        let error = validate_namespaced_settings(&config).expect_err("host IPC namespace");
+        assert!(
+            error.to_string().contains("require an IPC namespace"),
+            "{error}"
+        );
+        config.namespaces.ipc = true;
+        assert!(validate_namespaced_settings(&config).is_ok());
+    }
+
+    #[test]
+    fn hostname_requires_a_uts_namespace() {
+        let mut config = ProcessConfig {
+            hostname: Some("web".into()),
+            namespaces: NamespaceConfig {
+                uts: false,
+                ..NamespaceConfig::default()
+            },
+            ..bare_config()
+        };
+        let error = validate_namespaced_settings(&config).expect_err("host UTS namespace");
+        assert!(
+            error.to_string().contains("requires a UTS namespace"),
+            "{error}"
+        );
+        config.namespaces.uts = true;
+        assert!(validate_namespaced_settings(&config).is_ok());
+        config.hostname = Some(String::new());
+        assert!(validate_namespaced_settings(&config).is_err());
+    }
+
+    #[test]
+    fn user_requires_a_user_namespace() {
+        let mut config = ProcessConfig {
+            user: Some("1000".into()),
+            namespaces: NamespaceConfig {
+                user: false,
+                ..NamespaceConfig::default()
+            },
+            ..bare_config()
+        };
+        let error = validate_namespaced_settings(&config).expect_err("host user namespace");
+        assert!(
+            error.to_string().contains("requires a user namespace"),
+            "{error}"
+        );
+        config.namespaces.user = true;
+        assert!(validate_namespaced_settings(&config).is_ok());
+        config.user = Some("www-data".into());
+        assert!(validate_namespaced_settings(&config).is_err());
+    }
+
+    #[test]
+    fn setup_rootfs_mounts_orders_volumes_pseudo_fs_then_pivot() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let rootfs = dir.path().join("rootfs");
+        let data = dir.path().join("data");
+        std::fs::create_dir_all(&data).expect("data dir");
+        let volumes = vec![format!("{}:/data:ro", data.display())];
+        let setup = RootfsSetup {
+            rootfs: &rootfs,
+            volumes: &volumes,
+            readonly_rootfs: true,
+        };
+        let ops = RecordingMountOps::new();
+        setup_rootfs_mounts(&ops, &setup).expect("setup");
+
+        let calls = ops.calls();
+        assert_eq!(
+            calls[0],
+            MountCall::Mount(MountRequest::propagation(
+                "/",
+                MsFlags::MS_REC | MsFlags::MS_SLAVE
+            ))
+        );
+        assert_eq!(
+            mount_targets(&calls)[1..],
+            [
+                rootfs.join("data"),
+                rootfs.join("data"),
+                rootfs.join("proc"),
+                rootfs.join("sys"),
+                rootfs.join("dev"),
+                rootfs.join("dev/pts"),
+                rootfs.join("dev/shm"),
+                rootfs.join("tmp"),
+                rootfs.clone(),
+                PathBuf::from("/"),
+            ]
+        );
+        assert_eq!(
+            calls[calls.len() - 4..],
+            [
+                MountCall::PivotRoot {
+                    new_root: rootfs.clone(),
+                    put_old: rootfs.join(".old_root"),
+                },
+                MountCall::Chdir(PathBuf::from("/")),
+                MountCall::Umount {
+                    target: PathBuf::from("/.old_root"),
+                    flags: MntFlags::MNT_DETACH,
+                },
+                MountCall::Mount(MountRequest::remount_readonly("/")),
+            ]
+        );
+    }
+
+    #[test]
+    fn setup_rootfs_mounts_skips_optional_sysfs_failure() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let rootfs = dir.path().join("rootfs");
+        let setup = RootfsSetup {
+            rootfs: &rootfs,
+            volumes: &[],
+            readonly_rootfs: false,
+        };
+        let ops = RecordingMountOps::failing_at(rootfs.join("sys"));
+        setup_rootfs_mounts(&ops, &setup).expect("sysfs is optional");
+        assert!(
+            ops.calls()
+                .iter()
+                .any(|call| matches!(call, MountCall::PivotRoot { .. }))
+        );
+    }
+
+    #[test]
+    fn setup_rootfs_mounts_proc_failure_aborts_before_pivot() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let rootfs = dir.path().join("rootfs");
+        let setup = RootfsSetup {
+            rootfs: &rootfs,
+            volumes: &[],
+            readonly_rootfs: false,
+        };
+        let ops = RecordingMountOps::failing_at(rootfs.join("proc"));
+        assert!(setup_rootfs_mounts(&ops, &setup).is_err());
+        assert!(
+            !ops.calls()
+                .iter()
+                .any(|call| matches!(call, MountCall::PivotRoot { .. }))
+        );
+    }
+}
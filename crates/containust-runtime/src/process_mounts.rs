@@ -117,18 +117,29 @@ fn path_is_proc_mount(path: &Path) -> bool {
     })
 }
 
-/// Mounts essential pseudo-filesystems under `rootfs` (before `pivot_root`).
+/// Mounts essential pseudo-filesystems under `rootfs` (before `pivot_root`)
+/// through `ops`.
 ///
 /// Mounting before pivot keeps the init-userns proc anchor visible so the
 /// kernel's `mount_too_revealing` check can succeed under a user namespace.
 #[cfg(target_os = "linux")]
-pub fn mount_pseudo_filesystems_at(rootfs: &Path) -> std::io::Result<()> {
-    use nix::mount::mount;
+pub fn mount_pseudo_filesystems_with(
+    ops: &impl containust_core::filesystem::ops::MountOps,
+    rootfs: &Path,
+) -> std::io::Result<()> {
+    use containust_core::filesystem::ops::MountRequest;
 
     for (rel, src, fstype, flags, opts) in pseudo_mounts() {
         let path: PathBuf = rootfs.join(rel);
         let _ = std::fs::create_dir_all(&path);
-        if let Err(err) = mount(Some(src), &path, Some(fstype), flags, opts) {
+        let request = MountRequest {
+            source: Some(PathBuf::from(src)),
+            target: path.clone(),
+            fstype: Some(fstype.into()),
+            flags,
+            data: opts.map(str::to_owned),
+        };
+        if let Err(err) = ops.mount(&request) {
             // Optional inside single-UID user namespaces / non-TTY workloads.
             if rel == "dev/pts" || rel == "sys" {
                 tracing::warn!(error = %err, "optional mount {rel} skipped");
@@ -54,7 +54,7 @@ pub fn spawn_with_user_pid(config: &ProcessConfig) -> Result<u32> {
             drop_fd(child_tx);
             drop_fd(child_rx);
             let spawn_pid = u32::try_from(child.as_raw()).unwrap_or(u32::MAX);
-            let init_pid = parent_handshake(parent_rx, parent_tx, spawn_pid, &config.namespaces)?;
+            let init_pid = parent_handshake(parent_rx, parent_tx, spawn_pid, config)?;
             if config.namespaces.pid {
                 let _ = nix::sys::wait::waitpid(child, None);
             }
@@ -101,7 +101,11 @@ fn child_config_from(config: &ProcessConfig) -> ChildConfig {
         volumes: config.volumes.clone(),
         readonly_rootfs: config.readonly_rootfs,
         namespaces: config.namespaces.clone(),
+        ipc_limits: config.ipc_limits,
         join_netns: config.join_netns.clone(),
+        workdir: config.workdir.clone(),
+        hostname: config.hostname.clone(),
+        capabilities: config.capabilities.clone(),
     }
 }
 
@@ -110,7 +114,25 @@ struct ChildConfig {
     volumes: Vec<String>,
     readonly_rootfs: bool,
     namespaces: NamespaceConfig,
+    ipc_limits: containust_core::namespace::ipc::IpcLimits,
     join_netns: Option<std::path::PathBuf>,
+    workdir: Option<std::path::PathBuf>,
+    hostname: Option<String>,
+    capabilities: Vec<containust_core::capability::Capability>,
+}
+
+impl ChildConfig {
+    fn isolation(&self) -> crate::process::ChildIsolation<'_> {
+        crate::process::ChildIsolation {
+            rootfs: &self.rootfs,
+            volumes: &self.volumes,
+            readonly_rootfs: self.readonly_rootfs,
+            workdir: self.workdir.as_deref(),
+            hostname: self.hostname.as_deref(),
+            capabilities: &self.capabilities,
+            ipc_limits: self.ipc_limits,
+        }
+    }
 }
 
 struct ChildPipes {
@@ -164,11 +186,7 @@ fn child_main(
     apply_network_namespace(cfg)?;
     drop_fd(pipes.tx);
     drop_fd(pipes.rx);
-    crate::process::configure_child_isolation_after_ns(
-        &cfg.rootfs,
-        &cfg.volumes,
-        cfg.readonly_rootfs,
-    )?;
+    crate::process::configure_child_isolation_after_ns(&cfg.isolation())?;
     exec_container(exec)
 }
 
@@ -205,11 +223,7 @@ fn enter_pid_then_setup(
             write_all_file(&pipes.tx, &host_pid.to_le_bytes())?;
             // Now PID 1: safe to fork helpers (e.g. `ip`) and mount procfs.
             apply_network_namespace(cfg)?;
-            crate::process::configure_child_isolation_after_ns(
-                &cfg.rootfs,
-                &cfg.volumes,
-                cfg.readonly_rootfs,
-            )?;
+            crate::process::configure_child_isolation_after_ns(&cfg.isolation())?;
             write_all_file(&pipes.tx, &[MSG_READY])?;
             drop_fd(pipes.tx);
             drop_fd(pipes.rx);
@@ -264,16 +278,21 @@ fn parent_handshake(
     mut parent_rx: std::fs::File,
     mut parent_tx: std::fs::File,
     spawn_pid: u32,
-    namespaces: &NamespaceConfig,
+    config: &ProcessConfig,
 ) -> Result<u32> {
+    let namespaces = &config.namespaces;
     let mut tag = [0_u8; 1];
     read_exact_file(&mut parent_rx, &mut tag)?;
     if namespaces.user {
         if tag[0] != MSG_NEED_MAPS {
             return Err(handshake_err("NEED_MAPS", tag[0]));
         }
-        let host_uid = nix::unistd::geteuid().as_raw();
-        containust_core::namespace::user::write_uid_gid_map(spawn_pid, 0, host_uid, 1)?;
+        let maps = containust_core::namespace::user::IdMaps::for_user(
+            config.user.as_deref(),
+            nix::unistd::geteuid().as_raw(),
+            nix::unistd::getegid().as_raw(),
+        )?;
+        containust_core::namespace::user::write_id_maps(spawn_pid, &maps.uid, &maps.gid)?;
         parent_tx
             .write_all(&[MSG_MAPS_DONE])
             .map_err(|source| ContainustError::Io {
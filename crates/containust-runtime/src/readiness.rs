@@ -0,0 +1,149 @@
+//! Readiness gating during deployment.
+//!
+//! A component's `readiness` probe must pass before any component that
+//! `CONNECT`s to it is started. Liveness (`healthcheck` / `liveness`) is
+//! separate: it is recorded on the container and enforced by
+//! [`crate::supervise`], where failures trigger restarts.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use containust_common::cancel::CancellationToken;
+use containust_common::error::{ContainustError, Result};
+use containust_common::types::{ContainerId, HealthcheckSpec};
+use containust_compose::parser::ast::CompositionFile;
+
+use crate::backend::ContainerBackend;
+use crate::engine::DeployedComponent;
+
+/// Tracks which components have passed their readiness probe.
+pub struct ReadinessGate<'a> {
+    probes: HashMap<&'a str, HealthcheckSpec>,
+    dependencies: HashMap<&'a str, Vec<&'a str>>,
+    ready: HashSet<&'a str>,
+    cancel: CancellationToken,
+}
+
+impl<'a> ReadinessGate<'a> {
+    /// Collects readiness probes and `CONNECT` dependencies.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error when a readiness probe is invalid.
+    pub fn new(composition: &'a CompositionFile) -> Result<Self> {
+        let mut probes = HashMap::new();
+        for component in &composition.components {
+            if let Some(decl) = &component.readiness {
+                let ports =
+                    crate::engine::published_port_mappings(component, &composition.exposes)?;
+                let spec = crate::engine::parse_healthcheck_spec(&component.name, decl, &ports)?;
+                let _ = probes.insert(component.name.as_str(), spec);
+            }
+        }
+        let mut dependencies: HashMap<&str, Vec<&str>> = HashMap::new();
+        for connection in &composition.connections {
+            dependencies
+                .entry(connection.from.as_str())
+                .or_default()
+                .push(connection.to.as_str());
+        }
+        Ok(Self {
+            probes,
+            dependencies,
+            ready: HashSet::new(),
+            cancel: CancellationToken::new(),
+        })
+    }
+
+    /// Abandons readiness waits once `token` is cancelled.
+    #[must_use]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Blocks until every dependency of `component` with a readiness probe
+    /// has passed it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a dependency exhausts its probe retries, or
+    /// [`ContainustError::Cancelled`] once the gate's token is cancelled.
+    pub fn wait_for_dependencies(
+        &mut self,
+        backend: &dyn ContainerBackend,
+        component: &str,
+        deployed: &[DeployedComponent],
+    ) -> Result<()> {
+        let dependencies = self
+            .dependencies
+            .get(component)
+            .cloned()
+            .unwrap_or_default();
+        for dependency in dependencies {
+            if let Some(started) = deployed.iter().find(|c| c.name == dependency) {
+                self.wait_for(backend, dependency, &started.id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Blocks until `component` passes its readiness probe; returns
+    /// immediately when it has none or already passed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the probe exhausts its retries, or
+    /// [`ContainustError::Cancelled`] once the gate's token is cancelled.
+    pub fn wait_for(
+        &mut self,
+        backend: &dyn ContainerBackend,
+        component: &str,
+        id: &ContainerId,
+    ) -> Result<()> {
+        let Some((&name, _)) = self.probes.get_key_value(component) else {
+            return Ok(());
+        };
+        if self.ready.contains(name) {
+            return Ok(());
+        }
+        eprintln!("  Waiting for '{name}' to become ready...");
+        self.wait_until_ready(backend, name, id)?;
+        let _ = self.ready.insert(name);
+        Ok(())
+    }
+
+    /// Runs the readiness probe of `name` until it passes, `retries`
+    /// attempts fail, or the gate's token is cancelled.
+    fn wait_until_ready(
+        &self,
+        backend: &dyn ContainerBackend,
+        name: &str,
+        id: &ContainerId,
+    ) -> Result<()> {
+        let Some(spec) = self.probes.get(name) else {
+            return Ok(());
+        };
+        let cancel = &self.cancel;
+        cancel.sleep(
+            Duration::from_secs(spec.start_period_secs),
+            "readiness wait",
+        )?;
+        let attempts = spec.retries.max(1);
+        for attempt in 1..=attempts {
+            cancel.check("readiness wait")?;
+            if crate::supervise::run_probe(backend, id, spec) {
+                tracing::info!(id = %id, name, attempt, "component ready");
+                return Ok(());
+            }
+            if attempt < attempts {
+                cancel.sleep(Duration::from_secs(spec.interval_secs), "readiness wait")?;
+            }
+        }
+        Err(ContainustError::Config {
+            message: format!(
+                "component '{name}' did not become ready after {attempts} readiness probe(s)"
+            ),
+        })
+    }
+}
@@ -0,0 +1,150 @@
+//! Named signals deliverable to a container's main process.
+//!
+//! Numbers follow the Linux ABI: containers always run on a Linux kernel,
+//! either natively or inside the VM, whatever the host platform.
+
+use std::fmt;
+use std::str::FromStr;
+
+use containust_common::error::{ContainustError, Result};
+
+/// Signals accepted by `ctst kill`, with their Linux numbers.
+const KNOWN_SIGNALS: [(&str, i32); 15] = [
+    ("SIGHUP", 1),
+    ("SIGINT", 2),
+    ("SIGQUIT", 3),
+    ("SIGABRT", 6),
+    ("SIGKILL", 9),
+    ("SIGUSR1", 10),
+    ("SIGUSR2", 12),
+    ("SIGPIPE", 13),
+    ("SIGALRM", 14),
+    ("SIGTERM", 15),
+    ("SIGCHLD", 17),
+    ("SIGCONT", 18),
+    ("SIGSTOP", 19),
+    ("SIGTSTP", 20),
+    ("SIGWINCH", 28),
+];
+
+/// A validated signal name and number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContainerSignal {
+    name: &'static str,
+    number: i32,
+}
+
+impl ContainerSignal {
+    /// `SIGHUP`, conventionally used to reload configuration.
+    pub const HUP: Self = Self {
+        name: "SIGHUP",
+        number: 1,
+    };
+    /// `SIGKILL`, which cannot be caught.
+    pub const KILL: Self = Self {
+        name: "SIGKILL",
+        number: 9,
+    };
+    /// `SIGTERM`, the default graceful-stop request.
+    pub const TERM: Self = Self {
+        name: "SIGTERM",
+        number: 15,
+    };
+
+    /// Parses `SIGHUP`, `HUP`, `hup`, or a known signal number such as `1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error naming `raw` when it is not a known signal.
+    pub fn parse(raw: &str) -> Result<Self> {
+        let text = raw.trim();
+        let known = text.parse::<i32>().map_or_else(
+            |_| {
+                let upper = text.to_ascii_uppercase();
+                let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+                KNOWN_SIGNALS
+                    .iter()
+                    .find(|(known, _)| known.strip_prefix("SIG") == Some(name))
+            },
+            |number| KNOWN_SIGNALS.iter().find(|(_, known)| *known == number),
+        );
+        known
+            .map(|&(name, number)| Self { name, number })
+            .ok_or_else(|| ContainustError::Config {
+                message: format!(
+                    "unknown signal '{raw}' (expected one of {})",
+                    KNOWN_SIGNALS.map(|(name, _)| name).join(", ")
+                ),
+            })
+    }
+
+    /// Canonical `SIG`-prefixed name.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        self.name
+    }
+
+    /// Linux signal number.
+    #[must_use]
+    pub const fn number(self) -> i32 {
+        self.number
+    }
+}
+
+impl FromStr for ContainerSignal {
+    type Err = ContainustError;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        Self::parse(raw)
+    }
+}
+
+impl fmt::Display for ContainerSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_names_with_or_without_prefix_in_any_case() {
+        assert_eq!(
+            ContainerSignal::parse("SIGHUP").unwrap(),
+            ContainerSignal::HUP
+        );
+        assert_eq!(ContainerSignal::parse("hup").unwrap(), ContainerSignal::HUP);
+        assert_eq!(
+            ContainerSignal::parse(" SigTerm ").unwrap(),
+            ContainerSignal::TERM
+        );
+        assert_eq!(ContainerSignal::parse("usr1").unwrap().number(), 10);
+    }
+
+    #[test]
+    fn parse_accepts_known_numbers() {
+        assert_eq!(ContainerSignal::parse("9").unwrap(), ContainerSignal::KILL);
+        assert_eq!(ContainerSignal::parse("28").unwrap().name(), "SIGWINCH");
+    }
+
+    #[test]
+    fn parse_rejects_unknown_signals() {
+        for raw in ["SIGBOGUS", "", "SIG", "64", "-1"] {
+            let error = ContainerSignal::parse(raw).unwrap_err().to_string();
+            assert!(error.contains(&format!("'{raw}'")), "{error}");
+        }
+    }
+
+    #[test]
+    fn known_signal_constants_match_table() {
+        for signal in [
+            ContainerSignal::HUP,
+            ContainerSignal::KILL,
+            ContainerSignal::TERM,
+        ] {
+            assert_eq!(ContainerSignal::parse(signal.name()).unwrap(), signal);
+        }
+    }
+}
@@ -43,9 +43,45 @@ pub struct StateEntry {
     /// Configured CPU weight.
     #[serde(default)]
     pub cpu_shares: Option<u64>,
+    /// Configured maximum process count.
+    #[serde(default)]
+    pub pids_max: Option<u64>,
+    /// Configured swap beyond the memory limit.
+    #[serde(default)]
+    pub swap_bytes: Option<u64>,
+    /// Configured memory reservation (`memory.low`).
+    #[serde(default)]
+    pub memory_low_bytes: Option<u64>,
+    /// Configured memory throttling threshold (`memory.high`).
+    #[serde(default)]
+    pub memory_high_bytes: Option<u64>,
+    /// Configured CPU time per period (`cpu.max` quota), in microseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_quota_us: Option<u64>,
+    /// Configured `cpu.max` period in microseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_period_us: Option<u64>,
+    /// Cores the container is pinned to (`cpuset.cpus`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpuset_cpus: Option<Vec<u32>>,
+    /// NUMA nodes the container's memory is allocated from (`cpuset.mems`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpuset_mems: Option<Vec<u32>>,
+    /// Largest shared memory segment in the IPC namespace (`kernel.shmmax`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipc_shmmax_bytes: Option<u64>,
+    /// Total shared memory pages in the IPC namespace (`kernel.shmall`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipc_shmall_pages: Option<u64>,
+    /// Message queue count limit in the IPC namespace (`kernel.msgmni`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipc_msgmni: Option<u32>,
     /// Whether the root filesystem is read-only.
     #[serde(default = "default_readonly_rootfs")]
     pub readonly_rootfs: bool,
+    /// Whether the main process reads stdin from an `attach` FIFO.
+    #[serde(default)]
+    pub stdin: bool,
     /// Host-to-container bind mounts.
     #[serde(default)]
     pub volumes: Vec<String>,
@@ -73,18 +109,59 @@ pub struct StateEntry {
     /// Number of automatic restarts performed by the restart policy.
     #[serde(default)]
     pub restart_count: u32,
+    /// ISO-8601 timestamp of when the restart policy gave up on this
+    /// container, logged once; cleared with `restart_count`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart_limit_reported_at: Option<String>,
+    /// Declared graceful-stop signal name (e.g. `SIGQUIT`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_signal: Option<String>,
+    /// Declared grace period in seconds before a stop escalates to a kill.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_timeout_secs: Option<u64>,
+    /// Whether a limit the host refuses to apply fails the start.
+    #[serde(default = "default_enforce_limits")]
+    pub enforce_limits: bool,
+    /// Why the last start failed; cleared by a successful start.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Working directory of the main process, inherited by `exec`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workdir: Option<String>,
+    /// Hostname set in the container's UTS namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// Numeric `uid[:gid]` the main process runs as, inherited by `exec`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    /// Kernel names of the capabilities the main process keeps.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<String>,
     /// Rootfs path on disk.
     pub rootfs_path: Option<String>,
     /// Log file path.
     pub log_path: Option<String>,
     /// ISO-8601 timestamp of creation.
     pub created_at: String,
+    /// ISO-8601 timestamp of the last successful start.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    /// ISO-8601 timestamp of the last observed exit or stop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+    /// Exit code of the last run, when it is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
 }
 
 const fn default_readonly_rootfs() -> bool {
     true
 }
 
+const fn default_enforce_limits() -> bool {
+    true
+}
+
 fn default_network() -> String {
     "none".into()
 }
@@ -417,7 +494,19 @@ mod tests {
             env: Vec::new(),
             memory_bytes: None,
             cpu_shares: None,
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_shmmax_bytes: None,
+            ipc_shmall_pages: None,
+            ipc_msgmni: None,
             readonly_rootfs: true,
+            stdin: false,
             volumes: Vec::new(),
             rootfs_path: None,
             log_path: None,
@@ -429,7 +518,19 @@ mod tests {
             healthcheck: None,
             health: None,
             restart_count: 0,
+            restart_limit_reported_at: None,
+            stop_signal: None,
+            stop_timeout_secs: None,
+            enforce_limits: true,
+            last_error: None,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
             created_at: "2026-01-01T00:00:00Z".into(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
         }
     }
 
@@ -463,7 +564,6 @@ mod tests {
 
         let state = StateFile {
             containers: vec![StateEntry {
-                id: ContainerId::new("test-1"),
                 name: "my-container".into(),
                 state: ContainerState::Running,
                 pid: Some(1234),
@@ -472,19 +572,10 @@ mod tests {
                 env: vec![("KEY".into(), "value".into())],
                 memory_bytes: Some(128),
                 cpu_shares: Some(512),
-                readonly_rootfs: true,
-                volumes: Vec::new(),
                 rootfs_path: Some("/var/lib/containust/rootfs/test-1".into()),
-                log_path: None,
-                ports: Vec::new(),
-                port_mappings: Vec::new(),
-                network: "bridge".into(),
-                forwarder_pids: Vec::new(),
-                restart: containust_common::types::RestartPolicy::default(),
-                healthcheck: None,
-                health: None,
-                restart_count: 0,
-                created_at: "2026-01-01T00:00:00Z".into(),
+                workdir: Some("/srv".into()),
+                user: Some("1000".into()),
+                ..test_entry("test-1")
             }],
             ..StateFile::default()
         };
@@ -505,6 +596,8 @@ mod tests {
         );
         assert_eq!(loaded.containers[0].memory_bytes, Some(128));
         assert_eq!(loaded.containers[0].cpu_shares, Some(512));
+        assert_eq!(loaded.containers[0].workdir.as_deref(), Some("/srv"));
+        assert_eq!(loaded.containers[0].user.as_deref(), Some("1000"));
     }
 
     #[test]
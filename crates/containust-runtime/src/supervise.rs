@@ -3,16 +3,27 @@
 //! Containust is daemonless, so policies are applied during
 //! reconciliation (every `ctst ps` / `ctst run` invocation): containers
 //! whose process died are restarted according to their policy, and due
-//! health probes execute with unhealthy containers restarted.
+//! health probes execute with unhealthy containers restarted. A container
+//! that has used up [`MAX_POLICY_RESTARTS`] stays down, so a crash loop
+//! cannot restart forever; one that stays up for [`STABLE_RUN`] after a
+//! start gets its restart count back.
 
+use containust_common::clock::{Clock, SystemClock};
 use containust_common::error::Result;
 use containust_common::types::{
     ContainerId, ContainerState, HealthRecord, HealthState, HealthcheckSpec, RestartPolicy,
 };
 
-use crate::backend::ContainerBackend;
+use crate::backend::{ContainerBackend, HealthResult};
 use crate::state::{StateEntry, StateStore};
 
+/// Automatic restarts a container gets before its policy gives up.
+pub const MAX_POLICY_RESTARTS: u32 = 10;
+
+/// How long a container must keep running after its last start before
+/// its restart count is reset.
+pub const STABLE_RUN: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
 /// Work performed by one policy-enforcement pass.
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct PolicyOutcome {
@@ -33,10 +44,25 @@ pub struct PolicyOutcome {
 pub fn enforce_policies(
     store: &StateStore,
     backend: &dyn ContainerBackend,
+) -> Result<PolicyOutcome> {
+    enforce_policies_with_clock(store, backend, &SystemClock)
+}
+
+/// [`enforce_policies`] that decides which probes are due, and stamps
+/// their results, with `clock`.
+///
+/// # Errors
+///
+/// Returns an error when the state index cannot be read or updated.
+pub fn enforce_policies_with_clock(
+    store: &StateStore,
+    backend: &dyn ContainerBackend,
+    clock: &dyn Clock,
 ) -> Result<PolicyOutcome> {
     let mut outcome = PolicyOutcome::default();
-    outcome.restarted += restart_failed_containers(store, backend)?;
-    let (probed_unhealthy, probe_restarts) = probe_running_containers(store, backend)?;
+    reset_stable_restart_counts(store, clock)?;
+    outcome.restarted += restart_failed_containers(store, backend, clock)?;
+    let (probed_unhealthy, probe_restarts) = probe_running_containers(store, backend, clock)?;
     outcome.unhealthy += probed_unhealthy;
     outcome.restarted += probe_restarts;
     Ok(outcome)
@@ -47,11 +73,15 @@ pub fn enforce_policies(
 /// A `Failed` entry means reconciliation observed the process dead
 /// while the container was expected to run; both `always` and
 /// `on-failure` treat that as a restartable failure.
-fn restart_failed_containers(store: &StateStore, backend: &dyn ContainerBackend) -> Result<usize> {
+fn restart_failed_containers(
+    store: &StateStore,
+    backend: &dyn ContainerBackend,
+    clock: &dyn Clock,
+) -> Result<usize> {
     let snapshot = store.read()?;
     let mut restarted = 0;
     for entry in &snapshot.containers {
-        if entry.state != ContainerState::Failed || entry.restart == RestartPolicy::Never {
+        if entry.state != ContainerState::Failed || !may_restart(store, entry, clock)? {
             continue;
         }
         if try_restart(store, backend, &entry.id)? {
@@ -65,9 +95,10 @@ fn restart_failed_containers(store: &StateStore, backend: &dyn ContainerBackend)
 fn probe_running_containers(
     store: &StateStore,
     backend: &dyn ContainerBackend,
+    clock: &dyn Clock,
 ) -> Result<(usize, usize)> {
     let snapshot = store.read()?;
-    let now = chrono::Utc::now();
+    let now = clock.now();
     let mut unhealthy = 0;
     let mut restarted = 0;
     for entry in &snapshot.containers {
@@ -78,11 +109,11 @@ fn probe_running_containers(
             continue;
         }
         let healthy = run_probe(backend, &entry.id, spec);
-        let became_unhealthy = record_probe_result(store, &entry.id, spec, healthy)?;
+        let became_unhealthy = record_probe_result(store, &entry.id, healthy, clock)?;
         if became_unhealthy {
             unhealthy += 1;
             tracing::warn!(id = %entry.id, name = %entry.name, "container is unhealthy");
-            restarted += usize::from(restart_unhealthy(store, backend, entry)?);
+            restarted += usize::from(restart_unhealthy(store, backend, entry, clock)?);
         }
     }
     Ok((unhealthy, restarted))
@@ -93,14 +124,73 @@ fn restart_unhealthy(
     store: &StateStore,
     backend: &dyn ContainerBackend,
     entry: &StateEntry,
+    clock: &dyn Clock,
 ) -> Result<bool> {
-    if entry.restart == RestartPolicy::Never {
+    if !may_restart(store, entry, clock)? {
         return Ok(false);
     }
     backend.stop(&entry.id)?;
     try_restart(store, backend, &entry.id)
 }
 
+/// Whether the policy allows another restart of `entry`. The first time
+/// the restart limit stops one, a warning is logged and its time recorded
+/// so later passes stay quiet.
+fn may_restart(store: &StateStore, entry: &StateEntry, clock: &dyn Clock) -> Result<bool> {
+    if entry.restart == RestartPolicy::Never {
+        return Ok(false);
+    }
+    if entry.restart_count < MAX_POLICY_RESTARTS {
+        return Ok(true);
+    }
+    if entry.restart_limit_reported_at.is_none() {
+        tracing::warn!(
+            id = %entry.id,
+            name = %entry.name,
+            restarts = entry.restart_count,
+            "restart limit reached; leaving the container down"
+        );
+        store.update(|state| {
+            if let Some(entry) = state.containers.iter_mut().find(|e| e.id == entry.id) {
+                entry.restart_limit_reported_at = Some(clock.now().to_rfc3339());
+            }
+            Ok(())
+        })?;
+    }
+    Ok(false)
+}
+
+/// Resets the restart count of containers that have been running for
+/// [`STABLE_RUN`] since their last start.
+fn reset_stable_restart_counts(store: &StateStore, clock: &dyn Clock) -> Result<()> {
+    let stable = chrono::Duration::from_std(STABLE_RUN).unwrap_or(chrono::Duration::MAX);
+    let now = clock.now();
+    let is_stable = |entry: &StateEntry| {
+        entry.state == ContainerState::Running
+            && (entry.restart_count > 0 || entry.restart_limit_reported_at.is_some())
+            && entry
+                .started_at
+                .as_deref()
+                .and_then(parse_rfc3339)
+                .is_some_and(|started| now >= started + stable)
+    };
+    if !store.read()?.containers.iter().any(is_stable) {
+        return Ok(());
+    }
+    store.update(|state| {
+        for entry in state.containers.iter_mut().filter(|entry| is_stable(entry)) {
+            tracing::info!(
+                id = %entry.id,
+                restarts = entry.restart_count,
+                "container ran stably; restart count reset"
+            );
+            entry.restart_count = 0;
+            entry.restart_limit_reported_at = None;
+        }
+        Ok(())
+    })
+}
+
 /// Returns whether the probe interval (and start period) has elapsed.
 fn probe_is_due(
     entry: &StateEntry,
@@ -131,10 +221,19 @@ fn parse_rfc3339(text: &str) -> Option<chrono::DateTime<chrono::Utc>> {
         .map(|parsed| parsed.with_timezone(&chrono::Utc))
 }
 
-/// Executes one probe; any exec error counts as a failed probe.
-fn run_probe(backend: &dyn ContainerBackend, id: &ContainerId, spec: &HealthcheckSpec) -> bool {
-    match backend.exec(id, &spec.command) {
-        Ok(output) => output.exit_code == 0,
+/// Executes one probe through [`ContainerBackend::healthcheck`]; an error
+/// running the probe counts as a failure.
+pub(crate) fn run_probe(
+    backend: &dyn ContainerBackend,
+    id: &ContainerId,
+    spec: &HealthcheckSpec,
+) -> bool {
+    match backend.healthcheck(id, spec) {
+        Ok(HealthResult::Healthy) => true,
+        Ok(HealthResult::Unhealthy(reason)) => {
+            tracing::debug!(id = %id, %reason, "health probe failed");
+            false
+        }
         Err(error) => {
             tracing::warn!(id = %id, %error, "health probe execution failed");
             false
@@ -142,21 +241,25 @@ fn run_probe(backend: &dyn ContainerBackend, id: &ContainerId, spec: &Healthchec
     }
 }
 
-/// Persists a probe result; returns whether the container crossed the
+/// Persists a probe result, counting failures against the retries of the
+/// stored entry's healthcheck; returns whether the container crossed the
 /// failure threshold on this probe.
 fn record_probe_result(
     store: &StateStore,
     id: &ContainerId,
-    spec: &HealthcheckSpec,
     healthy: bool,
+    clock: &dyn Clock,
 ) -> Result<bool> {
-    let retries = spec.retries.max(1);
     store.update(|state| {
         let Some(entry) = state.containers.iter_mut().find(|entry| entry.id == *id) else {
             return Ok(false);
         };
+        let retries = entry
+            .healthcheck
+            .as_ref()
+            .map_or(1, |spec| spec.retries.max(1));
         let mut record = entry.health.clone().unwrap_or_default();
-        record.last_probe_at = Some(chrono::Utc::now().to_rfc3339());
+        record.last_probe_at = Some(clock.now().to_rfc3339());
         if healthy {
             record.consecutive_failures = 0;
             record.state = HealthState::Healthy;
@@ -203,6 +306,7 @@ fn try_restart(
 mod tests {
     use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
+    use containust_common::clock::MockClock;
     use containust_common::error::ContainustError;
 
     use super::*;
@@ -273,7 +377,19 @@ mod tests {
             env: Vec::new(),
             memory_bytes: None,
             cpu_shares: None,
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_shmmax_bytes: None,
+            ipc_shmall_pages: None,
+            ipc_msgmni: None,
             readonly_rootfs: true,
+            stdin: false,
             volumes: Vec::new(),
             ports: Vec::new(),
             port_mappings: Vec::new(),
@@ -283,9 +399,21 @@ mod tests {
             healthcheck,
             health: None,
             restart_count: 0,
+            restart_limit_reported_at: None,
+            stop_signal: None,
+            stop_timeout_secs: None,
+            enforce_limits: true,
+            last_error: None,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
             rootfs_path: None,
             log_path: None,
             created_at: chrono::Utc::now().to_rfc3339(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
         }
     }
 
@@ -307,7 +435,7 @@ mod tests {
             interval_secs: 0,
             timeout_secs: 1,
             retries: 1,
-            start_period_secs: 0,
+            ..HealthcheckSpec::default()
         }
     }
 
@@ -329,6 +457,70 @@ mod tests {
         assert_eq!(state.containers[0].restart_count, 1);
     }
 
+    #[test]
+    fn failed_container_past_restart_limit_stays_down() {
+        let mut crashing = entry("a", ContainerState::Failed, RestartPolicy::Always, None);
+        crashing.restart_count = MAX_POLICY_RESTARTS;
+        let (_dir, store) = store_with(vec![crashing]);
+        let backend = ProbeBackend::default();
+
+        let outcome = enforce_policies(&store, &backend).expect("enforce");
+
+        assert_eq!(outcome.restarted, 0);
+        assert_eq!(backend.starts.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn restart_limit_is_reported_once() {
+        let mut crashing = entry("a", ContainerState::Failed, RestartPolicy::Always, None);
+        crashing.restart_count = MAX_POLICY_RESTARTS;
+        let (_dir, store) = store_with(vec![crashing]);
+        let backend = ProbeBackend::default();
+
+        let _ = enforce_policies(&store, &backend).expect("first pass");
+        let state = store.read().expect("read");
+        let reported = state.containers[0].restart_limit_reported_at.clone();
+        assert!(reported.is_some());
+
+        let _ = enforce_policies(&store, &backend).expect("second pass");
+        let state = store.read().expect("read");
+        assert_eq!(state.containers[0].restart_limit_reported_at, reported);
+        assert_eq!(backend.starts.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn restart_count_resets_after_a_stable_run() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-01-01T01:00:00Z")
+            .expect("now")
+            .to_utc();
+        let mut stable = entry(
+            "stable",
+            ContainerState::Running,
+            RestartPolicy::Always,
+            None,
+        );
+        stable.restart_count = MAX_POLICY_RESTARTS;
+        stable.restart_limit_reported_at = Some("2026-01-01T00:40:00Z".into());
+        stable.started_at = Some("2026-01-01T00:45:00Z".into());
+        let mut recent = entry(
+            "recent",
+            ContainerState::Running,
+            RestartPolicy::Always,
+            None,
+        );
+        recent.restart_count = 3;
+        recent.started_at = Some("2026-01-01T00:55:00Z".into());
+        let (_dir, store) = store_with(vec![stable, recent]);
+
+        let _ = enforce_policies_with_clock(&store, &ProbeBackend::default(), &MockClock::new(now))
+            .expect("enforce");
+
+        let state = store.read().expect("read");
+        assert_eq!(state.containers[0].restart_count, 0);
+        assert_eq!(state.containers[0].restart_limit_reported_at, None);
+        assert_eq!(state.containers[1].restart_count, 3);
+    }
+
     #[test]
     fn failed_container_with_never_policy_is_not_restarted() {
         let (_dir, store) = store_with(vec![entry(
@@ -461,4 +653,38 @@ mod tests {
         let _ = enforce_policies(&store, &backend).expect("enforce");
         assert_eq!(backend.execs.load(Ordering::SeqCst), 0);
     }
+
+    #[test]
+    fn probe_runs_again_once_the_interval_elapses() {
+        let clock = MockClock::new(chrono::DateTime::from_timestamp(1_700_000_000, 0).expect("ts"));
+        let mut seeded = entry(
+            "a",
+            ContainerState::Running,
+            RestartPolicy::Never,
+            Some(HealthcheckSpec {
+                interval_secs: 30,
+                start_period_secs: 10,
+                ..quick_probe()
+            }),
+        );
+        seeded.created_at = clock.now().to_rfc3339();
+        let (_dir, store) = store_with(vec![seeded]);
+        let backend = ProbeBackend::default();
+        let probes = |seconds| {
+            clock.advance(std::time::Duration::from_secs(seconds));
+            let _ = enforce_policies_with_clock(&store, &backend, &clock).expect("enforce");
+            backend.execs.load(Ordering::SeqCst)
+        };
+
+        assert_eq!(probes(5), 0, "inside the start period");
+        assert_eq!(probes(5), 1, "start period over");
+        assert_eq!(probes(29), 1, "interval not yet elapsed");
+        assert_eq!(probes(1), 2, "interval elapsed");
+        let state = store.read().expect("read");
+        let health = state.containers[0].health.clone().expect("health record");
+        assert_eq!(
+            health.last_probe_at.as_deref(),
+            Some(clock.now().to_rfc3339().as_str())
+        );
+    }
 }
@@ -0,0 +1,137 @@
+//! Host binary lookup shared by backends and diagnostics.
+//!
+//! `PATH` lookups are cached for the life of the process, and a missing
+//! binary always yields the same [`ContainustError::NotFound`] carrying an
+//! install hint for the host OS when one is known.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use containust_common::error::{ContainustError, Result};
+
+/// Install commands for QEMU, keyed by `std::env::consts::OS`.
+const QEMU_HINTS: &[(&str, &str)] = &[
+    ("macos", "brew install qemu"),
+    ("windows", "choco install qemu"),
+    ("linux", "apt install qemu-system"),
+];
+
+/// A binary with a dedicated error kind and per-OS install hints.
+struct KnownBinary {
+    name: &'static str,
+    kind: &'static str,
+    hints: &'static [(&'static str, &'static str)],
+}
+
+/// Binaries the backends look up on `PATH`.
+const KNOWN_BINARIES: [KnownBinary; 2] = [
+    KnownBinary {
+        name: "qemu-system-x86_64",
+        kind: "QEMU binary",
+        hints: QEMU_HINTS,
+    },
+    KnownBinary {
+        name: "qemu-system-aarch64",
+        kind: "QEMU binary",
+        hints: QEMU_HINTS,
+    },
+];
+
+fn known(name: &str) -> Option<&'static KnownBinary> {
+    KNOWN_BINARIES.iter().find(|binary| binary.name == name)
+}
+
+/// Paths already resolved in this process.
+static FOUND: OnceLock<Mutex<HashMap<String, PathBuf>>> = OnceLock::new();
+
+/// Resolves `name` on `PATH`, reusing earlier successful lookups.
+///
+/// Misses are not cached, so a binary installed while the process runs
+/// is found on the next call.
+///
+/// # Errors
+///
+/// Returns [`ContainustError::NotFound`] with an install hint for the host
+/// OS when `name` is not on `PATH`.
+pub fn find_binary(name: &str) -> Result<PathBuf> {
+    let cache = FOUND.get_or_init(Mutex::default);
+    if let Some(path) = cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+    {
+        return Ok(path.clone());
+    }
+    let path = which::which(name).map_err(|_| not_found(name, std::env::consts::OS))?;
+    let _ = cache
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(name.to_string(), path.clone());
+    Ok(path)
+}
+
+/// Returns the install command for `name` on `os`, if known.
+#[must_use]
+pub fn install_hint(name: &str, os: &str) -> Option<&'static str> {
+    known(name)?
+        .hints
+        .iter()
+        .find(|(hint_os, _)| *hint_os == os)
+        .map(|(_, hint)| *hint)
+}
+
+/// Builds the error reported when `name` is missing on `os`.
+fn not_found(name: &str, os: &str) -> ContainustError {
+    let kind = known(name).map_or("binary", |binary| binary.kind);
+    ContainustError::NotFound {
+        kind,
+        id: install_hint(name, os).map_or_else(
+            || name.to_string(),
+            |hint| format!("{name} — Install with: {hint}"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_hint_depends_on_os() {
+        let qemu = "qemu-system-x86_64";
+        assert_eq!(install_hint(qemu, "macos"), Some("brew install qemu"));
+        assert_eq!(install_hint(qemu, "windows"), Some("choco install qemu"));
+        assert_eq!(install_hint(qemu, "linux"), Some("apt install qemu-system"));
+        assert_eq!(install_hint(qemu, "freebsd"), None);
+        assert_eq!(install_hint("no-such-tool", "linux"), None);
+    }
+
+    #[test]
+    fn not_found_carries_kind_and_hint() {
+        let error = not_found("qemu-system-aarch64", "macos").to_string();
+        assert!(error.contains("QEMU binary"), "{error}");
+        assert!(error.contains("brew install qemu"), "{error}");
+
+        let ContainustError::NotFound { kind, id } = not_found("no-such-tool", "linux") else {
+            unreachable!("not_found always builds NotFound");
+        };
+        assert_eq!(kind, "binary");
+        assert_eq!(id, "no-such-tool");
+    }
+
+    #[test]
+    fn missing_binary_is_not_found() {
+        let error = find_binary("containust-definitely-missing-binary").unwrap_err();
+        assert!(matches!(error, ContainustError::NotFound { .. }));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cached_lookup_returns_same_path() {
+        let first = find_binary("sh").unwrap();
+        let second = find_binary("sh").unwrap();
+        assert_eq!(first, second);
+        assert!(FOUND.get().unwrap().lock().unwrap().contains_key("sh"));
+    }
+}
@@ -325,6 +325,8 @@ fn pipeline_image_catalog_crud() {
         created_at: "2026-01-01T00:00:00Z".into(),
         digest: None,
         tool_version: String::new(),
+        entrypoint: None,
+        cmd: Vec::new(),
     };
     catalog.register(entry).expect("register image");
     assert_eq!(catalog.list().expect("list").len(), 1);
@@ -350,6 +352,8 @@ fn pipeline_image_catalog_multiple_entries() {
             created_at: "2026-01-01T00:00:00Z".into(),
             digest: None,
             tool_version: String::new(),
+            entrypoint: None,
+            cmd: Vec::new(),
         };
         catalog.register(entry).expect("register");
     }
@@ -358,36 +362,74 @@ fn pipeline_image_catalog_multiple_entries() {
 
 // ── State Persistence ────────────────────────────────────────────────
 
+fn state_entry(
+    id: &str,
+    name: &str,
+    state: containust_common::types::ContainerState,
+) -> containust_runtime::state::StateEntry {
+    containust_runtime::state::StateEntry {
+        id: containust_common::types::ContainerId::new(id),
+        name: name.into(),
+        state,
+        pid: None,
+        image: "file:///test".into(),
+        command: Vec::new(),
+        env: Vec::new(),
+        memory_bytes: None,
+        cpu_shares: None,
+        pids_max: None,
+        swap_bytes: None,
+        memory_low_bytes: None,
+        memory_high_bytes: None,
+        cpu_quota_us: None,
+        cpu_period_us: None,
+        cpuset_cpus: None,
+        cpuset_mems: None,
+        ipc_shmmax_bytes: None,
+        ipc_shmall_pages: None,
+        ipc_msgmni: None,
+        readonly_rootfs: true,
+        stdin: false,
+        volumes: Vec::new(),
+        rootfs_path: None,
+        log_path: None,
+        ports: Vec::new(),
+        port_mappings: Vec::new(),
+        network: "bridge".into(),
+        forwarder_pids: Vec::new(),
+        restart: containust_common::types::RestartPolicy::default(),
+        healthcheck: None,
+        health: None,
+        restart_count: 0,
+        restart_limit_reported_at: None,
+        stop_signal: None,
+        stop_timeout_secs: None,
+        enforce_limits: true,
+        last_error: None,
+        workdir: None,
+        hostname: None,
+        user: None,
+        capabilities: Vec::new(),
+        created_at: "2026-01-01T00:00:00Z".into(),
+        started_at: None,
+        finished_at: None,
+        exit_code: None,
+    }
+}
+
 #[test]
 fn pipeline_state_persistence_roundtrip() {
     let dir = tempfile::tempdir().expect("tempdir");
     let state_path = dir.path().join("state.json");
 
+    let mut entry = state_entry(
+        "test-container",
+        "web",
+        containust_common::types::ContainerState::Running,
+    );
+    entry.pid = Some(1234);
     let state = containust_runtime::state::StateFile {
-        containers: vec![containust_runtime::state::StateEntry {
-            id: containust_common::types::ContainerId::new("test-container"),
-            name: "web".into(),
-            state: containust_common::types::ContainerState::Running,
-            pid: Some(1234),
-            image: "file:///test".into(),
-            command: Vec::new(),
-            env: Vec::new(),
-            memory_bytes: None,
-            cpu_shares: None,
-            readonly_rootfs: true,
-            volumes: Vec::new(),
-            rootfs_path: None,
-            log_path: None,
-            ports: Vec::new(),
-            port_mappings: Vec::new(),
-            network: "bridge".into(),
-            forwarder_pids: Vec::new(),
-            restart: containust_common::types::RestartPolicy::default(),
-            healthcheck: None,
-            health: None,
-            restart_count: 0,
-            created_at: "2026-01-01T00:00:00Z".into(),
-        }],
+        containers: vec![entry],
         ..containust_runtime::state::StateFile::default()
     };
 
@@ -405,8 +447,8 @@ fn pipeline_state_persistence_roundtrip() {
 
 #[test]
 fn pipeline_state_all_lifecycle_states() {
-    use containust_common::types::{ContainerId, ContainerState};
-    use containust_runtime::state::{StateEntry, StateFile};
+    use containust_common::types::ContainerState;
+    use containust_runtime::state::StateFile;
 
     let dir = tempfile::tempdir().expect("tempdir");
     let path = dir.path().join("state.json");
@@ -422,30 +464,7 @@ fn pipeline_state_all_lifecycle_states() {
         containers: states
             .iter()
             .enumerate()
-            .map(|(i, s)| StateEntry {
-                id: ContainerId::new(format!("c-{i}")),
-                name: format!("container-{i}"),
-                state: *s,
-                pid: None,
-                image: "img".into(),
-                command: Vec::new(),
-                env: Vec::new(),
-                memory_bytes: None,
-                cpu_shares: None,
-                readonly_rootfs: true,
-                volumes: Vec::new(),
-                rootfs_path: None,
-                log_path: None,
-                ports: Vec::new(),
-                port_mappings: Vec::new(),
-                network: "bridge".into(),
-                forwarder_pids: Vec::new(),
-                restart: containust_common::types::RestartPolicy::default(),
-                healthcheck: None,
-                health: None,
-                restart_count: 0,
-                created_at: "2026-01-01T00:00:00Z".into(),
-            })
+            .map(|(i, s)| state_entry(&format!("c-{i}"), &format!("container-{i}"), *s))
             .collect(),
         ..StateFile::default()
     };
@@ -453,10 +472,9 @@ fn pipeline_state_all_lifecycle_states() {
     containust_runtime::state::save_state(&path, &state).expect("save");
     let loaded = containust_runtime::state::load_state(&path).expect("load");
     assert_eq!(loaded.containers.len(), 4);
-    assert_eq!(loaded.containers[0].state, ContainerState::Created);
-    assert_eq!(loaded.containers[1].state, ContainerState::Running);
-    assert_eq!(loaded.containers[2].state, ContainerState::Stopped);
-    assert_eq!(loaded.containers[3].state, ContainerState::Failed);
+    for (entry, expected) in loaded.containers.iter().zip(states) {
+        assert_eq!(entry.state, expected);
+    }
 }
 
 // ── Log Management ───────────────────────────────────────────────────
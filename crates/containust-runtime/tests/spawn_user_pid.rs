@@ -8,6 +8,7 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use containust_core::namespace::NamespaceConfig;
+use containust_core::namespace::ipc::IpcLimits;
 use containust_runtime::process::{ProcessConfig, spawn_container_process};
 use nix::sys::signal::{Signal, kill};
 use nix::unistd::Pid;
@@ -38,8 +39,14 @@ fn spawn_with_user_and_pid_runs_sleep() {
         readonly_rootfs: false,
         volumes: Vec::new(),
         namespaces: NamespaceConfig::default().with_user_and_pid(),
+        ipc_limits: IpcLimits::default(),
         join_netns: None,
+        workdir: None,
+        hostname: None,
+        user: None,
         log_path: None,
+        stdin_path: None,
+        capabilities: Vec::new(),
     };
     let pid = spawn_container_process(&config).expect("spawn user+pid");
     assert!(pid > 0, "init host pid should be positive, got {pid}");
@@ -46,7 +46,19 @@ fn sample_entry(name: &str) -> StateEntry {
         env: Vec::new(),
         memory_bytes: None,
         cpu_shares: None,
+        pids_max: None,
+        swap_bytes: None,
+        memory_low_bytes: None,
+        memory_high_bytes: None,
+        cpu_quota_us: None,
+        cpu_period_us: None,
+        cpuset_cpus: None,
+        cpuset_mems: None,
+        ipc_shmmax_bytes: None,
+        ipc_shmall_pages: None,
+        ipc_msgmni: None,
         readonly_rootfs: true,
+        stdin: false,
         volumes: Vec::new(),
         rootfs_path: None,
         log_path: None,
@@ -58,7 +70,19 @@ fn sample_entry(name: &str) -> StateEntry {
         healthcheck: None,
         health: None,
         restart_count: 0,
+        restart_limit_reported_at: None,
+        stop_signal: None,
+        stop_timeout_secs: None,
+        enforce_limits: true,
+        last_error: None,
+        workdir: None,
+        hostname: None,
+        user: None,
+        capabilities: Vec::new(),
         created_at: "2026-01-01T00:00:00Z".into(),
+        started_at: None,
+        finished_at: None,
+        exit_code: None,
     }
 }
 
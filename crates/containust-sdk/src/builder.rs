@@ -1,7 +1,10 @@
 //! Fluent API for configuring and launching containers.
 
 use containust_common::error::Result;
-use containust_common::types::ContainerId;
+use containust_common::types::{ContainerId, RestartPolicy};
+use containust_core::namespace::NamespaceConfig;
+use containust_core::namespace::ipc::IpcLimits;
+use containust_runtime::backend::{ContainerConfig, StopOptions};
 use containust_runtime::container::Container;
 
 /// Builder for configuring a container before launch.
@@ -13,7 +16,10 @@ pub struct ContainerBuilder {
     env: Vec<(String, String)>,
     memory_limit: Option<u64>,
     cpu_shares: Option<u64>,
+    cpu_quota_us: Option<u64>,
+    cpu_period_us: Option<u64>,
     readonly_rootfs: bool,
+    volumes: Vec<String>,
 }
 
 impl ContainerBuilder {
@@ -27,7 +33,10 @@ impl ContainerBuilder {
             env: Vec::new(),
             memory_limit: None,
             cpu_shares: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
             readonly_rootfs: true,
+            volumes: Vec::new(),
         }
     }
 
@@ -66,6 +75,15 @@ impl ContainerBuilder {
         self
     }
 
+    /// Allows `quota_us` of CPU time per `period_us` (the kernel default
+    /// period when `None`).
+    #[must_use]
+    pub const fn cpu_quota(mut self, quota_us: u64, period_us: Option<u64>) -> Self {
+        self.cpu_quota_us = Some(quota_us);
+        self.cpu_period_us = period_us;
+        self
+    }
+
     /// Sets whether the root filesystem should be read-only.
     #[must_use]
     pub const fn readonly_rootfs(mut self, readonly: bool) -> Self {
@@ -73,30 +91,82 @@ impl ContainerBuilder {
         self
     }
 
+    /// Adds a volume mount, `host_path:container_path[:ro]`.
+    #[must_use]
+    pub fn volume(mut self, spec: impl Into<String>) -> Self {
+        self.volumes.push(spec.into());
+        self
+    }
+
     /// Builds and returns the configured container (does not start it).
     ///
     /// # Errors
     ///
     /// Returns an error if required fields (image) are missing.
     pub fn build(self) -> Result<Container> {
-        let _image =
+        let config = self.build_config()?;
+        let mut container = Container::new(
+            ContainerId::new(config.name.clone()),
+            config.name,
+            config.command,
+        );
+        container.image_source = config.image;
+        container.env = config.env;
+        container.limits.memory_bytes = config.memory_bytes;
+        container.limits.cpu_shares = config.cpu_shares;
+        container.limits.cpu_quota_us = config.cpu_quota_us;
+        container.limits.cpu_period_us = config.cpu_period_us;
+        Ok(container)
+    }
+
+    /// Builds the backend configuration, volumes included, for
+    /// [`ContainerBackend::create`](containust_runtime::backend::ContainerBackend::create).
+    ///
+    /// The container gets a private, unconnected network and the
+    /// default restart and stop policies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if required fields (image) are missing.
+    pub fn build_config(self) -> Result<ContainerConfig> {
+        let image =
             self.image
                 .ok_or_else(|| containust_common::error::ContainustError::Config {
                     message: "image source is required".to_string(),
                 })?;
-
-        let name = self.name.clone();
-        let mut container = Container::new(ContainerId::new(self.name), name, self.command);
-        container.env = self.env;
-
-        if let Some(mem) = self.memory_limit {
-            container.limits.memory_bytes = Some(mem);
-        }
-        if let Some(cpu) = self.cpu_shares {
-            container.limits.cpu_shares = Some(cpu);
-        }
-
-        Ok(container)
+        Ok(ContainerConfig {
+            name: self.name,
+            image,
+            command: self.command,
+            env: self.env,
+            memory_bytes: self.memory_limit,
+            cpu_shares: self.cpu_shares,
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: self.cpu_quota_us,
+            cpu_period_us: self.cpu_period_us,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_limits: IpcLimits::default(),
+            readonly_rootfs: self.readonly_rootfs,
+            stdin: false,
+            volumes: self.volumes,
+            port: None,
+            ports: Vec::new(),
+            port_mappings: Vec::new(),
+            network: "none".into(),
+            restart: RestartPolicy::default(),
+            healthcheck: None,
+            namespaces: NamespaceConfig::default().with_user_and_pid(),
+            stop: StopOptions::default(),
+            enforce_limits: true,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
+        })
     }
 }
 
@@ -116,6 +186,7 @@ mod tests {
         assert!(builder.memory_limit.is_none());
         assert!(builder.cpu_shares.is_none());
         assert!(builder.readonly_rootfs);
+        assert!(builder.volumes.is_empty());
     }
 
     #[test]
@@ -181,6 +252,17 @@ mod tests {
         assert_eq!(builder.cpu_shares, Some(512));
     }
 
+    #[test]
+    fn builder_cpu_quota_reaches_the_config() {
+        let config = ContainerBuilder::new("app")
+            .image("file:///img")
+            .cpu_quota(50_000, Some(100_000))
+            .build_config()
+            .unwrap();
+        assert_eq!(config.cpu_quota_us, Some(50_000));
+        assert_eq!(config.cpu_period_us, Some(100_000));
+    }
+
     #[test]
     fn builder_readonly_rootfs_toggles() {
         let default_true = ContainerBuilder::new("app");
@@ -234,6 +316,7 @@ mod tests {
             .expect("build should succeed");
 
         assert_eq!(container.name, "valid");
+        assert_eq!(container.image_source, "file:///tmp/rootfs");
         assert_eq!(container.command, vec!["/bin/sh"]);
         assert_eq!(container.env.len(), 1);
         assert_eq!(container.limits.memory_bytes, Some(134_217_728));
@@ -279,6 +362,54 @@ mod tests {
         assert_eq!(container.limits.cpu_shares, Some(128));
     }
 
+    #[test]
+    fn builder_volume_accumulates_specs() {
+        let builder = ContainerBuilder::new("app")
+            .volume("/srv/data:/data")
+            .volume(String::from("/etc/app:/etc/app:ro"));
+        assert_eq!(builder.volumes, ["/srv/data:/data", "/etc/app:/etc/app:ro"]);
+    }
+
+    #[test]
+    fn builder_build_config_carries_every_setting() {
+        let config = ContainerBuilder::new("api")
+            .image("file:///opt/images/api")
+            .command(vec!["/bin/api".into(), "--port".into(), "80".into()])
+            .env("RUST_LOG", "info")
+            .env("PORT", "80")
+            .volume("/srv/api:/data")
+            .memory_limit(64 * 1024 * 1024)
+            .cpu_shares(512)
+            .readonly_rootfs(false)
+            .build_config()
+            .expect("build_config should succeed");
+
+        assert_eq!(config.name, "api");
+        assert_eq!(config.image, "file:///opt/images/api");
+        assert_eq!(config.command, ["/bin/api", "--port", "80"]);
+        assert_eq!(
+            config.env,
+            [
+                ("RUST_LOG".to_string(), "info".to_string()),
+                ("PORT".to_string(), "80".to_string())
+            ]
+        );
+        assert_eq!(config.volumes, ["/srv/api:/data"]);
+        assert_eq!(config.memory_bytes, Some(67_108_864));
+        assert_eq!(config.cpu_shares, Some(512));
+        assert!(!config.readonly_rootfs);
+        config.validate().expect("builder config is valid");
+    }
+
+    #[test]
+    fn builder_build_config_requires_image() {
+        let error = ContainerBuilder::new("no-image")
+            .volume("/a:/b")
+            .build_config()
+            .unwrap_err();
+        assert!(error.to_string().contains("image source is required"));
+    }
+
     #[test]
     fn builder_build_empty_name_succeeds() {
         let container = ContainerBuilder::new("")
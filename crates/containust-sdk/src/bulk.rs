@@ -0,0 +1,297 @@
+//! Batch operations over the containers an engine tracks.
+//!
+//! Selection mirrors the CLI: a container matches by ID or name and by
+//! lifecycle state. Each operation reports one [`BulkResult`] per selected
+//! container, so a single failure never aborts the rest of the batch.
+
+use containust_common::error::{ContainustError, Result};
+use containust_common::types::{ContainerId, ContainerState};
+use containust_runtime::backend::{ContainerInfo, StopOptions};
+use containust_runtime::engine::{Engine, StopOutcome};
+
+/// Selects containers for a bulk operation.
+///
+/// An empty filter matches every container; each non-empty criterion
+/// narrows the selection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContainerFilter {
+    names: Vec<String>,
+    states: Vec<ContainerState>,
+}
+
+impl ContainerFilter {
+    /// Matches every container.
+    #[must_use]
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Also matches the container whose ID or name is `name`.
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.names.push(name.into());
+        self
+    }
+
+    /// Also matches containers in `state`.
+    #[must_use]
+    pub fn state(mut self, state: ContainerState) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    /// Returns whether `info` is selected.
+    #[must_use]
+    pub fn matches(&self, info: &ContainerInfo) -> bool {
+        let name_matches = self.names.is_empty()
+            || self
+                .names
+                .iter()
+                .any(|name| info.id.as_str() == name || info.name == *name);
+        let state_matches = self.states.is_empty()
+            || self
+                .states
+                .iter()
+                .any(|state| info.state == state.to_string());
+        name_matches && state_matches
+    }
+}
+
+/// Outcome of a bulk operation on one container.
+#[derive(Debug)]
+pub struct BulkResult<T> {
+    /// Container ID.
+    pub id: ContainerId,
+    /// Container name.
+    pub name: String,
+    /// What the operation returned for this container.
+    pub result: Result<T>,
+}
+
+impl<T> BulkResult<T> {
+    /// Whether the operation succeeded for this container.
+    #[must_use]
+    pub const fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Lists the containers selected by `filter`.
+///
+/// # Errors
+///
+/// Returns an error if the engine cannot list its containers.
+pub fn list(engine: &Engine, filter: &ContainerFilter) -> Result<Vec<ContainerInfo>> {
+    Ok(engine
+        .list()?
+        .into_iter()
+        .filter(|info| filter.matches(info))
+        .collect())
+}
+
+/// Gracefully stops every running container selected by `filter`.
+///
+/// # Errors
+///
+/// Returns an error only if the engine cannot list its containers;
+/// per-container failures are reported in the results.
+pub fn stop_all(engine: &Engine, filter: &ContainerFilter) -> Result<Vec<BulkResult<StopOutcome>>> {
+    let running = ContainerState::Running.to_string();
+    for_each(engine, filter, |info| {
+        if info.state == running {
+            Some(engine.stop_with_options(&info.id, StopOptions::default()))
+        } else {
+            None
+        }
+    })
+}
+
+/// Removes every stopped, failed, or created container selected by
+/// `filter`; running containers are reported as errors.
+///
+/// # Errors
+///
+/// Returns an error only if the engine cannot list its containers;
+/// per-container failures are reported in the results.
+pub fn remove_all(engine: &Engine, filter: &ContainerFilter) -> Result<Vec<BulkResult<()>>> {
+    let running = ContainerState::Running.to_string();
+    for_each(engine, filter, |info| {
+        Some(if info.state == running {
+            Err(ContainustError::Config {
+                message: format!("container {} is running; stop it first", info.name),
+            })
+        } else {
+            engine.remove(&info.id)
+        })
+    })
+}
+
+/// Runs `op` on each selected container; `None` skips the container.
+fn for_each<T>(
+    engine: &Engine,
+    filter: &ContainerFilter,
+    mut op: impl FnMut(&ContainerInfo) -> Option<Result<T>>,
+) -> Result<Vec<BulkResult<T>>> {
+    Ok(list(engine, filter)?
+        .into_iter()
+        .filter_map(|info| {
+            let result = op(&info)?;
+            if let Err(error) = &result {
+                tracing::warn!(id = %info.id, name = %info.name, %error, "bulk operation failed");
+            }
+            Some(BulkResult {
+                id: info.id,
+                name: info.name,
+                result,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use std::sync::{Arc, Mutex};
+
+    use containust_runtime::backend::{ContainerBackend, ContainerConfig};
+    use containust_runtime::engine::EngineOptions;
+    use containust_runtime::exec::ExecOutput;
+
+    use super::*;
+
+    /// Tracks containers in memory; stopping `broken` fails.
+    struct MockBackend {
+        containers: Mutex<Vec<ContainerInfo>>,
+        stopped: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockBackend {
+        fn update(&self, id: &ContainerId, state: &str) {
+            let mut containers = self.containers.lock().unwrap();
+            for info in containers.iter_mut().filter(|info| info.id == *id) {
+                info.state = state.into();
+            }
+        }
+    }
+
+    impl ContainerBackend for MockBackend {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+        fn create(&self, _config: &ContainerConfig) -> Result<ContainerId> {
+            unreachable!("bulk operations never create")
+        }
+        fn start(&self, _id: &ContainerId) -> Result<u32> {
+            unreachable!("bulk operations never start")
+        }
+        fn stop(&self, id: &ContainerId) -> Result<()> {
+            if id.as_str() == "broken" {
+                return Err(ContainustError::Config {
+                    message: "stop failed".into(),
+                });
+            }
+            self.stopped.lock().unwrap().push(id.to_string());
+            self.update(id, "stopped");
+            Ok(())
+        }
+        fn exec(&self, _id: &ContainerId, _cmd: &[String]) -> Result<ExecOutput> {
+            unreachable!("bulk operations never exec")
+        }
+        fn remove(&self, id: &ContainerId) -> Result<()> {
+            self.containers
+                .lock()
+                .unwrap()
+                .retain(|info| info.id != *id);
+            Ok(())
+        }
+        fn logs(&self, _id: &ContainerId) -> Result<String> {
+            Ok(String::new())
+        }
+        fn list(&self) -> Result<Vec<ContainerInfo>> {
+            Ok(self.containers.lock().unwrap().clone())
+        }
+        fn is_available(&self) -> bool {
+            true
+        }
+    }
+
+    fn info(id: &str, name: &str, state: &str) -> ContainerInfo {
+        ContainerInfo {
+            id: ContainerId::new(id),
+            name: name.into(),
+            state: state.into(),
+            pid: None,
+            image: "file:///img".into(),
+            created_at: String::new(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
+            health: None,
+        }
+    }
+
+    /// Returns the engine and the IDs its backend has stopped.
+    fn engine(containers: Vec<ContainerInfo>) -> (Engine, Arc<Mutex<Vec<String>>>) {
+        let stopped = Arc::default();
+        let backend = MockBackend {
+            containers: Mutex::new(containers),
+            stopped: Arc::clone(&stopped),
+        };
+        let engine = Engine::with_backend(EngineOptions::default(), Box::new(backend));
+        (engine, stopped)
+    }
+
+    #[test]
+    fn filter_selects_subset_for_bulk_stop() {
+        let (engine, stopped) = engine(vec![
+            info("a", "web", "running"),
+            info("b", "db", "running"),
+            info("c", "cache", "stopped"),
+        ]);
+        let filter = ContainerFilter::all().name("web").name("cache");
+        let results = stop_all(&engine, &filter).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "web");
+        assert!(matches!(results[0].result, Ok(StopOutcome::Graceful)));
+        assert_eq!(*stopped.lock().unwrap(), ["a"]);
+    }
+
+    #[test]
+    fn bulk_stop_collects_per_container_errors() {
+        let (engine, stopped) = engine(vec![
+            info("broken", "web", "running"),
+            info("b", "db", "running"),
+        ]);
+        let results = stop_all(&engine, &ContainerFilter::all()).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(!results[0].is_ok());
+        assert!(results[1].is_ok());
+        assert_eq!(*stopped.lock().unwrap(), ["b"]);
+    }
+
+    #[test]
+    fn state_filter_lists_and_removes_matching_containers() {
+        let (engine, _) = engine(vec![
+            info("a", "web", "running"),
+            info("b", "db", "stopped"),
+            info("c", "job", "failed"),
+        ]);
+        let finished = ContainerFilter::all()
+            .state(ContainerState::Stopped)
+            .state(ContainerState::Failed);
+        let names: Vec<String> = list(&engine, &finished)
+            .unwrap()
+            .into_iter()
+            .map(|info| info.name)
+            .collect();
+        assert_eq!(names, ["db", "job"]);
+
+        let removed = remove_all(&engine, &finished).unwrap();
+        assert!(removed.iter().all(BulkResult::is_ok));
+        assert_eq!(engine.list().unwrap().len(), 1);
+
+        let refused = remove_all(&engine, &ContainerFilter::all()).unwrap();
+        assert!(!refused[0].is_ok());
+    }
+}
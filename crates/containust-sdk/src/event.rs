@@ -1,9 +1,20 @@
 //! Container lifecycle event streaming via the runtime event bus.
 
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use containust_common::clock::{SharedClock, SystemClock};
 use containust_common::types::{ContainerId, ContainerState};
 pub use containust_runtime::events::{EventBus, LifecycleEvent};
+use containust_runtime::state::{StateFile, load_state};
+
+/// Default interval between two reads of a watched state file.
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_millis(500);
 
 /// A container lifecycle event (SDK-facing aliases for runtime events).
 #[derive(Debug, Clone)]
@@ -57,24 +68,16 @@ impl From<LifecycleEvent> for ContainerEvent {
                 container_id,
                 from,
                 to,
+                ..
             } => Self::StateChange {
                 container_id: ContainerId::new(container_id),
-                from: parse_state(&from),
-                to: parse_state(&to),
+                from,
+                to,
             },
         }
     }
 }
 
-fn parse_state(label: &str) -> ContainerState {
-    match label.to_ascii_lowercase().as_str() {
-        "running" => ContainerState::Running,
-        "stopped" => ContainerState::Stopped,
-        "failed" => ContainerState::Failed,
-        _ => ContainerState::Created,
-    }
-}
-
 /// Listens for container lifecycle events from an [`EventBus`].
 #[derive(Debug)]
 pub struct EventListener {
@@ -100,6 +103,18 @@ impl EventListener {
     pub fn try_recv(&self) -> Option<ContainerEvent> {
         self.rx.as_ref()?.try_recv().ok().map(ContainerEvent::from)
     }
+
+    /// Watches a project state file and yields a
+    /// [`LifecycleEvent::StateChange`] for each container state transition.
+    ///
+    /// The file is read once to take a baseline, then polled every
+    /// [`DEFAULT_WATCH_INTERVAL`]; each read is diffed against the previous
+    /// one with [`diff_states`]. Iteration blocks between polls and ends once
+    /// the watch's [`StopHandle`] is triggered.
+    #[must_use]
+    pub fn watch(state_file: &Path) -> StateWatch {
+        StateWatch::new(state_file)
+    }
 }
 
 impl Default for EventListener {
@@ -108,6 +123,134 @@ impl Default for EventListener {
     }
 }
 
+/// Returns the [`LifecycleEvent::StateChange`] events between two state
+/// file snapshots, stamped with `at`.
+///
+/// A container missing from `previous` is treated as `Created`, so one that
+/// shows up already running yields `Created -> Running`. Containers removed
+/// from `current` yield nothing. Events follow `current`'s order.
+#[must_use]
+pub fn diff_states(
+    previous: &StateFile,
+    current: &StateFile,
+    at: DateTime<Utc>,
+) -> Vec<LifecycleEvent> {
+    let before: HashMap<&str, ContainerState> = previous
+        .containers
+        .iter()
+        .map(|entry| (entry.id.as_str(), entry.state))
+        .collect();
+    current
+        .containers
+        .iter()
+        .filter_map(|entry| {
+            let from = before
+                .get(entry.id.as_str())
+                .copied()
+                .unwrap_or(ContainerState::Created);
+            (from != entry.state).then(|| LifecycleEvent::StateChange {
+                container_id: entry.id.as_str().to_string(),
+                from,
+                to: entry.state,
+                at,
+            })
+        })
+        .collect()
+}
+
+/// Stops a [`StateWatch`] from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct StopHandle(Arc<AtomicBool>);
+
+impl StopHandle {
+    /// Ends the watch; its iterator returns `None` after the current poll.
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether [`StopHandle::stop`] has been called.
+    #[must_use]
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Blocking iterator over state change events read from a state file.
+///
+/// Created by [`EventListener::watch`].
+#[derive(Debug)]
+pub struct StateWatch {
+    path: PathBuf,
+    previous: StateFile,
+    pending: VecDeque<LifecycleEvent>,
+    interval: Duration,
+    stop: StopHandle,
+    clock: SharedClock,
+}
+
+impl StateWatch {
+    fn new(path: &Path) -> Self {
+        let previous = read_snapshot(path).unwrap_or_default();
+        Self {
+            path: path.to_path_buf(),
+            previous,
+            pending: VecDeque::new(),
+            interval: DEFAULT_WATCH_INTERVAL,
+            stop: StopHandle::default(),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Sets the interval between two reads of the state file.
+    #[must_use]
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Waits and stamps transitions with `clock` instead of the system clock.
+    #[must_use]
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns a handle that ends this watch when stopped.
+    #[must_use]
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop.clone()
+    }
+}
+
+impl Iterator for StateWatch {
+    type Item = LifecycleEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            if self.stop.is_stopped() {
+                return None;
+            }
+            self.clock.sleep(self.interval);
+            let Some(current) = read_snapshot(&self.path) else {
+                continue;
+            };
+            let now = self.clock.now();
+            self.pending
+                .extend(diff_states(&self.previous, &current, now));
+            self.previous = current;
+        }
+    }
+}
+
+fn read_snapshot(path: &Path) -> Option<StateFile> {
+    load_state(path)
+        .map_err(|e| tracing::debug!(path = %path.display(), error = %e, "state file not readable"))
+        .ok()
+}
+
 #[cfg(test)]
 #[allow(
     clippy::panic,
@@ -116,6 +259,170 @@ impl Default for EventListener {
 )]
 mod tests {
     use super::*;
+    use containust_runtime::state::{StateEntry, save_state};
+
+    fn entry(id: &str, state: ContainerState) -> StateEntry {
+        StateEntry {
+            id: ContainerId::new(id),
+            name: id.into(),
+            state,
+            pid: None,
+            image: "alpine:3.21".into(),
+            command: Vec::new(),
+            env: Vec::new(),
+            memory_bytes: None,
+            cpu_shares: None,
+            pids_max: None,
+            swap_bytes: None,
+            memory_low_bytes: None,
+            memory_high_bytes: None,
+            cpu_quota_us: None,
+            cpu_period_us: None,
+            cpuset_cpus: None,
+            cpuset_mems: None,
+            ipc_shmmax_bytes: None,
+            ipc_shmall_pages: None,
+            ipc_msgmni: None,
+            readonly_rootfs: true,
+            stdin: false,
+            volumes: Vec::new(),
+            rootfs_path: None,
+            log_path: None,
+            ports: Vec::new(),
+            port_mappings: Vec::new(),
+            network: "none".into(),
+            forwarder_pids: Vec::new(),
+            restart: containust_common::types::RestartPolicy::default(),
+            healthcheck: None,
+            health: None,
+            restart_count: 0,
+            restart_limit_reported_at: None,
+            stop_signal: None,
+            stop_timeout_secs: None,
+            enforce_limits: true,
+            last_error: None,
+            workdir: None,
+            hostname: None,
+            user: None,
+            capabilities: Vec::new(),
+            created_at: "2026-01-01T00:00:00Z".into(),
+            started_at: None,
+            finished_at: None,
+            exit_code: None,
+        }
+    }
+
+    fn snapshot(containers: &[(&str, ContainerState)]) -> StateFile {
+        StateFile {
+            containers: containers
+                .iter()
+                .map(|(id, state)| entry(id, *state))
+                .collect(),
+            ..StateFile::default()
+        }
+    }
+
+    fn moves(events: &[LifecycleEvent]) -> Vec<(&str, ContainerState, ContainerState)> {
+        events
+            .iter()
+            .map(state_change)
+            .map(|(id, from, to, _)| (id, from, to))
+            .collect()
+    }
+
+    fn state_change(
+        event: &LifecycleEvent,
+    ) -> (&str, ContainerState, ContainerState, DateTime<Utc>) {
+        match event {
+            LifecycleEvent::StateChange {
+                container_id,
+                from,
+                to,
+                at,
+            } => (container_id.as_str(), *from, *to, *at),
+            other => panic!("expected StateChange, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn diff_states_follows_successive_snapshots() {
+        let at = DateTime::UNIX_EPOCH;
+        let created = snapshot(&[("web", ContainerState::Created)]);
+        let running = snapshot(&[
+            ("web", ContainerState::Running),
+            ("db", ContainerState::Running),
+        ]);
+        let settled = snapshot(&[
+            ("web", ContainerState::Stopped),
+            ("db", ContainerState::Failed),
+        ]);
+
+        assert!(diff_states(&StateFile::default(), &created, at).is_empty());
+        let started = diff_states(&created, &running, at);
+        assert!(started.iter().all(|event| state_change(event).3 == at));
+        assert_eq!(
+            moves(&started),
+            vec![
+                ("web", ContainerState::Created, ContainerState::Running),
+                ("db", ContainerState::Created, ContainerState::Running),
+            ]
+        );
+        assert_eq!(
+            moves(&diff_states(&running, &settled, at)),
+            vec![
+                ("web", ContainerState::Running, ContainerState::Stopped),
+                ("db", ContainerState::Running, ContainerState::Failed),
+            ]
+        );
+        assert!(diff_states(&settled, &settled, at).is_empty());
+    }
+
+    #[test]
+    fn diff_states_ignores_removed_containers() {
+        let before = snapshot(&[("web", ContainerState::Running)]);
+        let after = snapshot(&[]);
+        assert!(diff_states(&before, &after, DateTime::UNIX_EPOCH).is_empty());
+    }
+
+    #[test]
+    fn watch_yields_transitions_until_stopped() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("state.json");
+        save_state(&path, &snapshot(&[("web", ContainerState::Created)])).expect("save");
+
+        let mut watch = EventListener::watch(&path).with_interval(Duration::from_millis(5));
+        let stop = watch.stop_handle();
+        save_state(&path, &snapshot(&[("web", ContainerState::Running)])).expect("save");
+
+        let event = watch.next().expect("transition");
+        let (id, from, to, _) = state_change(&event);
+        assert_eq!(id, "web");
+        assert_eq!(from, ContainerState::Created);
+        assert_eq!(to, ContainerState::Running);
+
+        stop.stop();
+        assert!(watch.next().is_none());
+    }
+
+    #[test]
+    fn watch_stamps_transitions_with_its_clock() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("state.json");
+        save_state(&path, &snapshot(&[("web", ContainerState::Created)])).expect("save");
+        let clock = containust_common::clock::MockClock::at_epoch();
+
+        let mut watch = EventListener::watch(&path)
+            .with_interval(Duration::from_secs(2))
+            .with_clock(Arc::new(clock.clone()));
+        save_state(&path, &snapshot(&[("web", ContainerState::Running)])).expect("save");
+
+        let event = watch.next().expect("transition");
+        assert_eq!(
+            state_change(&event).3,
+            DateTime::UNIX_EPOCH + Duration::from_secs(2)
+        );
+        assert_eq!(clock.sleeps(), vec![Duration::from_secs(2)]);
+    }
 
     #[test]
     fn event_listener_subscribes_to_bus() {
@@ -36,21 +36,27 @@ impl GraphResolver {
         let composition = containust_compose::parser::parse_ctst(&content)?;
         containust_compose::parser::validator::validate(&composition)?;
 
-        self.graph = containust_compose::graph::DependencyGraph::new();
-        let mut node_map = std::collections::HashMap::new();
-        for comp in &composition.components {
-            let idx = self.graph.add_component(&comp.name);
-            let _ = node_map.insert(comp.name.clone(), idx);
-        }
-        for conn in &composition.connections {
-            if let (Some(&from), Some(&to)) = (node_map.get(&conn.from), node_map.get(&conn.to)) {
-                self.graph.add_dependency(from, to);
-            }
-        }
-
+        self.graph = containust_compose::graph::DependencyGraph::from_composition(&composition);
         Ok(())
     }
 
+    /// Returns the components that no `CONNECT` or `DEPENDS_ON` edge
+    /// touches, so tooling can warn about likely copy-paste leftovers.
+    ///
+    /// An empty list means every component is connected; a composition
+    /// with a single component reports it.
+    ///
+    /// # Errors
+    ///
+    /// Currently infallible; the `Result` leaves room for stricter checks.
+    pub fn validate_no_orphans(&self) -> Result<Vec<String>> {
+        let orphans = self.graph.isolated_nodes();
+        if !orphans.is_empty() {
+            tracing::warn!(?orphans, "components with no connections");
+        }
+        Ok(orphans)
+    }
+
     /// Returns the deployment order for all components.
     ///
     /// # Errors
@@ -114,6 +120,33 @@ mod tests {
         assert!(db_pos < api_pos, "db must deploy before api");
     }
 
+    #[test]
+    fn validate_no_orphans_reports_isolated_components() {
+        let mut content = tempfile::NamedTempFile::new().expect("create temp file");
+        content
+            .write_all(
+                b"COMPONENT api {\n    image = \"file:///opt/api\"\n}\n\
+                  COMPONENT db {\n    image = \"file:///opt/db\"\n}\n\
+                  COMPONENT migrate {\n    image = \"file:///opt/migrate\"\n}\n\
+                  COMPONENT stray {\n    image = \"file:///opt/stray\"\n}\n\
+                  COMPONENT api-copy {\n    image = \"file:///opt/api\"\n}\n\
+                  CONNECT api -> db\n\
+                  DEPENDS_ON migrate -> db\n",
+            )
+            .expect("write");
+
+        let mut resolver = GraphResolver::new();
+        resolver.load_ctst(content.path()).expect("load");
+        let orphans = resolver.validate_no_orphans().expect("validate");
+        assert_eq!(orphans, ["stray", "api-copy"]);
+    }
+
+    #[test]
+    fn validate_no_orphans_empty_when_all_connected() {
+        let resolver = GraphResolver::new();
+        assert!(resolver.validate_no_orphans().expect("validate").is_empty());
+    }
+
     #[test]
     fn graph_resolver_load_missing_file_returns_error() {
         let mut resolver = GraphResolver::new();
@@ -2,10 +2,11 @@
 //!
 //! Public SDK for using Containust as a Rust library.
 //!
-//! Provides three main entry points:
+//! Provides these main entry points:
 //! - [`ContainerBuilder`](builder::ContainerBuilder): Fluent API for configuring and launching containers.
 //! - [`GraphResolver`](graph_resolver::GraphResolver): Validates and resolves component dependency graphs.
 //! - [`EventListener`](event::EventListener): Subscribes to container lifecycle events for monitoring.
+//! - [`bulk`]: Lists, stops, or removes every container matching a [`ContainerFilter`](bulk::ContainerFilter).
 //!
 //! # Example
 //!
@@ -19,5 +20,6 @@
 //! ```
 
 pub mod builder;
+pub mod bulk;
 pub mod event;
 pub mod graph_resolver;
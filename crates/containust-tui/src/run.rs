@@ -78,10 +78,8 @@ fn event_loop(
 fn handle_key(app: &mut App, code: KeyCode, row_count: usize) {
     match code {
         KeyCode::Char('q') | KeyCode::Esc => app.quit(),
-        KeyCode::Down | KeyCode::Char('j') => {
-            if row_count > 0 {
-                app.selected_index = (app.selected_index + 1).min(row_count - 1);
-            }
+        KeyCode::Down | KeyCode::Char('j') if row_count > 0 => {
+            app.selected_index = (app.selected_index + 1).min(row_count - 1);
         }
         KeyCode::Up | KeyCode::Char('k') => {
             app.selected_index = app.selected_index.saturating_sub(1);